@@ -0,0 +1,254 @@
+use crate::{GpuDevice, GpuMat};
+use equator::assert;
+use faer::Accum;
+use wgpu::util::DeviceExt;
+
+/// strided view into a device buffer, in units of `f32`
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct View {
+	pub offset: usize,
+	pub row_stride: usize,
+	pub col_stride: usize,
+}
+
+impl View {
+	/// returns the view of the submatrix starting at `(row, col)`
+	#[inline]
+	pub fn at(self, row: usize, col: usize) -> Self {
+		Self {
+			offset: self.offset + row * self.row_stride + col * self.col_stride,
+			..self
+		}
+	}
+
+	/// returns the transposed view
+	#[inline]
+	pub fn transpose(self) -> Self {
+		Self {
+			offset: self.offset,
+			row_stride: self.col_stride,
+			col_stride: self.row_stride,
+		}
+	}
+
+	#[inline]
+	fn params(self) -> [u32; 3] {
+		[to_u32(self.offset), to_u32(self.row_stride), to_u32(self.col_stride)]
+	}
+}
+
+#[inline]
+fn to_u32(value: usize) -> u32 {
+	u32::try_from(value).expect("device matrices are limited to u32::MAX elements")
+}
+
+#[inline]
+fn div_ceil(a: usize, b: usize) -> u32 {
+	to_u32(a.div_ceil(b))
+}
+
+pub(crate) struct Gemm {
+	pub m: usize,
+	pub n: usize,
+	pub k: usize,
+	pub accum: Accum,
+	/// only the lower triangular half of the destination is written
+	pub lower_only: bool,
+	pub alpha: f32,
+}
+
+pub(crate) struct Trsm {
+	pub n: usize,
+	pub ncols: usize,
+	pub lower: bool,
+	pub unit_diag: bool,
+}
+
+const GEMM: &str = include_str!("shaders/gemm.wgsl");
+const TRSM: &str = include_str!("shaders/trsm.wgsl");
+const COPY: &str = include_str!("shaders/copy.wgsl");
+const SWAP_ROWS: &str = include_str!("shaders/swap_rows.wgsl");
+
+/// compiled compute pipelines
+pub(crate) struct Kernels {
+	gemm: wgpu::ComputePipeline,
+	trsm: wgpu::ComputePipeline,
+	copy: wgpu::ComputePipeline,
+	swap_rows: wgpu::ComputePipeline,
+}
+
+fn pipeline(device: &wgpu::Device, label: &str, source: &str) -> wgpu::ComputePipeline {
+	let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+		label: Some(label),
+		source: wgpu::ShaderSource::Wgsl(source.into()),
+	});
+	device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+		label: Some(label),
+		layout: None,
+		module: &module,
+		entry_point: Some("main"),
+		compilation_options: Default::default(),
+		cache: None,
+	})
+}
+
+impl Kernels {
+	pub fn new(device: &wgpu::Device) -> Self {
+		Self {
+			gemm: pipeline(device, "faer-gpu gemm", GEMM),
+			trsm: pipeline(device, "faer-gpu trsm", TRSM),
+			copy: pipeline(device, "faer-gpu copy", COPY),
+			swap_rows: pipeline(device, "faer-gpu swap rows", SWAP_ROWS),
+		}
+	}
+
+	fn dispatch(&self, device: &GpuDevice, pipeline: &wgpu::ComputePipeline, params: &[u32], buffers: &[&wgpu::Buffer], workgroups: (u32, u32)) {
+		if workgroups.0 == 0 || workgroups.1 == 0 {
+			return;
+		}
+
+		let params = device.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+			label: Some("faer-gpu params"),
+			contents: bytemuck::cast_slice(params),
+			usage: wgpu::BufferUsages::UNIFORM,
+		});
+
+		let mut entries = vec![wgpu::BindGroupEntry {
+			binding: 0,
+			resource: params.as_entire_binding(),
+		}];
+		for (i, buffer) in buffers.iter().enumerate() {
+			entries.push(wgpu::BindGroupEntry {
+				binding: i as u32 + 1,
+				resource: buffer.as_entire_binding(),
+			});
+		}
+
+		let bind_group = device.device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: None,
+			layout: &pipeline.get_bind_group_layout(0),
+			entries: &entries,
+		});
+
+		let mut encoder = device.device.create_command_encoder(&Default::default());
+		{
+			let mut pass = encoder.begin_compute_pass(&Default::default());
+			pass.set_pipeline(pipeline);
+			pass.set_bind_group(0, &bind_group, &[]);
+			pass.dispatch_workgroups(workgroups.0, workgroups.1, 1);
+		}
+		device.queue.submit([encoder.finish()]);
+	}
+
+	/// `dst = (dst if accumulating else 0) + alpha * lhs * rhs`
+	///
+	/// `dst` must not share a buffer with `lhs` or `rhs`
+	pub fn gemm(&self, device: &GpuDevice, gemm: Gemm, dst: (&wgpu::Buffer, View), lhs: (&wgpu::Buffer, View), rhs: (&wgpu::Buffer, View)) {
+		let Gemm {
+			m,
+			n,
+			k,
+			accum,
+			lower_only,
+			alpha,
+		} = gemm;
+
+		let flags = (matches!(accum, Accum::Add) as u32) | ((lower_only as u32) << 1);
+		let [lo, lrs, lcs] = lhs.1.params();
+		let [ro, rrs, rcs] = rhs.1.params();
+		let [d_o, drs, dcs] = dst.1.params();
+
+		#[rustfmt::skip]
+		let params = [
+			to_u32(m), to_u32(n), to_u32(k), flags,
+			lo, lrs, lcs, alpha.to_bits(),
+			ro, rrs, rcs, 0,
+			d_o, drs, dcs, 0,
+		];
+
+		self.dispatch(device, &self.gemm, &params, &[lhs.0, rhs.0, dst.0], (div_ceil(m, 64), div_ceil(n, 64)));
+	}
+
+	/// solves `tri * x = rhs` in place, where `tri` is a small triangular block
+	///
+	/// `tri` must not share a buffer with `rhs`
+	pub fn trsm(&self, device: &GpuDevice, trsm: Trsm, tri: (&wgpu::Buffer, View), rhs: (&wgpu::Buffer, View)) {
+		let Trsm { n, ncols, lower, unit_diag } = trsm;
+
+		let flags = (lower as u32) | ((unit_diag as u32) << 1);
+		let [to, trs, tcs] = tri.1.params();
+		let [ro, rrs, rcs] = rhs.1.params();
+
+		#[rustfmt::skip]
+		let params = [
+			to_u32(n), to_u32(ncols), flags, 0,
+			to, trs, tcs, 0,
+			ro, rrs, rcs, 0,
+		];
+
+		self.dispatch(device, &self.trsm, &params, &[tri.0, rhs.0], (div_ceil(ncols, 64), 1));
+	}
+
+	/// copies the `m×n` view `src` into `dst`
+	///
+	/// `src` must not share a buffer with `dst`
+	pub fn copy(&self, device: &GpuDevice, m: usize, n: usize, dst: (&wgpu::Buffer, View), src: (&wgpu::Buffer, View)) {
+		let [so, srs, scs] = src.1.params();
+		let [d_o, drs, dcs] = dst.1.params();
+
+		#[rustfmt::skip]
+		let params = [
+			to_u32(m), to_u32(n), 0, 0,
+			so, srs, scs, 0,
+			d_o, drs, dcs, 0,
+		];
+
+		self.dispatch(device, &self.copy, &params, &[src.0, dst.0], (div_ceil(m, 16), div_ceil(n, 16)));
+	}
+
+	/// applies the transpositions `row0 + i <-> row0 + transpositions[i]`, in order, to the
+	/// columns of `data` outside of `skip`
+	pub fn swap_rows(&self, device: &GpuDevice, data: &GpuMat, row0: usize, transpositions: &[u32], skip: core::ops::Range<usize>) {
+		assert!(skip.start <= skip.end);
+		if transpositions.is_empty() {
+			return;
+		}
+
+		let transpositions = device.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+			label: Some("faer-gpu transpositions"),
+			contents: bytemuck::cast_slice(transpositions),
+			usage: wgpu::BufferUsages::STORAGE,
+		});
+
+		#[rustfmt::skip]
+		let params = [
+			to_u32(data.ncols), to_u32(transpositions.size() as usize / 4), to_u32(row0), to_u32(data.nrows),
+			to_u32(skip.start), to_u32(skip.end), 0, 0,
+		];
+
+		self.dispatch(
+			device,
+			&self.swap_rows,
+			&params,
+			&[&transpositions, &data.buffer],
+			(div_ceil(data.ncols, 64), 1),
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// the shaders are checked with the same frontend `wgpu` uses, so that errors show up even on
+	// machines with no adapter
+	#[test]
+	fn test_shaders_validate() {
+		for source in [GEMM, TRSM, COPY, SWAP_ROWS] {
+			let module = naga::front::wgsl::parse_str(source).unwrap();
+			naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::empty())
+				.validate(&module)
+				.unwrap();
+		}
+	}
+}
@@ -0,0 +1,399 @@
+//! gpu backend for `faer`, built on top of `wgpu`
+//!
+//! matrices live on the device as [`GpuMat`] values, and are only moved between the host and the
+//! device through explicit calls to [`GpuDevice::upload`] and [`GpuDevice::download`]. this makes
+//! the transfer cost visible, which matters since for most problem sizes it dominates the cost of
+//! the computation itself. the backend is aimed at large problems (dimension in the thousands and
+//! above), for smaller ones `faer` on the cpu is usually faster
+//!
+//! only `f32` is supported, since double precision arithmetic is not widely available in the
+//! shading languages that `wgpu` targets
+//!
+//! the factorizations are hybrid: panels are factorized on the host with the `faer` kernels, while
+//! the trailing matrix updates, which account for almost all the flops, run on the device
+
+#![allow(non_snake_case)]
+
+use equator::assert;
+use faer::Accum;
+use faer::prelude::*;
+
+mod kernels;
+pub mod linalg;
+
+use kernels::Kernels;
+
+/// error that can occur when setting up a [`GpuDevice`] or allocating device memory
+#[derive(Debug)]
+pub enum GpuError {
+	/// no suitable adapter was found on this system
+	NoAdapter(wgpu::RequestAdapterError),
+	/// the adapter failed to provide a device
+	RequestDevice(wgpu::RequestDeviceError),
+	/// the matrix doesn't fit in a single storage buffer of the device
+	BufferTooLarge {
+		/// requested size in bytes
+		size: u64,
+		/// largest storage buffer the device supports, in bytes
+		max: u64,
+	},
+}
+
+impl core::fmt::Display for GpuError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl std::error::Error for GpuError {}
+
+/// handle to a gpu device, along with the compiled compute kernels
+pub struct GpuDevice {
+	device: wgpu::Device,
+	queue: wgpu::Queue,
+	kernels: Kernels,
+}
+
+/// column-major `f32` matrix stored in device memory
+///
+/// the columns are stored contiguously, with no padding between them
+pub struct GpuMat {
+	buffer: wgpu::Buffer,
+	nrows: usize,
+	ncols: usize,
+}
+
+impl core::fmt::Debug for GpuMat {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("GpuMat").field("nrows", &self.nrows).field("ncols", &self.ncols).finish()
+	}
+}
+
+impl GpuMat {
+	/// returns the number of rows of the matrix
+	#[inline]
+	pub fn nrows(&self) -> usize {
+		self.nrows
+	}
+
+	/// returns the number of columns of the matrix
+	#[inline]
+	pub fn ncols(&self) -> usize {
+		self.ncols
+	}
+
+	/// returns the number of rows and columns of the matrix
+	#[inline]
+	pub fn shape(&self) -> (usize, usize) {
+		(self.nrows, self.ncols)
+	}
+
+	#[inline]
+	pub(crate) fn view(&self) -> kernels::View {
+		kernels::View {
+			offset: 0,
+			row_stride: 1,
+			col_stride: self.nrows,
+		}
+	}
+}
+
+const F32_SIZE: u64 = core::mem::size_of::<f32>() as u64;
+
+/// returns the size in bytes of a buffer holding `len` elements, or an error if it exceeds the
+/// largest storage buffer allowed by `limits`
+fn buffer_size(len: usize, limits: &wgpu::Limits) -> Result<u64, GpuError> {
+	let max = Ord::min(limits.max_buffer_size, limits.max_storage_buffer_binding_size);
+	match (len as u64).checked_mul(F32_SIZE) {
+		Some(size) if size <= max => Ok(size),
+		size => Err(GpuError::BufferTooLarge {
+			size: size.unwrap_or(u64::MAX),
+			max,
+		}),
+	}
+}
+
+impl GpuDevice {
+	/// requests the default high performance adapter and creates a device from it
+	pub fn new() -> Result<Self, GpuError> {
+		let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+		let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+			power_preference: wgpu::PowerPreference::HighPerformance,
+			..Default::default()
+		}))
+		.map_err(GpuError::NoAdapter)?;
+
+		Self::from_adapter(&adapter)
+	}
+
+	/// creates a device from the given adapter, requesting the largest buffer sizes it supports
+	pub fn from_adapter(adapter: &wgpu::Adapter) -> Result<Self, GpuError> {
+		let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+			label: Some("faer-gpu"),
+			required_limits: adapter.limits(),
+			..Default::default()
+		}))
+		.map_err(GpuError::RequestDevice)?;
+
+		let kernels = Kernels::new(&device);
+		Ok(Self { device, queue, kernels })
+	}
+
+	/// returns the underlying `wgpu` device
+	#[inline]
+	pub fn device(&self) -> &wgpu::Device {
+		&self.device
+	}
+
+	/// returns the underlying `wgpu` queue
+	#[inline]
+	pub fn queue(&self) -> &wgpu::Queue {
+		&self.queue
+	}
+
+	fn alloc(&self, nrows: usize, ncols: usize) -> Result<GpuMat, GpuError> {
+		// zero sized buffers can't be bound, so we always allocate at least one element
+		let len = Ord::max(1, nrows.checked_mul(ncols).unwrap_or(usize::MAX));
+		let size = buffer_size(len, &self.device.limits())?;
+		let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("faer-gpu matrix"),
+			size,
+			usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+		Ok(GpuMat { buffer, nrows, ncols })
+	}
+
+	/// allocates a scratch matrix for an operation on matrices that are at least as large
+	///
+	/// those already fit in a storage buffer, so the scratch matrix does too
+	pub(crate) fn alloc_scratch(&self, nrows: usize, ncols: usize) -> GpuMat {
+		self.alloc(nrows, ncols).expect("the scratch matrix is no larger than the operands")
+	}
+
+	/// allocates a zero-initialized matrix on the device
+	///
+	/// # errors
+	/// returns [`GpuError::BufferTooLarge`] if the matrix doesn't fit in a single storage buffer of
+	/// the device
+	pub fn zeros(&self, nrows: usize, ncols: usize) -> Result<GpuMat, GpuError> {
+		// buffers are zero-initialized by `wgpu`
+		self.alloc(nrows, ncols)
+	}
+
+	/// copies `src` from the host to a newly allocated device matrix
+	///
+	/// # errors
+	/// returns [`GpuError::BufferTooLarge`] if the matrix doesn't fit in a single storage buffer of
+	/// the device
+	pub fn upload(&self, src: MatRef<'_, f32>) -> Result<GpuMat, GpuError> {
+		let dst = self.alloc(src.nrows(), src.ncols())?;
+		self.write_cols(&dst, 0, src);
+		Ok(dst)
+	}
+
+	/// copies `src` from the host to the device matrix `dst`
+	///
+	/// # panics
+	/// panics if `dst` and `src` don't have the same shape
+	pub fn upload_into(&self, dst: &mut GpuMat, src: MatRef<'_, f32>) {
+		assert!(dst.shape() == src.shape());
+		self.write_cols(dst, 0, src);
+	}
+
+	/// copies `src` from the device to a newly allocated host matrix
+	///
+	/// this blocks until all previously submitted work is done
+	pub fn download(&self, src: &GpuMat) -> Mat<f32> {
+		self.read_cols(src, 0, src.ncols)
+	}
+
+	/// copies `src` from the device to the host matrix `dst`
+	///
+	/// this blocks until all previously submitted work is done
+	///
+	/// # panics
+	/// panics if `dst` and `src` don't have the same shape
+	pub fn download_into(&self, dst: MatMut<'_, f32>, src: &GpuMat) {
+		assert!(dst.shape() == src.shape());
+		let mut dst = dst;
+		dst.copy_from(self.read_cols(src, 0, src.ncols));
+	}
+
+	/// writes the columns of `src` into `dst`, starting at column `col_start`
+	pub(crate) fn write_cols(&self, dst: &GpuMat, col_start: usize, src: MatRef<'_, f32>) {
+		assert!(all(src.nrows() == dst.nrows, col_start + src.ncols() <= dst.ncols));
+		self.write_packed(&dst.buffer, col_start * dst.nrows, src);
+	}
+
+	/// writes `src` to `dst` as a column-major matrix with no padding, starting at the element
+	/// offset `offset`
+	pub(crate) fn write_packed(&self, dst: &wgpu::Buffer, offset: usize, src: MatRef<'_, f32>) {
+		if src.nrows() == 0 || src.ncols() == 0 {
+			return;
+		}
+
+		let mut data = Vec::with_capacity(src.nrows() * src.ncols());
+		for j in 0..src.ncols() {
+			data.extend(src.col(j).iter().copied());
+		}
+		self.queue.write_buffer(dst, offset as u64 * F32_SIZE, bytemuck::cast_slice(&data));
+	}
+
+	/// reads `ncols` columns of `src` starting at column `col_start`
+	pub(crate) fn read_cols(&self, src: &GpuMat, col_start: usize, ncols: usize) -> Mat<f32> {
+		assert!(col_start + ncols <= src.ncols);
+		let nrows = src.nrows;
+		if nrows == 0 || ncols == 0 {
+			return Mat::zeros(nrows, ncols);
+		}
+
+		// no larger than `src`, which already fits in a buffer
+		let size = (nrows * ncols) as u64 * F32_SIZE;
+		let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("faer-gpu staging"),
+			size,
+			usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+
+		let mut encoder = self.device.create_command_encoder(&Default::default());
+		encoder.copy_buffer_to_buffer(&src.buffer, (col_start * nrows) as u64 * F32_SIZE, &staging, 0, size);
+		self.queue.submit([encoder.finish()]);
+
+		let slice = staging.slice(..);
+		let (sender, receiver) = std::sync::mpsc::channel();
+		slice.map_async(wgpu::MapMode::Read, move |result| {
+			_ = sender.send(result);
+		});
+		self.device
+			.poll(wgpu::PollType::wait_indefinitely())
+			.expect("failed to wait for the device");
+		receiver
+			.recv()
+			.expect("the device was lost before the mapping finished")
+			.expect("failed to map the staging buffer");
+
+		let dst = {
+			let data = slice.get_mapped_range().expect("failed to access the mapped staging buffer");
+			let data: &[f32] = bytemuck::cast_slice(&data);
+			Mat::from_fn(nrows, ncols, |i, j| data[i + j * nrows])
+		};
+		staging.unmap();
+		dst
+	}
+}
+
+/// computes the matrix product `[alpha * lhs * rhs]` and stores the result in `dst`
+///
+/// if `accum` is [`Accum::Replace`], the previous contents of `dst` are overwritten, otherwise the
+/// product is added to them
+///
+/// # panics
+/// panics if the matrix dimensions are not compatible for matrix multiplication
+pub fn matmul(device: &GpuDevice, dst: &mut GpuMat, accum: Accum, lhs: &GpuMat, rhs: &GpuMat, alpha: f32) {
+	assert!(all(dst.nrows == lhs.nrows, dst.ncols == rhs.ncols, lhs.ncols == rhs.nrows));
+
+	device.kernels.gemm(
+		device,
+		kernels::Gemm {
+			m: dst.nrows,
+			n: dst.ncols,
+			k: lhs.ncols,
+			accum,
+			lower_only: false,
+			alpha,
+		},
+		(&dst.buffer, dst.view()),
+		(&lhs.buffer, lhs.view()),
+		(&rhs.buffer, rhs.view()),
+	);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use equator::assert;
+	use faer::stats::prelude::*;
+	use faer::utils::approx::*;
+
+	/// the tests that need a device are ignored by default, since most ci machines have no usable
+	/// adapter. they can be run with `cargo test -p faer-gpu -- --ignored`
+	pub(crate) fn device() -> GpuDevice {
+		GpuDevice::new().expect("no usable gpu adapter")
+	}
+
+	pub(crate) fn random(rng: &mut StdRng, nrows: usize, ncols: usize) -> Mat<f32> {
+		CwiseMatDistribution {
+			nrows,
+			ncols,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f32>>(rng)
+	}
+
+	#[test]
+	fn test_buffer_size() {
+		let limits = wgpu::Limits::downlevel_defaults();
+		let max = Ord::min(limits.max_buffer_size, limits.max_storage_buffer_binding_size);
+
+		assert!(buffer_size(1, &limits).unwrap() == F32_SIZE);
+		assert!(buffer_size((max / F32_SIZE) as usize, &limits).unwrap() == max / F32_SIZE * F32_SIZE);
+		// a 10k x 10k matrix is 400MB, larger than the downlevel limits
+		assert!(matches!(
+			buffer_size(10_000 * 10_000, &limits),
+			Err(GpuError::BufferTooLarge { size: 400_000_000, max: m }) if m == max,
+		));
+		assert!(matches!(buffer_size(usize::MAX, &limits), Err(GpuError::BufferTooLarge { size: u64::MAX, .. })));
+	}
+
+	#[test]
+	#[ignore = "requires a gpu adapter"]
+	fn test_too_large() {
+		let device = device();
+		let limits = device.device().limits();
+		let max = Ord::min(limits.max_buffer_size, limits.max_storage_buffer_binding_size);
+		let n = (max / F32_SIZE).isqrt() as usize + 1;
+
+		assert!(matches!(device.zeros(n, n), Err(GpuError::BufferTooLarge { .. })));
+	}
+
+	#[test]
+	#[ignore = "requires a gpu adapter"]
+	fn test_roundtrip() {
+		let device = device();
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(0, 0), (1, 1), (3, 7), (100, 65)] {
+			let A = random(rng, m, n);
+			let A_gpu = device.upload(A.as_ref()).unwrap();
+			assert!(device.download(&A_gpu) == A);
+		}
+	}
+
+	#[test]
+	#[ignore = "requires a gpu adapter"]
+	fn test_matmul() {
+		let device = device();
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::<f32>::eps() * 128.0);
+
+		for (m, n, k) in [(1, 1, 1), (15, 17, 3), (64, 64, 64), (130, 67, 201)] {
+			let A = random(rng, m, k);
+			let B = random(rng, k, n);
+			let C = random(rng, m, n);
+
+			let A_gpu = device.upload(A.as_ref()).unwrap();
+			let B_gpu = device.upload(B.as_ref()).unwrap();
+			let mut C_gpu = device.upload(C.as_ref()).unwrap();
+
+			matmul(&device, &mut C_gpu, Accum::Add, &A_gpu, &B_gpu, 2.0);
+			let target = &C + 2.0 * &A * &B;
+			assert!(device.download(&C_gpu) ~ target);
+
+			matmul(&device, &mut C_gpu, Accum::Replace, &A_gpu, &B_gpu, -1.0);
+			let target = -&A * &B;
+			assert!(device.download(&C_gpu) ~ target);
+		}
+	}
+}
@@ -0,0 +1,137 @@
+//! $LL^\top$ factorization on the device
+//!
+//! each diagonal panel is factorized on the host, after which the trailing matrix is updated on the
+//! device
+
+use super::BLOCK_SIZE;
+use crate::kernels::{Gemm, View};
+use crate::{GpuDevice, GpuMat};
+use dyn_stack::{MemBuffer, MemStack};
+use equator::assert;
+use faer::linalg::cholesky::llt::factor::{LltError, LltInfo};
+use faer::prelude::*;
+use faer::{Accum, Par};
+
+/// computes the $LL^\top$ factorization of the symmetric positive definite matrix `A` in place
+///
+/// the lower triangular half of `A` is overwritten by the factor $L$, and its strictly upper
+/// triangular half is not accessed
+///
+/// the panels are factorized on the host with the given parallelism
+///
+/// # panics
+/// panics if `A` is not square
+pub fn cholesky_in_place(device: &GpuDevice, A: &mut GpuMat, par: Par) -> Result<LltInfo, LltError> {
+	let n = A.nrows();
+	assert!(A.nrows() == A.ncols());
+
+	let mut info = LltInfo {
+		dynamic_regularization_count: 0,
+	};
+
+	let kernels = &device.kernels;
+	let A_view = A.view();
+
+	let scratch = device.alloc_scratch(n, Ord::min(BLOCK_SIZE, n));
+	let mut mem = MemBuffer::new(faer::linalg::cholesky::llt::factor::cholesky_in_place_scratch::<f32>(
+		Ord::min(BLOCK_SIZE, n),
+		par,
+		default(),
+	));
+
+	let mut k = 0;
+	while k < n {
+		let bs = Ord::min(BLOCK_SIZE, n - k);
+		let rest = n - k - bs;
+
+		let mut panel = device.read_cols(A, k, bs);
+		{
+			let (mut A11, mut A21) = panel.as_mut().subrows_mut(k, n - k).split_at_row_mut(bs);
+
+			// the host factorization may use the strictly upper half of the diagonal block as
+			// workspace, so we save it and restore it afterwards
+			let A11_copy = A11.to_owned();
+
+			let panel_info = faer::linalg::cholesky::llt::factor::cholesky_in_place(A11.rb_mut(), default(), par, MemStack::new(&mut mem), default())
+				.map_err(|LltError::NonPositivePivot { index }| LltError::NonPositivePivot { index: index + k })?;
+			info.dynamic_regularization_count += panel_info.dynamic_regularization_count;
+
+			for j in 0..bs {
+				for i in 0..j {
+					A11[(i, j)] = A11_copy[(i, j)];
+				}
+			}
+
+			// A21 := A21 * L11^-T
+			faer::linalg::triangular_solve::solve_lower_triangular_in_place(A11.rb(), A21.rb_mut().transpose_mut(), par);
+
+			if rest > 0 {
+				device.write_packed(&scratch.buffer, 0, A21.rb());
+			}
+		}
+		device.write_cols(A, k, panel.as_ref());
+
+		if rest > 0 {
+			// A22 -= L21 * L21^T
+			let L21 = View {
+				offset: 0,
+				row_stride: 1,
+				col_stride: rest,
+			};
+			kernels.gemm(
+				device,
+				Gemm {
+					m: rest,
+					n: rest,
+					k: bs,
+					accum: Accum::Add,
+					lower_only: true,
+					alpha: -1.0,
+				},
+				(&A.buffer, A_view.at(k + bs, k + bs)),
+				(&scratch.buffer, L21),
+				(&scratch.buffer, L21.transpose()),
+			);
+		}
+
+		k += bs;
+	}
+
+	Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{device, random};
+	use equator::assert;
+	use faer::stats::prelude::*;
+	use faer::utils::approx::*;
+
+	#[test]
+	#[ignore = "requires a gpu adapter"]
+	fn test_cholesky() {
+		let device = device();
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::<f32>::eps() * 4096.0);
+
+		for n in [1, 7, 128, 129, 300] {
+			let B = random(rng, n, n);
+			let A = &B * B.transpose() + Mat::<f32>::identity(n, n);
+
+			let mut A_gpu = device.upload(A.as_ref()).unwrap();
+			cholesky_in_place(&device, &mut A_gpu, Par::Seq).unwrap();
+			let LLT = device.download(&A_gpu);
+
+			let L = Mat::from_fn(n, n, |i, j| if i >= j { LLT[(i, j)] } else { 0.0 });
+			assert!(&L * L.transpose() ~ A);
+
+			// the strictly upper half must be left untouched
+			for j in 0..n {
+				for i in 0..j {
+					assert!(LLT[(i, j)] == A[(i, j)]);
+				}
+			}
+		}
+	}
+}
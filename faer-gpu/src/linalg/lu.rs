@@ -0,0 +1,263 @@
+//! $LU$ factorization with partial pivoting on the device
+//!
+//! each column panel is factorized on the host, after which the row interchanges are applied to
+//! the rest of the matrix, and the trailing matrix is updated on the device
+
+use super::BLOCK_SIZE;
+use crate::kernels::{Gemm, Trsm, View};
+use crate::{GpuDevice, GpuMat};
+use dyn_stack::{MemBuffer, MemStack};
+use equator::assert;
+use faer::linalg::lu::partial_pivoting::factor::PartialPivLuInfo;
+use faer::perm::PermRef;
+use faer::prelude::*;
+use faer::{Accum, Par};
+
+/// decomposes the permutation `fwd` into a sequence of transpositions `i <-> t[i]`, such that
+/// applying them in order to the rows of a matrix `A` produces the rows of `P * A`
+///
+/// trailing identity transpositions are omitted
+fn transpositions(fwd: &[usize]) -> Vec<u32> {
+	let n = fwd.len();
+	// `current[i]` is the original row currently at position `i`, and `position` is its inverse
+	let mut current = (0..n).collect::<Vec<_>>();
+	let mut position = (0..n).collect::<Vec<_>>();
+
+	let mut t = Vec::with_capacity(n);
+	for i in 0..n {
+		let j = position[fwd[i]];
+		t.push(j as u32);
+
+		let (ci, cj) = (current[i], current[j]);
+		current.swap(i, j);
+		position[ci] = j;
+		position[cj] = i;
+	}
+
+	while let Some(&j) = t.last() {
+		if j as usize != t.len() - 1 {
+			break;
+		}
+		t.pop();
+	}
+	t
+}
+
+/// computes the $LU$ factorization of `A` with partial pivoting, such that $PA = LU$
+///
+/// the strictly lower triangular part of `A` is overwritten by the unit lower triangular factor
+/// $L$, and its upper triangular part by $U$. the permutation $P$ is stored in `perm` and
+/// `perm_inv`
+///
+/// the panels are factorized on the host with the given parallelism
+///
+/// # panics
+/// panics if `perm` or `perm_inv` don't have a length equal to the number of rows of `A`
+pub fn lu_in_place<'out>(
+	device: &GpuDevice,
+	A: &mut GpuMat,
+	perm: &'out mut [usize],
+	perm_inv: &'out mut [usize],
+	par: Par,
+) -> (PartialPivLuInfo, PermRef<'out, usize>) {
+	let (m, n) = A.shape();
+	let size = Ord::min(m, n);
+	assert!(all(perm.len() == m, perm_inv.len() == m));
+
+	for (i, p) in perm.iter_mut().enumerate() {
+		*p = i;
+	}
+	let mut transposition_count = 0;
+
+	let kernels = &device.kernels;
+	let A_view = A.view();
+	let block = Ord::min(BLOCK_SIZE, size);
+
+	let L11_scratch = device.alloc_scratch(block, block);
+	let L21_scratch = device.alloc_scratch(m, block);
+	let U12_scratch = device.alloc_scratch(block, n);
+
+	let panel_perm = &mut *vec![0usize; m];
+	let panel_perm_inv = &mut *vec![0usize; m];
+	let mut mem = MemBuffer::new(faer::linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<usize, f32>(
+		m,
+		block,
+		par,
+		default(),
+	));
+
+	let mut k = 0;
+	while k < size {
+		let bs = Ord::min(BLOCK_SIZE, size - k);
+		let rest_rows = m - k - bs;
+		let rest_cols = n - k - bs;
+
+		let mut panel = device.read_cols(A, k, bs);
+		let t = {
+			let panel = panel.as_mut().subrows_mut(k, m - k);
+			let (_, p) = faer::linalg::lu::partial_pivoting::factor::lu_in_place(
+				panel,
+				&mut panel_perm[..m - k],
+				&mut panel_perm_inv[..m - k],
+				par,
+				MemStack::new(&mut mem),
+				default(),
+			);
+			transpositions(p.arrays().0)
+		};
+		for (i, &j) in t.iter().enumerate() {
+			let j = j as usize;
+			if i != j {
+				perm.swap(k + i, k + j);
+				transposition_count += 1;
+			}
+		}
+		device.write_cols(A, k, panel.as_ref());
+
+		// the panel is already permuted, so only the other columns need the row interchanges
+		kernels.swap_rows(device, A, k, &t, k..k + bs);
+
+		if rest_cols > 0 {
+			// A12 := L11^-1 * A12
+			let L11 = View {
+				offset: 0,
+				row_stride: 1,
+				col_stride: bs,
+			};
+			device.write_packed(&L11_scratch.buffer, 0, panel.as_ref().submatrix(k, 0, bs, bs));
+			kernels.trsm(
+				device,
+				Trsm {
+					n: bs,
+					ncols: rest_cols,
+					lower: true,
+					unit_diag: true,
+				},
+				(&L11_scratch.buffer, L11),
+				(&A.buffer, A_view.at(k, k + bs)),
+			);
+		}
+
+		if rest_cols > 0 && rest_rows > 0 {
+			// A22 -= L21 * A12
+			let L21 = View {
+				offset: 0,
+				row_stride: 1,
+				col_stride: rest_rows,
+			};
+			let U12 = View {
+				offset: 0,
+				row_stride: 1,
+				col_stride: bs,
+			};
+			device.write_packed(&L21_scratch.buffer, 0, panel.as_ref().subrows(k + bs, rest_rows));
+			kernels.copy(device, bs, rest_cols, (&U12_scratch.buffer, U12), (&A.buffer, A_view.at(k, k + bs)));
+			kernels.gemm(
+				device,
+				Gemm {
+					m: rest_rows,
+					n: rest_cols,
+					k: bs,
+					accum: Accum::Add,
+					lower_only: false,
+					alpha: -1.0,
+				},
+				(&A.buffer, A_view.at(k + bs, k + bs)),
+				(&L21_scratch.buffer, L21),
+				(&U12_scratch.buffer, U12),
+			);
+		}
+
+		k += bs;
+	}
+
+	for (i, &p) in perm.iter().enumerate() {
+		perm_inv[p] = i;
+	}
+
+	(
+		PartialPivLuInfo {
+			transposition_count,
+			cancelled: false,
+		},
+		// SAFETY: `perm` is a permutation of `0..m`, since it was built by swapping the entries of
+		// the identity, and `perm_inv` is its inverse
+		unsafe { PermRef::new_unchecked(perm, perm_inv, m) },
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{device, random};
+	use equator::assert;
+	use faer::stats::prelude::*;
+	use faer::utils::approx::*;
+
+	#[test]
+	fn test_transpositions() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(1, 1), (5, 3), (64, 16), (200, 37)] {
+			let A = random(rng, m, n);
+			let mut LU = A.clone();
+			let perm = &mut *vec![0usize; m];
+			let perm_inv = &mut *vec![0usize; m];
+
+			let (_, p) = faer::linalg::lu::partial_pivoting::factor::lu_in_place(
+				LU.as_mut(),
+				perm,
+				perm_inv,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(faer::linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<
+					usize,
+					f32,
+				>(m, n, Par::Seq, default()))),
+				default(),
+			);
+
+			let mut PA = A.clone();
+			for (i, &j) in transpositions(p.arrays().0).iter().enumerate() {
+				let j = j as usize;
+				for col in 0..n {
+					let tmp = PA[(i, col)];
+					PA[(i, col)] = PA[(j, col)];
+					PA[(j, col)] = tmp;
+				}
+			}
+			assert!(PA == p * &A);
+		}
+	}
+
+	#[test]
+	#[ignore = "requires a gpu adapter"]
+	fn test_lu() {
+		let device = device();
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::<f32>::eps() * 4096.0);
+
+		for (m, n) in [(1, 1), (7, 3), (3, 7), (128, 128), (300, 129), (129, 300)] {
+			let A = random(rng, m, n);
+
+			let mut A_gpu = device.upload(A.as_ref()).unwrap();
+			let perm = &mut *vec![0usize; m];
+			let perm_inv = &mut *vec![0usize; m];
+			let (_, p) = lu_in_place(&device, &mut A_gpu, perm, perm_inv, Par::Seq);
+			let LU = device.download(&A_gpu);
+
+			let size = Ord::min(m, n);
+			let L = Mat::from_fn(m, size, |i, j| {
+				if i == j {
+					1.0
+				} else if i > j {
+					LU[(i, j)]
+				} else {
+					0.0
+				}
+			});
+			let U = Mat::from_fn(size, n, |i, j| if i <= j { LU[(i, j)] } else { 0.0 });
+
+			assert!(p.inverse() * &L * &U ~ A);
+		}
+	}
+}
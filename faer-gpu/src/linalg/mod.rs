@@ -0,0 +1,13 @@
+//! device implementations of the `faer` dense linear algebra routines
+
+/// blocking size used by the factorizations, and by the triangular solve
+///
+/// the diagonal blocks are handled by a kernel that is sequential along the block dimension, so
+/// this shouldn't be made much larger
+pub(crate) const BLOCK_SIZE: usize = 128;
+
+pub mod cholesky;
+pub mod lu;
+pub mod triangular_solve;
+
+pub use crate::matmul;
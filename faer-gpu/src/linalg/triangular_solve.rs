@@ -0,0 +1,169 @@
+//! triangular solve on the device
+//!
+//! the system is solved block by block: each diagonal block is solved directly, then its
+//! contribution is removed from the remaining rows with a matrix multiplication
+
+use super::BLOCK_SIZE;
+use crate::kernels::{Gemm, Trsm, View};
+use crate::{GpuDevice, GpuMat};
+use equator::assert;
+use faer::Accum;
+
+fn solve_in_place(device: &GpuDevice, tri: &GpuMat, rhs: &mut GpuMat, lower: bool, unit_diag: bool) {
+	let n = tri.nrows();
+	let k = rhs.ncols();
+	assert!(all(tri.nrows() == tri.ncols(), rhs.nrows() == n));
+
+	if n == 0 || k == 0 {
+		return;
+	}
+
+	let kernels = &device.kernels;
+	let tri_view = tri.view();
+	let rhs_view = rhs.view();
+
+	// the solved block is copied out of `rhs` before the update, since the update kernel can't read
+	// from the buffer it writes to
+	let scratch = device.alloc_scratch(Ord::min(BLOCK_SIZE, n), k);
+
+	let nblocks = n.div_ceil(BLOCK_SIZE);
+	for b in 0..nblocks {
+		let b = if lower { b } else { nblocks - 1 - b };
+		let i = b * BLOCK_SIZE;
+		let bs = Ord::min(BLOCK_SIZE, n - i);
+
+		kernels.trsm(
+			device,
+			Trsm {
+				n: bs,
+				ncols: k,
+				lower,
+				unit_diag,
+			},
+			(&tri.buffer, tri_view.at(i, i)),
+			(&rhs.buffer, rhs_view.at(i, 0)),
+		);
+
+		let (rest, rest_len) = if lower { (i + bs, n - i - bs) } else { (0, i) };
+		if rest_len == 0 {
+			continue;
+		}
+
+		let scratch_view = View {
+			offset: 0,
+			row_stride: 1,
+			col_stride: bs,
+		};
+		kernels.copy(device, bs, k, (&scratch.buffer, scratch_view), (&rhs.buffer, rhs_view.at(i, 0)));
+		kernels.gemm(
+			device,
+			Gemm {
+				m: rest_len,
+				n: k,
+				k: bs,
+				accum: Accum::Add,
+				lower_only: false,
+				alpha: -1.0,
+			},
+			(&rhs.buffer, rhs_view.at(rest, 0)),
+			(&tri.buffer, tri_view.at(rest, i)),
+			(&scratch.buffer, scratch_view),
+		);
+	}
+}
+
+/// computes the solution of `triangular_lower * x = rhs`, and stores the result in `rhs`
+///
+/// the strictly upper triangular half of `triangular_lower` is not accessed
+///
+/// # panics
+/// panics if `triangular_lower` is not square, or if its dimension doesn't match the number of
+/// rows of `rhs`
+pub fn solve_lower_triangular_in_place(device: &GpuDevice, triangular_lower: &GpuMat, rhs: &mut GpuMat) {
+	solve_in_place(device, triangular_lower, rhs, true, false)
+}
+
+/// computes the solution of `triangular_lower * x = rhs`, and stores the result in `rhs`
+///
+/// the diagonal of `triangular_lower` is implicitly assumed to be filled with ones, and the upper
+/// triangular half is not accessed
+///
+/// # panics
+/// see [`solve_lower_triangular_in_place`]
+pub fn solve_unit_lower_triangular_in_place(device: &GpuDevice, triangular_lower: &GpuMat, rhs: &mut GpuMat) {
+	solve_in_place(device, triangular_lower, rhs, true, true)
+}
+
+/// computes the solution of `triangular_upper * x = rhs`, and stores the result in `rhs`
+///
+/// the strictly lower triangular half of `triangular_upper` is not accessed
+///
+/// # panics
+/// panics if `triangular_upper` is not square, or if its dimension doesn't match the number of
+/// rows of `rhs`
+pub fn solve_upper_triangular_in_place(device: &GpuDevice, triangular_upper: &GpuMat, rhs: &mut GpuMat) {
+	solve_in_place(device, triangular_upper, rhs, false, false)
+}
+
+/// computes the solution of `triangular_upper * x = rhs`, and stores the result in `rhs`
+///
+/// the diagonal of `triangular_upper` is implicitly assumed to be filled with ones, and the lower
+/// triangular half is not accessed
+///
+/// # panics
+/// see [`solve_upper_triangular_in_place`]
+pub fn solve_unit_upper_triangular_in_place(device: &GpuDevice, triangular_upper: &GpuMat, rhs: &mut GpuMat) {
+	solve_in_place(device, triangular_upper, rhs, false, true)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{device, random};
+	use equator::assert;
+	use faer::prelude::*;
+	use faer::stats::prelude::*;
+	use faer::utils::approx::*;
+
+	#[test]
+	#[ignore = "requires a gpu adapter"]
+	fn test_triangular_solve() {
+		let device = device();
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::<f32>::eps() * 1024.0);
+
+		for (n, k) in [(1, 1), (7, 3), (128, 5), (300, 17)] {
+			// make the system well conditioned, including when the diagonal is replaced by ones
+			let mut A = random(rng, n, n) * Scale(1.0 / n as f32);
+			for i in 0..n {
+				A[(i, i)] = 1.0 + A[(i, i)].abs();
+			}
+			let B = random(rng, n, k);
+
+			let A_gpu = device.upload(A.as_ref()).unwrap();
+			let mut X_gpu = device.upload(B.as_ref()).unwrap();
+
+			for (solve, lower, unit) in [
+				(solve_lower_triangular_in_place as fn(&_, &_, &mut _), true, false),
+				(solve_unit_lower_triangular_in_place, true, true),
+				(solve_upper_triangular_in_place, false, false),
+				(solve_unit_upper_triangular_in_place, false, true),
+			] {
+				device.upload_into(&mut X_gpu, B.as_ref());
+				solve(&device, &A_gpu, &mut X_gpu);
+				let X = device.download(&X_gpu);
+
+				let T = Mat::from_fn(n, n, |i, j| {
+					if i == j && unit {
+						1.0
+					} else if (lower && i >= j) || (!lower && i <= j) {
+						A[(i, j)]
+					} else {
+						0.0
+					}
+				});
+				assert!(&T * &X ~ B);
+			}
+		}
+	}
+}
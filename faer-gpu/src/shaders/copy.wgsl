@@ -0,0 +1,30 @@
+// dst = src, for two strided views of the same shape
+
+struct Params {
+	m: u32,
+	n: u32,
+	_pad0: u32,
+	_pad1: u32,
+	src_offset: u32,
+	src_rs: u32,
+	src_cs: u32,
+	_pad2: u32,
+	dst_offset: u32,
+	dst_rs: u32,
+	dst_cs: u32,
+	_pad3: u32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(1) var<storage, read> src: array<f32>;
+@group(0) @binding(2) var<storage, read_write> dst: array<f32>;
+
+@compute @workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+	let i = gid.x;
+	let j = gid.y;
+	if i >= p.m || j >= p.n {
+		return;
+	}
+	dst[p.dst_offset + i * p.dst_rs + j * p.dst_cs] = src[p.src_offset + i * p.src_rs + j * p.src_cs];
+}
@@ -0,0 +1,116 @@
+// dst = (dst if accumulate else 0) + alpha * lhs * rhs
+// every operand is addressed as `offset + row * row_stride + col * col_stride`, which lets the
+// caller express transposes and submatrices without copying
+
+struct Params {
+	m: u32,
+	n: u32,
+	k: u32,
+	flags: u32,
+	lhs_offset: u32,
+	lhs_rs: u32,
+	lhs_cs: u32,
+	alpha: u32,
+	rhs_offset: u32,
+	rhs_rs: u32,
+	rhs_cs: u32,
+	_pad0: u32,
+	dst_offset: u32,
+	dst_rs: u32,
+	dst_cs: u32,
+	_pad1: u32,
+}
+
+const ACCUMULATE: u32 = 1u;
+const LOWER_ONLY: u32 = 2u;
+
+const TILE: u32 = 64u;
+const KTILE: u32 = 16u;
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(1) var<storage, read> lhs: array<f32>;
+@group(0) @binding(2) var<storage, read> rhs: array<f32>;
+@group(0) @binding(3) var<storage, read_write> dst: array<f32>;
+
+// lhs_tile[kk * TILE + i], rhs_tile[kk * TILE + j]
+var<workgroup> lhs_tile: array<f32, 1024>;
+var<workgroup> rhs_tile: array<f32, 1024>;
+
+@compute @workgroup_size(16, 16, 1)
+fn main(@builtin(workgroup_id) wg: vec3<u32>, @builtin(local_invocation_id) lid: vec3<u32>) {
+	let row0 = wg.x * TILE;
+	let col0 = wg.y * TILE;
+	let tid = lid.y * 16u + lid.x;
+
+	// each invocation computes a 4x4 block of the 64x64 tile, strided by 16 so that neighboring
+	// invocations touch neighboring rows
+	var acc: array<f32, 16>;
+
+	var kb = 0u;
+	loop {
+		if kb >= p.k {
+			break;
+		}
+
+		for (var t = 0u; t < 4u; t++) {
+			let e = tid + t * 256u;
+			let ij = e % TILE;
+			let kk = e / TILE;
+			let gk = kb + kk;
+
+			let gi = row0 + ij;
+			var l = 0.0;
+			if gi < p.m && gk < p.k {
+				l = lhs[p.lhs_offset + gi * p.lhs_rs + gk * p.lhs_cs];
+			}
+			lhs_tile[e] = l;
+
+			let gj = col0 + ij;
+			var r = 0.0;
+			if gk < p.k && gj < p.n {
+				r = rhs[p.rhs_offset + gk * p.rhs_rs + gj * p.rhs_cs];
+			}
+			rhs_tile[e] = r;
+		}
+		workgroupBarrier();
+
+		for (var kk = 0u; kk < KTILE; kk++) {
+			var l: array<f32, 4>;
+			var r: array<f32, 4>;
+			for (var q = 0u; q < 4u; q++) {
+				l[q] = lhs_tile[kk * TILE + lid.x + q * 16u];
+				r[q] = rhs_tile[kk * TILE + lid.y + q * 16u];
+			}
+			for (var a = 0u; a < 4u; a++) {
+				for (var b = 0u; b < 4u; b++) {
+					acc[a * 4u + b] = fma(l[a], r[b], acc[a * 4u + b]);
+				}
+			}
+		}
+		workgroupBarrier();
+
+		kb += KTILE;
+	}
+
+	let alpha = bitcast<f32>(p.alpha);
+	for (var a = 0u; a < 4u; a++) {
+		for (var b = 0u; b < 4u; b++) {
+			let i = row0 + lid.x + a * 16u;
+			let j = col0 + lid.y + b * 16u;
+			if i >= p.m || j >= p.n {
+				continue;
+			}
+			if (p.flags & LOWER_ONLY) != 0u && i < j {
+				continue;
+			}
+
+			let idx = p.dst_offset + i * p.dst_rs + j * p.dst_cs;
+			let val = alpha * acc[a * 4u + b];
+			if (p.flags & ACCUMULATE) != 0u {
+				dst[idx] = dst[idx] + val;
+			} else {
+				dst[idx] = val;
+			}
+		}
+	}
+}
@@ -0,0 +1,36 @@
+// applies the row transpositions `row0 + t <-> row0 + transpositions[t]`, in order, to every column
+// of a column-major matrix except the ones in `[skip_begin, skip_end)`
+
+struct Params {
+	ncols: u32,
+	count: u32,
+	row0: u32,
+	col_stride: u32,
+	skip_begin: u32,
+	skip_end: u32,
+	_pad0: u32,
+	_pad1: u32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(1) var<storage, read> transpositions: array<u32>;
+@group(0) @binding(2) var<storage, read_write> data: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+	let j = gid.x;
+	if j >= p.ncols || (j >= p.skip_begin && j < p.skip_end) {
+		return;
+	}
+
+	let col = j * p.col_stride;
+	for (var t = 0u; t < p.count; t++) {
+		let a = col + p.row0 + t;
+		let b = col + p.row0 + transpositions[t];
+		if a != b {
+			let tmp = data[a];
+			data[a] = data[b];
+			data[b] = tmp;
+		}
+	}
+}
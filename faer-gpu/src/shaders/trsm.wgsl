@@ -0,0 +1,57 @@
+// solves `tri * x = rhs` in place for a small triangular block, one invocation per column of `rhs`.
+// larger systems are handled by blocking on top of this kernel and `gemm`
+
+struct Params {
+	n: u32,
+	ncols: u32,
+	flags: u32,
+	_pad0: u32,
+	tri_offset: u32,
+	tri_rs: u32,
+	tri_cs: u32,
+	_pad1: u32,
+	rhs_offset: u32,
+	rhs_rs: u32,
+	rhs_cs: u32,
+	_pad2: u32,
+}
+
+const LOWER: u32 = 1u;
+const UNIT_DIAG: u32 = 2u;
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(1) var<storage, read> tri: array<f32>;
+@group(0) @binding(2) var<storage, read_write> rhs: array<f32>;
+
+fn tri_at(i: u32, j: u32) -> f32 {
+	return tri[p.tri_offset + i * p.tri_rs + j * p.tri_cs];
+}
+
+fn rhs_idx(i: u32, j: u32) -> u32 {
+	return p.rhs_offset + i * p.rhs_rs + j * p.rhs_cs;
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+	let j = gid.x;
+	if j >= p.ncols {
+		return;
+	}
+
+	let lower = (p.flags & LOWER) != 0u;
+	let unit_diag = (p.flags & UNIT_DIAG) != 0u;
+
+	// upper triangular systems are solved as lower ones with the indices reversed
+	for (var s = 0u; s < p.n; s++) {
+		let i = select(p.n - 1u - s, s, lower);
+		var x = rhs[rhs_idx(i, j)];
+		for (var q = 0u; q < s; q++) {
+			let k = select(p.n - 1u - q, q, lower);
+			x = x - tri_at(i, k) * rhs[rhs_idx(k, j)];
+		}
+		if !unit_diag {
+			x = x / tri_at(i, i);
+		}
+		rhs[rhs_idx(i, j)] = x;
+	}
+}
@@ -613,6 +613,11 @@ impl<T: ComplexField, S: Simd> SimdCtx<T, S> {
 		unsafe { core::mem::transmute_copy(&T::simd_index_add(&self.0, lhs, rhs)) }
 	}
 
+	#[inline(always)]
+	pub fn ilt(&self, lhs: T::SimdIndex<S>, rhs: T::SimdIndex<S>) -> T::SimdMask<S> {
+		T::simd_index_less_than(&self.0, lhs, rhs)
+	}
+
 	#[inline(always)]
 	pub fn or_mask(&self, lhs: T::SimdMask<S>, rhs: T::SimdMask<S>) -> T::SimdMask<S> {
 		T::simd_or_mask(&self.0, lhs, rhs)
@@ -1,6 +1,8 @@
 use super::*;
-use crate::into_range::IntoRange;
+use crate::into_range::{IntoRange, strided};
 use crate::{Idx, IdxInc, assert, debug_assert};
+use core::iter::{Rev, StepBy};
+use core::ops::Range;
 
 impl<'a, R: Shape, T, Rs: Stride, RowRange: IntoRange<IdxInc<R>, Len<R>: 'a>> ColIndex<RowRange> for ColRef<'a, T, R, Rs> {
 	type Target = ColRef<'a, T, RowRange::Len<R>, Rs>;
@@ -90,3 +92,48 @@ macro_rules! idx_impl {
 
 idx_impl!(usize);
 idx_impl!(Dim<'N>, 'N);
+
+macro_rules! strided_impl {
+    ($($S: ty),*) => {$(
+        impl<'a, T, Rs: Stride> ColIndex<$S> for ColRef<'a, T, usize, Rs> {
+            type Target = ColRef<'a, T, usize, isize>;
+
+            #[track_caller]
+            #[inline]
+            fn get(this: Self, row: $S) -> Self::Target {
+                let (start, len, step) = strided(row, this.nrows());
+                let ptr = this.ptr_at(start);
+                unsafe { ColRef::from_raw_parts(ptr, len, this.row_stride().element_stride() * step) }
+            }
+
+            #[track_caller]
+            #[inline]
+            unsafe fn get_unchecked(this: Self, row: $S) -> Self::Target {
+                ColIndex::get(this, row)
+            }
+        }
+
+        impl<'a, T, Rs: Stride> ColIndex<$S> for ColMut<'a, T, usize, Rs> {
+            type Target = ColMut<'a, T, usize, isize>;
+
+            #[track_caller]
+            #[inline]
+            fn get(this: Self, row: $S) -> Self::Target {
+                unsafe { ColIndex::get(this.into_const(), row).const_cast() }
+            }
+
+            #[track_caller]
+            #[inline]
+            unsafe fn get_unchecked(this: Self, row: $S) -> Self::Target {
+                ColIndex::get(this, row)
+            }
+        }
+    )*};
+}
+
+strided_impl!(
+	StepBy<Range<usize>>,
+	Rev<Range<usize>>,
+	StepBy<Rev<Range<usize>>>,
+	Rev<StepBy<Range<usize>>>
+);
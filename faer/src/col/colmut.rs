@@ -304,6 +304,28 @@ impl<'a, T, Rows: Shape, RStride: Stride> ColMut<'a, T, Rows, RStride> {
 }
 
 impl<T, Rows: Shape, RStride: Stride, Inner: for<'short> ReborrowMut<'short, Target = Mut<'short, T, Rows, RStride>>> generic::Col<Inner> {
+	/// returns a mutable reference to the element at the given index, or `None` if the index is
+	/// out of bounds
+	#[inline]
+	pub fn get_mut_checked(&mut self, row: usize) -> Option<&mut T> {
+		let this = self.rb_mut();
+		let row = this.nrows().idx(row)?;
+		Some(unsafe { this.at_mut_unchecked(row) })
+	}
+
+	/// writes `value` to the element at the given index, or hands it back if the index is out of
+	/// bounds
+	#[inline]
+	pub fn try_write(&mut self, row: usize, value: T) -> Result<(), T> {
+		match self.get_mut_checked(row) {
+			Some(dst) => {
+				*dst = value;
+				Ok(())
+			},
+			None => Err(value),
+		}
+	}
+
 	/// copies `other` into `self`
 	#[inline]
 	pub fn copy_from<RhsT: Conjugate<Canonical = T>>(&mut self, other: impl AsColRef<T = RhsT, Rows = Rows>)
@@ -348,6 +370,32 @@ impl<T, Rows: Shape, RStride: Stride, Inner: for<'short> ReborrowMut<'short, Tar
 		z!(self.rb_mut().as_dyn_rows_mut()).for_each(cloner::<T>(value));
 	}
 
+	/// replaces the elements of `self` that satisfy `pred` with `value`, and leaves the other
+	/// elements unchanged
+	#[inline]
+	pub fn set_where(&mut self, pred: impl FnMut(&T) -> bool, value: T)
+	where
+		T: Clone,
+	{
+		self.rb_mut().as_mat_mut().set_where(pred, value)
+	}
+
+	/// copies the elements of `other` into `self` at the positions where `mask` is `true`, and
+	/// leaves the other elements unchanged
+	#[inline]
+	#[track_caller]
+	pub fn copy_from_where<RhsT: Conjugate<Canonical = T>>(
+		&mut self,
+		other: impl AsColRef<T = RhsT, Rows = Rows>,
+		mask: impl AsColRef<T = bool, Rows = Rows>,
+	) where
+		T: ComplexField,
+	{
+		self.rb_mut()
+			.as_mat_mut()
+			.copy_from_where(other.as_col_ref().as_mat(), mask.as_col_ref().as_mat())
+	}
+
 	#[inline]
 	/// returns a view over `self`
 	pub fn as_mut(&mut self) -> ColMut<'_, T, Rows, RStride> {
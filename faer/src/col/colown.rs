@@ -193,6 +193,40 @@ impl<T> FromIterator<T> for Col<T> {
 	}
 }
 
+impl<T> Extend<T> for Col<T> {
+	fn extend<I>(&mut self, iter: I)
+	where
+		I: IntoIterator<Item = T>,
+	{
+		let iter = iter.into_iter();
+		let mut count = self.nrows();
+		// lower bound on the capacity of the column
+		let mut cap = count + iter.size_hint().0;
+		self.reserve(cap);
+
+		for item in iter {
+			if count == cap {
+				cap = Ord::max(4, cap.checked_mul(2).unwrap());
+				self.reserve(cap);
+			}
+			unsafe {
+				self.0.column.as_ptr_mut().add(count).write(item);
+				count += 1;
+				self.0.column.set_dims(count, 1);
+			}
+		}
+	}
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for Col<T> {
+	fn extend<I>(&mut self, iter: I)
+	where
+		I: IntoIterator<Item = &'a T>,
+	{
+		self.extend(iter.into_iter().copied())
+	}
+}
+
 impl<T, Rows: Shape> Col<T, Rows> {
 	/// returns the number of rows of the column
 	#[inline]
@@ -360,6 +360,15 @@ impl<'a, T, Rows: Shape, RStride: Stride> ColRef<'a, T, Rows, RStride> {
 		unsafe { MatRef::from_raw_parts(self.as_ptr(), self.nrows(), self.ncols(), self.row_stride(), 0) }
 	}
 
+	/// returns a matrix view over `self`, repeated `ncols` times as the columns of the matrix
+	///
+	/// the view has a column stride of zero, so it can be combined with other matrices of the
+	/// same shape in [`zip!`](crate::zip!) without any copies
+	#[inline]
+	pub fn broadcast<Cols: Shape>(self, ncols: Cols) -> MatRef<'a, T, Rows, Cols, RStride, isize> {
+		unsafe { MatRef::from_raw_parts(self.as_ptr(), self.nrows(), ncols, self.row_stride(), 0) }
+	}
+
 	#[inline]
 	#[doc(hidden)]
 	pub fn bind_r<'N>(self, row: Guard<'N>) -> ColRef<'a, T, Dim<'N>, RStride> {
@@ -391,6 +400,24 @@ impl<'a, T, Rows: Shape, RStride: Stride> ColRef<'a, T, Rows, RStride> {
 }
 
 impl<T, Rows: Shape, RStride: Stride, Inner: for<'short> Reborrow<'short, Target = Ref<'short, T, Rows, RStride>>> generic::Col<Inner> {
+	/// returns a reference to the element at the given index, or `None` if the index is out of
+	/// bounds
+	#[inline]
+	pub fn get_checked(&self, row: usize) -> Option<&T> {
+		let this = self.rb();
+		let row = this.nrows().idx(row)?;
+		Some(unsafe { this.at_unchecked(row) })
+	}
+
+	/// returns a clone of the element at the given index, or `None` if the index is out of bounds
+	#[inline]
+	pub fn try_read(&self, row: usize) -> Option<T>
+	where
+		T: Clone,
+	{
+		self.get_checked(row).cloned()
+	}
+
 	/// returns a newly allocated column holding the cloned values of `self`
 	#[inline]
 	pub fn cloned(&self) -> Col<T, Rows>
@@ -490,6 +517,49 @@ impl<T, Rows: Shape, RStride: Stride, Inner: for<'short> Reborrow<'short, Target
 		imp(self.rb().as_mat().as_dyn().as_dyn_stride(), rhs.as_mat_ref().as_dyn().as_dyn_stride())
 	}
 
+	/// returns the dot product $x^\top y$ of `self` and `rhs`
+	///
+	/// # panics
+	/// panics if `self` and `rhs` don't have the same number of rows
+	#[inline]
+	#[track_caller]
+	pub fn dot(&self, rhs: impl AsColRef<T: Conjugate<Canonical = T::Canonical>>) -> T::Canonical
+	where
+		T: Conjugate,
+	{
+		dot_imp(
+			self.rb().as_dyn_rows().as_dyn_stride(),
+			rhs.as_col_ref().as_dyn_rows().as_dyn_stride(),
+			Conj::No,
+		)
+	}
+
+	/// returns the dot product $x^H y$ of `self` and `rhs`, conjugating `self`
+	///
+	/// # panics
+	/// panics if `self` and `rhs` don't have the same number of rows
+	#[inline]
+	#[track_caller]
+	pub fn conj_dot(&self, rhs: impl AsColRef<T: Conjugate<Canonical = T::Canonical>>) -> T::Canonical
+	where
+		T: Conjugate,
+	{
+		dot_imp(
+			self.rb().as_dyn_rows().as_dyn_stride(),
+			rhs.as_col_ref().as_dyn_rows().as_dyn_stride(),
+			Conj::Yes,
+		)
+	}
+
+	/// returns the outer product $x y^\top$ of `self` and `rhs`
+	#[inline]
+	pub fn outer(&self, rhs: impl AsColRef<T: Conjugate<Canonical = T::Canonical>>) -> Mat<T::Canonical>
+	where
+		T: Conjugate,
+	{
+		self.rb().as_dyn_rows().as_dyn_stride() * rhs.as_col_ref().as_dyn_rows().as_dyn_stride().transpose()
+	}
+
 	/// returns `true` if all of the elements of `self` are finite.
 	/// otherwise returns `false`.
 	#[inline]
@@ -543,6 +613,22 @@ impl<T, Rows: Shape, RStride: Stride, Inner: for<'short> Reborrow<'short, Target
 	}
 }
 
+/// returns $\sum_i \text{conj}(x_i) y_i$ if `conj_lhs` is `Conj::Yes`, and $\sum_i x_i y_i$ otherwise
+#[track_caller]
+pub(crate) fn dot_imp<T: ComplexField, LT: Conjugate<Canonical = T>, RT: Conjugate<Canonical = T>>(
+	lhs: ColRef<'_, LT>,
+	rhs: ColRef<'_, RT>,
+	conj_lhs: Conj,
+) -> T {
+	assert!(lhs.nrows() == rhs.nrows());
+	linalg::matmul::dot::inner_prod(
+		lhs.canonical().transpose(),
+		Conj::get::<LT>().compose(conj_lhs),
+		rhs.canonical(),
+		Conj::get::<RT>(),
+	)
+}
+
 impl<'a, T, Rows: Shape> ColRef<'a, T, Rows, ContiguousFwd> {
 	/// returns a reference over the elements as a slice
 	#[inline]
@@ -632,7 +718,7 @@ where
 
 #[cfg(test)]
 mod tests {
-	use crate::Col;
+	use crate::{Col, c64, col, mat, row};
 
 	#[test]
 	fn test_col_min() {
@@ -655,4 +741,24 @@ mod tests {
 		let emptyref = empty.as_ref();
 		assert_eq!(emptyref.max(), None);
 	}
+
+	#[test]
+	fn test_col_dot_outer() {
+		let x = col![1.0, 2.0, 3.0];
+		let y = col![4.0, -5.0, 6.0];
+		assert_eq!(x.dot(&y), 12.0);
+		assert_eq!(x.as_ref().conj_dot(y.as_ref()), 12.0);
+		assert_eq!(x.outer(row![1.0, 2.0].transpose()), mat![[1.0, 2.0], [2.0, 4.0], [3.0, 6.0]]);
+		assert_eq!((&x + &y) * 2.0 - &y, col![6.0, -1.0, 12.0]);
+		assert_eq!(y.norm_l1(), 15.0);
+
+		let x = col![c64::new(1.0, 2.0), c64::new(0.0, 1.0)];
+		let y = col![c64::new(3.0, -1.0), c64::new(2.0, 2.0)];
+		assert_eq!(x.dot(&y), c64::new(3.0, 7.0));
+		assert_eq!(x.conj_dot(&y), c64::new(3.0, -9.0));
+		assert_eq!(x.conjugate().dot(&y), x.conj_dot(&y));
+		assert_eq!(x.conjugate().conj_dot(&y), x.dot(&y));
+		assert_eq!(x.conj_dot(&x), c64::new(x.squared_norm_l2(), 0.0));
+		assert_eq!(x.outer(y.conjugate())[(1, 0)], c64::new(-1.0, 3.0));
+	}
 }
@@ -0,0 +1,248 @@
+use crate::internal_prelude::*;
+use core::iter::FusedIterator;
+
+/// iterator over references to the elements of a column or a row, in order
+///
+/// returned by the [`IntoIterator`] implementations of [`ColRef`] and [`RowRef`]
+pub struct Iter<'a, T> {
+	col: ColRef<'a, T>,
+	start: usize,
+	end: usize,
+}
+
+/// iterator over mutable references to the elements of a column or a row, in order
+///
+/// returned by the [`IntoIterator`] implementations of [`ColMut`] and [`RowMut`]
+pub struct IterMut<'a, T> {
+	col: ColMut<'a, T>,
+	start: usize,
+	end: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+	#[inline]
+	pub(crate) fn new(col: ColRef<'a, T>) -> Self {
+		Self {
+			col,
+			start: 0,
+			end: col.nrows(),
+		}
+	}
+}
+
+impl<'a, T> IterMut<'a, T> {
+	#[inline]
+	pub(crate) fn new(col: ColMut<'a, T>) -> Self {
+		let end = col.nrows();
+		Self { col, start: 0, end }
+	}
+}
+
+impl<T> Clone for Iter<'_, T> {
+	#[inline]
+	fn clone(&self) -> Self {
+		Self { ..*self }
+	}
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = &'a T;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.start == self.end {
+			return None;
+		}
+		let i = self.start;
+		self.start += 1;
+		Some(unsafe { self.col.at_unchecked(i) })
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.end - self.start;
+		(len, Some(len))
+	}
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+	#[inline]
+	fn next_back(&mut self) -> Option<Self::Item> {
+		if self.start == self.end {
+			return None;
+		}
+		self.end -= 1;
+		Some(unsafe { self.col.at_unchecked(self.end) })
+	}
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+	type Item = &'a mut T;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.start == self.end {
+			return None;
+		}
+		let i = self.start;
+		self.start += 1;
+		// each index is yielded at most once, so the references don't alias
+		Some(unsafe { &mut *self.col.ptr_inbounds_at_mut(i) })
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.end - self.start;
+		(len, Some(len))
+	}
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+	#[inline]
+	fn next_back(&mut self) -> Option<Self::Item> {
+		if self.start == self.end {
+			return None;
+		}
+		self.end -= 1;
+		Some(unsafe { &mut *self.col.ptr_inbounds_at_mut(self.end) })
+	}
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<'a, T, Rows: Shape, RStride: Stride> IntoIterator for ColRef<'a, T, Rows, RStride> {
+	type IntoIter = Iter<'a, T>;
+	type Item = &'a T;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		Iter::new(self.as_dyn_rows().as_dyn_stride())
+	}
+}
+
+impl<'a, T, Rows: Shape, RStride: Stride> IntoIterator for ColMut<'a, T, Rows, RStride> {
+	type IntoIter = IterMut<'a, T>;
+	type Item = &'a mut T;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		IterMut::new(self.as_dyn_rows_mut().as_dyn_stride_mut())
+	}
+}
+
+impl<'a, T, Rows: Shape> IntoIterator for &'a Col<T, Rows> {
+	type IntoIter = Iter<'a, T>;
+	type Item = &'a T;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.as_ref().into_iter()
+	}
+}
+
+impl<'a, T, Rows: Shape> IntoIterator for &'a mut Col<T, Rows> {
+	type IntoIter = IterMut<'a, T>;
+	type Item = &'a mut T;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.as_mut().into_iter()
+	}
+}
+
+impl<'a, T, Cols: Shape, CStride: Stride> IntoIterator for RowRef<'a, T, Cols, CStride> {
+	type IntoIter = Iter<'a, T>;
+	type Item = &'a T;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.transpose().into_iter()
+	}
+}
+
+impl<'a, T, Cols: Shape, CStride: Stride> IntoIterator for RowMut<'a, T, Cols, CStride> {
+	type IntoIter = IterMut<'a, T>;
+	type Item = &'a mut T;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.transpose_mut().into_iter()
+	}
+}
+
+impl<'a, T, Cols: Shape> IntoIterator for &'a Row<T, Cols> {
+	type IntoIter = Iter<'a, T>;
+	type Item = &'a T;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.as_ref().into_iter()
+	}
+}
+
+impl<'a, T, Cols: Shape> IntoIterator for &'a mut Row<T, Cols> {
+	type IntoIter = IterMut<'a, T>;
+	type Item = &'a mut T;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.as_mut().into_iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Col, Row, assert, col, row};
+
+	#[test]
+	fn test_iter() {
+		let mut x = col![1.0, 2.0, 3.0, 4.0];
+		let mut total = 0.0;
+		for v in &x {
+			total += *v;
+		}
+		assert!(total == 10.0);
+
+		for v in &mut x {
+			*v *= 2.0;
+		}
+		assert!(x == col![2.0, 4.0, 6.0, 8.0]);
+
+		// strided, reversed and double ended
+		let y = x.as_ref().reverse_rows().subrows(0, 3);
+		assert!(y.into_iter().copied().collect::<alloc::vec::Vec<_>>() == [8.0, 6.0, 4.0]);
+		assert!(y.into_iter().rev().copied().collect::<alloc::vec::Vec<_>>() == [4.0, 6.0, 8.0]);
+		assert!(y.into_iter().len() == 3);
+
+		let mut it = y.into_iter();
+		assert!(it.next() == Some(&8.0));
+		assert!(it.next_back() == Some(&4.0));
+		assert!(it.len() == 1);
+		assert!(it.next() == Some(&6.0));
+		assert!(it.next() == None);
+		assert!(it.next_back() == None);
+
+		// collect and extend
+		let z: Col<f64> = x.as_ref().into_iter().map(|v| v + 1.0).collect();
+		assert!(z == col![3.0, 5.0, 7.0, 9.0]);
+
+		let mut r: Row<f64> = (0..3).map(|i| i as f64).collect();
+		r.extend([3.0, 4.0]);
+		r.extend((5..40).map(|i| i as f64).filter(|v| *v < 10.0));
+		r.extend(&[10.0]);
+		assert!(r == Row::from_fn(11, |i| i as f64));
+
+		let mut w = Col::<f64>::zeros(0);
+		w.extend(r.transpose());
+		for v in r.as_mut() {
+			*v = -*v;
+		}
+		assert!(w == Col::from_fn(11, |i| i as f64));
+		assert!(r == -Row::from_fn(11, |i| i as f64));
+		assert!(row![1.0, 2.0].into_iter().sum::<f64>() == 3.0);
+	}
+}
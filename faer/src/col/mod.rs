@@ -29,6 +29,9 @@ impl<T: ?Sized, Rows: Copy, RStride: Copy> Clone for ColView<T, Rows, RStride> {
 
 mod col_index;
 
+/// iterators over the elements of a column or a row
+pub mod iter;
+
 pub(crate) mod colmut;
 pub(crate) mod colown;
 pub(crate) mod colref;
@@ -0,0 +1,261 @@
+//! human readable matrix formatting
+//!
+//! matrices, columns and rows implement [`Display`](core::fmt::Display) and
+//! [`LowerExp`](core::fmt::LowerExp), which print the entries in fixed and scientific notation
+//! respectively, aligned in columns. the precision of the format specifier is forwarded to each
+//! entry, so `{:.3}` prints three decimal places and `{:.2e}` prints two significant digits after
+//! the decimal point in scientific notation
+//!
+//! large matrices are truncated, showing only the first and last rows and columns, separated by
+//! `...`. the default limits can be changed with `display_with` and [`DisplayParams`]
+//!
+//! # example
+//! ```
+//! use faer::fmt::DisplayParams;
+//! use faer::mat;
+//!
+//! let A = mat![[1.0, -2.5], [3.0, 4.0]];
+//! assert_eq!(format!("{A:.2}"), "[[1.00  -2.50]\n [3.00   4.00]]");
+//! assert_eq!(format!("{A:.1e}"), "[[1.0e0  -2.5e0]\n [3.0e0   4.0e0]]");
+//!
+//! let B = faer::Mat::from_fn(100, 100, |i, j| (i + j) as f64);
+//! let params = DisplayParams {
+//!     max_rows: 2,
+//!     max_cols: 3,
+//!     ..Default::default()
+//! };
+//! assert_eq!(format!("{}", B.display_with(params)), "[[ 0    1  ...   99]\n ...\n [99  100  ...  198]]");
+//! ```
+
+use crate::internal_prelude::*;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+/// matrix display parameters
+#[derive(Copy, Clone, Debug)]
+pub struct DisplayParams {
+	/// maximum number of rows that are printed. larger matrices only show their first and last
+	/// rows
+	pub max_rows: usize,
+	/// maximum number of columns that are printed. larger matrices only show their first and last
+	/// columns
+	pub max_cols: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for DisplayParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			max_rows: 16,
+			max_cols: 8,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// wrapper for displaying a matrix with the given parameters, returned by `display_with`
+#[derive(Copy, Clone, Debug)]
+pub struct MatDisplay<'a, T> {
+	mat: MatRef<'a, T>,
+	params: DisplayParams,
+}
+
+impl<'a, T> MatDisplay<'a, T> {
+	/// returns a wrapper displaying `mat` with the given parameters
+	#[inline]
+	pub fn new(mat: MatRef<'a, T>, params: DisplayParams) -> Self {
+		Self { mat, params }
+	}
+}
+
+/// indices that are shown along a dimension of size `n`, and the position of the ellipsis if the
+/// dimension is truncated
+fn shown(n: usize, max: usize) -> (Vec<usize>, Option<usize>) {
+	let max = Ord::max(max, 1);
+	if n <= max {
+		((0..n).collect(), None)
+	} else {
+		let head = max.msrv_div_ceil(2);
+		let tail = max / 2;
+		((0..head).chain(n - tail..n).collect(), Some(head))
+	}
+}
+
+fn write_mat<T>(
+	f: &mut fmt::Formatter<'_>,
+	A: MatRef<'_, T>,
+	params: DisplayParams,
+	fmt_entry: impl Fn(&mut String, &T, Option<usize>) -> fmt::Result,
+) -> fmt::Result {
+	let (m, n) = A.shape();
+	if m == 0 || n == 0 {
+		return f.write_str("[]");
+	}
+
+	let (rows, row_ellipsis) = shown(m, params.max_rows);
+	let (cols, col_ellipsis) = shown(n, params.max_cols);
+
+	let precision = f.precision();
+	let mut entries = Vec::with_capacity(rows.len() * cols.len());
+	for &i in &rows {
+		for &j in &cols {
+			let mut s = String::new();
+			fmt_entry(&mut s, &A[(i, j)], precision)?;
+			entries.push(s);
+		}
+	}
+
+	let ncols = cols.len();
+	let widths = (0..ncols)
+		.map(|j| entries.iter().skip(j).step_by(ncols).map(|s| s.chars().count()).max().unwrap_or(0))
+		.collect::<Vec<_>>();
+
+	f.write_str("[")?;
+	for (k, row) in entries.chunks(ncols).enumerate() {
+		if k > 0 {
+			f.write_str("\n ")?;
+			if row_ellipsis == Some(k) {
+				f.write_str("...\n ")?;
+			}
+		}
+		f.write_str("[")?;
+		for (j, (entry, &width)) in row.iter().zip(&widths).enumerate() {
+			if j > 0 {
+				f.write_str("  ")?;
+				if col_ellipsis == Some(j) {
+					f.write_str("...  ")?;
+				}
+			}
+			for _ in entry.chars().count()..width {
+				f.write_char(' ')?;
+			}
+			f.write_str(entry)?;
+		}
+		if col_ellipsis == Some(ncols) {
+			f.write_str("  ...")?;
+		}
+		f.write_str("]")?;
+	}
+	if row_ellipsis == Some(rows.len()) {
+		f.write_str("\n ...")?;
+	}
+	f.write_str("]")
+}
+
+fn fixed<T: fmt::Display>(s: &mut String, value: &T, precision: Option<usize>) -> fmt::Result {
+	match precision {
+		Some(precision) => write!(s, "{value:.precision$}"),
+		None => write!(s, "{value}"),
+	}
+}
+
+fn scientific<T: fmt::LowerExp>(s: &mut String, value: &T, precision: Option<usize>) -> fmt::Result {
+	match precision {
+		Some(precision) => write!(s, "{value:.precision$e}"),
+		None => write!(s, "{value:e}"),
+	}
+}
+
+impl<T: fmt::Display> fmt::Display for MatDisplay<'_, T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write_mat(f, self.mat, self.params, fixed)
+	}
+}
+
+impl<T: fmt::LowerExp> fmt::LowerExp for MatDisplay<'_, T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write_mat(f, self.mat, self.params, scientific)
+	}
+}
+
+macro_rules! impl_display {
+	($({$($gen: tt)*} $ty: ty;)*) => {$(
+		impl<$($gen)*> $ty {
+			/// returns a wrapper that displays `self` with the given parameters
+			#[inline]
+			pub fn display_with(&self, params: DisplayParams) -> MatDisplay<'_, T> {
+				MatDisplay::new(AsMatRef::as_mat_ref(self).as_dyn().as_dyn_stride(), params)
+			}
+		}
+
+		impl<$($gen)*> fmt::Display for $ty
+		where
+			T: fmt::Display,
+		{
+			#[inline]
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				fmt::Display::fmt(&self.display_with(DisplayParams::default()), f)
+			}
+		}
+
+		impl<$($gen)*> fmt::LowerExp for $ty
+		where
+			T: fmt::LowerExp,
+		{
+			#[inline]
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				fmt::LowerExp::fmt(&self.display_with(DisplayParams::default()), f)
+			}
+		}
+	)*};
+}
+
+impl_display! {
+	{T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride} MatRef<'_, T, Rows, Cols, RStride, CStride>;
+	{T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride} MatMut<'_, T, Rows, Cols, RStride, CStride>;
+	{T, Rows: Shape, Cols: Shape} Mat<T, Rows, Cols>;
+	{T, Rows: Shape, RStride: Stride} ColRef<'_, T, Rows, RStride>;
+	{T, Rows: Shape, RStride: Stride} ColMut<'_, T, Rows, RStride>;
+	{T, Rows: Shape} Col<T, Rows>;
+	{T, Cols: Shape, CStride: Stride} RowRef<'_, T, Cols, CStride>;
+	{T, Cols: Shape, CStride: Stride} RowMut<'_, T, Cols, CStride>;
+	{T, Cols: Shape} Row<T, Cols>;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{assert, c64, col, mat, row};
+	use alloc::format;
+
+	#[test]
+	fn test_display() {
+		let A = mat![[1.0, -20.0, 3.5], [4.0, 5.0, 6.0]];
+		assert!(format!("{A}") == "[[1  -20  3.5]\n [4    5    6]]");
+		assert!(format!("{:.1}", A.as_ref()) == "[[1.0  -20.0  3.5]\n [4.0    5.0  6.0]]");
+		assert!(format!("{:.1e}", A.transpose()) == "[[ 1.0e0  4.0e0]\n [-2.0e1  5.0e0]\n [ 3.5e0  6.0e0]]");
+
+		let x = col![1.0, 2.0];
+		assert!(format!("{x}") == "[[1]\n [2]]");
+		let x = row![1.0, 2.0];
+		assert!(format!("{x}") == "[[1  2]]");
+
+		let z = mat![[c64::new(1.0, -2.0)], [c64::new(0.5, 0.25)]];
+		assert!(format!("{z:.2}") == "[[1.00-2.00i]\n [0.50+0.25i]]");
+
+		assert!(format!("{}", Mat::<f64>::zeros(0, 3)) == "[]");
+	}
+
+	#[test]
+	fn test_truncation() {
+		let A = Mat::from_fn(5, 6, |i, j| (10 * i + j) as f64);
+		let params = DisplayParams {
+			max_rows: 3,
+			max_cols: 2,
+			..Default::default()
+		};
+		assert!(format!("{}", A.display_with(params)) == "[[ 0  ...   5]\n [10  ...  15]\n ...\n [40  ...  45]]");
+
+		let params = DisplayParams {
+			max_rows: 1,
+			max_cols: 0,
+			..Default::default()
+		};
+		assert!(format!("{}", A.col(2).display_with(params)) == "[[2]\n ...]");
+		assert!(format!("{}", A.row(1).display_with(params)) == "[[10  ...]]");
+	}
+}
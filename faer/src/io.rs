@@ -173,3 +173,1751 @@ pub mod npy {
 		}
 	}
 }
+
+/// delimited text (csv/tsv) conversions
+///
+/// # example
+/// ```
+/// use faer::io::csv::{self, CsvFormat};
+/// use faer::mat;
+///
+/// let a = mat![[1.0, 2.5], [-3.0, 4.0]];
+///
+/// let mut buf = Vec::new();
+/// csv::write(&mut buf, a.as_ref(), Some(&["x", "y"]), CsvFormat::CSV).unwrap();
+/// assert!(buf == b"x,y\n1,2.5\n-3,4\n");
+///
+/// let (b, header) = csv::read::<f64>(&buf[..], true, CsvFormat::CSV).unwrap();
+/// assert!(b == a);
+/// assert!(header.unwrap() == ["x", "y"]);
+/// ```
+pub mod csv {
+	use super::*;
+	use core::fmt;
+	use core::str::FromStr;
+	use std::io::{BufRead, Error, ErrorKind};
+	use std::string::{String, ToString};
+	use std::vec::Vec;
+
+	/// formatting of complex entries
+	#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+	pub enum ComplexFormat {
+		/// each entry is written as a single field, such as `1.5-2i`
+		///
+		/// when reading, a trailing `j` is also accepted in place of `i`
+		Algebraic,
+		/// each entry is written as two adjacent fields, holding the real and imaginary parts.
+		/// header names are suffixed with `_re` and `_im`
+		Split,
+	}
+
+	/// layout of a delimited text file
+	#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+	pub struct CsvFormat {
+		/// field separator
+		pub delimiter: char,
+		/// formatting of complex entries, ignored for real matrices
+		pub complex: ComplexFormat,
+	}
+
+	impl CsvFormat {
+		/// comma separated values
+		pub const CSV: Self = Self {
+			delimiter: ',',
+			complex: ComplexFormat::Algebraic,
+		};
+		/// tab separated values
+		pub const TSV: Self = Self {
+			delimiter: '\t',
+			complex: ComplexFormat::Algebraic,
+		};
+	}
+
+	impl Default for CsvFormat {
+		#[inline]
+		fn default() -> Self {
+			Self::CSV
+		}
+	}
+
+	/// trait implemented for native types that can be written to and read from delimited text
+	pub trait CsvScalar: Copy {
+		/// whether the type has an imaginary part
+		const IS_COMPLEX: bool;
+		/// type of the real and imaginary parts
+		type Real: Copy + Default + fmt::Display + FromStr;
+
+		/// returns the real and imaginary parts of `self`
+		fn to_parts(&self) -> (Self::Real, Self::Real);
+		/// builds a value from its real and imaginary parts
+		fn from_parts(re: Self::Real, im: Self::Real) -> Self;
+	}
+
+	macro_rules! impl_real {
+		($($ty: ty),*) => {$(
+			impl CsvScalar for $ty {
+				const IS_COMPLEX: bool = false;
+				type Real = $ty;
+
+				#[inline]
+				fn to_parts(&self) -> ($ty, $ty) {
+					(*self, 0.0)
+				}
+
+				#[inline]
+				fn from_parts(re: $ty, _: $ty) -> Self {
+					re
+				}
+			}
+		)*};
+	}
+
+	macro_rules! impl_complex {
+		($($ty: ty => $real: ty),*) => {$(
+			impl CsvScalar for $ty {
+				const IS_COMPLEX: bool = true;
+				type Real = $real;
+
+				#[inline]
+				fn to_parts(&self) -> ($real, $real) {
+					(self.re, self.im)
+				}
+
+				#[inline]
+				fn from_parts(re: $real, im: $real) -> Self {
+					<$ty>::new(re, im)
+				}
+			}
+		)*};
+	}
+
+	impl_real!(f32, f64);
+	impl_complex!(c32 => f32, c64 => f64);
+
+	/// [`fmt::Display`] adapter that writes a matrix as delimited text, with one line per row
+	///
+	/// created by [`display`]. it can be written to any [`fmt::Write`] sink, such as a
+	/// [`String`], with [`write!`]
+	pub struct CsvDisplay<'a, T> {
+		mat: MatRef<'a, T>,
+		header: Option<&'a [&'a str]>,
+		format: CsvFormat,
+	}
+
+	/// returns an adapter that formats `mat` as delimited text, preceded by the `header` line if
+	/// provided
+	///
+	/// # panics
+	/// panics if `header` is provided and its length doesn't match `mat.ncols()`
+	#[track_caller]
+	pub fn display<'a, T: CsvScalar>(mat: MatRef<'a, T>, header: Option<&'a [&'a str]>, format: CsvFormat) -> CsvDisplay<'a, T> {
+		if let Some(header) = header {
+			assert!(header.len() == mat.ncols());
+		}
+		CsvDisplay { mat, header, format }
+	}
+
+	/// writes `mat` to `writer` as delimited text, preceded by the `header` line if provided
+	///
+	/// # panics
+	/// panics if `header` is provided and its length doesn't match `mat.ncols()`
+	#[track_caller]
+	pub fn write<T: CsvScalar>(mut writer: impl std::io::Write, mat: MatRef<'_, T>, header: Option<&[&str]>, format: CsvFormat) -> Result<(), Error> {
+		use std::io::Write;
+
+		let mut writer = std::io::BufWriter::new(&mut writer);
+		write!(writer, "{}", display(mat, header, format))?;
+		writer.flush()
+	}
+
+	fn is_split<T: CsvScalar>(format: CsvFormat) -> bool {
+		T::IS_COMPLEX && format.complex == ComplexFormat::Split
+	}
+
+	fn write_name(f: &mut fmt::Formatter<'_>, name: &str, suffix: &str, delimiter: char) -> fmt::Result {
+		if name.contains([delimiter, '"', '\n', '\r']) {
+			write!(f, "\"{}{suffix}\"", name.replace('"', "\"\""))
+		} else {
+			write!(f, "{name}{suffix}")
+		}
+	}
+
+	impl<T: CsvScalar> fmt::Display for CsvDisplay<'_, T> {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			let delimiter = self.format.delimiter;
+			let split = is_split::<T>(self.format);
+
+			if let Some(header) = self.header {
+				for (j, name) in header.iter().enumerate() {
+					if j > 0 {
+						write!(f, "{delimiter}")?;
+					}
+					if split {
+						write_name(f, name, "_re", delimiter)?;
+						write!(f, "{delimiter}")?;
+						write_name(f, name, "_im", delimiter)?;
+					} else {
+						write_name(f, name, "", delimiter)?;
+					}
+				}
+				writeln!(f)?;
+			}
+
+			for i in 0..self.mat.nrows() {
+				for j in 0..self.mat.ncols() {
+					if j > 0 {
+						write!(f, "{delimiter}")?;
+					}
+					let (re, im) = self.mat[(i, j)].to_parts();
+					if !T::IS_COMPLEX {
+						write!(f, "{re}")?;
+					} else if split {
+						write!(f, "{re}{delimiter}{im}")?;
+					} else {
+						let im = im.to_string();
+						let sign = if im.starts_with('-') { "" } else { "+" };
+						write!(f, "{re}{sign}{im}i")?;
+					}
+				}
+				writeln!(f)?;
+			}
+			Ok(())
+		}
+	}
+
+	/// splits a line into fields, handling double-quoted fields with `""` escapes
+	fn split_fields(line: &str, delimiter: char) -> Option<Vec<String>> {
+		let mut fields = Vec::new();
+		let mut chars = line.chars().peekable();
+
+		loop {
+			let mut field = String::new();
+			if chars.peek() == Some(&'"') {
+				chars.next();
+				loop {
+					match chars.next()? {
+						'"' if chars.peek() == Some(&'"') => {
+							chars.next();
+							field.push('"');
+						},
+						'"' => break,
+						c => field.push(c),
+					}
+				}
+				match chars.next() {
+					None => {
+						fields.push(field);
+						return Some(fields);
+					},
+					Some(c) if c == delimiter => {},
+					Some(_) => return None,
+				}
+			} else {
+				loop {
+					match chars.next() {
+						None => {
+							fields.push(field);
+							return Some(fields);
+						},
+						Some(c) if c == delimiter => break,
+						Some(c) => field.push(c),
+					}
+				}
+			}
+			fields.push(field);
+		}
+	}
+
+	fn parse_real<R: FromStr>(field: &str) -> Option<R> {
+		field.trim().parse().ok()
+	}
+
+	/// parses a complex number of the form `a`, `bi`, or `a+bi`
+	fn parse_algebraic<R: Copy + Default + FromStr>(field: &str) -> Option<(R, R)> {
+		let field = field.trim();
+		let Some(imag) = field.strip_suffix(['i', 'j']) else {
+			return Some((parse_real(field)?, R::default()));
+		};
+
+		// the imaginary part starts at the last sign that isn't part of an exponent
+		let bytes = imag.as_bytes();
+		let pos = (1..bytes.len())
+			.rev()
+			.find(|&k| matches!(bytes[k], b'+' | b'-') && !matches!(bytes[k - 1], b'e' | b'E'));
+
+		let (re, im) = match pos {
+			Some(k) => (parse_real(&imag[..k])?, &imag[k..]),
+			None => (R::default(), imag),
+		};
+		let im = im.strip_prefix('+').unwrap_or(im);
+		Some((re, parse_real(im)?))
+	}
+
+	fn invalid(line: usize, msg: &str) -> Error {
+		Error::new(ErrorKind::InvalidData, std::format!("line {line}: {msg}"))
+	}
+
+	/// reads a matrix from delimited text, with one line per row. empty lines are skipped
+	///
+	/// if `has_header` is `true`, the first line is parsed as the column names, which are returned
+	/// alongside the matrix. with [`ComplexFormat::Split`], the names of the real parts are
+	/// returned, with their `_re` suffix removed
+	pub fn read<T: CsvScalar>(reader: impl BufRead, has_header: bool, format: CsvFormat) -> Result<(Mat<T>, Option<Vec<String>>), Error> {
+		let split = is_split::<T>(format);
+		let width = if split { 2 } else { 1 };
+
+		let mut header = None;
+		let mut ncols = None;
+		let mut data = Vec::new();
+		let mut nrows = 0usize;
+
+		for (line_idx, line) in reader.lines().enumerate() {
+			let line = line?;
+			let line_no = line_idx + 1;
+			let line = line.strip_suffix('\r').unwrap_or(&line);
+			if line.trim().is_empty() {
+				continue;
+			}
+
+			let fields = split_fields(line, format.delimiter).ok_or_else(|| invalid(line_no, "malformed quoted field"))?;
+			if fields.len() % width != 0 {
+				return Err(invalid(line_no, "odd number of fields in split complex format"));
+			}
+			let n = fields.len() / width;
+			if *ncols.get_or_insert(n) != n {
+				return Err(invalid(line_no, "inconsistent number of fields"));
+			}
+
+			if has_header && header.is_none() {
+				header = Some(if split {
+					fields
+						.into_iter()
+						.step_by(2)
+						.map(|name| name.strip_suffix("_re").map(ToString::to_string).unwrap_or(name))
+						.collect()
+				} else {
+					fields
+				});
+				continue;
+			}
+
+			for chunk in fields.chunks(width) {
+				let parts = if split {
+					parse_real(&chunk[0]).zip(parse_real(&chunk[1]))
+				} else if T::IS_COMPLEX {
+					parse_algebraic(&chunk[0])
+				} else {
+					parse_real(&chunk[0]).map(|re| (re, T::Real::default()))
+				};
+				let (re, im) = parts.ok_or_else(|| invalid(line_no, "invalid number"))?;
+				data.push(T::from_parts(re, im));
+			}
+			nrows += 1;
+		}
+
+		let ncols = ncols.unwrap_or(0);
+		Ok((Mat::from_fn(nrows, ncols, |i, j| data[i * ncols + j]), header))
+	}
+}
+
+/// versioned binary container for dense and sparse matrices
+///
+/// a container starts with a 64-byte header, followed by the payload:
+/// - dense matrices store their entries in column-major order, with no padding between columns
+/// - sparse matrices store their column pointers and row indices, followed by their values,
+///   starting at the next multiple of 64 bytes
+///
+/// all the data is stored in little-endian byte order. since the payload of a dense matrix starts
+/// 64 bytes into the container, a memory-mapped file can be viewed in place with [`load_mmap`]
+///
+/// # example
+/// ```
+/// use faer::io::bin;
+/// use faer::mat;
+///
+/// let a = mat![[1.0, 2.0], [3.0, 4.0]];
+///
+/// let mut buf = Vec::new();
+/// bin::save(&mut buf, a.as_ref()).unwrap();
+/// let b = bin::load::<f64>(&buf[..]).unwrap();
+///
+/// assert!(b == a);
+/// ```
+pub mod bin {
+	use super::*;
+	use std::io::{Error, ErrorKind, Read, Write};
+
+	const MAGIC: [u8; 8] = *b"FAERBIN\0";
+	const VERSION: u32 = 1;
+	const HEADER_LEN: usize = 64;
+
+	const KIND_DENSE: u32 = 0;
+	#[cfg(feature = "sparse")]
+	const KIND_SPARSE_COL: u32 = 1;
+
+	/// data type of the entries of a binary container
+	#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+	pub enum DType {
+		/// 32-bit floating point
+		F32,
+		/// 64-bit floating point
+		F64,
+		/// 32-bit complex floating point
+		C32,
+		/// 64-bit complex floating point
+		C64,
+	}
+
+	impl DType {
+		fn code(self) -> u32 {
+			match self {
+				DType::F32 => 0,
+				DType::F64 => 1,
+				DType::C32 => 2,
+				DType::C64 => 3,
+			}
+		}
+	}
+
+	/// trait implemented for native types that can be stored in a binary container
+	pub trait Element: bytemuck::Pod {
+		/// data type of the entries
+		const DTYPE: DType;
+	}
+
+	impl Element for f32 {
+		const DTYPE: DType = DType::F32;
+	}
+	impl Element for f64 {
+		const DTYPE: DType = DType::F64;
+	}
+	impl Element for c32 {
+		const DTYPE: DType = DType::C32;
+	}
+	impl Element for c64 {
+		const DTYPE: DType = DType::C64;
+	}
+
+	struct Header {
+		kind: u32,
+		dtype: u32,
+		index_size: u32,
+		nrows: usize,
+		ncols: usize,
+		nnz: usize,
+	}
+
+	fn invalid(msg: &str) -> Error {
+		Error::new(ErrorKind::InvalidData, msg)
+	}
+
+	/// reads `len` entries from `reader`. the buffer grows as the data arrives instead of being
+	/// allocated upfront, so that a corrupted header can't request an arbitrarily large allocation
+	fn read_vec<T: bytemuck::Pod>(mut reader: impl Read, len: usize) -> Result<alloc::vec::Vec<T>, Error> {
+		const CHUNK_BYTES: usize = 1 << 16;
+
+		let mut out = alloc::vec::Vec::new();
+		while out.len() < len {
+			let start = out.len();
+			let chunk = Ord::min(len - start, Ord::max(start, CHUNK_BYTES / Ord::max(core::mem::size_of::<T>(), 1)));
+			out.resize(start + chunk, T::zeroed());
+			reader
+				.read_exact(bytemuck::cast_slice_mut(&mut out[start..]))
+				.map_err(|err| match err.kind() {
+					ErrorKind::UnexpectedEof => invalid("the container is shorter than its header declares"),
+					_ => err,
+				})?;
+		}
+		Ok(out)
+	}
+
+	fn check_endian() -> Result<(), Error> {
+		if cfg!(target_endian = "little") {
+			Ok(())
+		} else {
+			Err(Error::new(ErrorKind::Unsupported, "binary containers require a little-endian target"))
+		}
+	}
+
+	impl Header {
+		fn to_bytes(&self) -> [u8; HEADER_LEN] {
+			let mut bytes = [0u8; HEADER_LEN];
+			bytes[0..8].copy_from_slice(&MAGIC);
+			bytes[8..12].copy_from_slice(&VERSION.to_le_bytes());
+			bytes[12..16].copy_from_slice(&self.kind.to_le_bytes());
+			bytes[16..20].copy_from_slice(&self.dtype.to_le_bytes());
+			bytes[20..24].copy_from_slice(&self.index_size.to_le_bytes());
+			bytes[24..32].copy_from_slice(&(self.nrows as u64).to_le_bytes());
+			bytes[32..40].copy_from_slice(&(self.ncols as u64).to_le_bytes());
+			bytes[40..48].copy_from_slice(&(self.nnz as u64).to_le_bytes());
+			bytes
+		}
+
+		fn from_bytes(bytes: &[u8; HEADER_LEN]) -> Result<Self, Error> {
+			let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+			let usize_at =
+				|i: usize| usize::try_from(u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap())).map_err(|_| invalid("dimension overflows"));
+
+			if bytes[0..8] != MAGIC {
+				return Err(invalid("not a faer binary container"));
+			}
+			if u32_at(8) > VERSION {
+				return Err(invalid("unsupported container version"));
+			}
+			Ok(Self {
+				kind: u32_at(12),
+				dtype: u32_at(16),
+				index_size: u32_at(20),
+				nrows: usize_at(24)?,
+				ncols: usize_at(32)?,
+				nnz: usize_at(40)?,
+			})
+		}
+
+		fn read(mut reader: impl Read) -> Result<Self, Error> {
+			let mut bytes = [0u8; HEADER_LEN];
+			reader.read_exact(&mut bytes)?;
+			Self::from_bytes(&bytes)
+		}
+
+		fn check<T: Element>(&self, kind: u32, index_size: usize) -> Result<(), Error> {
+			if self.kind != kind {
+				return Err(invalid("unexpected matrix kind"));
+			}
+			if self.dtype != T::DTYPE.code() {
+				return Err(invalid("data type mismatch"));
+			}
+			if self.index_size as usize != index_size {
+				return Err(invalid("index type mismatch"));
+			}
+			Ok(())
+		}
+
+		fn dense_len<T>(&self) -> Result<usize, Error> {
+			self.nrows
+				.checked_mul(self.ncols)
+				.and_then(|len| len.checked_mul(core::mem::size_of::<T>()))
+				.ok_or_else(|| invalid("matrix size overflows"))
+		}
+	}
+
+	/// writes `mat` to `writer` as a dense binary container
+	pub fn save<T: Element>(writer: impl Write, mat: MatRef<'_, T>) -> Result<(), Error> {
+		check_endian()?;
+		let mut writer = std::io::BufWriter::new(writer);
+
+		let header = Header {
+			kind: KIND_DENSE,
+			dtype: T::DTYPE.code(),
+			index_size: 0,
+			nrows: mat.nrows(),
+			ncols: mat.ncols(),
+			nnz: 0,
+		};
+		writer.write_all(&header.to_bytes())?;
+
+		for j in 0..mat.ncols() {
+			let col = mat.col(j);
+			match col.try_as_col_major() {
+				Some(col) => writer.write_all(bytemuck::cast_slice(col.as_slice()))?,
+				None => {
+					for x in col.iter() {
+						writer.write_all(bytemuck::bytes_of(x))?;
+					}
+				},
+			}
+		}
+		writer.flush()
+	}
+
+	/// reads a dense matrix from a binary container
+	///
+	/// returns an error if the container doesn't hold a dense matrix with entries of type `T`
+	pub fn load<T: Element>(mut reader: impl Read) -> Result<Mat<T>, Error> {
+		check_endian()?;
+		let header = Header::read(&mut reader)?;
+		header.check::<T>(KIND_DENSE, 0)?;
+		header.dense_len::<T>()?;
+
+		let (m, n) = (header.nrows, header.ncols);
+		let data = read_vec::<T>(&mut reader, m * n)?;
+		Ok(Mat::from_fn(m, n, |i, j| data[i + m * j]))
+	}
+
+	/// dense matrix stored in a read-only memory-mapped binary container
+	#[cfg(feature = "mmap")]
+	pub struct MappedMat<T> {
+		map: memmap2::Mmap,
+		nrows: usize,
+		ncols: usize,
+		__marker: core::marker::PhantomData<T>,
+	}
+
+	#[cfg(feature = "mmap")]
+	impl<T> core::fmt::Debug for MappedMat<T> {
+		fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+			f.debug_struct("MappedMat")
+				.field("nrows", &self.nrows)
+				.field("ncols", &self.ncols)
+				.finish()
+		}
+	}
+
+	/// maps the dense binary container at `path` in memory, without copying its payload
+	///
+	/// returns an error if the container doesn't hold a dense matrix with entries of type `T`, or
+	/// if the file is too short
+	#[cfg(feature = "mmap")]
+	pub fn load_mmap<T: Element>(path: impl AsRef<std::path::Path>) -> Result<MappedMat<T>, Error> {
+		check_endian()?;
+		let file = std::fs::File::open(path)?;
+		let map = unsafe { memmap2::Mmap::map(&file)? };
+
+		let header = Header::from_bytes(
+			map.get(..HEADER_LEN)
+				.and_then(|bytes| bytes.try_into().ok())
+				.ok_or_else(|| invalid("truncated header"))?,
+		)?;
+		header.check::<T>(KIND_DENSE, 0)?;
+		if map.len() - HEADER_LEN < header.dense_len::<T>()? {
+			return Err(invalid("truncated payload"));
+		}
+		// the mapping is page aligned, so the payload is aligned to 64 bytes
+		if (map.as_ptr() as usize + HEADER_LEN) % core::mem::align_of::<T>() != 0 {
+			return Err(invalid("misaligned payload"));
+		}
+
+		Ok(MappedMat {
+			map,
+			nrows: header.nrows,
+			ncols: header.ncols,
+			__marker: core::marker::PhantomData,
+		})
+	}
+
+	#[cfg(feature = "mmap")]
+	impl<T: Element> MappedMat<T> {
+		/// returns the number of rows of the matrix
+		#[inline]
+		pub fn nrows(&self) -> usize {
+			self.nrows
+		}
+
+		/// returns the number of columns of the matrix
+		#[inline]
+		pub fn ncols(&self) -> usize {
+			self.ncols
+		}
+
+		/// returns a view over the matrix
+		#[inline]
+		pub fn as_ref(&self) -> MatRef<'_, T> {
+			let len = self.nrows * self.ncols * core::mem::size_of::<T>();
+			MatRef::from_column_major_slice(bytemuck::cast_slice(&self.map[HEADER_LEN..][..len]), self.nrows, self.ncols)
+		}
+	}
+
+	#[cfg(feature = "sparse")]
+	fn padding(len: usize) -> usize {
+		len.next_multiple_of(HEADER_LEN) - len
+	}
+
+	/// writes `mat` to `writer` as a sparse binary container, in compressed column format
+	#[cfg(feature = "sparse")]
+	pub fn save_sparse<I: crate::Index, T: Element>(writer: impl Write, mat: crate::sparse::SparseColMatRef<'_, I, T>) -> Result<(), Error> {
+		check_endian()?;
+		let mut writer = std::io::BufWriter::new(writer);
+
+		let nnz = mat.compute_nnz();
+		let header = Header {
+			kind: KIND_SPARSE_COL,
+			dtype: T::DTYPE.code(),
+			index_size: core::mem::size_of::<I>() as u32,
+			nrows: mat.nrows(),
+			ncols: mat.ncols(),
+			nnz,
+		};
+		writer.write_all(&header.to_bytes())?;
+
+		// the column pointers are recomputed, in case the matrix has gaps between its columns
+		let mut pos = I::truncate(0);
+		writer.write_all(bytemuck::bytes_of(&pos))?;
+		for j in 0..mat.ncols() {
+			pos += I::truncate(mat.col_range(j).len());
+			writer.write_all(bytemuck::bytes_of(&pos))?;
+		}
+		for j in 0..mat.ncols() {
+			writer.write_all(bytemuck::cast_slice(mat.symbolic().row_idx_of_col_raw(j)))?;
+		}
+
+		let index_len = (mat.ncols() + 1 + nnz) * core::mem::size_of::<I>();
+		writer.write_all(&[0u8; HEADER_LEN][..padding(index_len)])?;
+		for j in 0..mat.ncols() {
+			writer.write_all(bytemuck::cast_slice(mat.val_of_col(j)))?;
+		}
+		writer.flush()
+	}
+
+	/// reads a sparse matrix from a binary container
+	///
+	/// returns an error if the container doesn't hold a sparse matrix with indices of type `I` and
+	/// entries of type `T`, or if its structure is invalid
+	#[cfg(feature = "sparse")]
+	pub fn load_sparse<I: crate::Index, T: Element>(mut reader: impl Read) -> Result<crate::sparse::SparseColMat<I, T>, Error> {
+		use crate::sparse::{SparseColMat, SymbolicSparseColMat};
+		use faer_traits::SignedIndex;
+
+		check_endian()?;
+		let header = Header::read(&mut reader)?;
+		header.check::<T>(KIND_SPARSE_COL, core::mem::size_of::<I>())?;
+		let Header { nrows, ncols, nnz, .. } = header;
+
+		if ncols
+			.checked_add(1)
+			.and_then(|n| n.checked_add(nnz))
+			.and_then(|n| n.checked_mul(core::mem::size_of::<I>()))
+			.is_none()
+			|| nnz.checked_mul(core::mem::size_of::<T>()).is_none()
+			// the dimensions and the column pointers must fit in the signed index type
+			|| nrows > I::Signed::MAX.zx()
+			|| ncols > I::Signed::MAX.zx()
+			|| nnz > I::Signed::MAX.zx()
+		{
+			return Err(invalid("matrix size overflows"));
+		}
+
+		let col_ptr = read_vec::<I>(&mut reader, ncols + 1)?;
+		let row_idx = read_vec::<I>(&mut reader, nnz)?;
+		read_vec::<u8>(&mut reader, padding((ncols + 1 + nnz) * core::mem::size_of::<I>()))?;
+		let val = read_vec::<T>(&mut reader, nnz)?;
+
+		// validate the structure, so that building the matrix doesn't panic
+		if col_ptr[0].zx() != 0 || col_ptr[ncols].zx() != nnz || col_ptr.windows(2).any(|w| w[0] > w[1]) {
+			return Err(invalid("invalid column pointers"));
+		}
+		if row_idx.iter().any(|&i| i.zx() >= nrows) {
+			return Err(invalid("row index out of bounds"));
+		}
+
+		let symbolic = SymbolicSparseColMat::new_unsorted_checked(nrows, ncols, col_ptr, None, row_idx);
+		Ok(SparseColMat::new(symbolic, val))
+	}
+}
+
+/// conversions between arrow arrays and matrix views
+///
+/// numeric arrays without nulls can be viewed in place as columns, and fixed size list arrays,
+/// where each list holds one row, as row-major matrices. the conversions in the other direction
+/// copy the data, since arrow buffers can't borrow it
+///
+/// # example
+/// ```
+/// use arrow_array::Float64Array;
+/// use arrow_array::types::Float64Type;
+/// use faer::io::arrow;
+/// use faer::{col, mat};
+///
+/// let x = Float64Array::from(vec![1.0, 2.0, 3.0]);
+/// let y = Float64Array::from(vec![4.0, 5.0, 6.0]);
+///
+/// assert!(arrow::col_ref(&x).unwrap() == col![1.0, 2.0, 3.0]);
+///
+/// let a = arrow::mat_from_columns(&[&x, &y]).unwrap();
+/// assert!(a == mat![[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]);
+///
+/// let rows = arrow::to_fixed_size_list::<Float64Type>(a.as_ref());
+/// assert!(arrow::mat_ref::<Float64Type>(&rows).unwrap() == a);
+/// ```
+#[cfg(feature = "arrow")]
+pub mod arrow {
+	use super::*;
+	use arrow_array::builder::{FixedSizeListBuilder, PrimitiveBuilder};
+	use arrow_array::cast::AsArray;
+	use arrow_array::types::ArrowPrimitiveType;
+	use arrow_array::{Array, FixedSizeListArray, PrimitiveArray};
+	use std::vec::Vec;
+
+	/// returns a view over the values of `array`, or `None` if it contains nulls
+	#[inline]
+	pub fn col_ref<P: ArrowPrimitiveType>(array: &PrimitiveArray<P>) -> Option<ColRef<'_, P::Native>> {
+		if array.null_count() > 0 {
+			return None;
+		}
+		Some(ColRef::from_slice(array.values()))
+	}
+
+	/// returns an array holding a copy of the values of `col`
+	pub fn to_array<P: ArrowPrimitiveType>(col: ColRef<'_, P::Native>) -> PrimitiveArray<P> {
+		PrimitiveArray::from_iter_values(col.iter().copied())
+	}
+
+	/// returns a row-major view over the values of `array`, where the `i`-th list is the `i`-th
+	/// row of the matrix
+	///
+	/// returns `None` if the values aren't of type `P`, or if either the lists or the values
+	/// contain nulls
+	pub fn mat_ref<P: ArrowPrimitiveType>(array: &FixedSizeListArray) -> Option<MatRef<'_, P::Native>> {
+		let values = array.values().as_primitive_opt::<P>()?;
+		if array.null_count() > 0 || values.null_count() > 0 {
+			return None;
+		}
+		let ncols = array.value_length() as usize;
+		Some(MatRef::from_row_major_slice(&values.values()[..array.len() * ncols], array.len(), ncols))
+	}
+
+	/// returns a fixed size list array holding a copy of the rows of `mat`
+	pub fn to_fixed_size_list<P: ArrowPrimitiveType>(mat: MatRef<'_, P::Native>) -> FixedSizeListArray {
+		let (m, n) = mat.shape();
+		let mut builder = FixedSizeListBuilder::with_capacity(PrimitiveBuilder::<P>::with_capacity(m * n), n as i32, m);
+		for i in 0..m {
+			for j in 0..n {
+				builder.values().append_value(mat[(i, j)]);
+			}
+			builder.append(true);
+		}
+		builder.finish()
+	}
+
+	/// returns a matrix whose columns hold a copy of the values of `columns`
+	///
+	/// returns `None` if the columns have different lengths, or if any of them contains nulls
+	pub fn mat_from_columns<P: ArrowPrimitiveType>(columns: &[&PrimitiveArray<P>]) -> Option<Mat<P::Native>> {
+		let nrows = columns.first().map(|col| col.len()).unwrap_or(0);
+		let columns = columns
+			.iter()
+			.map(|col| col_ref(col).filter(|col| col.nrows() == nrows))
+			.collect::<Option<Vec<_>>>()?;
+		Some(Mat::from_fn(nrows, columns.len(), |i, j| columns[j][i]))
+	}
+
+	/// returns one array per column of `mat`, holding a copy of its values
+	pub fn to_columns<P: ArrowPrimitiveType>(mat: MatRef<'_, P::Native>) -> Vec<PrimitiveArray<P>> {
+		mat.col_iter().map(to_array).collect()
+	}
+}
+
+/// matlab level 5 mat-file conversions
+///
+/// supports reading and writing two-dimensional numeric arrays, which are converted to `f64` or
+/// [`c64`], and sparse matrices. other variables, such as cells, structs and strings, are skipped
+/// when reading. compressed variables, as written by default by matlab since version 7, are
+/// supported when reading
+///
+/// # example
+/// ```
+/// use faer::io::matlab::{self, MatlabArray};
+/// use faer::mat;
+///
+/// let a = mat![[1.0, 2.0], [3.0, 4.0]];
+///
+/// let mut buf = Vec::new();
+/// matlab::write(&mut buf, &[("A", a.as_ref().into())]).unwrap();
+///
+/// let vars = matlab::read(&buf[..]).unwrap();
+/// assert!(vars[0].0 == "A");
+/// assert!(matches!(&vars[0].1, MatlabArray::Real(b) if *b == a));
+/// ```
+#[cfg(feature = "matlab")]
+pub mod matlab {
+	use super::*;
+	use std::io::{Error, ErrorKind, Read, Write};
+	use std::string::String;
+	use std::vec::Vec;
+
+	#[cfg(feature = "sparse")]
+	use crate::sparse::{SparseColMat, SparseColMatRef, SymbolicSparseColMat};
+
+	const MI_INT8: u32 = 1;
+	const MI_UINT8: u32 = 2;
+	const MI_INT16: u32 = 3;
+	const MI_UINT16: u32 = 4;
+	const MI_INT32: u32 = 5;
+	const MI_UINT32: u32 = 6;
+	const MI_SINGLE: u32 = 7;
+	const MI_DOUBLE: u32 = 9;
+	const MI_INT64: u32 = 12;
+	const MI_UINT64: u32 = 13;
+	const MI_MATRIX: u32 = 14;
+	const MI_COMPRESSED: u32 = 15;
+
+	const MX_SPARSE_CLASS: u8 = 5;
+	const MX_DOUBLE_CLASS: u8 = 6;
+	// single, signed and unsigned integers up to 64 bits
+	const MX_NUMERIC_CLASSES: core::ops::RangeInclusive<u8> = 6..=15;
+
+	const FLAG_COMPLEX: u32 = 0x0800;
+
+	/// variable read from a mat-file
+	#[derive(Debug, Clone)]
+	pub enum MatlabArray {
+		/// dense real matrix
+		Real(Mat<f64>),
+		/// dense complex matrix
+		Complex(Mat<c64>),
+		/// sparse real matrix
+		#[cfg(feature = "sparse")]
+		SparseReal(SparseColMat<usize, f64>),
+		/// sparse complex matrix
+		#[cfg(feature = "sparse")]
+		SparseComplex(SparseColMat<usize, c64>),
+	}
+
+	/// variable to be written to a mat-file
+	#[derive(Debug, Copy, Clone)]
+	pub enum MatlabArrayRef<'a> {
+		/// dense real matrix
+		Real(MatRef<'a, f64>),
+		/// dense complex matrix
+		Complex(MatRef<'a, c64>),
+		/// sparse real matrix
+		#[cfg(feature = "sparse")]
+		SparseReal(SparseColMatRef<'a, usize, f64>),
+		/// sparse complex matrix
+		#[cfg(feature = "sparse")]
+		SparseComplex(SparseColMatRef<'a, usize, c64>),
+	}
+
+	impl<'a> From<MatRef<'a, f64>> for MatlabArrayRef<'a> {
+		#[inline]
+		fn from(mat: MatRef<'a, f64>) -> Self {
+			Self::Real(mat)
+		}
+	}
+	impl<'a> From<MatRef<'a, c64>> for MatlabArrayRef<'a> {
+		#[inline]
+		fn from(mat: MatRef<'a, c64>) -> Self {
+			Self::Complex(mat)
+		}
+	}
+	#[cfg(feature = "sparse")]
+	impl<'a> From<SparseColMatRef<'a, usize, f64>> for MatlabArrayRef<'a> {
+		#[inline]
+		fn from(mat: SparseColMatRef<'a, usize, f64>) -> Self {
+			Self::SparseReal(mat)
+		}
+	}
+	#[cfg(feature = "sparse")]
+	impl<'a> From<SparseColMatRef<'a, usize, c64>> for MatlabArrayRef<'a> {
+		#[inline]
+		fn from(mat: SparseColMatRef<'a, usize, c64>) -> Self {
+			Self::SparseComplex(mat)
+		}
+	}
+
+	fn invalid(msg: &str) -> Error {
+		Error::new(ErrorKind::InvalidData, msg)
+	}
+
+	fn u32_at(buf: &[u8], pos: usize) -> Result<u32, Error> {
+		buf.get(pos..pos + 4)
+			.map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+			.ok_or_else(|| invalid("truncated data element"))
+	}
+
+	/// reads the data element at `*pos`, and advances `*pos` past it
+	fn next_element<'a>(buf: &'a [u8], pos: &mut usize) -> Result<(u32, &'a [u8]), Error> {
+		let tag = u32_at(buf, *pos)?;
+		let (ty, start, len) = if tag >> 16 != 0 {
+			// small data element, packed with its tag in 8 bytes
+			(tag & 0xFFFF, *pos + 4, (tag >> 16) as usize)
+		} else {
+			(tag, *pos + 8, u32_at(buf, *pos + 4)? as usize)
+		};
+		let data = buf.get(start..start + len).ok_or_else(|| invalid("truncated data element"))?;
+
+		*pos = if tag >> 16 != 0 {
+			*pos + 8
+		} else if ty == MI_COMPRESSED {
+			start + len
+		} else {
+			start + len.next_multiple_of(8)
+		};
+		Ok((ty, data))
+	}
+
+	fn to_f64(ty: u32, data: &[u8]) -> Result<Vec<f64>, Error> {
+		macro_rules! convert {
+			($t: ty) => {
+				data.chunks_exact(core::mem::size_of::<$t>())
+					.map(|bytes| <$t>::from_le_bytes(bytes.try_into().unwrap()) as f64)
+					.collect()
+			};
+		}
+		Ok(match ty {
+			MI_INT8 => convert!(i8),
+			MI_UINT8 => convert!(u8),
+			MI_INT16 => convert!(i16),
+			MI_UINT16 => convert!(u16),
+			MI_INT32 => convert!(i32),
+			MI_UINT32 => convert!(u32),
+			MI_SINGLE => convert!(f32),
+			MI_DOUBLE => convert!(f64),
+			MI_INT64 => convert!(i64),
+			MI_UINT64 => convert!(u64),
+			_ => return Err(invalid("unsupported numeric data type")),
+		})
+	}
+
+	#[cfg(feature = "sparse")]
+	fn to_indices(ty: u32, data: &[u8]) -> Result<Vec<usize>, Error> {
+		to_f64(ty, data)?
+			.into_iter()
+			.map(|x| {
+				if x >= 0.0 && x == libm::trunc(x) {
+					Ok(x as usize)
+				} else {
+					Err(invalid("invalid sparse index"))
+				}
+			})
+			.collect()
+	}
+
+	fn parse_matrix(buf: &[u8]) -> Result<Option<(String, MatlabArray)>, Error> {
+		if buf.is_empty() {
+			return Ok(None);
+		}
+		let mut pos = 0;
+		let (_, flags) = next_element(buf, &mut pos)?;
+		let flags0 = u32_at(flags, 0)?;
+		let class = (flags0 & 0xFF) as u8;
+		let is_complex = flags0 & FLAG_COMPLEX != 0;
+
+		let (_, dims) = next_element(buf, &mut pos)?;
+		let (_, name) = next_element(buf, &mut pos)?;
+		let name = String::from_utf8_lossy(name).into_owned();
+
+		if dims.len() != 8 || !(class == MX_SPARSE_CLASS || MX_NUMERIC_CLASSES.contains(&class)) {
+			return Ok(None);
+		}
+		let nrows = u32_at(dims, 0)? as usize;
+		let ncols = u32_at(dims, 4)? as usize;
+
+		if class == MX_SPARSE_CLASS {
+			#[cfg(feature = "sparse")]
+			{
+				let (ty, row_idx) = next_element(buf, &mut pos)?;
+				let row_idx = to_indices(ty, row_idx)?;
+				let (ty, col_ptr) = next_element(buf, &mut pos)?;
+				let col_ptr = to_indices(ty, col_ptr)?;
+
+				if col_ptr.len() != ncols + 1 || col_ptr[0] != 0 || col_ptr.windows(2).any(|w| w[0] > w[1]) || col_ptr[ncols] > row_idx.len() {
+					return Err(invalid("invalid column pointers"));
+				}
+				let nnz = col_ptr[ncols];
+				let row_idx = row_idx[..nnz].to_vec();
+				if row_idx.iter().any(|&i| i >= nrows) {
+					return Err(invalid("row index out of bounds"));
+				}
+
+				let (ty, re) = next_element(buf, &mut pos)?;
+				let re = to_f64(ty, re)?;
+				if re.len() < nnz {
+					return Err(invalid("truncated sparse values"));
+				}
+				let symbolic = SymbolicSparseColMat::new_unsorted_checked(nrows, ncols, col_ptr, None, row_idx);
+
+				return Ok(Some((
+					name,
+					if is_complex {
+						let (ty, im) = next_element(buf, &mut pos)?;
+						let im = to_f64(ty, im)?;
+						if im.len() < nnz {
+							return Err(invalid("truncated sparse values"));
+						}
+						MatlabArray::SparseComplex(SparseColMat::new(symbolic, (0..nnz).map(|k| c64::new(re[k], im[k])).collect()))
+					} else {
+						MatlabArray::SparseReal(SparseColMat::new(symbolic, re[..nnz].to_vec()))
+					},
+				)));
+			}
+			#[cfg(not(feature = "sparse"))]
+			return Ok(None);
+		}
+
+		let len = nrows.checked_mul(ncols).ok_or_else(|| invalid("matrix size overflows"))?;
+		let (ty, re) = next_element(buf, &mut pos)?;
+		let re = to_f64(ty, re)?;
+		if re.len() != len {
+			return Err(invalid("data length doesn't match the dimensions"));
+		}
+
+		Ok(Some((
+			name,
+			if is_complex {
+				let (ty, im) = next_element(buf, &mut pos)?;
+				let im = to_f64(ty, im)?;
+				if im.len() != len {
+					return Err(invalid("data length doesn't match the dimensions"));
+				}
+				MatlabArray::Complex(Mat::from_fn(nrows, ncols, |i, j| c64::new(re[i + j * nrows], im[i + j * nrows])))
+			} else {
+				MatlabArray::Real(Mat::from_fn(nrows, ncols, |i, j| re[i + j * nrows]))
+			},
+		)))
+	}
+
+	/// reads the two-dimensional numeric and sparse variables of a mat-file, in order
+	///
+	/// returns an error if the file isn't a little-endian level 5 mat-file
+	pub fn read(mut reader: impl Read) -> Result<Vec<(String, MatlabArray)>, Error> {
+		let mut buf = Vec::new();
+		reader.read_to_end(&mut buf)?;
+
+		if buf.len() < 128 {
+			return Err(invalid("truncated header"));
+		}
+		match &buf[126..128] {
+			b"IM" => {},
+			b"MI" => return Err(Error::new(ErrorKind::Unsupported, "big-endian mat-files are not supported")),
+			_ => return Err(invalid("not a level 5 mat-file")),
+		}
+
+		let mut vars = Vec::new();
+		let mut pos = 128;
+		while pos < buf.len() {
+			let (ty, data) = next_element(&buf, &mut pos)?;
+			let var = match ty {
+				MI_MATRIX => parse_matrix(data)?,
+				MI_COMPRESSED => {
+					let mut inflated = Vec::new();
+					flate2::read::ZlibDecoder::new(data).read_to_end(&mut inflated)?;
+					let (ty, data) = next_element(&inflated, &mut 0)?;
+					if ty == MI_MATRIX { parse_matrix(data)? } else { None }
+				},
+				_ => None,
+			};
+			vars.extend(var);
+		}
+		Ok(vars)
+	}
+
+	fn push_element(out: &mut Vec<u8>, ty: u32, data: &[u8]) -> Result<(), Error> {
+		let len = u32::try_from(data.len()).map_err(|_| Error::new(ErrorKind::InvalidInput, "variable is too large"))?;
+		out.extend_from_slice(&ty.to_le_bytes());
+		out.extend_from_slice(&len.to_le_bytes());
+		out.extend_from_slice(data);
+		out.resize(out.len().next_multiple_of(8), 0);
+		Ok(())
+	}
+
+	fn push_i32(out: &mut Vec<u8>, values: impl IntoIterator<Item = usize>) -> Result<(), Error> {
+		let mut data = Vec::new();
+		for x in values {
+			let x = i32::try_from(x).map_err(|_| Error::new(ErrorKind::InvalidInput, "index doesn't fit in 32 bits"))?;
+			data.extend_from_slice(&x.to_le_bytes());
+		}
+		push_element(out, MI_INT32, &data)
+	}
+
+	fn push_f64(out: &mut Vec<u8>, values: impl IntoIterator<Item = f64>) -> Result<(), Error> {
+		let data = values.into_iter().flat_map(f64::to_le_bytes).collect::<Vec<_>>();
+		push_element(out, MI_DOUBLE, &data)
+	}
+
+	fn push_header(out: &mut Vec<u8>, class: u8, is_complex: bool, nzmax: usize, nrows: usize, ncols: usize, name: &str) -> Result<(), Error> {
+		let flags = class as u32 | if is_complex { FLAG_COMPLEX } else { 0 };
+		let nzmax = nzmax as u32;
+		push_element(out, MI_UINT32, &[flags.to_le_bytes(), nzmax.to_le_bytes()].concat())?;
+		push_i32(out, [nrows, ncols])?;
+		push_element(out, MI_INT8, name.as_bytes())
+	}
+
+	fn dense_values<T: Copy>(mat: MatRef<'_, T>) -> impl Iterator<Item = T> + '_ {
+		mat.col_iter().flat_map(|col| col.iter().copied())
+	}
+
+	#[cfg(feature = "sparse")]
+	fn push_sparse_indices<T>(out: &mut Vec<u8>, mat: SparseColMatRef<'_, usize, T>) -> Result<(), Error> {
+		let ncols = mat.ncols();
+		push_i32(out, (0..ncols).flat_map(|j| mat.row_idx_of_col(j)))?;
+		let col_ptr = (0..ncols).scan(0, |pos, j| {
+			*pos += mat.col_range(j).len();
+			Some(*pos)
+		});
+		push_i32(out, core::iter::once(0).chain(col_ptr))
+	}
+
+	#[cfg(feature = "sparse")]
+	fn sparse_values<T: Copy>(mat: SparseColMatRef<'_, usize, T>) -> impl Iterator<Item = T> + '_ {
+		(0..mat.ncols()).flat_map(move |j| mat.val_of_col(j).iter().copied())
+	}
+
+	/// writes the given named variables to an uncompressed level 5 mat-file
+	pub fn write(mut writer: impl Write, vars: &[(&str, MatlabArrayRef<'_>)]) -> Result<(), Error> {
+		let mut header = [b' '; 128];
+		let text = b"MATLAB 5.0 MAT-file, Platform: faer, Created by: faer";
+		header[..text.len()].copy_from_slice(text);
+		header[116..124].fill(0);
+		header[124..126].copy_from_slice(&0x0100u16.to_le_bytes());
+		header[126..128].copy_from_slice(b"IM");
+		writer.write_all(&header)?;
+
+		for &(name, var) in vars {
+			let mut body = Vec::new();
+			match var {
+				MatlabArrayRef::Real(mat) => {
+					push_header(&mut body, MX_DOUBLE_CLASS, false, 0, mat.nrows(), mat.ncols(), name)?;
+					push_f64(&mut body, dense_values(mat))?;
+				},
+				MatlabArrayRef::Complex(mat) => {
+					push_header(&mut body, MX_DOUBLE_CLASS, true, 0, mat.nrows(), mat.ncols(), name)?;
+					push_f64(&mut body, dense_values(mat).map(|z| z.re))?;
+					push_f64(&mut body, dense_values(mat).map(|z| z.im))?;
+				},
+				#[cfg(feature = "sparse")]
+				MatlabArrayRef::SparseReal(mat) => {
+					// matlab expects room for at least one entry
+					let nzmax = Ord::max(mat.compute_nnz(), 1);
+					push_header(&mut body, MX_SPARSE_CLASS, false, nzmax, mat.nrows(), mat.ncols(), name)?;
+					push_sparse_indices(&mut body, mat)?;
+					push_f64(&mut body, sparse_values(mat))?;
+				},
+				#[cfg(feature = "sparse")]
+				MatlabArrayRef::SparseComplex(mat) => {
+					let nzmax = Ord::max(mat.compute_nnz(), 1);
+					push_header(&mut body, MX_SPARSE_CLASS, true, nzmax, mat.nrows(), mat.ncols(), name)?;
+					push_sparse_indices(&mut body, mat)?;
+					push_f64(&mut body, sparse_values(mat).map(|z| z.re))?;
+					push_f64(&mut body, sparse_values(mat).map(|z| z.im))?;
+				},
+			}
+
+			let mut element = Vec::new();
+			push_element(&mut element, MI_MATRIX, &body)?;
+			writer.write_all(&element)?;
+		}
+		writer.flush()
+	}
+}
+
+/// conversions between image buffers and matrices
+///
+/// the pixel at column `x` and row `y` of an image corresponds to the entry at row `y` and column
+/// `x` of a matrix. grayscale images, and each channel of rgb images, can be viewed in place, or
+/// converted to `f32` matrices with the values rescaled to `[0, 1]`
+///
+/// # example
+/// ```
+/// use faer::io::image;
+/// use faer::mat;
+///
+/// let img = ::image::GrayImage::from_raw(3, 2, vec![0, 51, 102, 153, 204, 255]).unwrap();
+///
+/// let view = image::luma_ref(&img);
+/// assert!(view == mat![[0u8, 51, 102], [153, 204, 255]]);
+///
+/// let a = image::luma_to_mat(&img);
+/// assert!(a == mat![[0.0, 0.2, 0.4], [0.6, 0.8, 1.0]]);
+/// assert!(image::mat_to_luma::<u8>(a.as_ref()) == img);
+/// ```
+#[cfg(feature = "image")]
+pub mod image {
+	use super::*;
+	use crate::assert;
+	use ::image::{ImageBuffer, Luma, Pixel, Primitive, Rgb};
+	use core::ops::Deref;
+	use num_traits::NumCast;
+	use std::vec::Vec;
+
+	fn range<P: Primitive>() -> (f32, f32) {
+		let min = P::DEFAULT_MIN_VALUE.to_f32().unwrap();
+		let max = P::DEFAULT_MAX_VALUE.to_f32().unwrap();
+		(min, max - min)
+	}
+
+	fn to_unit<P: Primitive>(x: P) -> f32 {
+		let (min, scale) = range::<P>();
+		(x.to_f32().unwrap() - min) / scale
+	}
+
+	fn from_unit<P: Primitive>(x: f32) -> P {
+		let (min, scale) = range::<P>();
+		let is_float = <P as NumCast>::from(0.5f32).and_then(|half| half.to_f32()) == Some(0.5);
+
+		// nan is mapped to the minimum value
+		let x = if x.is_nan() { 0.0 } else { x.clamp(0.0, 1.0) };
+		let x = min + x * scale;
+		<P as NumCast>::from(if is_float { x } else { libm::roundf(x) }).unwrap_or(P::DEFAULT_MAX_VALUE)
+	}
+
+	fn dims(nrows: usize, ncols: usize) -> (u32, u32) {
+		let height = u32::try_from(nrows).expect("the number of rows fits in a `u32`");
+		let width = u32::try_from(ncols).expect("the number of columns fits in a `u32`");
+		(width, height)
+	}
+
+	/// returns a view over the pixels of a grayscale image
+	#[inline]
+	pub fn luma_ref<P: Primitive, C: Deref<Target = [P]>>(img: &ImageBuffer<Luma<P>, C>) -> MatRef<'_, P> {
+		let (width, height) = img.dimensions();
+		MatRef::from_row_major_slice(&img.as_raw()[..width as usize * height as usize], height as usize, width as usize)
+	}
+
+	/// returns views over the red, green and blue channels of an rgb image
+	#[inline]
+	pub fn rgb_ref<P: Primitive, C: Deref<Target = [P]>>(img: &ImageBuffer<Rgb<P>, C>) -> [MatRef<'_, P>; 3]
+	where
+		Rgb<P>: Pixel<Subpixel = P>,
+	{
+		let (width, height) = img.dimensions();
+		let (width, height) = (width as usize, height as usize);
+		let data = &img.as_raw()[..3 * width * height];
+
+		// SAFETY: channel `c` of pixel `(x, y)` is stored at offset `3 * (y * width + x) + c`
+		core::array::from_fn(|c| unsafe { MatRef::from_raw_parts(data.as_ptr().wrapping_add(c), height, width, 3 * width as isize, 3) })
+	}
+
+	/// returns a matrix holding the pixels of a grayscale image, rescaled to `[0, 1]`
+	pub fn luma_to_mat<P: Primitive, C: Deref<Target = [P]>>(img: &ImageBuffer<Luma<P>, C>) -> Mat<f32> {
+		let view = luma_ref(img);
+		Mat::from_fn(view.nrows(), view.ncols(), |i, j| to_unit(view[(i, j)]))
+	}
+
+	/// returns matrices holding the red, green and blue channels of an rgb image, rescaled to
+	/// `[0, 1]`
+	pub fn rgb_to_mats<P: Primitive, C: Deref<Target = [P]>>(img: &ImageBuffer<Rgb<P>, C>) -> [Mat<f32>; 3]
+	where
+		Rgb<P>: Pixel<Subpixel = P>,
+	{
+		rgb_ref(img).map(|view| Mat::from_fn(view.nrows(), view.ncols(), |i, j| to_unit(view[(i, j)])))
+	}
+
+	/// returns a grayscale image with the values of `mat`, which are clamped to `[0, 1]` and
+	/// rescaled to the range of `P`
+	///
+	/// # panics
+	/// panics if the dimensions of `mat` don't fit in a `u32`
+	#[track_caller]
+	pub fn mat_to_luma<P: Primitive>(mat: MatRef<'_, f32>) -> ImageBuffer<Luma<P>, Vec<P>> {
+		let (width, height) = dims(mat.nrows(), mat.ncols());
+		ImageBuffer::from_fn(width, height, |x, y| Luma([from_unit(mat[(y as usize, x as usize)])]))
+	}
+
+	/// returns an rgb image with the red, green and blue channels given by `channels`, whose
+	/// values are clamped to `[0, 1]` and rescaled to the range of `P`
+	///
+	/// # panics
+	/// panics if the channels don't have the same dimensions, or if they don't fit in a `u32`
+	#[track_caller]
+	pub fn mats_to_rgb<P: Primitive>(channels: [MatRef<'_, f32>; 3]) -> ImageBuffer<Rgb<P>, Vec<P>>
+	where
+		Rgb<P>: Pixel<Subpixel = P>,
+	{
+		let [r, g, b] = channels;
+		assert!(all(r.shape() == g.shape(), r.shape() == b.shape()));
+		let (width, height) = dims(r.nrows(), r.ncols());
+		ImageBuffer::from_fn(width, height, |x, y| {
+			let (i, j) = (y as usize, x as usize);
+			Rgb([from_unit(r[(i, j)]), from_unit(g[(i, j)]), from_unit(b[(i, j)])])
+		})
+	}
+}
+
+/// memory-mapped matrix storage
+#[cfg(feature = "mmap")]
+pub mod mmap {
+	use super::*;
+	use core::marker::PhantomData;
+	use std::fs::{File, OpenOptions};
+	use std::path::Path;
+
+	/// column-major matrix stored in a memory-mapped file
+	///
+	/// the file contains the raw column-major data with no header, so its size is exactly
+	/// `nrows * ncols * size_of::<T>()` bytes
+	///
+	/// this is mainly useful for matrices that don't fit in memory, in which case they can be
+	/// factorized with the routines in [`linalg::out_of_core`](crate::linalg::out_of_core)
+	pub struct MmapMat<T> {
+		map: memmap2::MmapMut,
+		nrows: usize,
+		ncols: usize,
+		__marker: PhantomData<T>,
+	}
+
+	impl<T> core::fmt::Debug for MmapMat<T> {
+		fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+			f.debug_struct("MmapMat").field("nrows", &self.nrows).field("ncols", &self.ncols).finish()
+		}
+	}
+
+	fn byte_len<T>(nrows: usize, ncols: usize) -> Result<u64, std::io::Error> {
+		nrows
+			.checked_mul(ncols)
+			.and_then(|len| len.checked_mul(core::mem::size_of::<T>()))
+			.map(|len| len as u64)
+			.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "matrix size overflows"))
+	}
+
+	impl<T: bytemuck::Pod> MmapMat<T> {
+		fn map(file: &File, nrows: usize, ncols: usize) -> Result<Self, std::io::Error> {
+			// mapping an empty file is an error on some platforms
+			let map = if nrows * ncols == 0 {
+				memmap2::MmapMut::map_anon(0)?
+			} else {
+				unsafe { memmap2::MmapMut::map_mut(file)? }
+			};
+			Ok(Self {
+				map,
+				nrows,
+				ncols,
+				__marker: PhantomData,
+			})
+		}
+
+		/// creates a file at `path` that can hold a matrix with the given dimensions, and maps it
+		/// in memory
+		///
+		/// if the file already exists, it is truncated. the matrix is initialized with zeros
+		pub fn create(path: impl AsRef<Path>, nrows: usize, ncols: usize) -> Result<Self, std::io::Error> {
+			let len = byte_len::<T>(nrows, ncols)?;
+			let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+			file.set_len(len)?;
+			Self::map(&file, nrows, ncols)
+		}
+
+		/// maps an existing file containing a matrix with the given dimensions
+		///
+		/// returns an error if the file size doesn't match the dimensions
+		pub fn open(path: impl AsRef<Path>, nrows: usize, ncols: usize) -> Result<Self, std::io::Error> {
+			let len = byte_len::<T>(nrows, ncols)?;
+			let file = OpenOptions::new().read(true).write(true).open(path)?;
+			if file.metadata()?.len() != len {
+				return Err(std::io::Error::new(
+					std::io::ErrorKind::InvalidData,
+					"file size doesn't match the matrix dimensions",
+				));
+			}
+			Self::map(&file, nrows, ncols)
+		}
+
+		/// returns the number of rows of the matrix
+		#[inline]
+		pub fn nrows(&self) -> usize {
+			self.nrows
+		}
+
+		/// returns the number of columns of the matrix
+		#[inline]
+		pub fn ncols(&self) -> usize {
+			self.ncols
+		}
+
+		/// returns a view over the matrix
+		#[inline]
+		pub fn as_ref(&self) -> MatRef<'_, T> {
+			MatRef::from_column_major_slice(bytemuck::cast_slice(&self.map), self.nrows, self.ncols)
+		}
+
+		/// returns a mutable view over the matrix
+		#[inline]
+		pub fn as_mut(&mut self) -> MatMut<'_, T> {
+			MatMut::from_column_major_slice_mut(bytemuck::cast_slice_mut(&mut self.map), self.nrows, self.ncols)
+		}
+
+		/// writes the modified pages back to the file, and waits for the write to complete
+		pub fn flush(&self) -> Result<(), std::io::Error> {
+			self.map.flush()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::csv::{self, ComplexFormat, CsvFormat};
+	use super::*;
+	use crate::assert;
+	use std::string::String;
+
+	#[test]
+	fn test_csv() {
+		let a = Mat::from_fn(3, 2, |i, j| c64::new(i as f64 - 1.5, if j == 0 { 1e-20 } else { -(j as f64) }));
+		let header = ["a", "b,c"];
+
+		for format in [
+			CsvFormat::CSV,
+			CsvFormat::TSV,
+			CsvFormat {
+				delimiter: ';',
+				complex: ComplexFormat::Split,
+			},
+		] {
+			let mut buf = std::vec::Vec::new();
+			csv::write(&mut buf, a.as_ref(), Some(&header), format).unwrap();
+			let (b, names) = csv::read::<c64>(&buf[..], true, format).unwrap();
+			assert!(b == a);
+			assert!(names.unwrap() == header);
+		}
+
+		let mut s = String::new();
+		core::fmt::write(&mut s, format_args!("{}", csv::display(a.as_ref().get(..1, ..), None, CsvFormat::CSV))).unwrap();
+		assert!(s == "-1.5+0.00000000000000000001i,-1.5-1i\n");
+
+		let text = "x\ty\r\n1.5e-3\t2+3j\n\n-4i\t\"inf\"\n";
+		let (b, names) = csv::read::<c32>(text.as_bytes(), true, CsvFormat::TSV).unwrap();
+		assert!(names.unwrap() == ["x", "y"]);
+		assert!(
+			b == crate::mat![
+				[c32::new(1.5e-3, 0.0), c32::new(2.0, 3.0)],
+				[c32::new(0.0, -4.0), c32::new(f32::INFINITY, 0.0)]
+			]
+		);
+
+		let (b, names) = csv::read::<f64>(&b""[..], false, CsvFormat::CSV).unwrap();
+		assert!(all(b.shape() == (0, 0), names.is_none()));
+
+		assert!(csv::read::<f64>(&b"1,2\n3\n"[..], false, CsvFormat::CSV).is_err());
+		assert!(csv::read::<f64>(&b"1,x\n"[..], false, CsvFormat::CSV).is_err());
+		assert!(
+			csv::read::<c64>(
+				&b"1,2,3\n"[..],
+				false,
+				CsvFormat {
+					delimiter: ',',
+					complex: ComplexFormat::Split
+				}
+			)
+			.is_err()
+		);
+	}
+
+	#[test]
+	fn test_bin() {
+		use super::bin;
+
+		let a = Mat::from_fn(5, 3, |i, j| c32::new(i as f32, -(j as f32)));
+		for view in [a.as_ref(), a.as_ref().reverse_rows().transpose().get(.., 1..4)] {
+			let mut buf = std::vec::Vec::new();
+			bin::save(&mut buf, view).unwrap();
+			assert!(buf.len() == 64 + view.nrows() * view.ncols() * 8);
+			assert!(bin::load::<c32>(&buf[..]).unwrap() == view);
+
+			assert!(bin::load::<c64>(&buf[..]).is_err());
+			assert!(bin::load::<c32>(&buf[..buf.len() - 1]).is_err());
+			assert!(bin::load::<c32>(&buf[1..]).is_err());
+		}
+
+		// a header that declares more data than the container holds is rejected before the matrix is
+		// allocated
+		let mut buf = std::vec::Vec::new();
+		bin::save(&mut buf, a.as_ref()).unwrap();
+		buf[24..32].copy_from_slice(&(1u64 << 40).to_le_bytes());
+		assert!(bin::load::<c32>(&buf[..]).unwrap_err().kind() == std::io::ErrorKind::InvalidData);
+
+		#[cfg(feature = "sparse")]
+		{
+			use crate::sparse::{SparseColMat, Triplet};
+
+			let a =
+				SparseColMat::<u32, f64>::try_new_from_triplets(4, 3, &[Triplet::new(0, 0, 1.0), Triplet::new(3, 0, 2.0), Triplet::new(1, 2, -3.0)])
+					.unwrap();
+			let mut buf = std::vec::Vec::new();
+			bin::save_sparse(&mut buf, a.as_ref()).unwrap();
+			// header, then the padded indices, then the values
+			assert!(buf.len() == 64 + 64 + 3 * 8);
+
+			let b = bin::load_sparse::<u32, f64>(&buf[..]).unwrap();
+			assert!(b.to_dense() == a.to_dense());
+			assert!(bin::load_sparse::<u64, f64>(&buf[..]).is_err());
+			assert!(bin::load::<f64>(&buf[..]).is_err());
+
+			// headers that declare more data than the container holds, or dimensions that don't fit in
+			// the index type, are rejected without panicking
+			let invalid = |buf: &[u8]| bin::load_sparse::<u32, f64>(buf).unwrap_err().kind() == std::io::ErrorKind::InvalidData;
+			let mut huge = buf.clone();
+			huge[40..48].copy_from_slice(&(1u64 << 30).to_le_bytes());
+			assert!(invalid(&huge));
+			assert!(invalid(&buf[..buf.len() - 1]));
+			assert!(invalid(&buf[..64 + 8]));
+
+			// empty matrix with too many rows for `u32`
+			let mut empty = std::vec::Vec::new();
+			bin::save_sparse(&mut empty, SparseColMat::<u32, f64>::try_new_from_triplets(4, 3, &[]).unwrap().as_ref()).unwrap();
+			assert!(bin::load_sparse::<u32, f64>(&empty[..]).is_ok());
+			empty[24..32].copy_from_slice(&3_000_000_000u64.to_le_bytes());
+			assert!(invalid(&empty));
+			let mut empty = empty.clone();
+			empty[24..32].copy_from_slice(&4u64.to_le_bytes());
+			empty[32..40].copy_from_slice(&3_000_000_000u64.to_le_bytes());
+			assert!(invalid(&empty));
+
+			// column pointers that aren't nondecreasing
+			buf[64 + 4] = 3;
+			assert!(bin::load_sparse::<u32, f64>(&buf[..]).is_err());
+		}
+
+		#[cfg(feature = "mmap")]
+		{
+			let a = Mat::from_fn(7, 4, |i, j| (i + 10 * j) as f64);
+			let path = std::env::temp_dir().join(std::format!("faer-bin-test-{}.bin", std::process::id()));
+			bin::save(std::fs::File::create(&path).unwrap(), a.as_ref()).unwrap();
+
+			let mapped = bin::load_mmap::<f64>(&path).unwrap();
+			assert!(all(mapped.nrows() == 7, mapped.ncols() == 4, mapped.as_ref() == a));
+			assert!(bin::load_mmap::<f32>(&path).is_err());
+			drop(mapped);
+			std::fs::remove_file(&path).unwrap();
+		}
+	}
+
+	#[cfg(feature = "arrow")]
+	#[test]
+	fn test_arrow() {
+		use super::arrow;
+		use arrow_array::builder::{FixedSizeListBuilder, Float32Builder};
+		use arrow_array::types::{Float32Type, Float64Type};
+		use arrow_array::{Array, Float32Array, Float64Array};
+
+		let x = Float64Array::from_iter_values((0..10).map(|i| i as f64));
+		let view = arrow::col_ref(&x).unwrap();
+		assert!(view.as_ptr() == x.values().as_ptr());
+		assert!(arrow::col_ref(&x.slice(3, 4)).unwrap() == crate::col![3.0, 4.0, 5.0, 6.0]);
+		assert!(arrow::to_array::<Float64Type>(view) == x);
+		assert!(arrow::col_ref(&Float64Array::from(std::vec![Some(1.0), None])).is_none());
+
+		let a = Mat::from_fn(4, 3, |i, j| (i + 10 * j) as f32);
+		let rows = arrow::to_fixed_size_list::<Float32Type>(a.as_ref());
+		assert!(all(rows.len() == 4, rows.value_length() == 3));
+		assert!(arrow::mat_ref::<Float32Type>(&rows).unwrap() == a);
+		assert!(arrow::mat_ref::<Float32Type>(&rows.slice(1, 2)).unwrap() == a.get(1..3, ..));
+		assert!(arrow::mat_ref::<Float64Type>(&rows).is_none());
+
+		let mut builder = FixedSizeListBuilder::new(Float32Builder::new(), 2);
+		builder.values().append_value(1.0);
+		builder.values().append_null();
+		builder.append(true);
+		assert!(arrow::mat_ref::<Float32Type>(&builder.finish()).is_none());
+
+		let cols = arrow::to_columns::<Float32Type>(a.as_ref());
+		assert!(cols.len() == 3);
+		assert!(cols[2] == Float32Array::from_iter_values((0..4).map(|i| (i + 20) as f32)));
+		assert!(arrow::mat_from_columns(&cols.iter().collect::<std::vec::Vec<_>>()).unwrap() == a);
+		assert!(arrow::mat_from_columns(&[&cols[0], &cols[1].slice(0, 3)]).is_none());
+		assert!(arrow::mat_from_columns::<Float32Type>(&[]).unwrap().shape() == (0, 0));
+	}
+
+	#[cfg(feature = "matlab")]
+	#[test]
+	fn test_matlab() {
+		use super::matlab::{self, MatlabArray};
+		use std::io::Write;
+		use std::vec::Vec;
+
+		let a = Mat::from_fn(3, 2, |i, j| (i as f64) - 0.5 * j as f64);
+		let z = Mat::from_fn(2, 4, |i, j| c64::new(i as f64, j as f64));
+		let mut buf = Vec::new();
+		matlab::write(&mut buf, &[("a", a.as_ref().into()), ("z_values", z.as_ref().transpose().into())]).unwrap();
+		assert!(all(buf.len() % 8 == 0, &buf[126..128] == b"IM"));
+
+		let vars = matlab::read(&buf[..]).unwrap();
+		assert!(vars.len() == 2);
+		assert!(matches!(&vars[0], (name, MatlabArray::Real(b)) if name == "a" && *b == a));
+		assert!(matches!(&vars[1], (name, MatlabArray::Complex(b)) if name == "z_values" && *b == z.transpose()));
+
+		#[cfg(feature = "sparse")]
+		{
+			use crate::sparse::{SparseColMat, Triplet};
+
+			let s = SparseColMat::<usize, f64>::try_new_from_triplets(
+				4,
+				3,
+				&[Triplet::new(0, 0, 1.0), Triplet::new(3, 0, 2.0), Triplet::new(1, 2, -3.0)],
+			)
+			.unwrap();
+			let sz = SparseColMat::<usize, c64>::try_new_from_triplets(2, 2, &[Triplet::new(1, 0, c64::new(1.0, -1.0))]).unwrap();
+			let empty = SparseColMat::<usize, f64>::try_new_from_triplets(2, 5, &[]).unwrap();
+
+			let mut buf = Vec::new();
+			matlab::write(
+				&mut buf,
+				&[("s", s.as_ref().into()), ("sz", sz.as_ref().into()), ("e", empty.as_ref().into())],
+			)
+			.unwrap();
+			let vars = matlab::read(&buf[..]).unwrap();
+			assert!(matches!(&vars[0].1, MatlabArray::SparseReal(b) if b.to_dense() == s.to_dense()));
+			assert!(matches!(&vars[1].1, MatlabArray::SparseComplex(b) if b.to_dense() == sz.to_dense()));
+			assert!(matches!(&vars[2].1, MatlabArray::SparseReal(b) if b.shape() == (2, 5) && b.compute_nnz() == 0));
+		}
+
+		// compressed variable as written by matlab, with small data elements and a double array
+		// stored as uint8, followed by a character array that is skipped
+		let small = |ty: u32, data: &[u8]| {
+			let mut out = ((data.len() as u32) << 16 | ty).to_le_bytes().to_vec();
+			out.extend_from_slice(data);
+			out.resize(8, 0);
+			out
+		};
+		let element = |ty: u32, data: &[u8]| {
+			let mut out = ty.to_le_bytes().to_vec();
+			out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+			out.extend_from_slice(data);
+			out.resize(out.len().next_multiple_of(8), 0);
+			out
+		};
+		let matrix = |class: u32, name: &[u8], ty: u32, data: &[u8]| {
+			let body = [
+				element(6, &[class.to_le_bytes(), [0; 4]].concat()),
+				element(5, &[2i32.to_le_bytes(), 1i32.to_le_bytes()].concat()),
+				small(1, name),
+				small(ty, data),
+			]
+			.concat();
+			element(14, &body)
+		};
+
+		let mut file = buf[..128].to_vec();
+		let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+		encoder.write_all(&matrix(6, b"x", 2, &[3, 7])).unwrap();
+		let compressed = encoder.finish().unwrap();
+		file.extend_from_slice(&15u32.to_le_bytes());
+		file.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+		file.extend_from_slice(&compressed);
+		file.extend_from_slice(&matrix(4, b"str", 4, &[b'h', 0, b'i', 0]));
+
+		let vars = matlab::read(&file[..]).unwrap();
+		assert!(vars.len() == 1);
+		assert!(matches!(&vars[0], (name, MatlabArray::Real(b)) if name == "x" && *b == crate::mat![[3.0], [7.0]]));
+
+		assert!(matlab::read(&file[..100]).is_err());
+		assert!(matlab::read(&file[..file.len() - 4]).is_err());
+	}
+
+	#[cfg(feature = "image")]
+	#[test]
+	fn test_image() {
+		use ::image::{ImageBuffer, Luma, Rgb, RgbImage};
+
+		let img = RgbImage::from_fn(4, 3, |x, y| Rgb([(10 * x + y) as u8, (20 * y) as u8, 255]));
+		let [r, g, b] = image::rgb_ref(&img);
+		assert!(all(r.nrows() == 3, r.ncols() == 4));
+		for i in 0..3 {
+			for j in 0..4 {
+				assert!(r[(i, j)] == (10 * j + i) as u8);
+				assert!(g[(i, j)] == (20 * i) as u8);
+				assert!(b[(i, j)] == 255);
+			}
+		}
+
+		let mats = image::rgb_to_mats(&img);
+		assert!(mats[2] == Mat::<f32>::full(3, 4, 1.0));
+		assert!(image::mats_to_rgb::<u8>([mats[0].as_ref(), mats[1].as_ref(), mats[2].as_ref()]) == img);
+
+		let a = Mat::from_fn(2, 3, |i, j| (i + 2 * j) as f32 / 4.0 - 0.25);
+		let luma = image::mat_to_luma::<u16>(a.as_ref());
+		assert!(luma.dimensions() == (3, 2));
+		assert!(luma.get_pixel(0, 0).0[0] == 0);
+		assert!(luma.get_pixel(1, 0).0[0] == 16384);
+		assert!(luma.get_pixel(2, 1).0[0] == u16::MAX);
+
+		let mut nan = a.clone();
+		nan[(0, 1)] = f32::NAN;
+		assert!(image::mat_to_luma::<f32>(nan.as_ref()).get_pixel(1, 0).0[0] == 0.0);
+
+		let float: ImageBuffer<Luma<f32>, _> = image::mat_to_luma(a.as_ref());
+		assert!(image::luma_ref(&float) == Mat::from_fn(2, 3, |i, j| a[(i, j)].clamp(0.0, 1.0)));
+		assert!(image::luma_to_mat(&float) == image::luma_ref(&float));
+
+		let empty = RgbImage::new(0, 0);
+		assert!(image::rgb_ref(&empty)[1].nrows() == 0);
+	}
+}
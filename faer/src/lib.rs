@@ -152,9 +152,17 @@
 //! - `rayon`: enabled by default. enables the `rayon` parallel backend and enables global
 //!   parallelism by default
 //! - `serde`: Enables serialization and deserialization of [`Mat`]
+//! - `rkyv`: enables zero-copy archiving of [`Mat`] with `rkyv`, see `mat::ArchivedMat`
 //! - `npy`: enables conversions to/from numpy's matrix file format
+//! - `arrow`: enables conversions between arrow arrays and matrix views, see `io::arrow`
+//! - `matlab`: enables reading and writing matlab mat-files, see `io::matlab`
+//! - `image`: enables conversions between image buffers and matrices, see `io::image`
 //! - `perf-warn`: produces performance warnings when matrix operations are called with suboptimal
 //! data layout
+//! - `tracing`: instruments the dense decompositions and the matrix multiplication with `tracing`
+//! spans (target `"faer"`) that record the matrix dimensions and the time spent in each stage
+//! - `counters`: accumulates estimated flop counts and memory traffic of the matrix multiplication,
+//! $LU$ factorizations and solves in a thread-local report, see `utils::counters`
 //! - `nightly`: requires the nightly compiler. enables experimental simd features such as avx512
 
 #![no_std]
@@ -595,6 +603,9 @@ pub mod linalg;
 pub mod matrix_free;
 pub mod sparse;
 
+/// human readable matrix formatting
+pub mod fmt;
+
 /// de-serialization from common matrix file formats
 #[cfg(feature = "std")]
 pub mod io;
@@ -602,6 +613,13 @@ pub mod io;
 #[cfg(feature = "serde")]
 mod serde;
 
+#[cfg(feature = "rkyv")]
+mod rkyv;
+
+/// property-based testing support
+#[cfg(feature = "proptest")]
+pub mod proptest;
+
 /// native unsigned integer type
 pub trait Index: traits::IndexCore + traits::Index + seal::Seal {}
 impl<T: faer_traits::Index<Signed: seal::Seal> + seal::Seal> Index for T {}
@@ -926,6 +944,11 @@ pub enum Par {
 	/// sequential, non portable across different platforms
 	Seq,
 	/// parallelized using the global rayon threadpool, non portable across different platforms
+	///
+	/// the dense kernels split their work at points that only depend on the dimensions and the
+	/// thread count, and combine the partial results in a fixed order, so their results are bitwise
+	/// reproducible across runs with the same thread count. they may differ from the results
+	/// computed with another thread count, or with [`Par::Seq`]
 	#[cfg(feature = "rayon")]
 	Rayon(NonZeroUsize),
 }
@@ -1065,7 +1088,7 @@ pub mod prelude {
 	pub use row::{Row, RowMut, RowRef};
 
 	#[cfg(feature = "linalg")]
-	pub use super::linalg::solvers::{DenseSolve, Solve, SolveLstsq};
+	pub use super::linalg::solvers::{DenseSolve, Solve, SolveLstsq, solve_llt, solve_lstsq_qr, solve_lu};
 
 	#[cfg(feature = "sparse")]
 	pub use super::prelude_sp::*;
@@ -1280,6 +1303,73 @@ mod into_range {
 			min..max
 		}
 	}
+
+	use crate::assert;
+
+	/// range of indices with a constant, possibly negative, step between them, such as
+	/// `(a..b).step_by(k)` or `(a..b).rev()`
+	pub trait IntoStridedRange {
+		/// returns the first index, the number of indices and the step between consecutive indices
+		fn into_strided_range(self) -> (usize, usize, isize);
+	}
+
+	#[inline]
+	fn strided_range(mut iter: impl ExactSizeIterator<Item = usize>) -> (usize, usize, isize) {
+		let len = iter.len();
+		let start = iter.next().unwrap_or(0);
+		let step = match iter.next() {
+			Some(next) => next.wrapping_sub(start) as isize,
+			None => 1,
+		};
+		(start, len, step)
+	}
+
+	impl IntoStridedRange for core::iter::StepBy<core::ops::Range<usize>> {
+		#[inline]
+		fn into_strided_range(self) -> (usize, usize, isize) {
+			strided_range(self)
+		}
+	}
+	impl IntoStridedRange for core::iter::Rev<core::ops::Range<usize>> {
+		#[inline]
+		fn into_strided_range(self) -> (usize, usize, isize) {
+			strided_range(self)
+		}
+	}
+	impl IntoStridedRange for core::iter::StepBy<core::iter::Rev<core::ops::Range<usize>>> {
+		#[inline]
+		fn into_strided_range(self) -> (usize, usize, isize) {
+			strided_range(self)
+		}
+	}
+	impl IntoStridedRange for core::iter::Rev<core::iter::StepBy<core::ops::Range<usize>>> {
+		#[inline]
+		fn into_strided_range(self) -> (usize, usize, isize) {
+			strided_range(self)
+		}
+	}
+
+	/// checks that `range` is contained in `0..dim`, and returns its first index, length and step
+	#[inline]
+	#[track_caller]
+	pub fn strided(range: impl IntoStridedRange, dim: usize) -> (usize, usize, isize) {
+		let (start, len, step) = range.into_strided_range();
+		if len > 0 {
+			let last = start as isize + (len - 1) as isize * step;
+			assert!(all(start < dim, last >= 0, (last as usize) < dim));
+		}
+		(start, len, step)
+	}
+
+	/// checks that `range` is contained in `0..dim`, and returns its first index, length and a
+	/// step of `1`
+	#[inline]
+	#[track_caller]
+	pub fn contiguous(range: impl IntoRange<usize>, dim: usize) -> (usize, usize, isize) {
+		let range = range.into_range(0, dim);
+		assert!(all(range.start <= range.end, range.end <= dim));
+		(range.start, range.end - range.start, 1)
+	}
 }
 
 mod sort;
@@ -0,0 +1,757 @@
+//! block diagonal and block triangular matrices
+//!
+//! systems assembled from independent subdomains, e.g., in multi-physics simulations, are often
+//! block diagonal, or block triangular when the subdomains are coupled in one direction only. the
+//! types in this module store the blocks separately, so that products and solves are dispatched to
+//! the dense kernels block by block, and independent blocks are processed in parallel
+//!
+//! # example
+//! ```
+//! use faer::linalg::block::{BlockDiag, BlockTriangular};
+//! use faer::linalg::solvers::Solve;
+//! use faer::{Par, Side, mat};
+//!
+//! let diag = BlockDiag::new(vec![mat![[4.0, 1.0], [1.0, 3.0]], mat![[2.0]]]);
+//! let mut A = BlockTriangular::new(diag, Side::Lower);
+//! A.set_block(1, 0, mat![[1.0, -1.0]].as_ref());
+//!
+//! let solver = A.partial_piv_lu(Par::Seq);
+//! let b = mat![[1.0], [2.0], [3.0]];
+//! let x = solver.solve(&b);
+//!
+//! assert!((A.to_dense() * &x - &b).norm_max() < 1e-14);
+//! ```
+
+use crate::internal_prelude::*;
+use crate::linalg::solvers::{Llt, LltError, PartialPivLu, ShapeCore, SolveCore};
+use crate::matrix_free::LinOp;
+use crate::utils::thread::join_raw;
+use crate::{assert, get_global_parallelism};
+use alloc::vec::Vec;
+
+fn offsets_from_sizes(sizes: impl Iterator<Item = usize>) -> Vec<usize> {
+	let mut offsets = Vec::new();
+	let mut acc = 0usize;
+	offsets.push(acc);
+	for size in sizes {
+		acc += size;
+		offsets.push(acc);
+	}
+	offsets
+}
+
+/// calls `f` on each block of rows of `dst`, as delimited by `offsets`, possibly in parallel
+fn for_each_row_block<T: ComplexField>(offsets: &[usize], dst: MatMut<'_, T>, par: Par, f: &(dyn Sync + Fn(usize, MatMut<'_, T>, Par))) {
+	fn imp<T: ComplexField>(offsets: &[usize], first: usize, dst: MatMut<'_, T>, par: Par, f: &(dyn Sync + Fn(usize, MatMut<'_, T>, Par))) {
+		let n = offsets.len() - 1;
+		match n {
+			0 => {},
+			1 => f(first, dst, par),
+			_ => {
+				let mid = n / 2;
+				let (top, bot) = dst.split_at_row_mut(offsets[mid] - offsets[0]);
+				join_raw(
+					|par| imp(&offsets[..mid + 1], first, top, par, f),
+					|par| imp(&offsets[mid..], first + mid, bot, par, f),
+					par,
+				);
+			},
+		}
+	}
+	imp(offsets, 0, dst, par, f)
+}
+
+/// returns `f` applied to each block, possibly in parallel
+fn map_blocks<T: ComplexField, S: Send>(blocks: &[Mat<T>], par: Par, f: &(dyn Sync + Fn(MatRef<'_, T>) -> S)) -> Vec<S> {
+	fn imp<T: ComplexField, S: Send>(blocks: &[Mat<T>], out: &mut [Option<S>], par: Par, f: &(dyn Sync + Fn(MatRef<'_, T>) -> S)) {
+		match blocks.len() {
+			0 => {},
+			1 => out[0] = Some(f(blocks[0].as_ref())),
+			n => {
+				let mid = n / 2;
+				let (out_lo, out_hi) = out.split_at_mut(mid);
+				join_raw(|par| imp(&blocks[..mid], out_lo, par, f), |par| imp(&blocks[mid..], out_hi, par, f), par);
+			},
+		}
+	}
+
+	let mut out: Vec<Option<S>> = (0..blocks.len()).map(|_| None).collect();
+	imp(blocks, &mut out, par, f);
+	out.into_iter().map(|s| s.unwrap()).collect()
+}
+
+/// splits `rhs` into the block of rows `src`, and the block of rows `dst`, which must be distinct
+fn split_row_blocks<'a, T>(rhs: MatMut<'a, T>, offsets: &[usize], src: usize, dst: usize) -> (MatRef<'a, T>, MatMut<'a, T>) {
+	let src_len = offsets[src + 1] - offsets[src];
+	let dst_len = offsets[dst + 1] - offsets[dst];
+	if src < dst {
+		let (top, bot) = rhs.split_at_row_mut(offsets[dst]);
+		(top.into_const().subrows(offsets[src], src_len), bot.subrows_mut(0, dst_len))
+	} else {
+		let (top, bot) = rhs.split_at_row_mut(offsets[src]);
+		(bot.into_const().subrows(0, src_len), top.subrows_mut(offsets[dst], dst_len))
+	}
+}
+
+/// block diagonal matrix
+///
+/// the diagonal blocks may be rectangular, in which case the matrix is rectangular as well, with
+/// the $i$-th block occupying the rows `row_offsets()[i]..row_offsets()[i + 1]` and the columns
+/// `col_offsets()[i]..col_offsets()[i + 1]`
+#[derive(Clone, Debug)]
+pub struct BlockDiag<T> {
+	blocks: Vec<Mat<T>>,
+	row_offsets: Vec<usize>,
+	col_offsets: Vec<usize>,
+}
+
+impl<T: ComplexField> BlockDiag<T> {
+	/// returns a block diagonal matrix with the given diagonal blocks
+	pub fn new(blocks: Vec<Mat<T>>) -> Self {
+		let row_offsets = offsets_from_sizes(blocks.iter().map(|b| b.nrows()));
+		let col_offsets = offsets_from_sizes(blocks.iter().map(|b| b.ncols()));
+		Self {
+			blocks,
+			row_offsets,
+			col_offsets,
+		}
+	}
+
+	/// returns the number of rows of the matrix
+	#[inline]
+	pub fn nrows(&self) -> usize {
+		*self.row_offsets.last().unwrap()
+	}
+
+	/// returns the number of columns of the matrix
+	#[inline]
+	pub fn ncols(&self) -> usize {
+		*self.col_offsets.last().unwrap()
+	}
+
+	/// returns the number of diagonal blocks
+	#[inline]
+	pub fn n_blocks(&self) -> usize {
+		self.blocks.len()
+	}
+
+	/// returns the diagonal blocks
+	#[inline]
+	pub fn blocks(&self) -> &[Mat<T>] {
+		&self.blocks
+	}
+
+	/// returns a view over the $i$-th diagonal block
+	#[inline]
+	#[track_caller]
+	pub fn block(&self, i: usize) -> MatRef<'_, T> {
+		self.blocks[i].as_ref()
+	}
+
+	/// returns a mutable view over the $i$-th diagonal block
+	#[inline]
+	#[track_caller]
+	pub fn block_mut(&mut self, i: usize) -> MatMut<'_, T> {
+		self.blocks[i].as_mut()
+	}
+
+	/// returns the row offsets of the blocks, followed by the number of rows of the matrix
+	#[inline]
+	pub fn row_offsets(&self) -> &[usize] {
+		&self.row_offsets
+	}
+
+	/// returns the column offsets of the blocks, followed by the number of columns of the matrix
+	#[inline]
+	pub fn col_offsets(&self) -> &[usize] {
+		&self.col_offsets
+	}
+
+	/// returns the matrix as a dense matrix
+	pub fn to_dense(&self) -> Mat<T> {
+		let mut out = Mat::zeros(self.nrows(), self.ncols());
+		for (i, block) in self.blocks.iter().enumerate() {
+			out.as_mut()
+				.submatrix_mut(self.row_offsets[i], self.col_offsets[i], block.nrows(), block.ncols())
+				.copy_from(block);
+		}
+		out
+	}
+
+	/// computes `dst := alpha * self * rhs` or `dst := dst + alpha * self * rhs` depending on
+	/// `accum`, one block at a time
+	///
+	/// # panics
+	///
+	/// panics if the dimensions of `dst`, `self` and `rhs` don't match
+	#[track_caller]
+	pub fn matmul<C: Conjugate<Canonical = T>>(&self, dst: MatMut<'_, T>, accum: Accum, rhs: MatRef<'_, C>, alpha: T, par: Par) {
+		self.matmul_imp(dst, accum, Conj::No, rhs.canonical(), Conj::get::<C>(), &alpha, par)
+	}
+
+	#[track_caller]
+	#[allow(clippy::too_many_arguments)]
+	fn matmul_imp(&self, dst: MatMut<'_, T>, accum: Accum, conj_lhs: Conj, rhs: MatRef<'_, T>, conj_rhs: Conj, alpha: &T, par: Par) {
+		assert!(all(dst.nrows() == self.nrows(), rhs.nrows() == self.ncols(), dst.ncols() == rhs.ncols()));
+
+		for_each_row_block(&self.row_offsets, dst, par, &|i, dst, par| {
+			let block = self.blocks[i].as_ref();
+			linalg::matmul::matmul_with_conj(
+				dst,
+				accum,
+				block,
+				conj_lhs,
+				rhs.subrows(self.col_offsets[i], block.ncols()),
+				conj_rhs,
+				alpha.clone(),
+				par,
+			);
+		});
+	}
+
+	/// returns the $LU$ decompositions with partial pivoting of the diagonal blocks, computed in
+	/// parallel
+	///
+	/// # panics
+	///
+	/// panics if any of the diagonal blocks is not square
+	#[track_caller]
+	pub fn partial_piv_lu(&self, par: Par) -> BlockDiagSolver<PartialPivLu<T>> {
+		assert!(self.row_offsets == self.col_offsets);
+		BlockDiagSolver::new(map_blocks(&self.blocks, par, &|block| PartialPivLu::new(block)))
+	}
+
+	/// returns the $L L^H$ decompositions of the diagonal blocks, computed in parallel
+	///
+	/// if one of the blocks is not positive definite, the returned error refers to the index of the
+	/// failed pivot in the whole matrix
+	///
+	/// # panics
+	///
+	/// panics if any of the diagonal blocks is not square
+	#[track_caller]
+	pub fn llt(&self, side: Side, par: Par) -> Result<BlockDiagSolver<Llt<T>>, LltError> {
+		assert!(self.row_offsets == self.col_offsets);
+		let solvers = map_blocks(&self.blocks, par, &|block| Llt::new(block, side));
+
+		let mut out = Vec::with_capacity(solvers.len());
+		for (i, solver) in solvers.into_iter().enumerate() {
+			match solver {
+				Ok(solver) => out.push(solver),
+				Err(LltError::NonPositivePivot { index }) => {
+					return Err(LltError::NonPositivePivot {
+						index: self.row_offsets[i] + index,
+					});
+				},
+			}
+		}
+		Ok(BlockDiagSolver::new(out))
+	}
+}
+
+/// solver for a block diagonal matrix, built from one solver per diagonal block
+///
+/// the right-hand sides are split into blocks of rows, which are solved in parallel
+#[derive(Clone, Debug)]
+pub struct BlockDiagSolver<S> {
+	solvers: Vec<S>,
+	offsets: Vec<usize>,
+}
+
+impl<S: ShapeCore> BlockDiagSolver<S> {
+	/// returns a solver for the block diagonal matrix whose $i$-th diagonal block is solved by
+	/// `solvers[i]`
+	///
+	/// # panics
+	///
+	/// panics if any of the solvers is not square
+	#[track_caller]
+	pub fn new(solvers: Vec<S>) -> Self {
+		for solver in &solvers {
+			assert!(solver.nrows() == solver.ncols());
+		}
+		let offsets = offsets_from_sizes(solvers.iter().map(|s| s.nrows()));
+		Self { solvers, offsets }
+	}
+
+	/// returns the solvers of the diagonal blocks
+	#[inline]
+	pub fn solvers(&self) -> &[S] {
+		&self.solvers
+	}
+
+	/// returns the offsets of the blocks, followed by the dimension of the matrix
+	#[inline]
+	pub fn offsets(&self) -> &[usize] {
+		&self.offsets
+	}
+}
+
+impl<S: ShapeCore> ShapeCore for BlockDiagSolver<S> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		*self.offsets.last().unwrap()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		*self.offsets.last().unwrap()
+	}
+}
+
+impl<T: ComplexField, S: SolveCore<T> + Sync> SolveCore<T> for BlockDiagSolver<S> {
+	#[track_caller]
+	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(rhs.nrows() == self.nrows());
+		for_each_row_block(&self.offsets, rhs, get_global_parallelism(), &|i, rhs, _| {
+			self.solvers[i].solve_in_place_with_conj(conj, rhs);
+		});
+	}
+
+	#[track_caller]
+	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(rhs.nrows() == self.nrows());
+		for_each_row_block(&self.offsets, rhs, get_global_parallelism(), &|i, rhs, _| {
+			self.solvers[i].solve_transpose_in_place_with_conj(conj, rhs);
+		});
+	}
+}
+
+/// block triangular matrix with square diagonal blocks
+///
+/// the matrix is block lower triangular if `side` is [`Side::Lower`], and block upper triangular
+/// otherwise. off-diagonal blocks that are not set are implicitly zero
+#[derive(Clone, Debug)]
+pub struct BlockTriangular<T> {
+	side: Side,
+	diag: BlockDiag<T>,
+	// off-diagonal blocks of each block row, along with their block column
+	off_diag: Vec<Vec<(usize, Mat<T>)>>,
+}
+
+impl<T: ComplexField> BlockTriangular<T> {
+	/// returns a block triangular matrix with the given diagonal, and zero off-diagonal blocks
+	///
+	/// # panics
+	///
+	/// panics if any of the diagonal blocks is not square
+	#[track_caller]
+	pub fn new(diag: BlockDiag<T>, side: Side) -> Self {
+		assert!(diag.row_offsets() == diag.col_offsets());
+		let off_diag = (0..diag.n_blocks()).map(|_| Vec::new()).collect();
+		Self { side, diag, off_diag }
+	}
+
+	/// returns whether the matrix is block lower or block upper triangular
+	#[inline]
+	pub fn side(&self) -> Side {
+		self.side
+	}
+
+	/// returns the block diagonal part of the matrix
+	#[inline]
+	pub fn diagonal(&self) -> &BlockDiag<T> {
+		&self.diag
+	}
+
+	/// returns the dimension of the matrix
+	#[inline]
+	pub fn nrows(&self) -> usize {
+		self.diag.nrows()
+	}
+
+	/// returns the dimension of the matrix
+	#[inline]
+	pub fn ncols(&self) -> usize {
+		self.diag.ncols()
+	}
+
+	/// returns the number of diagonal blocks
+	#[inline]
+	pub fn n_blocks(&self) -> usize {
+		self.diag.n_blocks()
+	}
+
+	/// returns the offsets of the blocks, followed by the dimension of the matrix
+	#[inline]
+	pub fn offsets(&self) -> &[usize] {
+		self.diag.row_offsets()
+	}
+
+	/// returns a view over the block at the $i$-th block row and $j$-th block column, or `None` if
+	/// it is implicitly zero
+	#[track_caller]
+	pub fn block(&self, i: usize, j: usize) -> Option<MatRef<'_, T>> {
+		assert!(all(i < self.n_blocks(), j < self.n_blocks()));
+		if i == j {
+			return Some(self.diag.block(i));
+		}
+		self.off_diag[i].iter().find(|(col, _)| *col == j).map(|(_, block)| block.as_ref())
+	}
+
+	/// sets the block at the $i$-th block row and $j$-th block column to `block`
+	///
+	/// # panics
+	///
+	/// panics if the block is not strictly in the lower (resp. upper) triangular part, or if the
+	/// dimensions of `block` don't match the ones of the $i$-th and $j$-th diagonal blocks
+	#[track_caller]
+	pub fn set_block(&mut self, i: usize, j: usize, block: MatRef<'_, impl Conjugate<Canonical = T>>) {
+		let offsets = self.diag.row_offsets();
+		assert!(all(i < self.n_blocks(), j < self.n_blocks()));
+		assert!(match self.side {
+			Side::Lower => j < i,
+			Side::Upper => j > i,
+		});
+		assert!(all(
+			block.nrows() == offsets[i + 1] - offsets[i],
+			block.ncols() == offsets[j + 1] - offsets[j]
+		));
+
+		let block = block.to_owned();
+		match self.off_diag[i].iter_mut().find(|(col, _)| *col == j) {
+			Some((_, dst)) => *dst = block,
+			None => self.off_diag[i].push((j, block)),
+		}
+	}
+
+	/// returns the matrix as a dense matrix
+	pub fn to_dense(&self) -> Mat<T> {
+		let offsets = self.diag.row_offsets();
+		let mut out = self.diag.to_dense();
+		for (i, row) in self.off_diag.iter().enumerate() {
+			for (j, block) in row {
+				out.as_mut()
+					.submatrix_mut(offsets[i], offsets[*j], block.nrows(), block.ncols())
+					.copy_from(block);
+			}
+		}
+		out
+	}
+
+	/// computes `dst := alpha * self * rhs` or `dst := dst + alpha * self * rhs` depending on
+	/// `accum`, one block row at a time
+	///
+	/// # panics
+	///
+	/// panics if the dimensions of `dst`, `self` and `rhs` don't match
+	#[track_caller]
+	pub fn matmul<C: Conjugate<Canonical = T>>(&self, dst: MatMut<'_, T>, accum: Accum, rhs: MatRef<'_, C>, alpha: T, par: Par) {
+		self.matmul_imp(dst, accum, Conj::No, rhs.canonical(), Conj::get::<C>(), &alpha, par)
+	}
+
+	#[track_caller]
+	#[allow(clippy::too_many_arguments)]
+	fn matmul_imp(&self, dst: MatMut<'_, T>, accum: Accum, conj_lhs: Conj, rhs: MatRef<'_, T>, conj_rhs: Conj, alpha: &T, par: Par) {
+		assert!(all(dst.nrows() == self.nrows(), rhs.nrows() == self.ncols(), dst.ncols() == rhs.ncols()));
+
+		let offsets = self.diag.row_offsets();
+		for_each_row_block(offsets, dst, par, &|i, mut dst, par| {
+			linalg::matmul::matmul_with_conj(
+				dst.rb_mut(),
+				accum,
+				self.diag.block(i),
+				conj_lhs,
+				rhs.subrows(offsets[i], offsets[i + 1] - offsets[i]),
+				conj_rhs,
+				alpha.clone(),
+				par,
+			);
+			for (j, block) in &self.off_diag[i] {
+				linalg::matmul::matmul_with_conj(
+					dst.rb_mut(),
+					Accum::Add,
+					block,
+					conj_lhs,
+					rhs.subrows(offsets[*j], block.ncols()),
+					conj_rhs,
+					alpha.clone(),
+					par,
+				);
+			}
+		});
+	}
+
+	/// returns a solver for the matrix, using the given solver for the block diagonal part
+	///
+	/// # panics
+	///
+	/// panics if the blocks of `diag` don't match the diagonal blocks of the matrix
+	#[track_caller]
+	pub fn with_diag_solver<S: SolveCore<T>>(&self, diag: BlockDiagSolver<S>) -> BlockTriangularSolver<T, S> {
+		assert!(diag.offsets() == self.offsets());
+		BlockTriangularSolver {
+			side: self.side,
+			off_diag: self.off_diag.clone(),
+			diag,
+		}
+	}
+
+	/// returns a solver for the matrix, using the $LU$ decompositions with partial pivoting of the
+	/// diagonal blocks, computed in parallel
+	#[track_caller]
+	pub fn partial_piv_lu(&self, par: Par) -> BlockTriangularSolver<T, PartialPivLu<T>> {
+		self.with_diag_solver(self.diag.partial_piv_lu(par))
+	}
+}
+
+/// solver for a block triangular matrix, using block forward or back substitution
+///
+/// the diagonal blocks are solved by a [`BlockDiagSolver`], and the off-diagonal blocks are applied
+/// with the dense matrix multiplication kernels
+#[derive(Clone, Debug)]
+pub struct BlockTriangularSolver<T, S> {
+	side: Side,
+	off_diag: Vec<Vec<(usize, Mat<T>)>>,
+	diag: BlockDiagSolver<S>,
+}
+
+impl<T: ComplexField, S: SolveCore<T>> BlockTriangularSolver<T, S> {
+	/// returns the solver of the block diagonal part
+	#[inline]
+	pub fn diagonal(&self) -> &BlockDiagSolver<S> {
+		&self.diag
+	}
+}
+
+impl<T: ComplexField, S: SolveCore<T>> ShapeCore for BlockTriangularSolver<T, S> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.diag.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.diag.ncols()
+	}
+}
+
+impl<T: ComplexField, S: SolveCore<T>> SolveCore<T> for BlockTriangularSolver<T, S> {
+	#[track_caller]
+	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(rhs.nrows() == self.nrows());
+		let par = get_global_parallelism();
+		let offsets = self.diag.offsets();
+		let n_blocks = self.off_diag.len();
+		let mut rhs = rhs;
+
+		let order: &mut dyn Iterator<Item = usize> = match self.side {
+			Side::Lower => &mut (0..n_blocks),
+			Side::Upper => &mut (0..n_blocks).rev(),
+		};
+
+		// the blocks of the solution that the i-th block row depends on are already computed
+		for i in order {
+			for (j, block) in &self.off_diag[i] {
+				let (x, b) = split_row_blocks(rhs.rb_mut(), offsets, *j, i);
+				linalg::matmul::matmul_with_conj(b, Accum::Add, block, conj, x, Conj::No, neg(&one::<T>()), par);
+			}
+			self.diag.solvers()[i].solve_in_place_with_conj(conj, rhs.rb_mut().subrows_mut(offsets[i], offsets[i + 1] - offsets[i]));
+		}
+	}
+
+	#[track_caller]
+	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(rhs.nrows() == self.nrows());
+		let par = get_global_parallelism();
+		let offsets = self.diag.offsets();
+		let n_blocks = self.off_diag.len();
+		let mut rhs = rhs;
+
+		let order: &mut dyn Iterator<Item = usize> = match self.side {
+			Side::Lower => &mut (0..n_blocks).rev(),
+			Side::Upper => &mut (0..n_blocks),
+		};
+
+		// the i-th block row of the matrix is the i-th block column of its transpose, so each block
+		// of the solution is eliminated from the remaining right-hand sides as soon as it's computed
+		for i in order {
+			self.diag.solvers()[i].solve_transpose_in_place_with_conj(conj, rhs.rb_mut().subrows_mut(offsets[i], offsets[i + 1] - offsets[i]));
+			for (j, block) in &self.off_diag[i] {
+				let (x, b) = split_row_blocks(rhs.rb_mut(), offsets, i, *j);
+				linalg::matmul::matmul_with_conj(b, Accum::Add, block.transpose(), conj, x, Conj::No, neg(&one::<T>()), par);
+			}
+		}
+	}
+}
+
+impl<T: ComplexField> LinOp<T> for BlockDiag<T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		self.matmul_imp(out, Accum::Replace, Conj::No, rhs, Conj::No, &one(), par);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		self.matmul_imp(out, Accum::Replace, Conj::Yes, rhs, Conj::No, &one(), par);
+	}
+}
+
+impl<T: ComplexField> LinOp<T> for BlockTriangular<T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		self.matmul_imp(out, Accum::Replace, Conj::No, rhs, Conj::No, &one(), par);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		self.matmul_imp(out, Accum::Replace, Conj::Yes, rhs, Conj::No, &one(), par);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::Solve;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	fn random_blocks(rng: &mut StdRng, sizes: &[(usize, usize)]) -> Vec<Mat<c64>> {
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		sizes
+			.iter()
+			.map(|&(m, n)| {
+				let mut A = CwiseMatDistribution { nrows: m, ncols: n, dist }.rand::<Mat<c64>>(rng);
+				for i in 0..Ord::min(m, n) {
+					A[(i, i)] += c64::new(2.0 * (m as f64), 0.0);
+				}
+				A
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_block_diag() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+
+		for par in [Par::Seq, Par::rayon(4)] {
+			let A = BlockDiag::new(random_blocks(rng, &[(3, 2), (0, 1), (5, 5), (1, 4), (2, 2)]));
+			assert!(all(A.nrows() == 11, A.ncols() == 14, A.n_blocks() == 5));
+			let dense = A.to_dense();
+
+			let rhs = CwiseMatDistribution { nrows: 14, ncols: 3, dist }.rand::<Mat<c64>>(rng);
+			let mut dst = Mat::<c64>::zeros(11, 3);
+			A.matmul(dst.as_mut(), Accum::Replace, rhs.as_ref(), c64::new(2.0, 0.0), par);
+			assert!(dst ~ Scale(c64::new(2.0, 0.0)) * &dense * &rhs);
+			A.matmul(dst.as_mut(), Accum::Add, rhs.conjugate(), c64::new(1.0, 0.0), par);
+			assert!(dst ~ Scale(c64::new(2.0, 0.0)) * &dense * &rhs + &dense * rhs.conjugate());
+
+			let A = BlockDiag::new(random_blocks(rng, &[(3, 3), (0, 0), (5, 5), (1, 1), (2, 2)]));
+			let dense = A.to_dense();
+			let b = CwiseMatDistribution { nrows: 11, ncols: 3, dist }.rand::<Mat<c64>>(rng);
+
+			let lu = A.partial_piv_lu(par);
+			assert!(lu.solve(&b) ~ dense.partial_piv_lu().solve(&b));
+			assert!(lu.solve_transpose(&b) ~ dense.partial_piv_lu().solve_transpose(&b));
+			assert!(lu.solve_adjoint(&b) ~ dense.partial_piv_lu().solve_adjoint(&b));
+
+			let mut out = Mat::<c64>::zeros(11, 3);
+			A.conj_apply(out.as_mut(), b.as_ref(), par, MemStack::new(&mut []));
+			assert!(out ~ dense.conjugate() * &b);
+		}
+	}
+
+	#[test]
+	fn test_block_diag_llt() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let blocks: Vec<Mat<c64>> = random_blocks(rng, &[(3, 3), (4, 4), (2, 2)]).iter().map(|B| B * B.adjoint()).collect();
+
+		let A = BlockDiag::new(blocks.clone());
+		let llt = A.llt(Side::Lower, Par::rayon(2)).unwrap();
+		let b = Mat::<c64>::from_fn(9, 2, |i, j| c64::new(i as f64, j as f64));
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+		assert!(&A.to_dense() * llt.solve(&b) ~ b);
+
+		let mut blocks = blocks;
+		blocks[1][(2, 2)] = c64::new(-1.0, 0.0);
+		let A = BlockDiag::new(blocks);
+		assert!(matches!(A.llt(Side::Lower, Par::Seq), Err(LltError::NonPositivePivot { index: 5 })));
+	}
+
+	#[test]
+	fn test_block_triangular() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0);
+		let sizes = [3, 1, 4, 0, 2];
+		let nb = sizes.len();
+
+		for side in [Side::Lower, Side::Upper] {
+			for par in [Par::Seq, Par::rayon(4)] {
+				let diag = BlockDiag::new(random_blocks(rng, &sizes.map(|n| (n, n))));
+				let mut A = BlockTriangular::new(diag, side);
+				for i in 0..nb {
+					for j in 0..nb {
+						let strict = match side {
+							Side::Lower => j < i,
+							Side::Upper => j > i,
+						};
+						if strict && (i + j) % 3 != 1 {
+							let B = CwiseMatDistribution {
+								nrows: sizes[i],
+								ncols: sizes[j],
+								dist,
+							}
+							.rand::<Mat<c64>>(rng);
+							A.set_block(i, j, B.as_ref());
+						}
+					}
+				}
+				assert!(A.block(2, 2).unwrap() == A.diagonal().block(2));
+				let dense = A.to_dense();
+				let n = dense.nrows();
+
+				let rhs = CwiseMatDistribution { nrows: n, ncols: 3, dist }.rand::<Mat<c64>>(rng);
+				let mut dst = Mat::<c64>::zeros(n, 3);
+				A.matmul(dst.as_mut(), Accum::Replace, rhs.as_ref(), c64::new(1.0, 0.0), par);
+				assert!(dst ~ &dense * &rhs);
+
+				let solver = A.partial_piv_lu(par);
+				let lu = dense.partial_piv_lu();
+				assert!(solver.solve(&rhs) ~ lu.solve(&rhs));
+				assert!(solver.solve_conjugate(&rhs) ~ lu.solve_conjugate(&rhs));
+				assert!(solver.solve_transpose(&rhs) ~ lu.solve_transpose(&rhs));
+				assert!(solver.solve_adjoint(&rhs) ~ lu.solve_adjoint(&rhs));
+			}
+		}
+	}
+}
@@ -0,0 +1,413 @@
+use crate::internal_prelude::*;
+use crate::sparse::{SymbolicSparseColMat, SymbolicSparseColMatRef};
+use crate::{Index, Par};
+use alloc::vec::Vec;
+use core::fmt;
+use equator::assert;
+
+const WORD: usize = u64::BITS as usize;
+
+/// number of columns of the left hand side that are combined in the lookup tables of the matrix
+/// product
+const TABLE_BITS: usize = 8;
+/// number of words of each column that are processed together in the matrix product, so that the
+/// lookup table fits in the l2 cache
+const WORD_BLOCK: usize = 32;
+
+/// operation used to accumulate the products in a boolean matrix product
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BoolAdd {
+	/// logical or, such that the product computes the reachability in a graph
+	Or,
+	/// exclusive or, such that the product is computed over $\mathbb{F}_2$
+	Xor,
+}
+
+impl BoolAdd {
+	#[inline(always)]
+	fn apply(self, dst: &mut [u64], src: &[u64]) {
+		match self {
+			BoolAdd::Or => {
+				for (dst, src) in dst.iter_mut().zip(src) {
+					*dst |= *src;
+				}
+			},
+			BoolAdd::Xor => {
+				for (dst, src) in dst.iter_mut().zip(src) {
+					*dst ^= *src;
+				}
+			},
+		}
+	}
+}
+
+/// boolean matrix, stored in column-major format with each entry packed in a single bit
+///
+/// # example
+/// ```
+/// use faer::linalg::boolean::BoolMat;
+///
+/// // path graph `0 -> 1 -> 2 -> 3`
+/// let adj = BoolMat::from_fn(4, 4, |i, j| j == i + 1);
+/// let reach = adj.transitive_closure();
+/// assert!(reach == BoolMat::from_fn(4, 4, |i, j| j > i));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BoolMat {
+	nrows: usize,
+	ncols: usize,
+	col_words: usize,
+	// the bits past the last row of each column are always zero
+	data: Vec<u64>,
+}
+
+impl BoolMat {
+	/// returns a new matrix with dimensions `(nrows, ncols)`, filled with `false`
+	pub fn zeros(nrows: usize, ncols: usize) -> Self {
+		let col_words = nrows.div_ceil(WORD);
+		Self {
+			nrows,
+			ncols,
+			col_words,
+			data: alloc::vec![0; col_words.checked_mul(ncols).unwrap()],
+		}
+	}
+
+	/// returns the identity matrix of dimension `dim`
+	pub fn identity(dim: usize) -> Self {
+		let mut mat = Self::zeros(dim, dim);
+		for i in 0..dim {
+			mat.set(i, i, true);
+		}
+		mat
+	}
+
+	/// returns a new matrix with dimensions `(nrows, ncols)`, filled with the provided function
+	pub fn from_fn(nrows: usize, ncols: usize, f: impl FnMut(usize, usize) -> bool) -> Self {
+		let mut f = f;
+		let mut mat = Self::zeros(nrows, ncols);
+		for j in 0..ncols {
+			for i in 0..nrows {
+				if f(i, j) {
+					mat.set(i, j, true);
+				}
+			}
+		}
+		mat
+	}
+
+	/// returns a packed copy of `mat`
+	pub fn from_mat(mat: MatRef<'_, bool>) -> Self {
+		Self::from_fn(mat.nrows(), mat.ncols(), |i, j| mat[(i, j)])
+	}
+
+	/// returns an unpacked copy of `self`
+	pub fn to_mat(&self) -> Mat<bool> {
+		Mat::from_fn(self.nrows, self.ncols, |i, j| self.get(i, j))
+	}
+
+	/// returns the matrix whose entries are `true` at the positions of the nonzeros of `pattern`
+	pub fn from_pattern<I: Index>(pattern: SymbolicSparseColMatRef<'_, I>) -> Self {
+		let mut mat = Self::zeros(pattern.nrows(), pattern.ncols());
+		for j in 0..pattern.ncols() {
+			for i in pattern.row_idx_of_col(j) {
+				mat.set(i, j, true);
+			}
+		}
+		mat
+	}
+
+	/// returns the sparsity pattern of the `true` entries of `self`, with sorted row indices
+	///
+	/// # panics
+	/// panics if the number of `true` entries doesn't fit in `I`
+	#[track_caller]
+	pub fn to_pattern<I: Index>(&self) -> SymbolicSparseColMat<I> {
+		let nnz = self.count_ones();
+		assert!(nnz <= I::MAX.zx() / 2);
+
+		let mut col_ptr = Vec::with_capacity(self.ncols + 1);
+		let mut row_idx = Vec::with_capacity(nnz);
+		col_ptr.push(I::truncate(0));
+		for j in 0..self.ncols {
+			for (w, &word) in self.col(j).iter().enumerate() {
+				let mut word = word;
+				while word != 0 {
+					row_idx.push(I::truncate(w * WORD + word.trailing_zeros() as usize));
+					word &= word - 1;
+				}
+			}
+			col_ptr.push(I::truncate(row_idx.len()));
+		}
+		SymbolicSparseColMat::new_checked(self.nrows, self.ncols, col_ptr, None, row_idx)
+	}
+
+	/// returns the number of rows of the matrix
+	#[inline]
+	pub fn nrows(&self) -> usize {
+		self.nrows
+	}
+
+	/// returns the number of columns of the matrix
+	#[inline]
+	pub fn ncols(&self) -> usize {
+		self.ncols
+	}
+
+	/// returns the number of rows and columns of the matrix
+	#[inline]
+	pub fn shape(&self) -> (usize, usize) {
+		(self.nrows, self.ncols)
+	}
+
+	/// returns the words storing the column at index `j`. the entry at row `i` is stored in bit
+	/// `i % 64` of word `i / 64`
+	///
+	/// # panics
+	/// panics if `j >= self.ncols()`
+	#[inline]
+	#[track_caller]
+	pub fn col(&self, j: usize) -> &[u64] {
+		assert!(j < self.ncols);
+		&self.data[j * self.col_words..][..self.col_words]
+	}
+
+	#[inline]
+	fn col_mut(&mut self, j: usize) -> &mut [u64] {
+		&mut self.data[j * self.col_words..][..self.col_words]
+	}
+
+	/// returns the entry at the given indices
+	///
+	/// # panics
+	/// panics if `i >= self.nrows()` or `j >= self.ncols()`
+	#[inline]
+	#[track_caller]
+	pub fn get(&self, i: usize, j: usize) -> bool {
+		assert!(all(i < self.nrows, j < self.ncols));
+		(self.data[j * self.col_words + i / WORD] >> (i % WORD)) & 1 == 1
+	}
+
+	/// sets the entry at the given indices to `value`
+	///
+	/// # panics
+	/// panics if `i >= self.nrows()` or `j >= self.ncols()`
+	#[inline]
+	#[track_caller]
+	pub fn set(&mut self, i: usize, j: usize, value: bool) {
+		assert!(all(i < self.nrows, j < self.ncols));
+		let word = &mut self.data[j * self.col_words + i / WORD];
+		let bit = 1u64 << (i % WORD);
+		if value {
+			*word |= bit;
+		} else {
+			*word &= !bit;
+		}
+	}
+
+	/// returns the number of `true` entries
+	pub fn count_ones(&self) -> usize {
+		self.data.iter().map(|w| w.count_ones() as usize).sum()
+	}
+
+	/// returns the transpose of `self`
+	pub fn transpose(&self) -> Self {
+		let mut out = Self::zeros(self.ncols, self.nrows);
+		for j in 0..self.ncols {
+			for (w, &word) in self.col(j).iter().enumerate() {
+				let mut word = word;
+				while word != 0 {
+					out.set(j, w * WORD + word.trailing_zeros() as usize, true);
+					word &= word - 1;
+				}
+			}
+		}
+		out
+	}
+
+	/// returns the transitive closure of `self`, whose entry at `(i, j)` is `true` if and only if
+	/// there is a path of length at least one from `i` to `j` in the graph with adjacency matrix
+	/// `self`
+	///
+	/// the reflexive transitive closure can be obtained by setting the diagonal to `true`
+	/// beforehand
+	///
+	/// # panics
+	/// panics if `self` is not square
+	#[track_caller]
+	pub fn transitive_closure(&self) -> Self {
+		assert!(self.nrows == self.ncols);
+		let n = self.nrows;
+
+		// warshall's algorithm, where each step adds column `k` to the columns whose row `k` is set
+		let mut closure = self.clone();
+		let mut col_k = alloc::vec![0u64; self.col_words];
+		for k in 0..n {
+			col_k.copy_from_slice(closure.col(k));
+			for j in 0..n {
+				if closure.get(k, j) {
+					BoolAdd::Or.apply(closure.col_mut(j), &col_k);
+				}
+			}
+		}
+		closure
+	}
+}
+
+impl fmt::Debug for BoolMat {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "[")?;
+		for i in 0..self.nrows {
+			write!(f, "    ")?;
+			for j in 0..self.ncols {
+				f.write_str(if self.get(i, j) { "1" } else { "0" })?;
+			}
+			writeln!(f)?;
+		}
+		write!(f, "]")
+	}
+}
+
+/// computes the columns of `dst` from the columns of `rhs`, starting at `col_start`, with the
+/// method of four russians
+fn matmul_imp(dst: &mut [u64], lhs: &BoolMat, rhs: &BoolMat, col_start: usize, add: BoolAdd) {
+	let col_words = lhs.col_words;
+	let ncols = dst.len() / col_words;
+	let depth = lhs.ncols;
+	let mut table = alloc::vec![0u64; (1 << TABLE_BITS) * WORD_BLOCK];
+
+	for word_start in (0..col_words).step_by(WORD_BLOCK) {
+		let bw = Ord::min(WORD_BLOCK, col_words - word_start);
+
+		for k in (0..depth).step_by(TABLE_BITS) {
+			let bk = Ord::min(TABLE_BITS, depth - k);
+
+			// `table[mask]` holds the sum of the columns of `lhs` selected by the bits of `mask`,
+			// which is computed from the same sum with the lowest bit cleared
+			table[..bw].fill(0);
+			for mask in 1..1usize << bk {
+				let prev = mask & (mask - 1);
+				let col = &lhs.col(k + mask.trailing_zeros() as usize)[word_start..][..bw];
+				let (head, tail) = table.split_at_mut(mask * bw);
+				let dst = &mut tail[..bw];
+				dst.copy_from_slice(&head[prev * bw..][..bw]);
+				add.apply(dst, col);
+			}
+
+			// `k` is a multiple of `TABLE_BITS`, so the bits never straddle two words
+			for j in 0..ncols {
+				let mask = (rhs.col(col_start + j)[k / WORD] >> (k % WORD)) as usize & ((1 << bk) - 1);
+				if mask != 0 {
+					add.apply(&mut dst[j * col_words + word_start..][..bw], &table[mask * bw..][..bw]);
+				}
+			}
+		}
+	}
+}
+
+/// computes the boolean matrix product `[beta * acc] + lhs * rhs`, and stores the result in `acc`
+///
+/// performs the operation:
+/// - `acc = lhs * rhs` if `beta` is `Accum::Replace` (in this case, the preexisting values in `acc`
+///   are not read)
+/// - `acc = acc + lhs * rhs` if `beta` is `Accum::Add`
+///
+/// where the multiplication is the logical and, and the addition is given by `add`
+///
+/// # panics
+/// panics if the matrix dimensions are not compatible for matrix multiplication
+#[track_caller]
+pub fn matmul(dst: &mut BoolMat, beta: Accum, lhs: &BoolMat, rhs: &BoolMat, add: BoolAdd, par: Par) {
+	assert!(all(dst.nrows == lhs.nrows, dst.ncols == rhs.ncols, lhs.ncols == rhs.nrows));
+
+	if beta == Accum::Replace {
+		dst.data.fill(0);
+	}
+	if dst.data.is_empty() {
+		return;
+	}
+
+	match par {
+		Par::Seq => matmul_imp(&mut dst.data, lhs, rhs, 0, add),
+		#[cfg(feature = "rayon")]
+		Par::Rayon(nthreads) => {
+			use rayon::prelude::*;
+			let (ncols, col_words) = (dst.ncols, dst.col_words);
+			let chunk = ncols.div_ceil(Ord::min(nthreads.get(), ncols));
+			dst.data
+				.par_chunks_mut(chunk * col_words)
+				.enumerate()
+				.for_each(|(idx, dst)| matmul_imp(dst, lhs, rhs, idx * chunk, add));
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::linalg::matmul::semiring::{self, OrAnd};
+	use crate::stats::prelude::*;
+
+	fn random(rng: &mut StdRng, m: usize, n: usize, density: f64) -> BoolMat {
+		BoolMat::from_fn(m, n, |_, _| rng.gen_bool(density))
+	}
+
+	#[test]
+	fn test_matmul() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n, k) in [(0, 3, 2), (3, 0, 2), (1, 1, 1), (70, 5, 13), (150, 40, 300), (2100, 3, 20)] {
+			for par in [Par::Seq, Par::rayon(4)] {
+				let A = random(rng, m, k, 0.1);
+				let B = random(rng, k, n, 0.1);
+				let C = random(rng, m, n, 0.5);
+
+				let mut expected = C.to_mat();
+				semiring::matmul(&mut expected, Accum::Add, A.to_mat(), B.to_mat(), OrAnd, par);
+				let mut dst = C.clone();
+				matmul(&mut dst, Accum::Add, &A, &B, BoolAdd::Or, par);
+				assert!(dst.to_mat() == expected);
+
+				let expected = BoolMat::from_fn(m, n, |i, j| (0..k).filter(|&l| A.get(i, l) && B.get(l, j)).count() % 2 == 1);
+				let mut dst = C.clone();
+				matmul(&mut dst, Accum::Replace, &A, &B, BoolAdd::Xor, par);
+				assert!(dst == expected);
+			}
+		}
+	}
+
+	#[test]
+	fn test_closure() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 10, 100] {
+			let adj = random(rng, n, n, f64::min(1.5 / n as f64, 1.0));
+			let closure = adj.transitive_closure();
+
+			// the closure is the sum of the powers of the adjacency matrix
+			let mut power = adj.clone();
+			let mut expected = adj.clone();
+			for _ in 1..n {
+				let prev = power.clone();
+				matmul(&mut power, Accum::Replace, &prev, &adj, BoolAdd::Or, Par::Seq);
+				matmul(&mut expected, Accum::Add, &power, &BoolMat::identity(n), BoolAdd::Or, Par::Seq);
+			}
+			assert!(closure == expected);
+		}
+	}
+
+	#[test]
+	fn test_pattern() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let A = random(rng, 130, 7, 0.2);
+
+		let pattern = A.to_pattern::<u32>();
+		assert!(pattern.compute_nnz() == A.count_ones());
+		assert!(BoolMat::from_pattern(pattern.as_ref()) == A);
+
+		let At = A.transpose();
+		assert!(At.shape() == (7, 130));
+		assert!(At.to_mat() == A.to_mat().transpose());
+	}
+}
@@ -79,6 +79,7 @@ pub fn cholesky_in_place<T: ComplexField>(
 ) -> Result<LltInfo, LltError> {
 	let params = params.config;
 	let N = A.nrows();
+	crate::utils::trace::span!(DEBUG, "cholesky", n = N);
 	let mut D = unsafe { temp_mat_uninit(N, 1, stack).0 };
 	let D = D.as_mat_mut();
 
@@ -12,6 +12,8 @@ pub fn solve_in_place_with_conj<T: ComplexField>(L: MatRef<'_, T>, conj_lhs: Con
 	let n = L.nrows();
 	assert!(all(L.nrows() == n, L.ncols() == n, rhs.nrows() == n));
 
+	crate::utils::counters::count!(Solve, T, fma = n * n * rhs.ncols(), elems = n * n / 2 + 2 * n * rhs.ncols());
+
 	_ = stack;
 	let mut rhs = rhs;
 	linalg::triangular_solve::solve_lower_triangular_in_place_with_conj(L, conj_lhs, rhs.rb_mut(), par);
@@ -0,0 +1,716 @@
+//! vectorized elementwise functions
+//!
+//! the functions in this module replace each entry of a matrix, row or column by the image of the
+//! entry under a scalar function, e.g. [`exp`] or [`tanh`]. they're implemented for `f32` and `f64`
+//! with [`pulp`], and process the contiguous columns (or rows) of the input several entries at a
+//! time. the other layouts fall back to a scalar loop over the entries
+//!
+//! the transcendental functions are accurate to a few units in the last place, but aren't
+//! correctly rounded, so their results may differ slightly from the ones of the standard library.
+//! results in the subnormal range are flushed to zero
+//!
+//! # example
+//! ```
+//! use faer::linalg::elementwise;
+//! use faer::{Col, Mat};
+//!
+//! let mut a = Mat::from_fn(4, 3, |i, j| (i as f64) - (j as f64));
+//! let b = a.clone();
+//!
+//! // softmax of the first column
+//! let mut x = Col::from_fn(4, |i| b[(i, 0)]);
+//! elementwise::exp(&mut x);
+//! let total = x.sum();
+//! x /= total;
+//!
+//! elementwise::sigmoid(&mut a);
+//! assert!((a[(2, 1)] - 1.0 / (1.0 + (-b[(2, 1)]).exp())).abs() < 1e-15);
+//! ```
+
+use crate::internal_prelude::*;
+use pulp::Simd;
+
+/// real floating point types supported by the elementwise functions, i.e. `f32` and `f64`
+pub trait SimdFloat: RealField + sealed::Lane {}
+
+impl SimdFloat for f32 {}
+impl SimdFloat for f64 {}
+
+#[derive(Copy, Clone, Debug)]
+enum Func {
+	Exp,
+	Ln,
+	Sqrt,
+	Abs,
+	Tanh,
+	Sigmoid,
+}
+
+mod sealed {
+	use super::*;
+
+	/// simd operations on the lanes of a floating point type
+	pub trait Lane: Copy + 'static {
+		type V<S: Simd>: Copy;
+		type M<S: Simd>: Copy;
+
+		const MIN_POSITIVE: f64;
+		/// logarithm of the largest finite value
+		const MAX_LN: f64;
+		/// logarithm of the smallest positive normal value
+		const MIN_LN: f64;
+		/// power of two that maps the subnormal values to normal values, and its base 2 logarithm
+		const SUBNORMAL_SCALE: f64;
+		const SUBNORMAL_SCALE_LOG2: f64;
+
+		fn as_mut_simd<S: Simd>(slice: &mut [Self]) -> (&mut [Self::V<S>], &mut [Self]);
+		fn partial_load<S: Simd>(simd: S, slice: &[Self]) -> Self::V<S>;
+		fn partial_store<S: Simd>(simd: S, slice: &mut [Self], value: Self::V<S>);
+
+		fn splat<S: Simd>(simd: S, value: f64) -> Self::V<S>;
+		fn add<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S>;
+		fn sub<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S>;
+		fn mul<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S>;
+		fn div<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S>;
+		/// computes `lhs * rhs + acc`
+		fn mul_add<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>, acc: Self::V<S>) -> Self::V<S>;
+		/// computes `lhs * rhs + acc` with a single rounding
+		fn mul_add_fused<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>, acc: Self::V<S>) -> Self::V<S>;
+		fn min<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S>;
+		fn max<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S>;
+		fn abs<S: Simd>(simd: S, value: Self::V<S>) -> Self::V<S>;
+
+		fn less_than<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::M<S>;
+		fn equal<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::M<S>;
+		fn not<S: Simd>(simd: S, mask: Self::M<S>) -> Self::M<S>;
+		fn select<S: Simd>(simd: S, mask: Self::M<S>, if_true: Self::V<S>, if_false: Self::V<S>) -> Self::V<S>;
+
+		/// returns $n = \operatorname{round}(y)$ and $2^n$, for $n$ in the range of the normal
+		/// exponents
+		fn exp2_round<S: Simd>(simd: S, y: Self::V<S>) -> (Self::V<S>, Self::V<S>);
+		/// returns $e$ and $m \in [1, 2)$ such that $x = m 2^e$, for positive normal $x$
+		fn frexp<S: Simd>(simd: S, x: Self::V<S>) -> (Self::V<S>, Self::V<S>);
+	}
+
+	impl Lane for f64 {
+		type M<S: Simd> = S::m64s;
+		type V<S: Simd> = S::f64s;
+
+		const MAX_LN: f64 = 709.782712893384;
+		const MIN_LN: f64 = -708.3964185322641;
+		const MIN_POSITIVE: f64 = f64::MIN_POSITIVE;
+		const SUBNORMAL_SCALE: f64 = (1u64 << 54) as f64;
+		const SUBNORMAL_SCALE_LOG2: f64 = 54.0;
+
+		#[inline(always)]
+		fn as_mut_simd<S: Simd>(slice: &mut [Self]) -> (&mut [Self::V<S>], &mut [Self]) {
+			S::as_mut_simd_f64s(slice)
+		}
+
+		#[inline(always)]
+		fn partial_load<S: Simd>(simd: S, slice: &[Self]) -> Self::V<S> {
+			simd.partial_load_f64s(slice)
+		}
+
+		#[inline(always)]
+		fn partial_store<S: Simd>(simd: S, slice: &mut [Self], value: Self::V<S>) {
+			simd.partial_store_f64s(slice, value)
+		}
+
+		#[inline(always)]
+		fn splat<S: Simd>(simd: S, value: f64) -> Self::V<S> {
+			simd.splat_f64s(value)
+		}
+
+		#[inline(always)]
+		fn add<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.add_f64s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn sub<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.sub_f64s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn mul<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.mul_f64s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn div<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.div_f64s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn mul_add<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>, acc: Self::V<S>) -> Self::V<S> {
+			simd.mul_add_e_f64s(lhs, rhs, acc)
+		}
+
+		#[inline(always)]
+		fn mul_add_fused<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>, acc: Self::V<S>) -> Self::V<S> {
+			simd.mul_add_f64s(lhs, rhs, acc)
+		}
+
+		#[inline(always)]
+		fn min<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.min_f64s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn max<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.max_f64s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn abs<S: Simd>(simd: S, value: Self::V<S>) -> Self::V<S> {
+			simd.abs_f64s(value)
+		}
+
+		#[inline(always)]
+		fn less_than<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::M<S> {
+			simd.less_than_f64s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn equal<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::M<S> {
+			simd.equal_f64s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn not<S: Simd>(simd: S, mask: Self::M<S>) -> Self::M<S> {
+			simd.not_m64s(mask)
+		}
+
+		#[inline(always)]
+		fn select<S: Simd>(simd: S, mask: Self::M<S>, if_true: Self::V<S>, if_false: Self::V<S>) -> Self::V<S> {
+			simd.select_f64s_m64s(mask, if_true, if_false)
+		}
+
+		#[inline(always)]
+		fn exp2_round<S: Simd>(simd: S, y: Self::V<S>) -> (Self::V<S>, Self::V<S>) {
+			if core::mem::size_of::<S::u32s>() != core::mem::size_of::<S::u64s>() {
+				// scalar backend
+				let n = libm::round(bytemuck::cast::<S::f64s, f64>(y));
+				let pow2 = f64::from_bits(((n as i64 + 1023) as u64) << 52);
+				return (bytemuck::cast(n), bytemuck::cast(pow2));
+			}
+
+			// the unit in the last place of `MAGIC` is $2^{-32}$, so that adding it to $y + 1/2$
+			// stores $\lfloor y + 1/2 \rfloor$ in the high 32 bits of the result, and the fractional
+			// part in the low 32 bits
+			const MAGIC: f64 = 1.5 * (1u64 << 20) as f64;
+			let magic = simd.splat_f64s(MAGIC);
+			let t = simd.add_f64s(simd.add_f64s(y, simd.splat_f64s(0.5)), magic);
+			let n_bits = simd.and_u64s(
+				simd.sub_u64s(simd.transmute_u64s_f64s(t), simd.transmute_u64s_f64s(magic)),
+				simd.splat_u64s(0xFFFF_FFFF_0000_0000),
+			);
+			let n = simd.sub_f64s(simd.transmute_f64s_u64s(simd.add_u64s(simd.transmute_u64s_f64s(magic), n_bits)), magic);
+
+			// shift $n$ from bit 32 to the exponent field at bit 52
+			let exponent: S::u32s = bytemuck::cast(n_bits);
+			let exponent: S::u64s = bytemuck::cast(simd.wrapping_dyn_shl_u32s(exponent, simd.splat_u32s(20)));
+			let pow2 = simd.transmute_f64s_u64s(simd.add_u64s(exponent, simd.splat_u64s(1.0f64.to_bits())));
+
+			(n, pow2)
+		}
+
+		#[inline(always)]
+		fn frexp<S: Simd>(simd: S, x: Self::V<S>) -> (Self::V<S>, Self::V<S>) {
+			let bits = simd.transmute_u64s_f64s(x);
+			let m = simd.transmute_f64s_u64s(simd.or_u64s(
+				simd.and_u64s(bits, simd.splat_u64s(0x000F_FFFF_FFFF_FFFF)),
+				simd.splat_u64s(1.0f64.to_bits()),
+			));
+
+			if core::mem::size_of::<S::u32s>() != core::mem::size_of::<S::u64s>() {
+				// scalar backend
+				let e = ((bytemuck::cast::<S::u64s, u64>(bits) >> 52) as i64 - 1023) as f64;
+				return (bytemuck::cast(e), m);
+			}
+
+			// move the biased exponent to bit 32, then convert it to a float with the same trick as
+			// in `exp2_round`
+			const MAGIC: f64 = 1.5 * (1u64 << 20) as f64;
+			let magic = simd.splat_f64s(MAGIC);
+			let e: S::u32s = bytemuck::cast(bits);
+			let e: S::u64s = bytemuck::cast(simd.wrapping_dyn_shr_u32s(e, simd.splat_u32s(20)));
+			let e = simd.and_u64s(e, simd.splat_u64s(0xFFFF_FFFF_0000_0000));
+			let e = simd.sub_f64s(simd.transmute_f64s_u64s(simd.add_u64s(simd.transmute_u64s_f64s(magic), e)), magic);
+
+			(simd.sub_f64s(e, simd.splat_f64s(1023.0)), m)
+		}
+	}
+
+	impl Lane for f32 {
+		type M<S: Simd> = S::m32s;
+		type V<S: Simd> = S::f32s;
+
+		const MAX_LN: f64 = 88.72283;
+		const MIN_LN: f64 = -87.33654;
+		const MIN_POSITIVE: f64 = f32::MIN_POSITIVE as f64;
+		const SUBNORMAL_SCALE: f64 = (1u64 << 24) as f64;
+		const SUBNORMAL_SCALE_LOG2: f64 = 24.0;
+
+		#[inline(always)]
+		fn as_mut_simd<S: Simd>(slice: &mut [Self]) -> (&mut [Self::V<S>], &mut [Self]) {
+			S::as_mut_simd_f32s(slice)
+		}
+
+		#[inline(always)]
+		fn partial_load<S: Simd>(simd: S, slice: &[Self]) -> Self::V<S> {
+			simd.partial_load_f32s(slice)
+		}
+
+		#[inline(always)]
+		fn partial_store<S: Simd>(simd: S, slice: &mut [Self], value: Self::V<S>) {
+			simd.partial_store_f32s(slice, value)
+		}
+
+		#[inline(always)]
+		fn splat<S: Simd>(simd: S, value: f64) -> Self::V<S> {
+			simd.splat_f32s(value as f32)
+		}
+
+		#[inline(always)]
+		fn add<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.add_f32s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn sub<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.sub_f32s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn mul<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.mul_f32s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn div<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.div_f32s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn mul_add<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>, acc: Self::V<S>) -> Self::V<S> {
+			simd.mul_add_e_f32s(lhs, rhs, acc)
+		}
+
+		#[inline(always)]
+		fn mul_add_fused<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>, acc: Self::V<S>) -> Self::V<S> {
+			simd.mul_add_f32s(lhs, rhs, acc)
+		}
+
+		#[inline(always)]
+		fn min<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.min_f32s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn max<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::V<S> {
+			simd.max_f32s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn abs<S: Simd>(simd: S, value: Self::V<S>) -> Self::V<S> {
+			simd.abs_f32s(value)
+		}
+
+		#[inline(always)]
+		fn less_than<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::M<S> {
+			simd.less_than_f32s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn equal<S: Simd>(simd: S, lhs: Self::V<S>, rhs: Self::V<S>) -> Self::M<S> {
+			simd.equal_f32s(lhs, rhs)
+		}
+
+		#[inline(always)]
+		fn not<S: Simd>(simd: S, mask: Self::M<S>) -> Self::M<S> {
+			simd.not_m32s(mask)
+		}
+
+		#[inline(always)]
+		fn select<S: Simd>(simd: S, mask: Self::M<S>, if_true: Self::V<S>, if_false: Self::V<S>) -> Self::V<S> {
+			simd.select_f32s_m32s(mask, if_true, if_false)
+		}
+
+		#[inline(always)]
+		fn exp2_round<S: Simd>(simd: S, y: Self::V<S>) -> (Self::V<S>, Self::V<S>) {
+			// the unit in the last place of `MAGIC` is $1$, so that adding it to $y$ rounds it to
+			// the nearest integer, stored in the low bits of the result
+			const MAGIC: f32 = 1.5 * (1u32 << 23) as f32;
+			let magic = simd.splat_f32s(MAGIC);
+			let t = simd.add_f32s(y, magic);
+			let n = simd.sub_f32s(t, magic);
+
+			let n_bits = simd.sub_u32s(simd.transmute_u32s_f32s(t), simd.transmute_u32s_f32s(magic));
+			let exponent = simd.wrapping_dyn_shl_u32s(simd.add_u32s(n_bits, simd.splat_u32s(127)), simd.splat_u32s(23));
+			(n, simd.transmute_f32s_u32s(exponent))
+		}
+
+		#[inline(always)]
+		fn frexp<S: Simd>(simd: S, x: Self::V<S>) -> (Self::V<S>, Self::V<S>) {
+			let bits = simd.transmute_u32s_f32s(x);
+			let m = simd.transmute_f32s_u32s(simd.or_u32s(simd.and_u32s(bits, simd.splat_u32s(0x007F_FFFF)), simd.splat_u32s(1.0f32.to_bits())));
+
+			// the unit in the last place of `MAGIC` is $1$, so adding the biased exponent to its bits
+			// adds it to its value
+			const MAGIC: f32 = (1u32 << 23) as f32;
+			let magic = simd.splat_f32s(MAGIC);
+			let e = simd.wrapping_dyn_shr_u32s(bits, simd.splat_u32s(23));
+			let e = simd.sub_f32s(simd.transmute_f32s_u32s(simd.add_u32s(simd.transmute_u32s_f32s(magic), e)), magic);
+
+			(simd.sub_f32s(e, simd.splat_f32s(127.0)), m)
+		}
+	}
+}
+
+use sealed::Lane;
+
+const LN2_HI: f64 = 0.693359375;
+const LN2_LO: f64 = -2.121_944_400_546_905_7e-4;
+
+/// evaluates the polynomial with the given coefficients, in increasing degree order
+#[inline(always)]
+fn horner<L: Lane, S: Simd>(simd: S, x: L::V<S>, coeffs: &[f64]) -> L::V<S> {
+	let mut acc = L::splat(simd, coeffs[coeffs.len() - 1]);
+	for &c in coeffs[..coeffs.len() - 1].iter().rev() {
+		acc = L::mul_add(simd, acc, x, L::splat(simd, c));
+	}
+	acc
+}
+
+#[inline(always)]
+fn exp_simd<L: Lane, S: Simd>(simd: S, x: L::V<S>) -> L::V<S> {
+	let zero = L::splat(simd, 0.0);
+	let one = L::splat(simd, 1.0);
+	let x_clamped = L::min(simd, L::max(simd, x, L::splat(simd, L::MIN_LN)), L::splat(simd, L::MAX_LN));
+
+	// $e^x = 2^n e^r$, with $|r| \le \ln(2) / 2$. for positive $x$, the power of two is split as
+	// $2^{n - 1} \times 2$, so that it stays in the range of the normal values
+	let positive = L::less_than(simd, zero, x_clamped);
+	let shift = L::select(simd, positive, one, zero);
+	let (n, pow2) = L::exp2_round(
+		simd,
+		L::sub(simd, L::mul(simd, x_clamped, L::splat(simd, core::f64::consts::LOG2_E)), shift),
+	);
+	let n = L::add(simd, n, shift);
+
+	let r = L::mul_add(simd, n, L::splat(simd, -LN2_HI), x_clamped);
+	let r = L::mul_add(simd, n, L::splat(simd, -LN2_LO), r);
+
+	const COEFFS: &[f64] = &[
+		1.0,
+		1.0,
+		1.0 / 2.0,
+		1.0 / 6.0,
+		1.0 / 24.0,
+		1.0 / 120.0,
+		1.0 / 720.0,
+		1.0 / 5040.0,
+		1.0 / 40320.0,
+		1.0 / 362880.0,
+		1.0 / 3628800.0,
+		1.0 / 39916800.0,
+		1.0 / 479001600.0,
+		1.0 / 6227020800.0,
+	];
+	let y = L::mul(simd, L::mul(simd, horner::<L, S>(simd, r, COEFFS), pow2), L::add(simd, one, shift));
+
+	let y = L::select(simd, L::less_than(simd, x, L::splat(simd, L::MIN_LN)), zero, y);
+	let y = L::select(simd, L::less_than(simd, L::splat(simd, L::MAX_LN), x), L::splat(simd, f64::INFINITY), y);
+	// propagate nans
+	L::select(simd, L::equal(simd, x, x), y, x)
+}
+
+#[inline(always)]
+fn ln_simd<L: Lane, S: Simd>(simd: S, x: L::V<S>) -> L::V<S> {
+	let zero = L::splat(simd, 0.0);
+	let one = L::splat(simd, 1.0);
+	let two = L::splat(simd, 2.0);
+
+	// scale the subnormal values to the normal range
+	let subnormal = L::less_than(simd, x, L::splat(simd, L::MIN_POSITIVE));
+	let x_scaled = L::select(simd, subnormal, L::mul(simd, x, L::splat(simd, L::SUBNORMAL_SCALE)), x);
+	let (e, m) = L::frexp(simd, x_scaled);
+	let e = L::select(simd, subnormal, L::sub(simd, e, L::splat(simd, L::SUBNORMAL_SCALE_LOG2)), e);
+
+	// $m \in [\sqrt{2} / 2, \sqrt{2})$
+	let large = L::less_than(simd, L::splat(simd, core::f64::consts::SQRT_2), m);
+	let m = L::select(simd, large, L::mul(simd, m, L::splat(simd, 0.5)), m);
+	let e = L::select(simd, large, L::add(simd, e, one), e);
+
+	// $\ln(m) = 2 \operatorname{atanh}(s)$, with $s = (m - 1) / (m + 1)$
+	let f = L::sub(simd, m, one);
+	let s = L::div(simd, f, L::add(simd, f, two));
+	let z = L::mul(simd, s, s);
+	const COEFFS: &[f64] = &[
+		1.0 / 3.0,
+		1.0 / 5.0,
+		1.0 / 7.0,
+		1.0 / 9.0,
+		1.0 / 11.0,
+		1.0 / 13.0,
+		1.0 / 15.0,
+		1.0 / 17.0,
+		1.0 / 19.0,
+		1.0 / 21.0,
+	];
+	let r = L::mul(simd, z, horner::<L, S>(simd, z, COEFFS));
+	let two_s = L::add(simd, s, s);
+	let ln_m = L::mul_add(simd, two_s, r, two_s);
+
+	let y = L::mul_add(simd, e, L::splat(simd, LN2_HI), L::mul_add(simd, e, L::splat(simd, LN2_LO), ln_m));
+
+	let y = L::select(simd, L::equal(simd, x, L::splat(simd, f64::INFINITY)), x, y);
+	let y = L::select(simd, L::equal(simd, x, zero), L::splat(simd, f64::NEG_INFINITY), y);
+	let y = L::select(simd, L::less_than(simd, x, zero), L::splat(simd, f64::NAN), y);
+	L::select(simd, L::equal(simd, x, x), y, x)
+}
+
+#[inline(always)]
+fn tanh_simd<L: Lane, S: Simd>(simd: S, x: L::V<S>) -> L::V<S> {
+	let one = L::splat(simd, 1.0);
+	let a = L::abs(simd, x);
+
+	// $\tanh(a) = (1 - e^{-2a}) / (1 + e^{-2a})$, which loses accuracy to cancellation for small $a$
+	let t = exp_simd::<L, S>(simd, L::mul(simd, a, L::splat(simd, -2.0)));
+	let large = L::div(simd, L::sub(simd, one, t), L::add(simd, one, t));
+
+	// taylor series for $|a| < 1/8$
+	const COEFFS: &[f64] = &[
+		1.0,
+		-1.0 / 3.0,
+		2.0 / 15.0,
+		-17.0 / 315.0,
+		62.0 / 2835.0,
+		-1382.0 / 155925.0,
+		21844.0 / 6081075.0,
+		-929569.0 / 638512875.0,
+	];
+	let small = L::mul(simd, a, horner::<L, S>(simd, L::mul(simd, a, a), COEFFS));
+
+	let y = L::select(simd, L::less_than(simd, a, L::splat(simd, 0.125)), small, large);
+	L::select(simd, L::less_than(simd, x, L::splat(simd, 0.0)), L::sub(simd, L::splat(simd, 0.0), y), y)
+}
+
+#[inline(always)]
+fn sigmoid_simd<L: Lane, S: Simd>(simd: S, x: L::V<S>) -> L::V<S> {
+	let one = L::splat(simd, 1.0);
+	let t = exp_simd::<L, S>(simd, L::sub(simd, L::splat(simd, 0.0), x));
+	L::div(simd, one, L::add(simd, one, t))
+}
+
+#[inline(always)]
+fn sqrt_simd<L: Lane, S: Simd>(simd: S, x: L::V<S>) -> L::V<S> {
+	let zero = L::splat(simd, 0.0);
+	let half = L::splat(simd, 0.5);
+
+	// scale the subnormal values to the normal range. the scale is an even power of two, so that its
+	// square root is exact
+	let subnormal = L::less_than(simd, x, L::splat(simd, L::MIN_POSITIVE));
+	let x_scaled = L::select(simd, subnormal, L::mul(simd, x, L::splat(simd, L::SUBNORMAL_SCALE)), x);
+	let (e, m) = L::frexp(simd, x_scaled);
+	let e = L::select(simd, subnormal, L::sub(simd, e, L::splat(simd, L::SUBNORMAL_SCALE_LOG2)), e);
+
+	// $x = m' 4^h$, with $h = \lfloor e / 2 \rfloor$ and $m' \in [1, 4)$
+	let (h, pow2) = L::exp2_round(simd, L::mul_add(simd, e, half, L::splat(simd, -0.25)));
+	let odd = L::less_than(simd, L::add(simd, h, h), e);
+	let m = L::select(simd, odd, L::add(simd, m, m), m);
+
+	// newton iterations for $\sqrt{m'}$, starting from a linear approximation with a relative error
+	// below $6\%$
+	let mut y = L::mul_add(simd, m, L::splat(simd, 1.0 / 3.0), L::splat(simd, 2.0 / 3.0));
+	for _ in 0..4 {
+		y = L::mul(simd, half, L::add(simd, y, L::div(simd, m, y)));
+	}
+	// the residual $m' - y^2$ is computed exactly, so that the last correction rounds $y$ correctly
+	let r = L::mul_add_fused(simd, L::sub(simd, zero, y), y, m);
+	let y = L::mul_add_fused(simd, r, L::div(simd, half, y), y);
+	let y = L::mul(simd, y, pow2);
+
+	// zeros (of both signs) and infinity are their own square roots
+	let y = L::select(simd, L::equal(simd, x, zero), x, y);
+	let y = L::select(simd, L::equal(simd, x, L::splat(simd, f64::INFINITY)), x, y);
+	let y = L::select(simd, L::less_than(simd, x, zero), L::splat(simd, f64::NAN), y);
+	L::select(simd, L::equal(simd, x, x), y, x)
+}
+
+#[inline(always)]
+fn apply_simd<L: Lane, S: Simd>(simd: S, func: Func, x: L::V<S>) -> L::V<S> {
+	match func {
+		Func::Exp => exp_simd::<L, S>(simd, x),
+		Func::Ln => ln_simd::<L, S>(simd, x),
+		Func::Abs => L::abs(simd, x),
+		Func::Tanh => tanh_simd::<L, S>(simd, x),
+		Func::Sigmoid => sigmoid_simd::<L, S>(simd, x),
+		Func::Sqrt => sqrt_simd::<L, S>(simd, x),
+	}
+}
+
+#[inline(always)]
+fn apply_slice<T: SimdFloat, S: Simd>(simd: S, func: Func, data: &mut [T]) {
+	let (body, tail) = T::as_mut_simd::<S>(data);
+	for x in body {
+		*x = apply_simd::<T, S>(simd, func, *x);
+	}
+	if !tail.is_empty() {
+		let x = T::partial_load(simd, tail);
+		T::partial_store(simd, tail, apply_simd::<T, S>(simd, func, x));
+	}
+}
+
+fn apply<T: SimdFloat>(mat: MatMut<'_, T>, func: Func) {
+	struct Impl<'a, T> {
+		mat: MatMut<'a, T, usize, usize, ContiguousFwd>,
+		func: Func,
+	}
+
+	impl<T: SimdFloat> pulp::WithSimd for Impl<'_, T> {
+		type Output = ();
+
+		#[inline(always)]
+		fn with_simd<S: Simd>(self, simd: S) -> Self::Output {
+			let Self { mut mat, func } = self;
+			for j in 0..mat.ncols() {
+				apply_slice::<T, S>(simd, func, mat.rb_mut().col_mut(j).as_slice_mut());
+			}
+		}
+	}
+
+	let mut mat = mat;
+	if mat.ncols() > 1 && mat.col_stride().unsigned_abs() == 1 {
+		mat = mat.transpose_mut();
+	}
+	if mat.row_stride() < 0 {
+		mat = mat.reverse_rows_mut();
+	}
+
+	if mat.row_stride() == 1 {
+		let mat = mat.try_as_col_major_mut().unwrap();
+		pulp::Arch::new().dispatch(Impl { mat, func });
+	} else {
+		let simd = pulp::Scalar::new();
+		zip!(mat).for_each(|unzip!(x)| apply_slice::<T, _>(simd, func, core::slice::from_mut(x)));
+	}
+}
+
+macro_rules! elementwise_fn {
+	($($(#[$attr: meta])* $name: ident => $func: ident;)*) => {$(
+		$(#[$attr])*
+		pub fn $name<T: SimdFloat>(mat: impl AsMatMut<T = T>) {
+			let mut mat = mat;
+			apply(mat.as_mat_mut().as_dyn_mut().as_dyn_stride_mut(), Func::$func);
+		}
+	)*};
+}
+
+elementwise_fn! {
+	/// replaces each entry $x$ of `mat` by $e^x$
+	exp => Exp;
+	/// replaces each entry $x$ of `mat` by $\ln(x)$
+	ln => Ln;
+	/// replaces each entry $x$ of `mat` by $\sqrt{x}$
+	sqrt => Sqrt;
+	/// replaces each entry $x$ of `mat` by $|x|$
+	abs => Abs;
+	/// replaces each entry $x$ of `mat` by $\tanh(x)$
+	tanh => Tanh;
+	/// replaces each entry $x$ of `mat` by the logistic sigmoid $1 / (1 + e^{-x})$
+	sigmoid => Sigmoid;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+
+	fn check<T: SimdFloat + Into<f64>>(f: fn(MatMut<'_, T>), reference: fn(f64) -> f64, round: fn(f64) -> f64, inputs: &[T], tol: f64) {
+		let n = inputs.len();
+		let expected = |i: usize| round(reference(inputs[i].into()));
+
+		let check_close = |actual: T, expected: f64| {
+			let actual: f64 = actual.into();
+			if expected.is_nan() {
+				assert!(actual.is_nan());
+			} else if expected.is_infinite() || expected == 0.0 {
+				assert!(actual == expected);
+			} else if expected.abs() < T::MIN_POSITIVE {
+				// subnormal results may be flushed to zero
+				assert!(actual.abs() <= T::MIN_POSITIVE);
+			} else {
+				assert!((actual - expected).abs() <= tol * expected.abs());
+			}
+		};
+
+		// column-major storage, then row-major storage
+		let mut a = Mat::from_fn(n, 3, |i, _| inputs[i]);
+		f(a.as_mut());
+		let mut b = Mat::from_fn(3, n, |_, i| inputs[i]);
+		f(b.as_mut().transpose_mut());
+		// strided entries, processed by the scalar fallback
+		let mut d = Mat::from_fn(2 * n, 3, |i, _| inputs[i / 2]);
+		f(unsafe { MatMut::from_raw_parts_mut(d.as_ptr_mut(), n, 3, 2, d.col_stride()) });
+
+		for i in 0..n {
+			let e = expected(i);
+			for j in 0..2 {
+				check_close(a[(i, j)], e);
+				check_close(b[(j, i)], e);
+				check_close(d[(2 * i, j)], e);
+				check_close(d[(2 * i + 1, j)], inputs[i].into());
+			}
+		}
+	}
+
+	#[test]
+	fn test_elementwise() {
+		let mut inputs = alloc::vec![
+			0.0, 1e-310, 1e-300, 1e-40, 1e-30, 0.01, 0.1, 0.124, 0.126, 0.5, 1.0, 2.0, 10.0, 80.0, 88.0, 700.0, 709.5, 710.0, 1e300,
+		];
+		inputs.extend(inputs.clone().iter().map(|x| -x));
+		inputs.extend([f64::INFINITY, f64::NEG_INFINITY, f64::NAN, f64::MIN_POSITIVE]);
+		inputs.extend((0..200).map(|i| (i as f64 - 100.0) * 0.37));
+		let inputs_f32 = inputs.iter().map(|&x| x as f32).collect::<alloc::vec::Vec<_>>();
+
+		let sigmoid_ref = |x: f64| 1.0 / (1.0 + (-x).exp());
+
+		check::<f64>(|a| exp(a), f64::exp, |x| x, &inputs, 1e-15);
+		check::<f64>(|a| ln(a), f64::ln, |x| x, &inputs, 1e-15);
+		check::<f64>(|a| sqrt(a), f64::sqrt, |x| x, &inputs, 1e-16);
+		check::<f64>(|a| abs(a), f64::abs, |x| x, &inputs, 0.0);
+		check::<f64>(|a| tanh(a), f64::tanh, |x| x, &inputs, 4e-15);
+		check::<f64>(|a| sigmoid(a), sigmoid_ref, |x| x, &inputs, 1e-15);
+
+		check::<f32>(|a| exp(a), f64::exp, |x| x as f32 as f64, &inputs_f32, 1e-6);
+		check::<f32>(|a| ln(a), f64::ln, |x| x as f32 as f64, &inputs_f32, 1e-6);
+		check::<f32>(|a| sqrt(a), f64::sqrt, |x| x as f32 as f64, &inputs_f32, 1e-7);
+		check::<f32>(|a| abs(a), f64::abs, |x| x as f32 as f64, &inputs_f32, 0.0);
+		check::<f32>(|a| tanh(a), f64::tanh, |x| x as f32 as f64, &inputs_f32, 1e-6);
+		check::<f32>(|a| sigmoid(a), sigmoid_ref, |x| x as f32 as f64, &inputs_f32, 1e-6);
+
+		// the square root matches the correctly rounded one over the whole exponent range
+		let inputs = (0..4096)
+			.map(|i| f64::from_bits(0x0000_1234_5678_9abc + i * 0x0007_fedc_ba98_7654))
+			.collect::<alloc::vec::Vec<_>>();
+		check::<f64>(|a| sqrt(a), f64::sqrt, |x| x, &inputs, 0.0);
+		let inputs_f32 = (0..4096)
+			.map(|i| f32::from_bits(0x0012_3456 + i * 0x0007_fedc))
+			.collect::<alloc::vec::Vec<_>>();
+		check::<f32>(|a| sqrt(a), f64::sqrt, |x| x as f32 as f64, &inputs_f32, 0.0);
+
+		// rows and columns
+		let mut x = Col::from_fn(37, |i| i as f64 * 0.5);
+		exp(&mut x);
+		let mut y = Row::from_fn(37, |i| i as f64 * 0.5);
+		exp(y.as_mut());
+		for i in 0..37 {
+			assert!((x[i] - (i as f64 * 0.5).exp()).abs() <= 1e-15 * x[i]);
+			assert!(y[i] == x[i]);
+		}
+	}
+}
@@ -0,0 +1,363 @@
+//! backward and forward error bounds of computed solutions of linear systems
+//!
+//! given a computed solution $x$ of $A x = b$, the componentwise backward error
+//! $$\max_i \frac{|A x - b|_i}{(|A| |x| + |b|)_i}$$
+//! is the smallest relative perturbation of the entries of $A$ and $b$ such that $x$ is the exact
+//! solution of the perturbed system. a value close to the unit roundoff certifies that the solver
+//! is stable for this particular system
+//!
+//! the forward error bound is an estimate of $\|x - x_\star\|_\infty / \|x\|_\infty$, where
+//! $x_\star$ is the exact solution, obtained by bounding $|A^{-1}| (|A x - b| + \varepsilon (|A|
+//! |x| + |b|))$ with a $1$-norm estimate of $A^{-1}$ scaled by the residual
+//!
+//! both quantities are computed for each column of $x$ separately, and match the `berr` and `ferr`
+//! outputs of lapack's `xgerfs`, with the modulus of complex numbers replaced by $|\Re z| + |\Im
+//! z|$ as in lapack. the residuals are computed with the [compensated](super::matmul::compensated)
+//! matrix product, so they're at least as accurate as the ones computed by lapack
+//!
+//! # example
+//! ```
+//! use faer::linalg::error_bounds::{backward_error, forward_error_bound};
+//! use faer::linalg::solvers::Solve;
+//! use faer::{Par, mat};
+//! use rand::prelude::*;
+//!
+//! let A = mat![[4.0, 1.0, 0.0], [1.0, 3.0, 1.0], [0.0, 1.0, 2.0]];
+//! let b = mat![[1.0], [2.0], [3.0]];
+//!
+//! let lu = A.partial_piv_lu();
+//! let x = lu.solve(&b);
+//!
+//! let berr = backward_error(A.as_ref(), x.as_ref(), b.as_ref(), Par::Seq);
+//! let ferr = forward_error_bound(
+//! 	A.as_ref(),
+//! 	&lu,
+//! 	x.as_ref(),
+//! 	b.as_ref(),
+//! 	&mut StdRng::seed_from_u64(0),
+//! 	Par::Seq,
+//! );
+//! assert!(berr[0] < 1e-15);
+//! assert!(ferr[0] < 1e-14);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::linalg::matmul::compensated;
+
+/// returns `b - A * x`, and `|A| * |x| + |b|`, where the modulus of complex numbers is replaced by
+/// the sum of the moduli of their real and imaginary parts
+#[math]
+fn residual_and_scale<T: ComplexField>(A: MatRef<'_, T>, x: MatRef<'_, T>, b: MatRef<'_, T>, par: Par) -> (Mat<T>, Mat<T::Real>) {
+	let (m, n) = A.shape();
+	let k = x.ncols();
+
+	let mut r = b.to_owned();
+	compensated::matmul(r.as_mut(), Accum::Add, A, x, -one::<T>(), par);
+
+	let mut scale = Mat::<T::Real>::zeros(m, k);
+	for j in 0..k {
+		let mut scale = scale.as_mut().col_mut(j);
+		for i in 0..m {
+			scale[i] = abs1(b[(i, j)]);
+		}
+		for p in 0..n {
+			let x = abs1(x[(p, j)]);
+			for i in 0..m {
+				scale[i] = scale[i] + abs1(A[(i, p)]) * x;
+			}
+		}
+	}
+
+	(r, scale)
+}
+
+/// returns the safe minimum used to avoid dividing by zero entries of `|A| * |x| + |b|`, following
+/// lapack's `xgerfs`
+#[math]
+fn safe_min<T: RealField>(n: usize) -> T {
+	from_f64::<T>((n + 1) as f64) * min_positive::<T>()
+}
+
+/// returns the componentwise backward error $\max_i |A x - b|_i / (|A| |x| + |b|)_i$ of each
+/// column of `x`, as a solution of the linear system $A x = b$
+///
+/// see the [module level documentation](self) for more details
+///
+/// # panics
+/// panics if `A.ncols() != x.nrows()`, or if `b` doesn't have the shape of `A * x`
+#[track_caller]
+#[math]
+pub fn backward_error<T: ComplexField>(A: MatRef<'_, T>, x: MatRef<'_, T>, b: MatRef<'_, T>, par: Par) -> Col<T::Real> {
+	assert!(all(A.ncols() == x.nrows(), b.nrows() == A.nrows(), b.ncols() == x.ncols()));
+
+	let (r, scale) = residual_and_scale(A, x, b, par);
+
+	let safe1 = safe_min::<T::Real>(A.ncols());
+	let safe2 = safe1 / eps::<T::Real>();
+
+	Col::from_fn(x.ncols(), |j| {
+		let mut berr = zero::<T::Real>();
+		for i in 0..A.nrows() {
+			let r = abs1(r[(i, j)]);
+			let s = copy(scale[(i, j)]);
+
+			// if the denominator is tiny, the entry is treated as exactly zero, since the residual
+			// is then dominated by the rounding errors of the numerator
+			let e = if s > safe2 { r / s } else { (r + safe1) / (s + safe1) };
+			if e > berr {
+				berr = e;
+			}
+		}
+		berr
+	})
+}
+
+/// linear operator applying $D A^{-H}$, where $D$ is a real diagonal matrix. its $1$-norm is the
+/// $\infty$-norm of $A^{-1} D$
+#[cfg(feature = "rand")]
+struct ScaledInverseAdjoint<'a, T: ComplexField, S: ?Sized> {
+	solver: &'a S,
+	diag: ColRef<'a, T::Real>,
+}
+
+#[cfg(feature = "rand")]
+impl<T: ComplexField, S: ?Sized> core::fmt::Debug for ScaledInverseAdjoint<'_, T, S> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("ScaledInverseAdjoint").field("diag", &self.diag).finish_non_exhaustive()
+	}
+}
+
+#[cfg(feature = "rand")]
+impl<T: ComplexField, S: ?Sized> ScaledInverseAdjoint<'_, T, S> {
+	#[math]
+	fn scale(&self, mat: MatMut<'_, T>) {
+		let mut mat = mat;
+		for j in 0..mat.ncols() {
+			for i in 0..mat.nrows() {
+				mat[(i, j)] = mul_real(mat[(i, j)], self.diag[i]);
+			}
+		}
+	}
+}
+
+#[cfg(feature = "rand")]
+impl<T: ComplexField, S: ?Sized + Sync + crate::linalg::solvers::SolveCore<T>> crate::matrix_free::LinOp<T> for ScaledInverseAdjoint<'_, T, S> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.diag.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.diag.nrows()
+	}
+
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solver.solve_transpose_in_place_with_conj(Conj::Yes, out.rb_mut());
+		self.scale(out);
+	}
+
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solver.solve_transpose_in_place_with_conj(Conj::No, out.rb_mut());
+		self.scale(out);
+	}
+}
+
+#[cfg(feature = "rand")]
+impl<T: ComplexField, S: ?Sized + Sync + crate::linalg::solvers::SolveCore<T>> crate::matrix_free::BiLinOp<T> for ScaledInverseAdjoint<'_, T, S> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.scale(out.rb_mut());
+		self.solver.solve_in_place_with_conj(Conj::Yes, out);
+	}
+
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.scale(out.rb_mut());
+		self.solver.solve_in_place_with_conj(Conj::No, out);
+	}
+}
+
+/// returns an estimated bound on the relative forward error $\|x - x_\star\|_\infty /
+/// \|x\|_\infty$ of each column of `x`, as a solution of the linear system $A x = b$ whose exact
+/// solution is $x_\star$
+///
+/// `solver` is a factorization of $A$, e.g., the one that was used to compute `x`. the bound
+/// relies on a $1$-norm estimate of a scaled inverse of $A$, which is usually exact or within a
+/// small factor of the exact value, so the bound may rarely underestimate the error
+///
+/// see the [module level documentation](self) for more details
+///
+/// # panics
+/// panics if `A` isn't square, if its dimension doesn't match the one of `solver`, if
+/// `A.ncols() != x.nrows()`, or if `b` doesn't have the shape of `A * x`
+#[cfg(feature = "rand")]
+#[track_caller]
+#[math]
+pub fn forward_error_bound<T: ComplexField>(
+	A: MatRef<'_, T>,
+	solver: &(impl ?Sized + Sync + crate::linalg::solvers::SolveCore<T>),
+	x: MatRef<'_, T>,
+	b: MatRef<'_, T>,
+	rng: &mut (impl ?Sized + rand::Rng),
+	par: Par,
+) -> Col<T::Real> {
+	use crate::stats::estimate::{NormL1EstParams, operator_norm_l1_est, operator_norm_l1_est_scratch};
+
+	let n = A.nrows();
+	assert!(all(
+		A.ncols() == n,
+		solver.nrows() == n,
+		solver.ncols() == n,
+		x.nrows() == n,
+		b.nrows() == n,
+		b.ncols() == x.ncols(),
+	));
+
+	let (r, mut scale) = residual_and_scale(A, x, b, par);
+
+	let safe1 = safe_min::<T::Real>(n);
+	let safe2 = safe1 / eps::<T::Real>();
+	// lapack's machine epsilon is the unit roundoff, half of ours
+	let nz_eps = from_f64::<T::Real>((n + 1) as f64) * eps::<T::Real>() * from_f64::<T::Real>(0.5);
+
+	let params = NormL1EstParams::default();
+	let mut out = Col::<T::Real>::zeros(x.ncols());
+
+	for j in 0..x.ncols() {
+		let mut w = scale.as_mut().col_mut(j);
+		for i in 0..n {
+			// the rounding errors of the residual computation are accounted for, as in lapack
+			w[i] = if w[i] > safe2 {
+				abs1(r[(i, j)]) + nz_eps * w[i]
+			} else {
+				abs1(r[(i, j)]) + nz_eps * w[i] + safe1
+			};
+		}
+
+		let op = ScaledInverseAdjoint::<'_, T, _> { solver, diag: w.rb() };
+		let mut mem = dyn_stack::MemBuffer::new(operator_norm_l1_est_scratch(&op, params, par));
+		let est = operator_norm_l1_est(&op, params, rng, par, MemStack::new(&mut mem));
+
+		let mut x_norm = zero::<T::Real>();
+		for i in 0..n {
+			let a = abs1(x[(i, j)]);
+			if a > x_norm {
+				x_norm = a;
+			}
+		}
+
+		out[j] = if x_norm != zero::<T::Real>() { est / x_norm } else { est };
+	}
+
+	out
+}
+
+#[cfg(all(test, feature = "rand"))]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::{DenseSolveCore, Solve};
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_backward_error() {
+		let A = mat![[2.0, 1.0], [1.0, 3.0]];
+		let b = mat![[3.0, 1.0], [4.0, 0.5]];
+		let x = mat![[1.0, 0.5], [1.0 + 1e-3, 0.0]];
+
+		// |Ax - b| = [1e-3, 3e-3], |A||x| + |b| = [6.001, 8.003] for the first column
+		// the second column is exact
+		let berr: Col<f64> = backward_error(A.as_ref(), x.as_ref(), b.as_ref(), Par::Seq);
+		assert!((berr[0] - 3e-3 / 8.003).abs() < 1e-15);
+		assert!(berr[1] == 0.0);
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 40;
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		let A = CwiseMatDistribution { nrows: n, ncols: n, dist }.rand::<Mat<c64>>(rng);
+		let b = CwiseMatDistribution { nrows: n, ncols: 3, dist }.rand::<Mat<c64>>(rng);
+
+		let x = A.partial_piv_lu().solve(&b);
+		let berr = backward_error(A.as_ref(), x.as_ref(), b.as_ref(), Par::Seq);
+		for j in 0..3 {
+			assert!(berr[j] < 1e-14);
+		}
+	}
+
+	#[test]
+	fn test_forward_error_bound() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 30;
+
+		// single precision system with a moderate condition number, so that the error of the
+		// computed solution is measurable
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f32>>(rng);
+		let b = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f32>>(rng);
+
+		let lu = A.partial_piv_lu();
+		let x = lu.solve(&b);
+		let ferr = forward_error_bound(A.as_ref(), &lu, x.as_ref(), b.as_ref(), rng, Par::Seq);
+
+		let x_ref = Mat::from_fn(n, n, |i, j| A[(i, j)] as f64)
+			.partial_piv_lu()
+			.solve(Mat::from_fn(n, 2, |i, j| b[(i, j)] as f64));
+
+		let (r, scale) = residual_and_scale(A.as_ref(), x.as_ref(), b.as_ref(), Par::Seq);
+		let A_inv = lu.inverse();
+		let nz_eps = (n + 1) as f32 * f32::EPSILON / 2.0;
+
+		for j in 0..2 {
+			let x_norm = (0..n).map(|i| x[(i, j)].abs()).fold(0.0, f32::max);
+			let err = (0..n).map(|i| (x[(i, j)] as f64 - x_ref[(i, j)]).abs()).fold(0.0, f64::max) / x_norm as f64;
+
+			// the exact value of the bound, computed with the explicit inverse
+			let exact = (0..n)
+				.map(|i| {
+					(0..n)
+						.map(|k| A_inv[(i, k)].abs() * (r[(k, j)].abs() + nz_eps * scale[(k, j)]))
+						.sum::<f32>()
+				})
+				.fold(0.0, f32::max)
+				/ x_norm;
+
+			assert!(err <= ferr[j] as f64);
+			assert!(ferr[j] <= exact * 1.0001);
+			assert!(ferr[j] >= exact / 3.0);
+			assert!(ferr[j] < 1e-3);
+		}
+	}
+}
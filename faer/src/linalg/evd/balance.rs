@@ -0,0 +1,315 @@
+//! balancing of general matrices before computing their eigenvalues
+//!
+//! the rounding errors committed by the eigenvalue solver are proportional to the norm of the
+//! matrix. when the rows and columns of a matrix have very different norms, balancing it with a
+//! similarity transformation $B = D^{-1} P^\top A P D$ reduces its norm, and can therefore improve
+//! the accuracy of the computed eigenvalues by several orders of magnitude
+//!
+//! - the permutation $P$ moves the rows and columns that isolate an eigenvalue (i.e., that have
+//!   no off-diagonal nonzeros in the remaining part of the matrix) to the bottom right and top left
+//!   corners, so that $B$ is upper triangular outside of the rows and columns in `ilo..ihi`,
+//! - the diagonal scaling $D$, whose entries are powers of two so that no rounding errors are
+//!   introduced, makes the norms of the rows and columns in `ilo..ihi` as close as possible.
+//!
+//! this follows the approach of lapack's `gebal` and `gebak`. balancing is applied automatically
+//! by [`evd_real`](super::evd_real) and [`evd_cplx`](super::evd_cplx), unless disabled in
+//! [`EvdParams`](super::EvdParams)
+//!
+//! # example
+//! ```
+//! use faer::linalg::evd::balance::{self, BalanceJob};
+//! use faer::{Col, Mat, mat};
+//!
+//! let A = mat![[1.0, 1e6, 0.0], [1e-6, 2.0, 0.0], [3.0, 4.0, 5.0]];
+//! let n = A.nrows();
+//!
+//! let mut B = A.clone();
+//! let mut perm = vec![0usize; n];
+//! let mut scale = Col::<f64>::zeros(n);
+//! let info = balance::balance_in_place(B.as_mut(), &mut perm, scale.as_mut(), BalanceJob::Both);
+//!
+//! // the last column isolates the eigenvalue 5.0, which is moved to the top left corner
+//! assert!(info.ilo == 1);
+//! assert!(B[(0, 0)] == 5.0);
+//! assert!(B.norm_l2() < 1e-2 * A.norm_l2());
+//!
+//! // eigenvectors of the balanced matrix are mapped back to eigenvectors of the original one
+//! let mut V = Mat::<f64>::identity(n, n);
+//! balance::unbalance_right_eigenvectors_in_place(V.as_mut(), &perm, scale.as_ref(), info);
+//! assert!((&A * &V - &V * &B).norm_max() < 1e-9);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::perm::{swap_cols_idx, swap_rows_idx};
+
+/// transformations applied by the balancing
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BalanceJob {
+	/// the matrix is left unchanged
+	None,
+	/// only permute the rows and columns that isolate an eigenvalue
+	Permute,
+	/// only scale the rows and columns
+	Scale,
+	/// permute, then scale the rows and columns
+	Both,
+}
+
+/// result of the balancing of a matrix
+///
+/// the rows and columns outside of `ilo..ihi` were isolated by the permutation, and the balanced
+/// matrix is upper triangular outside of `ilo..ihi`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BalanceInfo {
+	/// start of the unreduced part of the matrix
+	pub ilo: usize,
+	/// end of the unreduced part of the matrix
+	pub ihi: usize,
+}
+
+/// swaps the rows and columns `i` and `j` of the leading `l` rows and trailing columns from `k`
+fn swap<T: ComplexField>(A: MatMut<'_, T>, i: usize, j: usize, k: usize, l: usize) {
+	let mut A = A;
+	let n = A.ncols();
+	swap_cols_idx(A.rb_mut().subrows_mut(0, l), i, j);
+	swap_rows_idx(A.rb_mut().subcols_mut(k, n - k), i, j);
+}
+
+/// balances the square matrix `A` in place with a similarity transformation $D^{-1} P^\top A P D$
+///
+/// on exit, for the indices `j` outside of `ilo..ihi`, `perm[j]` contains the index that was
+/// swapped with `j`, and `scale[j]` is one. for the indices inside, `perm[j] == j` and `scale[j]`
+/// contains the $j$-th diagonal entry of $D$
+///
+/// # panics
+///
+/// panics if `A` is not square, or if `perm` or `scale` don't have the same length as the
+/// dimension of `A`
+#[track_caller]
+#[math]
+pub fn balance_in_place<T: ComplexField>(A: MatMut<'_, T>, perm: &mut [usize], scale: ColMut<'_, T::Real>, job: BalanceJob) -> BalanceInfo {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, perm.len() == n, scale.nrows() == n));
+
+	let mut A = A;
+	let mut scale = scale;
+	for j in 0..n {
+		perm[j] = j;
+		scale[j] = one();
+	}
+
+	let mut k = 0;
+	let mut l = n;
+
+	if n == 0 {
+		return BalanceInfo { ilo: 0, ihi: 0 };
+	}
+
+	if job == BalanceJob::Permute || job == BalanceJob::Both {
+		// push the rows isolating an eigenvalue to the bottom
+		while let Some(i) = (0..l).rev().find(|&i| (0..l).all(|j| i == j || A[(i, j)] == zero())) {
+			perm[l - 1] = i;
+			if i != l - 1 {
+				swap(A.rb_mut(), i, l - 1, k, l);
+			}
+
+			if l == 1 {
+				return BalanceInfo { ilo: 0, ihi: 1 };
+			}
+			l -= 1;
+		}
+
+		// push the columns isolating an eigenvalue to the left
+		while let Some(j) = (k..l).find(|&j| (k..l).all(|i| i == j || A[(i, j)] == zero())) {
+			perm[k] = j;
+			if j != k {
+				swap(A.rb_mut(), j, k, k, l);
+			}
+			k += 1;
+		}
+	}
+
+	if job == BalanceJob::Scale || job == BalanceJob::Both {
+		let radix = from_f64::<T::Real>(2.0);
+		let factor = from_f64::<T::Real>(0.95);
+
+		let sfmin1 = min_positive::<T::Real>() / eps::<T::Real>();
+		let sfmax1 = recip(sfmin1);
+		let sfmin2 = sfmin1 * radix;
+		let sfmax2 = recip(sfmin2);
+
+		let mut noconv = true;
+		while noconv {
+			noconv = false;
+
+			for i in k..l {
+				let mut c = A.rb().col(i).get(k..l).norm_l2();
+				let mut r = A.rb().row(i).get(k..l).norm_l2();
+				let mut ca = A.rb().col(i).get(..l).norm_max();
+				let mut ra = A.rb().row(i).get(k..).norm_max();
+
+				// guard against zero norms due to underflow, and non-finite values
+				if c == zero() || r == zero() || !is_finite(c + ca + r + ra) {
+					continue;
+				}
+
+				let mut g = r / radix;
+				let mut f = one::<T::Real>();
+				let s = c + r;
+				while c < g && max(f, max(c, ca)) < sfmax2 && min(r, min(g, ra)) > sfmin2 {
+					f = f * radix;
+					c = c * radix;
+					ca = ca * radix;
+					r = r / radix;
+					g = g / radix;
+					ra = ra / radix;
+				}
+
+				g = c / radix;
+				while g >= r && max(r, ra) < sfmax2 && min(min(f, c), min(g, ca)) > sfmin2 {
+					f = f / radix;
+					c = c / radix;
+					g = g / radix;
+					ca = ca / radix;
+					r = r * radix;
+					ra = ra * radix;
+				}
+
+				if c + r >= factor * s {
+					continue;
+				}
+				if f < one() && scale[i] < one() && f * scale[i] <= sfmin1 {
+					continue;
+				}
+				if f > one() && scale[i] > one() && scale[i] >= sfmax1 / f {
+					continue;
+				}
+
+				let g = recip(f);
+				scale[i] = scale[i] * f;
+				noconv = true;
+
+				for j in k..n {
+					A[(i, j)] = mul_real(A[(i, j)], g);
+				}
+				for j in 0..l {
+					A[(j, i)] = mul_real(A[(j, i)], f);
+				}
+			}
+		}
+	}
+
+	BalanceInfo { ilo: k, ihi: l }
+}
+
+#[math]
+fn unbalance_imp<T: ComplexField>(V: MatMut<'_, T>, perm: &[usize], scale: ColRef<'_, T::Real>, info: BalanceInfo, left: bool) {
+	let n = V.nrows();
+	assert!(all(perm.len() == n, scale.nrows() == n, info.ilo <= info.ihi, info.ihi <= n));
+
+	let mut V = V;
+	let BalanceInfo { ilo, ihi } = info;
+
+	for i in ilo..ihi {
+		let s = if left { recip(scale[i]) } else { copy(scale[i]) };
+		for j in 0..V.ncols() {
+			V[(i, j)] = mul_real(V[(i, j)], s);
+		}
+	}
+
+	// undo the swaps in the reverse order
+	for i in (0..ilo).rev().chain(ihi..n) {
+		swap_rows_idx(V.rb_mut(), i, perm[i]);
+	}
+}
+
+/// transforms the right eigenvectors $V$ of a matrix balanced by [`balance_in_place`] to those of
+/// the original matrix, $P D V$
+///
+/// `perm`, `scale` and `info` must be the outputs of [`balance_in_place`]
+#[track_caller]
+pub fn unbalance_right_eigenvectors_in_place<T: ComplexField>(V: MatMut<'_, T>, perm: &[usize], scale: ColRef<'_, T::Real>, info: BalanceInfo) {
+	unbalance_imp(V, perm, scale, info, false)
+}
+
+/// transforms the left eigenvectors $V$ of a matrix balanced by [`balance_in_place`] to those of
+/// the original matrix, $P D^{-1} V$
+///
+/// `perm`, `scale` and `info` must be the outputs of [`balance_in_place`]
+#[track_caller]
+pub fn unbalance_left_eigenvectors_in_place<T: ComplexField>(V: MatMut<'_, T>, perm: &[usize], scale: ColRef<'_, T::Real>, info: BalanceInfo) {
+	unbalance_imp(V, perm, scale, info, true)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_balance() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 12;
+
+		let mut A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+
+		// badly scaled, with two isolated eigenvalues
+		for i in 0..n {
+			for j in 0..n {
+				A[(i, j)] = A[(i, j)] * 10.0f64.powi(i as i32 - j as i32);
+			}
+		}
+		for j in 0..n {
+			if j != 3 {
+				A[(3, j)] = c64::ZERO;
+			}
+			if j != 7 {
+				A[(j, 7)] = c64::ZERO;
+			}
+		}
+
+		for job in [BalanceJob::None, BalanceJob::Permute, BalanceJob::Scale, BalanceJob::Both] {
+			let mut B = A.clone();
+			let mut perm = vec![0usize; n];
+			let mut scale = Col::<f64>::zeros(n);
+			let info = balance_in_place(B.as_mut(), &mut perm, scale.as_mut(), job);
+
+			match job {
+				BalanceJob::None | BalanceJob::Scale => assert!(info == BalanceInfo { ilo: 0, ihi: n }),
+				BalanceJob::Permute | BalanceJob::Both => assert!(info == BalanceInfo { ilo: 1, ihi: n - 1 }),
+			}
+			if job == BalanceJob::Both {
+				assert!(B.norm_l2() < 1e-3 * A.norm_l2());
+			}
+			for j in 0..info.ilo {
+				for i in j + 1..n {
+					assert!(B[(i, j)] == c64::ZERO);
+				}
+			}
+			for i in info.ihi..n {
+				for j in 0..i {
+					assert!(B[(i, j)] == c64::ZERO);
+				}
+			}
+
+			// A X = X B, with X = P D
+			let mut X = Mat::<c64>::identity(n, n);
+			unbalance_right_eigenvectors_in_place(X.as_mut(), &perm, scale.as_ref(), info);
+			let approx_eq = CwiseMat(ApproxEq::eps() * 64.0 * A.norm_l2());
+			assert!(&A * &X ~ &X * &B);
+
+			// Y^H A = B Y^H, with Y = P D^{-1}
+			let mut Y = Mat::<c64>::identity(n, n);
+			unbalance_left_eigenvectors_in_place(Y.as_mut(), &perm, scale.as_ref(), info);
+			assert!(&X * Y.adjoint() ~ Mat::<c64>::identity(n, n));
+		}
+	}
+}
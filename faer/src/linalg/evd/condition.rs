@@ -0,0 +1,233 @@
+//! condition numbers of individual eigenvalues and eigenvectors, computed from an
+//! eigendecomposition $A = X \Lambda X^{-1}$
+//!
+//! the rows of $Y^H = X^{-1}$ are the left eigenvectors of $A$, normalized so that $y_i^H x_i =
+//! 1$. for a perturbation $E$ of $A$, to first order:
+//!
+//! - the eigenvalue $\lambda_i$ moves by at most $\kappa(\lambda_i) \|E\|_2$, where
+//!   $\kappa(\lambda_i) = \|x_i\| \|y_i\|$,
+//! - the eigenvector $x_i$, normalized so that $y_i^H x_i = 1$ holds before and after the
+//!   perturbation, moves by at most $\kappa(x_i) \|x_i\| \|E\|_2$, where $\kappa(x_i) = \sum_{j
+//!   \neq i} \kappa(\lambda_j) / |\lambda_i - \lambda_j|$
+//!
+//! for self-adjoint matrices, all the eigenvalues have a condition number equal to $1$, and the
+//! angle between the exact and perturbed eigenvectors is bounded by $\|E\|_2 / \text{gap}_i$, where
+//! $\text{gap}_i$ is the distance from $\lambda_i$ to the closest other eigenvalue
+//!
+//! when the schur form of the matrix is available, the functions of
+//! [`schur_reorder`](super::schur_reorder) avoid computing the inverse of the eigenvectors, and
+//! give a sharper bound for the eigenvectors
+//!
+//! # example
+//! ```
+//! use faer::linalg::evd::condition;
+//! use faer::{Col, mat};
+//!
+//! let A = mat![[1.0, 100.0], [0.0, 1.01]];
+//! let evd = A.eigen().unwrap();
+//!
+//! let mut cond = Col::<f64>::zeros(2);
+//! condition::eigenvalue_condition_numbers(evd.U(), cond.as_mut());
+//!
+//! // the eigenvalues are close to each other and the matrix is far from normal, so they're very
+//! // sensitive to perturbations
+//! assert!(cond[0] > 1e3);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::linalg::solvers::DenseSolveCore;
+
+/// computes the condition numbers $\kappa(\lambda_i) = \|x_i\| \|y_i\|$ of the eigenvalues of a
+/// diagonalizable matrix from its eigenvectors `U`, and stores them in `cond`
+///
+/// this requires computing the inverse of `U`. the condition numbers are infinite, up to rounding
+/// errors, if `U` is singular, i.e., if the matrix isn't diagonalizable
+///
+/// see the [module level documentation](self) for more details
+///
+/// # panics
+///
+/// panics if `U` is not square, or if `cond` doesn't have as many rows as `U`
+#[track_caller]
+#[math]
+pub fn eigenvalue_condition_numbers<T: ComplexField>(U: MatRef<'_, T>, cond: ColMut<'_, T::Real>) {
+	let n = U.nrows();
+	assert!(all(U.ncols() == n, cond.nrows() == n));
+
+	let mut cond = cond;
+	if n == 0 {
+		return;
+	}
+
+	let Y = U.partial_piv_lu().inverse();
+	for i in 0..n {
+		cond[i] = U.col(i).norm_l2() * Y.row(i).norm_l2();
+	}
+}
+
+/// computes the condition numbers $\kappa(x_i) = \sum_{j \neq i} \kappa(\lambda_j) / |\lambda_i -
+/// \lambda_j|$ of the eigenvectors of a diagonalizable matrix from its eigenvectors `U` and
+/// eigenvalues `S`, and stores them in `cond`
+///
+/// this requires computing the inverse of `U`. the condition number of an eigenvector is infinite
+/// if its eigenvalue is multiple
+///
+/// see the [module level documentation](self) for more details
+///
+/// # panics
+///
+/// panics if `U` is not square, or if `S` and `cond` don't have as many rows as `U`
+#[track_caller]
+#[math]
+pub fn eigenvector_condition_numbers<T: ComplexField>(U: MatRef<'_, T>, S: ColRef<'_, T>, cond: ColMut<'_, T::Real>) {
+	let n = U.nrows();
+	assert!(all(U.ncols() == n, S.nrows() == n, cond.nrows() == n));
+
+	let mut cond = cond;
+	let mut kappa = Col::<T::Real>::zeros(n);
+	eigenvalue_condition_numbers(U, kappa.as_mut());
+
+	for i in 0..n {
+		let mut acc = zero::<T::Real>();
+		for j in 0..n {
+			if j != i {
+				acc = acc + kappa[j] / abs(S[i] - S[j]);
+			}
+		}
+		cond[i] = acc;
+	}
+}
+
+/// computes the condition numbers $1 / \text{gap}_i$ of the eigenvectors of a self-adjoint matrix
+/// from its eigenvalues `S`, and stores them in `cond`
+///
+/// $\text{gap}_i$ is the distance from the $i$-th eigenvalue to the closest other one. the sine of
+/// the angle between the exact and perturbed eigenvectors is bounded by $\|E\|_2 / \text{gap}_i$
+/// for a perturbation $E$, to first order
+///
+/// # panics
+///
+/// panics if `S` and `cond` don't have the same number of rows
+#[track_caller]
+#[math]
+pub fn self_adjoint_eigenvector_condition_numbers<T: ComplexField>(S: ColRef<'_, T>, cond: ColMut<'_, T::Real>) {
+	let n = S.nrows();
+	assert!(cond.nrows() == n);
+
+	let mut cond = cond;
+	for i in 0..n {
+		let mut gap = infinity::<T::Real>();
+		for j in 0..n {
+			if j != i {
+				let d = abs(real(S[i]) - real(S[j]));
+				if d < gap {
+					gap = d;
+				}
+			}
+		}
+		cond[i] = recip(gap);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{Scale, assert, c64};
+
+	#[test]
+	fn test_eigen_condition_numbers() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 8;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let E = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let E = &E * Scale(1e-9 / E.norm_l2());
+		let E_norm = E.singular_values().unwrap()[0];
+
+		let evd = A.eigen().unwrap();
+		let (U, S) = (evd.U(), evd.S().column_vector());
+		let Y = U.partial_piv_lu().inverse();
+
+		let mut cond_val = Col::<f64>::zeros(n);
+		let mut cond_vec = Col::<f64>::zeros(n);
+		eigenvalue_condition_numbers(U, cond_val.as_mut());
+		eigenvector_condition_numbers(U, S, cond_vec.as_mut());
+
+		let evd2 = (&A + &E).eigen().unwrap();
+		let (U2, S2) = (evd2.U(), evd2.S().column_vector());
+
+		for i in 0..n {
+			assert!(cond_val[i] >= 1.0 - 1e-12);
+
+			let k = (0..n).min_by(|&a, &b| (S2[a] - S[i]).norm().total_cmp(&(S2[b] - S[i]).norm())).unwrap();
+			assert!((S2[k] - S[i]).norm() <= 1.01 * cond_val[i] * E_norm);
+
+			// normalize the perturbed eigenvector so that y^H x = 1
+			let x = U2.col(k);
+			let scale = (Y.row(i) * x).inv();
+			let dx = Col::<c64>::from_fn(n, |p| x[p] * scale - U[(p, i)]);
+			assert!(dx.norm_l2() <= 1.01 * cond_vec[i] * U.col(i).norm_l2() * E_norm);
+		}
+
+		// the eigenvalues of a normal matrix are perfectly conditioned
+		let Q = A.qr().compute_Q();
+		let evd = Q.eigen().unwrap();
+		eigenvalue_condition_numbers(evd.U(), cond_val.as_mut());
+		for i in 0..n {
+			assert!((cond_val[i] - 1.0).abs() < 1e-10);
+		}
+	}
+
+	#[test]
+	fn test_self_adjoint_condition_numbers() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 8;
+
+		let B = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let A = &B + B.adjoint();
+		let F = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let E = (&F + F.adjoint()) * Scale(c64::new(1e-9 / F.norm_l2(), 0.0));
+		let E_norm = E.singular_values().unwrap()[0];
+
+		let evd = A.self_adjoint_eigen(Side::Lower).unwrap();
+		let evd2 = (&A + &E).self_adjoint_eigen(Side::Lower).unwrap();
+
+		let mut cond = Col::<f64>::zeros(n);
+		self_adjoint_eigenvector_condition_numbers(evd.S().column_vector(), cond.as_mut());
+
+		for i in 0..n {
+			// the eigenvalues are sorted, so they can be matched by index
+			let (x, x2) = (evd.U().col(i), evd2.U().col(i));
+			let sin = (x2 - x * Scale(x.adjoint() * x2)).norm_l2();
+			assert!(sin <= 1.01 * cond[i] * E_norm + 1e-14);
+		}
+
+		let S = Col::<f64>::from_fn(3, |i| [1.0, 1.5, 4.0][i]);
+		self_adjoint_eigenvector_condition_numbers(S.as_ref(), cond.get_mut(..3));
+		assert!(cond[0] == 2.0);
+		assert!(cond[1] == 2.0);
+		assert!(cond[2] == 1.0 / 2.5);
+	}
+}
@@ -1,6 +1,7 @@
 use crate::assert;
 use crate::internal_prelude::*;
 use linalg::householder::{self, HouseholderInfo};
+use linalg::jacobi::JacobiRotation;
 use linalg::matmul::triangular::BlockStructure;
 use linalg::matmul::{self, dot, matmul};
 use linalg::triangular_solve;
@@ -688,6 +689,86 @@ fn hessenberg_gqvdg_blocked<T: ComplexField>(A: MatMut<'_, T>, H: MatMut<'_, T>,
 	}
 }
 
+/// computes the size and alignment of the workspace required to solve a shifted hessenberg system
+/// with [`shifted_hessenberg_solve_in_place`]
+pub fn shifted_hessenberg_solve_in_place_scratch<T: ComplexField>(dim: usize) -> StackReq {
+	temp_mat_scratch::<T>(dim, dim)
+}
+
+/// computes the size and alignment of the workspace required to solve shifted hessenberg systems
+/// with [`multi_shift_hessenberg_solve_in_place`]
+pub fn multi_shift_hessenberg_solve_in_place_scratch<T: ComplexField>(dim: usize) -> StackReq {
+	temp_mat_scratch::<T>(dim, dim)
+}
+
+/// reduces $H - \sigma I$ to upper triangular form in `R` with givens rotations, applies the same
+/// rotations to `rhs`, then solves the triangular system
+fn shifted_hessenberg_solve_imp<T: ComplexField>(R: MatMut<'_, T>, H: MatRef<'_, T>, shift: &T, rhs: MatMut<'_, T>, par: Par) {
+	let mut R = R;
+	let mut rhs = rhs;
+	let n = H.nrows();
+
+	for j in 0..n {
+		for i in 0..Ord::min(j + 2, n) {
+			R[(i, j)] = if i == j { sub(&H[(i, j)], shift) } else { copy(&H[(i, j)]) };
+		}
+	}
+
+	for k in 0..n.saturating_sub(1) {
+		let (rot, _) = JacobiRotation::rotg(copy(&R[(k, k)]), copy(&R[(k + 1, k)]));
+		let rot = rot.adjoint();
+		rot.apply_on_the_left_in_place(R.rb_mut().subcols_mut(k, n - k).two_rows_mut(k, k + 1));
+		R[(k + 1, k)] = zero();
+		rot.apply_on_the_left_in_place(rhs.rb_mut().two_rows_mut(k, k + 1));
+	}
+
+	triangular_solve::solve_upper_triangular_in_place(R.rb(), rhs, par);
+}
+
+/// solves $(H - \sigma I) X = B$ for $X$, where $H$ is an upper hessenberg matrix and $\sigma$ is a
+/// scalar shift, and stores the result in `rhs`
+///
+/// only the upper triangular half of $H$ and its subdiagonal are accessed. the shifted matrix is
+/// reduced to triangular form with $n - 1$ givens rotations, which costs $O(n^2)$ operations instead
+/// of the $O(n^3)$ of a general factorization
+///
+/// if $H - \sigma I$ is singular, the solution contains non-finite values
+#[track_caller]
+pub fn shifted_hessenberg_solve_in_place<T: ComplexField>(H: MatRef<'_, T>, shift: T, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+	let n = H.nrows();
+	assert!(all(H.ncols() == n, rhs.nrows() == n));
+
+	let (mut R, _) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	shifted_hessenberg_solve_imp(R.as_mat_mut(), H, &shift, rhs, par);
+}
+
+/// solves $(H - \sigma_j I) x_j = b_j$ for each $j$, where $H$ is an upper hessenberg matrix, the
+/// shifts $\sigma_j$ are the entries of `shifts`, and $b_j$ is the $j$-th column of `rhs`. the
+/// solutions are stored in the columns of `rhs`
+///
+/// this is the kernel needed by shift-and-invert and rational krylov methods, where the same
+/// hessenberg matrix is solved against many shifts. each shift costs $O(n^2)$ operations
+///
+/// only the upper triangular half of $H$ and its subdiagonal are accessed
+#[track_caller]
+pub fn multi_shift_hessenberg_solve_in_place<T: ComplexField>(
+	H: MatRef<'_, T>,
+	shifts: ColRef<'_, T>,
+	rhs: MatMut<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+) {
+	let n = H.nrows();
+	assert!(all(H.ncols() == n, rhs.nrows() == n, shifts.nrows() == rhs.ncols()));
+
+	let mut rhs = rhs;
+	let (mut R, _) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut R = R.as_mat_mut();
+	for j in 0..rhs.ncols() {
+		shifted_hessenberg_solve_imp(R.rb_mut(), H, &shifts[j], rhs.rb_mut().subcols_mut(j, 1), par);
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use dyn_stack::MemBuffer;
@@ -896,4 +977,73 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn test_shifted_hessenberg_solve() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 5, 40] {
+			let mut H = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let B = CwiseMatDistribution {
+				nrows: n,
+				ncols: 3,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+
+			// entries below the subdiagonal are ignored
+			let mut Hu = H.clone();
+			for j in 0..n {
+				for i in j + 2..n {
+					H[(i, j)] = c64::new(f64::NAN, 0.0);
+					Hu[(i, j)] = c64::ZERO;
+				}
+			}
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * (n as f64 + 1.0) * 64.0);
+			let shift = c64::new(0.5, -1.0);
+			let mut X = B.clone();
+			shifted_hessenberg_solve_in_place(
+				H.as_ref(),
+				shift,
+				X.as_mut(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(shifted_hessenberg_solve_in_place_scratch::<c64>(n))),
+			);
+			let shifted = &Hu - Mat::<c64>::identity(n, n) * Scale(shift);
+			assert!(&shifted * &X ~ B);
+
+			let shifts = Col::from_fn(3, |j| c64::new(j as f64, 1.0 - j as f64));
+			let mut X = B.clone();
+			multi_shift_hessenberg_solve_in_place(
+				H.as_ref(),
+				shifts.as_ref(),
+				X.as_mut(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(multi_shift_hessenberg_solve_in_place_scratch::<c64>(n))),
+			);
+			for j in 0..3 {
+				let shifted = &Hu - Mat::<c64>::identity(n, n) * Scale(shifts[j]);
+				assert!(&shifted * X.col(j) ~ B.col(j));
+			}
+		}
+
+		let H = crate::mat![[1.0, 2.0, 0.5], [3.0, -1.0, 4.0], [0.0, 2.0, 1.0]];
+		let b = crate::mat![[1.0], [0.0], [-2.0]];
+		let mut x = b.clone();
+		shifted_hessenberg_solve_in_place(
+			H.as_ref(),
+			0.25,
+			x.as_mut(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(shifted_hessenberg_solve_in_place_scratch::<f64>(3))),
+		);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 16.0);
+		assert!((&H - Mat::<f64>::identity(3, 3) * Scale(0.25)) * &x ~ b);
+	}
 }
@@ -0,0 +1,290 @@
+//! cyclic jacobi eigenvalue algorithm
+//!
+//! the jacobi algorithm diagonalizes a self-adjoint matrix by applying a sequence of plane
+//! rotations, each of which annihilates one pair of off-diagonal entries. the rotations are applied
+//! in cyclic row order, and a full pass over the strictly lower triangular half is called a sweep.
+//! the convergence is quadratic, and a few sweeps are usually enough
+//!
+//! it is slower than the tridiagonalization based algorithm used by
+//! [`self_adjoint_evd`](super::self_adjoint_evd), but a pair of entries is only annihilated when
+//! $|a_{ij}| > \varepsilon \sqrt{|a_{ii} a_{jj}|}$. for positive definite matrices, and more
+//! generally for scaled diagonally dominant ones, the eigenvalues are then computed to high
+//! relative accuracy, even when they differ by many orders of magnitude. this makes it well suited
+//! to small matrices, up to a few hundred rows, when accuracy matters more than speed
+//!
+//! # example
+//! ```
+//! use faer::dyn_stack::{MemBuffer, MemStack};
+//! use faer::linalg::evd::jacobi_evd::{self, JacobiEvdParams};
+//! use faer::linalg::evd::ComputeEigenvectors;
+//! use faer::diag::Diag;
+//! use faer::{Mat, mat};
+//!
+//! // graded positive definite matrix
+//! let A = mat![[1.0, 1e-10, 0.0], [1e-10, 1e-18, 1e-28], [0.0, 1e-28, 1e-36]];
+//!
+//! let mut s = Diag::<f64>::zeros(3);
+//! let mut U = Mat::<f64>::zeros(3, 3);
+//! let params = JacobiEvdParams::default();
+//! jacobi_evd::self_adjoint_evd_jacobi(
+//!     A.as_ref(),
+//!     s.as_mut(),
+//!     Some(U.as_mut()),
+//!     MemStack::new(&mut MemBuffer::new(jacobi_evd::self_adjoint_evd_jacobi_scratch::<f64>(3, ComputeEigenvectors::Yes))),
+//!     params,
+//! )
+//! .unwrap();
+//!
+//! // the smallest eigenvalue is computed to high relative accuracy
+//! let s = s.column_vector();
+//! assert!(s[0] > 0.0);
+//! assert!((&A * &U - &U * s.as_diagonal()).norm_max() < 1e-15);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::perm::swap_cols_idx;
+use linalg::evd::{ComputeEigenvectors, EvdError};
+use linalg::jacobi::JacobiRotation;
+
+/// jacobi eigenvalue algorithm parameters
+#[derive(Copy, Clone, Debug)]
+pub struct JacobiEvdParams {
+	/// maximum number of sweeps before the algorithm gives up and reports
+	/// [`EvdError::NoConvergence`]
+	pub max_sweeps: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for JacobiEvdParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			max_sweeps: 64,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// computes the size and alignment of the workspace required to compute a self-adjoint matrix's
+/// eigendecomposition with [`self_adjoint_evd_jacobi`]
+pub fn self_adjoint_evd_jacobi_scratch<T: ComplexField>(dim: usize, compute_u: ComputeEigenvectors) -> StackReq {
+	_ = compute_u;
+	temp_mat_scratch::<T>(dim, dim)
+}
+
+/// returns the rotation $J$ such that $J^H \begin{bmatrix} a & \bar b \\\\ b & d \end{bmatrix} J$
+/// is diagonal, as well as the diagonal entries of the result
+#[math]
+fn rotation<T: ComplexField>(a: T::Real, b: T, d: T::Real) -> (JacobiRotation<T>, T::Real, T::Real) {
+	let abs_b = abs(b);
+	let phase = mul_real(b, recip(abs_b));
+
+	let tau = (d - a) * recip(abs_b + abs_b);
+	let t = recip(abs(tau) + hypot(one(), tau));
+	let t = if tau >= zero() { -t } else { t };
+	let c = recip(hypot(one(), t));
+	let s = t * c;
+
+	(
+		JacobiRotation {
+			c: from_real(c),
+			s: mul_real(phase, s),
+		},
+		a + t * abs_b,
+		d - t * abs_b,
+	)
+}
+
+/// computes the matrix $A$'s eigendecomposition with the cyclic jacobi algorithm, assuming it is
+/// self-adjoint
+///
+/// the eigenvalues are stored in $S$, and the eigenvectors in $U$ such that the eigenvalues are
+/// sorted in nondecreasing order
+///
+/// only the lower triangular half of $A$ is accessed
+#[track_caller]
+#[math]
+pub fn self_adjoint_evd_jacobi<T: ComplexField>(
+	A: MatRef<'_, T>,
+	s: DiagMut<'_, T>,
+	u: Option<MatMut<'_, T>>,
+	stack: &mut MemStack,
+	params: JacobiEvdParams,
+) -> Result<(), EvdError> {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, s.dim() == n));
+	if let Some(u) = u.rb() {
+		assert!(all(u.nrows() == n, u.ncols() == n));
+	}
+
+	let mut s = s.column_vector_mut();
+	let mut u = u;
+
+	let (mut W, _) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut W = W.as_mat_mut();
+	for j in 0..n {
+		W[(j, j)] = from_real(real(A[(j, j)]));
+		for i in j + 1..n {
+			W[(i, j)] = copy(A[(i, j)]);
+			W[(j, i)] = conj(A[(i, j)]);
+		}
+	}
+	if let Some(u) = u.rb_mut() {
+		let mut u = u;
+		u.fill(zero());
+		u.rb_mut().diagonal_mut().fill(one());
+	}
+
+	let eps = eps::<T::Real>();
+	let sml = min_positive::<T::Real>();
+
+	let mut converged = false;
+	for _ in 0..params.max_sweeps {
+		let mut rotated = false;
+		for p in 0..n {
+			for q in p + 1..n {
+				let a = real(W[(p, p)]);
+				let d = real(W[(q, q)]);
+				let b = copy(W[(q, p)]);
+				let abs_b = abs(b);
+
+				if abs_b <= sml || abs_b <= eps * sqrt(abs(a) * abs(d)) {
+					continue;
+				}
+				rotated = true;
+
+				// W <- J^H W J
+				let (rot, a, d) = rotation::<T>(a, b, d);
+				rot.conjugate().apply_on_the_right_in_place(W.rb_mut().two_cols_mut(p, q));
+				rot.transpose().apply_on_the_left_in_place(W.rb_mut().two_rows_mut(p, q));
+				W[(p, p)] = from_real(a);
+				W[(q, q)] = from_real(d);
+				W[(p, q)] = zero();
+				W[(q, p)] = zero();
+
+				if let Some(u) = u.rb_mut() {
+					rot.conjugate().apply_on_the_right_in_place(u.two_cols_mut(p, q));
+				}
+			}
+		}
+		if !rotated {
+			converged = true;
+			break;
+		}
+	}
+
+	// sort the eigenvalues in nondecreasing order
+	for j in 0..n {
+		s[j] = from_real(real(W[(j, j)]));
+	}
+	for j in 0..n {
+		let mut imin = j;
+		for i in j + 1..n {
+			if real(s[i]) < real(s[imin]) {
+				imin = i;
+			}
+		}
+		if imin != j {
+			let tmp = copy(s[j]);
+			s[j] = copy(s[imin]);
+			s[imin] = tmp;
+			if let Some(u) = u.rb_mut() {
+				swap_cols_idx(u, j, imin);
+			}
+		}
+	}
+
+	if converged { Ok(()) } else { Err(EvdError::NoConvergence) }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	fn jacobi<T: ComplexField>(A: MatRef<'_, T>) -> (Diag<T>, Mat<T>) {
+		let n = A.nrows();
+		let mut s = Diag::zeros(n);
+		let mut U = Mat::zeros(n, n);
+		self_adjoint_evd_jacobi(
+			A,
+			s.as_mut(),
+			Some(U.as_mut()),
+			MemStack::new(&mut MemBuffer::new(self_adjoint_evd_jacobi_scratch::<T>(n, ComputeEigenvectors::Yes))),
+			Default::default(),
+		)
+		.unwrap();
+		(s, U)
+	}
+
+	#[test]
+	fn test_jacobi_evd() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 10, 33] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let A = &A + A.adjoint();
+
+			let (s, U) = jacobi(A.as_ref());
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n as f64 + 1.0));
+			assert!(&A * &U ~ &U * &s);
+			assert!(U.adjoint() * &U ~ Mat::<c64>::identity(n, n));
+
+			let exact = A.self_adjoint_eigenvalues(Side::Lower).unwrap();
+			for i in 0..n {
+				assert!(s[i].im == 0.0);
+				assert!((s[i].re - exact[i]).abs() < 1e-12 * (n as f64));
+			}
+		}
+
+		let A = CwiseMatDistribution {
+			nrows: 20,
+			ncols: 20,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let A = &A + A.transpose();
+		let (s, U) = jacobi(A.as_ref());
+		let approx_eq = CwiseMat(ApproxEq::eps() * 4096.0);
+		assert!(&A * &U ~ &U * &s);
+		assert!(U.transpose() * &U ~ Mat::<f64>::identity(20, 20));
+	}
+
+	#[test]
+	fn test_jacobi_evd_relative_accuracy() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 6;
+
+		// A = D H D, with H well conditioned and D badly graded
+		let X = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let H = X.transpose() * &X + Mat::<f64>::identity(n, n) * Scale(n as f64);
+		let d = Col::from_fn(n, |i| 2.0f64.powi(-8 * i as i32));
+		let A = Mat::from_fn(n, n, |i, j| d[i] * H[(i, j)] * d[j]);
+
+		// the largest eigenvalue of the inverse is computed accurately by any backward stable method,
+		// and D^{-1} is applied exactly since its entries are powers of two
+		let Hinv = linalg::solvers::DenseSolveCore::inverse(&H.llt(Side::Lower).unwrap());
+		let Ainv = Mat::from_fn(n, n, |i, j| Hinv[(i, j)] / (d[i] * d[j]));
+		let max_inv = Ainv.self_adjoint_eigenvalues(Side::Lower).unwrap()[n - 1];
+
+		let (s, _) = jacobi(A.as_ref());
+		let min = s[0];
+		assert!(((min * max_inv) - 1.0).abs() < 1e-12);
+	}
+}
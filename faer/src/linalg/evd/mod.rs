@@ -13,15 +13,32 @@
 
 /// hessenberg decomposition
 pub mod hessenberg;
+
+/// balancing of general matrices
+pub mod balance;
+
 #[doc(hidden)]
 pub mod schur;
 
+/// reordering of complex schur forms and eigenvalue condition numbers
+pub mod schur_reorder;
+
+/// condition numbers of individual eigenvalues and eigenvectors
+pub mod condition;
+
 /// self-adjoint tridiagonalization
 pub mod tridiag;
 pub(crate) mod tridiag_evd;
 
+/// selected eigenvalues and eigenvectors of self-adjoint matrices
+pub mod selected;
+
+/// jacobi eigenvalue algorithm
+pub mod jacobi_evd;
+
 use crate::assert;
 use crate::internal_prelude::*;
+use balance::BalanceJob;
 use hessenberg::HessenbergParams;
 use linalg::matmul::triangular::BlockStructure;
 use schur::SchurParams;
@@ -52,6 +69,8 @@ pub struct EvdParams {
 	pub schur: SchurParams,
 	/// eigendecomposition from schur conversion parameters
 	pub evd_from_schur: EvdFromSchurParams,
+	/// balancing applied to the matrix before the hessenberg reduction
+	pub balance: BalanceJob,
 
 	#[doc(hidden)]
 	pub non_exhaustive: NonExhaustive,
@@ -75,6 +94,7 @@ impl<T: ComplexField> Auto<T> for EvdParams {
 			hessenberg: auto!(T),
 			schur: auto!(T),
 			evd_from_schur: auto!(T),
+			balance: BalanceJob::Both,
 			non_exhaustive: NonExhaustive(()),
 		}
 	}
@@ -169,6 +189,7 @@ pub fn self_adjoint_evd<T: ComplexField>(
 	if n == 0 {
 		return Ok(());
 	}
+	crate::utils::trace::span!(DEBUG, "self_adjoint_evd", n = n, compute_u = u.is_some());
 
 	#[cfg(feature = "perf-warn")]
 	if let Some(matrix) = u.rb() {
@@ -833,6 +854,8 @@ pub fn evd_scratch<T: ComplexField>(
 	StackReq::all_of(&[
 		H,
 		Z,
+		StackReq::new::<usize>(n),
+		temp_mat_scratch::<T::Real>(n, 1),
 		StackReq::any_of(&[
 			householder.and(hessenberg::hessenberg_in_place_scratch::<T>(n, bs, par, params.hessenberg.into()).or(apply)),
 			schur::multishift_qr_scratch::<T>(n, n, compute_eigen, compute_eigen, par, params.schur),
@@ -857,6 +880,13 @@ fn evd_imp<T: ComplexField>(
 	if n == 0 {
 		return Ok(());
 	}
+	crate::utils::trace::span!(
+		DEBUG,
+		"evd",
+		n = n,
+		compute_u_left = u_left.is_some(),
+		compute_u_right = u_right.is_some()
+	);
 
 	for j in 0..n {
 		for i in 0..n {
@@ -882,6 +912,13 @@ fn evd_imp<T: ComplexField>(
 
 	H.copy_from(A);
 
+	let (mut perm, stack) = stack.make_with(n, |_| 0usize);
+	let (mut scale, stack) = unsafe { temp_mat_uninit::<T::Real, _, _>(n, 1, stack) };
+	let mut scale = scale.as_mat_mut().col_mut(0);
+	let info = balance::balance_in_place(H.rb_mut(), &mut perm, scale.rb_mut(), params.balance);
+	let perm = &*perm;
+	let scale = scale.rb();
+
 	{
 		let (mut householder, stack) = unsafe { temp_mat_uninit::<T, _, _>(bs, n - 1, stack) };
 		let mut householder = householder.as_mat_mut();
@@ -954,6 +991,8 @@ fn evd_imp<T: ComplexField>(
 			one(),
 			par,
 		);
+
+		balance::unbalance_right_eigenvectors_in_place(u, perm, scale, info);
 	}
 
 	if let (Some(mut u), Some(Z)) = (u_left, Z.rb()) {
@@ -982,6 +1021,8 @@ fn evd_imp<T: ComplexField>(
 			one(),
 			par,
 		);
+
+		balance::unbalance_left_eigenvectors_in_place(u, perm, scale, info);
 	}
 
 	Ok(())
@@ -1205,6 +1246,57 @@ mod general_tests {
 			test_real_evd(mat.as_ref());
 		}
 	}
+
+	#[test]
+	fn test_badly_scaled() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 8;
+
+		// A = D (V S V^{-1}) D^{-1}, whose entries span many orders of magnitude
+		let V = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let S = Mat::<f64>::from_fn(n, n, |i, j| if i == j { (i + 1) as f64 } else { 0.0 });
+		let M = &V * &S * crate::linalg::solvers::DenseSolveCore::inverse(&V.partial_piv_lu());
+		let A = Mat::<f64>::from_fn(n, n, |i, j| M[(i, j)] * 8.0f64.powi(3 * (i as i32 - j as i32)));
+
+		let params: Spec<EvdParams, f64> = default();
+
+		let mut s_re = Diag::zeros(n);
+		let mut s_im = Diag::zeros(n);
+		let mut ur = Mat::zeros(n, n);
+		evd_real(
+			A.as_ref(),
+			s_re.as_mut(),
+			s_im.as_mut(),
+			None,
+			Some(ur.as_mut()),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(evd_scratch::<f64>(
+				n,
+				ComputeEigenvectors::No,
+				ComputeEigenvectors::Yes,
+				Par::Seq,
+				params,
+			))),
+			params,
+		)
+		.unwrap();
+
+		let mut eigs = s_re.column_vector().iter().copied().collect::<Vec<_>>();
+		eigs.sort_by(f64::total_cmp);
+		for i in 0..n {
+			assert!(s_im[i] == 0.0);
+			assert!((eigs[i] - (i + 1) as f64).abs() < 1e-8);
+
+			let u = ur.col(i);
+			let su = u * Scale(s_re[i]);
+			assert!((&A * u - &su).norm_max() < 1e-10 * su.norm_max());
+		}
+	}
 }
 
 #[cfg(test)]
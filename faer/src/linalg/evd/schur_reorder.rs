@@ -0,0 +1,310 @@
+//! reordering of complex schur forms and eigenvalue condition numbers
+//!
+//! given a schur decomposition $A = Q T Q^H$, with $T$ upper triangular and $Q$ unitary, the
+//! eigenvalues of $A$ appear on the diagonal of $T$ in an arbitrary order. swapping two adjacent
+//! diagonal entries can be done with a single plane rotation, which allows moving any subset of the
+//! eigenvalues to the top left corner of $T$. the leading columns of the updated $Q$ then span the
+//! invariant subspace associated with these eigenvalues
+//!
+//! the schur form also gives cheap access to the sensitivity of the eigenvalues and eigenvectors:
+//!
+//! - the condition number of a simple eigenvalue $\lambda$ is $\kappa(\lambda) = \|x\| \|y\| / |y^H
+//!   x|$, where $x$ and $y$ are the right and left eigenvectors. a perturbation $E$ of $A$ moves
+//!   $\lambda$ by at most $\kappa(\lambda) \|E\|_2$, to first order,
+//! - the separation of $\lambda$ from the rest of the spectrum is $\text{sep}(\lambda) =
+//!   \sigma_{\min}(T_{22} - \lambda I)$, where $T_{22}$ is the trailing block of $T$ once $\lambda$
+//!   has been moved to the top left corner. the eigenvector of $\lambda$ moves by an angle of at
+//!   most $\|E\|_2 / \text{sep}(\lambda)$, to first order.
+//!
+//! the reordering follows lapack's `trexc` and `trsen`, and the condition numbers follow `trsna`
+//!
+//! # example
+//! ```
+//! use faer::linalg::evd::schur_reorder;
+//! use faer::{Col, Mat, c64};
+//!
+//! let T = Mat::<c64>::from_fn(3, 3, |i, j| if i <= j { c64::new((i + 2 * j) as f64, (i * j) as f64) } else { c64::ZERO });
+//! let mut T2 = T.clone();
+//! let mut Q = Mat::<c64>::identity(3, 3);
+//!
+//! // move the last eigenvalue to the front
+//! let m = schur_reorder::reorder_schur_in_place(T2.as_mut(), Some(Q.as_mut()), &[false, false, true]);
+//! assert!(m == 1);
+//! assert!((T2[(0, 0)] - T[(2, 2)]).norm() < 1e-12);
+//! assert!((&Q * &T2 * Q.adjoint() - &T).norm_max() < 1e-12);
+//!
+//! let mut cond = Col::<f64>::zeros(3);
+//! schur_reorder::schur_eigenvalue_condition_numbers(T.as_ref(), cond.as_mut());
+//! assert!(cond.iter().all(|&c| c >= 1.0));
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use linalg::evd::schur::complex_schur::schur_swap;
+
+/// moves the eigenvalue at index `from` on the diagonal of the upper triangular matrix `T` to the
+/// index `to`, with a sequence of swaps of adjacent eigenvalues. the eigenvalues in between are
+/// shifted by one position, preserving their relative order
+///
+/// if `Q` is provided, it is updated with the rotations applied to `T`, so that $Q T Q^H$ is left
+/// unchanged
+///
+/// # panics
+///
+/// panics if `T` is not square, if `Q` doesn't have as many columns as `T`, or if `from` or `to`
+/// are out of bounds
+#[track_caller]
+pub fn move_schur_eigenvalue_in_place<T: ComplexField>(T: MatMut<'_, T>, Q: Option<MatMut<'_, T>>, from: usize, to: usize) {
+	let n = T.nrows();
+	assert!(all(T.ncols() == n, from < n, to < n));
+	if let Some(Q) = Q.rb() {
+		assert!(Q.ncols() == n);
+	}
+
+	let mut T = T;
+	let mut Q = Q;
+	if from < to {
+		for j in from..to {
+			schur_swap(T.rb_mut(), Q.rb_mut(), j);
+		}
+	} else {
+		for j in (to..from).rev() {
+			schur_swap(T.rb_mut(), Q.rb_mut(), j);
+		}
+	}
+}
+
+/// moves the eigenvalues of the upper triangular matrix `T` such that `select[j]` is `true` to the
+/// top left corner, preserving the relative order of the selected and unselected eigenvalues, and
+/// returns their count $m$
+///
+/// if `Q` is provided, it is updated with the rotations applied to `T`, so that $Q T Q^H$ is left
+/// unchanged. the first $m$ columns of `Q` then span the invariant subspace associated with the
+/// selected eigenvalues
+///
+/// # panics
+///
+/// panics if `T` is not square, if `Q` doesn't have as many columns as `T`, or if `select` doesn't
+/// have as many elements as the dimension of `T`
+#[track_caller]
+pub fn reorder_schur_in_place<T: ComplexField>(T: MatMut<'_, T>, Q: Option<MatMut<'_, T>>, select: &[bool]) -> usize {
+	let n = T.nrows();
+	assert!(all(T.ncols() == n, select.len() == n));
+
+	let mut T = T;
+	let mut Q = Q;
+	let mut m = 0;
+	for (j, &selected) in select.iter().enumerate() {
+		if selected {
+			if j != m {
+				move_schur_eigenvalue_in_place(T.rb_mut(), Q.rb_mut(), j, m);
+			}
+			m += 1;
+		}
+	}
+	m
+}
+
+/// returns the norms of the right and left eigenvectors $x$ and $y$ of the upper triangular matrix
+/// `T`, associated with its $k$-th diagonal entry and normalized so that $x_k = y_k = 1$
+///
+/// since $y^H x = 1$, the product of the norms is the condition number of the eigenvalue
+#[math]
+fn eigenvector_norms<T: ComplexField>(T: MatRef<'_, T>, k: usize, work: ColMut<'_, T>) -> (T::Real, T::Real) {
+	let n = T.nrows();
+	let mut work = work;
+	let lambda = copy(T[(k, k)]);
+
+	let smin = max(eps::<T::Real>() * T.norm_max(), min_positive::<T::Real>());
+	let pivot = |i: usize| {
+		let d = T[(i, i)] - lambda;
+		if abs(d) < smin { from_real(smin) } else { d }
+	};
+
+	// (T[..k, ..k] - λ I) x = -T[..k, k]
+	for i in (0..k).rev() {
+		let mut acc = -T[(i, k)];
+		for j in i + 1..k {
+			acc = acc - T[(i, j)] * work[j];
+		}
+		work[i] = acc * recip(pivot(i));
+	}
+	let x = sqrt(one::<T::Real>() + work.rb().get(..k).squared_norm_l2());
+
+	// y^H T[k.., k..] = λ y^H, i.e., (T[k + 1.., k + 1..] - λ I)^H y = -T[k, k + 1..]^H
+	for i in k + 1..n {
+		let mut acc = -conj(T[(k, i)]);
+		for j in k + 1..i {
+			acc = acc - conj(T[(j, i)]) * work[j];
+		}
+		work[i] = acc * recip(conj(pivot(i)));
+	}
+	let y = sqrt(one::<T::Real>() + work.rb().get(k + 1..).squared_norm_l2());
+
+	(x, y)
+}
+
+/// computes the condition numbers of the eigenvalues of the upper triangular matrix `T`, i.e., of
+/// the schur form of a matrix, and stores them in `cond`
+///
+/// the condition number of the $k$-th eigenvalue is $\frac{\|x\| \|y\|}{|y^H x|} \geq 1$, where $x$
+/// and $y$ are the corresponding right and left eigenvectors. it is infinite, up to rounding
+/// errors, for a multiple eigenvalue. this requires $O(n^2)$ operations per eigenvalue
+///
+/// # panics
+///
+/// panics if `T` is not square, or if `cond` doesn't have as many rows as `T`
+#[track_caller]
+#[math]
+pub fn schur_eigenvalue_condition_numbers<T: ComplexField>(T: MatRef<'_, T>, cond: ColMut<'_, T::Real>) {
+	let n = T.nrows();
+	assert!(all(T.ncols() == n, cond.nrows() == n));
+
+	let mut cond = cond;
+	let mut work = Col::<T>::zeros(n);
+	for k in 0..n {
+		let (x, y) = eigenvector_norms(T, k, work.as_mut());
+		cond[k] = x * y;
+	}
+}
+
+/// computes the separations of the eigenvalues of the upper triangular matrix `T`, i.e., of the
+/// schur form of a matrix, from the rest of its spectrum, and stores them in `sep`
+///
+/// the separation of the $k$-th eigenvalue $\lambda$ is $\sigma_{\min}(T_{22} - \lambda I)$, where
+/// $T_{22}$ is the trailing $(n - 1) \times (n - 1)$ block of $T$ once $\lambda$ has been moved to
+/// the top left corner. its inverse is the condition number of the corresponding eigenvector. this
+/// requires $O(n^3)$ operations per eigenvalue
+///
+/// # panics
+///
+/// panics if `T` is not square, or if `sep` doesn't have as many rows as `T`
+#[track_caller]
+#[math]
+pub fn schur_eigenvector_separations<T: ComplexField>(T: MatRef<'_, T>, sep: ColMut<'_, T::Real>) {
+	let n = T.nrows();
+	assert!(all(T.ncols() == n, sep.nrows() == n));
+
+	let mut sep = sep;
+	if n == 1 {
+		sep[0] = infinity();
+		return;
+	}
+
+	let mut W = Mat::<T>::zeros(n, n);
+	for k in 0..n {
+		W.copy_from(T);
+		move_schur_eigenvalue_in_place(W.as_mut(), None, k, 0);
+
+		let lambda = copy(W[(0, 0)]);
+		let mut T22 = Mat::<T>::zeros(n - 1, n - 1);
+		T22.copy_from_triangular_upper(W.as_ref().submatrix(1, 1, n - 1, n - 1));
+		for i in 0..n - 1 {
+			T22[(i, i)] = T22[(i, i)] - lambda;
+		}
+
+		sep[k] = match T22.singular_values() {
+			Ok(s) => copy(s[n - 2]),
+			Err(_) => nan(),
+		};
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+	use linalg::solvers::DenseSolveCore;
+
+	fn random_triangular(n: usize, rng: &mut StdRng) -> Mat<c64> {
+		let mut T = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		for j in 0..n {
+			for i in j + 1..n {
+				T[(i, j)] = c64::ZERO;
+			}
+		}
+		T
+	}
+
+	#[test]
+	fn test_reorder() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 12;
+		let T = random_triangular(n, rng);
+
+		let select = (0..n).map(|j| j % 3 == 1).collect::<Vec<_>>();
+		let mut T2 = T.clone();
+		let mut Q = Mat::<c64>::identity(n, n);
+		let m = reorder_schur_in_place(T2.as_mut(), Some(Q.as_mut()), &select);
+		assert!(m == 4);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 256.0);
+		assert!(&Q * &T2 * Q.adjoint() ~ T);
+		assert!(Q.adjoint() * &Q ~ Mat::<c64>::identity(n, n));
+
+		let expected = (0..n).filter(|&j| select[j]).chain((0..n).filter(|&j| !select[j]));
+		for (i, j) in expected.enumerate() {
+			assert!((T2[(i, i)] - T[(j, j)]).norm() < 1e-12);
+			for k in 0..i {
+				assert!(T2[(i, k)] == c64::ZERO);
+			}
+		}
+
+		// the leading columns of Q span an invariant subspace
+		let Q1 = Q.subcols(0, m);
+		assert!(&T * Q1 ~ Q1 * T2.submatrix(0, 0, m, m));
+	}
+
+	#[test]
+	fn test_condition_numbers() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 8;
+		let T = random_triangular(n, rng);
+
+		let mut cond = Col::<f64>::zeros(n);
+		schur_eigenvalue_condition_numbers(T.as_ref(), cond.as_mut());
+
+		// compare with the eigenvectors computed by the general eigensolver
+		let evd = T.eigen().unwrap();
+		let X = evd.U();
+		let Y = DenseSolveCore::inverse(&X.partial_piv_lu());
+		for j in 0..n {
+			let i = (0..n).find(|&i| (evd.S()[i] - T[(j, j)]).norm() < 1e-10).unwrap();
+			let expected = X.col(i).norm_l2() * Y.row(i).norm_l2();
+			assert!((cond[j] - expected).abs() < 1e-8 * expected);
+		}
+
+		let mut sep = Col::<f64>::zeros(n);
+		schur_eigenvector_separations(T.as_ref(), sep.as_mut());
+		for j in 0..n {
+			// the separation is bounded by the distance to the closest other eigenvalue
+			let dist = (0..n)
+				.filter(|&i| i != j)
+				.map(|i| (T[(i, i)] - T[(j, j)]).norm())
+				.fold(f64::INFINITY, f64::min);
+			assert!(sep[j] > 0.0);
+			assert!(sep[j] <= dist * (1.0 + 1e-12));
+		}
+
+		// closed form for 2x2 matrices
+		let (a, b, d) = (c64::new(1.0, 2.0), c64::new(3.0, -1.0), c64::new(-2.0, 0.5));
+		let T = Mat::from_fn(2, 2, |i, j| [[a, b], [c64::ZERO, d]][i][j]);
+		let mut cond = Col::<f64>::zeros(2);
+		let mut sep = Col::<f64>::zeros(2);
+		schur_eigenvalue_condition_numbers(T.as_ref(), cond.as_mut());
+		schur_eigenvector_separations(T.as_ref(), sep.as_mut());
+
+		let expected = (1.0 + (b / (a - d)).norm_sqr()).sqrt();
+		assert!((cond[0] - expected).abs() < 1e-14 * expected);
+		assert!((cond[1] - expected).abs() < 1e-14 * expected);
+		assert!((sep[0] - (a - d).norm()).abs() < 1e-14);
+		assert!((sep[1] - (a - d).norm()).abs() < 1e-14);
+	}
+}
@@ -0,0 +1,561 @@
+//! selected eigenvalues and eigenvectors of self-adjoint matrices
+//!
+//! when only a few eigenpairs of a self-adjoint matrix are needed, e.g., the smallest ones, or
+//! those in a given interval, computing the full eigendecomposition is wasteful. after the
+//! reduction to a real symmetric tridiagonal matrix $T$:
+//!
+//! - each selected eigenvalue is computed independently by bisection, using the sturm sequence
+//!   of $T - x I$ to count the eigenvalues smaller than $x$. this costs $O(n)$ operations per
+//!   bisection step, and the eigenvalues are computed to high relative accuracy,
+//! - the corresponding eigenvectors are computed by inverse iteration with $T - \lambda I$, with a
+//!   reorthogonalization against the previous eigenvectors of the same cluster, for $O(n)$
+//!   operations per eigenvector.
+//!
+//! for $k$ eigenpairs of an $n \times n$ matrix, the cost is dominated by the $O(n^3)$
+//! tridiagonalization and the $O(n^2 k)$ back-transformation, instead of the $O(n^3)$ of the
+//! full eigenvector computation
+//!
+//! # example
+//! ```
+//! use faer::linalg::evd::selected::{self, EigenRange};
+//! use faer::{Mat, Par};
+//!
+//! let n = 200;
+//! // discrete laplacian
+//! let A = Mat::<f64>::from_fn(n, n, |i, j| if i == j { 2.0 } else if i.abs_diff(j) == 1 { -1.0 } else { 0.0 });
+//!
+//! // the 10 smallest eigenpairs
+//! let (s, U) = selected::self_adjoint_evd_selected(A.as_ref(), EigenRange::Index(0..10), Par::Seq);
+//! assert!(s.nrows() == 10);
+//! assert!(U.ncols() == 10);
+//!
+//! for k in 0..10 {
+//!     let exact = 2.0 - 2.0 * ((k + 1) as f64 * core::f64::consts::PI / (n + 1) as f64).cos();
+//!     assert!((s[k] - exact).abs() < 1e-13);
+//!     assert!((&A * U.col(k) - U.col(k) * faer::Scale(s[k])).norm_l2() < 1e-12);
+//! }
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use core::ops::Range;
+use dyn_stack::MemBuffer;
+use linalg::evd::tridiag;
+
+/// selection of eigenvalues, sorted in nondecreasing order
+#[derive(Clone, Debug)]
+pub enum EigenRange<T> {
+	/// the eigenvalues whose indices are in the given range, with the smallest eigenvalue at index
+	/// zero
+	Index(Range<usize>),
+	/// the eigenvalues in the half-open interval `[lower, upper)`
+	Value {
+		/// lower bound of the interval
+		lower: T,
+		/// upper bound of the interval
+		upper: T,
+	},
+}
+
+/// returns the smallest pivot allowed in the sturm sequence
+#[math]
+fn pivmin<T: RealField>(offdiag: ColRef<'_, T>) -> T {
+	let mut emax = one::<T>();
+	for i in 0..offdiag.nrows() {
+		emax = max(emax, abs2(offdiag[i]));
+	}
+	min_positive::<T>() * emax
+}
+
+/// returns the number of eigenvalues of the tridiagonal matrix that are smaller than `x`
+#[math]
+fn sturm_count<T: RealField>(diag: ColRef<'_, T>, offdiag: ColRef<'_, T>, x: T, pivmin: T) -> usize {
+	let n = diag.nrows();
+	let mut count = 0;
+	let mut d = zero::<T>();
+	for i in 0..n {
+		d = if i == 0 { diag[i] - x } else { (diag[i] - x) - abs2(offdiag[i - 1]) / d };
+		if abs(d) < pivmin {
+			d = -pivmin;
+		}
+		if d < zero() {
+			count += 1;
+		}
+	}
+	count
+}
+
+/// returns the `k`-th smallest eigenvalue, given an interval `[lo, hi)` containing it
+#[math]
+fn bisect<T: RealField>(diag: ColRef<'_, T>, offdiag: ColRef<'_, T>, k: usize, lo: T, hi: T, pivmin: T) -> T {
+	let mut lo = lo;
+	let mut hi = hi;
+	let two = from_f64::<T>(2.0);
+	loop {
+		let mid = (lo + hi) * from_f64::<T>(0.5);
+		let tol = two * eps::<T>() * max(abs(lo), abs(hi)) + pivmin;
+		if hi - lo <= tol || mid <= lo || mid >= hi {
+			return mid;
+		}
+
+		if sturm_count(diag, offdiag, copy(mid), copy(pivmin)) > k {
+			hi = mid;
+		} else {
+			lo = mid;
+		}
+	}
+}
+
+/// computes the selected eigenvalues of the real symmetric tridiagonal matrix with diagonal `diag`
+/// and subdiagonal `offdiag`, by bisection. the eigenvalues are returned in nondecreasing order
+///
+/// # panics
+///
+/// panics if `offdiag` doesn't have one element less than `diag` (unless `diag` is empty), or if
+/// the index range is out of bounds
+#[track_caller]
+#[math]
+pub fn tridiag_selected_eigenvalues<T: RealField>(diag: ColRef<'_, T>, offdiag: ColRef<'_, T>, range: EigenRange<T>) -> Col<T> {
+	let n = diag.nrows();
+	assert!(offdiag.nrows() == n.saturating_sub(1));
+
+	if n == 0 {
+		if let EigenRange::Index(range) = &range {
+			assert!(range.end == 0);
+		}
+		return Col::zeros(0);
+	}
+
+	// gershgorin bounds
+	let mut lo = copy(diag[0]);
+	let mut hi = copy(diag[0]);
+	for i in 0..n {
+		let mut r = zero::<T>();
+		if i > 0 {
+			r = r + abs(offdiag[i - 1]);
+		}
+		if i + 1 < n {
+			r = r + abs(offdiag[i]);
+		}
+		lo = min(lo, diag[i] - r);
+		hi = max(hi, diag[i] + r);
+	}
+
+	let pivmin = pivmin(offdiag);
+	let tnorm = max(abs(lo), abs(hi));
+	let margin = from_f64::<T>(2.0 * n as f64) * eps::<T>() * tnorm + from_f64::<T>(4.0) * pivmin;
+	let lo = lo - margin;
+	let hi = hi + margin;
+
+	let (start, end, lo, hi) = match range {
+		EigenRange::Index(range) => {
+			assert!(all(range.start <= range.end, range.end <= n));
+			(range.start, range.end, lo, hi)
+		},
+		EigenRange::Value { lower, upper } => {
+			if lower >= upper {
+				return Col::zeros(0);
+			}
+			let lower = max(lower, lo);
+			let upper = min(upper, hi);
+			let start = sturm_count(diag, offdiag, copy(lower), copy(pivmin));
+			let end = Ord::max(start, sturm_count(diag, offdiag, copy(upper), copy(pivmin)));
+			(start, end, lower, upper)
+		},
+	};
+
+	Col::from_fn(end - start, |k| bisect(diag, offdiag, start + k, copy(lo), copy(hi), copy(pivmin)))
+}
+
+/// returns an arbitrary starting vector entry in `[-1, 1]` for the inverse iteration
+fn start_entry(i: usize, j: usize) -> f64 {
+	let mut x = (i as u64).wrapping_mul(0x9e3779b97f4a7c15) ^ (j as u64).wrapping_mul(0xbf58476d1ce4e5b9) ^ 0x94d049bb133111eb;
+	x ^= x >> 31;
+	x = x.wrapping_mul(0xd6e8feb86659fd39);
+	x ^= x >> 29;
+	(x >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+}
+
+/// solves $(T - \lambda I) x = b$ in place, with the $LU$ decomposition with partial pivoting of
+/// the tridiagonal matrix. zero pivots are replaced by `tiny`
+#[math]
+#[allow(clippy::too_many_arguments)]
+fn shifted_tridiag_solve<T: RealField>(
+	diag: ColRef<'_, T>,
+	offdiag: ColRef<'_, T>,
+	shift: T,
+	tiny: T,
+	b: ColMut<'_, T>,
+	u0: &mut [T],
+	u1: &mut [T],
+	u2: &mut [T],
+) {
+	let n = diag.nrows();
+	let mut b = b;
+
+	for i in 0..n {
+		u0[i] = diag[i] - shift;
+		u1[i] = if i + 1 < n { copy(offdiag[i]) } else { zero() };
+		u2[i] = zero();
+	}
+
+	for i in 0..n.saturating_sub(1) {
+		let e = copy(offdiag[i]);
+		if abs(u0[i]) >= abs(e) {
+			if u0[i] == zero() {
+				u0[i] = copy(tiny);
+			}
+			let l = e / u0[i];
+			u0[i + 1] = u0[i + 1] - l * u1[i];
+			b[i + 1] = b[i + 1] - l * b[i];
+		} else {
+			// swap the rows i and i + 1
+			let l = u0[i] / e;
+			let a1 = copy(u1[i]);
+			let (b1, b2) = (copy(u0[i + 1]), copy(u1[i + 1]));
+
+			u0[i] = e;
+			u1[i] = copy(b1);
+			u2[i] = copy(b2);
+			u0[i + 1] = a1 - l * b1;
+			u1[i + 1] = -l * b2;
+
+			let (bi, bi1) = (copy(b[i]), copy(b[i + 1]));
+			b[i] = copy(bi1);
+			b[i + 1] = bi - l * bi1;
+		}
+	}
+	if u0[n - 1] == zero() {
+		u0[n - 1] = copy(tiny);
+	}
+
+	for i in (0..n).rev() {
+		let mut x = copy(b[i]);
+		if i + 1 < n {
+			x = x - u1[i] * b[i + 1];
+		}
+		if i + 2 < n {
+			x = x - u2[i] * b[i + 2];
+		}
+		b[i] = x / u0[i];
+	}
+}
+
+/// computes the eigenvectors of the real symmetric tridiagonal matrix with diagonal `diag` and
+/// subdiagonal `offdiag`, corresponding to the given eigenvalues sorted in nondecreasing order, by
+/// inverse iteration, and stores them in the columns of `u`
+///
+/// eigenvectors whose eigenvalues are close to each other are reorthogonalized against each other,
+/// so that the columns of `u` are orthonormal
+///
+/// # panics
+///
+/// panics if `offdiag` doesn't have one element less than `diag` (unless `diag` is empty), or if
+/// the dimensions of `u` don't match
+#[track_caller]
+#[math]
+pub fn tridiag_eigenvectors<T: RealField>(diag: ColRef<'_, T>, offdiag: ColRef<'_, T>, eigenvalues: ColRef<'_, T>, u: MatMut<'_, T>) {
+	let n = diag.nrows();
+	let k = eigenvalues.nrows();
+	assert!(all(offdiag.nrows() == n.saturating_sub(1), u.nrows() == n, u.ncols() == k));
+
+	let mut u = u;
+	if n == 0 {
+		return;
+	}
+
+	let mut tnorm = zero::<T>();
+	for i in 0..n {
+		let mut r = abs(diag[i]);
+		if i > 0 {
+			r = r + abs(offdiag[i - 1]);
+		}
+		if i + 1 < n {
+			r = r + abs(offdiag[i]);
+		}
+		tnorm = max(tnorm, r);
+	}
+	let ortol = from_f64::<T>(1e-3) * tnorm;
+	let tiny = max(eps::<T>() * tnorm, min_positive::<T>());
+
+	let mut u0 = alloc::vec![zero::<T>(); n];
+	let mut u1 = alloc::vec![zero::<T>(); n];
+	let mut u2 = alloc::vec![zero::<T>(); n];
+
+	let mut cluster_start = 0;
+	let mut prev_shift = zero::<T>();
+	for j in 0..k {
+		let mut shift = copy(eigenvalues[j]);
+		if j > 0 {
+			if eigenvalues[j] - eigenvalues[j - 1] > ortol {
+				cluster_start = j;
+			}
+			// perturb the shift of close eigenvalues, so that the iterations don't converge to
+			// the same vector
+			let pertol = from_f64::<T>(10.0) * abs(eps::<T>() * shift);
+			if shift - prev_shift < pertol {
+				shift = prev_shift + pertol;
+			}
+		}
+		prev_shift = copy(shift);
+
+		let (prev, x) = u.rb_mut().split_at_col_mut(j);
+		let prev = prev.rb().subcols(cluster_start, j - cluster_start);
+		let mut x = x.col_mut(0);
+
+		for i in 0..n {
+			x[i] = from_f64::<T>(start_entry(i, j));
+		}
+
+		for _ in 0..3 {
+			let norm = x.rb().norm_l2();
+			x *= Scale(recip(norm));
+			shifted_tridiag_solve(diag, offdiag, copy(shift), copy(tiny), x.rb_mut(), &mut u0, &mut u1, &mut u2);
+
+			for c in 0..prev.ncols() {
+				let q = prev.col(c);
+				let dot = q.transpose() * x.rb();
+				zip!(x.rb_mut(), q).for_each(|unzip!(x, q)| *x = *x - dot * *q);
+			}
+		}
+
+		let norm = x.rb().norm_l2();
+		x *= Scale(recip(norm));
+
+		// normalize the sign so that the largest component is positive
+		let mut imax = 0;
+		for i in 0..n {
+			if abs(x[i]) > abs(x[imax]) {
+				imax = i;
+			}
+		}
+		if x[imax] < zero() {
+			x *= Scale(-one::<T>());
+		}
+	}
+}
+
+/// computes the selected eigenvalues of the self-adjoint matrix $A$, sorted in nondecreasing order
+///
+/// only the lower triangular half of $A$ is accessed
+#[track_caller]
+pub fn self_adjoint_eigenvalues_selected<T: ComplexField>(A: MatRef<'_, T>, range: EigenRange<T::Real>, par: Par) -> Col<T::Real> {
+	let Tridiag { diag, offdiag, .. } = tridiagonalize(A, par);
+	tridiag_selected_eigenvalues(diag.as_ref(), offdiag.as_ref(), range)
+}
+
+/// computes the selected eigenvalues of the self-adjoint matrix $A$, sorted in nondecreasing
+/// order, and the corresponding eigenvectors
+///
+/// only the lower triangular half of $A$ is accessed
+#[track_caller]
+#[math]
+pub fn self_adjoint_evd_selected<T: ComplexField>(A: MatRef<'_, T>, range: EigenRange<T::Real>, par: Par) -> (Col<T::Real>, Mat<T>) {
+	let n = A.nrows();
+	let Tridiag {
+		diag,
+		offdiag,
+		trid,
+		householder,
+	} = tridiagonalize(A, par);
+	let s = tridiag_selected_eigenvalues(diag.as_ref(), offdiag.as_ref(), range);
+	let k = s.nrows();
+
+	let mut u_real = Mat::<T::Real>::zeros(n, k);
+	tridiag_eigenvectors(diag.as_ref(), offdiag.as_ref(), s.as_ref(), u_real.as_mut());
+
+	// in the complex case, the tridiagonal matrix was made real by a diagonal unitary similarity
+	let mut u = Mat::<T>::zeros(n, k);
+	let mut scale = one::<T>();
+	for i in 0..n {
+		if i > 0 && !try_const! { T::IS_REAL } {
+			let x = trid[(i, i - 1)] * scale;
+			scale = if x == zero() { one() } else { mul_real(x, recip(abs(x))) };
+		}
+		for j in 0..k {
+			u[(i, j)] = mul_real(scale, u_real[(i, j)]);
+		}
+	}
+
+	if n > 1 {
+		let bs = householder.nrows();
+		linalg::householder::apply_block_householder_sequence_on_the_left_in_place_with_conj(
+			trid.as_ref().submatrix(1, 0, n - 1, n - 1),
+			householder.as_ref(),
+			Conj::No,
+			u.as_mut().subrows_mut(1, n - 1),
+			par,
+			MemStack::new(&mut MemBuffer::new(
+				linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(n - 1, bs, k),
+			)),
+		);
+	}
+
+	(s, u)
+}
+
+/// real tridiagonal matrix similar to a self-adjoint matrix
+struct Tridiag<T: ComplexField> {
+	diag: Col<T::Real>,
+	offdiag: Col<T::Real>,
+	/// householder basis of the reduction, below the subdiagonal
+	trid: Mat<T>,
+	/// householder factor of the reduction
+	householder: Mat<T>,
+}
+
+/// reduces $A$ to a real tridiagonal matrix
+#[track_caller]
+#[math]
+fn tridiagonalize<T: ComplexField>(A: MatRef<'_, T>, par: Par) -> Tridiag<T> {
+	let n = A.nrows();
+	assert!(A.ncols() == n);
+
+	let mut trid = Mat::<T>::zeros(n, n);
+	trid.copy_from_triangular_lower(A);
+
+	let bs = linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(n, n);
+	let mut householder = Mat::<T>::zeros(bs, n.saturating_sub(1));
+	if n > 0 {
+		tridiag::tridiag_in_place(
+			trid.as_mut(),
+			householder.as_mut(),
+			par,
+			MemStack::new(&mut MemBuffer::new(tridiag::tridiag_in_place_scratch::<T>(n, par, default()))),
+			default(),
+		);
+	}
+
+	let diag = Col::from_fn(n, |i| real(trid[(i, i)]));
+	let offdiag = Col::from_fn(n.saturating_sub(1), |i| {
+		if try_const! { T::IS_REAL } {
+			real(trid[(i + 1, i)])
+		} else {
+			abs(trid[(i + 1, i)])
+		}
+	});
+
+	Tridiag {
+		diag,
+		offdiag,
+		trid,
+		householder,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_tridiag_selected() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 60;
+
+		let diag = CwiseColDistribution {
+			nrows: n,
+			dist: StandardNormal,
+		}
+		.rand::<Col<f64>>(rng);
+		let mut offdiag = CwiseColDistribution {
+			nrows: n - 1,
+			dist: StandardNormal,
+		}
+		.rand::<Col<f64>>(rng);
+		// split the matrix into two blocks
+		offdiag[20] = 0.0;
+
+		let T = Mat::from_fn(n, n, |i, j| {
+			if i == j {
+				diag[i]
+			} else if i == j + 1 {
+				offdiag[j]
+			} else if j == i + 1 {
+				offdiag[i]
+			} else {
+				0.0
+			}
+		});
+		let exact = T.self_adjoint_eigen(Side::Lower).unwrap();
+		let exact = exact.S().column_vector();
+
+		let s = tridiag_selected_eigenvalues(diag.as_ref(), offdiag.as_ref(), EigenRange::Index(5..15));
+		assert!(s.nrows() == 10);
+		for k in 0..10 {
+			assert!((s[k] - exact[5 + k]).abs() < 1e-13);
+		}
+
+		let s = tridiag_selected_eigenvalues(diag.as_ref(), offdiag.as_ref(), EigenRange::Value { lower: -0.5, upper: 1.0 });
+		let expected = exact.iter().filter(|&&x| (-0.5..1.0).contains(&x)).count();
+		assert!(s.nrows() == expected);
+		assert!(s.iter().all(|&x| (-0.5..1.0).contains(&x)));
+
+		let mut u = Mat::zeros(n, s.nrows());
+		tridiag_eigenvectors(diag.as_ref(), offdiag.as_ref(), s.as_ref(), u.as_mut());
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0);
+		assert!(u.transpose() * &u ~ Mat::<f64>::identity(s.nrows(), s.nrows()));
+		assert!(&T * &u ~ &u * s.as_diagonal());
+
+		let s = tridiag_selected_eigenvalues(diag.as_ref(), offdiag.as_ref(), EigenRange::Value { lower: 1.0, upper: -1.0 });
+		assert!(s.nrows() == 0);
+	}
+
+	#[test]
+	fn test_clustered() {
+		// the wilkinson matrix has pairs of nearly equal eigenvalues
+		let m = 10;
+		let n = 2 * m + 1;
+		let diag = Col::from_fn(n, |i| (i as f64 - m as f64).abs());
+		let offdiag = Col::from_fn(n - 1, |_| 1.0);
+
+		let s = tridiag_selected_eigenvalues(diag.as_ref(), offdiag.as_ref(), EigenRange::Index(n - 6..n));
+		let mut u = Mat::zeros(n, 6);
+		tridiag_eigenvectors(diag.as_ref(), offdiag.as_ref(), s.as_ref(), u.as_mut());
+
+		let T = Mat::from_fn(n, n, |i, j| {
+			if i == j {
+				diag[i]
+			} else if i.abs_diff(j) == 1 {
+				1.0
+			} else {
+				0.0
+			}
+		});
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0);
+		assert!(u.transpose() * &u ~ Mat::<f64>::identity(6, 6));
+		assert!(&T * &u ~ &u * s.as_diagonal());
+	}
+
+	#[test]
+	fn test_self_adjoint_selected() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 50;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let A = &A + A.adjoint();
+		let exact = A.self_adjoint_eigen(Side::Lower).unwrap();
+		let exact = exact.S().column_vector();
+
+		let (s, U) = self_adjoint_evd_selected(A.as_ref(), EigenRange::Index(40..50), Par::Seq);
+		for k in 0..10 {
+			assert!((s[k] - exact[40 + k].re).abs() < 1e-12);
+		}
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 4096.0);
+		let S = Mat::from_fn(10, 10, |i, j| if i == j { c64::new(s[i], 0.0) } else { c64::ZERO });
+		assert!(U.adjoint() * &U ~ Mat::<c64>::identity(10, 10));
+		assert!(&A * &U ~ &U * &S);
+
+		let s2 = self_adjoint_eigenvalues_selected(A.as_ref(), EigenRange::Index(40..50), Par::Seq);
+		assert!(s2 == s);
+	}
+}
@@ -0,0 +1,517 @@
+//! matrix exponential, with structure preserving variants for skew-symmetric and hamiltonian
+//! matrices
+//!
+//! the general exponential [`expm`] uses the scaling and squaring method with a diagonal padé
+//! approximant, following higham's 2005 algorithm. for structured matrices, the exponential
+//! inherits some structure that geometric integrators rely on:
+//!
+//! - the exponential of a real skew-symmetric matrix $A = -A^\top$ is orthogonal.
+//!   [`expm_skew_symmetric`] computes it from the real block schur form $A = Q S Q^\top$ returned
+//!   by [`skew_symmetric_schur`], where $S$ is block diagonal with $2 \times 2$ blocks
+//!   $\begin{bmatrix} 0 & \theta \\\\ -\theta & 0 \end{bmatrix}$, whose exponentials are plane
+//!   rotations. the result is orthogonal to working precision, regardless of the norm of $A$,
+//! - the exponential of a hamiltonian matrix $H$, i.e., such that $JH$ is symmetric with $J =
+//!   \begin{bmatrix} 0 & I \\\\ -I & 0 \end{bmatrix}$, is symplectic, and the eigenvalues of $H$
+//!   come in pairs $(\lambda, -\lambda)$. [`expm_hamiltonian`] relies on the fact that diagonal padé
+//!   approximants map hamiltonian matrices to symplectic ones, and [`hamiltonian_eigenvalues`]
+//!   returns eigenvalues that are paired exactly.
+//!
+//! # example
+//! ```
+//! use faer::linalg::expm;
+//! use faer::{Mat, mat};
+//!
+//! let A = mat![[0.0, 2.0, -1.0], [-2.0, 0.0, 0.5], [1.0, -0.5, 0.0]];
+//! let E = expm::expm_skew_symmetric(A.as_ref()).unwrap();
+//!
+//! // the exponential of a skew-symmetric matrix is orthogonal
+//! assert!((E.transpose() * &E - Mat::<f64>::identity(3, 3)).norm_max() < 1e-14);
+//! assert!((&E - expm::expm(A.as_ref())).norm_max() < 1e-13);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::linalg::evd::EvdError;
+use crate::linalg::solvers::Solve;
+
+/// returns $c_0 I + \sum_i c_i M_i$
+fn linear_combination<T: ComplexField>(n: usize, c0: f64, terms: &[(f64, MatRef<'_, T>)]) -> Mat<T> {
+	let mut out = Mat::<T>::zeros(n, n);
+	out.as_mut().diagonal_mut().fill(from_f64::<T>(c0));
+	for &(c, M) in terms {
+		let c = from_f64::<T>(c);
+		zip!(out.as_mut(), M).for_each(|unzip!(out, m)| *out = add(out, &mul(&c, m)));
+	}
+	out
+}
+
+/// returns the operator 1-norm of `A`
+fn norm_1<T: ComplexField>(A: MatRef<'_, T>) -> T::Real {
+	let mut norm = zero::<T::Real>();
+	for j in 0..A.ncols() {
+		norm = max(&norm, &A.col(j).norm_l1());
+	}
+	norm
+}
+
+/// computes the exponential of the square matrix $A$
+///
+/// the order of the padé approximant and the number of squarings are chosen from the 1-norm of
+/// $A$, such that the backward error is at most the unit roundoff of `f64`
+///
+/// # panics
+///
+/// panics if `A` is not square
+#[track_caller]
+pub fn expm<T: ComplexField>(A: MatRef<'_, T>) -> Mat<T> {
+	let n = A.nrows();
+	assert!(A.ncols() == n);
+
+	const THETA: [f64; 4] = [1.495585217958292e-2, 2.53939833006323e-1, 9.504178996162932e-1, 2.097847961257068];
+	const THETA_13: f64 = 5.371920351148152;
+	const B: [&[f64]; 4] = [
+		&[120.0, 60.0, 12.0, 1.0],
+		&[30240.0, 15120.0, 3360.0, 420.0, 30.0, 1.0],
+		&[17297280.0, 8648640.0, 1995840.0, 277200.0, 25200.0, 1512.0, 56.0, 1.0],
+		&[
+			17643225600.0,
+			8821612800.0,
+			2075673600.0,
+			302702400.0,
+			30270240.0,
+			2162160.0,
+			110880.0,
+			3960.0,
+			90.0,
+			1.0,
+		],
+	];
+	const B_13: [f64; 14] = [
+		64764752532480000.0,
+		32382376266240000.0,
+		7771770303897600.0,
+		1187353796428800.0,
+		129060195264000.0,
+		10559470521600.0,
+		670442572800.0,
+		33522128640.0,
+		1323241920.0,
+		40840800.0,
+		960960.0,
+		16380.0,
+		182.0,
+		1.0,
+	];
+
+	if n == 0 {
+		return Mat::zeros(0, 0);
+	}
+
+	let norm = norm_1(A);
+
+	// low order approximants, without squaring
+	let mut powers = alloc::vec::Vec::<Mat<T>>::new();
+	for (theta, b) in THETA.iter().zip(B) {
+		if norm <= from_f64::<T::Real>(*theta) {
+			// even powers of A
+			powers.push(A * A);
+			for _ in 1..b.len() / 2 {
+				let last = powers.last().unwrap();
+				let next = last * &powers[0];
+				powers.push(next);
+			}
+
+			let odd = (1..b.len() / 2)
+				.map(|k| (b[2 * k + 1], powers[k - 1].as_ref()))
+				.collect::<alloc::vec::Vec<_>>();
+			let even = (1..b.len() / 2)
+				.map(|k| (b[2 * k], powers[k - 1].as_ref()))
+				.collect::<alloc::vec::Vec<_>>();
+			let U = A * linear_combination(n, b[1], &odd);
+			let V = linear_combination(n, b[0], &even);
+			return pade(U, V);
+		}
+	}
+
+	// scaling and squaring with the approximant of order 13
+	let ratio = div(&norm, &from_f64::<T::Real>(THETA_13));
+	let mut s = 0usize;
+	let mut scale = one::<T::Real>();
+	let two = from_f64::<T::Real>(2.0);
+	while ratio > scale {
+		scale = mul(&scale, &two);
+		s += 1;
+	}
+
+	let A = A * Scale(from_real::<T>(&recip(&scale)));
+	let A2 = &A * &A;
+	let A4 = &A2 * &A2;
+	let A6 = &A4 * &A2;
+	let b = &B_13;
+
+	let U = &A6 * linear_combination(n, 0.0, &[(b[13], A6.as_ref()), (b[11], A4.as_ref()), (b[9], A2.as_ref())]);
+	let U = &A * (U + linear_combination(n, b[1], &[(b[7], A6.as_ref()), (b[5], A4.as_ref()), (b[3], A2.as_ref())]));
+	let V = &A6 * linear_combination(n, 0.0, &[(b[12], A6.as_ref()), (b[10], A4.as_ref()), (b[8], A2.as_ref())]);
+	let V = V + linear_combination(n, b[0], &[(b[6], A6.as_ref()), (b[4], A4.as_ref()), (b[2], A2.as_ref())]);
+
+	let mut E = pade(U, V);
+	for _ in 0..s {
+		E = &E * &E;
+	}
+	E
+}
+
+/// returns $(V - U)^{-1} (V + U)$
+fn pade<T: ComplexField>(U: Mat<T>, V: Mat<T>) -> Mat<T> {
+	let P = &V + &U;
+	let Q = &V - &U;
+	Q.partial_piv_lu().solve(P)
+}
+
+/// real block schur form of a skew-symmetric matrix, computed by [`skew_symmetric_schur`]
+///
+/// $A = Q S Q^\top$, where $Q$ is orthogonal and $S$ is block diagonal. the first $k$ diagonal
+/// blocks, where $k$ is the number of angles, are $\begin{bmatrix} 0 & \theta_i \\\\ -\theta_i & 0
+/// \end{bmatrix}$, and the remaining $n - 2k$ diagonal entries are zero
+#[derive(Clone, Debug)]
+pub struct SkewSymmetricSchur<T: RealField> {
+	/// orthogonal schur vectors
+	pub Q: Mat<T>,
+	/// positive angles $\theta_i$, sorted in nonincreasing order. the eigenvalues of $A$ are $\pm i
+	/// \theta_i$, and zero with multiplicity $n - 2k$
+	pub angles: Col<T>,
+}
+
+/// computes the real block schur form of the skew-symmetric matrix $A$
+///
+/// only the strictly lower triangular half of $A$ is accessed
+///
+/// # panics
+///
+/// panics if `A` is not square
+#[track_caller]
+#[math]
+pub fn skew_symmetric_schur<T: RealField>(A: MatRef<'_, T>) -> Result<SkewSymmetricSchur<T>, EvdError> {
+	let n = A.nrows();
+	assert!(A.ncols() == n);
+
+	// iA is hermitian, with eigenvalues ±θ
+	let iA = Mat::<Complex<T>>::from_fn(n, n, |i, j| {
+		if i > j {
+			Complex::new(zero(), copy(A[(i, j)]))
+		} else {
+			Complex::new(zero(), zero())
+		}
+	});
+	let evd = iA.self_adjoint_eigen(Side::Lower)?;
+	let s = evd.S().column_vector();
+	let V = evd.U();
+
+	let mut smax = zero::<T>();
+	for i in 0..n {
+		smax = max(smax, abs(s[i].re));
+	}
+	let tol = from_f64::<T>(n as f64) * eps::<T>() * smax;
+
+	let k = (0..n).filter(|&i| s[i].re > tol).count();
+	let z = n - 2 * k;
+
+	// if v = x + iy is an eigenvector of iA for the eigenvalue θ > 0, then x and y are orthogonal,
+	// with the same norm, and A y = -θ x, A x = θ y
+	let mut Q = Mat::<T>::zeros(n, n);
+	let mut angles = Col::<T>::zeros(k);
+	let sqrt2 = sqrt(from_f64::<T>(2.0));
+	for i in 0..k {
+		let idx = n - 1 - i;
+		angles[i] = copy(s[idx].re);
+		for r in 0..n {
+			Q[(r, 2 * i)] = sqrt2 * V[(r, idx)].im;
+			Q[(r, 2 * i + 1)] = sqrt2 * V[(r, idx)].re;
+		}
+	}
+
+	// the null space of A is spanned by the real and imaginary parts of the remaining eigenvectors
+	if z > 0 {
+		let W = Mat::<T>::from_fn(n, 2 * z, |r, j| if j < z { copy(V[(r, k + j)].re) } else { copy(V[(r, k + j - z)].im) });
+		let svd = W.thin_svd().map_err(|_| EvdError::NoConvergence)?;
+		Q.as_mut().subcols_mut(2 * k, z).copy_from(svd.U().subcols(0, z));
+	}
+
+	Ok(SkewSymmetricSchur { Q, angles })
+}
+
+/// returns $(\cos \theta, \sin \theta)$, normalized so that $c^2 + s^2 = 1$ to working precision
+#[math]
+fn cos_sin<T: RealField>(theta: T) -> (T, T) {
+	// reduce the angle by a power of two, which is exact
+	let half = from_f64::<T>(0.5);
+	let mut x = theta;
+	let mut k = 0usize;
+	while abs(x) > half {
+		x = x * half;
+		k += 1;
+	}
+
+	// taylor series
+	let mut c = one::<T>();
+	let mut s = copy(x);
+	let mut term_c = one::<T>();
+	let mut term_s = copy(x);
+	let x2 = x * x;
+	let mut i = 1usize;
+	while abs(term_c) > eps::<T>() || abs(term_s) > eps::<T>() * abs(s) {
+		term_c = -term_c * x2 / from_f64::<T>(((2 * i - 1) * (2 * i)) as f64);
+		term_s = -term_s * x2 / from_f64::<T>(((2 * i) * (2 * i + 1)) as f64);
+		c = c + term_c;
+		s = s + term_s;
+		i += 1;
+	}
+
+	// double angle formulas
+	for _ in 0..k {
+		let c2 = c * c - s * s;
+		let s2 = from_f64::<T>(2.0) * s * c;
+		let norm = hypot(c2, s2);
+		c = c2 / norm;
+		s = s2 / norm;
+	}
+	(c, s)
+}
+
+/// computes the exponential of the skew-symmetric matrix $A$, which is orthogonal
+///
+/// only the strictly lower triangular half of $A$ is accessed
+///
+/// # panics
+///
+/// panics if `A` is not square
+#[track_caller]
+#[math]
+pub fn expm_skew_symmetric<T: RealField>(A: MatRef<'_, T>) -> Result<Mat<T>, EvdError> {
+	let n = A.nrows();
+	let SkewSymmetricSchur { Q, angles } = skew_symmetric_schur(A)?;
+
+	// Q exp(S), where exp(S) is a product of plane rotations
+	let mut QE = Q.clone();
+	for i in 0..angles.nrows() {
+		let (c, s) = cos_sin(copy(angles[i]));
+		for r in 0..n {
+			let x = copy(Q[(r, 2 * i)]);
+			let y = copy(Q[(r, 2 * i + 1)]);
+			QE[(r, 2 * i)] = c * x - s * y;
+			QE[(r, 2 * i + 1)] = s * x + c * y;
+		}
+	}
+
+	Ok(QE * Q.transpose())
+}
+
+/// computes the exponential of the hamiltonian matrix $H$, which is symplectic
+///
+/// the diagonal padé approximant $r$ used by [`expm`] satisfies $r(z) r(-z) = 1$, so that $r(H)$
+/// is exactly symplectic for a hamiltonian $H$, and so are its successive squares. the symplecticity
+/// of the result is therefore only affected by rounding errors
+///
+/// # panics
+///
+/// panics if `H` is not square with an even dimension
+#[track_caller]
+pub fn expm_hamiltonian<T: RealField>(H: MatRef<'_, T>) -> Mat<T> {
+	assert!(all(H.nrows() == H.ncols(), H.nrows() % 2 == 0));
+	expm(H)
+}
+
+/// computes the eigenvalues of the hamiltonian matrix $H$ of dimension $2n$
+///
+/// the first $n$ eigenvalues have a nonnegative real part, and are sorted by increasing real part,
+/// then imaginary part. the last $n$ eigenvalues are their opposites, so that the symmetry of the
+/// spectrum is preserved exactly
+///
+/// # panics
+///
+/// panics if `H` is not square with an even dimension
+#[track_caller]
+#[math]
+pub fn hamiltonian_eigenvalues<T: RealField>(H: MatRef<'_, T>) -> Result<Col<Complex<T>>, EvdError> {
+	assert!(all(H.nrows() == H.ncols(), H.nrows() % 2 == 0));
+	let n = H.nrows() / 2;
+
+	let mut s = H.eigenvalues()?;
+
+	let mut half = alloc::vec::Vec::with_capacity(n);
+	while let Some(a) = s.pop() {
+		// pair a with the closest remaining eigenvalue to -a
+		let Some((j, _)) = s.iter().enumerate().min_by(|(_, x), (_, y)| {
+			let dx = abs(x.re + a.re) + abs(x.im + a.im);
+			let dy = abs(y.re + a.re) + abs(y.im + a.im);
+			dx.partial_cmp(&dy).unwrap_or(core::cmp::Ordering::Equal)
+		}) else {
+			break;
+		};
+		let b = s.swap_remove(j);
+
+		let half_ = from_f64::<T>(0.5);
+		let mut x = Complex::new((a.re - b.re) * half_, (a.im - b.im) * half_);
+		if x.re < zero() || (x.re == zero() && x.im < zero()) {
+			x = Complex::new(-x.re, -x.im);
+		}
+		half.push(x);
+	}
+
+	half.sort_by(|x, y| {
+		x.re.partial_cmp(&y.re)
+			.unwrap_or(core::cmp::Ordering::Equal)
+			.then(x.im.partial_cmp(&y.im).unwrap_or(core::cmp::Ordering::Equal))
+	});
+
+	Ok(Col::from_fn(2 * n, |i| {
+		if i < n {
+			copy(half[i])
+		} else {
+			Complex::new(-half[i - n].re, -half[i - n].im)
+		}
+	}))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_expm() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (n, scale) in [(1, 1.0), (4, 1e-3), (6, 0.1), (7, 0.5), (8, 1.0), (10, 10.0)] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng)
+				* Scale(c64::new(scale / n as f64, 0.0));
+
+			// compare with the eigendecomposition, which is well conditioned for random matrices
+			let evd = A.eigen().unwrap();
+			let X = evd.U();
+			let D = Mat::from_fn(n, n, |i, j| if i == j { evd.S()[i].exp() } else { c64::ZERO });
+			let expected = X * D * linalg::solvers::DenseSolveCore::inverse(&X.partial_piv_lu());
+
+			let E = expm(A.as_ref());
+			let approx_eq = CwiseMat(ApproxEq::eps() * 1e4 * (1.0 + expected.norm_max()));
+			assert!(E ~ expected);
+		}
+
+		// nilpotent matrix
+		let A = Mat::<f64>::from_fn(3, 3, |i, j| if j == i + 1 { 1.0 } else { 0.0 });
+		let E = expm(A.as_ref());
+		let approx_eq = CwiseMat(ApproxEq::eps() * 16.0);
+		assert!(E ~ Mat::from_fn(3, 3, |i, j| [[1.0, 1.0, 0.5], [0.0, 1.0, 1.0], [0.0, 0.0, 1.0]][i][j]));
+	}
+
+	#[test]
+	fn test_skew_symmetric() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [1, 2, 5, 8] {
+			let X = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			let mut A = &X - X.transpose();
+			// make the matrix singular
+			if n > 2 {
+				A.col_mut(0).fill(0.0);
+				A.row_mut(0).fill(0.0);
+			}
+
+			let schur = skew_symmetric_schur(A.as_ref()).unwrap();
+			let k = schur.angles.nrows();
+			let S = Mat::from_fn(n, n, |i, j| {
+				if i / 2 == j / 2 && i / 2 < k && i != j {
+					if i < j { schur.angles[i / 2] } else { -schur.angles[i / 2] }
+				} else {
+					0.0
+				}
+			});
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 256.0);
+			assert!(schur.Q.transpose() * &schur.Q ~ Mat::<f64>::identity(n, n));
+			assert!(&schur.Q * &S * schur.Q.transpose() ~ A);
+
+			let A = &A * Scale(20.0);
+			let E = expm_skew_symmetric(A.as_ref()).unwrap();
+			assert!(E.transpose() * &E ~ Mat::<f64>::identity(n, n));
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 1e5);
+			assert!(E ~ expm(A.as_ref()));
+		}
+	}
+
+	#[test]
+	fn test_hamiltonian() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 4;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let G = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let Q = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let G = &G + G.transpose();
+		let Q = &Q + Q.transpose();
+
+		let H = Mat::from_fn(2 * n, 2 * n, |i, j| match (i < n, j < n) {
+			(true, true) => A[(i, j)],
+			(true, false) => G[(i, j - n)],
+			(false, true) => Q[(i - n, j)],
+			(false, false) => -A[(j - n, i - n)],
+		});
+		let J = Mat::from_fn(2 * n, 2 * n, |i, j| {
+			if j == i + n {
+				1.0
+			} else if i == j + n {
+				-1.0
+			} else {
+				0.0
+			}
+		});
+
+		let M = expm_hamiltonian((&H * Scale(0.3)).as_ref());
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+		assert!(M.transpose() * &J * &M ~ J);
+
+		let s = hamiltonian_eigenvalues(H.as_ref()).unwrap();
+		let mut exact = H.eigenvalues().unwrap();
+		for i in 0..n {
+			assert!(s[i].re >= 0.0);
+			assert!(s[i + n] == -s[i]);
+		}
+		for i in 0..2 * n {
+			let (j, _) = exact
+				.iter()
+				.enumerate()
+				.min_by(|(_, x), (_, y)| (**x - s[i]).norm().partial_cmp(&(**y - s[i]).norm()).unwrap())
+				.unwrap();
+			assert!((exact[j] - s[i]).norm() < 1e-10);
+			exact.swap_remove(j);
+		}
+	}
+}
@@ -0,0 +1,507 @@
+//! fast fourier transform
+//!
+//! the forward transform of a vector $x$ of length $n$ is $X_k = \sum_j x_j e^{-2 \pi i j k / n}$,
+//! and the inverse transform includes the $1/n$ normalization, so that it exactly undoes the
+//! forward transform
+//!
+//! power of two lengths use an iterative radix-2 algorithm, and other lengths are reduced to a
+//! power of two convolution with bluestein's algorithm, so that all the transforms run in
+//! $O(n \log n)$ operations. a plan precomputes the twiddle factors for a given length, and can be
+//! reused for any number of transforms
+//!
+//! row vectors can be transformed through their transpose
+//!
+//! # example
+//! ```
+//! use faer::c64;
+//! use faer::{Col, col};
+//! use faer::linalg::fft::{Fft, rfft};
+//!
+//! let x = col![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+//! let X = rfft(x.as_ref());
+//! assert!(X.nrows() == 4);
+//! assert!((X[0] - c64::new(21.0, 0.0)).norm() < 1e-12);
+//!
+//! let plan = Fft::<f64>::new(6);
+//! let z = Col::from_fn(6, |j| c64::new(x[j], 0.0));
+//! let mut y = z.clone();
+//! plan.forward_in_place(y.as_mut());
+//! plan.inverse_in_place(y.as_mut());
+//! assert!((&y - &z).norm_max() < 1e-12);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use alloc::vec::Vec;
+
+/// returns $e^{-2 \pi i k / n}$
+fn root_of_unity<T: RealField>(k: usize, n: usize) -> Complex<T> {
+	let k = k % n;
+	let theta = -2.0 * core::f64::consts::PI * (k as f64) / (n as f64);
+	Complex::new(from_f64::<T>(libm::cos(theta)), from_f64::<T>(libm::sin(theta)))
+}
+
+fn bit_reverse_permute<T>(x: &mut [T]) {
+	let n = x.len();
+	let mut j = 0usize;
+	for i in 1..n {
+		let mut bit = n >> 1;
+		while j & bit != 0 {
+			j ^= bit;
+			bit >>= 1;
+		}
+		j |= bit;
+		if i < j {
+			x.swap(i, j);
+		}
+	}
+}
+
+/// computes the forward transform of `x`, whose length must be a power of two, given the twiddle
+/// factors $e^{-2 \pi i k / n}$ for $k < n / 2$
+fn radix2_in_place<T: RealField>(x: &mut [Complex<T>], twiddles: &[Complex<T>]) {
+	let n = x.len();
+	bit_reverse_permute(x);
+
+	let mut len = 2;
+	while len <= n {
+		let half = len / 2;
+		let step = n / len;
+		for start in (0..n).step_by(len) {
+			for j in 0..half {
+				let u = copy(&x[start + j]);
+				let v = mul(&x[start + j + half], &twiddles[j * step]);
+				x[start + j] = add(&u, &v);
+				x[start + j + half] = sub(&u, &v);
+			}
+		}
+		len *= 2;
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Bluestein<T> {
+	// e^{-i pi k^2 / n}, for k < n
+	chirp: Vec<Complex<T>>,
+	// transform of the conjugate chirp, wrapped around for the circular convolution, and
+	// premultiplied by the normalization factor of the inverse transform
+	kernel: Vec<Complex<T>>,
+}
+
+/// plan for the fast fourier transform of complex vectors of a given length
+#[derive(Clone, Debug)]
+pub struct Fft<T> {
+	n: usize,
+	// twiddle factors for the power of two length used by the radix-2 algorithm
+	twiddles: Vec<Complex<T>>,
+	bluestein: Option<Bluestein<T>>,
+}
+
+impl<T: RealField> Fft<T> {
+	/// returns a plan for the transforms of length `n`
+	pub fn new(n: usize) -> Self {
+		if n <= 1 || n.is_power_of_two() {
+			let twiddles = (0..n / 2).map(|k| root_of_unity(k, n)).collect();
+			return Self {
+				n,
+				twiddles,
+				bluestein: None,
+			};
+		}
+
+		let m = (2 * n - 1).next_power_of_two();
+		let twiddles: Vec<Complex<T>> = (0..m / 2).map(|k| root_of_unity(k, m)).collect();
+
+		// e^{-i pi k^2 / n} = e^{-2 pi i (k^2 mod 2n) / 2n}
+		let chirp: Vec<Complex<T>> = (0..n)
+			.map(|k| {
+				let k2 = ((k as u128 * k as u128) % (2 * n as u128)) as usize;
+				root_of_unity(k2, 2 * n)
+			})
+			.collect();
+
+		let scale = from_f64::<T>(1.0 / m as f64);
+		let mut kernel = alloc::vec![zero::<Complex<T>>(); m];
+		kernel[0] = mul_real(&conj(&chirp[0]), &scale);
+		for k in 1..n {
+			let w = mul_real(&conj(&chirp[k]), &scale);
+			kernel[m - k] = copy(&w);
+			kernel[k] = w;
+		}
+		radix2_in_place(&mut kernel, &twiddles);
+
+		Self {
+			n,
+			twiddles,
+			bluestein: Some(Bluestein { chirp, kernel }),
+		}
+	}
+
+	/// returns the length of the transforms
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.n
+	}
+
+	/// returns `true` if the length of the transforms is zero
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.n == 0
+	}
+
+	fn forward_slice(&self, x: &mut [Complex<T>]) {
+		match &self.bluestein {
+			None => radix2_in_place(x, &self.twiddles),
+			Some(Bluestein { chirp, kernel }) => {
+				let m = kernel.len();
+				let mut buf = alloc::vec![zero::<Complex<T>>(); m];
+				for k in 0..self.n {
+					buf[k] = mul(&x[k], &chirp[k]);
+				}
+
+				// circular convolution with the conjugate chirp, computed as the inverse transform of
+				// the product of the transforms
+				radix2_in_place(&mut buf, &self.twiddles);
+				for (b, k) in buf.iter_mut().zip(kernel.iter()) {
+					*b = conj(&mul(b, k));
+				}
+				radix2_in_place(&mut buf, &self.twiddles);
+
+				for k in 0..self.n {
+					x[k] = mul(&conj(&buf[k]), &chirp[k]);
+				}
+			},
+		}
+	}
+
+	/// computes the forward transform of `x`, and stores the result in `x`
+	///
+	/// # panics
+	///
+	/// panics if the length of `x` doesn't match the length of the plan
+	#[track_caller]
+	pub fn forward_in_place(&self, x: ColMut<'_, Complex<T>>) {
+		assert!(x.nrows() == self.n);
+		let mut x = x;
+		let mut buf: Vec<Complex<T>> = x.rb().iter().cloned().collect();
+		self.forward_slice(&mut buf);
+		for (dst, src) in x.rb_mut().iter_mut().zip(buf) {
+			*dst = src;
+		}
+	}
+
+	/// computes the inverse transform of `x`, and stores the result in `x`
+	///
+	/// # panics
+	///
+	/// panics if the length of `x` doesn't match the length of the plan
+	#[track_caller]
+	pub fn inverse_in_place(&self, x: ColMut<'_, Complex<T>>) {
+		assert!(x.nrows() == self.n);
+		let mut x = x;
+
+		// the inverse transform is the conjugate of the forward transform of the conjugate
+		let mut buf: Vec<Complex<T>> = x.rb().iter().map(conj).collect();
+		self.forward_slice(&mut buf);
+		let scale = from_f64::<T>(1.0 / self.n as f64);
+		for (dst, src) in x.rb_mut().iter_mut().zip(buf) {
+			*dst = mul_real(&conj(&src), &scale);
+		}
+	}
+
+	/// returns the forward transform of `x`
+	///
+	/// # panics
+	///
+	/// panics if the length of `x` doesn't match the length of the plan
+	#[track_caller]
+	pub fn forward(&self, x: ColRef<'_, impl Conjugate<Canonical = Complex<T>>>) -> Col<Complex<T>> {
+		let mut out = x.to_owned();
+		self.forward_in_place(out.as_mut());
+		out
+	}
+
+	/// returns the inverse transform of `x`
+	///
+	/// # panics
+	///
+	/// panics if the length of `x` doesn't match the length of the plan
+	#[track_caller]
+	pub fn inverse(&self, x: ColRef<'_, impl Conjugate<Canonical = Complex<T>>>) -> Col<Complex<T>> {
+		let mut out = x.to_owned();
+		self.inverse_in_place(out.as_mut());
+		out
+	}
+}
+
+/// plan for the fast fourier transform of real vectors of a given length
+///
+/// the transform of a real vector of length $n$ is conjugate symmetric, so only its first
+/// $\lfloor n / 2 \rfloor + 1$ coefficients are computed. for even lengths, the transform is
+/// computed with a complex transform of half the length
+#[derive(Clone, Debug)]
+pub struct RealFft<T> {
+	n: usize,
+	inner: Fft<T>,
+	// e^{-2 pi i k / n}, for k < n / 2, when n is even
+	twiddles: Vec<Complex<T>>,
+}
+
+impl<T: RealField> RealFft<T> {
+	/// returns a plan for the transforms of length `n`
+	pub fn new(n: usize) -> Self {
+		if n % 2 == 0 && n > 0 {
+			Self {
+				n,
+				inner: Fft::new(n / 2),
+				twiddles: (0..n / 2).map(|k| root_of_unity(k, n)).collect(),
+			}
+		} else {
+			Self {
+				n,
+				inner: Fft::new(n),
+				twiddles: Vec::new(),
+			}
+		}
+	}
+
+	/// returns the length of the real vectors
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.n
+	}
+
+	/// returns `true` if the length of the real vectors is zero
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.n == 0
+	}
+
+	/// returns the number of coefficients of the transform, $\lfloor n / 2 \rfloor + 1$
+	#[inline]
+	pub fn output_len(&self) -> usize {
+		if self.n == 0 { 0 } else { self.n / 2 + 1 }
+	}
+
+	/// returns the first $\lfloor n / 2 \rfloor + 1$ coefficients of the forward transform of `x`
+	///
+	/// # panics
+	///
+	/// panics if the length of `x` doesn't match the length of the plan
+	#[track_caller]
+	pub fn forward(&self, x: ColRef<'_, T>) -> Col<Complex<T>> {
+		assert!(x.nrows() == self.n);
+		let n = self.n;
+
+		if self.twiddles.is_empty() {
+			let mut buf: Vec<Complex<T>> = x.iter().map(|x| Complex::new(copy(x), zero())).collect();
+			self.inner.forward_slice(&mut buf);
+			return Col::from_fn(self.output_len(), |k| copy(&buf[k]));
+		}
+
+		// pack the even and odd entries into the real and imaginary parts of a half length vector
+		let h = n / 2;
+		let mut z: Vec<Complex<T>> = (0..h).map(|j| Complex::new(copy(&x[2 * j]), copy(&x[2 * j + 1]))).collect();
+		self.inner.forward_slice(&mut z);
+
+		let half = from_f64::<T>(0.5);
+		let minus_i = Complex::new(zero::<T>(), neg(&one::<T>()));
+		Col::from_fn(h + 1, |k| {
+			let zk = &z[k % h];
+			let zc = conj(&z[(h - k) % h]);
+			// transforms of the even and odd entries
+			let even = mul_real(&add(zk, &zc), &half);
+			let odd = mul(&mul_real(&sub(zk, &zc), &half), &minus_i);
+			let w = if k == h {
+				Complex::new(neg(&one::<T>()), zero())
+			} else {
+				copy(&self.twiddles[k])
+			};
+			add(&even, &mul(&w, &odd))
+		})
+	}
+
+	/// returns the real vector of length $n$ whose forward transform has the first
+	/// $\lfloor n / 2 \rfloor + 1$ coefficients `X`
+	///
+	/// the imaginary parts of the coefficients that must be real for the transform of a real
+	/// vector, i.e., the first one and, for even lengths, the last one, are ignored
+	///
+	/// # panics
+	///
+	/// panics if the length of `X` doesn't match the number of coefficients of the transform
+	#[track_caller]
+	pub fn inverse(&self, X: ColRef<'_, impl Conjugate<Canonical = Complex<T>>>) -> Col<T> {
+		let X = X.to_owned();
+		assert!(X.nrows() == self.output_len());
+		let n = self.n;
+		let scale = from_f64::<T>(1.0 / n as f64);
+
+		if self.twiddles.is_empty() {
+			// extend the coefficients by conjugate symmetry, and use the complex inverse transform
+			let mut buf: Vec<Complex<T>> = (0..n)
+				.map(|k| {
+					if k == 0 {
+						Complex::new(real(&X[0]), zero())
+					} else if k < X.nrows() {
+						conj(&X[k])
+					} else {
+						copy(&X[n - k])
+					}
+				})
+				.collect();
+			self.inner.forward_slice(&mut buf);
+			return Col::from_fn(n, |j| mul(&real(&buf[j]), &scale));
+		}
+
+		let h = n / 2;
+		let mut X = X;
+		X[0] = Complex::new(real(&X[0]), zero());
+		X[h] = Complex::new(real(&X[h]), zero());
+
+		let half = from_f64::<T>(0.5);
+		let i = Complex::new(zero::<T>(), one::<T>());
+		let mut z: Vec<Complex<T>> = (0..h)
+			.map(|k| {
+				let xk = &X[k];
+				let xc = conj(&X[h - k]);
+				let even = mul_real(&add(xk, &xc), &half);
+				let odd = mul(&mul_real(&sub(xk, &xc), &half), &conj(&self.twiddles[k]));
+				// the conjugate of the packed transform, for the inverse transform
+				conj(&add(&even, &mul(&i, &odd)))
+			})
+			.collect();
+		self.inner.forward_slice(&mut z);
+
+		let scale = from_f64::<T>(1.0 / h as f64);
+		Col::from_fn(n, |j| {
+			let zj = &z[j / 2];
+			if j % 2 == 0 {
+				mul(&real(zj), &scale)
+			} else {
+				mul(&neg(&imag(zj)), &scale)
+			}
+		})
+	}
+}
+
+/// returns the forward transform of `x`
+///
+/// see [`Fft`] to reuse the precomputations across several transforms of the same length
+#[track_caller]
+pub fn fft<T: RealField>(x: ColRef<'_, impl Conjugate<Canonical = Complex<T>>>) -> Col<Complex<T>> {
+	Fft::new(x.nrows()).forward(x)
+}
+
+/// returns the inverse transform of `x`
+///
+/// see [`Fft`] to reuse the precomputations across several transforms of the same length
+#[track_caller]
+pub fn ifft<T: RealField>(x: ColRef<'_, impl Conjugate<Canonical = Complex<T>>>) -> Col<Complex<T>> {
+	Fft::new(x.nrows()).inverse(x)
+}
+
+/// returns the first $\lfloor n / 2 \rfloor + 1$ coefficients of the forward transform of the real
+/// vector `x`
+///
+/// see [`RealFft`] to reuse the precomputations across several transforms of the same length
+#[track_caller]
+pub fn rfft<T: RealField>(x: ColRef<'_, T>) -> Col<Complex<T>> {
+	RealFft::new(x.nrows()).forward(x)
+}
+
+/// returns the real vector of length `n` whose forward transform has the first
+/// $\lfloor n / 2 \rfloor + 1$ coefficients `X`
+///
+/// see [`RealFft`] to reuse the precomputations across several transforms of the same length
+///
+/// # panics
+///
+/// panics if the length of `X` isn't $\lfloor n / 2 \rfloor + 1$ (or zero if `n` is zero)
+#[track_caller]
+pub fn irfft<T: RealField>(X: ColRef<'_, impl Conjugate<Canonical = Complex<T>>>, n: usize) -> Col<T> {
+	RealFft::new(n).inverse(X)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	fn naive_dft(x: ColRef<'_, c64>) -> Col<c64> {
+		let n = x.nrows();
+		Col::from_fn(n, |k| {
+			let mut acc = c64::new(0.0, 0.0);
+			for j in 0..n {
+				let theta = -2.0 * core::f64::consts::PI * ((j * k) % n) as f64 / n as f64;
+				acc += x[j] * c64::new(theta.cos(), theta.sin());
+			}
+			acc
+		})
+	}
+
+	#[test]
+	fn test_fft() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+
+		for n in [0, 1, 2, 3, 4, 5, 7, 8, 12, 17, 31, 64, 100, 127, 256] {
+			let x = CwiseColDistribution { nrows: n, dist }.rand::<Col<c64>>(rng);
+			let plan = Fft::<f64>::new(n);
+			let tol = 1e-13 * (n as f64).max(1.0);
+
+			let X = plan.forward(x.as_ref());
+			let expected = naive_dft(x.as_ref());
+			assert!((&X - &expected).norm_max() < tol * expected.norm_max().max(1.0));
+			assert!((fft(x.as_ref()) - &X).norm_max() == 0.0);
+
+			let y = plan.inverse(X.as_ref());
+			assert!((&y - &x).norm_max() < tol);
+
+			let y = ifft(x.conjugate());
+			let X = naive_dft(x.as_ref());
+			let expected = Col::from_fn(n, |k| X[k].conj() / n as f64);
+			assert!((&y - &expected).norm_max() < tol);
+		}
+	}
+
+	#[test]
+	fn test_rfft() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 4, 5, 6, 9, 10, 16, 30, 64, 99] {
+			let x = CwiseColDistribution {
+				nrows: n,
+				dist: StandardNormal,
+			}
+			.rand::<Col<f64>>(rng);
+			let plan = RealFft::<f64>::new(n);
+			let tol = 1e-13 * (n as f64).max(1.0);
+
+			let X = plan.forward(x.as_ref());
+			assert!(X.nrows() == plan.output_len());
+			let expected = naive_dft(Col::from_fn(n, |j| c64::new(x[j], 0.0)).as_ref());
+			for k in 0..X.nrows() {
+				assert!((X[k] - expected[k]).norm() < tol);
+			}
+
+			let y = irfft(X.as_ref(), n);
+			assert!((&y - &x).norm_max() < tol);
+			assert!((rfft(x.as_ref()) - &X).norm_max() == 0.0);
+		}
+
+		// row vectors, through their transpose
+		let x = Row::<f64>::from_fn(8, |j| j as f64);
+		let X = rfft(x.transpose());
+		assert!((X[0] - c64::new(28.0, 0.0)).norm() < 1e-12);
+		assert!((X[4] - c64::new(-4.0, 0.0)).norm() < 1e-12);
+	}
+
+	#[test]
+	fn test_fft_f32() {
+		let x = Col::<Complex<f32>>::from_fn(12, |j| Complex::new(j as f32, 1.0));
+		let y = ifft(fft(x.as_ref()).as_ref());
+		assert!((&y - &x).norm_max() < 1e-5);
+	}
+}
@@ -0,0 +1,529 @@
+use crate::Par;
+use crate::internal_prelude::*;
+use crate::linalg::matmul::semiring::{PlusTimes, Semiring};
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// field whose arithmetic is exact, such that gaussian elimination can be performed without
+/// worrying about the pivot magnitudes
+pub trait Field:
+	Copy + PartialEq + fmt::Debug + Send + Sync + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self> + 'static
+{
+	/// returns the additive identity
+	fn zero() -> Self;
+	/// returns the multiplicative identity
+	fn one() -> Self;
+	/// returns the multiplicative inverse of `self`, or `None` if it's zero
+	fn inv(self) -> Option<Self>;
+}
+
+/// element of the prime field $\mathbb{Z}/p\mathbb{Z}$
+///
+/// the value is stored in montgomery form, so that multiplications only need a single wide
+/// product and no division. `P` must be an odd prime smaller than $2^{63}$, which is checked at
+/// compile time except for the primality
+///
+/// # example
+/// ```
+/// use faer::linalg::finite_field::{Zp, nullspace};
+/// use faer::{Mat, Par};
+///
+/// type F = Zp<7>;
+/// assert!((F::new(3) * F::new(5)).value() == 1);
+///
+/// let A = Mat::from_fn(2, 3, |i, j| F::new((i + 2 * j) as u64));
+/// let N = nullspace(A.as_ref(), Par::Seq);
+/// assert!(N.ncols() == 1);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct Zp<const P: u64>(u64);
+
+impl<const P: u64> Zp<P> {
+	const CHECK: () = core::assert!(P % 2 == 1 && P > 2 && P < (1 << 63), "the modulus must be an odd prime smaller than 2^63");
+	/// the modulus
+	pub const MODULUS: u64 = P;
+	/// $-P^{-1} \bmod 2^{64}$
+	const NEG_INV: u64 = {
+		// newton's iteration doubles the number of correct bits, starting from 3 bits since
+		// `P * P = 1 (mod 8)` for odd `P`
+		let mut inv = P;
+		let mut i = 0;
+		while i < 5 {
+			inv = inv.wrapping_mul(2u64.wrapping_sub(P.wrapping_mul(inv)));
+			i += 1;
+		}
+		inv.wrapping_neg()
+	};
+	/// $2^{128} \bmod P$, used for converting to montgomery form
+	const R2: u64 = {
+		let r = ((1u128 << 64) % P as u128) as u64;
+		((r as u128 * r as u128) % P as u128) as u64
+	};
+
+	/// returns $x R^{-1} \bmod P$, for $x < P 2^{64}$
+	#[inline(always)]
+	const fn redc(x: u128) -> u64 {
+		let m = (x as u64).wrapping_mul(Self::NEG_INV);
+		// `x + m * P < 2^128` since `P < 2^63`
+		let t = ((x + m as u128 * P as u128) >> 64) as u64;
+		if t >= P { t - P } else { t }
+	}
+
+	/// returns the element equal to `value` modulo `P`
+	#[inline]
+	pub const fn new(value: u64) -> Self {
+		#[allow(clippy::let_unit_value)]
+		let () = Self::CHECK;
+		Self(Self::redc((value % P) as u128 * Self::R2 as u128))
+	}
+
+	/// returns the element equal to `value` modulo `P`
+	#[inline]
+	pub const fn from_i64(value: i64) -> Self {
+		Self::new(value.rem_euclid(P as i64) as u64)
+	}
+
+	/// returns the representative of `self` in $[0, P)$
+	#[inline]
+	pub const fn value(self) -> u64 {
+		Self::redc(self.0 as u128)
+	}
+
+	/// returns `self` raised to the power `exp`
+	#[inline]
+	pub fn pow(self, exp: u64) -> Self {
+		let mut base = self;
+		let mut exp = exp;
+		let mut acc = Self::new(1);
+		while exp > 0 {
+			if exp % 2 == 1 {
+				acc *= base;
+			}
+			base *= base;
+			exp /= 2;
+		}
+		acc
+	}
+}
+
+impl<const P: u64> From<u64> for Zp<P> {
+	#[inline]
+	fn from(value: u64) -> Self {
+		Self::new(value)
+	}
+}
+
+impl<const P: u64> fmt::Debug for Zp<P> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(&self.value(), f)
+	}
+}
+
+impl<const P: u64> fmt::Display for Zp<P> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.value(), f)
+	}
+}
+
+impl<const P: u64> Add for Zp<P> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn add(self, rhs: Self) -> Self {
+		let sum = self.0 + rhs.0;
+		Self(if sum >= P { sum - P } else { sum })
+	}
+}
+
+impl<const P: u64> Sub for Zp<P> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn sub(self, rhs: Self) -> Self {
+		Self(if self.0 >= rhs.0 { self.0 - rhs.0 } else { self.0 + P - rhs.0 })
+	}
+}
+
+impl<const P: u64> Neg for Zp<P> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn neg(self) -> Self {
+		Self(if self.0 == 0 { 0 } else { P - self.0 })
+	}
+}
+
+impl<const P: u64> Mul for Zp<P> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn mul(self, rhs: Self) -> Self {
+		Self(Self::redc(self.0 as u128 * rhs.0 as u128))
+	}
+}
+
+impl<const P: u64> Div for Zp<P> {
+	type Output = Self;
+
+	/// # panics
+	/// panics if `rhs` is zero
+	#[inline]
+	#[track_caller]
+	fn div(self, rhs: Self) -> Self {
+		Mul::mul(self, rhs.inv().expect("division by zero"))
+	}
+}
+
+macro_rules! impl_assign {
+	($($trait: ident, $method: ident, $op: ident);*) => {$(
+		impl<const P: u64> $trait for Zp<P> {
+			#[inline(always)]
+			#[track_caller]
+			fn $method(&mut self, rhs: Self) {
+				*self = (*self).$op(rhs);
+			}
+		}
+	)*};
+}
+
+impl_assign!(AddAssign, add_assign, add; SubAssign, sub_assign, sub; MulAssign, mul_assign, mul; DivAssign, div_assign, div);
+
+impl<const P: u64> Field for Zp<P> {
+	#[inline(always)]
+	fn zero() -> Self {
+		Self(0)
+	}
+
+	#[inline(always)]
+	fn one() -> Self {
+		Self::new(1)
+	}
+
+	#[inline]
+	fn inv(self) -> Option<Self> {
+		// fermat's little theorem
+		if self.0 == 0 { None } else { Some(self.pow(P - 2)) }
+	}
+}
+
+impl<const P: u64> Semiring<Zp<P>> for PlusTimes {
+	#[inline(always)]
+	fn zero(&self) -> Zp<P> {
+		Zp(0)
+	}
+
+	#[inline(always)]
+	fn one(&self) -> Zp<P> {
+		Zp::new(1)
+	}
+
+	#[inline(always)]
+	fn add(&self, lhs: &Zp<P>, rhs: &Zp<P>) -> Zp<P> {
+		*lhs + *rhs
+	}
+
+	#[inline(always)]
+	fn mul(&self, lhs: &Zp<P>, rhs: &Zp<P>) -> Zp<P> {
+		*lhs * *rhs
+	}
+}
+
+/// computes `dst[i] = dst[i] - factor * src[i]` for every row `i` of `src`, starting at `row_start`
+#[inline(always)]
+fn axpy<F: Field>(dst: MatMut<'_, F>, j: usize, src: &[F], row_start: usize, factor: F) {
+	let mut dst = dst;
+	let mut dst = dst.rb_mut().col_mut(j).subrows_mut(row_start, src.len());
+	match dst.rb_mut().try_as_col_major_mut() {
+		Some(dst) => {
+			// the loop is split into independent lanes so that the compiler can interleave the
+			// wide multiplications
+			let dst = dst.as_slice_mut();
+			let (dst_head, dst_tail) = dst.split_at_mut(dst.len() / 4 * 4);
+			let (src_head, src_tail) = src.split_at(dst_head.len());
+			for (dst, src) in dst_head.chunks_exact_mut(4).zip(src_head.chunks_exact(4)) {
+				dst[0] = dst[0] - factor * src[0];
+				dst[1] = dst[1] - factor * src[1];
+				dst[2] = dst[2] - factor * src[2];
+				dst[3] = dst[3] - factor * src[3];
+			}
+			for (dst, src) in dst_tail.iter_mut().zip(src_tail) {
+				*dst = *dst - factor * *src;
+			}
+		},
+		None => {
+			for (i, src) in src.iter().enumerate() {
+				dst[i] = dst[i] - factor * *src;
+			}
+		},
+	}
+}
+
+/// eliminates the entries of the rows `row_start..` of the columns `col_start..` of `mat`, using
+/// the multipliers in `mult` and the pivot row `pivot_row`
+fn eliminate<F: Field>(mat: MatMut<'_, F>, pivot_row: usize, col_start: usize, row_start: usize, mult: &[F], par: Par) {
+	let ncols = mat.ncols() - col_start;
+	let mat = mat.subcols_mut(col_start, ncols);
+
+	let imp = |mut mat: MatMut<'_, F>| {
+		for j in 0..mat.ncols() {
+			let factor = mat[(pivot_row, j)];
+			if factor != F::zero() {
+				axpy(mat.rb_mut(), j, mult, row_start, factor);
+			}
+		}
+	};
+
+	match par {
+		Par::Seq => imp(mat),
+		#[cfg(feature = "rayon")]
+		Par::Rayon(nthreads) => {
+			use rayon::prelude::*;
+			let nthreads = Ord::min(nthreads.get(), ncols);
+			if nthreads <= 1 {
+				imp(mat);
+			} else {
+				mat.par_col_partition_mut(nthreads).for_each(imp);
+			}
+		},
+	}
+}
+
+fn swap_rows<F: Field>(mat: MatMut<'_, F>, i: usize, k: usize) {
+	let mut mat = mat;
+	if i != k {
+		for j in 0..mat.ncols() {
+			let tmp = mat[(i, j)];
+			mat[(i, j)] = mat[(k, j)];
+			mat[(k, j)] = tmp;
+		}
+	}
+}
+
+/// reduces `mat` to its reduced row echelon form in place, and returns the columns of the pivots
+///
+/// the number of pivots is the rank of the matrix
+pub fn rref<F: Field>(mat: MatMut<'_, F>, par: Par) -> alloc::vec::Vec<usize> {
+	let mut mat = mat;
+	let (m, n) = mat.shape();
+	let mut pivots = alloc::vec::Vec::new();
+	let mut mult = alloc::vec::Vec::with_capacity(m);
+
+	let mut r = 0;
+	for col in 0..n {
+		if r == m {
+			break;
+		}
+		let Some(pi) = (r..m).find(|&i| mat[(i, col)] != F::zero()) else {
+			continue;
+		};
+		swap_rows(mat.rb_mut(), r, pi);
+
+		// normalize the pivot row
+		let inv = mat[(r, col)].inv().unwrap();
+		for j in col..n {
+			mat[(r, j)] = mat[(r, j)] * inv;
+		}
+
+		// the pivot row is included, with a multiplier of zero so that it's left untouched
+		mult.clear();
+		mult.extend((0..m).map(|i| if i == r { F::zero() } else { mat[(i, col)] }));
+		eliminate(mat.rb_mut(), r, col, 0, &mult, par);
+
+		pivots.push(col);
+		r += 1;
+	}
+	pivots
+}
+
+/// returns the rank of `mat`
+pub fn rank<F: Field>(mat: MatRef<'_, F>, par: Par) -> usize {
+	let mut mat = Mat::from_fn(mat.nrows(), mat.ncols(), |i, j| mat[(i, j)]);
+	rref(mat.as_mut(), par).len()
+}
+
+/// returns a basis of the nullspace of `mat`, stored in the columns of the output
+pub fn nullspace<F: Field>(mat: MatRef<'_, F>, par: Par) -> Mat<F> {
+	let n = mat.ncols();
+	let mut r = Mat::from_fn(mat.nrows(), n, |i, j| mat[(i, j)]);
+	let pivots = rref(r.as_mut(), par);
+
+	let mut is_pivot = alloc::vec![false; n];
+	for &col in &pivots {
+		is_pivot[col] = true;
+	}
+	let free = (0..n).filter(|&j| !is_pivot[j]).collect::<alloc::vec::Vec<_>>();
+
+	// each free variable is set to one in turn, and the pivot variables are solved for
+	let mut basis = Mat::from_fn(n, free.len(), |_, _| F::zero());
+	for (k, &col) in free.iter().enumerate() {
+		basis[(col, k)] = F::one();
+		for (i, &pivot) in pivots.iter().enumerate() {
+			basis[(pivot, k)] = -r[(i, col)];
+		}
+	}
+	basis
+}
+
+/// $LU$ decomposition over a field
+#[derive(Clone, Debug)]
+pub struct FieldLu<F> {
+	/// the unit lower triangular factor $L$
+	pub l: Mat<F>,
+	/// the factor $U$, in row echelon form
+	pub u: Mat<F>,
+	/// the row permutation $P$, such that $PA = LU$
+	pub row_perm: Perm<usize>,
+	/// the rank of $A$
+	pub rank: usize,
+}
+
+/// computes the $LU$ decomposition of `mat`, using gaussian elimination with row pivoting
+pub fn lu<F: Field>(mat: MatRef<'_, F>, par: Par) -> FieldLu<F> {
+	let (m, n) = mat.shape();
+	let mut u = Mat::from_fn(m, n, |i, j| mat[(i, j)]);
+	let mut l = Mat::from_fn(m, m, |_, _| F::zero());
+	let mut perm = (0..m).collect::<alloc::vec::Vec<_>>();
+	let mut mult = alloc::vec::Vec::with_capacity(m);
+
+	let mut r = 0;
+	for col in 0..n {
+		if r == m {
+			break;
+		}
+		let Some(pi) = (r..m).find(|&i| u[(i, col)] != F::zero()) else {
+			continue;
+		};
+		swap_rows(u.as_mut(), r, pi);
+		swap_rows(l.as_mut(), r, pi);
+		perm.swap(r, pi);
+
+		let inv = u[(r, col)].inv().unwrap();
+		mult.clear();
+		mult.extend((r + 1..m).map(|i| u[(i, col)] * inv));
+		for (i, &f) in (r + 1..m).zip(&mult) {
+			l[(i, r)] = f;
+		}
+		eliminate(u.as_mut(), r, col, r + 1, &mult, par);
+
+		r += 1;
+	}
+	for i in 0..m {
+		l[(i, i)] = F::one();
+	}
+
+	let mut inv = alloc::vec![0usize; m];
+	for (i, &p) in perm.iter().enumerate() {
+		inv[p] = i;
+	}
+
+	FieldLu {
+		l,
+		u,
+		row_perm: Perm::new_checked(perm.into_boxed_slice(), inv.into_boxed_slice(), m),
+		rank: r,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::linalg::matmul::semiring;
+	use crate::stats::prelude::*;
+
+	const P: u64 = 1_000_000_007;
+	const Q: u64 = (1 << 61) - 1;
+
+	type F = Zp<P>;
+
+	fn matmul<const P: u64>(lhs: &Mat<Zp<P>>, rhs: &Mat<Zp<P>>) -> Mat<Zp<P>> {
+		let mut dst = Mat::from_fn(lhs.nrows(), rhs.ncols(), |_, _| Zp::new(0));
+		semiring::matmul(&mut dst, Accum::Replace, lhs, rhs, PlusTimes, Par::Seq);
+		dst
+	}
+
+	fn random<const P: u64>(rng: &mut StdRng, m: usize, n: usize) -> Mat<Zp<P>> {
+		Mat::from_fn(m, n, |_, _| Zp::new(rng.gen_range(0..P)))
+	}
+
+	#[test]
+	fn test_arithmetic() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for _ in 0..1000 {
+			let (a, b) = (rng.gen_range(0..Q), rng.gen_range(0..Q));
+			let (x, y) = (Zp::<Q>::new(a), Zp::<Q>::new(b));
+			let (a, b, q) = (a as u128, b as u128, Q as u128);
+
+			assert!((x + y).value() as u128 == (a + b) % q);
+			assert!((x - y).value() as u128 == (a + q - b) % q);
+			assert!((x * y).value() as u128 == (a * b) % q);
+			assert!((-x).value() as u128 == (q - a) % q);
+			if b != 0 {
+				assert!((x / y) * y == x);
+			}
+		}
+
+		assert!(F::new(P + 3) == F::new(3));
+		assert!(F::from_i64(-1).value() == P - 1);
+		assert!(F::new(0).inv().is_none());
+		assert!(F::new(3).pow(P - 1) == F::new(1));
+	}
+
+	#[test]
+	fn test_lu() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(0, 3), (5, 5), (4, 7), (40, 30)] {
+			for par in [Par::Seq, Par::rayon(4)] {
+				let mut A = random::<P>(rng, m, n);
+				if m > 0 {
+					// make the matrix rank deficient
+					for j in 0..n {
+						A[(m - 1, j)] = A[(0, j)] * F::new(3);
+					}
+				}
+
+				let FieldLu { l, u, row_perm, rank: r } = lu(A.as_ref(), par);
+				assert!(r == Ord::min(m.saturating_sub(1), n));
+				assert!(r == rank(A.as_ref(), par));
+
+				let fwd = row_perm.as_ref().arrays().0;
+				assert!(matmul(&l, &u) == Mat::from_fn(m, n, |i, j| A[(fwd[i], j)]));
+				for j in 0..m {
+					assert!(l[(j, j)] == F::one());
+					for i in 0..j {
+						assert!(l[(i, j)] == F::zero());
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_nullspace() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(3, 6), (6, 6), (10, 4)] {
+			for par in [Par::Seq, Par::rayon(4)] {
+				// `A = B * C` has rank at most 3
+				let A = matmul(&random::<Q>(rng, m, 3), &random::<Q>(rng, 3, n));
+				let N = nullspace(A.as_ref(), par);
+
+				let r = rank(A.as_ref(), par);
+				assert!(N.ncols() == n - r);
+				assert!(rank(N.as_ref(), par) == n - r);
+				assert!(matmul(&A, &N) == Mat::from_fn(m, n - r, |_, _| Zp::new(0)));
+
+				let mut R = A.clone();
+				let pivots = rref(R.as_mut(), par);
+				for (i, &col) in pivots.iter().enumerate() {
+					for k in 0..m {
+						assert!(R[(k, col)] == if k == i { Zp::one() } else { Zp::zero() });
+					}
+				}
+			}
+		}
+	}
+}
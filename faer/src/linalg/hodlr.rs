@@ -0,0 +1,627 @@
+//! hierarchically off-diagonal low-rank (hodlr) matrices
+//!
+//! **experimental**: the api of this module may change in future releases
+//!
+//! a hodlr matrix is split recursively into $2 \times 2$ blocks, where the diagonal blocks are
+//! split further and the off-diagonal blocks are stored as low-rank products $U V^H$. kernel
+//! matrices $K_{ij} = k(x_i, x_j)$ arising from smooth kernels, e.g., in $n$-body problems or
+//! gaussian processes, are well approximated by hodlr matrices when the points are sorted along a
+//! space-filling order, so that consecutive indices refer to nearby points
+//!
+//! the off-diagonal blocks are compressed with the randomized range finder, which only needs the
+//! products of each block with a few vectors. the entries of a block are generated panel by panel
+//! from the kernel callback during these products, so a block is never stored in full. once
+//! compressed, a matrix of dimension $n$ and off-diagonal rank $r$ uses $O(n r \log n)$ storage,
+//! and products with it cost as many operations
+//!
+//! the matrix can then be factorized with [`Hodlr::factorize`], which applies the woodbury identity
+//! recursively at each level, so that solves cost $O(n r \log n)$ operations after an
+//! $O(n r^2 \log^2 n)$ factorization
+//!
+//! # example
+//! ```
+//! use faer::Par;
+//! use faer::linalg::hodlr::Hodlr;
+//! use faer::linalg::solvers::Solve;
+//! use faer::stats::prelude::*;
+//!
+//! let n = 1000;
+//! let x = |i: usize| i as f64 / n as f64;
+//! // exponential kernel on sorted points, with a regularization of the diagonal
+//! let kernel = |i: usize, j: usize| (-(x(i) - x(j)).abs()).exp() + if i == j { 1e-2 } else { 0.0 };
+//!
+//! let rng = &mut StdRng::seed_from_u64(0);
+//! let A = Hodlr::from_kernel(n, kernel, Default::default(), rng, Par::Seq).unwrap();
+//! let lu = A.factorize();
+//!
+//! let b = faer::Mat::<f64>::ones(n, 1);
+//! let x = lu.solve(&b);
+//!
+//! let dense = faer::Mat::from_fn(n, n, kernel);
+//! assert!((&dense * &x - &b).norm_max() < 1e-8);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::linalg::solvers::{PartialPivLu, ShapeCore, SolveCore, SvdError};
+use crate::matrix_free::{BiLinOp, LinOp};
+use crate::{Par, get_global_parallelism};
+use alloc::boxed::Box;
+use rand::Rng;
+
+/// hodlr compression parameters
+#[derive(Copy, Clone, Debug)]
+pub struct HodlrParams<T: RealField> {
+	/// blocks of dimension at most `leaf_size` are stored as dense matrices
+	pub leaf_size: usize,
+	/// maximum rank of the off-diagonal blocks
+	pub max_rank: usize,
+	/// number of additional random samples used by the range finder, which improves the accuracy
+	/// of the compressed blocks
+	pub oversampling: usize,
+	/// singular values of an off-diagonal block below `rel_tolerance` times its largest singular
+	/// value are truncated
+	pub rel_tolerance: T,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for HodlrParams<T> {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			leaf_size: 64,
+			max_rank: 32,
+			oversampling: 8,
+			rel_tolerance: sqrt(&eps::<T>()),
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// off-diagonal blocks $A_{12} \approx U_{12} V_{12}^H$ and $A_{21} \approx U_{21} V_{21}^H$
+#[derive(Clone, Debug)]
+struct OffDiag<T> {
+	U12: Mat<T>,
+	V12: Mat<T>,
+	U21: Mat<T>,
+	V21: Mat<T>,
+}
+
+#[derive(Clone, Debug)]
+enum Node<T> {
+	Leaf(Mat<T>),
+	Split {
+		// dimension of the first diagonal block
+		mid: usize,
+		children: Box<[Node<T>; 2]>,
+		off_diag: OffDiag<T>,
+	},
+}
+
+/// hierarchically off-diagonal low-rank matrix
+///
+/// see the [module level documentation](self)
+#[derive(Clone, Debug)]
+pub struct Hodlr<T> {
+	dim: usize,
+	root: Node<T>,
+}
+
+/// approximate factorization of a [`Hodlr`] matrix
+///
+/// the diagonal leaves are factorized with [`PartialPivLu`], and the low-rank off-diagonal blocks
+/// are handled with the woodbury identity at each level
+#[derive(Clone, Debug)]
+pub struct HodlrFactor<T> {
+	dim: usize,
+	root: FactorNode<T>,
+}
+
+#[derive(Clone, Debug)]
+enum FactorNode<T> {
+	Leaf(PartialPivLu<T>),
+	Split {
+		mid: usize,
+		children: Box<[FactorNode<T>; 2]>,
+		off_diag: Box<OffDiag<T>>,
+		// A11^{-1} U12 and A22^{-1} U21
+		Z1: Mat<T>,
+		Z2: Mat<T>,
+		// capacitance matrix [I, V12^H Z2; V21^H Z1, I]
+		K: Box<PartialPivLu<T>>,
+	},
+}
+
+/// number of columns of the block that are generated from the kernel at once
+const PANEL_SIZE: usize = 64;
+
+/// fills `dst` with the entries of the block starting at `(row_start, col_start)`
+fn eval_block<T: ComplexField>(dst: MatMut<'_, T>, row_start: usize, col_start: usize, kernel: &dyn Fn(usize, usize) -> T) {
+	let mut dst = dst;
+	for j in 0..dst.ncols() {
+		for i in 0..dst.nrows() {
+			dst[(i, j)] = kernel(row_start + i, col_start + j);
+		}
+	}
+}
+
+/// returns the compressed form $U V^H$ of the block with the given position and shape
+#[allow(clippy::too_many_arguments)]
+fn compress<T: ComplexField>(
+	row_start: usize,
+	col_start: usize,
+	m: usize,
+	n: usize,
+	kernel: &dyn Fn(usize, usize) -> T,
+	params: &HodlrParams<T::Real>,
+	rng: &mut dyn rand::RngCore,
+	par: Par,
+) -> Result<(Mat<T>, Mat<T>), SvdError> {
+	let s = Ord::min(params.max_rank + params.oversampling, Ord::min(m, n));
+	let Omega = Mat::<T>::random_normal(rng, n, s);
+
+	// Y = B Omega
+	let mut Y = Mat::<T>::zeros(m, s);
+	let mut panel = Mat::<T>::zeros(m, PANEL_SIZE);
+	for j in (0..n).step_by(PANEL_SIZE) {
+		let w = Ord::min(PANEL_SIZE, n - j);
+		let mut panel = panel.as_mut().subcols_mut(0, w);
+		eval_block(panel.rb_mut(), row_start, col_start + j, kernel);
+		linalg::matmul::matmul(Y.as_mut(), Accum::Add, panel.rb(), Omega.subrows(j, w), one(), par);
+	}
+	let Q = Y.qr().compute_thin_Q();
+
+	// B ≈ Q Q^H B = Q Z^H, with Z = B^H Q
+	let mut Z = Mat::<T>::zeros(n, s);
+	for j in (0..n).step_by(PANEL_SIZE) {
+		let w = Ord::min(PANEL_SIZE, n - j);
+		let mut panel = panel.as_mut().subcols_mut(0, w);
+		eval_block(panel.rb_mut(), row_start, col_start + j, kernel);
+		linalg::matmul::matmul(Z.as_mut().subrows_mut(j, w), Accum::Replace, panel.rb().adjoint(), Q.as_ref(), one(), par);
+	}
+
+	// truncate the rank using the svd of the small factor, Z = W S X^H, so that B ≈ (Q X S) W^H
+	let svd = Z.thin_svd()?;
+	let S = svd.S().column_vector();
+	let smax = if s > 0 { real(&S[0]) } else { zero() };
+	let tol = mul(&params.rel_tolerance, &smax);
+	let mut rank = 0;
+	while rank < Ord::min(s, params.max_rank) && real(&S[rank]) > tol {
+		rank += 1;
+	}
+
+	let mut U = Mat::<T>::zeros(m, rank);
+	linalg::matmul::matmul(U.as_mut(), Accum::Replace, Q.as_ref(), svd.V().subcols(0, rank), one(), par);
+	for k in 0..rank {
+		let sk = copy(&S[k]);
+		for i in 0..m {
+			U[(i, k)] = mul(&U[(i, k)], &sk);
+		}
+	}
+	let V = svd.U().subcols(0, rank).to_owned();
+
+	Ok((U, V))
+}
+
+fn build<T: ComplexField>(
+	start: usize,
+	n: usize,
+	kernel: &dyn Fn(usize, usize) -> T,
+	params: &HodlrParams<T::Real>,
+	rng: &mut dyn rand::RngCore,
+	par: Par,
+) -> Result<Node<T>, SvdError> {
+	if n <= Ord::max(params.leaf_size, 1) {
+		let mut D = Mat::<T>::zeros(n, n);
+		eval_block(D.as_mut(), start, start, kernel);
+		return Ok(Node::Leaf(D));
+	}
+
+	let mid = n / 2;
+	let (U12, V12) = compress(start, start + mid, mid, n - mid, kernel, params, rng, par)?;
+	let (U21, V21) = compress(start + mid, start, n - mid, mid, kernel, params, rng, par)?;
+	let children = Box::new([
+		build(start, mid, kernel, params, rng, par)?,
+		build(start + mid, n - mid, kernel, params, rng, par)?,
+	]);
+
+	Ok(Node::Split {
+		mid,
+		children,
+		off_diag: OffDiag { U12, V12, U21, V21 },
+	})
+}
+
+impl<T: ComplexField> Hodlr<T> {
+	/// returns the hodlr approximation of the $n \times n$ matrix with entries `kernel(i, j)`
+	///
+	/// each entry of the off-diagonal blocks is evaluated twice, and each entry of the dense
+	/// leaves once
+	#[track_caller]
+	pub fn from_kernel(
+		n: usize,
+		kernel: impl Fn(usize, usize) -> T,
+		params: HodlrParams<T::Real>,
+		rng: &mut (impl ?Sized + Rng),
+		par: Par,
+	) -> Result<Self, SvdError> {
+		let root = build(0, n, &kernel, &params, &mut { rng } as &mut dyn rand::RngCore, par)?;
+		Ok(Self { dim: n, root })
+	}
+
+	/// returns the dimension of the matrix
+	#[inline]
+	pub fn nrows(&self) -> usize {
+		self.dim
+	}
+
+	/// returns the dimension of the matrix
+	#[inline]
+	pub fn ncols(&self) -> usize {
+		self.dim
+	}
+
+	/// returns the largest rank among the off-diagonal blocks
+	pub fn max_rank(&self) -> usize {
+		fn imp<T>(node: &Node<T>) -> usize {
+			match node {
+				Node::Leaf(_) => 0,
+				Node::Split { children, off_diag, .. } => Ord::max(
+					Ord::max(off_diag.U12.ncols(), off_diag.U21.ncols()),
+					Ord::max(imp(&children[0]), imp(&children[1])),
+				),
+			}
+		}
+		imp(&self.root)
+	}
+
+	/// returns the number of scalars stored in the compressed representation
+	pub fn stored_len(&self) -> usize {
+		fn imp<T>(node: &Node<T>) -> usize {
+			match node {
+				Node::Leaf(D) => D.nrows() * D.ncols(),
+				Node::Split { children, off_diag, .. } => {
+					let OffDiag { U12, V12, U21, V21 } = off_diag;
+					[U12, V12, U21, V21].iter().map(|M| M.nrows() * M.ncols()).sum::<usize>() + imp(&children[0]) + imp(&children[1])
+				},
+			}
+		}
+		imp(&self.root)
+	}
+
+	/// returns the matrix as a dense matrix
+	pub fn to_dense(&self) -> Mat<T> {
+		fn imp<T: ComplexField>(node: &Node<T>, dst: MatMut<'_, T>) {
+			let mut dst = dst;
+			match node {
+				Node::Leaf(D) => dst.copy_from(D),
+				Node::Split { mid, children, off_diag } => {
+					let mid = *mid;
+					let (A11, A12, A21, A22) = dst.rb_mut().split_at_mut(mid, mid);
+					imp(&children[0], A11);
+					imp(&children[1], A22);
+					linalg::matmul::matmul(A12, Accum::Replace, &off_diag.U12, off_diag.V12.adjoint(), one(), Par::Seq);
+					linalg::matmul::matmul(A21, Accum::Replace, &off_diag.U21, off_diag.V21.adjoint(), one(), Par::Seq);
+				},
+			}
+		}
+
+		let mut out = Mat::zeros(self.dim, self.dim);
+		imp(&self.root, out.as_mut());
+		out
+	}
+
+	/// returns the approximate factorization of the matrix
+	///
+	/// the factorization doesn't fail, but its solutions are not finite if one of the diagonal
+	/// leaves or capacitance matrices is singular
+	pub fn factorize(&self) -> HodlrFactor<T> {
+		let par = get_global_parallelism();
+
+		fn imp<T: ComplexField>(node: &Node<T>, par: Par) -> FactorNode<T> {
+			match node {
+				Node::Leaf(D) => FactorNode::Leaf(PartialPivLu::new(D.as_ref())),
+				Node::Split { mid, children, off_diag } => {
+					let children = Box::new([imp(&children[0], par), imp(&children[1], par)]);
+					let OffDiag { U12, V12, U21, V21 } = off_diag;
+					let (k1, k2) = (U12.ncols(), U21.ncols());
+
+					let mut Z1 = U12.clone();
+					let mut Z2 = U21.clone();
+					children[0].solve(Conj::No, Z1.as_mut(), par);
+					children[1].solve(Conj::No, Z2.as_mut(), par);
+
+					let mut K = Mat::<T>::identity(k1 + k2, k1 + k2);
+					let (_, K12, K21, _) = K.as_mut().split_at_mut(k1, k1);
+					linalg::matmul::matmul(K12, Accum::Replace, V12.adjoint(), &Z2, one(), par);
+					linalg::matmul::matmul(K21, Accum::Replace, V21.adjoint(), &Z1, one(), par);
+
+					FactorNode::Split {
+						mid: *mid,
+						children,
+						off_diag: Box::new(off_diag.clone()),
+						Z1,
+						Z2,
+						K: Box::new(PartialPivLu::new(K.as_ref())),
+					}
+				},
+			}
+		}
+
+		HodlrFactor {
+			dim: self.dim,
+			root: imp(&self.root, par),
+		}
+	}
+}
+
+impl<T: ComplexField> Node<T> {
+	/// computes `out = op(self) rhs`, where `op` conjugates and/or transposes
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, conj: Conj, transpose: bool, par: Par) {
+		let mut out = out;
+		match self {
+			Node::Leaf(D) => {
+				let D = if transpose { D.transpose() } else { D.as_ref() };
+				linalg::matmul::matmul_with_conj(out, Accum::Replace, D, conj, rhs, Conj::No, one(), par);
+			},
+			Node::Split { mid, children, off_diag } => {
+				let mid = *mid;
+				let (mut out1, mut out2) = out.rb_mut().split_at_row_mut(mid);
+				let (rhs1, rhs2) = rhs.split_at_row(mid);
+				children[0].apply(out1.rb_mut(), rhs1, conj, transpose, par);
+				children[1].apply(out2.rb_mut(), rhs2, conj, transpose, par);
+
+				// out1 += op(A12) rhs2 and out2 += op(A21) rhs1, where op(U V^H) is either U V^H,
+				// or conj(V) U^T
+				let OffDiag { U12, V12, U21, V21 } = off_diag;
+				let (U12, V12, U21, V21) = if transpose { (V21, U21, V12, U12) } else { (U12, V12, U21, V21) };
+				let (conj_l, conj_r) = if transpose {
+					(Conj::Yes.compose(conj), conj)
+				} else {
+					(conj, Conj::Yes.compose(conj))
+				};
+
+				for (out, rhs, U, V) in [(out1, rhs2, U12, V12), (out2, rhs1, U21, V21)] {
+					let mut t = Mat::<T>::zeros(V.ncols(), rhs.ncols());
+					linalg::matmul::matmul_with_conj(t.as_mut(), Accum::Replace, V.transpose(), conj_r, rhs, Conj::No, one(), par);
+					linalg::matmul::matmul_with_conj(out, Accum::Add, U.as_ref(), conj_l, t.as_ref(), Conj::No, one(), par);
+				}
+			},
+		}
+	}
+}
+
+impl<T: ComplexField> FactorNode<T> {
+	fn solve(&self, conj: Conj, rhs: MatMut<'_, T>, par: Par) {
+		let mut rhs = rhs;
+		match self {
+			FactorNode::Leaf(lu) => lu.solve_in_place_with_conj(conj, rhs),
+			FactorNode::Split {
+				mid,
+				children,
+				off_diag,
+				Z1,
+				Z2,
+				K,
+			} => {
+				let mid = *mid;
+				let k1 = Z1.ncols();
+				let ncols = rhs.ncols();
+
+				// y = D^{-1} b
+				{
+					let (y1, y2) = rhs.rb_mut().split_at_row_mut(mid);
+					children[0].solve(conj, y1, par);
+					children[1].solve(conj, y2, par);
+				}
+
+				// x = y - Z K^{-1} W y
+				let (mut y1, mut y2) = rhs.rb_mut().split_at_row_mut(mid);
+				let mut s = Mat::<T>::zeros(k1 + Z2.ncols(), ncols);
+				{
+					let (s1, s2) = s.as_mut().split_at_row_mut(k1);
+					let c = Conj::Yes.compose(conj);
+					linalg::matmul::matmul_with_conj(s1, Accum::Replace, off_diag.V12.transpose(), c, y2.rb(), Conj::No, one(), par);
+					linalg::matmul::matmul_with_conj(s2, Accum::Replace, off_diag.V21.transpose(), c, y1.rb(), Conj::No, one(), par);
+				}
+				K.solve_in_place_with_conj(conj, s.as_mut());
+				let (s1, s2) = s.as_ref().split_at_row(k1);
+				linalg::matmul::matmul_with_conj(y1.rb_mut(), Accum::Add, Z1.as_ref(), conj, s1, Conj::No, neg(&one::<T>()), par);
+				linalg::matmul::matmul_with_conj(y2.rb_mut(), Accum::Add, Z2.as_ref(), conj, s2, Conj::No, neg(&one::<T>()), par);
+			},
+		}
+	}
+
+	fn solve_transpose(&self, conj: Conj, rhs: MatMut<'_, T>, par: Par) {
+		let mut rhs = rhs;
+		match self {
+			FactorNode::Leaf(lu) => lu.solve_transpose_in_place_with_conj(conj, rhs),
+			FactorNode::Split {
+				mid,
+				children,
+				off_diag,
+				Z1,
+				Z2,
+				K,
+			} => {
+				let mid = *mid;
+				let k1 = Z1.ncols();
+				let ncols = rhs.ncols();
+
+				// x = D^{-T} (b - W^T K^{-T} Z^T b)
+				let (mut b1, mut b2) = rhs.rb_mut().split_at_row_mut(mid);
+				let mut t = Mat::<T>::zeros(k1 + Z2.ncols(), ncols);
+				{
+					let (t1, t2) = t.as_mut().split_at_row_mut(k1);
+					linalg::matmul::matmul_with_conj(t1, Accum::Replace, Z1.transpose(), conj, b1.rb(), Conj::No, one(), par);
+					linalg::matmul::matmul_with_conj(t2, Accum::Replace, Z2.transpose(), conj, b2.rb(), Conj::No, one(), par);
+				}
+				K.solve_transpose_in_place_with_conj(conj, t.as_mut());
+				let (t1, t2) = t.as_ref().split_at_row(k1);
+				let c = Conj::Yes.compose(conj);
+				linalg::matmul::matmul_with_conj(b1.rb_mut(), Accum::Add, off_diag.V21.as_ref(), c, t2, Conj::No, neg(&one::<T>()), par);
+				linalg::matmul::matmul_with_conj(b2.rb_mut(), Accum::Add, off_diag.V12.as_ref(), c, t1, Conj::No, neg(&one::<T>()), par);
+
+				children[0].solve_transpose(conj, b1, par);
+				children[1].solve_transpose(conj, b2, par);
+			},
+		}
+	}
+}
+
+impl<T: ComplexField> LinOp<T> for Hodlr<T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.dim
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.dim
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		assert!(all(out.nrows() == self.dim, rhs.nrows() == self.dim, out.ncols() == rhs.ncols()));
+		self.root.apply(out, rhs, Conj::No, false, par);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		assert!(all(out.nrows() == self.dim, rhs.nrows() == self.dim, out.ncols() == rhs.ncols()));
+		self.root.apply(out, rhs, Conj::Yes, false, par);
+	}
+}
+
+impl<T: ComplexField> BiLinOp<T> for Hodlr<T> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		assert!(all(out.nrows() == self.dim, rhs.nrows() == self.dim, out.ncols() == rhs.ncols()));
+		self.root.apply(out, rhs, Conj::No, true, par);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		assert!(all(out.nrows() == self.dim, rhs.nrows() == self.dim, out.ncols() == rhs.ncols()));
+		self.root.apply(out, rhs, Conj::Yes, true, par);
+	}
+}
+
+impl<T: ComplexField> ShapeCore for HodlrFactor<T> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.dim
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.dim
+	}
+}
+
+impl<T: ComplexField> SolveCore<T> for HodlrFactor<T> {
+	#[track_caller]
+	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(rhs.nrows() == self.dim);
+		self.root.solve(conj, rhs, get_global_parallelism());
+	}
+
+	#[track_caller]
+	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(rhs.nrows() == self.dim);
+		self.root.solve_transpose(conj, rhs, get_global_parallelism());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::Solve;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_hodlr() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 300;
+		let x = |i: usize| (i as f64 / n as f64).powi(2);
+
+		// the off-diagonal blocks of this kernel have rank one
+		let kernel = |i: usize, j: usize| {
+			let d = x(i) - x(j);
+			c64::new(0.0, 3.0 * d).exp() * (-d.abs()).exp() + if i == j { c64::new(0.5, 0.0) } else { c64::new(0.0, 0.0) }
+		};
+		let dense = Mat::from_fn(n, n, kernel);
+
+		let params = HodlrParams {
+			leaf_size: 16,
+			..Default::default()
+		};
+		let A = Hodlr::from_kernel(n, kernel, params, rng, Par::Seq).unwrap();
+		assert!(A.max_rank() == 1);
+		assert!(A.stored_len() < n * n / 4);
+		assert!((A.to_dense() - &dense).norm_max() < 1e-12);
+
+		let b = Mat::<c64>::random_normal(rng, n, 3);
+		let mut out = Mat::<c64>::zeros(n, 3);
+		let mut mem = MemBuffer::new(StackReq::EMPTY);
+		let stack = MemStack::new(&mut mem);
+
+		A.apply(out.as_mut(), b.as_ref(), Par::Seq, stack);
+		assert!((&out - &dense * &b).norm_max() < 1e-10);
+		A.conj_apply(out.as_mut(), b.as_ref(), Par::Seq, stack);
+		assert!((&out - dense.conjugate() * &b).norm_max() < 1e-10);
+		A.transpose_apply(out.as_mut(), b.as_ref(), Par::Seq, stack);
+		assert!((&out - dense.transpose() * &b).norm_max() < 1e-10);
+		A.adjoint_apply(out.as_mut(), b.as_ref(), Par::Seq, stack);
+		assert!((&out - dense.adjoint() * &b).norm_max() < 1e-10);
+
+		let lu = A.factorize();
+		assert!((&dense * lu.solve(&b) - &b).norm_max() < 1e-10);
+		assert!((dense.conjugate() * lu.solve_conjugate(&b) - &b).norm_max() < 1e-10);
+		assert!((dense.transpose() * lu.solve_transpose(&b) - &b).norm_max() < 1e-10);
+		assert!((dense.adjoint() * lu.solve_adjoint(&b) - &b).norm_max() < 1e-10);
+	}
+
+	#[test]
+	fn test_hodlr_compression() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 256;
+
+		// smooth kernel with numerically low-rank off-diagonal blocks
+		let kernel = |i: usize, j: usize| 1.0 / (1.0 + (i as f64 - j as f64).powi(2) / 16.0) + if i == j { 1.0 } else { 0.0 };
+		let dense = Mat::from_fn(n, n, kernel);
+
+		let params = HodlrParams {
+			leaf_size: 32,
+			rel_tolerance: 1e-10,
+			..Default::default()
+		};
+		let A = Hodlr::from_kernel(n, kernel, params, rng, Par::Seq).unwrap();
+		assert!(A.max_rank() < 32);
+		assert!((A.to_dense() - &dense).norm_max() < 1e-8);
+
+		let b = Mat::<f64>::random_normal(rng, n, 2);
+		let x = A.factorize().solve(&b);
+		assert!((&dense * &x - &b).norm_max() < 1e-7);
+	}
+}
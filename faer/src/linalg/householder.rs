@@ -25,8 +25,66 @@
 //! bases of each block householder transformation,
 //! - a horizontal concatenation of the householder factors.
 //!
-//! examples on how to create and manipulate block householder sequences are provided in the
+//! the building blocks are exposed so that custom orthogonalization schemes can reuse them:
+//! - [`make_householder_in_place`] and [`make_householder_out_of_place`] compute a single
+//!   reflection that maps a vector to a multiple of $e_0$,
+//! - [`make_householder_factor_in_place`] and [`upgrade_householder_factor`] form the householder
+//!   factor (the compact wy representation) of a block of reflections from its basis,
+//! - the `apply_block_householder_*` functions apply a block householder transformation, or a
+//!   sequence of them, to a matrix from either side.
+//!
+//! more examples on how to create and manipulate block householder sequences are provided in the
 //! documentation of the $QR$ module.
+//!
+//! # example
+//! ```
+//! use faer::dyn_stack::{MemBuffer, MemStack};
+//! use faer::linalg::householder;
+//! use faer::reborrow::*;
+//! use faer::{Conj, Mat, Par, mat};
+//!
+//! // unblocked qr decomposition of a tall matrix
+//! let A = mat![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0], [7.0, 9.0]];
+//! let (m, n) = A.shape();
+//!
+//! let mut V = A.clone();
+//! let mut T = Mat::<f64>::zeros(n, n);
+//! for j in 0..n {
+//!     let (mut left, right) = V.as_mut().split_at_col_mut(j + 1);
+//!     let (mut head, tail) = left.rb_mut().col_mut(j).split_at_row_mut(j + 1);
+//!     let info = householder::make_householder_in_place(&mut head[j], tail);
+//!     T[(j, j)] = info.tau;
+//!
+//!     // apply the reflection $I - v v^H / \tau$ to the remaining columns
+//!     let v = left.rb().get(j.., j..j + 1);
+//!     householder::apply_block_householder_on_the_left_in_place_with_conj(
+//!         v,
+//!         T.get(j..j + 1, j..j + 1),
+//!         Conj::No,
+//!         right.subrows_mut(j, m - j),
+//!         Par::Seq,
+//!         MemStack::new(&mut MemBuffer::new(
+//!             householder::apply_block_householder_on_the_left_in_place_scratch::<f64>(m - j, 1, n),
+//!         )),
+//!     );
+//! }
+//!
+//! // the reflections are now stored below the diagonal of `V`, and `R` above it
+//! householder::make_householder_factor_in_place(T.as_mut(), V.as_ref(), Par::Seq);
+//! let mut QR = Mat::from_fn(m, n, |i, j| if i <= j { V[(i, j)] } else { 0.0 });
+//! householder::apply_block_householder_on_the_left_in_place_with_conj(
+//!     V.as_ref(),
+//!     T.as_ref(),
+//!     Conj::No,
+//!     QR.as_mut(),
+//!     Par::Seq,
+//!     MemStack::new(&mut MemBuffer::new(
+//!         householder::apply_block_householder_on_the_left_in_place_scratch::<f64>(m, n, n),
+//!     )),
+//! );
+//!
+//! assert!((&QR - &A).norm_max() < 1e-12);
+//! ```
 
 use crate::assert;
 use crate::internal_prelude::*;
@@ -114,12 +172,52 @@ pub fn make_householder_in_place<T: ComplexField>(head: &mut T, tail: ColMut<'_,
 	make_householder_imp(head, tail, None)
 }
 
+/// computes the householder reflection $I - \frac{v v^H}{\tau}$ such that when multiplied by $x$
+/// from the left, the result is $\beta e_0$. $\tau$ and $(\text{head} - \beta)^{-1}$ are returned
+/// and $\tau$ is real-valued. $\beta$ is stored in `head`
+///
+/// $x$ is determined by $x_0$, contained in `head`, and $x_{1\dots}$, contained in `tail`. the
+/// vector $v$ is such that $v_0 = 1$ and $v_{1\dots}$ is stored in `out`, while `tail` is left
+/// unchanged
 #[inline]
-pub(crate) fn make_householder_out_of_place<T: ComplexField>(head: &mut T, out: ColMut<'_, T>, tail: ColRef<'_, T>) -> HouseholderInfo<T> {
+#[track_caller]
+pub fn make_householder_out_of_place<T: ComplexField>(head: &mut T, out: ColMut<'_, T>, tail: ColRef<'_, T>) -> HouseholderInfo<T> {
+	assert!(out.nrows() == tail.nrows());
 	make_householder_imp(head, out, Some(tail))
 }
 
-#[doc(hidden)]
+/// computes the householder factor $T$ of a block householder transformation
+/// $I - V T^{-1} V^H$ from its householder basis $V$
+///
+/// on entry, the diagonal of `householder_factor` must contain the coefficients $\tau_i$ of the
+/// reflections, as returned by [`make_householder_in_place`]. on exit, its strictly upper
+/// triangular half contains the remaining entries of $T$. the strictly lower triangular half is not
+/// accessed
+///
+/// only the strictly lower triangular half of `householder_basis` is accessed, and its diagonal is
+/// implicitly assumed to be one
+#[track_caller]
+pub fn make_householder_factor_in_place<T: ComplexField>(householder_factor: MatMut<'_, T>, householder_basis: MatRef<'_, T>, par: Par) {
+	let n = householder_basis.ncols();
+	assert!(all(
+		householder_factor.nrows() == n,
+		householder_factor.ncols() == n,
+		householder_basis.nrows() >= n,
+	));
+	upgrade_householder_factor(householder_factor, householder_basis, Ord::max(n, 1), 1, par);
+}
+
+/// computes the householder factor of a block householder transformation of size `blocksize` from
+/// the householder factors of its sub-blocks of size `prev_blocksize`
+///
+/// `householder_factor` is a square matrix whose diagonal blocks of size `prev_blocksize` contain
+/// the factors of the corresponding sub-blocks of `essentials`, which is the householder basis. on
+/// exit, its diagonal blocks of size `blocksize` contain the factors of the merged blocks.
+/// `blocksize` must be a multiple of `prev_blocksize`
+///
+/// this allows the reflections to be generated one panel at a time, and merged into larger blocks
+/// for a more efficient application afterwards
+#[track_caller]
 #[math]
 pub fn upgrade_householder_factor<T: ComplexField>(
 	householder_factor: MatMut<'_, T>,
@@ -793,3 +891,59 @@ pub fn apply_block_householder_sequence_transpose_on_the_right_in_place_with_con
 		stack,
 	)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_make_householder_factor() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, b) = (12, 5);
+
+		let V = CwiseMatDistribution {
+			nrows: m,
+			ncols: b,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let V = Mat::from_fn(m, b, |i, j| {
+			if i == j {
+				c64::ONE
+			} else if i > j {
+				V[(i, j)]
+			} else {
+				c64::ZERO
+			}
+		});
+
+		let mut T = Mat::<c64>::zeros(b, b);
+		let mut expected = Mat::<c64>::identity(m, m);
+		for j in 0..b {
+			let v = V.col(j);
+			let tau = v.squared_norm_l2() * 0.5;
+			T[(j, j)] = c64::new(tau, 0.0);
+			expected = &expected * (Mat::<c64>::identity(m, m) - v * v.adjoint() * Scale(c64::new(1.0 / tau, 0.0)));
+		}
+		make_householder_factor_in_place(T.as_mut(), V.as_ref(), Par::Seq);
+
+		let mut Q = Mat::<c64>::identity(m, m);
+		apply_block_householder_on_the_left_in_place_with_conj(
+			V.as_ref(),
+			T.as_ref(),
+			Conj::No,
+			Q.as_mut(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(apply_block_householder_on_the_left_in_place_scratch::<c64>(m, b, m))),
+		);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 64.0);
+		assert!(Q ~ expected);
+		// the reflections are unitary, since tau = |v|^2 / 2
+		assert!(Q.adjoint() * &Q ~ Mat::<c64>::identity(m, m));
+	}
+}
@@ -0,0 +1,601 @@
+use crate::internal_prelude::*;
+use crate::linalg::matmul::semiring::{self, PlusTimes, Semiring};
+use crate::{Par, Shape};
+use core::fmt::Debug;
+use equator::assert;
+
+/// integer type that can be used in exact matrix computations
+pub trait Integer: Copy + Ord + Default + Debug + Send + Sync + Into<i128> + TryFrom<i128> + 'static {}
+
+impl Integer for i32 {}
+impl Integer for i64 {}
+
+/// error returned when the result of an integer computation doesn't fit in the element type
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OverflowError;
+
+impl core::fmt::Display for OverflowError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+impl core::error::Error for OverflowError {}
+
+type Result<T> = core::result::Result<T, OverflowError>;
+
+#[inline]
+fn add(a: i128, b: i128) -> Result<i128> {
+	a.checked_add(b).ok_or(OverflowError)
+}
+
+#[inline]
+fn mul(a: i128, b: i128) -> Result<i128> {
+	a.checked_mul(b).ok_or(OverflowError)
+}
+
+#[inline]
+fn neg(a: i128) -> Result<i128> {
+	a.checked_neg().ok_or(OverflowError)
+}
+
+/// returns `(g, x, y)` such that `g = gcd(a, b) >= 0` and `x * a + y * b = g`
+fn ext_gcd(a: i128, b: i128) -> Result<(i128, i128, i128)> {
+	let (mut r0, mut r1) = (a, b);
+	let (mut x0, mut x1) = (1i128, 0i128);
+	let (mut y0, mut y1) = (0i128, 1i128);
+	while r1 != 0 {
+		let q = r0 / r1;
+		(r0, r1) = (r1, r0 - q * r1);
+		(x0, x1) = (x1, add(x0, neg(mul(q, x1)?)?)?);
+		(y0, y1) = (y1, add(y0, neg(mul(q, y1)?)?)?);
+	}
+	if r0 < 0 { Ok((neg(r0)?, neg(x0)?, neg(y0)?)) } else { Ok((r0, x0, y0)) }
+}
+
+fn to_wide<I: Integer>(mat: MatRef<'_, I>) -> Mat<i128> {
+	Mat::from_fn(mat.nrows(), mat.ncols(), |i, j| mat[(i, j)].into())
+}
+
+fn to_narrow<I: Integer>(mat: &Mat<i128>) -> Result<Mat<I>> {
+	let mut out = Mat::from_fn(mat.nrows(), mat.ncols(), |_, _| I::default());
+	for j in 0..mat.ncols() {
+		for i in 0..mat.nrows() {
+			out[(i, j)] = I::try_from(mat[(i, j)]).map_err(|_| OverflowError)?;
+		}
+	}
+	Ok(out)
+}
+
+fn identity(n: usize) -> Mat<i128> {
+	Mat::from_fn(n, n, |i, j| (i == j) as i128)
+}
+
+/// replaces rows `i` and `k` of `mat` with `a * row_i + b * row_k` and `c * row_i + d * row_k`
+fn combine_rows(mat: &mut Mat<i128>, i: usize, k: usize, [a, b, c, d]: [i128; 4]) -> Result<()> {
+	for j in 0..mat.ncols() {
+		let (x, y) = (mat[(i, j)], mat[(k, j)]);
+		mat[(i, j)] = add(mul(a, x)?, mul(b, y)?)?;
+		mat[(k, j)] = add(mul(c, x)?, mul(d, y)?)?;
+	}
+	Ok(())
+}
+
+/// replaces row `i` of `mat` with `row_i + factor * row_k`
+fn add_row(mat: &mut Mat<i128>, i: usize, k: usize, factor: i128) -> Result<()> {
+	for j in 0..mat.ncols() {
+		mat[(i, j)] = add(mat[(i, j)], mul(factor, mat[(k, j)])?)?;
+	}
+	Ok(())
+}
+
+fn neg_row(mat: &mut Mat<i128>, i: usize) -> Result<()> {
+	for j in 0..mat.ncols() {
+		mat[(i, j)] = neg(mat[(i, j)])?;
+	}
+	Ok(())
+}
+
+/// replaces column `j` of `mat` with `col_j + factor * col_k`
+fn add_col(mat: &mut Mat<i128>, j: usize, k: usize, factor: i128) -> Result<()> {
+	for i in 0..mat.nrows() {
+		mat[(i, j)] = add(mat[(i, j)], mul(factor, mat[(i, k)])?)?;
+	}
+	Ok(())
+}
+
+fn swap_cols(mat: &mut Mat<i128>, j: usize, k: usize) {
+	if j != k {
+		for i in 0..mat.nrows() {
+			let tmp = mat[(i, j)];
+			mat[(i, j)] = mat[(i, k)];
+			mat[(i, k)] = tmp;
+		}
+	}
+}
+
+fn swap_rows(mat: &mut Mat<i128>, i: usize, k: usize) {
+	if i != k {
+		for j in 0..mat.ncols() {
+			let tmp = mat[(i, j)];
+			mat[(i, j)] = mat[(k, j)];
+			mat[(k, j)] = tmp;
+		}
+	}
+}
+
+/// computes the matrix product `[beta * acc] + lhs * rhs` with wrapping integer arithmetic, and
+/// stores the result in `acc`
+///
+/// the result is exact whenever it, and all the intermediate sums, fit in the element type. see
+/// [`checked_matmul`] for a version that reports overflow instead
+///
+/// # panics
+/// panics if the matrix dimensions are not compatible for matrix multiplication
+#[track_caller]
+#[inline]
+pub fn matmul<I: Integer, M: Shape, N: Shape, K: Shape>(
+	dst: impl AsMatMut<T = I, Rows = M, Cols = N>,
+	beta: Accum,
+	lhs: impl AsMatRef<T = I, Rows = M, Cols = K>,
+	rhs: impl AsMatRef<T = I, Rows = K, Cols = N>,
+	par: Par,
+) where
+	PlusTimes: Semiring<I>,
+{
+	semiring::matmul(dst, beta, lhs, rhs, PlusTimes, par);
+}
+
+/// computes the matrix product `[beta * acc] + lhs * rhs` exactly, and stores the result in `acc`
+///
+/// the products are accumulated in 128-bit integers, so the computation only fails if the final
+/// result doesn't fit in the element type, in which case `acc` is left unmodified
+///
+/// # panics
+/// panics if the matrix dimensions are not compatible for matrix multiplication
+#[track_caller]
+pub fn checked_matmul<I: Integer, M: Shape, N: Shape, K: Shape>(
+	dst: impl AsMatMut<T = I, Rows = M, Cols = N>,
+	beta: Accum,
+	lhs: impl AsMatRef<T = I, Rows = M, Cols = K>,
+	rhs: impl AsMatRef<T = I, Rows = K, Cols = N>,
+	par: Par,
+) -> Result<()> {
+	let mut dst = dst;
+	let dst = dst.as_mat_mut();
+	let lhs = lhs.as_mat_ref();
+	let rhs = rhs.as_mat_ref();
+
+	assert!(all(dst.nrows() == lhs.nrows(), dst.ncols() == rhs.ncols(), lhs.ncols() == rhs.nrows()));
+
+	let mut dst = dst.as_dyn_mut().as_dyn_stride_mut();
+	let lhs = lhs.as_dyn().as_dyn_stride();
+	let rhs = rhs.as_dyn().as_dyn_stride();
+
+	let mut acc = match beta {
+		Accum::Replace => Mat::<i128>::from_fn(dst.nrows(), dst.ncols(), |_, _| 0),
+		Accum::Add => to_wide(dst.rb()),
+	};
+
+	let imp = |mut acc: MatMut<'_, i128>, rhs: MatRef<'_, I>| -> Result<()> {
+		for j in 0..acc.ncols() {
+			for k in 0..lhs.ncols() {
+				let b: i128 = rhs[(k, j)].into();
+				if b == 0 {
+					continue;
+				}
+				for i in 0..acc.nrows() {
+					acc[(i, j)] = add(acc[(i, j)], mul(lhs[(i, k)].into(), b)?)?;
+				}
+			}
+		}
+		Ok(())
+	};
+
+	match par {
+		Par::Seq => imp(acc.as_mut(), rhs)?,
+		#[cfg(feature = "rayon")]
+		Par::Rayon(nthreads) => {
+			use rayon::prelude::*;
+			let nthreads = Ord::min(nthreads.get(), acc.ncols());
+			if nthreads <= 1 {
+				imp(acc.as_mut(), rhs)?;
+			} else {
+				acc.par_col_partition_mut(nthreads)
+					.zip(rhs.par_col_partition(nthreads))
+					.try_for_each(|(acc, rhs)| imp(acc, rhs))?;
+			}
+		},
+	}
+
+	let acc = to_narrow::<I>(&acc)?;
+	for j in 0..dst.ncols() {
+		for i in 0..dst.nrows() {
+			dst[(i, j)] = acc[(i, j)];
+		}
+	}
+	Ok(())
+}
+
+/// hermite normal form of an integer matrix
+#[derive(Clone, Debug)]
+pub struct HermiteNormalForm<I> {
+	/// the hermite normal form $H$, in row echelon form. the pivots are positive, and the entries
+	/// above each pivot are non-negative and smaller than it
+	pub h: Mat<I>,
+	/// the unimodular transformation $U$, such that $UA = H$
+	pub u: Mat<I>,
+	/// the columns of the pivots of $H$, whose count is the rank of $A$
+	pub pivots: alloc::vec::Vec<usize>,
+}
+
+/// computes the (row-style) hermite normal form of `mat`
+///
+/// the computation is exact, and fails if any of the intermediate values overflow 128-bit
+/// integers, or if the results don't fit in the element type
+pub fn hermite_normal_form<I: Integer>(mat: MatRef<'_, I>) -> Result<HermiteNormalForm<I>> {
+	let (m, n) = mat.shape();
+	let mut h = to_wide(mat);
+	let mut u = identity(m);
+	let mut pivots = alloc::vec::Vec::new();
+
+	let mut r = 0;
+	for col in 0..n {
+		if r == m {
+			break;
+		}
+
+		// combine the rows so that the gcd of the column ends up in row `r`, and zeros below it
+		for i in r + 1..m {
+			let (a, b) = (h[(r, col)], h[(i, col)]);
+			if b == 0 {
+				continue;
+			}
+			let (g, x, y) = ext_gcd(a, b)?;
+			// the transformation has determinant `x * a / g + y * b / g = 1`
+			let t = [x, y, neg(b / g)?, a / g];
+			combine_rows(&mut h, r, i, t)?;
+			combine_rows(&mut u, r, i, t)?;
+		}
+
+		let pivot = h[(r, col)];
+		if pivot == 0 {
+			continue;
+		}
+		if pivot < 0 {
+			neg_row(&mut h, r)?;
+			neg_row(&mut u, r)?;
+		}
+
+		let pivot = h[(r, col)];
+		for i in 0..r {
+			let q = h[(i, col)].div_euclid(pivot);
+			if q != 0 {
+				add_row(&mut h, i, r, neg(q)?)?;
+				add_row(&mut u, i, r, neg(q)?)?;
+			}
+		}
+
+		pivots.push(col);
+		r += 1;
+	}
+
+	Ok(HermiteNormalForm {
+		h: to_narrow(&h)?,
+		u: to_narrow(&u)?,
+		pivots,
+	})
+}
+
+/// smith normal form of an integer matrix
+#[derive(Clone, Debug)]
+pub struct SmithNormalForm<I> {
+	/// the invariant factors, which are non-negative, and each one divides the next
+	pub s: Col<I>,
+	/// the unimodular left transformation $U$
+	pub u: Mat<I>,
+	/// the unimodular right transformation $V$, such that $UAV = \operatorname{diag}(s)$
+	pub v: Mat<I>,
+}
+
+/// computes the smith normal form of `mat`
+///
+/// the computation is exact, and fails if any of the intermediate values overflow 128-bit
+/// integers, or if the results don't fit in the element type
+pub fn smith_normal_form<I: Integer>(mat: MatRef<'_, I>) -> Result<SmithNormalForm<I>> {
+	let (m, n) = mat.shape();
+	let size = Ord::min(m, n);
+
+	let mut d = to_wide(mat);
+	let mut u = identity(m);
+	let mut v = identity(n);
+
+	'outer: for t in 0..size {
+		loop {
+			// move the smallest nonzero entry of the trailing submatrix to the pivot position
+			let mut best = None;
+			for j in t..n {
+				for i in t..m {
+					let x = d[(i, j)].unsigned_abs();
+					if x != 0 && best.is_none_or(|(_, _, b)| x < b) {
+						best = Some((i, j, x));
+					}
+				}
+			}
+			let Some((pi, pj, _)) = best else {
+				break 'outer;
+			};
+
+			swap_rows(&mut d, t, pi);
+			swap_rows(&mut u, t, pi);
+			swap_cols(&mut d, t, pj);
+			swap_cols(&mut v, t, pj);
+
+			let pivot = d[(t, t)];
+			let mut done = true;
+
+			for i in t + 1..m {
+				let q = d[(i, t)] / pivot;
+				add_row(&mut d, i, t, neg(q)?)?;
+				add_row(&mut u, i, t, neg(q)?)?;
+				done &= d[(i, t)] == 0;
+			}
+			for j in t + 1..n {
+				let q = d[(t, j)] / pivot;
+				add_col(&mut d, j, t, neg(q)?)?;
+				add_col(&mut v, j, t, neg(q)?)?;
+				done &= d[(t, j)] == 0;
+			}
+
+			if !done {
+				continue;
+			}
+
+			// the pivot must divide every entry of the trailing submatrix. otherwise, adding the
+			// offending row to the pivot row leaves a smaller remainder in the next iteration
+			let bad = (t + 1..m).find(|&i| (t + 1..n).any(|j| d[(i, j)] % pivot != 0));
+			match bad {
+				Some(i) => {
+					add_row(&mut d, t, i, 1)?;
+					add_row(&mut u, t, i, 1)?;
+				},
+				None => break,
+			}
+		}
+
+		if d[(t, t)] < 0 {
+			neg_row(&mut d, t)?;
+			neg_row(&mut u, t)?;
+		}
+	}
+
+	let mut s = Col::from_fn(size, |_| I::default());
+	for i in 0..size {
+		s[i] = I::try_from(d[(i, i)]).map_err(|_| OverflowError)?;
+	}
+
+	Ok(SmithNormalForm {
+		s,
+		u: to_narrow(&u)?,
+		v: to_narrow(&v)?,
+	})
+}
+
+/// $LU$ decomposition of an integer matrix over the prime field $\mathbb{Z}/p\mathbb{Z}$
+#[derive(Clone, Debug)]
+pub struct ModularLu<I> {
+	/// the unit lower triangular factor $L$, with entries in $[0, p)$
+	pub l: Mat<I>,
+	/// the factor $U$, in row echelon form with entries in $[0, p)$
+	pub u: Mat<I>,
+	/// the row permutation $P$, such that $PA = LU$ modulo $p$
+	pub row_perm: Perm<usize>,
+	/// the rank of $A$ modulo $p$
+	pub rank: usize,
+}
+
+/// computes the $LU$ decomposition of `mat` modulo the prime `p`, using gaussian elimination with
+/// row pivoting
+///
+/// # panics
+/// panics if `p` is not a prime larger than one. primality is only checked through the
+/// invertibility of the pivots
+#[track_caller]
+pub fn lu_mod_p<I: Integer>(mat: MatRef<'_, I>, p: I) -> ModularLu<I> {
+	let p: i128 = p.into();
+	assert!(p > 1);
+
+	let (m, n) = mat.shape();
+	let mut a = Mat::from_fn(m, n, |i, j| Into::<i128>::into(mat[(i, j)]).rem_euclid(p));
+	let mut l = Mat::<i128>::from_fn(m, m, |_, _| 0);
+	let mut perm: alloc::vec::Vec<usize> = (0..m).collect();
+
+	// the entries are in `[0, p)` with `p` fitting in 64 bits, so the products fit in 128 bits
+	let mut r = 0;
+	for col in 0..n {
+		if r == m {
+			break;
+		}
+		let Some(pi) = (r..m).find(|&i| a[(i, col)] != 0) else {
+			continue;
+		};
+		swap_rows(&mut a, r, pi);
+		swap_rows(&mut l, r, pi);
+		perm.swap(r, pi);
+
+		let (g, inv, _) = ext_gcd(a[(r, col)], p).unwrap();
+		assert!(g == 1);
+		let inv = inv.rem_euclid(p);
+
+		for i in r + 1..m {
+			let factor = (a[(i, col)] * inv) % p;
+			l[(i, r)] = factor;
+			for j in col..n {
+				a[(i, j)] = (a[(i, j)] - factor * a[(r, j)]).rem_euclid(p);
+			}
+		}
+		r += 1;
+	}
+	for i in 0..m {
+		l[(i, i)] = 1;
+	}
+
+	let mut inv = alloc::vec![0usize; m];
+	for (i, &p) in perm.iter().enumerate() {
+		inv[p] = i;
+	}
+
+	ModularLu {
+		l: to_narrow(&l).unwrap(),
+		u: to_narrow(&a).unwrap(),
+		row_perm: Perm::new_checked(perm.into_boxed_slice(), inv.into_boxed_slice(), m),
+		rank: r,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+
+	fn naive_mul(lhs: &Mat<i64>, rhs: &Mat<i64>) -> Mat<i128> {
+		Mat::from_fn(lhs.nrows(), rhs.ncols(), |i, j| {
+			(0..lhs.ncols()).map(|k| lhs[(i, k)] as i128 * rhs[(k, j)] as i128).sum()
+		})
+	}
+
+	fn widen(mat: &Mat<i64>) -> Mat<i128> {
+		to_wide(mat.as_ref())
+	}
+
+	fn random(rng: &mut StdRng, m: usize, n: usize, range: i64) -> Mat<i64> {
+		Mat::from_fn(m, n, |_, _| rng.gen_range(-range..=range))
+	}
+
+	#[test]
+	fn test_matmul() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n, k) in [(0, 2, 3), (4, 3, 17), (20, 30, 15)] {
+			for par in [Par::Seq, Par::rayon(4)] {
+				let A = random(rng, m, k, 1000);
+				let B = random(rng, k, n, 1000);
+
+				let mut dst = Mat::from_fn(m, n, |_, _| 0i64);
+				matmul(&mut dst, Accum::Replace, &A, &B, par);
+				assert!(widen(&dst) == naive_mul(&A, &B));
+
+				let mut checked = Mat::from_fn(m, n, |_, _| 1i64);
+				checked_matmul(&mut checked, Accum::Add, &A, &B, par).unwrap();
+				assert!(widen(&checked) == Mat::from_fn(m, n, |i, j| dst[(i, j)] as i128 + 1));
+			}
+		}
+
+		// the intermediate sums overflow, but the result doesn't
+		let A = Mat::from_fn(1, 3, |_, j| [i32::MAX, i32::MAX, -i32::MAX][j]);
+		let B = Mat::from_fn(3, 1, |_, _| 1i32);
+		let mut dst = Mat::from_fn(1, 1, |_, _| 0i32);
+		checked_matmul(&mut dst, Accum::Replace, &A, &B, Par::Seq).unwrap();
+		assert!(dst[(0, 0)] == i32::MAX);
+
+		let mut dst = Mat::from_fn(1, 1, |_, _| 5i32);
+		assert!(checked_matmul(&mut dst, Accum::Add, &A, &B, Par::Seq) == Err(OverflowError));
+		assert!(dst[(0, 0)] == 5);
+	}
+
+	#[test]
+	fn test_hermite() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(4, 4), (3, 6), (6, 3)] {
+			let mut A = random(rng, m, n, 10);
+			if m > 2 {
+				// make the matrix rank deficient
+				for j in 0..n {
+					A[(2, j)] = A[(0, j)] * 3 - A[(1, j)];
+				}
+			}
+
+			let HermiteNormalForm { h, u, pivots } = hermite_normal_form(A.as_ref()).unwrap();
+			assert!(naive_mul(&u, &A) == widen(&h));
+
+			for (r, &col) in pivots.iter().enumerate() {
+				assert!(h[(r, col)] > 0);
+				for i in 0..m {
+					if i < r {
+						assert!(all(h[(i, col)] >= 0, h[(i, col)] < h[(r, col)]));
+					}
+					if i > r {
+						assert!(h[(i, col)] == 0);
+					}
+				}
+				for j in 0..col {
+					assert!(h[(r, j)] == 0);
+				}
+			}
+			for i in pivots.len()..m {
+				for j in 0..n {
+					assert!(h[(i, j)] == 0);
+				}
+			}
+			assert!(pivots.len() == Ord::min(Ord::min(m, n), if m > 2 { m - 1 } else { m }));
+
+			// the transformation is unimodular, so its inverse is an integer matrix
+			let HermiteNormalForm { h: uh, .. } = hermite_normal_form(u.as_ref()).unwrap();
+			assert!(widen(&uh) == identity(m));
+		}
+	}
+
+	#[test]
+	fn test_smith() {
+		let A = Mat::from_fn(3, 3, |i, j| [[2i64, 4, 4], [-6, 6, 12], [10, -4, -16]][i][j]);
+		let SmithNormalForm { s, u, v } = smith_normal_form(A.as_ref()).unwrap();
+		assert!(s == Col::from_fn(3, |i| [2i64, 6, 12][i]));
+
+		let uav = naive_mul(&to_narrow(&naive_mul(&u, &A)).unwrap(), &v);
+		assert!(uav == Mat::from_fn(3, 3, |i, j| if i == j { s[i] as i128 } else { 0 }));
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		for (m, n) in [(4, 4), (3, 5), (5, 2)] {
+			let A = random(rng, m, n, 20);
+			let SmithNormalForm { s, u, v } = smith_normal_form(A.as_ref()).unwrap();
+
+			let uav = naive_mul(&to_narrow(&naive_mul(&u, &A)).unwrap(), &v);
+			assert!(uav == Mat::from_fn(m, n, |i, j| if i == j { s[i] as i128 } else { 0 }));
+			for i in 1..s.nrows() {
+				assert!(s[i - 1] >= 0);
+				assert!(if s[i - 1] == 0 { s[i] == 0 } else { s[i] % s[i - 1] == 0 });
+			}
+		}
+	}
+
+	#[test]
+	fn test_lu_mod_p() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let p = 1_000_000_007i64;
+
+		for (m, n) in [(5, 5), (4, 7), (7, 4)] {
+			let mut A = random(rng, m, n, 1 << 40);
+			// make the matrix rank deficient modulo `p`
+			for j in 0..n {
+				A[(m - 1, j)] = A[(0, j)] + p * 3;
+			}
+
+			let ModularLu { l, u, row_perm, rank } = lu_mod_p(A.as_ref(), p);
+			assert!(rank == Ord::min(m - 1, n));
+
+			let lu = naive_mul(&l, &u);
+			let fwd = row_perm.as_ref().arrays().0;
+			for i in 0..m {
+				for j in 0..n {
+					assert!(lu[(i, j)].rem_euclid(p as i128) == (A[(fwd[i], j)] as i128).rem_euclid(p as i128));
+				}
+			}
+			for i in rank..m {
+				for j in 0..n {
+					assert!(u[(i, j)] == 0);
+				}
+			}
+		}
+	}
+}
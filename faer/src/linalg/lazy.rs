@@ -0,0 +1,440 @@
+//! lazily evaluated matrix expressions
+//!
+//! the arithmetic operators on [`Mat`] and its views evaluate eagerly, and allocate a new matrix
+//! for each intermediate result. wrapping the operands with [`lazy`] instead builds an expression
+//! object that is only evaluated when it's written to a destination with [`LazyExpr::eval_into`]
+//! or [`LazyExpr::eval`]
+//!
+//! during the evaluation, the coefficient-wise part of the expression (the sums, differences,
+//! negations and scalings of the operands) is computed in a single pass over the destination, and
+//! each matrix product is then accumulated into it with [`matmul`](crate::linalg::matmul::matmul),
+//! so that no intermediate matrix is allocated
+//!
+//! only products of two operands are supported, since a product involving a compound expression
+//! would require storing it in a temporary matrix
+//!
+//! the existing operators are unchanged and stay eager, so that the lazy evaluation is only used
+//! when it's requested explicitly with [`lazy`]
+//!
+//! # example
+//! ```
+//! use faer::linalg::lazy::{LazyExpr, lazy};
+//! use faer::{Mat, Par, Scale, mat};
+//!
+//! let a = mat![[1.0, 2.0], [3.0, 4.0]];
+//! let b = mat![[5.0, 6.0], [7.0, 8.0]];
+//! let d = mat![[1.0, 0.0], [0.0, 1.0]];
+//!
+//! let mut c = Mat::zeros(2, 2);
+//! // c = a * b + 2 * d - a, without allocating any intermediate matrix
+//! (lazy(&a) * lazy(&b) + Scale(2.0) * lazy(&d) - lazy(&a)).eval_into(&mut c, Par::Seq);
+//!
+//! assert!(c == &a * &b + Scale(2.0) * &d - &a);
+//! ```
+
+use crate::internal_prelude::*;
+use crate::{Scale, assert};
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// matrix expression that can be evaluated into a destination without allocating intermediates
+pub trait LazyExpr: Sized {
+	/// scalar type of the expression
+	type T: ComplexField;
+
+	/// returns the number of rows of the expression
+	fn nrows(&self) -> usize;
+	/// returns the number of columns of the expression
+	fn ncols(&self) -> usize;
+
+	#[doc(hidden)]
+	fn has_cwise(&self) -> bool;
+	/// returns the value of the coefficient-wise part of the expression at the given position,
+	/// excluding the products
+	#[doc(hidden)]
+	unsafe fn cwise_unchecked(&self, row: usize, col: usize) -> Self::T;
+	/// accumulates `alpha` times the products of the expression into `dst`
+	#[doc(hidden)]
+	fn add_products(&self, dst: MatMut<'_, Self::T>, accum: &mut Accum, alpha: &Self::T, par: Par);
+
+	/// evaluates the expression and stores the result in `dst`
+	#[track_caller]
+	fn eval_into(&self, dst: impl AsMatMut<T = Self::T>, par: Par) {
+		let mut dst = dst;
+		let mut dst = dst.as_mat_mut().as_dyn_mut().as_dyn_stride_mut();
+		assert!(all(dst.nrows() == self.nrows(), dst.ncols() == self.ncols()));
+
+		let mut accum = Accum::Replace;
+		if self.has_cwise() {
+			zip!(dst.rb_mut()).for_each_with_index(|i, j, unzip!(dst)| *dst = unsafe { self.cwise_unchecked(i, j) });
+			accum = Accum::Add;
+		}
+		self.add_products(dst.rb_mut(), &mut accum, &one(), par);
+	}
+
+	/// evaluates the expression into a new matrix
+	#[track_caller]
+	fn eval(&self) -> Mat<Self::T> {
+		let mut out = Mat::zeros(self.nrows(), self.ncols());
+		self.eval_into(&mut out, crate::get_global_parallelism());
+		out
+	}
+}
+
+/// matrix operand of a lazy expression
+#[derive(Copy, Clone, Debug)]
+pub struct Leaf<'a, T>(MatRef<'a, T>);
+
+/// lazy product of two matrices
+#[derive(Copy, Clone, Debug)]
+pub struct Product<'a, T> {
+	lhs: MatRef<'a, T>,
+	rhs: MatRef<'a, T>,
+}
+
+/// lazy sum of two expressions
+#[derive(Copy, Clone, Debug)]
+pub struct Sum<A, B> {
+	lhs: A,
+	rhs: B,
+}
+
+/// lazy negation of an expression
+#[derive(Copy, Clone, Debug)]
+pub struct Negation<A>(A);
+
+/// lazy scaling of an expression
+#[derive(Copy, Clone, Debug)]
+pub struct Scaled<T, A> {
+	factor: T,
+	inner: A,
+}
+
+/// wraps a matrix, column or row so that arithmetic on it builds a lazy expression
+#[inline]
+pub fn lazy<'a, T: ComplexField>(operand: impl Into<Leaf<'a, T>>) -> Leaf<'a, T> {
+	operand.into()
+}
+
+impl<'a, T> From<MatRef<'a, T>> for Leaf<'a, T> {
+	#[inline]
+	fn from(value: MatRef<'a, T>) -> Self {
+		Self(value)
+	}
+}
+impl<'a, T> From<MatMut<'a, T>> for Leaf<'a, T> {
+	#[inline]
+	fn from(value: MatMut<'a, T>) -> Self {
+		Self(value.into_const())
+	}
+}
+impl<'a, T> From<&'a Mat<T>> for Leaf<'a, T> {
+	#[inline]
+	fn from(value: &'a Mat<T>) -> Self {
+		Self(value.as_ref())
+	}
+}
+impl<'a, T> From<ColRef<'a, T>> for Leaf<'a, T> {
+	#[inline]
+	fn from(value: ColRef<'a, T>) -> Self {
+		Self(value.as_mat())
+	}
+}
+impl<'a, T> From<&'a Col<T>> for Leaf<'a, T> {
+	#[inline]
+	fn from(value: &'a Col<T>) -> Self {
+		Self(value.as_mat())
+	}
+}
+impl<'a, T> From<RowRef<'a, T>> for Leaf<'a, T> {
+	#[inline]
+	fn from(value: RowRef<'a, T>) -> Self {
+		Self(value.as_mat())
+	}
+}
+impl<'a, T> From<&'a Row<T>> for Leaf<'a, T> {
+	#[inline]
+	fn from(value: &'a Row<T>) -> Self {
+		Self(value.as_mat())
+	}
+}
+
+impl<T: ComplexField> LazyExpr for Leaf<'_, T> {
+	type T = T;
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.0.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.0.ncols()
+	}
+
+	#[inline]
+	fn has_cwise(&self) -> bool {
+		true
+	}
+
+	#[inline]
+	unsafe fn cwise_unchecked(&self, row: usize, col: usize) -> T {
+		copy(unsafe { self.0.get_unchecked(row, col) })
+	}
+
+	#[inline]
+	fn add_products(&self, dst: MatMut<'_, T>, accum: &mut Accum, alpha: &T, par: Par) {
+		_ = (dst, accum, alpha, par);
+	}
+}
+
+impl<T: ComplexField> LazyExpr for Product<'_, T> {
+	type T = T;
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.lhs.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.rhs.ncols()
+	}
+
+	#[inline]
+	fn has_cwise(&self) -> bool {
+		false
+	}
+
+	#[inline]
+	unsafe fn cwise_unchecked(&self, row: usize, col: usize) -> T {
+		_ = (row, col);
+		zero()
+	}
+
+	#[inline]
+	fn add_products(&self, dst: MatMut<'_, T>, accum: &mut Accum, alpha: &T, par: Par) {
+		linalg::matmul::matmul(dst, *accum, self.lhs, self.rhs, copy(alpha), par);
+		*accum = Accum::Add;
+	}
+}
+
+impl<A: LazyExpr, B: LazyExpr<T = A::T>> LazyExpr for Sum<A, B> {
+	type T = A::T;
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.lhs.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.lhs.ncols()
+	}
+
+	#[inline]
+	fn has_cwise(&self) -> bool {
+		self.lhs.has_cwise() || self.rhs.has_cwise()
+	}
+
+	#[inline]
+	#[math]
+	unsafe fn cwise_unchecked(&self, row: usize, col: usize) -> Self::T {
+		match (self.lhs.has_cwise(), self.rhs.has_cwise()) {
+			(true, true) => unsafe { self.lhs.cwise_unchecked(row, col) + self.rhs.cwise_unchecked(row, col) },
+			(true, false) => unsafe { self.lhs.cwise_unchecked(row, col) },
+			(false, _) => unsafe { self.rhs.cwise_unchecked(row, col) },
+		}
+	}
+
+	#[inline]
+	fn add_products(&self, dst: MatMut<'_, Self::T>, accum: &mut Accum, alpha: &Self::T, par: Par) {
+		let mut dst = dst;
+		self.lhs.add_products(dst.rb_mut(), accum, alpha, par);
+		self.rhs.add_products(dst, accum, alpha, par);
+	}
+}
+
+impl<A: LazyExpr> LazyExpr for Negation<A> {
+	type T = A::T;
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.0.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.0.ncols()
+	}
+
+	#[inline]
+	fn has_cwise(&self) -> bool {
+		self.0.has_cwise()
+	}
+
+	#[inline]
+	#[math]
+	unsafe fn cwise_unchecked(&self, row: usize, col: usize) -> Self::T {
+		-unsafe { self.0.cwise_unchecked(row, col) }
+	}
+
+	#[inline]
+	#[math]
+	fn add_products(&self, dst: MatMut<'_, Self::T>, accum: &mut Accum, alpha: &Self::T, par: Par) {
+		self.0.add_products(dst, accum, &-*alpha, par);
+	}
+}
+
+impl<A: LazyExpr> LazyExpr for Scaled<A::T, A> {
+	type T = A::T;
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.inner.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.inner.ncols()
+	}
+
+	#[inline]
+	fn has_cwise(&self) -> bool {
+		self.inner.has_cwise()
+	}
+
+	#[inline]
+	#[math]
+	unsafe fn cwise_unchecked(&self, row: usize, col: usize) -> Self::T {
+		self.factor * unsafe { self.inner.cwise_unchecked(row, col) }
+	}
+
+	#[inline]
+	#[math]
+	fn add_products(&self, dst: MatMut<'_, Self::T>, accum: &mut Accum, alpha: &Self::T, par: Par) {
+		self.inner.add_products(dst, accum, &(*alpha * self.factor), par);
+	}
+}
+
+impl<'a, T: ComplexField> Mul<Leaf<'a, T>> for Leaf<'a, T> {
+	type Output = Product<'a, T>;
+
+	#[track_caller]
+	#[inline]
+	fn mul(self, rhs: Leaf<'a, T>) -> Self::Output {
+		assert!(self.0.ncols() == rhs.0.nrows());
+		Product { lhs: self.0, rhs: rhs.0 }
+	}
+}
+
+macro_rules! impl_ops {
+	($(impl[$($gen: tt)*] for $ty: ty;)*) => {$(
+		impl<$($gen)* Rhs: LazyExpr<T = <$ty as LazyExpr>::T>> Add<Rhs> for $ty {
+			type Output = Sum<Self, Rhs>;
+
+			#[track_caller]
+			#[inline]
+			fn add(self, rhs: Rhs) -> Self::Output {
+				assert!(all(self.nrows() == rhs.nrows(), self.ncols() == rhs.ncols()));
+				Sum { lhs: self, rhs }
+			}
+		}
+
+		impl<$($gen)* Rhs: LazyExpr<T = <$ty as LazyExpr>::T>> Sub<Rhs> for $ty {
+			type Output = Sum<Self, Negation<Rhs>>;
+
+			#[track_caller]
+			#[inline]
+			fn sub(self, rhs: Rhs) -> Self::Output {
+				assert!(all(self.nrows() == rhs.nrows(), self.ncols() == rhs.ncols()));
+				Sum { lhs: self, rhs: Negation(rhs) }
+			}
+		}
+
+		impl<$($gen)*> Neg for $ty {
+			type Output = Negation<Self>;
+
+			#[inline]
+			fn neg(self) -> Self::Output {
+				Negation(self)
+			}
+		}
+
+		impl<$($gen)*> Mul<Scale<<$ty as LazyExpr>::T>> for $ty {
+			type Output = Scaled<<$ty as LazyExpr>::T, Self>;
+
+			#[inline]
+			fn mul(self, rhs: Scale<<$ty as LazyExpr>::T>) -> Self::Output {
+				Scaled { factor: rhs.0, inner: self }
+			}
+		}
+
+		impl<$($gen)*> Mul<$ty> for Scale<<$ty as LazyExpr>::T> {
+			type Output = Scaled<<$ty as LazyExpr>::T, $ty>;
+
+			#[inline]
+			fn mul(self, rhs: $ty) -> Self::Output {
+				Scaled { factor: self.0, inner: rhs }
+			}
+		}
+	)*};
+}
+
+impl_ops! {
+	impl['a, T: ComplexField,] for Leaf<'a, T>;
+	impl['a, T: ComplexField,] for Product<'a, T>;
+	impl[A: LazyExpr, B: LazyExpr<T = A::T>,] for Sum<A, B>;
+	impl[A: LazyExpr,] for Negation<A>;
+	impl[A: LazyExpr,] for Scaled<A::T, A>;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_lazy() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n, k) = (20, 15, 10);
+
+		let a = Mat::<c64>::random_normal(rng, m, k);
+		let b = Mat::<c64>::random_normal(rng, k, n);
+		let c = Mat::<c64>::random_normal(rng, m, k);
+		let d = Mat::<c64>::random_normal(rng, m, n);
+		let e = Mat::<c64>::random_normal(rng, k, n);
+		let x = Col::<c64>::random_normal(rng, n);
+		let y = Col::<c64>::random_normal(rng, m);
+		let alpha = c64::new(0.5, -1.5);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+
+		let expr = lazy(&a) * lazy(&b) + Scale(alpha) * lazy(&d) - lazy(&c) * lazy(&e);
+		let mut out = Mat::zeros(m, n);
+		expr.eval_into(&mut out, Par::Seq);
+		assert!(out ~ &a * &b + Scale(alpha) * &d - &c * &e);
+		assert!(expr.eval() ~ out);
+
+		// products only
+		let expr = -(lazy(&a) * lazy(&b)) * Scale(alpha) + lazy(&c) * lazy(&e);
+		expr.eval_into(&mut out, Par::rayon(4));
+		assert!(out ~ Scale(-alpha) * (&a * &b) + &c * &e);
+
+		// coefficient-wise only
+		let expr = lazy(&d) - Scale(alpha) * (lazy(&d) + lazy(&d));
+		expr.eval_into(&mut out, Par::Seq);
+		assert!(out ~ &d - Scale(alpha * 2.0) * &d);
+
+		// destinations with an arbitrary layout are written at the logical positions
+		let mut out_t = Mat::zeros(n, m);
+		(lazy(&a) * lazy(&b) + lazy(&d)).eval_into(out_t.as_mut().transpose_mut().reverse_rows_mut(), Par::Seq);
+		assert!(out_t.transpose().reverse_rows() ~ &a * &b + &d);
+
+		// matrix-vector products
+		let mut z = Col::zeros(m);
+		(lazy(&y) - lazy(&d) * lazy(&x)).eval_into(&mut z, Par::Seq);
+		assert!(z.as_mat() ~ (&y - &d * &x).as_mat());
+	}
+}
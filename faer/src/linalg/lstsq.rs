@@ -0,0 +1,386 @@
+//! weighted, generalized and equality-constrained least squares
+//!
+//! these drivers reduce each problem to an ordinary least squares problem, which is then solved
+//! with a $QR$ decomposition:
+//! - [`weighted_lstsq`] minimizes $\sum_i w_i |(A x - b)_i|^2$ for nonnegative weights $w$,
+//! - [`generalized_lstsq`] minimizes $(A x - b)^H W (A x - b)$ for a self-adjoint positive
+//!   definite weight matrix $W$, using its $L L^H$ decomposition,
+//! - [`equality_constrained_lstsq`] minimizes $\|A x - b\|_2$ subject to $B x = d$
+//!
+//! the sensitivity of the solution of an ordinary least squares problem to perturbations of $A$
+//! and $b$ can be estimated with [`lstsq_condition`]
+//!
+//! # example
+//! ```
+//! use faer::linalg::lstsq::equality_constrained_lstsq;
+//! use faer::mat;
+//!
+//! // fit a line through three points, forcing it to pass through the origin
+//! let A = mat![[1.0, 1.0], [1.0, 2.0], [1.0, 3.0]];
+//! let b = mat![[2.0], [3.0], [5.0]];
+//! let B = mat![[1.0, 0.0]];
+//! let d = mat![[0.0]];
+//!
+//! let x: faer::Mat<f64> = equality_constrained_lstsq(A.as_ref(), b.as_ref(), B.as_ref(), d.as_ref());
+//! assert!(x[(0, 0)].abs() < 1e-14);
+//! assert!((x[(1, 0)] - 23.0 / 14.0).abs() < 1e-14);
+//! ```
+
+use crate::internal_prelude::*;
+use crate::linalg::solvers::{Llt, LltError, Qr, SolveLstsq, Svd, SvdError};
+use crate::{assert, get_global_parallelism};
+
+/// solves the weighted least squares problem $\min_x \sum_i w_i |(A x - b)_i|^2$
+///
+/// this is equivalent to the ordinary least squares problem for $W^{1/2} A$ and $W^{1/2} b$, where
+/// $W$ is the diagonal matrix with the entries of `weights`
+///
+/// # panics
+///
+/// panics if the dimensions of `A`, `weights` and `rhs` don't match, or if $A$ has more columns
+/// than rows
+#[track_caller]
+pub fn weighted_lstsq<T: ComplexField>(
+	A: MatRef<'_, impl Conjugate<Canonical = T>>,
+	weights: ColRef<'_, T::Real>,
+	rhs: MatRef<'_, impl Conjugate<Canonical = T>>,
+) -> Mat<T> {
+	let (m, n) = A.shape();
+	assert!(all(weights.nrows() == m, rhs.nrows() == m, m >= n));
+
+	let sqrt_w = Col::<T::Real>::from_fn(m, |i| sqrt(&weights[i]));
+
+	let mut A = A.to_owned();
+	let mut rhs = rhs.to_owned();
+	for mut col in A.col_iter_mut().chain(rhs.col_iter_mut()) {
+		z!(col.rb_mut(), sqrt_w.as_ref()).for_each(|uz!(x, w)| *x = mul_real(x, w));
+	}
+
+	Qr::new(A.as_ref()).solve_lstsq(rhs)
+}
+
+/// solves the generalized least squares problem $\min_x (A x - b)^H W (A x - b)$, where $W$ is
+/// self-adjoint positive definite (e.g., the inverse of the covariance matrix of the errors)
+///
+/// only the lower triangular half of $W$ is accessed. with $W = L L^H$, this is equivalent to the
+/// ordinary least squares problem for $L^H A$ and $L^H b$
+///
+/// # errors
+///
+/// returns an error if the $L L^H$ decomposition of $W$ fails
+///
+/// # panics
+///
+/// panics if the dimensions of `A`, `W` and `rhs` don't match, or if $A$ has more columns than
+/// rows
+#[track_caller]
+pub fn generalized_lstsq<T: ComplexField>(
+	A: MatRef<'_, impl Conjugate<Canonical = T>>,
+	W: MatRef<'_, impl Conjugate<Canonical = T>>,
+	rhs: MatRef<'_, impl Conjugate<Canonical = T>>,
+) -> Result<Mat<T>, LltError> {
+	let (m, n) = A.shape();
+	assert!(all(W.nrows() == m, W.ncols() == m, rhs.nrows() == m, m >= n));
+
+	let llt = Llt::new(W, Side::Lower)?;
+	let Lt = llt.L().adjoint();
+
+	let A = Lt * A;
+	let rhs = Lt * rhs;
+
+	Ok(Qr::new(A.as_ref()).solve_lstsq(rhs))
+}
+
+/// solves the equality-constrained least squares problem $\min_x \|A x - b\|_2$ subject to
+/// $B x = d$
+///
+/// $B$ is assumed to have full row rank, and the stacked matrix $\begin{bmatrix} A \\ B
+/// \end{bmatrix}$ to have full column rank, which guarantees that the solution is unique
+///
+/// the problem is solved with the null space method, which is equivalent to the generalized $RQ$
+/// approach: with the $QR$ decomposition $B^H = Q R$, the constraint fixes the first $p$
+/// components of $Q^H x$, and the remaining ones are the solution of an unconstrained least
+/// squares problem of size $m \times (n - p)$
+///
+/// # panics
+///
+/// panics if the dimensions of the inputs don't match, or if they don't satisfy $p \le n \le m +
+/// p$, where $A$ is $m \times n$ and $B$ is $p \times n$
+#[track_caller]
+pub fn equality_constrained_lstsq<T: ComplexField>(
+	A: MatRef<'_, impl Conjugate<Canonical = T>>,
+	rhs: MatRef<'_, impl Conjugate<Canonical = T>>,
+	B: MatRef<'_, impl Conjugate<Canonical = T>>,
+	d: MatRef<'_, impl Conjugate<Canonical = T>>,
+) -> Mat<T> {
+	let (m, n) = A.shape();
+	let p = B.nrows();
+	let k = rhs.ncols();
+	assert!(all(rhs.nrows() == m, B.ncols() == n, d.nrows() == p, d.ncols() == k, p <= n, n <= m + p,));
+	let par = get_global_parallelism();
+
+	// B^H = Q R, so that B x = R^H (Q^H x)[..p]
+	let qr = Qr::new(B.adjoint());
+	let Q = qr.compute_Q();
+	let R = qr.thin_R();
+
+	// y = Q^H x, the first p components are determined by the constraint
+	let mut y = Mat::<T>::zeros(n, k);
+	let (mut y1, mut y2) = y.as_mut().split_at_row_mut(p);
+	y1.copy_from(d);
+	linalg::triangular_solve::solve_lower_triangular_in_place(R.get(.., ..p).adjoint(), y1.rb_mut(), par);
+
+	// the remaining components minimize |A Q2 y2 - (b - A Q1 y1)|
+	let AQ = A * &Q;
+	let (AQ1, AQ2) = AQ.split_at_col(p);
+	let c = rhs - AQ1 * y1.rb();
+	y2.copy_from(Qr::new(AQ2).solve_lstsq(c));
+
+	Q * y
+}
+
+/// condition numbers of the solution and residual of a least squares problem, see
+/// [`lstsq_condition`]
+#[derive(Copy, Clone, Debug)]
+pub struct LstsqCondition<T: RealField> {
+	/// spectral condition number $\kappa = \sigma_{\max}(A) / \sigma_{\min}(A)$ of the matrix
+	pub kappa: T,
+	/// normwise condition number of the solution, $\kappa (2 + (\kappa + 1) \eta)$, where $\eta =
+	/// \|r\|_2 / (\|A\|_2 \|x\|_2)$
+	///
+	/// for perturbations such that $\|\delta A\|_2 \le \varepsilon \|A\|_2$ and $\|\delta b\|_2 \le
+	/// \varepsilon \|b\|_2$, the relative error $\|\delta x\|_2 / \|x\|_2$ is bounded by this value
+	/// times $\varepsilon$, to first order
+	pub normwise: T,
+	/// componentwise condition number of the solution, $\| |A^+| (|A| |x| + |b|) + |(A^H A)^{-1}|
+	/// |A^H| |r| \|_\infty / \|x\|_\infty$
+	///
+	/// for perturbations such that $|\delta A| \le \varepsilon |A|$ and $|\delta b| \le \varepsilon
+	/// |b|$ elementwise, the relative error $\|\delta x\|_\infty / \|x\|_\infty$ is bounded by this
+	/// value times $\varepsilon$, to first order. this is usually much smaller than the normwise
+	/// condition number when the columns of $A$ are badly scaled
+	pub componentwise: T,
+	/// normwise condition number of the residual, $1 + 2 \kappa$
+	///
+	/// for the same perturbations as [`Self::normwise`], the error $\|\delta r\|_2 / \|b\|_2$ is
+	/// bounded by this value times $\varepsilon$, to first order
+	pub residual: T,
+}
+
+/// computes the condition numbers of the solution $x$ and the residual $r = b - A x$ of the least
+/// squares problem $\min_x \|A x - b\|_2$
+///
+/// the normwise bounds are the ones of wedin's theorem, and the componentwise bound is the one of
+/// björck. they're computed from the thin $SVD$ of $A$, so this requires $O(m n^2)$ operations.
+/// the condition numbers are infinite if $A$ doesn't have full column rank
+///
+/// # errors
+///
+/// returns an error if the $SVD$ of $A$ fails to converge
+///
+/// # panics
+///
+/// panics if `rhs` doesn't have as many rows as `A`, or if $A$ has more columns than rows
+#[track_caller]
+#[math]
+pub fn lstsq_condition<T: ComplexField>(
+	A: MatRef<'_, impl Conjugate<Canonical = T>>,
+	rhs: ColRef<'_, impl Conjugate<Canonical = T>>,
+) -> Result<LstsqCondition<T::Real>, SvdError> {
+	let (m, n) = A.shape();
+	assert!(all(rhs.nrows() == m, m >= n));
+
+	let A = A.to_owned();
+	let b = rhs.to_owned();
+	if n == 0 {
+		return Ok(LstsqCondition {
+			kappa: zero(),
+			normwise: zero(),
+			componentwise: zero(),
+			residual: one(),
+		});
+	}
+
+	let svd = Svd::new_thin(A.as_ref())?;
+	let (U, V) = (svd.U(), svd.V());
+	let s = svd.S().column_vector();
+	let s_max = real(s[0]);
+	let s_min = real(s[n - 1]);
+	let kappa = s_max / s_min;
+
+	// x = V S^-1 U^H b, A^+ = V S^-1 U^H and (A^H A)^-1 = V S^-2 V^H
+	let V_s = Mat::<T>::from_fn(n, n, |i, j| mul_real(V[(i, j)], recip(real(s[j]))));
+	let pinv = V_s.as_ref() * U.adjoint();
+	let gram_inv = V_s.as_ref() * V_s.adjoint();
+	let x = pinv.as_ref() * b.as_ref();
+	let Ax = A.as_ref() * x.as_ref();
+	let r = b.as_ref() - Ax.as_ref();
+
+	let x_norm = x.norm_l2();
+	let eta = r.norm_l2() / (s_max * x_norm);
+	let normwise = kappa * (from_f64::<T::Real>(2.0) + (kappa + one::<T::Real>()) * eta);
+
+	let abs_A = Mat::<T::Real>::from_fn(m, n, |i, j| abs(A[(i, j)]));
+	let scale = Col::<T::Real>::from_fn(m, |i| {
+		let mut acc = abs(b[i]);
+		for j in 0..n {
+			acc = acc + abs_A[(i, j)] * abs(x[j]);
+		}
+		acc
+	});
+	let abs_r = Col::<T::Real>::from_fn(m, |i| abs(r[i]));
+	let abs_At_r = abs_A.transpose() * abs_r.as_ref();
+
+	let mut bound = zero::<T::Real>();
+	let mut x_max = zero::<T::Real>();
+	for i in 0..n {
+		let mut acc = zero::<T::Real>();
+		for k in 0..m {
+			acc = acc + abs(pinv[(i, k)]) * scale[k];
+		}
+		for k in 0..n {
+			acc = acc + abs(gram_inv[(i, k)]) * abs_At_r[k];
+		}
+		bound = max(bound, acc);
+		x_max = max(x_max, abs(x[i]));
+	}
+
+	Ok(LstsqCondition {
+		normwise,
+		componentwise: bound / x_max,
+		residual: one::<T::Real>() + from_f64::<T::Real>(2.0) * kappa,
+		kappa,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::Solve;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	fn rand_mat(rng: &mut StdRng, nrows: usize, ncols: usize) -> Mat<c64> {
+		CwiseMatDistribution {
+			nrows,
+			ncols,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng)
+	}
+
+	#[test]
+	fn test_generalized_lstsq() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n, k) = (20, 6, 2);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0);
+
+		let A = rand_mat(rng, m, n);
+		let b = rand_mat(rng, m, k);
+		let C = rand_mat(rng, m, m);
+		let W = &C * C.adjoint() + Mat::<c64>::identity(m, m);
+
+		// normal equations, A^H W A x = A^H W b
+		let x = generalized_lstsq(A.as_ref(), W.as_ref(), b.as_ref()).unwrap();
+		let lhs = A.adjoint() * &W * &A;
+		let expected = lhs.partial_piv_lu().solve(A.adjoint() * &W * &b);
+		assert!(x ~ expected);
+
+		// conjugated inputs
+		let x = generalized_lstsq(A.conjugate(), W.conjugate(), b.conjugate()).unwrap();
+		assert!(x ~ expected.conjugate().to_owned());
+
+		let w = Col::<f64>::from_fn(m, |i| (i + 1) as f64);
+		let x = weighted_lstsq(A.as_ref(), w.as_ref(), b.as_ref());
+		let W = Mat::<c64>::from_fn(m, m, |i, j| if i == j { c64::new(w[i], 0.0) } else { c64::new(0.0, 0.0) });
+		let expected = generalized_lstsq(A.as_ref(), W.as_ref(), b.as_ref()).unwrap();
+		assert!(x ~ expected);
+
+		let mut not_pd = W.clone();
+		not_pd[(3, 3)] = c64::new(-1.0, 0.0);
+		assert!(generalized_lstsq(A.as_ref(), not_pd.as_ref(), b.as_ref()).is_err());
+	}
+
+	#[test]
+	fn test_equality_constrained_lstsq() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 4096.0);
+
+		for (m, n, p, k) in [(20, 6, 2, 3), (5, 8, 4, 1), (3, 4, 4, 2), (6, 4, 0, 1)] {
+			let A = rand_mat(rng, m, n);
+			let b = rand_mat(rng, m, k);
+			let B = rand_mat(rng, p, n);
+			let d = rand_mat(rng, p, k);
+
+			let x = equality_constrained_lstsq(A.as_ref(), b.as_ref(), B.as_ref(), d.as_ref());
+			assert!(x.shape() == (n, k));
+			assert!(&B * &x ~ d);
+
+			// kkt system, [A^H A, B^H; B, 0] [x; l] = [A^H b; d]
+			let mut K = Mat::<c64>::zeros(n + p, n + p);
+			K.get_mut(..n, ..n).copy_from(A.adjoint() * &A);
+			K.get_mut(..n, n..).copy_from(B.adjoint());
+			K.get_mut(n.., ..n).copy_from(&B);
+			let mut r = Mat::<c64>::zeros(n + p, k);
+			r.get_mut(..n, ..).copy_from(A.adjoint() * &b);
+			r.get_mut(n.., ..).copy_from(&d);
+			let expected = K.partial_piv_lu().solve(&r);
+			assert!(x ~ expected.get(..n, ..).to_owned());
+		}
+	}
+
+	#[test]
+	fn test_lstsq_condition() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n) = (30, 5);
+		let omega = 1e-10;
+
+		// badly scaled columns, and an inconsistent right hand side
+		let mut A = rand_mat(rng, m, n);
+		for j in 0..n {
+			let scale = c64::new(libm::pow(10.0, 2.0 * j as f64), 0.0);
+			for i in 0..m {
+				A[(i, j)] *= scale;
+			}
+		}
+		let b = rand_mat(rng, m, 1);
+		let cond = lstsq_condition(A.as_ref(), b.col(0)).unwrap();
+
+		let s = A.singular_values().unwrap();
+		assert!((cond.kappa - s[0] / s[n - 1]).abs() < 1e-6 * cond.kappa);
+		assert!(cond.residual == 1.0 + 2.0 * cond.kappa);
+		assert!(cond.componentwise < 1e-3 * cond.normwise);
+
+		let x = Qr::new(A.as_ref()).solve_lstsq(&b);
+		let r = &b - &A * &x;
+
+		for _ in 0..4 {
+			// componentwise relative perturbations
+			let dA = Mat::<c64>::from_fn(m, n, |i, j| A[(i, j)] * c64::new(omega * rng.gen_range(-1.0..1.0), 0.0));
+			let db = Mat::<c64>::from_fn(m, 1, |i, j| b[(i, j)] * c64::new(omega * rng.gen_range(-1.0..1.0), 0.0));
+
+			let A2 = &A + &dA;
+			let b2 = &b + &db;
+			let x2 = Qr::new(A2.as_ref()).solve_lstsq(&b2);
+			let r2 = &b2 - &A2 * &x2;
+
+			let dx = (&x2 - &x).norm_max() / x.norm_max();
+			assert!(dx <= 1.01 * cond.componentwise * omega);
+
+			let eps = f64::max(dA.singular_values().unwrap()[0] / s[0], db.norm_l2() / b.norm_l2());
+			assert!((&x2 - &x).norm_l2() / x.norm_l2() <= 1.01 * cond.normwise * eps);
+			assert!((&r2 - &r).norm_l2() / b.norm_l2() <= 1.01 * cond.residual * eps);
+		}
+
+		// for a consistent square system, the residual vanishes
+		let A = rand_mat(rng, n, n);
+		let b = &A * rand_mat(rng, n, 1);
+		let cond = lstsq_condition(A.as_ref(), b.col(0)).unwrap();
+		assert!((cond.normwise - 2.0 * cond.kappa).abs() < 1e-8 * cond.kappa);
+
+		let A = Mat::<c64>::zeros(4, 0);
+		let cond = lstsq_condition(A.as_ref(), b.col(0).get(..4)).unwrap();
+		assert!(cond.kappa == 0.0);
+	}
+}
@@ -7,7 +7,7 @@ use crate::perm::{swap_cols_idx, swap_rows_idx};
 use crate::utils::thread::par_split_indices;
 
 #[inline(always)]
-fn best_value<T: ComplexField, S: Simd>(
+fn best_value<T: ComplexField, S: Simd, const REV: bool>(
 	simd: &SimdCtx<T, S>,
 	best_value: RealReg<T::SimdVec<S>>,
 	best_indices: T::SimdIndex<S>,
@@ -15,7 +15,13 @@ fn best_value<T: ComplexField, S: Simd>(
 	indices: T::SimdIndex<S>,
 ) -> (RealReg<T::SimdVec<S>>, T::SimdIndex<S>) {
 	let value = simd.abs1(value);
-	let is_better = (**simd).gt(value, best_value);
+	// each accumulator visits its indices in increasing order, so a strict comparison keeps the
+	// lowest index on ties, and a non-strict one keeps the highest index
+	let is_better = if REV {
+		(**simd).ge(value, best_value)
+	} else {
+		(**simd).gt(value, best_value)
+	};
 	(
 		RealReg(simd.select(is_better, value.0, best_value.0)),
 		simd.iselect(is_better, indices, best_indices),
@@ -23,14 +29,22 @@ fn best_value<T: ComplexField, S: Simd>(
 }
 
 #[inline(always)]
-fn best_score<T: ComplexField, S: Simd>(
+fn best_score<T: ComplexField, S: Simd, const REV: bool>(
 	simd: &SimdCtx<T, S>,
 	best_score: RealReg<T::SimdVec<S>>,
 	best_indices: T::SimdIndex<S>,
 	score: RealReg<T::SimdVec<S>>,
 	indices: T::SimdIndex<S>,
 ) -> (RealReg<T::SimdVec<S>>, T::SimdIndex<S>) {
-	let is_better = (**simd).gt(score, best_score);
+	// unlike in `best_value`, the indices aren't visited in increasing order, so ties have to be
+	// broken explicitly for the result to not depend on the number of accumulators
+	let is_before = if REV {
+		(**simd).ilt(best_indices, indices)
+	} else {
+		(**simd).ilt(indices, best_indices)
+	};
+	let is_tied_and_before = (**simd).and_mask((**simd).ge(score, best_score), is_before);
+	let is_better = (**simd).or_mask((**simd).gt(score, best_score), is_tied_and_before);
 	(
 		RealReg(simd.select(is_better, score.0, best_score.0)),
 		simd.iselect(is_better, indices, best_indices),
@@ -38,8 +52,9 @@ fn best_score<T: ComplexField, S: Simd>(
 }
 
 #[inline(always)]
-fn best_score_2d<T: ComplexField, S: Simd>(
+fn best_score_2d<T: ComplexField, S: Simd, const REV: bool>(
 	simd: &SimdCtx<T, S>,
+	order: TieOrder,
 	best_score: RealReg<T::SimdVec<S>>,
 	best_row: T::SimdIndex<S>,
 	best_col: T::SimdIndex<S>,
@@ -47,7 +62,17 @@ fn best_score_2d<T: ComplexField, S: Simd>(
 	row: T::SimdIndex<S>,
 	col: T::SimdIndex<S>,
 ) -> (RealReg<T::SimdVec<S>>, T::SimdIndex<S>, T::SimdIndex<S>) {
-	let is_better = (**simd).gt(score, best_score);
+	// the columns are visited in increasing order, so a strict comparison is enough unless the
+	// rows take precedence
+	let is_better = if order.rows_first {
+		let is_before = if REV { (**simd).ilt(best_row, row) } else { (**simd).ilt(row, best_row) };
+		(**simd).or_mask(
+			(**simd).gt(score, best_score),
+			(**simd).and_mask((**simd).ge(score, best_score), is_before),
+		)
+	} else {
+		(**simd).gt(score, best_score)
+	};
 	(
 		RealReg(simd.select(is_better, score.0, best_score.0)),
 		simd.iselect(is_better, row, best_row),
@@ -57,87 +82,181 @@ fn best_score_2d<T: ComplexField, S: Simd>(
 
 #[inline(always)]
 #[math]
-fn reduce_2d<T: ComplexField, S: Simd>(
+fn reduce_2d<T: ComplexField, S: Simd, const REV: bool>(
 	simd: &SimdCtx<T, S>,
+	order: TieOrder,
 	best_values: RealReg<T::SimdVec<S>>,
 	best_row: T::SimdIndex<S>,
 	best_col: T::SimdIndex<S>,
 ) -> (usize, usize, Real<T>) {
 	let best_val = simd.reduce_max_real(best_values);
+	// when every entry is zero, the lanes traversing the rows in decreasing order may hold the
+	// position of a padding entry
+	if REV && best_val == zero() {
+		return (0, 0, best_val);
+	}
 
 	let best_val_splat = simd.splat_real(&best_val);
 	let is_best = (**simd).ge(best_values, best_val_splat);
-	let idx = simd.first_true_mask(is_best);
 
-	let best_row = bytemuck::cast_slice::<T::SimdIndex<S>, T::Index>(core::slice::from_ref(&best_row))[idx];
-	let best_col = bytemuck::cast_slice::<T::SimdIndex<S>, T::Index>(core::slice::from_ref(&best_col))[idx];
+	let rows = bytemuck::cast_slice::<T::SimdIndex<S>, T::Index>(core::slice::from_ref(&best_row));
+	let cols = bytemuck::cast_slice::<T::SimdIndex<S>, T::Index>(core::slice::from_ref(&best_col));
+
+	// the lane order doesn't match the index order, so among the lanes that hold the maximum, we
+	// look for the one that comes first in the order of the logical matrix
+	let row_before = |lhs, rhs| if REV { (**simd).ilt(rhs, lhs) } else { (**simd).ilt(lhs, rhs) };
+	let col_before = |lhs, rhs| (**simd).ilt(lhs, rhs);
+
+	let mut idx = simd.first_true_mask(is_best);
+	loop {
+		let row = simd.isplat(rows[idx]);
+		let col = simd.isplat(cols[idx]);
+
+		let (major_before, major_after, minor_before) = if order.rows_first {
+			(row_before(best_row, row), row_before(row, best_row), col_before(best_col, col))
+		} else {
+			(col_before(best_col, col), col_before(col, best_col), row_before(best_row, row))
+		};
+		let is_lower = (**simd).or_mask(major_before, (**simd).and_mask((**simd).not_mask(major_after), minor_before));
+		let next = simd.first_true_mask((**simd).and_mask(is_best, is_lower));
+		if next >= rows.len() {
+			break;
+		}
+		idx = next;
+	}
+
+	(rows[idx].zx(), cols[idx].zx(), best_val)
+}
 
-	(best_row.zx(), best_col.zx(), best_val)
+/// order in which ties are broken by the pivot search kernels, in terms of the rows and columns of
+/// the contiguous view that they traverse
+///
+/// the logical matrix may be a transposed or reversed version of that view, in which case the
+/// lowest logical column and row don't match the lowest traversed ones
+#[derive(Copy, Clone, Debug)]
+struct TieOrder {
+	/// whether the rows take precedence over the columns
+	rows_first: bool,
+	/// whether the rows are compared in decreasing order
+	rows_rev: bool,
 }
 
+impl TieOrder {
+	const COL_MAJOR: Self = Self {
+		rows_first: false,
+		rows_rev: false,
+	};
+	const ROW_MAJOR: Self = Self {
+		rows_first: true,
+		rows_rev: false,
+	};
+}
+
+/// whether the pivot search kernels should use twice as many independent accumulators
+///
+/// this is only the case for 512-bit vectors, which pulp only provides with the `nightly` feature,
+/// since the targets that support them also have twice as many vector registers
 #[inline(always)]
-#[math]
-fn best_in_col_simd<'M, T: ComplexField, S: Simd>(
+fn use_wide_unroll<T: ComplexField, S: Simd>() -> bool {
+	try_const! { core::mem::size_of::<T::SimdVec<S>>() >= 64 }
+}
+
+#[inline(always)]
+fn best_in_col_simd<'M, T: ComplexField, S: Simd, const REV: bool>(
 	simd: SimdCtx<'M, T, S>,
 	data: ColRef<'_, T, Dim<'M>, ContiguousFwd>,
 ) -> (RealReg<T::SimdVec<S>>, T::SimdIndex<S>) {
-	let (head, body4, body1, tail) = simd.batch_indices::<4>();
+	if use_wide_unroll::<T, S>() {
+		best_in_col_simd_unrolled::<T, S, 8, REV>(simd, data)
+	} else {
+		best_in_col_simd_unrolled::<T, S, 4, REV>(simd, data)
+	}
+}
+
+#[inline(always)]
+fn update_and_best_in_col_simd<'M, T: ComplexField, S: Simd>(
+	simd: SimdCtx<'M, T, S>,
+	data: ColMut<'_, T, Dim<'M>, ContiguousFwd>,
+	lhs: ColRef<'_, T, Dim<'M>, ContiguousFwd>,
+	rhs: T,
+) -> (RealReg<T::SimdVec<S>>, T::SimdIndex<S>) {
+	if use_wide_unroll::<T, S>() {
+		update_and_best_in_col_simd_unrolled::<T, S, 6>(simd, data, lhs, rhs)
+	} else {
+		update_and_best_in_col_simd_unrolled::<T, S, 3>(simd, data, lhs, rhs)
+	}
+}
+
+/// reduces the `N` independent accumulators into the first one
+#[inline(always)]
+fn reduce_accumulators<T: ComplexField, S: Simd, const N: usize, const REV: bool>(
+	simd: &SimdCtx<T, S>,
+	best_val: [RealReg<T::SimdVec<S>>; N],
+	best_idx: [T::SimdIndex<S>; N],
+) -> (RealReg<T::SimdVec<S>>, T::SimdIndex<S>) {
+	let mut best_val = best_val;
+	let mut best_idx = best_idx;
+
+	let mut n = N;
+	while n > 1 {
+		let half = n.div_ceil(2);
+		for k in 0..n - half {
+			(best_val[k], best_idx[k]) = best_score::<T, S, REV>(simd, best_val[k], best_idx[k], best_val[k + half], best_idx[k + half]);
+		}
+		n = half;
+	}
+
+	(best_val[0], best_idx[0])
+}
+
+#[inline(always)]
+fn best_in_col_simd_unrolled<'M, T: ComplexField, S: Simd, const N: usize, const REV: bool>(
+	simd: SimdCtx<'M, T, S>,
+	data: ColRef<'_, T, Dim<'M>, ContiguousFwd>,
+) -> (RealReg<T::SimdVec<S>>, T::SimdIndex<S>) {
+	let (head, body, body1, tail) = simd.batch_indices::<N>();
 
 	let iota = T::simd_iota(&simd.0);
 	let lane_count = core::mem::size_of::<T::SimdVec<S>>() / core::mem::size_of::<T>();
 
 	let inc1 = simd.isplat(T::Index::truncate(lane_count));
-	let inc4 = simd.isplat(T::Index::truncate(4 * lane_count));
+	let inc = simd.isplat(T::Index::truncate(N * lane_count));
 
-	let mut best_val0 = simd.splat_real(&zero());
-	let mut best_val1 = simd.splat_real(&zero());
-	let mut best_val2 = simd.splat_real(&zero());
-	let mut best_val3 = simd.splat_real(&zero());
+	let mut best_val = [simd.splat_real(&zero()); N];
+	let mut best_idx = [simd.isplat(T::Index::truncate(0)); N];
 
-	let mut best_idx0 = simd.isplat(T::Index::truncate(0));
-	let mut best_idx1 = simd.isplat(T::Index::truncate(0));
-	let mut best_idx2 = simd.isplat(T::Index::truncate(0));
-	let mut best_idx3 = simd.isplat(T::Index::truncate(0));
-
-	let mut idx0 = simd.iadd(iota, simd.isplat(T::Index::truncate(simd.offset().wrapping_neg())));
-	let mut idx1 = simd.iadd(idx0, inc1);
-	let mut idx2 = simd.iadd(idx1, inc1);
-	let mut idx3 = simd.iadd(idx2, inc1);
+	let mut idx = [simd.iadd(iota, simd.isplat(T::Index::truncate(simd.offset().wrapping_neg()))); N];
+	for k in 1..N {
+		idx[k] = simd.iadd(idx[k - 1], inc1);
+	}
 
 	if let Some(i0) = head {
-		(best_val0, best_idx0) = best_value(&simd, best_val0, best_idx0, simd.read(data, i0), idx0);
-		idx0 = simd.iadd(idx0, inc1);
+		(best_val[0], best_idx[0]) = best_value::<T, S, REV>(&simd, best_val[0], best_idx[0], simd.read(data, i0), idx[0]);
+		idx[0] = simd.iadd(idx[0], inc1);
 	}
 
-	for [i0, i1, i2, i3] in body4 {
-		(best_val0, best_idx0) = best_value(&simd, best_val0, best_idx0, simd.read(data, i0), idx0);
-		(best_val1, best_idx1) = best_value(&simd, best_val1, best_idx1, simd.read(data, i1), idx1);
-		(best_val2, best_idx2) = best_value(&simd, best_val2, best_idx2, simd.read(data, i2), idx2);
-		(best_val3, best_idx3) = best_value(&simd, best_val3, best_idx3, simd.read(data, i3), idx3);
-
-		idx0 = simd.iadd(idx0, inc4);
-		idx1 = simd.iadd(idx1, inc4);
-		idx2 = simd.iadd(idx2, inc4);
-		idx3 = simd.iadd(idx3, inc4);
+	for i in body {
+		for k in 0..N {
+			(best_val[k], best_idx[k]) = best_value::<T, S, REV>(&simd, best_val[k], best_idx[k], simd.read(data, i[k]), idx[k]);
+			idx[k] = simd.iadd(idx[k], inc);
+		}
 	}
 
 	for i0 in body1 {
-		(best_val0, best_idx0) = best_value(&simd, best_val0, best_idx0, simd.read(data, i0), idx0);
-		idx0 = simd.iadd(idx0, inc1);
+		(best_val[0], best_idx[0]) = best_value::<T, S, REV>(&simd, best_val[0], best_idx[0], simd.read(data, i0), idx[0]);
+		idx[0] = simd.iadd(idx[0], inc1);
 	}
 
 	if let Some(i0) = tail {
-		(best_val0, best_idx0) = best_value(&simd, best_val0, best_idx0, simd.read(data, i0), idx0);
+		(best_val[0], best_idx[0]) = best_value::<T, S, REV>(&simd, best_val[0], best_idx[0], simd.read(data, i0), idx[0]);
 	}
 
-	(best_val0, best_idx0) = best_score(&simd, best_val0, best_idx0, best_val1, best_idx1);
-	(best_val2, best_idx2) = best_score(&simd, best_val2, best_idx2, best_val3, best_idx3);
-	best_score(&simd, best_val0, best_idx0, best_val2, best_idx2)
+	reduce_accumulators::<T, S, N, REV>(&simd, best_val, best_idx)
 }
 
 #[inline(always)]
 #[math]
-fn update_and_best_in_col_simd<'M, T: ComplexField, S: Simd>(
+fn update_and_best_in_col_simd_unrolled<'M, T: ComplexField, S: Simd, const N: usize>(
 	simd: SimdCtx<'M, T, S>,
 	data: ColMut<'_, T, Dim<'M>, ContiguousFwd>,
 	lhs: ColRef<'_, T, Dim<'M>, ContiguousFwd>,
@@ -145,25 +264,21 @@ fn update_and_best_in_col_simd<'M, T: ComplexField, S: Simd>(
 ) -> (RealReg<T::SimdVec<S>>, T::SimdIndex<S>) {
 	let mut data = data;
 
-	let (head, body4, body1, tail) = simd.batch_indices::<3>();
+	let (head, body, body1, tail) = simd.batch_indices::<N>();
 
 	let iota = T::simd_iota(&simd.0);
 	let lane_count = core::mem::size_of::<T::SimdVec<S>>() / core::mem::size_of::<T>();
 
 	let inc1 = simd.isplat(T::Index::truncate(lane_count));
-	let inc3 = simd.isplat(T::Index::truncate(3 * lane_count));
+	let inc = simd.isplat(T::Index::truncate(N * lane_count));
 
-	let mut best_val0 = simd.splat_real(&zero());
-	let mut best_val1 = simd.splat_real(&zero());
-	let mut best_val2 = simd.splat_real(&zero());
+	let mut best_val = [simd.splat_real(&zero()); N];
+	let mut best_idx = [simd.isplat(T::Index::truncate(0)); N];
 
-	let mut best_idx0 = simd.isplat(T::Index::truncate(0));
-	let mut best_idx1 = simd.isplat(T::Index::truncate(0));
-	let mut best_idx2 = simd.isplat(T::Index::truncate(0));
-
-	let mut idx0 = simd.iadd(iota, simd.isplat(T::Index::truncate(simd.offset().wrapping_neg())));
-	let mut idx1 = simd.iadd(idx0, inc1);
-	let mut idx2 = simd.iadd(idx1, inc1);
+	let mut idx = [simd.iadd(iota, simd.isplat(T::Index::truncate(simd.offset().wrapping_neg()))); N];
+	for k in 1..N {
+		idx[k] = simd.iadd(idx[k - 1], inc1);
+	}
 
 	let rhs = simd.splat(&-rhs);
 
@@ -172,34 +287,22 @@ fn update_and_best_in_col_simd<'M, T: ComplexField, S: Simd>(
 		let l0 = simd.read(lhs, i0);
 		x0 = simd.mul_add(l0, rhs, x0);
 
-		(best_val0, best_idx0) = best_value(&simd, best_val0, best_idx0, x0, idx0);
-		idx0 = simd.iadd(idx0, inc1);
+		(best_val[0], best_idx[0]) = best_value::<T, S, false>(&simd, best_val[0], best_idx[0], x0, idx[0]);
+		idx[0] = simd.iadd(idx[0], inc1);
 
 		simd.write(data.rb_mut(), i0, x0);
 	}
 
-	for [i0, i1, i2] in body4 {
-		let mut x0 = simd.read(data.rb(), i0);
-		let l0 = simd.read(lhs, i0);
-		x0 = simd.mul_add(l0, rhs, x0);
-		(best_val0, best_idx0) = best_value(&simd, best_val0, best_idx0, simd.read(data.rb(), i0), idx0);
-		simd.write(data.rb_mut(), i0, x0);
+	for i in body {
+		for k in 0..N {
+			let mut x = simd.read(data.rb(), i[k]);
+			let l = simd.read(lhs, i[k]);
+			x = simd.mul_add(l, rhs, x);
+			(best_val[k], best_idx[k]) = best_value::<T, S, false>(&simd, best_val[k], best_idx[k], x, idx[k]);
+			simd.write(data.rb_mut(), i[k], x);
 
-		let mut x1 = simd.read(data.rb(), i1);
-		let l1 = simd.read(lhs, i1);
-		x1 = simd.mul_add(l1, rhs, x1);
-		(best_val1, best_idx1) = best_value(&simd, best_val1, best_idx1, simd.read(data.rb(), i1), idx1);
-		simd.write(data.rb_mut(), i1, x1);
-
-		let mut x2 = simd.read(data.rb(), i2);
-		let l2 = simd.read(lhs, i2);
-		x2 = simd.mul_add(l2, rhs, x2);
-		(best_val2, best_idx2) = best_value(&simd, best_val2, best_idx2, simd.read(data.rb(), i2), idx2);
-		simd.write(data.rb_mut(), i2, x2);
-
-		idx0 = simd.iadd(idx0, inc3);
-		idx1 = simd.iadd(idx1, inc3);
-		idx2 = simd.iadd(idx2, inc3);
+			idx[k] = simd.iadd(idx[k], inc);
+		}
 	}
 
 	for i0 in body1 {
@@ -207,8 +310,8 @@ fn update_and_best_in_col_simd<'M, T: ComplexField, S: Simd>(
 		let l0 = simd.read(lhs, i0);
 		x0 = simd.mul_add(l0, rhs, x0);
 
-		(best_val0, best_idx0) = best_value(&simd, best_val0, best_idx0, x0, idx0);
-		idx0 = simd.iadd(idx0, inc1);
+		(best_val[0], best_idx[0]) = best_value::<T, S, false>(&simd, best_val[0], best_idx[0], x0, idx[0]);
+		idx[0] = simd.iadd(idx[0], inc1);
 
 		simd.write(data.rb_mut(), i0, x0);
 	}
@@ -218,58 +321,78 @@ fn update_and_best_in_col_simd<'M, T: ComplexField, S: Simd>(
 		let l0 = simd.read(lhs, i0);
 		x0 = simd.mul_add(l0, rhs, x0);
 
-		(best_val0, best_idx0) = best_value(&simd, best_val0, best_idx0, x0, idx0);
+		(best_val[0], best_idx[0]) = best_value::<T, S, false>(&simd, best_val[0], best_idx[0], x0, idx[0]);
 
 		simd.write(data.rb_mut(), i0, x0);
 	}
 
-	(best_val0, best_idx0) = best_score(&simd, best_val0, best_idx0, best_val1, best_idx1);
-	best_score(&simd, best_val0, best_idx0, best_val2, best_idx2)
+	reduce_accumulators::<T, S, N, false>(&simd, best_val, best_idx)
+}
+
+#[inline(always)]
+#[math]
+fn best_in_mat_simd_imp<'M, 'N, T: ComplexField, S: Simd, const REV: bool>(
+	simd: S,
+	data: MatRef<'_, T, Dim<'M>, Dim<'N>, ContiguousFwd>,
+	order: TieOrder,
+) -> (usize, usize, Real<T>) {
+	let M = data.nrows();
+	let N = data.ncols();
+	let simd = SimdCtx::<'_, T, S>::new(T::simd_ctx(simd), M);
+
+	let mut best_row = simd.isplat(T::Index::truncate(0));
+	let mut best_col = simd.isplat(T::Index::truncate(0));
+	let mut best_val = simd.splat_real(&zero());
+
+	for j in N.indices() {
+		let col = data.col(j);
+		let (best_val_j, best_row_j) = best_in_col_simd::<T, S, REV>(simd, col);
+
+		(best_val, best_row, best_col) = best_score_2d::<T, S, REV>(
+			&simd,
+			order,
+			best_val,
+			best_row,
+			best_col,
+			best_val_j,
+			best_row_j,
+			simd.isplat(T::Index::truncate(*j)),
+		);
+	}
+	reduce_2d::<T, S, REV>(&simd, order, best_val, best_row, best_col)
 }
 
 #[inline(always)]
-fn best_in_mat_simd<T: ComplexField>(data: MatRef<'_, T, usize, usize, ContiguousFwd>) -> (usize, usize, Real<T>) {
+fn best_in_mat_simd<T: ComplexField>(data: MatRef<'_, T, usize, usize, ContiguousFwd>, order: TieOrder) -> (usize, usize, Real<T>) {
 	struct Impl<'a, 'M, 'N, T: ComplexField> {
 		data: MatRef<'a, T, Dim<'M>, Dim<'N>, ContiguousFwd>,
+		order: TieOrder,
 	}
 
 	impl<'a, 'M, 'N, T: ComplexField> pulp::WithSimd for Impl<'a, 'M, 'N, T> {
 		type Output = (usize, usize, Real<T>);
 
-		#[math]
 		#[inline(always)]
 		fn with_simd<S: Simd>(self, simd: S) -> Self::Output {
-			let Self { data } = self;
-
-			let M = data.nrows();
-			let N = data.ncols();
-			let simd = SimdCtx::<'_, T, S>::new(T::simd_ctx(simd), M);
-
-			let mut best_row = simd.isplat(T::Index::truncate(0));
-			let mut best_col = simd.isplat(T::Index::truncate(0));
-			let mut best_val = simd.splat_real(&zero());
-
-			for j in N.indices() {
-				let col = data.col(j);
-				let (best_val_j, best_row_j) = best_in_col_simd(simd, col);
-
-				(best_val, best_row, best_col) = best_score_2d(
-					&simd,
-					best_val,
-					best_row,
-					best_col,
-					best_val_j,
-					best_row_j,
-					simd.isplat(T::Index::truncate(*j)),
-				);
+			let Self { data, order } = self;
+			if order.rows_rev {
+				best_in_mat_simd_imp::<T, S, true>(simd, data, order)
+			} else {
+				best_in_mat_simd_imp::<T, S, false>(simd, data, order)
 			}
-			reduce_2d(&simd, best_val, best_row, best_col)
 		}
 	}
 
 	with_dim!(M, data.nrows());
 	with_dim!(N, data.ncols());
-	dispatch!(Impl { data: data.as_shape(M, N) }, Impl, T)
+	dispatch!(
+		Impl {
+			data: data.as_shape(M, N),
+			order
+		},
+		Impl,
+		T
+	)
 }
 
 #[inline(always)]
@@ -278,12 +401,14 @@ fn update_and_best_in_mat_simd<T: ComplexField>(
 	lhs: ColRef<'_, T, usize, ContiguousFwd>,
 	rhs: RowRef<'_, T, usize>,
 	align: usize,
+	order: TieOrder,
 ) -> (usize, usize, Real<T>) {
 	struct Impl<'a, 'M, 'N, T: ComplexField> {
 		data: MatMut<'a, T, Dim<'M>, Dim<'N>, ContiguousFwd>,
 		lhs: ColRef<'a, T, Dim<'M>, ContiguousFwd>,
 		rhs: RowRef<'a, T, Dim<'N>>,
 		align: usize,
+		order: TieOrder,
 	}
 
 	impl<'a, 'M, 'N, T: ComplexField> pulp::WithSimd for Impl<'a, 'M, 'N, T> {
@@ -292,7 +417,13 @@ fn update_and_best_in_mat_simd<T: ComplexField>(
 		#[math]
 		#[inline(always)]
 		fn with_simd<S: Simd>(self, simd: S) -> Self::Output {
-			let Self { data, lhs, rhs, align } = self;
+			let Self {
+				data,
+				lhs,
+				rhs,
+				align,
+				order,
+			} = self;
 
 			let M = data.nrows();
 			let N = data.ncols();
@@ -308,8 +439,9 @@ fn update_and_best_in_mat_simd<T: ComplexField>(
 				let rhs = copy(rhs[j]);
 				let (best_val_j, best_row_j) = update_and_best_in_col_simd(simd, data, lhs, rhs);
 
-				(best_val, best_row, best_col) = best_score_2d(
+				(best_val, best_row, best_col) = best_score_2d::<T, S, false>(
 					&simd,
+					order,
 					best_val,
 					best_row,
 					best_col,
@@ -318,7 +450,7 @@ fn update_and_best_in_mat_simd<T: ComplexField>(
 					simd.isplat(T::Index::truncate(*j)),
 				);
 			}
-			reduce_2d(&simd, best_val, best_row, best_col)
+			reduce_2d::<T, S, false>(&simd, order, best_val, best_row, best_col)
 		}
 	}
 
@@ -330,6 +462,7 @@ fn update_and_best_in_mat_simd<T: ComplexField>(
 			lhs: lhs.as_row_shape(M),
 			rhs: rhs.as_col_shape(N),
 			align,
+			order,
 		},
 		Impl,
 		T
@@ -358,18 +491,54 @@ fn best_in_matrix_fallback<T: ComplexField>(data: MatRef<'_, T>) -> (usize, usiz
 	(row, col, max)
 }
 
+/// returns the position and score of the entry of `data` with the largest score
+///
+/// ties are broken in favor of the lowest column, then the lowest row, of `data`, regardless of
+/// whether it is traversed transposed or reversed. the result only depends on the values of
+/// `data`, and not on its layout or the simd width, so factorizations are reproducible across
+/// machines
 #[math]
 fn best_in_matrix<T: ComplexField>(data: MatRef<'_, T>) -> (usize, usize, Real<T>) {
+	let (m, n) = data.shape();
+	if m == 0 || n == 0 {
+		return (0, 0, zero());
+	}
+	crate::utils::trace::span!(TRACE, "pivot_search", m = m, n = n);
+
 	if try_const! { T::SIMD_CAPABILITIES.is_simd() } {
-		if let Some(dst) = data.try_as_col_major() {
-			best_in_mat_simd(dst)
+		// any matrix with a unit stride along one of its dimensions can be traversed with the
+		// contiguous kernel, after transposing and/or reversing it. the order in which ties are
+		// broken is adjusted so that it matches the one of `data`
+		let (row, col, max) = if let Some(data) = data.try_as_col_major() {
+			best_in_mat_simd(data, TieOrder::COL_MAJOR)
+		} else if let Some(data) = data.transpose().try_as_col_major() {
+			let (col, row, max) = best_in_mat_simd(data, TieOrder::ROW_MAJOR);
+			(row, col, max)
+		} else if let Some(data) = data.reverse_rows().try_as_col_major() {
+			let order = TieOrder {
+				rows_first: false,
+				rows_rev: true,
+			};
+			let (row, col, max) = best_in_mat_simd(data, order);
+			(m - 1 - row, col, max)
+		} else if let Some(data) = data.reverse_cols().transpose().try_as_col_major() {
+			let order = TieOrder {
+				rows_first: true,
+				rows_rev: true,
+			};
+			let (col, row, max) = best_in_mat_simd(data, order);
+			(row, n - 1 - col, max)
 		} else {
-			best_in_matrix_fallback(data)
-		}
-	} else {
-		best_in_matrix_fallback(data)
+			return best_in_matrix_fallback(data);
+		};
+
+		// an entry is only selected if it's strictly larger than zero, like in the fallback
+		return if max == zero() { (0, 0, max) } else { (row, col, max) };
 	}
+
+	best_in_matrix_fallback(data)
 }
+
 #[math]
 fn rank_one_update_and_best_in_matrix<T: ComplexField>(
 	mut dst: MatMut<'_, T>,
@@ -379,15 +548,16 @@ fn rank_one_update_and_best_in_matrix<T: ComplexField>(
 ) -> (usize, usize, Real<T>) {
 	if try_const! { T::SIMD_CAPABILITIES.is_simd() } {
 		if let (Some(dst), Some(lhs)) = (dst.rb_mut().try_as_col_major_mut(), lhs.try_as_col_major()) {
-			update_and_best_in_mat_simd(dst, lhs, rhs, align)
-		} else {
-			matmul(dst.rb_mut(), Accum::Add, lhs.as_mat(), rhs.as_mat(), -one::<T>(), Par::Seq);
-			best_in_matrix(dst.rb())
+			return update_and_best_in_mat_simd(dst, lhs, rhs, align, TieOrder::COL_MAJOR);
+		}
+		if let (Some(dst), Some(rhs)) = (dst.rb_mut().transpose_mut().try_as_col_major_mut(), rhs.transpose().try_as_col_major()) {
+			let (col, row, max) = update_and_best_in_mat_simd(dst, rhs, lhs.transpose(), align, TieOrder::ROW_MAJOR);
+			return (row, col, max);
 		}
-	} else {
-		matmul(dst.rb_mut(), Accum::Add, lhs.as_mat(), rhs.as_mat(), -one::<T>(), Par::Seq);
-		best_in_matrix(dst.rb())
 	}
+
+	matmul(dst.rb_mut(), Accum::Add, lhs.as_mat(), rhs.as_mat(), -one::<T>(), Par::Seq);
+	best_in_matrix(dst.rb())
 }
 
 #[math]
@@ -501,6 +671,40 @@ fn lu_in_place_unblocked<T: ComplexField>(
 	n_trans
 }
 
+/// returns a power of two that brings the largest entry of `mat` back to a safe range, if the
+/// entries are so large that the pivot scores would overflow, or so small that the whole matrix
+/// would be considered numerically zero
+///
+/// the scale is a power of two, so scaling is exact unless some entries underflow
+#[math]
+fn extreme_scale<T: ComplexField>(mat: MatRef<'_, T>) -> Option<T::Real> {
+	// the max norm uses $\max(|\text{re}|, |\text{im}|)$, which can't overflow for finite entries
+	let max = crate::linalg::reductions::norm_max::norm_max(mat);
+	if !is_finite(max) || max == zero::<T::Real>() {
+		return None;
+	}
+
+	let hi = max_positive::<T::Real>() * eps::<T::Real>();
+	let lo = min_positive::<T::Real>() / eps::<T::Real>();
+	if max <= hi && max >= lo {
+		return None;
+	}
+
+	// the scale is restricted to $[\sqrt{\text{min}}, \sqrt{\text{max}}]$ so that it and its
+	// reciprocal are representable, which is enough to bring any finite nonzero max into $[\text{lo},
+	// \text{hi}]$
+	let two = from_f64::<T::Real>(2.0);
+	let half = from_f64::<T::Real>(0.5);
+	let mut scale = one::<T::Real>();
+	while max * scale >= one::<T::Real>() && scale > sqrt_min_positive::<T::Real>() {
+		scale = scale * half;
+	}
+	while max * scale < half && scale < sqrt_max_positive::<T::Real>() {
+		scale = scale * two;
+	}
+	Some(scale)
+}
+
 /// $LU$ factorization tuning parameters
 #[derive(Copy, Clone, Debug)]
 pub struct FullPivLuParams {
@@ -511,9 +715,9 @@ pub struct FullPivLuParams {
 	pub non_exhaustive: NonExhaustive,
 }
 
-impl<T: ComplexField> Auto<T> for FullPivLuParams {
+impl FullPivLuParams {
 	#[inline]
-	fn auto() -> Self {
+	pub(crate) fn builtin() -> Self {
 		Self {
 			par_threshold: 256 * 512,
 			non_exhaustive: NonExhaustive(()),
@@ -521,6 +725,17 @@ impl<T: ComplexField> Auto<T> for FullPivLuParams {
 	}
 }
 
+impl<T: ComplexField> Auto<T> for FullPivLuParams {
+	#[inline]
+	fn auto() -> Self {
+		let mut params = Self::builtin();
+		if let Some(tuned) = linalg::tune::params_or_tune::<T>() {
+			params.par_threshold = tuned.full_piv_lu_par_threshold;
+		}
+		params
+	}
+}
+
 #[inline]
 pub fn lu_in_place_scratch<I: Index, T: ComplexField>(nrows: usize, ncols: usize, par: Par, params: Spec<FullPivLuParams, T>) -> StackReq {
 	_ = par;
@@ -534,6 +749,11 @@ pub struct FullPivLuInfo {
 	pub transposition_count: usize,
 }
 
+/// computes the $LU$ factorization of $A$ with full pivoting, overwriting $A$ with the factors
+///
+/// the pivot at each step is the entry of the trailing submatrix with the largest magnitude, with
+/// ties broken in favor of the lowest column, then the lowest row, counted in the logical order of
+/// $A$ regardless of its strides. see the [module level documentation](super) for more details
 pub fn lu_in_place<'out, I: Index, T: ComplexField>(
 	mat: MatMut<'_, T>,
 	row_perm: &'out mut [I],
@@ -550,6 +770,8 @@ pub fn lu_in_place<'out, I: Index, T: ComplexField>(
 	}
 
 	let (M, N) = mat.shape();
+	crate::utils::trace::span!(DEBUG, "lu_full_piv", m = M, n = N);
+	crate::utils::counters::count!(Lu, T, fma = crate::utils::counters::lu_fma(M, N), elems = 2 * M * N);
 
 	let size = Ord::min(M, N);
 
@@ -558,12 +780,27 @@ pub fn lu_in_place<'out, I: Index, T: ComplexField>(
 	let (mut col_transpositions, _) = stack.make_with(size, |_| 0);
 	let col_transpositions = col_transpositions.as_mut();
 
+	let mut mat = mat;
+	let scale = extreme_scale::<T>(mat.rb());
+	if let Some(scale) = &scale {
+		z!(mat.rb_mut()).for_each(|uz!(x)| *x = mul_real(x, scale));
+	}
+
 	let n_transpositions = if mat.row_stride().abs() < mat.col_stride().abs() {
-		lu_in_place_unblocked(mat, row_transpositions, col_transpositions, par, false, params)
+		lu_in_place_unblocked(mat.rb_mut(), row_transpositions, col_transpositions, par, false, params)
 	} else {
-		lu_in_place_unblocked(mat.transpose_mut(), col_transpositions, row_transpositions, par, true, params)
+		lu_in_place_unblocked(mat.rb_mut().transpose_mut(), col_transpositions, row_transpositions, par, true, params)
 	};
 
+	if let Some(scale) = &scale {
+		let inv = recip(scale);
+		for j in 0..N {
+			for i in 0..Ord::min(j + 1, M) {
+				mat[(i, j)] = mul_real(&mat[(i, j)], &inv);
+			}
+		}
+	}
+
 	for i in 0..M {
 		row_perm[i] = I::truncate(i);
 	}
@@ -600,7 +837,7 @@ mod tests {
 	use super::*;
 	use crate::stats::prelude::*;
 	use crate::utils::approx::*;
-	use crate::{Mat, assert, c64};
+	use crate::{Col, Mat, Row, assert, c32, c64};
 	use dyn_stack::MemBuffer;
 
 	#[test]
@@ -717,4 +954,248 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	#[cfg(feature = "rayon")]
+	fn test_flu_reproducible() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n) = (300, 200);
+		let par = Par::rayon(4);
+
+		let A = CwiseMatDistribution {
+			nrows: m,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+
+		// parallelize every step, so that the pivot search and the trailing updates are split
+		let params = FullPivLuParams {
+			par_threshold: 0,
+			..auto!(f64)
+		};
+
+		let factor = || {
+			let mut LU = A.clone();
+			let row_perm = &mut *vec![0usize; m];
+			let row_perm_inv = &mut *vec![0usize; m];
+			let col_perm = &mut *vec![0usize; n];
+			let col_perm_inv = &mut *vec![0usize; n];
+			lu_in_place(
+				LU.as_mut(),
+				row_perm,
+				row_perm_inv,
+				col_perm,
+				col_perm_inv,
+				par,
+				MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, f64>(m, n, par, params.into()))),
+				params.into(),
+			);
+			(Mat::from_fn(m, n, |i, j| LU[(i, j)].to_bits()), row_perm.to_vec(), col_perm.to_vec())
+		};
+
+		let first = factor();
+		for _ in 0..4 {
+			assert!(factor() == first);
+		}
+	}
+
+	#[test]
+	fn test_flu_extreme_scale() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n) = (12, 9);
+
+		let widen = |A: MatRef<'_, c32>| Mat::<c64>::from_fn(A.nrows(), A.ncols(), |i, j| c64::new(A[(i, j)].re as f64, A[(i, j)].im as f64));
+
+		// subnormal entries, and entries whose $|\text{re}| + |\text{im}|$ overflows
+		for factor in [2.0f32.powi(-130), 2.0f32.powi(126)] {
+			for row_major in [false, true] {
+				let A = CwiseMatDistribution {
+					nrows: m,
+					ncols: n,
+					dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+				}
+				.rand::<Mat<c32>>(rng);
+				let A = Mat::<c32>::from_fn(m, n, |i, j| A[(i, j)] * factor);
+
+				let mut storage = if row_major { A.transpose().to_owned() } else { A.clone() };
+				let mut LU = if row_major { storage.as_mut().transpose_mut() } else { storage.as_mut() };
+
+				let row_perm = &mut *vec![0usize; m];
+				let row_perm_inv = &mut *vec![0usize; m];
+				let col_perm = &mut *vec![0usize; n];
+				let col_perm_inv = &mut *vec![0usize; n];
+
+				let (_, p, q) = lu_in_place(
+					LU.rb_mut(),
+					row_perm,
+					row_perm_inv,
+					col_perm,
+					col_perm_inv,
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, c32>(m, n, Par::Seq, default()))),
+					default(),
+				);
+
+				let LU = widen(LU.rb());
+				let L = Mat::<c64>::from_fn(m, n, |i, j| {
+					if i > j {
+						LU[(i, j)]
+					} else if i == j {
+						c64::ONE
+					} else {
+						c64::ZERO
+					}
+				});
+				let U = Mat::<c64>::from_fn(n, n, |i, j| if i <= j { LU[(i, j)] } else { c64::ZERO });
+
+				// the matrix is full rank, so none of the pivots should be zero
+				for k in 0..n {
+					assert!(U[(k, k)] != c64::ZERO);
+				}
+
+				let A = widen(A.as_ref());
+				let err = (p.inverse() * &L * &U * q - &A).norm_max();
+				assert!(err <= 1e-5 * A.norm_max());
+			}
+		}
+	}
+
+	#[test]
+	fn test_best_in_matrix_ties() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(1, 1), (3, 5), (17, 9), (64, 33), (129, 130)] {
+			// few distinct values, so the maximum is reached many times
+			let A = Mat::<f64>::from_fn(m + 3, n, |_, _| (rng.gen_range(0..4) as f64) - 2.0);
+			let Z = Mat::<c64>::from_fn(m + 3, n, |i, j| c64::new(A[(i, j)], 0.0));
+
+			let lhs = Col::<f64>::from_fn(m + 3, |i| (i % 2) as f64);
+			let rhs = Row::<f64>::from_fn(n, |j| (j % 3) as f64);
+
+			// the offset views exercise the masked head of the simd kernels
+			for offset in 0..4 {
+				let view = A.get(offset..offset + m, ..);
+				let Z = Z.get(offset..offset + m, ..);
+
+				// the ties are broken in the order of the logical matrix, whichever way it's traversed
+				for (view, Z) in [
+					(view, Z),
+					(view.transpose(), Z.transpose()),
+					(view.reverse_rows(), Z.reverse_rows()),
+					(view.reverse_cols(), Z.reverse_cols()),
+					(view.reverse_rows_and_cols(), Z.reverse_rows_and_cols()),
+					(view.transpose().reverse_rows(), Z.transpose().reverse_rows()),
+					(view.transpose().reverse_cols(), Z.transpose().reverse_cols()),
+				] {
+					assert!(best_in_matrix(view) == best_in_matrix_fallback(view));
+					assert!(best_in_matrix(Z) == best_in_matrix_fallback(Z));
+				}
+
+				let mut dst = A.clone();
+				let mut dst = dst.get_mut(offset..offset + m, ..);
+				// `lhs` must have the same alignment as `dst`, as is the case during the factorization
+				let lhs = lhs.get(offset..offset + m);
+				let best = rank_one_update_and_best_in_matrix(dst.rb_mut(), lhs, rhs.as_ref(), simd_align(offset));
+				assert!(best == best_in_matrix_fallback(dst.rb()));
+			}
+
+			// row-major destination, which is traversed transposed
+			let mut dst_t = A.transpose().to_owned();
+			let mut dst = dst_t.as_mut().transpose_mut();
+			let best = rank_one_update_and_best_in_matrix(dst.rb_mut(), lhs.as_ref(), rhs.as_ref(), 0);
+			assert!(best == best_in_matrix_fallback(dst.rb()));
+
+			// all the entries are zero
+			let zero = Mat::<f64>::zeros(m, n);
+			assert!(best_in_matrix(zero.as_ref().reverse_rows()) == (0, 0, 0.0));
+			assert!(best_in_matrix(zero.as_ref().transpose().reverse_cols()) == (0, 0, 0.0));
+		}
+	}
+
+	#[test]
+	fn test_update_and_best_in_matrix_updated_values() {
+		for m in [8, 64, 128, 257] {
+			let n = 3;
+
+			// the largest entry before the update is cancelled by it, and the largest entry after
+			// the update was zero before it
+			let (big, small) = (m / 2 + 1, m / 4);
+			let A = Mat::<f64>::from_fn(m, n, |i, j| if (i, j) == (big, 1) { 10.0 } else { 0.0 });
+			let lhs = Col::<f64>::from_fn(m, |i| {
+				if i == big {
+					10.0
+				} else if i == small {
+					-5.0
+				} else {
+					0.0
+				}
+			});
+			let rhs = Row::<f64>::from_fn(n, |j| if j == 1 { 1.0 } else { 0.0 });
+
+			let mut dst = A.clone();
+			let best = rank_one_update_and_best_in_matrix(dst.as_mut(), lhs.as_ref(), rhs.as_ref(), 0);
+			assert!(dst[(big, 1)] == 0.0);
+			assert!(best == (small, 1, 5.0));
+		}
+	}
+
+	#[test]
+	fn test_best_in_matrix_strided() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq {
+			abs_tol: 1e-12,
+			rel_tol: 1e-12,
+		});
+
+		for (m, n) in [(1, 1), (3, 5), (17, 9), (64, 33), (129, 130)] {
+			let A = CwiseMatDistribution {
+				nrows: m,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			let At = A.transpose().to_owned();
+
+			let (_, _, expected) = best_in_matrix_fallback(A.as_ref());
+
+			for view in [
+				A.as_ref(),
+				At.transpose(),
+				A.reverse_rows(),
+				At.transpose().reverse_cols(),
+				A.reverse_rows_and_cols(),
+			] {
+				let (row, col, max) = best_in_matrix(view);
+				assert!(max == expected);
+				assert!(view[(row, col)].abs() == expected);
+			}
+
+			let lhs = CwiseColDistribution {
+				nrows: m,
+				dist: StandardNormal,
+			}
+			.rand::<Col<f64>>(rng);
+			let rhs = CwiseRowDistribution {
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Row<f64>>(rng);
+
+			let mut target = A.clone();
+			target -= &lhs * &rhs;
+
+			let mut dst = A.clone();
+			let (row, col, max) = rank_one_update_and_best_in_matrix(dst.as_mut(), lhs.as_ref(), rhs.as_ref(), 0);
+			assert!(dst ~ target);
+			assert!(max == best_in_matrix_fallback(dst.as_ref()).2);
+			assert!(dst[(row, col)].abs() == max);
+
+			let mut dst = At.clone();
+			let (row, col, max) = rank_one_update_and_best_in_matrix(dst.as_mut().transpose_mut(), lhs.as_ref(), rhs.as_ref(), 0);
+			assert!(dst.transpose() ~ target);
+			assert!(max == best_in_matrix_fallback(dst.as_ref()).2);
+			assert!(dst[(col, row)].abs() == max);
+		}
+	}
 }
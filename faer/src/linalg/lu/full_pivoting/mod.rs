@@ -2,6 +2,12 @@
 //! $$P A Q^\top = LU$$
 //! where $P$ and $Q$ are permutation matrices, $L$ is a unit lower triangular matrix, and $U$ is
 //! an upper triangular matrix.
+//!
+//! at each step, the pivot is the entry of the trailing submatrix with the largest magnitude. when
+//! several entries tie, the one in the lowest column wins, then the one in the lowest row, where
+//! rows and columns are counted in the logical (not the in-memory) order of the matrix. the choice
+//! doesn't depend on the memory layout of the matrix or on the simd width of the machine, so the
+//! factorization is reproducible across machines for a given number of threads.
 #![allow(missing_docs)]
 
 pub mod factor;
@@ -2,11 +2,19 @@ use crate::assert;
 use crate::internal_prelude::*;
 use linalg::matmul::triangular::BlockStructure;
 
+/// computes the size and alignment of required workspace for reconstructing a matrix from its
+/// $LU$ factors
 pub fn reconstruct_scratch<I: Index, T: ComplexField>(nrows: usize, ncols: usize, par: Par) -> StackReq {
 	_ = par;
 	temp_mat_scratch::<T>(nrows, ncols)
 }
 
+/// computes the matrix $A = P^\top LU Q$ from its full pivoting $LU$ factors, and stores it in
+/// `out`
+///
+/// `L` is only accessed through its strictly lower trapezoidal part, and `U` through its upper
+/// trapezoidal part, so both can refer to the same matrix, as produced by
+/// [`factor::lu_in_place`](super::factor::lu_in_place)
 #[track_caller]
 pub fn reconstruct<I: Index, T: ComplexField>(
 	out: MatMut<'_, T>,
@@ -81,6 +89,32 @@ pub fn reconstruct<I: Index, T: ComplexField>(
 	}
 }
 
+/// computes the size and alignment of required workspace for computing the residual norm of an
+/// $LU$ factorization
+pub fn residual_norm_scratch<I: Index, T: ComplexField>(nrows: usize, ncols: usize, par: Par) -> StackReq {
+	linalg::lu::partial_pivoting::reconstruct::residual_norm_scratch::<I, T>(nrows, ncols, par)
+}
+
+/// computes the relative residual $\|PAQ^\top - LU\|_F / \|A\|_F$ of the full pivoting $LU$
+/// factorization of $A$
+///
+/// the product $LU$ is formed one block of columns at a time, so the workspace only grows with the
+/// number of rows. the factors may share storage as in [`reconstruct`]
+#[track_caller]
+pub fn residual_norm<I: Index, T: ComplexField>(
+	A: MatRef<'_, T>,
+	L: MatRef<'_, T>,
+	U: MatRef<'_, T>,
+	row_perm: PermRef<'_, I>,
+	col_perm: PermRef<'_, I>,
+	par: Par,
+	stack: &mut MemStack,
+) -> T::Real {
+	let (m, n) = A.shape();
+	assert!(all(row_perm.len() == m, col_perm.len() == n));
+	linalg::lu::partial_pivoting::reconstruct::residual_norm_imp(A, L, U, row_perm.arrays().0, Some(col_perm.arrays().0), par, stack)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -132,6 +166,30 @@ mod tests {
 			);
 
 			assert!(A_rec ~ A);
+
+			let residual = reconstruct::residual_norm(
+				A.as_ref(),
+				LU.as_ref(),
+				LU.as_ref(),
+				row_perm,
+				col_perm,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(reconstruct::residual_norm_scratch::<usize, c64>(m, n, Par::Seq))),
+			);
+			assert!(residual < 1e-14);
+
+			let mut B = A.clone();
+			B[(m / 3, n / 2)] -= c64::new(0.0, 2.0);
+			let residual = reconstruct::residual_norm(
+				B.as_ref(),
+				LU.as_ref(),
+				LU.as_ref(),
+				row_perm,
+				col_perm,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(reconstruct::residual_norm_scratch::<usize, c64>(m, n, Par::Seq))),
+			);
+			assert!((residual - 2.0 / B.norm_l2()).abs() < 1e-12);
 		}
 	}
 }
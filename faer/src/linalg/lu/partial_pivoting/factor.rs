@@ -1,4 +1,5 @@
 use crate::internal_prelude::*;
+use crate::linalg::progress::ProgressMonitor;
 use crate::perm::swap_rows_idx;
 use crate::{assert, debug_assert};
 
@@ -23,6 +24,7 @@ fn lu_in_place_unblocked<I: Index, T: ComplexField>(matrix: MatMut<'_, T>, start
 	if start == end {
 		return 0;
 	}
+	crate::utils::trace::span!(DEBUG, "lu_panel", m = m, n = end - start);
 
 	let mut n_trans = 0;
 
@@ -34,11 +36,14 @@ fn lu_in_place_unblocked<I: Index, T: ComplexField>(matrix: MatMut<'_, T>, start
 		let mut imax = row;
 		let mut max = zero();
 
-		for i in imax..m {
-			let abs = abs1(matrix[(i, col)]);
-			if abs > max {
-				max = abs;
-				imax = i;
+		{
+			crate::utils::trace::span!(TRACE, "pivot_search", m = m - row);
+			for i in imax..m {
+				let abs = abs1(matrix[(i, col)]);
+				if abs > max {
+					max = abs;
+					imax = i;
+				}
 			}
 		}
 
@@ -162,12 +167,12 @@ pub(crate) fn lu_in_place_recursion<I: Index, T: ComplexField>(
 			rayon::join(
 				|| {
 					if A_left.ncols() > 0 {
-						A_left.par_col_partition_mut(left_threads).for_each(|A| swap(A))
+						A_left.par_col_partition_mut(Ord::max(left_threads, 1)).for_each(|A| swap(A))
 					}
 				},
 				|| {
 					if A_right.ncols() > 0 {
-						A_right.par_col_partition_mut(right_threads).for_each(|A| swap(A))
+						A_right.par_col_partition_mut(Ord::max(right_threads, 1)).for_each(|A| swap(A))
 					}
 				},
 			);
@@ -177,6 +182,45 @@ pub(crate) fn lu_in_place_recursion<I: Index, T: ComplexField>(
 	n_trans
 }
 
+/// right-looking variant that factors the matrix one panel at a time, so that the progress can be
+/// reported between two panels
+///
+/// returns the number of transpositions, and whether the factorization was cancelled
+#[math]
+fn lu_in_place_panels<I: Index, T: ComplexField>(
+	A: MatMut<'_, T>,
+	size: usize,
+	trans: &mut [I],
+	progress: &dyn ProgressMonitor,
+	par: Par,
+	params: Spec<PartialPivLuParams, T>,
+) -> (usize, bool) {
+	let mut A = A;
+	let blocksize = Ord::max(params.blocksize, 1);
+
+	let mut n_trans = 0;
+	let mut j = 0;
+	while j < size {
+		let bs = Ord::min(blocksize, size - j);
+		crate::utils::trace::span!(DEBUG, "lu_block", m = A.nrows() - j, n = bs, col = j);
+
+		// factor the panel, and apply its row permutation to the rest of the matrix
+		n_trans += lu_in_place_recursion(A.rb_mut().get_mut(j.., ..), j, j + bs, &mut trans[j..j + bs], par, params);
+
+		let (A00, A01, A10, A11) = A.rb_mut().get_mut(j.., j..size).split_at_mut(bs, bs);
+		let mut A01 = A01;
+		linalg::triangular_solve::solve_unit_lower_triangular_in_place(A00.rb(), A01.rb_mut(), par);
+		linalg::matmul::matmul(A11, Accum::Add, A10.rb(), A01.rb(), -one::<T>(), par);
+
+		j += bs;
+		if linalg::progress::report(Some(progress), j, size) {
+			return (n_trans, true);
+		}
+	}
+
+	(n_trans, false)
+}
+
 /// $LU$ factorization tuning parameters
 #[derive(Copy, Clone, Debug)]
 pub struct PartialPivLuParams {
@@ -197,6 +241,9 @@ pub struct PartialPivLuInfo {
 	/// number of transpositions that were performed, can be used to compute the determinant of
 	/// $P$
 	pub transposition_count: usize,
+	/// whether the factorization was cancelled by the progress monitor, in which case the
+	/// contents of the output matrix are unspecified
+	pub cancelled: bool,
 }
 
 /// error in the $LU$ factorization
@@ -205,9 +252,9 @@ pub enum LdltError {
 	ZeroPivot { index: usize },
 }
 
-impl<T: ComplexField> Auto<T> for PartialPivLuParams {
+impl PartialPivLuParams {
 	#[inline]
-	fn auto() -> Self {
+	pub(crate) fn builtin() -> Self {
 		Self {
 			recursion_threshold: 16,
 			blocksize: 64,
@@ -217,6 +264,17 @@ impl<T: ComplexField> Auto<T> for PartialPivLuParams {
 	}
 }
 
+impl<T: ComplexField> Auto<T> for PartialPivLuParams {
+	#[inline]
+	fn auto() -> Self {
+		let mut params = Self::builtin();
+		if let Some(tuned) = linalg::tune::params_or_tune::<T>() {
+			params.recursion_threshold = tuned.lu_recursion_threshold;
+		}
+		params
+	}
+}
+
 #[inline]
 pub fn lu_in_place_scratch<I: Index, T: ComplexField>(nrows: usize, ncols: usize, par: Par, params: Spec<PartialPivLuParams, T>) -> StackReq {
 	_ = par;
@@ -231,6 +289,36 @@ pub fn lu_in_place<'out, I: Index, T: ComplexField>(
 	par: Par,
 	stack: &mut MemStack,
 	params: Spec<PartialPivLuParams, T>,
+) -> (PartialPivLuInfo, PermRef<'out, I>) {
+	lu_in_place_imp(A, perm, perm_inv, par, stack, params, None)
+}
+
+/// computes the $LU$ factorization like [`lu_in_place`], reporting its progress to `progress`
+/// after each panel of [`PartialPivLuParams::blocksize`] columns
+///
+/// the matrix is factored panel by panel instead of recursively. if the monitor cancels the
+/// factorization, the `cancelled` flag of the returned info is set and the contents of `A` are
+/// unspecified
+pub fn lu_in_place_with_progress<'out, I: Index, T: ComplexField>(
+	A: MatMut<'_, T>,
+	perm: &'out mut [I],
+	perm_inv: &'out mut [I],
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<PartialPivLuParams, T>,
+	progress: &dyn ProgressMonitor,
+) -> (PartialPivLuInfo, PermRef<'out, I>) {
+	lu_in_place_imp(A, perm, perm_inv, par, stack, params, Some(progress))
+}
+
+fn lu_in_place_imp<'out, I: Index, T: ComplexField>(
+	A: MatMut<'_, T>,
+	perm: &'out mut [I],
+	perm_inv: &'out mut [I],
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<PartialPivLuParams, T>,
+	progress: Option<&dyn ProgressMonitor>,
 ) -> (PartialPivLuInfo, PermRef<'out, I>) {
 	let _ = &params;
 	let truncate = I::truncate;
@@ -246,6 +334,8 @@ pub fn lu_in_place<'out, I: Index, T: ComplexField>(
 	let n = matrix.ncols();
 
 	let size = Ord::min(n, m);
+	crate::utils::trace::span!(DEBUG, "lu_partial_piv", m = m, n = n);
+	crate::utils::counters::count!(Lu, T, fma = crate::utils::counters::lu_fma(m, n), elems = 2 * m * n);
 
 	for i in 0..m {
 		let p = &mut perm[i];
@@ -255,14 +345,20 @@ pub fn lu_in_place<'out, I: Index, T: ComplexField>(
 	let (mut transpositions, _) = stack.rb_mut().make_with(size, |_| truncate(0));
 	let transpositions = transpositions.as_mut();
 
-	let n_transpositions = lu_in_place_recursion(matrix.rb_mut(), 0, size, transpositions.as_mut(), par, params);
+	let (n_transpositions, cancelled) = match progress {
+		Some(progress) => lu_in_place_panels(matrix.rb_mut(), size, transpositions.as_mut(), progress, par, params),
+		None => (
+			lu_in_place_recursion(matrix.rb_mut(), 0, size, transpositions.as_mut(), par, params),
+			false,
+		),
+	};
 
 	for idx in 0..size {
 		let t = transpositions[idx];
 		perm.as_mut().swap(idx, idx + t.zx());
 	}
 
-	if m < n {
+	if m < n && !cancelled {
 		let (left, right) = matrix.split_at_col_mut(size);
 		linalg::triangular_solve::solve_unit_lower_triangular_in_place(left.rb(), right, par);
 	}
@@ -274,6 +370,7 @@ pub fn lu_in_place<'out, I: Index, T: ComplexField>(
 	(
 		PartialPivLuInfo {
 			transposition_count: n_transpositions,
+			cancelled,
 		},
 		unsafe { PermRef::new_unchecked(perm, perm_inv, m) },
 	)
@@ -392,4 +489,76 @@ mod tests {
 			assert!(p.inverse() * L * U ~ A);
 		}
 	}
+
+	#[test]
+	#[cfg(feature = "rayon")]
+	fn test_plu_reproducible() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 300;
+		let par = Par::rayon(4);
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+
+		// parallelize every step, so that the row swaps and the trailing updates are split
+		let params = PartialPivLuParams {
+			par_threshold: 0,
+			..auto!(f64)
+		};
+
+		let factor = || {
+			let mut LU = A.clone();
+			let perm = &mut *vec![0usize; n];
+			let perm_inv = &mut *vec![0usize; n];
+			lu_in_place(
+				LU.as_mut(),
+				perm,
+				perm_inv,
+				par,
+				MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, f64>(n, n, par, params.into()))),
+				params.into(),
+			);
+			(Mat::from_fn(n, n, |i, j| LU[(i, j)].to_bits()), perm.to_vec())
+		};
+
+		let first = factor();
+		for _ in 0..4 {
+			assert!(factor() == first);
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "rayon")]
+	fn test_plu_panel_par() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 64;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+
+		let params = PartialPivLuParams {
+			recursion_threshold: 1,
+			par_threshold: 0,
+			..auto!(f64)
+		};
+
+		// factor the panel `2..4` of the full matrix. the columns to the left of the panel are
+		// few enough that they get assigned less than one thread for the row swaps
+		let factor = |par: Par| {
+			let mut LU = A.clone();
+			let trans = &mut *vec![0usize; 2];
+			let n_trans = lu_in_place_recursion(LU.as_mut().get_mut(2.., ..), 2, 4, trans, par, params.into());
+			(LU, trans.to_vec(), n_trans)
+		};
+
+		assert!(factor(Par::rayon(4)) == factor(Par::Seq));
+	}
 }
@@ -2,11 +2,19 @@ use crate::assert;
 use crate::internal_prelude::*;
 use linalg::matmul::triangular::BlockStructure;
 
+/// computes the size and alignment of required workspace for reconstructing a matrix from its
+/// $LU$ factors
 pub fn reconstruct_scratch<I: Index, T: ComplexField>(nrows: usize, ncols: usize, par: Par) -> StackReq {
 	_ = par;
 	temp_mat_scratch::<T>(nrows, ncols)
 }
 
+/// computes the matrix $A = P^\top LU$ from its partial pivoting $LU$ factors, and stores it in
+/// `out`
+///
+/// `L` is only accessed through its strictly lower trapezoidal part, and `U` through its upper
+/// trapezoidal part, so both can refer to the same matrix, as produced by
+/// [`factor::lu_in_place`](super::factor::lu_in_place)
 #[track_caller]
 pub fn reconstruct<I: Index, T: ComplexField>(
 	out: MatMut<'_, T>,
@@ -67,6 +75,127 @@ pub fn reconstruct<I: Index, T: ComplexField>(
 	crate::perm::permute_rows(out.rb_mut(), tmp.rb(), row_perm.inverse());
 }
 
+const RESIDUAL_BLOCKSIZE: usize = 64;
+
+/// computes the size and alignment of required workspace for computing the residual norm of an
+/// $LU$ factorization
+pub fn residual_norm_scratch<I: Index, T: ComplexField>(nrows: usize, ncols: usize, par: Par) -> StackReq {
+	_ = par;
+	let blocksize = Ord::min(ncols, RESIDUAL_BLOCKSIZE);
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(nrows, blocksize),
+		temp_mat_scratch::<T>(Ord::min(nrows, ncols), blocksize),
+	])
+}
+
+/// computes the relative residual $\|PA - LU\|_F / \|A\|_F$ of the partial pivoting $LU$
+/// factorization of $A$
+///
+/// the product $LU$ is formed one block of columns at a time, so the workspace only grows with the
+/// number of rows. the factors may share storage as in [`reconstruct`]
+#[track_caller]
+pub fn residual_norm<I: Index, T: ComplexField>(
+	A: MatRef<'_, T>,
+	L: MatRef<'_, T>,
+	U: MatRef<'_, T>,
+	row_perm: PermRef<'_, I>,
+	par: Par,
+	stack: &mut MemStack,
+) -> T::Real {
+	assert!(row_perm.len() == A.nrows());
+	residual_norm_imp(A, L, U, row_perm.arrays().0, None, par, stack)
+}
+
+/// computes $\|PAQ^\top - LU\|_F / \|A\|_F$, where `row_perm_fwd` and `col_perm_fwd` map the
+/// rows and columns of $LU$ to those of $A$
+#[track_caller]
+pub(crate) fn residual_norm_imp<I: Index, T: ComplexField>(
+	A: MatRef<'_, T>,
+	L: MatRef<'_, T>,
+	U: MatRef<'_, T>,
+	row_perm_fwd: &[I],
+	col_perm_fwd: Option<&[I]>,
+	par: Par,
+	stack: &mut MemStack,
+) -> T::Real {
+	let (m, n) = A.shape();
+	let size = Ord::min(m, n);
+	assert!(all(
+		L.nrows() == m,
+		L.ncols() >= size,
+		U.nrows() >= size,
+		U.ncols() == n,
+		row_perm_fwd.len() == m,
+		col_perm_fwd.map(|perm| perm.len() == n).unwrap_or(true),
+	));
+
+	let L = L.get(.., ..size);
+	let U = U.get(..size, ..);
+
+	let blocksize = Ord::min(n, RESIDUAL_BLOCKSIZE);
+	let (mut LU, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, blocksize, stack) };
+	let (mut U_block, _) = unsafe { temp_mat_uninit::<T, _, _>(size, blocksize, stack) };
+	let mut LU = LU.as_mat_mut();
+	let mut U_block = U_block.as_mat_mut();
+
+	let mut residual = zero::<T::Real>();
+
+	let mut j0 = 0;
+	while j0 < n {
+		let bs = Ord::min(blocksize, n - j0);
+		let mut LU = LU.rb_mut().get_mut(.., ..bs);
+		let mut U_block = U_block.rb_mut().get_mut(.., ..bs);
+
+		// copy the upper trapezoidal part of the block, so that the product can be computed with
+		// dense kernels
+		for jj in 0..bs {
+			let j = j0 + jj;
+			for i in 0..size {
+				U_block[(i, jj)] = if i <= j { copy(&U[(i, j)]) } else { zero() };
+			}
+		}
+
+		linalg::matmul::triangular::matmul(
+			LU.rb_mut().get_mut(..size, ..),
+			BlockStructure::Rectangular,
+			Accum::Replace,
+			L.get(..size, ..),
+			BlockStructure::UnitTriangularLower,
+			U_block.rb(),
+			BlockStructure::Rectangular,
+			one(),
+			par,
+		);
+		if m > size {
+			linalg::matmul::matmul(
+				LU.rb_mut().get_mut(size.., ..),
+				Accum::Replace,
+				L.get(size.., ..),
+				U_block.rb(),
+				one(),
+				par,
+			);
+		}
+
+		for jj in 0..bs {
+			let j = j0 + jj;
+			let j = match col_perm_fwd {
+				Some(perm) => perm[j].zx(),
+				None => j,
+			};
+			for i in 0..m {
+				LU[(i, jj)] = sub(&LU[(i, jj)], &A[(row_perm_fwd[i].zx(), j)]);
+			}
+		}
+
+		residual = hypot(&residual, &LU.rb().norm_l2());
+		j0 += bs;
+	}
+
+	let norm = A.norm_l2();
+	if norm == zero() { residual } else { div(&residual, &norm) }
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -113,6 +242,28 @@ mod tests {
 			);
 
 			assert!(A_rec ~ A);
+
+			let residual = reconstruct::residual_norm(
+				A.as_ref(),
+				LU.as_ref(),
+				LU.as_ref(),
+				perm,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(reconstruct::residual_norm_scratch::<usize, c64>(m, n, Par::Seq))),
+			);
+			assert!(residual < 1e-14);
+
+			let mut B = A.clone();
+			B[(m / 2, n / 3)] += c64::new(1.0, 0.0);
+			let residual = reconstruct::residual_norm(
+				B.as_ref(),
+				LU.as_ref(),
+				LU.as_ref(),
+				perm,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(reconstruct::residual_norm_scratch::<usize, c64>(m, n, Par::Seq))),
+			);
+			assert!((residual - 1.0 / B.norm_l2()).abs() < 1e-12);
 		}
 	}
 }
@@ -37,6 +37,8 @@ pub fn solve_in_place_with_conj<I: Index, T: ComplexField>(
 		rhs.nrows() == n,
 	));
 
+	crate::utils::counters::count!(Solve, T, fma = n * n * rhs.ncols(), elems = n * n + 2 * n * rhs.ncols());
+
 	let mut rhs = rhs;
 	permute_rows_in_place(rhs.rb_mut(), row_perm, stack);
 
@@ -71,6 +73,8 @@ pub fn solve_transpose_in_place_with_conj<I: Index, T: ComplexField>(
 		rhs.nrows() == n,
 	));
 
+	crate::utils::counters::count!(Solve, T, fma = n * n * rhs.ncols(), elems = n * n + 2 * n * rhs.ncols());
+
 	let mut rhs = rhs;
 
 	linalg::triangular_solve::solve_lower_triangular_in_place_with_conj(U.transpose(), conj_LU, rhs.rb_mut(), par);
@@ -576,7 +576,7 @@ impl_binop!({
 			#[track_caller]
 			fn imp<T: ComplexField, LT: Conjugate<Canonical = T>, RT: Conjugate<Canonical = T>>(lhs: RowRef<'_, LT>, rhs: RowRef<'_, RT>) -> Row<T> {
 				assert!(all(lhs.nrows() == rhs.nrows(), lhs.ncols() == rhs.ncols()));
-				(lhs.transpose() + rhs.transpose()).into_transpose()
+				(lhs.transpose() - rhs.transpose()).into_transpose()
 			}
 			let lhs = self.rb();
 			imp(lhs.as_dyn_cols().as_dyn_stride(), rhs.rb().as_dyn_cols().as_dyn_stride()).into_col_shape(lhs.ncols())
@@ -600,7 +600,7 @@ impl_binop!({
 			#[track_caller]
 			fn imp<T: ComplexField, LT: Conjugate<Canonical = T>, RT: Conjugate<Canonical = T>>(lhs: ColRef<'_, LT>, rhs: ColRef<'_, RT>) -> Col<T> {
 				assert!(all(lhs.nrows() == rhs.nrows()));
-				lhs + rhs
+				lhs - rhs
 			}
 			let lhs = self.rb();
 			imp(
@@ -1836,6 +1836,67 @@ impl_binop!({
 	}
 });
 
+#[inline(always)]
+#[math]
+fn scale_in_place_simd<'N, T: ComplexField>(dst: ColMut<'_, T, Dim<'N>, ContiguousFwd>, alpha: &T) {
+	struct Impl<'a, 'b, 'N, T: ComplexField> {
+		dst: ColMut<'a, T, Dim<'N>, ContiguousFwd>,
+		alpha: &'b T,
+	}
+
+	impl<'N, T: ComplexField> pulp::WithSimd for Impl<'_, '_, 'N, T> {
+		type Output = ();
+
+		#[inline(always)]
+		fn with_simd<S: pulp::Simd>(self, simd: S) -> Self::Output {
+			let Self { mut dst, alpha } = self;
+			let simd = SimdCtx::<T, S>::new(T::simd_ctx(simd), dst.nrows());
+			let alpha = simd.splat(alpha);
+
+			let (head, body, tail) = simd.indices();
+			if let Some(i) = head {
+				let x = simd.read(dst.rb(), i);
+				simd.write(dst.rb_mut(), i, simd.mul(alpha, x));
+			}
+			for i in body {
+				let x = simd.read(dst.rb(), i);
+				simd.write(dst.rb_mut(), i, simd.mul(alpha, x));
+			}
+			if let Some(i) = tail {
+				let x = simd.read(dst.rb(), i);
+				simd.write(dst.rb_mut(), i, simd.mul(alpha, x));
+			}
+		}
+	}
+
+	dispatch!(Impl { dst, alpha }, Impl, T)
+}
+
+/// multiplies `dst` by `alpha` in place, using simd instructions when its columns or rows are
+/// contiguous
+#[math]
+fn scale_in_place<T: ComplexField>(dst: MatMut<'_, T>, alpha: &T) {
+	let mut dst = dst;
+	if dst.ncols() > 1 && dst.col_stride().unsigned_abs() == 1 {
+		dst = dst.transpose_mut();
+	}
+	if dst.row_stride() < 0 {
+		dst = dst.reverse_rows_mut();
+	}
+
+	if try_const! { T::SIMD_CAPABILITIES.is_simd() } {
+		if let Some(mut dst) = dst.rb_mut().try_as_col_major_mut() {
+			with_dim!(M, dst.nrows());
+			for j in 0..dst.ncols() {
+				scale_in_place_simd(dst.rb_mut().col_mut(j).as_row_shape_mut(M), alpha);
+			}
+			return;
+		}
+	}
+
+	zip!(dst).for_each(|unzip!(x)| *x = *alpha * *x)
+}
+
 impl_op_assign!({
 	impl<
 		T: ComplexField,
@@ -1850,7 +1911,7 @@ impl_op_assign!({
 			#[track_caller]
 			#[math]
 			fn imp<T: ComplexField>(lhs: MatMut<'_, T>, rhs: &T) {
-				zip!(lhs).for_each(|unzip!(x)| *x = *x * *rhs)
+				scale_in_place(lhs, rhs)
 			}
 			let lhs = self.rb_mut();
 			let rhs = &rhs.0;
@@ -1889,7 +1950,7 @@ impl_op_assign!({
 			#[track_caller]
 			#[math]
 			fn imp<T: ComplexField>(lhs: ColMut<'_, T>, rhs: &T) {
-				zip!(lhs).for_each(|unzip!(x)| *x = *x * *rhs)
+				scale_in_place(lhs.as_mat_mut(), rhs)
 			}
 			let lhs = self.rb_mut();
 			let rhs = &rhs.0;
@@ -1995,7 +2056,7 @@ impl_op_assign!({
 			#[track_caller]
 			#[math]
 			fn imp<T: ComplexField>(lhs: MatMut<'_, T>, rhs: &T) {
-				zip!(lhs).for_each(|unzip!(x)| *x = *x * *rhs)
+				scale_in_place(lhs, rhs)
 			}
 			let lhs = self.rb_mut();
 			let rhs = &from_f64::<T>(*rhs);
@@ -2034,7 +2095,7 @@ impl_op_assign!({
 			#[track_caller]
 			#[math]
 			fn imp<T: ComplexField>(lhs: ColMut<'_, T>, rhs: &T) {
-				zip!(lhs).for_each(|unzip!(x)| *x = *x * *rhs)
+				scale_in_place(lhs.as_mat_mut(), rhs)
 			}
 			let lhs = self.rb_mut();
 			let rhs = &from_f64::<T>(*rhs);
@@ -2126,11 +2187,157 @@ impl_op_assign!({
 	}
 });
 
+impl_op_assign!({
+	impl<
+		T: ComplexField,
+		Rows: Shape,
+		Cols: Shape,
+		LRStride: Stride,
+		LCStride: Stride,
+		L: (for<'a> ReborrowMut<'a, Target = mat::Mut<'a, T, Rows, Cols, LRStride, LCStride>>),
+	> MulAssign<f32> for mat::generic::Mat<L>
+	{
+		fn mul_assign(&mut self, rhs: _) {
+			#[track_caller]
+			#[math]
+			fn imp<T: ComplexField>(lhs: MatMut<'_, T>, rhs: &T) {
+				scale_in_place(lhs, rhs)
+			}
+			let lhs = self.rb_mut();
+			let rhs = &from_f64::<T>(*rhs as f64);
+
+			imp(lhs.as_dyn_mut().as_dyn_stride_mut(), rhs)
+		}
+	}
+
+	impl<
+		T: ComplexField,
+		Rows: Shape,
+		Cols: Shape,
+		LRStride: Stride,
+		LCStride: Stride,
+		L: (for<'a> ReborrowMut<'a, Target = mat::Mut<'a, T, Rows, Cols, LRStride, LCStride>>),
+	> DivAssign<f32> for mat::generic::Mat<L>
+	{
+		fn div_assign(&mut self, rhs: _) {
+			#[track_caller]
+			fn imp<T: ComplexField>(mut lhs: MatMut<'_, T>, rhs: &T) {
+				lhs *= Scale(recip(rhs));
+			}
+			let lhs = self.rb_mut();
+			let rhs = &from_f64::<T>(*rhs as f64);
+
+			imp(lhs.as_dyn_mut().as_dyn_stride_mut(), rhs)
+		}
+	}
+});
+
+impl_op_assign!({
+	impl<T: ComplexField, Rows: Shape, LRStride: Stride, L: (for<'a> ReborrowMut<'a, Target = col::Mut<'a, T, Rows, LRStride>>)> MulAssign<f32>
+		for col::generic::Col<L>
+	{
+		fn mul_assign(&mut self, rhs: _) {
+			#[track_caller]
+			#[math]
+			fn imp<T: ComplexField>(lhs: ColMut<'_, T>, rhs: &T) {
+				scale_in_place(lhs.as_mat_mut(), rhs)
+			}
+			let lhs = self.rb_mut();
+			let rhs = &from_f64::<T>(*rhs as f64);
+
+			imp(lhs.as_dyn_rows_mut().as_dyn_stride_mut(), rhs)
+		}
+	}
+
+	impl<T: ComplexField, Rows: Shape, LRStride: Stride, L: (for<'a> ReborrowMut<'a, Target = col::Mut<'a, T, Rows, LRStride>>)> DivAssign<f32>
+		for col::generic::Col<L>
+	{
+		fn div_assign(&mut self, rhs: _) {
+			#[track_caller]
+			fn imp<T: ComplexField>(mut lhs: ColMut<'_, T>, rhs: &T) {
+				lhs *= Scale(recip(rhs));
+			}
+			let lhs = self.rb_mut();
+			let rhs = &from_f64::<T>(*rhs as f64);
+
+			imp(lhs.as_dyn_rows_mut().as_dyn_stride_mut(), rhs)
+		}
+	}
+});
+
+impl_op_assign!({
+	impl<T: ComplexField, Cols: Shape, LCStride: Stride, L: (for<'a> ReborrowMut<'a, Target = row::Mut<'a, T, Cols, LCStride>>)> MulAssign<f32>
+		for row::generic::Row<L>
+	{
+		fn mul_assign(&mut self, rhs: _) {
+			#[track_caller]
+			#[math]
+			fn imp<T: ComplexField>(lhs: RowMut<'_, T>, rhs: &T) {
+				let mut lhs = lhs.transpose_mut();
+				lhs *= Scale::from_ref(rhs);
+			}
+			let lhs = self.rb_mut();
+			let rhs = &from_f64::<T>(*rhs as f64);
+
+			imp(lhs.as_dyn_cols_mut().as_dyn_stride_mut(), rhs)
+		}
+	}
+
+	impl<T: ComplexField, Cols: Shape, LCStride: Stride, L: (for<'a> ReborrowMut<'a, Target = row::Mut<'a, T, Cols, LCStride>>)> DivAssign<f32>
+		for row::generic::Row<L>
+	{
+		fn div_assign(&mut self, rhs: _) {
+			#[track_caller]
+			fn imp<T: ComplexField>(mut lhs: RowMut<'_, T>, rhs: &T) {
+				lhs *= Scale(recip(rhs));
+			}
+			let lhs = self.rb_mut();
+			let rhs = &from_f64::<T>(*rhs as f64);
+
+			imp(lhs.as_dyn_cols_mut().as_dyn_stride_mut(), rhs)
+		}
+	}
+});
+
+impl_op_assign!({
+	impl<T: ComplexField, Cols: Shape, LCStride: Stride, L: (for<'a> ReborrowMut<'a, Target = diag::Mut<'a, T, Cols, LCStride>>)> MulAssign<f32>
+		for diag::generic::Diag<L>
+	{
+		fn mul_assign(&mut self, rhs: _) {
+			#[track_caller]
+			#[math]
+			fn imp<T: ComplexField>(mut lhs: ColMut<'_, T>, rhs: &T) {
+				lhs *= Scale::from_ref(rhs);
+			}
+			let lhs = self.rb_mut().column_vector_mut();
+			let rhs = &from_f64::<T>(*rhs as f64);
+
+			imp(lhs.as_dyn_rows_mut().as_dyn_stride_mut(), rhs)
+		}
+	}
+
+	impl<T: ComplexField, Cols: Shape, LCStride: Stride, L: (for<'a> ReborrowMut<'a, Target = diag::Mut<'a, T, Cols, LCStride>>)> DivAssign<f32>
+		for diag::generic::Diag<L>
+	{
+		fn div_assign(&mut self, rhs: _) {
+			#[track_caller]
+			fn imp<T: ComplexField>(mut lhs: ColMut<'_, T>, rhs: &T) {
+				lhs *= Scale(recip(rhs));
+			}
+			let lhs = self.rb_mut().column_vector_mut();
+			let rhs = &from_f64::<T>(*rhs as f64);
+
+			imp(lhs.as_dyn_rows_mut().as_dyn_stride_mut(), rhs)
+		}
+	}
+});
+
 #[cfg(feature = "sparse")]
 mod sparse {
 	use super::*;
 	use crate::internal_prelude_sp::*;
-	use {csc_numeric as csc, csr_numeric as csr};
+	use csc_numeric as csc;
+	use csr_numeric as csr;
 
 	impl_binop!({
 		impl<
@@ -3613,7 +3820,7 @@ mod test {
 	use crate::mat::*;
 	use crate::perm::*;
 	use crate::row::*;
-	use crate::{assert, mat};
+	use crate::{Scale, assert, c64, mat};
 	use assert_approx_eq::assert_approx_eq;
 
 	fn matrices() -> (Mat<f64>, Mat<f64>) {
@@ -3673,6 +3880,15 @@ mod test {
 		assert_matrix_approx_eq(A - B, &expected);
 	}
 
+	#[test]
+	fn test_sub_vectors() {
+		let (A, B) = matrices();
+
+		assert!(A.col(0) - B.col(0) == (&A - &B).col(0));
+		assert!(A.row(1) - B.row(1) == (&A - &B).row(1));
+		assert!(A.col(1).as_diagonal() - B.col(1).as_diagonal() == (&A - &B).col(1).as_diagonal());
+	}
+
 	#[test]
 	fn test_neg() {
 		let (A, _) = matrices();
@@ -3716,6 +3932,48 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn test_scalar_mul_assign() {
+		let A = Mat::from_fn(37, 11, |i, j| (i as f64) - 2.5 * (j as f64));
+		let expected = Mat::from_fn(37, 11, |i, j| 3.0 * A[(i, j)]);
+
+		// contiguous columns, contiguous rows and strided layouts
+		let mut B = A.clone();
+		B *= 3.0;
+		assert!(B == expected);
+		let mut B = A.transpose().to_owned();
+		let mut view = B.as_mut().transpose_mut();
+		view *= Scale(3.0);
+		assert!(B == expected.transpose());
+		let mut B = A.clone();
+		let mut view = B.as_mut().reverse_rows_mut().subcols_mut(1, 5);
+		view /= 0.5;
+		B.as_mut().subcols_mut(6, 5).col_iter_mut().for_each(|mut col| col *= 2.0f32);
+		let mut view = B.as_mut().row_mut(0).subcols_mut(0, 1);
+		view *= 2.0f32;
+		for j in 0..11 {
+			for i in 0..37 {
+				if j > 0 || i == 0 {
+					assert!(B[(i, j)] == 2.0 * A[(i, j)]);
+				} else {
+					assert!(B[(i, j)] == A[(i, j)]);
+				}
+			}
+		}
+
+		let z = c64::new(0.5, -1.5);
+		let A = Mat::from_fn(13, 4, |i, j| c64::new(i as f64, j as f64));
+		let mut B = A.clone();
+		B *= Scale(z);
+		let mut x = A.col(1).to_owned();
+		x /= 2.0f32;
+		let mut y = A.row(2).to_owned();
+		y *= Scale(z);
+		assert!(B == Mat::from_fn(13, 4, |i, j| z * A[(i, j)]));
+		assert!(x == Col::from_fn(13, |i| A[(i, 1)] * 0.5));
+		assert!(y == Row::from_fn(4, |j| z * A[(2, j)]));
+	}
+
 	#[test]
 	fn test_diag_mul() {
 		let (A, _) = matrices();
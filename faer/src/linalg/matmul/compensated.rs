@@ -0,0 +1,792 @@
+//! compensated matrix multiplication and reductions
+//!
+//! the routines in this module accumulate their results with error-free transformations
+//! (`two_sum` and `two_prod`), which keep track of the rounding error of every operation in a
+//! separate accumulator. the result is as accurate as if it had been computed in twice the working
+//! precision, then rounded back to the working precision. this is mostly useful for computing
+//! residuals during iterative refinement, or dot products that suffer from heavy cancellation
+//!
+//! the extra accuracy comes at a cost of roughly two to four times that of the standard routines
+//! in [`linalg::matmul`](super), so they should only be used where it's needed
+//!
+//! the error-free transformations assume ieee 754 arithmetic with rounding to nearest, and the
+//! accuracy guarantees may be lost if the intermediate products overflow or underflow
+
+use crate::col::ColRef;
+use crate::internal_prelude::*;
+use crate::mat::{MatMut, MatRef};
+use crate::row::RowRef;
+use crate::utils::bound::Dim;
+use crate::utils::simd::SimdCtx;
+use crate::{Conj, ContiguousFwd, Par, Shape};
+use equator::assert;
+use faer_macros::math;
+use faer_traits::{ComplexField, Conjugate, RealField};
+use pulp::Simd;
+
+/// returns `(s, e)` such that `s = fl(a + b)` and `s + e = a + b` exactly
+#[inline(always)]
+#[math]
+fn two_sum<T: RealField>(a: T, b: T) -> (T, T) {
+	let s = a + b;
+	let bb = s - a;
+	let e = (a - (s - bb)) + (b - bb);
+	(s, e)
+}
+
+/// returns the factor used to split a value into two halves that can be multiplied with no
+/// rounding error
+#[math]
+fn split_factor<T: RealField>() -> T {
+	let mut factor = one::<T>();
+	for _ in 0..nbits::<T>().div_ceil(2) {
+		factor = factor + factor;
+	}
+	factor + one::<T>()
+}
+
+/// returns `(p, e)` such that `p = fl(a * b)` and `p + e = a * b` exactly
+///
+/// uses dekker's algorithm, so that no fused multiply-add is needed
+#[inline(always)]
+#[math]
+fn two_prod<T: RealField>(a: T, b: T, factor: &T) -> (T, T) {
+	let p = a * b;
+
+	let a_ = *factor * a;
+	let a_hi = a_ - (a_ - a);
+	let a_lo = a - a_hi;
+
+	let b_ = *factor * b;
+	let b_hi = b_ - (b_ - b);
+	let b_lo = b - b_hi;
+
+	let e = a_lo * b_lo - (((p - a_hi * b_hi) - a_lo * b_hi) - a_hi * b_lo);
+	(p, e)
+}
+
+/// real value stored as an unevaluated sum `hi + lo`
+#[derive(Clone, Debug)]
+struct Acc<T> {
+	hi: T,
+	lo: T,
+}
+
+impl<T: RealField> Acc<T> {
+	#[inline(always)]
+	fn new() -> Self {
+		Self { hi: zero(), lo: zero() }
+	}
+
+	#[inline(always)]
+	#[math]
+	fn add(&mut self, value: T) {
+		let (hi, e) = two_sum(copy(self.hi), value);
+		self.hi = hi;
+		self.lo = self.lo + e;
+	}
+
+	#[inline(always)]
+	#[math]
+	fn add_prod(&mut self, lhs: T, rhs: T, factor: &T) {
+		let (p, e) = two_prod(lhs, rhs, factor);
+		self.add(p);
+		self.lo = self.lo + e;
+	}
+}
+
+/// complex value stored as an unevaluated sum `hi + lo`, with the real and imaginary parts
+/// accumulated separately
+#[derive(Clone, Debug)]
+struct CplxAcc<T: ComplexField> {
+	re: Acc<T::Real>,
+	im: Acc<T::Real>,
+}
+
+impl<T: ComplexField> CplxAcc<T> {
+	#[inline(always)]
+	fn new() -> Self {
+		Self {
+			re: Acc::new(),
+			im: Acc::new(),
+		}
+	}
+
+	#[inline(always)]
+	#[math]
+	fn add(&mut self, value: &T) {
+		self.re.add(real(*value));
+		if try_const! { !T::IS_REAL } {
+			self.im.add(imag(*value));
+		}
+	}
+
+	#[inline(always)]
+	#[math]
+	fn add_prod(&mut self, lhs: &T, conj_lhs: Conj, rhs: &T, conj_rhs: Conj, factor: &T::Real) {
+		let lhs_re = real(*lhs);
+		let rhs_re = real(*rhs);
+
+		if try_const! { T::IS_REAL } {
+			self.re.add_prod(lhs_re, rhs_re, factor);
+		} else {
+			let mut lhs_im = imag(*lhs);
+			let mut rhs_im = imag(*rhs);
+			if conj_lhs == Conj::Yes {
+				lhs_im = -lhs_im;
+			}
+			if conj_rhs == Conj::Yes {
+				rhs_im = -rhs_im;
+			}
+
+			self.re.add_prod(copy(lhs_re), copy(rhs_re), factor);
+			self.re.add_prod(-lhs_im, copy(rhs_im), factor);
+			self.im.add_prod(lhs_re, rhs_im, factor);
+			self.im.add_prod(lhs_im, rhs_re, factor);
+		}
+	}
+
+	/// adds `value` to the error term
+	#[inline(always)]
+	#[math]
+	fn add_err(&mut self, value: &T) {
+		self.re.lo = self.re.lo + real(*value);
+		if try_const! { !T::IS_REAL } {
+			self.im.lo = self.im.lo + imag(*value);
+		}
+	}
+
+	/// adds `alpha * other` to `self`
+	#[inline(always)]
+	fn add_scaled(&mut self, alpha: &T, other: &Self, factor: &T::Real) {
+		let hi = from_parts::<T>(&other.re.hi, &other.im.hi);
+		let lo = from_parts::<T>(&other.re.lo, &other.im.lo);
+		self.add_prod(alpha, Conj::No, &hi, Conj::No, factor);
+		self.add_prod(alpha, Conj::No, &lo, Conj::No, factor);
+	}
+
+	#[inline(always)]
+	#[math]
+	fn finish(self) -> T {
+		from_parts::<T>(&(self.re.hi + self.re.lo), &(self.im.hi + self.im.lo))
+	}
+}
+
+/// returns `re + i * im`
+#[inline(always)]
+#[math]
+fn from_parts<T: ComplexField>(re: &T::Real, im: &T::Real) -> T {
+	if try_const! { T::IS_REAL } {
+		from_real::<T>(re)
+	} else {
+		// the square root of `-1` is computed exactly, and so is the product below since one of
+		// the components of the imaginary unit is zero
+		let i = sqrt(-one::<T>());
+		from_real::<T>(re) + mul_real(i, *im)
+	}
+}
+
+#[inline(always)]
+fn inner_prod_scalar<'K, T: ComplexField>(
+	acc: &mut CplxAcc<T>,
+	lhs: RowRef<'_, T, Dim<'K>>,
+	conj_lhs: Conj,
+	rhs: ColRef<'_, T, Dim<'K>>,
+	conj_rhs: Conj,
+	factor: &T::Real,
+) {
+	for k in lhs.ncols().indices() {
+		acc.add_prod(&lhs[k], conj_lhs, &rhs[k], conj_rhs, factor);
+	}
+}
+
+/// compensated dot product of contiguous slices for native floating point types, where the simd
+/// multiply-add is fused and `two_prod` can be computed with it directly
+#[inline(always)]
+fn inner_prod_simd<'K, T: ComplexField>(
+	acc: &mut CplxAcc<T>,
+	len: Dim<'K>,
+	lhs: ColRef<'_, T, Dim<'K>, ContiguousFwd>,
+	rhs: ColRef<'_, T, Dim<'K>, ContiguousFwd>,
+) {
+	struct Impl<'a, 'K, T: ComplexField> {
+		acc: &'a mut CplxAcc<T>,
+		len: Dim<'K>,
+		lhs: ColRef<'a, T, Dim<'K>, ContiguousFwd>,
+		rhs: ColRef<'a, T, Dim<'K>, ContiguousFwd>,
+	}
+
+	impl<T: ComplexField> pulp::WithSimd for Impl<'_, '_, T> {
+		type Output = ();
+
+		#[inline(always)]
+		fn with_simd<S: Simd>(self, simd: S) -> Self::Output {
+			let Self { acc, len, lhs, rhs } = self;
+			let simd = SimdCtx::<T, S>::new(T::simd_ctx(simd), len);
+
+			let mut hi0 = simd.zero();
+			let mut hi1 = simd.zero();
+			let mut lo0 = simd.zero();
+			let mut lo1 = simd.zero();
+
+			let step = |hi: T::SimdVec<S>, lo: T::SimdVec<S>, l: T::SimdVec<S>, r: T::SimdVec<S>| {
+				let p = simd.mul(l, r);
+				let p_err = simd.mul_add(l, r, simd.neg(p));
+
+				let s = simd.add(hi, p);
+				let bb = simd.sub(s, hi);
+				let s_err = simd.add(simd.sub(hi, simd.sub(s, bb)), simd.sub(p, bb));
+
+				(s, simd.add(lo, simd.add(p_err, s_err)))
+			};
+
+			let (head, idx2, idx, tail) = simd.batch_indices::<2>();
+
+			if let Some(i0) = head {
+				(hi0, lo0) = step(hi0, lo0, simd.read(lhs, i0), simd.read(rhs, i0));
+			}
+			for [i0, i1] in idx2 {
+				(hi0, lo0) = step(hi0, lo0, simd.read(lhs, i0), simd.read(rhs, i0));
+				(hi1, lo1) = step(hi1, lo1, simd.read(lhs, i1), simd.read(rhs, i1));
+			}
+			for i0 in idx {
+				(hi0, lo0) = step(hi0, lo0, simd.read(lhs, i0), simd.read(rhs, i0));
+			}
+			if let Some(i0) = tail {
+				(hi0, lo0) = step(hi0, lo0, simd.read(lhs, i0), simd.read(rhs, i0));
+			}
+
+			// the lanes of the high parts can cancel out, so they're summed with compensation
+			// too
+			for hi in [hi0, hi1] {
+				for x in lanes::<T, S>(&hi) {
+					acc.add(x);
+				}
+			}
+			acc.add_err(&simd.reduce_sum(simd.add(lo0, lo1)));
+		}
+	}
+
+	dispatch!(Impl { acc, len, lhs, rhs }, Impl, T)
+}
+
+/// returns the lanes of a simd register
+#[inline(always)]
+fn lanes<T: ComplexField, S: Simd>(value: &T::SimdVec<S>) -> &[T] {
+	assert!(try_const! { T::IS_NATIVE_F32 || T::IS_NATIVE_F64 });
+	// SAFETY: the simd registers of the native real types are arrays of scalars
+	unsafe {
+		core::slice::from_raw_parts(
+			value as *const T::SimdVec<S> as *const T,
+			core::mem::size_of::<T::SimdVec<S>>() / core::mem::size_of::<T>(),
+		)
+	}
+}
+
+#[inline(always)]
+fn inner_prod_imp<'K, T: ComplexField>(
+	acc: &mut CplxAcc<T>,
+	lhs: RowRef<'_, T, Dim<'K>>,
+	conj_lhs: Conj,
+	rhs: ColRef<'_, T, Dim<'K>>,
+	conj_rhs: Conj,
+	factor: &T::Real,
+) {
+	if try_const! { T::IS_NATIVE_F32 || T::IS_NATIVE_F64 } {
+		if let (Some(lhs), Some(rhs)) = (lhs.try_as_row_major(), rhs.try_as_col_major()) {
+			return inner_prod_simd(acc, lhs.ncols(), lhs.transpose(), rhs);
+		}
+	}
+	inner_prod_scalar(acc, lhs, conj_lhs, rhs, conj_rhs, factor);
+}
+
+/// returns `lhs * rhs`, implicitly conjugating the operands if needed, computed with compensated
+/// accumulation
+///
+/// see the [module level documentation](self) for the accuracy characteristics
+///
+/// # panics
+/// panics if `lhs.ncols() != rhs.nrows()`
+#[track_caller]
+pub fn inner_prod<K: Shape, T: ComplexField>(lhs: RowRef<T, K>, conj_lhs: Conj, rhs: ColRef<T, K>, conj_rhs: Conj) -> T {
+	assert!(lhs.ncols() == rhs.nrows());
+
+	with_dim!(K, lhs.ncols().unbound());
+	let factor = split_factor::<T::Real>();
+
+	let mut acc = CplxAcc::<T>::new();
+	inner_prod_imp(&mut acc, lhs.as_col_shape(K), conj_lhs, rhs.as_row_shape(K), conj_rhs, &factor);
+	acc.finish()
+}
+
+/// returns the sum of the elements of `mat`, computed with compensated accumulation
+///
+/// see the [module level documentation](self) for the accuracy characteristics
+pub fn sum<T: ComplexField>(mat: MatRef<'_, T>) -> T {
+	let mut acc = CplxAcc::<T>::new();
+	for j in 0..mat.ncols() {
+		for i in 0..mat.nrows() {
+			acc.add(&mat[(i, j)]);
+		}
+	}
+	acc.finish()
+}
+
+#[math]
+#[allow(clippy::too_many_arguments)]
+fn matmul_imp<T: ComplexField>(
+	dst: MatMut<'_, T>,
+	beta: Accum,
+	lhs: MatRef<'_, T>,
+	conj_lhs: Conj,
+	rhs: MatRef<'_, T>,
+	conj_rhs: Conj,
+	alpha: &T,
+	par: Par,
+) {
+	let factor = split_factor::<T::Real>();
+
+	// the dot products are computed between the rows of `lhs` and the columns of `rhs`, so they're
+	// copied to a layout that allows using the simd kernels if needed
+	let packed = try_const! { T::IS_NATIVE_F32 || T::IS_NATIVE_F64 };
+	let lhs_t;
+	let lhs = if packed && lhs.transpose().try_as_col_major().is_none() {
+		lhs_t = lhs.transpose().to_owned();
+		lhs_t.as_ref().transpose()
+	} else {
+		lhs
+	};
+	let rhs_;
+	let rhs = if packed && rhs.try_as_col_major().is_none() {
+		rhs_ = rhs.to_owned();
+		rhs_.as_ref()
+	} else {
+		rhs
+	};
+
+	let imp = |dst: MatMut<'_, T>, rhs: MatRef<'_, T>| {
+		let mut dst = dst;
+		with_dim!(K, lhs.ncols());
+
+		for j in 0..dst.ncols() {
+			for i in 0..dst.nrows() {
+				let mut dot = CplxAcc::<T>::new();
+				inner_prod_imp(
+					&mut dot,
+					lhs.row(i).as_col_shape(K),
+					conj_lhs,
+					rhs.col(j).as_row_shape(K),
+					conj_rhs,
+					&factor,
+				);
+
+				let mut acc = CplxAcc::<T>::new();
+				if beta == Accum::Add {
+					acc.add(&dst[(i, j)]);
+				}
+				acc.add_scaled(alpha, &dot, &factor);
+				dst[(i, j)] = acc.finish();
+			}
+		}
+	};
+
+	match par {
+		Par::Seq => imp(dst, rhs),
+		#[cfg(feature = "rayon")]
+		Par::Rayon(nthreads) => {
+			let nthreads = Ord::min(nthreads.get(), dst.ncols());
+			if nthreads <= 1 {
+				return imp(dst, rhs);
+			}
+
+			use rayon::prelude::*;
+			dst.par_col_partition_mut(nthreads)
+				.zip(rhs.par_col_partition(nthreads))
+				.for_each(|(dst, rhs)| imp(dst, rhs));
+		},
+	}
+}
+
+/// computes the matrix product `[beta * acc] + alpha * lhs * rhs` with compensated accumulation,
+/// and stores the result in `acc`
+///
+/// performs the operation:
+/// - `acc = alpha * lhs * rhs` if `beta` is `Accum::Replace` (in this case, the preexisting
+///   values in `acc` are not read)
+/// - `acc = acc + alpha * lhs * rhs` if `beta` is `Accum::Add`
+///
+/// the whole expression is evaluated with compensation, including the addition to the previous
+/// values of `acc`, so that residuals such as `b - A * x` can be computed accurately with
+/// `matmul(&mut b, Accum::Add, &A, &x, -1.0, par)`
+///
+/// see the [module level documentation](self) for the accuracy characteristics
+///
+/// # panics
+/// panics if the matrix dimensions are not compatible for matrix multiplication
+#[track_caller]
+#[inline]
+pub fn matmul<T: ComplexField, LhsT: Conjugate<Canonical = T>, RhsT: Conjugate<Canonical = T>, M: Shape, N: Shape, K: Shape>(
+	dst: impl AsMatMut<T = T, Rows = M, Cols = N>,
+	beta: Accum,
+	lhs: impl AsMatRef<T = LhsT, Rows = M, Cols = K>,
+	rhs: impl AsMatRef<T = RhsT, Rows = K, Cols = N>,
+	alpha: T,
+	par: Par,
+) {
+	let mut dst = dst;
+	let dst = dst.as_mat_mut();
+	let lhs = lhs.as_mat_ref();
+	let rhs = rhs.as_mat_ref();
+
+	assert!(all(dst.nrows() == lhs.nrows(), dst.ncols() == rhs.ncols(), lhs.ncols() == rhs.nrows()));
+
+	matmul_imp(
+		dst.as_dyn_mut().as_dyn_stride_mut(),
+		beta,
+		lhs.as_dyn().as_dyn_stride().canonical(),
+		try_const! { Conj::get::<LhsT>() },
+		rhs.as_dyn().as_dyn_stride().canonical(),
+		try_const! { Conj::get::<RhsT>() },
+		&alpha,
+		par,
+	);
+}
+
+/// computes the matrix product `[beta * acc] + alpha * lhs * rhs` (implicitly conjugating the
+/// operands if needed) with compensated accumulation, and stores the result in `acc`
+///
+/// see [`matmul`] for more details
+///
+/// # panics
+/// panics if the matrix dimensions are not compatible for matrix multiplication
+#[track_caller]
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn matmul_with_conj<T: ComplexField, M: Shape, N: Shape, K: Shape>(
+	dst: impl AsMatMut<T = T, Rows = M, Cols = N>,
+	beta: Accum,
+	lhs: impl AsMatRef<T = T, Rows = M, Cols = K>,
+	conj_lhs: Conj,
+	rhs: impl AsMatRef<T = T, Rows = K, Cols = N>,
+	conj_rhs: Conj,
+	alpha: T,
+	par: Par,
+) {
+	let mut dst = dst;
+	let dst = dst.as_mat_mut();
+	let lhs = lhs.as_mat_ref();
+	let rhs = rhs.as_mat_ref();
+
+	assert!(all(dst.nrows() == lhs.nrows(), dst.ncols() == rhs.ncols(), lhs.ncols() == rhs.nrows()));
+
+	matmul_imp(
+		dst.as_dyn_mut().as_dyn_stride_mut(),
+		beta,
+		lhs.as_dyn().as_dyn_stride(),
+		conj_lhs,
+		rhs.as_dyn().as_dyn_stride(),
+		conj_rhs,
+		&alpha,
+		par,
+	);
+}
+
+/// computes the residual `b - a * x` with compensated accumulation, and stores the result in
+/// `dst`
+///
+/// this is the residual needed for iterative refinement to converge to the full working
+/// precision, since the one computed in working precision is dominated by rounding errors once
+/// `x` is close to the solution. the computation uses the global parallelism setting
+///
+/// see the [module level documentation](self) for the accuracy characteristics
+///
+/// # example
+/// ```
+/// use faer::linalg::matmul::compensated::residual;
+/// use faer::linalg::solvers::Solve;
+/// use faer::{Mat, mat};
+///
+/// let a = mat![[4.0, 1.0, 0.5], [1.0, 3.0, 0.25], [0.5, 0.25, 2.0]];
+/// let b = mat![[1.0], [2.0], [3.0]];
+///
+/// let lu = a.partial_piv_lu();
+/// let mut x = lu.solve(&b);
+/// let mut r = Mat::zeros(3, 1);
+/// for _ in 0..3 {
+/// 	residual(&mut r, &a, &x, &b);
+/// 	x += lu.solve(&r);
+/// }
+/// ```
+///
+/// # panics
+/// panics if `a.ncols() != x.nrows()`, or if `dst` and `b` don't have the shape of `a * x`
+#[track_caller]
+#[inline]
+pub fn residual<T: ComplexField, AT: Conjugate<Canonical = T>, XT: Conjugate<Canonical = T>, M: Shape, N: Shape, K: Shape>(
+	dst: impl AsMatMut<T = T, Rows = M, Cols = N>,
+	a: impl AsMatRef<T = AT, Rows = M, Cols = K>,
+	x: impl AsMatRef<T = XT, Rows = K, Cols = N>,
+	b: impl AsMatRef<T = T, Rows = M, Cols = N>,
+) {
+	let mut dst = dst;
+	let mut dst = dst.as_mat_mut();
+	let a = a.as_mat_ref();
+	let x = x.as_mat_ref();
+	let b = b.as_mat_ref();
+
+	assert!(all(
+		dst.nrows() == a.nrows(),
+		dst.ncols() == x.ncols(),
+		a.ncols() == x.nrows(),
+		b.nrows() == a.nrows(),
+		b.ncols() == x.ncols(),
+	));
+
+	dst.copy_from(b);
+	matmul_imp(
+		dst.as_dyn_mut().as_dyn_stride_mut(),
+		Accum::Add,
+		a.as_dyn().as_dyn_stride().canonical(),
+		try_const! { Conj::get::<AT>() },
+		x.as_dyn().as_dyn_stride().canonical(),
+		try_const! { Conj::get::<XT>() },
+		&neg(&one::<T>()),
+		crate::get_global_parallelism(),
+	);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{Col, Mat, Row, assert, c64};
+
+	/// returns a dot product whose terms cancel out, along with its exact value
+	fn ill_conditioned_dot(rng: &mut StdRng, n: usize) -> (Row<f64>, Col<f64>, f64) {
+		let x = CwiseColDistribution {
+			nrows: n,
+			dist: StandardNormal,
+		}
+		.rand::<Col<f64>>(rng);
+
+		// each small term is surrounded by a pair of huge terms that cancel out exactly, so that it
+		// gets lost in their rounding errors
+		let lhs = Row::from_fn(n, |i| match i % 3 {
+			0 => 1e16 * x[i],
+			1 => x[i],
+			_ => -1e16 * x[i - 2],
+		});
+		let rhs = Col::from_fn(n, |_| 1.0);
+		let exact = (0..n).filter(|i| i % 3 == 1).map(|i| x[i]).sum::<f64>();
+		(lhs, rhs, exact)
+	}
+
+	#[test]
+	fn test_inner_prod() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [3, 30, 300, 3000] {
+			let (lhs, rhs, exact) = ill_conditioned_dot(rng, n);
+			let tol = 1e-12 * exact.abs().max(1.0);
+
+			let compensated = inner_prod(lhs.as_ref(), Conj::No, rhs.as_ref(), Conj::No);
+			assert!((compensated - exact).abs() <= tol);
+
+			// strided operands go through the scalar path
+			let lhs_strided = Mat::from_fn(2, n, |i, j| if i == 0 { lhs[j] } else { 0.0 });
+			let compensated = inner_prod(lhs_strided.row(0), Conj::No, rhs.as_ref(), Conj::No);
+			assert!((compensated - exact).abs() <= tol);
+		}
+	}
+
+	#[test]
+	fn test_two_prod() {
+		let factor = split_factor::<f64>();
+		let a = 1.0 + f64::EPSILON;
+		let b = 1.0 - f64::EPSILON;
+		let (p, e) = two_prod(a, b, &factor);
+		assert!(p == 1.0);
+		assert!(e == -f64::EPSILON * f64::EPSILON);
+
+		let factor = split_factor::<f32>();
+		let a = 1.0 + f32::EPSILON;
+		let (p, e) = two_prod(a, a, &factor);
+		assert!(p == 1.0 + 2.0 * f32::EPSILON);
+		assert!(e == f32::EPSILON * f32::EPSILON);
+	}
+
+	#[test]
+	fn test_matmul() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::<f64>::eps() * 16.0);
+
+		for (m, n, k) in [(1, 1, 1), (4, 3, 17), (30, 20, 65)] {
+			for par in [Par::Seq, Par::rayon(4)] {
+				let A = CwiseMatDistribution {
+					nrows: m,
+					ncols: k,
+					dist: StandardNormal,
+				}
+				.rand::<Mat<f64>>(rng);
+				let B = CwiseMatDistribution {
+					nrows: k,
+					ncols: n,
+					dist: StandardNormal,
+				}
+				.rand::<Mat<f64>>(rng);
+				let C = CwiseMatDistribution {
+					nrows: m,
+					ncols: n,
+					dist: StandardNormal,
+				}
+				.rand::<Mat<f64>>(rng);
+
+				let mut dst = C.clone();
+				matmul(&mut dst, Accum::Add, &A, &B, 2.5, par);
+				assert!(dst ~ &C + 2.5 * &A * &B);
+
+				// row-major operands
+				let mut dst = C.clone();
+				matmul(
+					&mut dst,
+					Accum::Replace,
+					A.transpose().to_owned().transpose(),
+					B.transpose().to_owned().transpose(),
+					-1.0,
+					par,
+				);
+				assert!(dst ~ -&A * &B);
+			}
+		}
+	}
+
+	#[test]
+	fn test_matmul_complex() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::<f64>::eps() * 16.0);
+
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		let (m, n, k) = (7, 5, 33);
+		let A = CwiseMatDistribution { nrows: k, ncols: m, dist }.rand::<Mat<c64>>(rng);
+		let B = CwiseMatDistribution { nrows: k, ncols: n, dist }.rand::<Mat<c64>>(rng);
+		let C = CwiseMatDistribution { nrows: m, ncols: n, dist }.rand::<Mat<c64>>(rng);
+		let alpha = c64::new(0.5, -1.5);
+
+		let mut dst = C.clone();
+		matmul(&mut dst, Accum::Add, A.adjoint(), &B.adjoint().to_owned().adjoint(), alpha, Par::Seq);
+		assert!(dst ~ &C + Scale(alpha) * A.adjoint() * &B);
+
+		let mut dst = C.clone();
+		matmul_with_conj(&mut dst, Accum::Replace, A.transpose(), Conj::Yes, &B, Conj::No, alpha, Par::Seq);
+		assert!(dst ~ Scale(alpha) * A.adjoint() * &B);
+	}
+
+	#[test]
+	fn test_residual() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 50;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let x = CwiseMatDistribution {
+			nrows: n,
+			ncols: 1,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+
+		// the residual of an exact solution is only made of rounding errors, which the
+		// compensated product recovers
+		let b = &A * &x;
+		let mut r = b.clone();
+		matmul(&mut r, Accum::Add, &A, &x, -1.0, Par::Seq);
+
+		let factor = split_factor::<f64>();
+		let approx_eq = CwiseMat(ApproxEq {
+			abs_tol: 0.0,
+			rel_tol: 1e-10,
+		});
+		let r_exact = Mat::from_fn(n, 1, |i, _| {
+			let mut acc = Acc::<f64>::new();
+			acc.add(b[(i, 0)]);
+			for k in 0..n {
+				acc.add_prod(-A[(i, k)], x[(k, 0)], &factor);
+			}
+			acc.hi + acc.lo
+		});
+		assert!(r ~ r_exact);
+	}
+
+	#[test]
+	fn test_residual_refinement() {
+		use crate::linalg::solvers::Solve;
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 50;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let b = CwiseMatDistribution {
+			nrows: n,
+			ncols: 1,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let A32 = Mat::from_fn(n, n, |i, j| A[(i, j)] as f32);
+		let b32 = Mat::from_fn(n, 1, |i, j| b[(i, j)] as f32);
+
+		// reference solution of the single precision system, computed in double precision
+		let x_ref = Mat::from_fn(n, n, |i, j| A32[(i, j)] as f64)
+			.partial_piv_lu()
+			.solve(Mat::from_fn(n, 1, |i, j| b32[(i, j)] as f64));
+		let rel_err = |x: &Mat<f32>| (Mat::from_fn(n, 1, |i, j| x[(i, j)] as f64) - &x_ref).norm_l2() / x_ref.norm_l2();
+
+		let lu = A32.partial_piv_lu();
+		let mut x = lu.solve(&b32);
+		let err_before = rel_err(&x);
+
+		let mut r = Mat::zeros(n, 1);
+		for _ in 0..5 {
+			residual(&mut r, &A32, &x, &b32);
+			x += lu.solve(&r);
+		}
+		let err_after = rel_err(&x);
+
+		assert!(err_after < 2.0 * f32::EPSILON as f64);
+		assert!(err_after < err_before);
+
+		// the residual matches the one computed with `matmul`, including for conjugated and
+		// strided operands
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		let A = CwiseMatDistribution { nrows: 6, ncols: 9, dist }.rand::<Mat<c64>>(rng);
+		let x = CwiseMatDistribution { nrows: 6, ncols: 2, dist }.rand::<Mat<c64>>(rng);
+		let b = CwiseMatDistribution { nrows: 9, ncols: 2, dist }.rand::<Mat<c64>>(rng);
+
+		let mut r = Mat::zeros(9, 2);
+		residual(&mut r, A.adjoint(), &x, &b);
+		let mut r_target = b.clone();
+		matmul(&mut r_target, Accum::Add, A.adjoint(), &x, c64::new(-1.0, 0.0), Par::Seq);
+		assert!(r == r_target);
+	}
+
+	#[test]
+	fn test_sum() {
+		let mat = Mat::from_fn(3, 2, |i, j| match (i, j) {
+			(0, 0) => 1e20,
+			(1, 0) => 1.0,
+			(2, 0) => -1e20,
+			(0, 1) => 1e-3,
+			_ => 0.0,
+		});
+		assert!(sum(mat.as_ref()) == 1.0 + 1e-3);
+	}
+}
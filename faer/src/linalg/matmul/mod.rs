@@ -24,6 +24,9 @@ pub(crate) mod internal;
 /// matrices
 pub mod triangular;
 
+pub mod compensated;
+pub mod semiring;
+
 mod matmul_shared {
 	use super::*;
 
@@ -1276,6 +1279,13 @@ fn matmul_imp<'M, 'N, 'K, T: ComplexField>(
 	let M = dst.nrows();
 	let N = dst.ncols();
 	let K = lhs.ncols();
+	crate::utils::trace::span!(TRACE, "matmul", m = M.unbound(), n = N.unbound(), k = K.unbound());
+	crate::utils::counters::count!(
+		Matmul,
+		T,
+		fma = *M * *N * *K,
+		elems = *M * *K + *K * *N + *M * *N * (1 + (beta == Accum::Add) as usize),
+	);
 	if *M == 0 || *N == 0 {
 		return;
 	}
@@ -2153,6 +2163,41 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_matmul_reproducible() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let par = Par::rayon(4);
+
+		for (m, n, k) in [(256, 256, 256), (300, 1, 2000), (1, 300, 2000), (63, 65, 1024)] {
+			let lhs = CwiseMatDistribution {
+				nrows: m,
+				ncols: k,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			let rhs = CwiseMatDistribution {
+				nrows: k,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+
+			let mut first = Mat::<f64>::zeros(m, n);
+			matmul(first.as_mut(), Accum::Replace, lhs.as_ref(), rhs.as_ref(), 1.0, par);
+
+			for _ in 0..4 {
+				let mut dst = Mat::<f64>::zeros(m, n);
+				matmul(dst.as_mut(), Accum::Replace, lhs.as_ref(), rhs.as_ref(), 1.0, par);
+
+				for j in 0..n {
+					for i in 0..m {
+						assert!(dst[(i, j)].to_bits() == first[(i, j)].to_bits());
+					}
+				}
+			}
+		}
+	}
+
 	#[test]
 	fn test_triangular() {
 		use BlockStructure::*;
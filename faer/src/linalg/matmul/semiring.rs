@@ -0,0 +1,522 @@
+//! matrix multiplication over semirings
+//!
+//! the routines in this module compute matrix products where the addition and multiplication are
+//! replaced by the operations of a user-provided [`Semiring`]. this allows expressing graph
+//! algorithms in the language of linear algebra, such as shortest paths with the [`MinPlus`]
+//! semiring, or reachability with the [`OrAnd`] semiring
+//!
+//! # example
+//! ```
+//! use faer::linalg::matmul::semiring::{MinPlus, matmul};
+//! use faer::{Accum, Par, mat};
+//!
+//! const INF: u32 = u32::MAX;
+//!
+//! // edge weights of a directed graph, with `INF` marking missing edges
+//! let mut dist = mat![
+//! 	[0, 4, INF, INF],
+//! 	[INF, 0, 1, INF],
+//! 	[2, INF, 0, 7],
+//! 	[INF, INF, INF, 0],
+//! ];
+//!
+//! // repeated squaring computes the shortest paths with up to 2, 4, ... edges
+//! for _ in 0..2 {
+//! 	let prev = dist.clone();
+//! 	matmul(&mut dist, Accum::Replace, &prev, &prev, MinPlus, Par::Seq);
+//! }
+//!
+//! assert!(
+//! 	dist == mat![
+//! 		[0, 4, 5, 12],
+//! 		[3, 0, 1, 8],
+//! 		[2, 6, 0, 7],
+//! 		[INF, INF, INF, 0]
+//! 	]
+//! );
+//! ```
+
+use crate::internal_prelude::*;
+use crate::mat::{MatMut, MatRef};
+use crate::{Par, Shape};
+use equator::assert;
+
+/// set of values equipped with an addition and a multiplication
+///
+/// the addition must be associative and commutative, with [`Semiring::zero`] as its identity.
+/// the multiplication must be associative, with [`Semiring::one`] as its identity, and must
+/// distribute over the addition. [`Semiring::zero`] must be absorbing for the multiplication
+pub trait Semiring<T>: Copy + Send + Sync {
+	/// returns the identity of the addition
+	fn zero(&self) -> T;
+	/// returns the identity of the multiplication
+	fn one(&self) -> T;
+	/// returns `lhs + rhs`
+	fn add(&self, lhs: &T, rhs: &T) -> T;
+	/// returns `lhs * rhs`
+	fn mul(&self, lhs: &T, rhs: &T) -> T;
+}
+
+/// usual arithmetic semiring, with wrapping operations for integers
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PlusTimes;
+
+/// tropical semiring, where the addition is the minimum and the multiplication is the sum
+///
+/// the zero is the infinity for floating point types, and the maximum value for integers. sums
+/// involving it saturate to it, and other sums saturate to the range of the integer type
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MinPlus;
+
+/// tropical semiring, where the addition is the maximum and the multiplication is the sum
+///
+/// the zero is the negative infinity for floating point types, and the minimum value for
+/// integers. sums involving it saturate to it, and other sums saturate to the range of the
+/// integer type
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MaxPlus;
+
+/// semiring where the addition is the maximum and the multiplication is the product
+///
+/// only forms a semiring over non-negative values. products of integers saturate to the maximum
+/// value
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MaxTimes;
+
+/// boolean semiring, where the addition is the logical or and the multiplication is the logical
+/// and
+#[derive(Copy, Clone, Debug, Default)]
+pub struct OrAnd;
+
+macro_rules! impl_int {
+	($($signed: ty),*; $($unsigned: ty),*) => {
+		$(impl_int!(@common $signed);)*
+		$(impl_int!(@common $unsigned);)*
+
+		$(impl Semiring<$unsigned> for MaxTimes {
+			#[inline(always)]
+			fn zero(&self) -> $unsigned {
+				0
+			}
+
+			#[inline(always)]
+			fn one(&self) -> $unsigned {
+				1
+			}
+
+			#[inline(always)]
+			fn add(&self, lhs: &$unsigned, rhs: &$unsigned) -> $unsigned {
+				Ord::max(*lhs, *rhs)
+			}
+
+			#[inline(always)]
+			fn mul(&self, lhs: &$unsigned, rhs: &$unsigned) -> $unsigned {
+				lhs.saturating_mul(*rhs)
+			}
+		})*
+	};
+
+	(@common $ty: ty) => {
+		impl Semiring<$ty> for PlusTimes {
+			#[inline(always)]
+			fn zero(&self) -> $ty {
+				0
+			}
+
+			#[inline(always)]
+			fn one(&self) -> $ty {
+				1
+			}
+
+			#[inline(always)]
+			fn add(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				lhs.wrapping_add(*rhs)
+			}
+
+			#[inline(always)]
+			fn mul(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				lhs.wrapping_mul(*rhs)
+			}
+		}
+
+		impl Semiring<$ty> for MinPlus {
+			#[inline(always)]
+			fn zero(&self) -> $ty {
+				<$ty>::MAX
+			}
+
+			#[inline(always)]
+			fn one(&self) -> $ty {
+				0
+			}
+
+			#[inline(always)]
+			fn add(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				Ord::min(*lhs, *rhs)
+			}
+
+			#[inline(always)]
+			fn mul(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				if *lhs == <$ty>::MAX || *rhs == <$ty>::MAX {
+					<$ty>::MAX
+				} else {
+					lhs.saturating_add(*rhs)
+				}
+			}
+		}
+
+		impl Semiring<$ty> for MaxPlus {
+			#[inline(always)]
+			fn zero(&self) -> $ty {
+				<$ty>::MIN
+			}
+
+			#[inline(always)]
+			fn one(&self) -> $ty {
+				0
+			}
+
+			#[inline(always)]
+			fn add(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				Ord::max(*lhs, *rhs)
+			}
+
+			#[inline(always)]
+			fn mul(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				if *lhs == <$ty>::MIN || *rhs == <$ty>::MIN {
+					<$ty>::MIN
+				} else {
+					lhs.saturating_add(*rhs)
+				}
+			}
+		}
+	};
+}
+
+macro_rules! impl_float {
+	($($ty: ty),*) => {$(
+		impl Semiring<$ty> for PlusTimes {
+			#[inline(always)]
+			fn zero(&self) -> $ty {
+				0.0
+			}
+
+			#[inline(always)]
+			fn one(&self) -> $ty {
+				1.0
+			}
+
+			#[inline(always)]
+			fn add(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				*lhs + *rhs
+			}
+
+			#[inline(always)]
+			fn mul(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				*lhs * *rhs
+			}
+		}
+
+		impl Semiring<$ty> for MinPlus {
+			#[inline(always)]
+			fn zero(&self) -> $ty {
+				<$ty>::INFINITY
+			}
+
+			#[inline(always)]
+			fn one(&self) -> $ty {
+				0.0
+			}
+
+			#[inline(always)]
+			fn add(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				<$ty>::min(*lhs, *rhs)
+			}
+
+			#[inline(always)]
+			fn mul(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				*lhs + *rhs
+			}
+		}
+
+		impl Semiring<$ty> for MaxPlus {
+			#[inline(always)]
+			fn zero(&self) -> $ty {
+				<$ty>::NEG_INFINITY
+			}
+
+			#[inline(always)]
+			fn one(&self) -> $ty {
+				0.0
+			}
+
+			#[inline(always)]
+			fn add(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				<$ty>::max(*lhs, *rhs)
+			}
+
+			#[inline(always)]
+			fn mul(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				*lhs + *rhs
+			}
+		}
+
+		impl Semiring<$ty> for MaxTimes {
+			#[inline(always)]
+			fn zero(&self) -> $ty {
+				0.0
+			}
+
+			#[inline(always)]
+			fn one(&self) -> $ty {
+				1.0
+			}
+
+			#[inline(always)]
+			fn add(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				<$ty>::max(*lhs, *rhs)
+			}
+
+			#[inline(always)]
+			fn mul(&self, lhs: &$ty, rhs: &$ty) -> $ty {
+				*lhs * *rhs
+			}
+		}
+	)*};
+}
+
+impl_int!(i8, i16, i32, i64, i128, isize; u8, u16, u32, u64, u128, usize);
+impl_float!(f32, f64);
+
+impl Semiring<bool> for OrAnd {
+	#[inline(always)]
+	fn zero(&self) -> bool {
+		false
+	}
+
+	#[inline(always)]
+	fn one(&self) -> bool {
+		true
+	}
+
+	#[inline(always)]
+	fn add(&self, lhs: &bool, rhs: &bool) -> bool {
+		*lhs || *rhs
+	}
+
+	#[inline(always)]
+	fn mul(&self, lhs: &bool, rhs: &bool) -> bool {
+		*lhs && *rhs
+	}
+}
+
+/// number of rows of `dst` that are updated together, so that they stay in the cache while the
+/// columns of `lhs` are streamed through
+const ROW_BLOCK: usize = 256;
+/// number of columns of `lhs` that are processed together, so that they stay in the cache while
+/// the columns of `dst` are updated
+const DEPTH_BLOCK: usize = 128;
+
+/// computes `dst[i] = dst[i] + lhs[i] * rhs`
+#[inline(always)]
+fn axpy<T, S: Semiring<T>>(dst: MatMut<'_, T>, j: usize, lhs: MatRef<'_, T>, k: usize, rhs: &T, semiring: S) {
+	let mut dst = dst;
+	match (dst.rb_mut().col_mut(j).try_as_col_major_mut(), lhs.col(k).try_as_col_major()) {
+		(Some(dst), Some(lhs)) => {
+			for (dst, lhs) in dst.as_slice_mut().iter_mut().zip(lhs.as_slice()) {
+				*dst = semiring.add(dst, &semiring.mul(lhs, rhs));
+			}
+		},
+		_ => {
+			for i in 0..dst.nrows() {
+				dst[(i, j)] = semiring.add(&dst[(i, j)], &semiring.mul(&lhs[(i, k)], rhs));
+			}
+		},
+	}
+}
+
+fn matmul_imp<T: Send + Sync, S: Semiring<T>>(dst: MatMut<'_, T>, beta: Accum, lhs: MatRef<'_, T>, rhs: MatRef<'_, T>, semiring: S, par: Par) {
+	let mut dst = dst;
+	if beta == Accum::Replace {
+		for j in 0..dst.ncols() {
+			for i in 0..dst.nrows() {
+				dst[(i, j)] = semiring.zero();
+			}
+		}
+	}
+
+	let imp = |dst: MatMut<'_, T>, lhs: MatRef<'_, T>, rhs: MatRef<'_, T>| {
+		let mut dst = dst;
+		let (m, n, k) = (dst.nrows(), dst.ncols(), lhs.ncols());
+
+		for i in (0..m).step_by(ROW_BLOCK) {
+			let bm = Ord::min(ROW_BLOCK, m - i);
+			let mut dst = dst.rb_mut().subrows_mut(i, bm);
+			let lhs = lhs.subrows(i, bm);
+
+			for depth in (0..k).step_by(DEPTH_BLOCK) {
+				let bk = Ord::min(DEPTH_BLOCK, k - depth);
+
+				for j in 0..n {
+					for depth in depth..depth + bk {
+						axpy(dst.rb_mut(), j, lhs, depth, &rhs[(depth, j)], semiring);
+					}
+				}
+			}
+		}
+	};
+
+	match par {
+		Par::Seq => imp(dst, lhs, rhs),
+		#[cfg(feature = "rayon")]
+		Par::Rayon(nthreads) => {
+			use rayon::prelude::*;
+
+			// each element of `dst` is computed by a single thread, in the same order as the
+			// sequential version, so the partitioning doesn't affect the results
+			if dst.ncols() >= dst.nrows() {
+				let nthreads = Ord::min(nthreads.get(), dst.ncols());
+				if nthreads <= 1 {
+					return imp(dst, lhs, rhs);
+				}
+				dst.par_col_partition_mut(nthreads)
+					.zip(rhs.par_col_partition(nthreads))
+					.for_each(|(dst, rhs)| imp(dst, lhs, rhs));
+			} else {
+				let nthreads = Ord::min(nthreads.get(), dst.nrows());
+				if nthreads <= 1 {
+					return imp(dst, lhs, rhs);
+				}
+				dst.par_row_partition_mut(nthreads)
+					.zip(lhs.par_row_partition(nthreads))
+					.for_each(|(dst, lhs)| imp(dst, lhs, rhs));
+			}
+		},
+	}
+}
+
+/// computes the matrix product `[beta * acc] + lhs * rhs` over the given semiring, and stores
+/// the result in `acc`
+///
+/// performs the operation:
+/// - `acc = lhs * rhs` if `beta` is `Accum::Replace` (in this case, the preexisting values in `acc`
+///   are not read)
+/// - `acc = acc + lhs * rhs` if `beta` is `Accum::Add`
+///
+/// where the additions and multiplications are those of `semiring`. for example, with the
+/// [`MinPlus`] semiring, `acc[(i, j)]` is set to the minimum of `lhs[(i, k)] + rhs[(k, j)]` over
+/// all `k`
+///
+/// # panics
+/// panics if the matrix dimensions are not compatible for matrix multiplication
+#[track_caller]
+#[inline]
+pub fn matmul<T: Send + Sync, S: Semiring<T>, M: Shape, N: Shape, K: Shape>(
+	dst: impl AsMatMut<T = T, Rows = M, Cols = N>,
+	beta: Accum,
+	lhs: impl AsMatRef<T = T, Rows = M, Cols = K>,
+	rhs: impl AsMatRef<T = T, Rows = K, Cols = N>,
+	semiring: S,
+	par: Par,
+) {
+	let mut dst = dst;
+	let dst = dst.as_mat_mut();
+	let lhs = lhs.as_mat_ref();
+	let rhs = rhs.as_mat_ref();
+
+	assert!(all(dst.nrows() == lhs.nrows(), dst.ncols() == rhs.ncols(), lhs.ncols() == rhs.nrows()));
+
+	matmul_imp(
+		dst.as_dyn_mut().as_dyn_stride_mut(),
+		beta,
+		lhs.as_dyn().as_dyn_stride(),
+		rhs.as_dyn().as_dyn_stride(),
+		semiring,
+		par,
+	);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{Mat, assert};
+
+	fn naive<T: Copy, S: Semiring<T>>(acc: Option<&Mat<T>>, lhs: &Mat<T>, rhs: &Mat<T>, semiring: S) -> Mat<T> {
+		Mat::from_fn(lhs.nrows(), rhs.ncols(), |i, j| {
+			let mut sum = match acc {
+				Some(acc) => acc[(i, j)],
+				None => semiring.zero(),
+			};
+			for k in 0..lhs.ncols() {
+				sum = semiring.add(&sum, &semiring.mul(&lhs[(i, k)], &rhs[(k, j)]));
+			}
+			sum
+		})
+	}
+
+	#[test]
+	fn test_matmul() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n, k) in [(0, 3, 2), (1, 1, 1), (4, 3, 17), (300, 5, 270), (6, 300, 140)] {
+			for par in [Par::Seq, Par::rayon(4)] {
+				let A = Mat::from_fn(m, k, |_, _| if rng.gen_bool(0.2) { u32::MAX } else { rng.gen_range(0..1000u32) });
+				let B = Mat::from_fn(k, n, |_, _| if rng.gen_bool(0.2) { u32::MAX } else { rng.gen_range(0..1000u32) });
+				let C = Mat::from_fn(m, n, |_, _| rng.gen_range(0..3000u32));
+
+				let mut dst = C.clone();
+				matmul(&mut dst, Accum::Add, &A, &B, MinPlus, par);
+				assert!(dst == naive(Some(&C), &A, &B, MinPlus));
+
+				// row-major operands
+				let mut dst = C.clone();
+				matmul(
+					&mut dst,
+					Accum::Replace,
+					Mat::from_fn(k, m, |j, i| A[(i, j)]).transpose(),
+					Mat::from_fn(n, k, |j, i| B[(i, j)]).transpose(),
+					MaxTimes,
+					par,
+				);
+				assert!(dst == naive(None, &A, &B, MaxTimes));
+
+				let A = Mat::from_fn(m, k, |_, _| rng.gen_range(-100..100i64));
+				let B = Mat::from_fn(k, n, |_, _| rng.gen_range(-100..100i64));
+
+				let mut dst = Mat::full(m, n, 0);
+				matmul(&mut dst, Accum::Replace, &A, &B, PlusTimes, par);
+				assert!(dst == naive(None, &A, &B, PlusTimes));
+
+				let mut dst = Mat::full(m, n, i64::MIN);
+				matmul(dst.transpose_mut(), Accum::Add, B.transpose(), A.transpose(), MaxPlus, par);
+				assert!(dst == naive(None, &A, &B, MaxPlus));
+			}
+		}
+	}
+
+	#[test]
+	fn test_saturation() {
+		assert!(MinPlus.mul(&i32::MAX, &-5) == i32::MAX);
+		assert!(MinPlus.mul(&(i32::MAX - 1), &5) == i32::MAX);
+		assert!(MinPlus.mul(&i32::MIN, &-5) == i32::MIN);
+		assert!(MaxPlus.mul(&i32::MIN, &5) == i32::MIN);
+		assert!(MaxTimes.mul(&u8::MAX, &2) == u8::MAX);
+		assert!(MinPlus.mul(&f64::INFINITY, &-5.0) == f64::INFINITY);
+	}
+
+	#[test]
+	fn test_reachability() {
+		let n = 50;
+
+		// path graph `0 -> 1 -> ... -> n - 1`, with self loops
+		let adj = Mat::from_fn(n, n, |i, j| j == i || j == i + 1);
+
+		let mut reach = adj.clone();
+		for _ in 0..6 {
+			let prev = reach.clone();
+			matmul(&mut reach, Accum::Replace, &prev, &prev, OrAnd, Par::rayon(4));
+		}
+		assert!(reach == Mat::from_fn(n, n, |i, j| j >= i));
+	}
+}
@@ -191,6 +191,40 @@ pub mod jacobi;
 /// kronecker product
 pub mod kron;
 
+/// row, column and diagonal congruence scaling
+pub mod scaling;
+
+pub mod elementwise;
+pub mod scan;
+
+/// block diagonal and block triangular matrices
+pub mod block;
+
+/// fast fourier transform
+pub mod fft;
+
+/// matrix exponential
+pub mod expm;
+
+/// exact integer matrix products, hermite and smith normal forms, and $LU$ modulo a prime
+pub mod integer;
+
+/// prime field arithmetic, and gaussian elimination over exact fields
+pub mod finite_field;
+
+/// bit-packed boolean matrices
+pub mod boolean;
+
+/// hierarchically off-diagonal low-rank matrices
+#[cfg(feature = "rand")]
+pub mod hodlr;
+
+/// gaussian, subsampled randomized hadamard and count sketches
+#[cfg(feature = "rand")]
+pub mod sketch;
+
+pub mod relayout;
+
 pub mod cholesky;
 pub mod lu;
 pub mod qr;
@@ -198,7 +232,27 @@ pub mod qr;
 pub mod evd;
 pub mod svd;
 
+pub mod subspace;
+
+pub mod projection;
+
+pub mod lstsq;
+
+pub mod error_bounds;
+
 mod mat_ops;
 
 /// high level solvers
 pub mod solvers;
+
+pub mod tune;
+
+pub mod progress;
+
+pub mod workspace;
+
+pub mod scratch_pool;
+
+pub mod lazy;
+
+pub mod out_of_core;
@@ -0,0 +1,415 @@
+//! factorizations of matrices that are too large to fit in memory
+//!
+//! the matrix is processed one column panel at a time, in a left-looking fashion: each panel is
+//! loaded in memory, updated with the contributions of the previously factorized panels, factorized
+//! with the blocked in-core kernels, then written back. the previously factorized panels are only
+//! ever read sequentially, one at a time
+//!
+//! the routines accept any [`MatMut`], but are intended to be used with a matrix backed by a
+//! memory-mapped file, such as [`io::mmap::MmapMat`](crate::io::mmap::MmapMat). the memory usage
+//! is dominated by a single panel of size `nrows × panel_width`, while the amount of data read from
+//! the matrix is roughly `n³ / panel_width` for an `n × n` matrix, so the panel width should be
+//! chosen as large as memory allows
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::linalg::cholesky::llt::factor::{LltError, LltInfo, LltParams};
+use crate::linalg::lu::partial_pivoting::factor::{PartialPivLuInfo, PartialPivLuParams, lu_in_place_recursion};
+use linalg::matmul::matmul;
+use linalg::triangular_solve::{solve_lower_triangular_in_place, solve_unit_lower_triangular_in_place};
+
+/// out-of-core factorization tuning parameters
+#[derive(Copy, Clone, Debug)]
+pub struct OutOfCoreParams {
+	/// number of columns held in memory at a time
+	pub panel_width: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: ComplexField> Auto<T> for OutOfCoreParams {
+	#[inline]
+	fn auto() -> Self {
+		Self {
+			panel_width: 512,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+#[inline]
+fn panel_width(params: &OutOfCoreParams) -> usize {
+	Ord::max(1, params.panel_width)
+}
+
+/// computes the size and alignment of required workspace for performing an out-of-core
+/// cholesky decomposition
+pub fn cholesky_in_place_scratch<T: ComplexField>(dim: usize, par: Par, params: Spec<OutOfCoreParams, T>) -> StackReq {
+	let width = Ord::min(dim, panel_width(&params.config));
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(dim, width),
+		linalg::cholesky::llt::factor::cholesky_in_place_scratch::<T>(width, par, Spec::<LltParams, T>::default()),
+	])
+}
+
+/// computes the cholesky factor $L$ of the hermitian positive definite input matrix, such that
+/// $A = LL^H$, where $L$ is lower triangular
+///
+/// the lower triangular half of `A` is overwritten by $L$, and its strictly upper triangular half
+/// is not accessed
+///
+/// see the [module level documentation](self) for the memory and i/o characteristics
+///
+/// # panics
+/// panics if `A` is not square
+#[math]
+pub fn cholesky_in_place<T: ComplexField>(
+	A: MatMut<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<OutOfCoreParams, T>,
+) -> Result<LltInfo, LltError> {
+	let n = A.nrows();
+	assert!(A.nrows() == A.ncols());
+
+	let mut A = A;
+	let width = Ord::min(n, panel_width(&params.config));
+
+	let (mut panel, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, width, stack) };
+	let mut panel = panel.as_mat_mut();
+
+	let mut info = LltInfo {
+		dynamic_regularization_count: 0,
+	};
+
+	let mut j = 0;
+	while j < n {
+		let w = Ord::min(width, n - j);
+		let mut panel = panel.rb_mut().get_mut(..n - j, ..w);
+
+		panel.copy_from(A.rb().get(j.., j..j + w));
+
+		// apply the contributions of the previous panels
+		let mut k = 0;
+		while k < j {
+			let wk = Ord::min(width, j - k);
+			let L = A.rb().get(j.., k..k + wk);
+			matmul(panel.rb_mut(), Accum::Add, L, L.get(..w, ..).adjoint(), -one::<T>(), par);
+			k += wk;
+		}
+
+		{
+			let (mut A11, mut A21) = panel.rb_mut().split_at_row_mut(w);
+
+			let count = linalg::cholesky::llt::factor::cholesky_in_place(A11.rb_mut(), default(), par, stack, default())
+				.map_err(|LltError::NonPositivePivot { index }| LltError::NonPositivePivot { index: index + j })?;
+			info.dynamic_regularization_count += count.dynamic_regularization_count;
+
+			// A21 := A21 * L11^-H
+			solve_lower_triangular_in_place(A11.rb().conjugate(), A21.rb_mut().transpose_mut(), par);
+		}
+
+		// only the lower triangular half is written back
+		for c in 0..w {
+			A.rb_mut().get_mut(j + c.., j + c).copy_from(panel.rb().get(c.., c));
+		}
+
+		j += w;
+	}
+
+	Ok(info)
+}
+
+/// computes the size and alignment of required workspace for performing an out-of-core
+/// $LU$ decomposition with partial pivoting
+pub fn lu_in_place_scratch<I: Index, T: ComplexField>(nrows: usize, ncols: usize, par: Par, params: Spec<OutOfCoreParams, T>) -> StackReq {
+	_ = par;
+	let width = Ord::min(ncols, panel_width(&params.config));
+	StackReq::all_of(&[temp_mat_scratch::<T>(nrows, width), StackReq::new::<usize>(Ord::min(nrows, ncols))])
+}
+
+/// computes the $LU$ decomposition of `A` with partial pivoting, such that $PA = LU$
+///
+/// the strictly lower triangular part of `A` is overwritten by the unit lower triangular factor
+/// $L$, and its upper triangular part by $U$. the permutation $P$ is stored in `perm` and
+/// `perm_inv`
+///
+/// see the [module level documentation](self) for the memory and i/o characteristics
+///
+/// # panics
+/// panics if `perm` or `perm_inv` don't have a length equal to the number of rows of `A`
+#[math]
+pub fn lu_in_place<'out, I: Index, T: ComplexField>(
+	A: MatMut<'_, T>,
+	perm: &'out mut [I],
+	perm_inv: &'out mut [I],
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<OutOfCoreParams, T>,
+) -> (PartialPivLuInfo, PermRef<'out, I>) {
+	let (m, n) = A.shape();
+	let size = Ord::min(m, n);
+	assert!(all(perm.len() == m, perm_inv.len() == m));
+
+	let mut A = A;
+	let width = Ord::min(n, panel_width(&params.config));
+
+	let (mut panel, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, width, stack) };
+	let mut panel = panel.as_mat_mut();
+
+	// `trans[i] = t` means that rows `i` and `i + t` were swapped
+	let (mut trans, _) = stack.make_with(size, |_| 0usize);
+	let trans = &mut *trans;
+
+	let swap_rows = |mut mat: MatMut<'_, T>, range: core::ops::Range<usize>, trans: &[usize]| {
+		for i in range {
+			let t = trans[i];
+			if t != 0 {
+				crate::perm::swap_rows_idx(mat.rb_mut(), i, i + t);
+			}
+		}
+	};
+
+	let mut n_trans = 0;
+
+	let mut j = 0;
+	while j < n {
+		let w = Ord::min(width, n - j);
+		let mut panel = panel.rb_mut().get_mut(.., ..w);
+
+		panel.copy_from(A.rb().get(.., j..j + w));
+
+		// apply the row interchanges and the contributions of the previous panels
+		let mut k = 0;
+		while k < Ord::min(j, size) {
+			let wk = Ord::min(width, Ord::min(j, size) - k);
+
+			swap_rows(panel.rb_mut(), k..k + wk, trans);
+
+			let L = A.rb().get(k.., k..k + wk);
+			let (L11, L21) = L.split_at_row(wk);
+			let (mut U12, mut A22) = panel.rb_mut().get_mut(k.., ..).split_at_row_mut(wk);
+
+			solve_unit_lower_triangular_in_place(L11, U12.rb_mut(), par);
+			matmul(A22.rb_mut(), Accum::Add, L21, U12.rb(), -one::<T>(), par);
+
+			k += wk;
+		}
+
+		if j < size {
+			let pw = Ord::min(w, m - j);
+			let mut panel = panel.rb_mut().get_mut(j.., ..);
+
+			n_trans += lu_in_place_recursion(
+				panel.rb_mut(),
+				0,
+				pw,
+				&mut trans[j..j + pw],
+				par,
+				Spec::<PartialPivLuParams, T>::default(),
+			);
+
+			// the panel is wider than the remaining rows, in which case its right part belongs to
+			// $U$
+			if pw < w {
+				let (L, U) = panel.rb_mut().split_at_col_mut(pw);
+				solve_unit_lower_triangular_in_place(L.rb().get(..pw, ..), U.get_mut(..pw, ..), par);
+			}
+		}
+
+		A.rb_mut().get_mut(.., j..j + w).copy_from(panel.rb());
+		j += w;
+	}
+
+	// the factorized panels only have the row interchanges that were found up to the point where
+	// they were processed, so the later ones still need to be applied to them
+	let mut k = 0;
+	while k < size {
+		let wk = Ord::min(width, size - k);
+		swap_rows(A.rb_mut().get_mut(.., k..k + wk), k + wk..size, trans);
+		k += wk;
+	}
+
+	for (i, p) in perm.iter_mut().enumerate() {
+		*p = I::truncate(i);
+	}
+	for (i, &t) in trans.iter().enumerate() {
+		perm.swap(i, i + t);
+	}
+	for i in 0..m {
+		perm_inv[perm[i].zx()] = I::truncate(i);
+	}
+
+	(
+		PartialPivLuInfo {
+			transposition_count: n_trans,
+			cancelled: false,
+		},
+		unsafe { PermRef::new_unchecked(perm, perm_inv, m) },
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{Mat, assert, c64};
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_cholesky() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+
+		for n in [1, 7, 64, 129] {
+			for panel_width in [1, 5, 32, 200] {
+				let B = CwiseMatDistribution {
+					nrows: n,
+					ncols: n,
+					dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+				}
+				.rand::<Mat<c64>>(rng);
+				let A = &B * B.adjoint() + Mat::<c64>::identity(n, n);
+
+				let params = OutOfCoreParams { panel_width, ..auto!(c64) };
+
+				let mut L = A.clone();
+				cholesky_in_place(
+					L.as_mut(),
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(cholesky_in_place_scratch::<c64>(n, Par::Seq, params.into()))),
+					params.into(),
+				)
+				.unwrap();
+
+				for j in 0..n {
+					for i in 0..j {
+						assert!(L[(i, j)] == A[(i, j)]);
+						L[(i, j)] = c64::ZERO;
+					}
+				}
+				assert!(&L * L.adjoint() ~ A);
+			}
+		}
+	}
+
+	#[test]
+	fn test_lu() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+
+		for (m, n) in [(1, 1), (8, 8), (50, 20), (20, 50), (129, 129)] {
+			for panel_width in [1, 7, 16, 200] {
+				let A = CwiseMatDistribution {
+					nrows: m,
+					ncols: n,
+					dist: StandardNormal,
+				}
+				.rand::<Mat<f64>>(rng);
+
+				let params = OutOfCoreParams { panel_width, ..auto!(f64) };
+
+				let mut LU = A.clone();
+				let perm = &mut *vec![0usize; m];
+				let perm_inv = &mut *vec![0usize; m];
+				let (_, p) = lu_in_place(
+					LU.as_mut(),
+					perm,
+					perm_inv,
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, f64>(m, n, Par::Seq, params.into()))),
+					params.into(),
+				);
+
+				let size = Ord::min(m, n);
+				let L = Mat::from_fn(m, size, |i, j| {
+					if i == j {
+						1.0
+					} else if i > j {
+						LU[(i, j)]
+					} else {
+						0.0
+					}
+				});
+				let U = Mat::from_fn(size, n, |i, j| if i <= j { LU[(i, j)] } else { 0.0 });
+
+				assert!(p.inverse() * &L * &U ~ A);
+
+				// the result should match the in-core factorization
+				let mut LU_in_core = A.clone();
+				let perm_in_core = &mut *vec![0usize; m];
+				let perm_inv_in_core = &mut *vec![0usize; m];
+				linalg::lu::partial_pivoting::factor::lu_in_place(
+					LU_in_core.as_mut(),
+					perm_in_core,
+					perm_inv_in_core,
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(
+						linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<usize, f64>(m, n, Par::Seq, default()),
+					)),
+					default(),
+				);
+				assert!(perm == perm_in_core);
+				assert!(LU ~ LU_in_core);
+			}
+		}
+	}
+
+	#[cfg(feature = "mmap")]
+	#[test]
+	fn test_lu_mmap() {
+		use crate::io::mmap::MmapMat;
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+
+		let n = 100;
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+
+		let path = std::env::temp_dir().join(std::format!("faer-out-of-core-test-{}.bin", std::process::id()));
+		let mut LU = MmapMat::<f64>::create(&path, n, n).unwrap();
+		LU.as_mut().copy_from(&A);
+
+		let params = OutOfCoreParams {
+			panel_width: 16,
+			..auto!(f64)
+		};
+		let perm = &mut *vec![0usize; n];
+		let perm_inv = &mut *vec![0usize; n];
+		lu_in_place(
+			LU.as_mut(),
+			perm,
+			perm_inv,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, f64>(n, n, Par::Seq, params.into()))),
+			params.into(),
+		);
+		LU.flush().unwrap();
+		drop(LU);
+
+		// the factors are persisted in the file
+		let LU = MmapMat::<f64>::open(&path, n, n).unwrap();
+		let mut LU_in_core = A.clone();
+		linalg::lu::partial_pivoting::factor::lu_in_place(
+			LU_in_core.as_mut(),
+			&mut *vec![0usize; n],
+			&mut *vec![0usize; n],
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(
+				linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<usize, f64>(n, n, Par::Seq, default()),
+			)),
+			default(),
+		);
+		assert!(LU.as_ref() ~ LU_in_core);
+
+		drop(LU);
+		std::fs::remove_file(&path).unwrap();
+	}
+}
@@ -0,0 +1,315 @@
+//! progress reporting and cancellation for the blocked factorizations
+//!
+//! the $LU$, $QR$ and svd drivers have `_with_progress` variants taking a [`ProgressMonitor`],
+//! which is invoked from the calling thread between two panels (or stages, for the svd) of the
+//! factorization. the monitor receives the fraction of the work that has been done, and can cancel
+//! the factorization by returning [`ControlFlow::Break`]
+//!
+//! - [`lu_in_place_with_progress`](crate::linalg::lu::partial_pivoting::factor::lu_in_place_with_progress)
+//! reports the number of eliminated columns. the matrix is factored panel by panel with panels of
+//! [`PartialPivLuParams::blocksize`](crate::linalg::lu::partial_pivoting::factor::PartialPivLuParams::blocksize)
+//! columns instead of recursively
+//! - [`qr_in_place_with_progress`](crate::linalg::qr::no_pivoting::factor::qr_in_place_with_progress)
+//! reports the number of processed columns
+//! - [`svd_with_progress`](crate::linalg::svd::svd_with_progress) reports the number of completed
+//! stages: the optional $QR$ preconditioning, the bidiagonalization, the bidiagonal svd and the
+//! computation of the singular vectors
+//!
+//! when a factorization is cancelled, the $LU$ and $QR$ drivers return early with the `cancelled`
+//! flag of their info set, and skip the remaining post-processing, so the contents of the output
+//! matrices are unspecified. the svd returns
+//! [`SvdError::Cancelled`](crate::linalg::svd::SvdError::Cancelled)
+//!
+//! an [`AtomicBool`] can be used directly as a cancellation flag
+//!
+//! # example
+//! ```
+//! use core::sync::atomic::{AtomicBool, Ordering};
+//! use faer::dyn_stack::{MemBuffer, MemStack};
+//! use faer::linalg::lu::partial_pivoting::factor::{
+//! 	PartialPivLuParams, lu_in_place_scratch, lu_in_place_with_progress,
+//! };
+//! use faer::{Mat, Par, Spec};
+//!
+//! let cancel = AtomicBool::new(false);
+//!
+//! let n = 256;
+//! let mut A = Mat::from_fn(n, n, |i, j| {
+//! 	if i == j {
+//! 		2.0
+//! 	} else {
+//! 		1.0 / (1.0 + i as f64 + j as f64)
+//! 	}
+//! });
+//! let perm = &mut *vec![0usize; n];
+//! let perm_inv = &mut *vec![0usize; n];
+//!
+//! let params = Spec::<PartialPivLuParams, f64>::default();
+//!
+//! // another thread can abort the factorization with `cancel.store(true, Ordering::Relaxed)`
+//! let (info, _) = lu_in_place_with_progress(
+//! 	A.as_mut(),
+//! 	perm,
+//! 	perm_inv,
+//! 	Par::Seq,
+//! 	MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, f64>(
+//! 		n,
+//! 		n,
+//! 		Par::Seq,
+//! 		params,
+//! 	))),
+//! 	params,
+//! 	&cancel,
+//! );
+//! assert!(!cancel.load(Ordering::Relaxed));
+//! assert!(!info.cancelled);
+//! ```
+
+use crate::NonExhaustive;
+use core::ops::ControlFlow;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// progress of a factorization
+#[derive(Copy, Clone, Debug)]
+pub struct Progress {
+	/// amount of work that has been done, in units that depend on the factorization
+	pub done: usize,
+	/// total amount of work, in the same units as `done`
+	pub total: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// receives the progress reports of a factorization
+pub trait ProgressMonitor: Sync + core::fmt::Debug {
+	/// called between two panels of the factorization, with the amount of work done so far
+	///
+	/// returning [`ControlFlow::Break`] cancels the factorization
+	fn report(&self, progress: Progress) -> ControlFlow<()>;
+}
+
+/// cancels the factorization once the flag is set
+impl ProgressMonitor for AtomicBool {
+	#[inline]
+	fn report(&self, _: Progress) -> ControlFlow<()> {
+		if self.load(Ordering::Relaxed) {
+			ControlFlow::Break(())
+		} else {
+			ControlFlow::Continue(())
+		}
+	}
+}
+
+/// reports the progress to the monitor, if there is one, and returns whether the factorization
+/// should be cancelled
+#[inline]
+pub(crate) fn report(monitor: Option<&dyn ProgressMonitor>, done: usize, total: usize) -> bool {
+	match monitor {
+		Some(monitor) => monitor
+			.report(Progress {
+				done,
+				total,
+				non_exhaustive: NonExhaustive(()),
+			})
+			.is_break(),
+		None => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::internal_prelude::*;
+	use crate::linalg::lu::partial_pivoting::factor::{PartialPivLuParams, lu_in_place_scratch, lu_in_place_with_progress};
+	use crate::linalg::qr::no_pivoting::factor::{QrParams, qr_in_place, qr_in_place_scratch, qr_in_place_with_progress};
+	use crate::linalg::svd::{ComputeSvdVectors, SvdError, SvdParams, svd, svd_scratch, svd_with_progress};
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+	use core::sync::atomic::AtomicUsize;
+	use dyn_stack::MemBuffer;
+
+	/// records the reports, and cancels after `cancel_after` of them
+	#[derive(Debug)]
+	struct Recorder {
+		count: AtomicUsize,
+		done: AtomicUsize,
+		total: AtomicUsize,
+		cancel_after: usize,
+	}
+
+	impl Recorder {
+		fn new(cancel_after: usize) -> Self {
+			Self {
+				count: AtomicUsize::new(0),
+				done: AtomicUsize::new(0),
+				total: AtomicUsize::new(0),
+				cancel_after,
+			}
+		}
+	}
+
+	impl ProgressMonitor for Recorder {
+		fn report(&self, progress: Progress) -> ControlFlow<()> {
+			assert!(progress.done <= progress.total);
+			assert!(progress.done > self.done.swap(progress.done, Ordering::Relaxed));
+			self.total.store(progress.total, Ordering::Relaxed);
+			if self.count.fetch_add(1, Ordering::Relaxed) + 1 >= self.cancel_after {
+				ControlFlow::Break(())
+			} else {
+				ControlFlow::Continue(())
+			}
+		}
+	}
+
+	#[test]
+	fn test_lu_progress() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 200;
+		let A = Mat::<f64>::random_normal(rng, n, n);
+
+		for par in [Par::Seq, Par::rayon(4)] {
+			let recorder = Recorder::new(usize::MAX);
+			let params: Spec<PartialPivLuParams, f64> = PartialPivLuParams { blocksize: 32, ..auto!(f64) }.into();
+
+			let mut LU = A.clone();
+			let perm = &mut *alloc::vec![0usize; n];
+			let perm_inv = &mut *alloc::vec![0usize; n];
+			let (info, p) = lu_in_place_with_progress(
+				LU.as_mut(),
+				perm,
+				perm_inv,
+				par,
+				MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, f64>(n, n, par, params))),
+				params,
+				&recorder,
+			);
+			assert!(recorder.count.load(Ordering::Relaxed) == n.div_ceil(32));
+			assert!(recorder.done.load(Ordering::Relaxed) == n);
+			assert!(recorder.total.load(Ordering::Relaxed) == n);
+			assert!(!info.cancelled);
+
+			let L = Mat::from_fn(n, n, |i, j| {
+				if i > j {
+					LU[(i, j)]
+				} else if i == j {
+					1.0
+				} else {
+					0.0
+				}
+			});
+			let U = Mat::from_fn(n, n, |i, j| if i <= j { LU[(i, j)] } else { 0.0 });
+			assert!((p.inverse() * &L * &U - &A).norm_max() < 1e-10);
+		}
+
+		// cancelled after the second panel
+		let recorder = Recorder::new(2);
+		let params: Spec<PartialPivLuParams, f64> = PartialPivLuParams { blocksize: 32, ..auto!(f64) }.into();
+		let mut LU = A.clone();
+		let perm = &mut *alloc::vec![0usize; n];
+		let perm_inv = &mut *alloc::vec![0usize; n];
+		let (info, _) = lu_in_place_with_progress(
+			LU.as_mut(),
+			perm,
+			perm_inv,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, f64>(n, n, Par::Seq, params))),
+			params,
+			&recorder,
+		);
+		assert!(info.cancelled);
+		assert!(recorder.count.load(Ordering::Relaxed) == 2);
+		assert!(recorder.done.load(Ordering::Relaxed) == 64);
+
+		// a cancelled wide factorization skips the solve for the trailing columns
+		let (m, n) = (64, 200);
+		let A = Mat::<f64>::random_normal(rng, m, n);
+		let recorder = Recorder::new(1);
+		let params: Spec<PartialPivLuParams, f64> = PartialPivLuParams { blocksize: 32, ..auto!(f64) }.into();
+		let mut LU = A.clone();
+		let perm = &mut *alloc::vec![0usize; m];
+		let perm_inv = &mut *alloc::vec![0usize; m];
+		let (info, _) = lu_in_place_with_progress(
+			LU.as_mut(),
+			perm,
+			perm_inv,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, f64>(m, n, Par::Seq, params))),
+			params,
+			&recorder,
+		);
+		assert!(info.cancelled);
+		// the trailing columns are only permuted by the row swaps of the first panel
+		for j in m..n {
+			let mut before = A.col(j).iter().copied().collect::<alloc::vec::Vec<_>>();
+			let mut after = LU.col(j).iter().copied().collect::<alloc::vec::Vec<_>>();
+			before.sort_by(f64::total_cmp);
+			after.sort_by(f64::total_cmp);
+			assert!(before == after);
+		}
+	}
+
+	#[test]
+	fn test_qr_progress() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n) = (300, 200);
+		let A = Mat::<c64>::random_normal(rng, m, n);
+		let bs = 32;
+
+		let run = |progress: Option<&dyn ProgressMonitor>| {
+			let params = Spec::<QrParams, c64>::default();
+			let mut QR = A.clone();
+			let mut H = Mat::<c64>::zeros(bs, n);
+			let mem = &mut MemBuffer::new(qr_in_place_scratch::<c64>(m, n, bs, Par::Seq, params));
+			let stack = MemStack::new(mem);
+			let info = match progress {
+				Some(progress) => qr_in_place_with_progress(QR.as_mut(), H.as_mut(), Par::Seq, stack, params, progress),
+				None => qr_in_place(QR.as_mut(), H.as_mut(), Par::Seq, stack, params),
+			};
+			(QR, info.cancelled)
+		};
+
+		// the monitor doesn't change the result
+		let recorder = Recorder::new(usize::MAX);
+		assert!(run(Some(&recorder)) == run(None));
+		assert!(recorder.count.load(Ordering::Relaxed) == n.div_ceil(bs));
+		assert!(recorder.done.load(Ordering::Relaxed) == n);
+
+		let recorder = Recorder::new(1);
+		assert!(run(Some(&recorder)).1);
+		assert!(recorder.count.load(Ordering::Relaxed) == 1);
+		assert!(recorder.done.load(Ordering::Relaxed) == bs);
+	}
+
+	#[test]
+	fn test_svd_progress() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n, n_stages) in [(60, 50, 3), (200, 40, 4), (40, 200, 4)] {
+			let A = Mat::<f64>::random_normal(rng, m, n);
+			let size = Ord::min(m, n);
+
+			let run = |progress: Option<&dyn ProgressMonitor>| {
+				let params = Spec::<SvdParams, f64>::default();
+				let mut s = Diag::<f64>::zeros(size);
+				let mut U = Mat::<f64>::zeros(m, size);
+				let mem = &mut MemBuffer::new(svd_scratch::<f64>(m, n, ComputeSvdVectors::Thin, ComputeSvdVectors::No, Par::Seq, params));
+				let stack = MemStack::new(mem);
+				match progress {
+					Some(progress) => svd_with_progress(A.as_ref(), s.as_mut(), Some(U.as_mut()), None, Par::Seq, stack, params, progress),
+					None => svd(A.as_ref(), s.as_mut(), Some(U.as_mut()), None, Par::Seq, stack, params),
+				}
+				.map(|()| (s, U))
+			};
+
+			let recorder = Recorder::new(usize::MAX);
+			assert!(run(Some(&recorder)).unwrap() == run(None).unwrap());
+			assert!(recorder.count.load(Ordering::Relaxed) == n_stages);
+			assert!(recorder.total.load(Ordering::Relaxed) == n_stages);
+
+			let recorder = Recorder::new(1);
+			assert!(run(Some(&recorder)) == Err(SvdError::Cancelled));
+			assert!(recorder.count.load(Ordering::Relaxed) == 1);
+		}
+	}
+}
@@ -0,0 +1,173 @@
+//! projections onto the orthogonal and positive semidefinite matrices
+//!
+//! these projections are the retractions used by optimization algorithms on the stiefel manifold
+//! and on the cone of positive semidefinite matrices, where they're applied once per iteration
+//!
+//! - [`project_to_orthogonal`] returns the matrix with orthonormal columns that is closest to $A$
+//! in the frobenius norm, which is the unitary factor $Q$ of the polar decomposition $A = Q H$.
+//! [`procrustes`] uses it to solve the orthogonal procrustes problem
+//! $\min_{Q^H Q = I} \|A Q - B\|_F$,
+//! - [`project_to_psd`] returns the positive semidefinite matrix that is closest to $A$ in the
+//! frobenius norm, which is obtained by clipping the negative eigenvalues of the self-adjoint part
+//! of $A$ to zero
+//!
+//! # example
+//! ```
+//! use faer::linalg::projection::{procrustes, project_to_orthogonal, project_to_psd};
+//! use faer::{Mat, mat};
+//!
+//! // a rotation, perturbed by some noise
+//! let (c, s) = (0.6, 0.8);
+//! let A = mat![[c + 0.01, -s], [s, c - 0.02]];
+//! let Q = project_to_orthogonal(A.as_ref()).unwrap();
+//! assert!((Q.transpose() * &Q - Mat::<f64>::identity(2, 2)).norm_max() < 1e-14);
+//!
+//! // recover the rotation that maps the points of `A` onto the ones of `B`
+//! let R = mat![[c, -s], [s, c]];
+//! let A = mat![[1.0, 2.0], [3.0, -1.0], [0.5, 0.5]];
+//! let B = &A * &R;
+//! let Q = procrustes(A.as_ref(), B.as_ref()).unwrap();
+//! assert!((&Q - &R).norm_max() < 1e-12);
+//!
+//! // clip the negative eigenvalue
+//! let A = mat![[1.0, 2.0], [2.0, 1.0]];
+//! let P = project_to_psd(A.as_ref()).unwrap();
+//! assert!((&P - mat![[1.5, 1.5], [1.5, 1.5]]).norm_max() < 1e-14);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::linalg::solvers::{EvdError, SelfAdjointEigen, Svd, SvdError};
+
+/// returns the nearest matrix to $A$ with orthonormal columns, or orthonormal rows if $A$ is wide,
+/// in the frobenius norm
+///
+/// this is the unitary factor $Q = U V^H$ of the polar decomposition $A = Q H$, where
+/// $A = U S V^H$ is the thin singular value decomposition of $A$. the result is unique if $A$ has
+/// full rank
+pub fn project_to_orthogonal<T: ComplexField>(A: MatRef<'_, impl Conjugate<Canonical = T>>) -> Result<Mat<T>, SvdError> {
+	let svd = Svd::new_thin(A)?;
+	Ok(svd.U() * svd.V().adjoint())
+}
+
+/// returns the matrix $Q$ with orthonormal columns that minimizes $\|A Q - B\|_F$
+///
+/// the solution is the projection of $A^H B$ onto the orthogonal matrices
+///
+/// # panics
+///
+/// panics if $A$ and $B$ don't have the same number of rows
+#[track_caller]
+pub fn procrustes<T: ComplexField>(
+	A: MatRef<'_, impl Conjugate<Canonical = T>>,
+	B: MatRef<'_, impl Conjugate<Canonical = T>>,
+) -> Result<Mat<T>, SvdError> {
+	assert!(A.nrows() == B.nrows());
+	project_to_orthogonal((A.adjoint() * B).as_ref())
+}
+
+/// returns the nearest positive semidefinite matrix to the square matrix $A$ in the frobenius norm
+///
+/// the result is computed from the eigendecomposition $U \Lambda U^H$ of the self-adjoint part
+/// $\frac{1}{2}(A + A^H)$, as $U \max(\Lambda, 0) U^H$
+///
+/// # panics
+///
+/// panics if $A$ is not square
+#[track_caller]
+#[math]
+pub fn project_to_psd<T: ComplexField>(A: MatRef<'_, impl Conjugate<Canonical = T>>) -> Result<Mat<T>, EvdError> {
+	let n = A.nrows();
+	assert!(A.ncols() == n);
+
+	let A = A.to_owned();
+	let half = from_f64::<T::Real>(0.5);
+	let H = Mat::<T>::from_fn(n, n, |i, j| mul_real(A[(i, j)] + conj(A[(j, i)]), half));
+
+	let evd = SelfAdjointEigen::new(H.as_ref(), Side::Lower)?;
+	let U = evd.U();
+	let S = evd.S().column_vector();
+
+	let mut US = U.to_owned();
+	US.as_mut()
+		.scale_cols(Row::<T>::from_fn(n, |i| from_real(max(real(S[i]), zero()))).as_ref());
+	Ok(US * U.adjoint())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_project_to_orthogonal() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(8, 8), (12, 5), (5, 12)] {
+			let A = Mat::<c64>::random_normal(rng, m, n);
+			let Q = project_to_orthogonal(A.as_ref()).unwrap();
+			let k = Ord::min(m, n);
+			if m >= n {
+				assert!((Q.adjoint() * &Q - Mat::<c64>::identity(k, k)).norm_max() < 1e-12);
+			} else {
+				assert!((&Q * Q.adjoint() - Mat::<c64>::identity(k, k)).norm_max() < 1e-12);
+			}
+
+			// Q^H A is the self-adjoint positive semidefinite factor of the polar decomposition
+			if m >= n {
+				let H = Q.adjoint() * &A;
+				assert!((&H - H.adjoint()).norm_max() < 1e-12);
+				assert!(H.self_adjoint_eigenvalues(Side::Lower).unwrap().iter().all(|&x| x > 0.0));
+			}
+
+			// the result is closer to A than other random orthogonal matrices
+			let dist = (&A - &Q).norm_l2();
+			for _ in 0..10 {
+				let P = project_to_orthogonal(Mat::<c64>::random_normal(rng, m, n).as_ref()).unwrap();
+				assert!(dist <= (&A - &P).norm_l2());
+			}
+
+			// conjugated input
+			let Qc = project_to_orthogonal(A.conjugate()).unwrap();
+			assert!((&Qc - Q.conjugate()).norm_max() < 1e-12);
+		}
+
+		let (m, n) = (20, 4);
+		let A = Mat::<c64>::random_normal(rng, m, n);
+		let R = project_to_orthogonal(Mat::<c64>::random_normal(rng, n, n).as_ref()).unwrap();
+		let B = &A * &R + Mat::<c64>::random_normal(rng, m, n) * Scale(c64::new(1e-3, 0.0));
+		let Q = procrustes(A.as_ref(), B.as_ref()).unwrap();
+		assert!((&Q - &R).norm_max() < 1e-2);
+		assert!((Q.adjoint() * &Q - Mat::<c64>::identity(n, n)).norm_max() < 1e-12);
+	}
+
+	#[test]
+	fn test_project_to_psd() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 10;
+
+		let A = Mat::<c64>::random_normal(rng, n, n);
+		let P = project_to_psd(A.as_ref()).unwrap();
+		assert!((&P - P.adjoint()).norm_max() < 1e-12);
+
+		let eigs = P.self_adjoint_eigenvalues(Side::Lower).unwrap();
+		assert!(eigs.iter().all(|&x| x > -1e-12));
+		assert!(eigs.iter().any(|&x| x.abs() < 1e-12));
+
+		// projecting twice doesn't change the result
+		let PP = project_to_psd(P.as_ref()).unwrap();
+		assert!((&PP - &P).norm_max() < 1e-12);
+
+		// the residual is orthogonal to the projection, and negative semidefinite
+		let H = (&A + A.adjoint()) * Scale(c64::new(0.5, 0.0));
+		let R = &H - &P;
+		let inner = (0..n).map(|j| (R.col(j).adjoint() * P.col(j)).re).sum::<f64>();
+		assert!(inner.abs() < 1e-10);
+		assert!(R.self_adjoint_eigenvalues(Side::Lower).unwrap().iter().all(|&x| x < 1e-12));
+
+		// conjugated input
+		let Pc = project_to_psd(A.conjugate()).unwrap();
+		assert!((&Pc - P.conjugate()).norm_max() < 1e-12);
+	}
+}
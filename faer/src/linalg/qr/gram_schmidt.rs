@@ -0,0 +1,355 @@
+//! gram-schmidt orthonormalization
+//!
+//! the gram-schmidt process computes a thin $QR$ decomposition $A = QR$ by orthogonalizing each
+//! column of $A$ against the previous ones. unlike the householder $QR$ decomposition, $Q$ is formed
+//! explicitly, and new columns can be appended cheaply, which makes it the method of choice for
+//! building krylov bases
+//!
+//! - the classical variant computes the projection of a column on all the previous ones with a
+//!   single matrix product, which is the fastest, but loses orthogonality quickly when $A$ is ill
+//!   conditioned,
+//! - the modified variant projects out the previous columns one at a time, which is more stable
+//!   but only uses matrix-vector products,
+//! - the block variant orthogonalizes a panel of columns against the previous ones with matrix
+//!   products, then orthogonalizes the panel internally with the classical variant.
+//!
+//! with reorthogonalization enabled, each projection is repeated twice, which yields a $Q$ that is
+//! orthonormal to working precision for all three variants, as long as $A$ is numerically full rank
+//!
+//! # example
+//! ```
+//! use faer::dyn_stack::{MemBuffer, MemStack};
+//! use faer::linalg::qr::gram_schmidt::{self, GramSchmidtParams};
+//! use faer::{Mat, Par, mat};
+//!
+//! let A = mat![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]];
+//!
+//! let mut Q = A.clone();
+//! let mut R = Mat::<f64>::zeros(2, 2);
+//! let params = GramSchmidtParams::default();
+//! gram_schmidt::gram_schmidt_in_place(
+//!     Q.as_mut(),
+//!     R.as_mut(),
+//!     Par::Seq,
+//!     MemStack::new(&mut MemBuffer::new(gram_schmidt::gram_schmidt_in_place_scratch::<f64>(3, 2, params))),
+//!     params,
+//! );
+//!
+//! assert!((&Q * &R - &A).norm_max() < 1e-14);
+//! assert!((Q.adjoint() * &Q - Mat::<f64>::identity(2, 2)).norm_max() < 1e-14);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use linalg::matmul::matmul;
+
+/// gram-schmidt variant
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GramSchmidtKind {
+	/// classical gram-schmidt, projecting out all the previous columns at once
+	Classical,
+	/// modified gram-schmidt, projecting out the previous columns one at a time
+	Modified,
+	/// block classical gram-schmidt, processing `blocksize` columns at a time
+	Block {
+		/// number of columns in each panel
+		blocksize: usize,
+	},
+}
+
+/// gram-schmidt parameters
+#[derive(Copy, Clone, Debug)]
+pub struct GramSchmidtParams {
+	/// gram-schmidt variant
+	pub kind: GramSchmidtKind,
+	/// whether each projection should be repeated a second time, to recover the orthogonality lost
+	/// to rounding errors
+	pub reorthogonalize: bool,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for GramSchmidtParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			kind: GramSchmidtKind::Classical,
+			reorthogonalize: true,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+impl GramSchmidtKind {
+	#[inline]
+	fn blocksize(self) -> usize {
+		match self {
+			GramSchmidtKind::Block { blocksize } => Ord::max(blocksize, 1),
+			_ => 1,
+		}
+	}
+}
+
+/// computes $D = Q^H X$, then $X \gets X - Q D$ and $C \gets C + D$
+fn project_out<T: ComplexField>(Q: MatRef<'_, T>, X: MatMut<'_, T>, C: MatMut<'_, T>, modified: bool, par: Par, stack: &mut MemStack) {
+	let mut X = X;
+	let mut C = C;
+	let k = Q.ncols();
+
+	let (mut D, _) = unsafe { temp_mat_uninit::<T, _, _>(k, X.ncols(), stack) };
+	let mut D = D.as_mat_mut();
+
+	if modified {
+		for i in 0..k {
+			let q = Q.subcols(i, 1);
+			let mut d = D.rb_mut().subrows_mut(i, 1);
+			matmul(d.rb_mut(), Accum::Replace, q.adjoint(), X.rb(), one(), par);
+			matmul(X.rb_mut(), Accum::Add, q, d.rb(), neg(&one::<T>()), par);
+		}
+	} else {
+		matmul(D.rb_mut(), Accum::Replace, Q.adjoint(), X.rb(), one(), par);
+		matmul(X.rb_mut(), Accum::Add, Q, D.rb(), neg(&one::<T>()), par);
+	}
+
+	C += D.rb();
+}
+
+/// computes the size and alignment of the workspace required to orthonormalize a matrix with
+/// [`gram_schmidt_in_place`]
+pub fn gram_schmidt_in_place_scratch<T: ComplexField>(nrows: usize, ncols: usize, params: GramSchmidtParams) -> StackReq {
+	_ = nrows;
+	let bs = Ord::min(params.kind.blocksize(), ncols);
+	StackReq::all_of(&[temp_mat_scratch::<T>(bs, 1), temp_mat_scratch::<T>(ncols, bs)])
+}
+
+/// computes the size and alignment of the workspace required to orthogonalize a matrix against an
+/// orthonormal basis with [`orthogonalize_against_in_place`]
+pub fn orthogonalize_against_in_place_scratch<T: ComplexField>(basis_ncols: usize, rhs_ncols: usize) -> StackReq {
+	temp_mat_scratch::<T>(basis_ncols, rhs_ncols)
+}
+
+/// computes the thin $QR$ decomposition of `A` with the gram-schmidt process. $Q$ is stored in `A`
+/// and $R$ in `R`
+///
+/// `R` must be a square matrix with as many columns as `A`. its strictly lower triangular half is
+/// set to zero
+///
+/// a column whose norm after orthogonalization is at most $\max(m, n) \varepsilon$ times its
+/// original norm is treated as linearly dependent on the previous ones. its column in $Q$ and the
+/// corresponding diagonal entry of $R$ are then set to zero, so that $A = QR$ still holds
+#[track_caller]
+pub fn gram_schmidt_in_place<T: ComplexField>(A: MatMut<'_, T>, R: MatMut<'_, T>, par: Par, stack: &mut MemStack, params: GramSchmidtParams) {
+	let (m, n) = A.shape();
+	assert!(all(R.nrows() == n, R.ncols() == n));
+
+	let mut A = A;
+	let mut R = R;
+	R.fill(zero());
+
+	let passes = if params.reorthogonalize { 2 } else { 1 };
+	let modified = params.kind == GramSchmidtKind::Modified;
+	let bs = params.kind.blocksize();
+	let tol = mul(&eps::<T::Real>(), &from_f64::<T::Real>(Ord::max(m, n) as f64));
+
+	let mut j = 0;
+	while j < n {
+		let w = Ord::min(bs, n - j);
+		let (Q, X) = A.rb_mut().split_at_col_mut(j);
+		let mut X = X.subcols_mut(0, w);
+
+		let (mut norms, stack) = unsafe { temp_mat_uninit::<T, _, _>(w, 1, stack) };
+		let mut norms = norms.as_mat_mut().col_mut(0);
+		for jj in 0..w {
+			norms[jj] = from_real(&X.rb().col(jj).norm_l2());
+		}
+
+		// orthogonalize the panel against the previous columns
+		if j > 0 {
+			for _ in 0..passes {
+				project_out(Q.rb(), X.rb_mut(), R.rb_mut().submatrix_mut(0, j, j, w), modified, par, stack);
+			}
+		}
+
+		// then orthogonalize the panel internally
+		for jj in 0..w {
+			let col = j + jj;
+			let (Qb, x) = X.rb_mut().split_at_col_mut(jj);
+			let mut x = x.subcols_mut(0, 1);
+
+			if jj > 0 {
+				for _ in 0..passes {
+					project_out(Qb.rb(), x.rb_mut(), R.rb_mut().submatrix_mut(j, col, jj, 1), modified, par, stack);
+				}
+			}
+
+			let norm = x.rb().col(0).norm_l2();
+			if norm > mul(&tol, &real(&norms[jj])) {
+				R[(col, col)] = from_real(&norm);
+				x *= Scale(from_real::<T>(&recip(&norm)));
+			} else {
+				R[(col, col)] = zero();
+				x.fill(zero());
+			}
+		}
+
+		j += w;
+	}
+}
+
+/// orthogonalizes the columns of `X` against the orthonormal columns of `Q`, and stores the
+/// coefficients $Q^H X$ of the removed components in `coeffs`, so that $X_{\text{old}} = Q \cdot
+/// \text{coeffs} + X_{\text{new}}$
+///
+/// this is the building block of the arnoldi process, where each new krylov vector is
+/// orthogonalized against the current basis. the block variant is treated as the classical one
+#[track_caller]
+pub fn orthogonalize_against_in_place<T: ComplexField>(
+	Q: MatRef<'_, T>,
+	X: MatMut<'_, T>,
+	coeffs: MatMut<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+	params: GramSchmidtParams,
+) {
+	assert!(all(X.nrows() == Q.nrows(), coeffs.nrows() == Q.ncols(), coeffs.ncols() == X.ncols()));
+
+	let mut X = X;
+	let mut coeffs = coeffs;
+	coeffs.fill(zero());
+
+	let passes = if params.reorthogonalize { 2 } else { 1 };
+	let modified = params.kind == GramSchmidtKind::Modified;
+	for _ in 0..passes {
+		project_out(Q, X.rb_mut(), coeffs.rb_mut(), modified, par, stack);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	fn gram_schmidt(A: MatRef<'_, c64>, params: GramSchmidtParams) -> (Mat<c64>, Mat<c64>) {
+		let (m, n) = A.shape();
+		let mut Q = A.to_owned();
+		let mut R = Mat::zeros(n, n);
+		gram_schmidt_in_place(
+			Q.as_mut(),
+			R.as_mut(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(gram_schmidt_in_place_scratch::<c64>(m, n, params))),
+			params,
+		);
+		(Q, R)
+	}
+
+	#[test]
+	fn test_gram_schmidt() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n) = (40, 13);
+
+		let A = CwiseMatDistribution {
+			nrows: m,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+		for kind in [
+			GramSchmidtKind::Classical,
+			GramSchmidtKind::Modified,
+			GramSchmidtKind::Block { blocksize: 4 },
+			GramSchmidtKind::Block { blocksize: 64 },
+		] {
+			for reorthogonalize in [false, true] {
+				let params = GramSchmidtParams {
+					kind,
+					reorthogonalize,
+					..Default::default()
+				};
+				let (Q, R) = gram_schmidt(A.as_ref(), params);
+
+				assert!(&Q * &R ~ A);
+				assert!(Q.adjoint() * &Q ~ Mat::<c64>::identity(n, n));
+				for j in 0..n {
+					for i in j + 1..n {
+						assert!(R[(i, j)] == c64::ZERO);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_gram_schmidt_rank_deficient() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n) = (20, 6);
+
+		let mut A = CwiseMatDistribution {
+			nrows: m,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		// the fourth column is a combination of the first two
+		let col = A.col(0) * Scale(c64::new(2.0, 1.0)) - A.col(1);
+		A.col_mut(3).copy_from(&col);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+		for kind in [
+			GramSchmidtKind::Classical,
+			GramSchmidtKind::Modified,
+			GramSchmidtKind::Block { blocksize: 4 },
+		] {
+			let params = GramSchmidtParams { kind, ..Default::default() };
+			let (Q, R) = gram_schmidt(A.as_ref(), params);
+
+			assert!(R[(3, 3)] == c64::ZERO);
+			assert!(Q.col(3).norm_l2() == 0.0);
+			assert!(&Q * &R ~ A);
+		}
+	}
+
+	#[test]
+	fn test_orthogonalize_against() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, k) = (30, 5);
+
+		let A = CwiseMatDistribution {
+			nrows: m,
+			ncols: k,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let X = CwiseMatDistribution {
+			nrows: m,
+			ncols: 2,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let (Q, _) = gram_schmidt(A.as_ref(), Default::default());
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+		for kind in [GramSchmidtKind::Classical, GramSchmidtKind::Modified] {
+			let params = GramSchmidtParams { kind, ..Default::default() };
+			let mut Y = X.clone();
+			let mut C = Mat::<c64>::zeros(k, 2);
+			orthogonalize_against_in_place(
+				Q.as_ref(),
+				Y.as_mut(),
+				C.as_mut(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(orthogonalize_against_in_place_scratch::<c64>(k, 2))),
+				params,
+			);
+
+			assert!(Q.adjoint() * &Y ~ Mat::<c64>::zeros(k, 2));
+			assert!(&Q * &C + &Y ~ X);
+		}
+	}
+}
@@ -124,6 +124,9 @@
 pub mod col_pivoting;
 pub mod no_pivoting;
 
+/// gram-schmidt orthonormalization
+pub mod gram_schmidt;
+
 #[cfg(test)]
 mod tests {
 	use crate as faer;
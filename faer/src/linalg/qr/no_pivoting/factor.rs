@@ -1,6 +1,7 @@
 use crate::assert;
 use crate::internal_prelude::*;
 use linalg::householder::{self};
+use linalg::progress::ProgressMonitor;
 
 // R R R R|R R R | A A A
 // H R R R|R R R | A A A
@@ -14,6 +15,9 @@ use linalg::householder::{self};
 pub struct QrInfo {
 	/// estimated rank of the matrix.
 	pub rank: usize,
+	/// whether the factorization was cancelled by the progress monitor, in which case the
+	/// contents of the output matrices are unspecified.
+	pub cancelled: bool,
 }
 
 #[math]
@@ -133,7 +137,8 @@ fn qr_in_place_blocked<T: ComplexField>(
 	par: Par,
 	stack: &mut MemStack,
 	params: Spec<QrParams, T>,
-) -> usize {
+	progress: Option<&dyn ProgressMonitor>,
+) -> (usize, bool) {
 	let params = params.config;
 
 	let (m, n) = A.shape();
@@ -143,7 +148,7 @@ fn qr_in_place_blocked<T: ComplexField>(
 	assert!(blocksize > 0);
 
 	if blocksize == 1 {
-		return qr_in_place_unblocked(A, H.row_mut(0), row_start, col_start);
+		return (qr_in_place_unblocked(A, H.row_mut(0), row_start, col_start), false);
 	}
 	let sub_blocksize = if m * n < params.blocking_threshold { 1 } else { blocksize / 2 };
 
@@ -156,6 +161,7 @@ fn qr_in_place_blocked<T: ComplexField>(
 	while row < size && col < n {
 		let blocksize = Ord::min(blocksize, Ord::min(size - row, n - col));
 		let sub_blocksize = Ord::min(blocksize, sub_blocksize);
+		crate::utils::trace::span!(DEBUG, "qr_panel", m = m - row, n = blocksize, col = col);
 
 		let mut A = A.rb_mut();
 		let mut H = H.rb_mut();
@@ -183,7 +189,7 @@ fn qr_in_place_blocked<T: ComplexField>(
 			let blocksize = Ord::min(n - col, blocksize - offset);
 			let sub_blocksize = Ord::min(blocksize, sub_blocksize);
 
-			let new_row = qr_in_place_blocked(
+			let (new_row, _) = qr_in_place_blocked(
 				A.rb_mut().subcols_mut(0, col + blocksize),
 				H.rb_mut().subrows_mut(offset, sub_blocksize),
 				row,
@@ -191,6 +197,8 @@ fn qr_in_place_blocked<T: ComplexField>(
 				par,
 				stack,
 				params.into(),
+				// only the outermost level reports its progress
+				None,
 			);
 			let local = new_row - row;
 
@@ -262,12 +270,45 @@ fn qr_in_place_blocked<T: ComplexField>(
 			row += local;
 			col += blocksize;
 		}
+
+		if linalg::progress::report(progress, col, n) {
+			return (row, true);
+		}
 	}
-	row
+	(row, false)
 }
 
 #[track_caller]
 pub fn qr_in_place<T: ComplexField>(A: MatMut<'_, T>, Q_coeff: MatMut<'_, T>, par: Par, stack: &mut MemStack, params: Spec<QrParams, T>) -> QrInfo {
+	qr_in_place_imp(A, Q_coeff, par, stack, params, None)
+}
+
+/// computes the $QR$ factorization like [`qr_in_place`], reporting its progress to `progress`
+/// after each block of columns
+///
+/// if the monitor cancels the factorization, the `cancelled` flag of the returned info is set and
+/// the contents of `A` and `Q_coeff` are unspecified
+#[track_caller]
+pub fn qr_in_place_with_progress<T: ComplexField>(
+	A: MatMut<'_, T>,
+	Q_coeff: MatMut<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<QrParams, T>,
+	progress: &dyn ProgressMonitor,
+) -> QrInfo {
+	qr_in_place_imp(A, Q_coeff, par, stack, params, Some(progress))
+}
+
+#[track_caller]
+fn qr_in_place_imp<T: ComplexField>(
+	A: MatMut<'_, T>,
+	Q_coeff: MatMut<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<QrParams, T>,
+	progress: Option<&dyn ProgressMonitor>,
+) -> QrInfo {
 	let blocksize = Q_coeff.nrows();
 	assert!(all(blocksize > 0, Q_coeff.ncols() == Ord::min(A.nrows(), A.ncols()),));
 
@@ -280,8 +321,13 @@ pub fn qr_in_place<T: ComplexField>(A: MatMut<'_, T>, Q_coeff: MatMut<'_, T>, pa
 		}
 	}
 
+	crate::utils::trace::span!(DEBUG, "qr", m = A.nrows(), n = A.ncols(), blocksize = blocksize);
+
 	let mut Q_coeff = Q_coeff;
-	let rank = qr_in_place_blocked(A, Q_coeff.rb_mut(), 0, 0, par, stack, params);
+	let (rank, cancelled) = qr_in_place_blocked(A, Q_coeff.rb_mut(), 0, 0, par, stack, params, progress);
+	if cancelled {
+		return QrInfo { rank, cancelled };
+	}
 	Q_coeff.rb_mut().get_mut(.., rank..).fill(zero());
 
 	let mut col = rank / blocksize * blocksize;
@@ -300,7 +346,7 @@ pub fn qr_in_place<T: ComplexField>(A: MatMut<'_, T>, Q_coeff: MatMut<'_, T>, pa
 		col += blocksize;
 	}
 
-	QrInfo { rank }
+	QrInfo { rank, cancelled }
 }
 
 /// computes the size and alignment of required workspace for performing a qr
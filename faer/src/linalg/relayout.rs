@@ -0,0 +1,227 @@
+//! conversions between column-major and row-major storage
+//!
+//! most of the dense kernels in `faer` (matrix multiplication, factorizations, triangular solves)
+//! are written for column-major matrices, and accept other layouts by working on strided data,
+//! which is noticeably slower. when the same matrix is used as the input of many kernels, it can be
+//! worth copying it once into the preferred layout with [`relayout_copy`], or with
+//! [`MatRef::to_col_major`](crate::MatRef::to_col_major) and
+//! [`MatRef::to_row_major`](crate::MatRef::to_row_major)
+//!
+//! # example
+//! ```
+//! use faer::linalg::relayout::{Layout, has_layout};
+//! use faer::mat;
+//!
+//! let A = mat![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+//! let At = A.transpose();
+//! assert!(has_layout(At, Layout::RowMajor));
+//!
+//! // copy the row-major view into a column-major matrix
+//! let B = At.to_col_major();
+//! assert!(has_layout(B.as_ref(), Layout::ColMajor));
+//! assert!(B == At);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+
+/// storage order of a matrix
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Layout {
+	/// elements of each column are contiguous in memory, i.e., the row stride is `1`
+	ColMajor,
+	/// elements of each row are contiguous in memory, i.e., the column stride is `1`
+	RowMajor,
+}
+
+impl Layout {
+	/// returns the storage order of `mat`, or `None` if neither its rows nor its columns are
+	/// contiguous
+	///
+	/// if both strides are `1`, the matrix is reported as column-major
+	#[inline]
+	pub fn of<T>(mat: MatRef<'_, T>) -> Option<Layout> {
+		if mat.row_stride() == 1 {
+			Some(Layout::ColMajor)
+		} else if mat.col_stride() == 1 {
+			Some(Layout::RowMajor)
+		} else {
+			None
+		}
+	}
+}
+
+/// returns `true` if `mat` is stored with the given layout
+///
+/// each column of a matrix with a single row is trivially contiguous, so such a matrix is
+/// considered column-major regardless of its strides. similarly, a matrix with a single column is
+/// always considered row-major
+#[inline]
+pub fn has_layout<T>(mat: MatRef<'_, T>, layout: Layout) -> bool {
+	match layout {
+		Layout::ColMajor => mat.row_stride() == 1 || mat.nrows() <= 1,
+		Layout::RowMajor => mat.col_stride() == 1 || mat.ncols() <= 1,
+	}
+}
+
+/// size of the square tiles used by the blocked copy, chosen so that a tile of `src` and a tile of
+/// `dst` both fit comfortably in the l1 cache
+#[inline]
+fn block_size<T>() -> usize {
+	Ord::clamp(256 / Ord::max(core::mem::size_of::<T>(), 1), 4, 64)
+}
+
+/// copies `src` into `dst`, where the two matrices may have different storage orders
+///
+/// when one matrix is column-major and the other is row-major, the copy is performed in square
+/// tiles, so that the strided accesses of each tile stay in cache. otherwise this is equivalent to
+/// [`MatMut::copy_from`]
+///
+/// # panics
+///
+/// panics if `dst` and `src` don't have the same shape
+#[track_caller]
+pub fn relayout_copy<T: ComplexField>(dst: MatMut<'_, T>, src: MatRef<'_, impl Conjugate<Canonical = T>>) {
+	let mut dst = dst;
+	let mut src = src;
+	assert!(all(dst.nrows() == src.nrows(), dst.ncols() == src.ncols()));
+
+	// make `dst` column-major when possible, so that the tiles are traversed along its columns
+	if dst.col_stride().unsigned_abs() < dst.row_stride().unsigned_abs() {
+		dst = dst.transpose_mut();
+		src = src.transpose();
+	}
+
+	let (m, n) = dst.shape();
+	let transposed = src.row_stride().unsigned_abs() > src.col_stride().unsigned_abs();
+	if !transposed || m <= 1 || n <= 1 {
+		dst.copy_from(src);
+		return;
+	}
+
+	let bs = block_size::<T>();
+	let mut j = 0;
+	while j < n {
+		let bn = Ord::min(bs, n - j);
+		let mut i = 0;
+		while i < m {
+			let bm = Ord::min(bs, m - i);
+			dst.rb_mut().submatrix_mut(i, j, bm, bn).copy_from(src.submatrix(i, j, bm, bn));
+			i += bm;
+		}
+		j += bn;
+	}
+}
+
+/// transposes the square matrix `mat` in place
+///
+/// this converts the storage of `mat` between column-major and row-major: after the call,
+/// `mat.transpose()` holds the original values
+///
+/// # panics
+///
+/// panics if `mat` is not square
+#[track_caller]
+pub fn transpose_in_place<T>(mat: MatMut<'_, T>) {
+	assert!(mat.nrows() == mat.ncols());
+	let n = mat.nrows();
+	let bs = block_size::<T>();
+
+	// swaps the element at `(i, j)` with the one at `(j, i)`, for `i != j`
+	let swap = |i: usize, j: usize| unsafe { core::ptr::swap(mat.ptr_inbounds_at_mut(i, j), mat.ptr_inbounds_at_mut(j, i)) };
+
+	let mut jb = 0;
+	while jb < n {
+		let bn = Ord::min(bs, n - jb);
+
+		// diagonal tile
+		for j in jb..jb + bn {
+			for i in j + 1..jb + bn {
+				swap(i, j);
+			}
+		}
+
+		// off-diagonal tiles below the diagonal tile, swapped with their mirror images
+		let mut ib = jb + bn;
+		while ib < n {
+			let bm = Ord::min(bs, n - ib);
+			for j in jb..jb + bn {
+				for i in ib..ib + bm {
+					swap(i, j);
+				}
+			}
+			ib += bm;
+		}
+		jb += bn;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_relayout_copy() {
+		for (m, n) in [(0, 3), (1, 7), (7, 1), (5, 5), (70, 33), (129, 260)] {
+			let A = Mat::from_fn(m, n, |i, j| c64::new(i as f64, j as f64));
+
+			let row_major = A.transpose().to_owned();
+			let row_major = row_major.transpose();
+			if m > 1 && n > 1 {
+				assert!(Layout::of(row_major) == Some(Layout::RowMajor));
+			}
+
+			let mut dst = Mat::zeros(m, n);
+			relayout_copy(dst.as_mut(), row_major);
+			assert!(dst == A);
+
+			relayout_copy(dst.as_mut(), row_major.conjugate());
+			assert!(dst == Mat::from_fn(m, n, |i, j| A[(i, j)].conj()));
+
+			let mut dst = Mat::zeros(n, m);
+			relayout_copy(dst.as_mut().transpose_mut(), A.as_ref());
+			assert!(dst.transpose() == A);
+
+			let B = row_major.to_col_major();
+			assert!(B == A);
+			assert!(has_layout(B.as_ref(), Layout::ColMajor));
+
+			let B = A.to_row_major();
+			assert!(B.transpose() == A);
+			assert!(has_layout(B.transpose(), Layout::RowMajor));
+		}
+	}
+
+	#[test]
+	fn test_transpose_in_place() {
+		for n in [0, 1, 2, 31, 32, 33, 100] {
+			let A = Mat::from_fn(n, n, |i, j| (i * n + j) as f64);
+			let mut B = A.clone();
+			transpose_in_place(B.as_mut());
+			assert!(B.transpose() == A);
+
+			// strided view
+			let mut C = Mat::<f64>::zeros(2 * n, n);
+			let mut view = C.as_mut().get_mut((0..2 * n).step_by(2), ..);
+			view.copy_from(&A);
+			transpose_in_place(view.rb_mut());
+			assert!(view.transpose() == A);
+		}
+	}
+
+	#[test]
+	fn test_layout() {
+		let A = Mat::<f64>::zeros(3, 4);
+		assert!(Layout::of(A.as_ref()) == Some(Layout::ColMajor));
+		assert!(Layout::of(A.transpose()) == Some(Layout::RowMajor));
+		assert!(Layout::of(A.as_ref().get((0..3).step_by(2), (0..4).step_by(2))) == None);
+
+		assert!(has_layout(A.as_ref(), Layout::ColMajor));
+		assert!(!has_layout(A.as_ref(), Layout::RowMajor));
+		assert!(!has_layout(A.row(0).as_mat(), Layout::RowMajor));
+		assert!(has_layout(A.row(0).as_mat(), Layout::ColMajor));
+		assert!(has_layout(A.col(0).as_mat(), Layout::ColMajor));
+		assert!(has_layout(A.col(0).as_mat(), Layout::RowMajor));
+	}
+}
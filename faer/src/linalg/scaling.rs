@@ -0,0 +1,101 @@
+use crate::assert;
+use crate::internal_prelude::*;
+
+/// multiplies the $i$-th row of `mat` by `d[i]`, i.e., computes $D A$ in place
+///
+/// # panics
+///
+/// panics if `d` doesn't have the same number of rows as `mat`
+#[track_caller]
+pub fn scale_rows_in_place<T: ComplexField>(mat: MatMut<'_, T>, d: DiagRef<'_, impl Conjugate<Canonical = T>>) {
+	let mut mat = mat;
+	mat.scale_rows(d.column_vector());
+}
+
+/// multiplies the $j$-th column of `mat` by `d[j]`, i.e., computes $A D$ in place
+///
+/// # panics
+///
+/// panics if `d` doesn't have the same number of columns as `mat`
+#[track_caller]
+pub fn scale_cols_in_place<T: ComplexField>(mat: MatMut<'_, T>, d: DiagRef<'_, impl Conjugate<Canonical = T>>) {
+	let mut mat = mat;
+	mat.scale_cols(d.column_vector().transpose());
+}
+
+/// computes the diagonal congruence $D A D$ and stores the result in `dst`, with a single pass over
+/// the matrix
+///
+/// this is the scaling used by symmetric equilibration, jacobi preconditioning and the normalized
+/// laplacian $D^{-1/2} A D^{-1/2}$. `d` is not conjugated, so the result is self-adjoint if $A$ is
+/// self-adjoint and $D$ is real
+///
+/// # panics
+///
+/// panics if `a` is not square, or if `dst` or `d` don't have the same dimensions as `a`
+///
+/// # example
+///
+/// ```
+/// use faer::linalg::scaling::diag_congruence;
+/// use faer::{Col, Mat, mat};
+///
+/// let a = mat![[4.0, 2.0], [2.0, 9.0f64]];
+/// // inverse square roots of the diagonal entries
+/// let d = Col::from_fn(2, |i| 1.0 / a[(i, i)].sqrt());
+///
+/// let mut dst = Mat::zeros(2, 2);
+/// diag_congruence(dst.as_mut(), d.as_diagonal(), a.as_ref());
+/// assert!(dst == mat![[1.0, 1.0 / 3.0], [1.0 / 3.0, 1.0]]);
+/// ```
+#[track_caller]
+#[math]
+pub fn diag_congruence<T: ComplexField>(
+	dst: MatMut<'_, T>,
+	d: DiagRef<'_, impl Conjugate<Canonical = T>>,
+	a: MatRef<'_, impl Conjugate<Canonical = T>>,
+) {
+	let n = a.nrows();
+	assert!(all(a.ncols() == n, d.dim() == n, dst.nrows() == n, dst.ncols() == n));
+
+	let mut dst = dst;
+	let d = d.column_vector();
+	for j in 0..n {
+		let dj = Conj::apply(&d[j]);
+		zip!(dst.rb_mut().col_mut(j), a.col(j), d).for_each(|unzip!(dst, a, di)| *dst = Conj::apply(di) * Conj::apply(a) * dj);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{assert, c64};
+	use crate::stats::prelude::*;
+
+	#[test]
+	fn test_scaling() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 7;
+
+		let a = Mat::<c64>::random_normal(rng, n, n);
+		let d = Col::<c64>::random_normal(rng, n);
+		let D = d.as_diagonal();
+
+		let mut dst = Mat::zeros(n, n);
+		diag_congruence(dst.as_mut(), D, a.as_ref());
+		assert!(dst == Mat::from_fn(n, n, |i, j| d[i] * a[(i, j)] * d[j]));
+
+		// conjugated inputs
+		diag_congruence(dst.as_mut(), d.conjugate().as_diagonal(), a.adjoint());
+		assert!(dst == Mat::from_fn(n, n, |i, j| d[i].conj() * a[(j, i)].conj() * d[j].conj()));
+
+		let b = Mat::<c64>::random_normal(rng, n, 3);
+		let mut out = b.clone();
+		scale_rows_in_place(out.as_mut(), D);
+		assert!(out == Mat::from_fn(n, 3, |i, j| b[(i, j)] * d[i]));
+
+		let mut out = b.transpose().to_owned();
+		scale_cols_in_place(out.as_mut(), D);
+		assert!(out == Mat::from_fn(3, n, |i, j| b[(j, i)] * d[j]));
+	}
+}
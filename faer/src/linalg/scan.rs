@@ -0,0 +1,180 @@
+//! cumulative sums and products along an axis
+//!
+//! the scans are computed in place. [`cumsum_rows`] replaces each row by the sum of itself and the
+//! rows above it, and [`cumsum_cols`] replaces each column by the sum of itself and the columns to
+//! its left, matching the naming of [`col_mean`](crate::stats::col_mean) and
+//! [`row_mean`](crate::stats::row_mean)
+//!
+//! when the scanned rows or columns are contiguous in memory, they're combined with vectorized
+//! elementwise operations. with a parallel [`Par`], independent lanes are split across threads, and
+//! a single long scan is split into blocks that are scanned concurrently, then offset by the
+//! running total of the previous blocks
+//!
+//! # example
+//! ```
+//! use faer::linalg::scan::{cumsum_cols, cumsum_rows};
+//! use faer::{Par, mat};
+//!
+//! // integral image, where each entry is the sum of the entries above and to the left of it
+//! let mut a = mat![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+//! cumsum_rows(a.as_mut(), Par::Seq);
+//! cumsum_cols(a.as_mut(), Par::Seq);
+//!
+//! assert!(a == mat![[1.0, 3.0, 6.0], [5.0, 12.0, 21.0]]);
+//! ```
+
+use crate::internal_prelude::*;
+
+#[derive(Copy, Clone, Debug)]
+enum ScanOp {
+	Sum,
+	Prod,
+}
+
+impl ScanOp {
+	#[inline(always)]
+	#[math]
+	fn apply<T: ComplexField>(self, acc: &T, x: &T) -> T {
+		match self {
+			ScanOp::Sum => *acc + *x,
+			ScanOp::Prod => *acc * *x,
+		}
+	}
+}
+
+/// scans the rows of `mat` sequentially
+fn scan_rows_seq<T: ComplexField>(mat: MatMut<'_, T>, op: ScanOp) {
+	let mut mat = mat;
+	let (m, n) = mat.shape();
+
+	if n > 1 && mat.col_stride().unsigned_abs() == 1 {
+		// contiguous rows, each row is combined with the previous one
+		for i in 1..m {
+			let (top, bot) = mat.rb_mut().split_at_row_mut(i);
+			zip!(bot.row_mut(0), top.rb().row(i - 1)).for_each(|unzip!(x, acc)| *x = op.apply(acc, x));
+		}
+	} else {
+		for j in 0..n {
+			let mut col = mat.rb_mut().col_mut(j);
+			for i in 1..m {
+				let x = op.apply(&col[i - 1], &col[i]);
+				col[i] = x;
+			}
+		}
+	}
+}
+
+fn scan_rows<T: ComplexField>(mat: MatMut<'_, T>, op: ScanOp, par: Par) {
+	match par {
+		Par::Seq => scan_rows_seq(mat, op),
+		#[cfg(feature = "rayon")]
+		Par::Rayon(nthreads) => {
+			use rayon::prelude::*;
+			let nthreads = nthreads.get();
+			let (m, n) = mat.shape();
+
+			if n >= nthreads {
+				// the columns are independent
+				mat.par_col_partition_mut(nthreads).for_each(|mat| scan_rows_seq(mat, op));
+			} else if m < 4 * nthreads {
+				scan_rows_seq(mat, op);
+			} else {
+				let mut mat = mat;
+				let blocksize = m.div_ceil(nthreads);
+				let nblocks = m.div_ceil(blocksize);
+
+				// scan each block independently
+				mat.rb_mut().par_row_chunks_mut(blocksize).for_each(|block| scan_rows_seq(block, op));
+
+				// propagate the totals of the blocks sequentially, through their last rows
+				let mut carries = Mat::<T>::zeros(nblocks, n);
+				for b in 1..nblocks {
+					let prev = b * blocksize - 1;
+					let last = Ord::min((b + 1) * blocksize, m) - 1;
+					for j in 0..n {
+						let x = op.apply(&mat[(prev, j)], &mat[(last, j)]);
+						mat[(last, j)] = x;
+					}
+					carries.row_mut(b).copy_from(mat.rb().row(prev));
+				}
+
+				// offset the remaining rows of each block
+				mat.par_row_chunks_mut(blocksize).enumerate().skip(1).for_each(|(b, block)| {
+					let nrows = block.nrows();
+					let (mut head, _) = block.split_at_row_mut(nrows - 1);
+					for j in 0..n {
+						let carry = &carries[(b, j)];
+						zip!(head.rb_mut().col_mut(j)).for_each(|unzip!(x)| *x = op.apply(carry, x));
+					}
+				});
+			}
+		},
+	}
+}
+
+/// replaces each row of `mat` by the sum of itself and all the rows above it
+pub fn cumsum_rows<T: ComplexField>(mat: MatMut<'_, T>, par: Par) {
+	scan_rows(mat, ScanOp::Sum, par);
+}
+
+/// replaces each column of `mat` by the sum of itself and all the columns to its left
+pub fn cumsum_cols<T: ComplexField>(mat: MatMut<'_, T>, par: Par) {
+	scan_rows(mat.transpose_mut(), ScanOp::Sum, par);
+}
+
+/// replaces each row of `mat` by the product of itself and all the rows above it
+pub fn cumprod_rows<T: ComplexField>(mat: MatMut<'_, T>, par: Par) {
+	scan_rows(mat, ScanOp::Prod, par);
+}
+
+/// replaces each column of `mat` by the product of itself and all the columns to its left
+pub fn cumprod_cols<T: ComplexField>(mat: MatMut<'_, T>, par: Par) {
+	scan_rows(mat.transpose_mut(), ScanOp::Prod, par);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_scan() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+
+		for (m, n) in [(0, 3), (1, 1), (7, 1), (1, 9), (100, 3), (5, 40), (33, 17)] {
+			let A = Mat::<c64>::random_normal(rng, m, n);
+
+			let sum_rows = Mat::from_fn(m, n, |i, j| (0..=i).map(|k| A[(k, j)]).sum::<c64>());
+			let sum_cols = Mat::from_fn(m, n, |i, j| (0..=j).map(|k| A[(i, k)]).sum::<c64>());
+			let prod_rows = Mat::from_fn(m, n, |i, j| (0..=i).map(|k| A[(k, j)]).product::<c64>());
+			let prod_cols = Mat::from_fn(m, n, |i, j| (0..=j).map(|k| A[(i, k)]).product::<c64>());
+
+			for par in [Par::Seq, Par::rayon(4)] {
+				// column-major and row-major storage
+				for row_major in [false, true] {
+					let mut storage = if row_major { A.transpose().to_owned() } else { A.clone() };
+					let mut B = if row_major { storage.as_mut().transpose_mut() } else { storage.as_mut() };
+
+					B.copy_from(&A);
+					cumsum_rows(B.rb_mut(), par);
+					assert!(B ~ sum_rows);
+
+					B.copy_from(&A);
+					cumsum_cols(B.rb_mut(), par);
+					assert!(B ~ sum_cols);
+
+					B.copy_from(&A);
+					cumprod_rows(B.rb_mut(), par);
+					assert!(B ~ prod_rows);
+
+					B.copy_from(&A);
+					cumprod_cols(B.rb_mut(), par);
+					assert!(B ~ prod_cols);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,222 @@
+//! thread-local workspace memory pool
+//!
+//! the high level api, such as [`Mat::solve_lu`](crate::Mat::solve_lu) or the decompositions in
+//! [`solvers`](crate::linalg::solvers), takes its workspace memory from a buffer owned by the
+//! current thread. the buffer grows to the largest requirement seen so far and is kept between
+//! calls, so that repeated calls in a loop don't allocate any workspace once it is large enough
+//!
+//! the same buffer can be borrowed explicitly with [`scratch`], to call the low level api without
+//! managing the memory by hand. if the buffer is already borrowed, e.g. by a caller further up the
+//! stack, a new buffer is allocated instead
+//!
+//! the memory held by the pool can be released with [`clear`], and its size bounded with
+//! [`set_capacity_limit`]. without the `std` feature, there is no pool and each call allocates a
+//! new buffer
+//!
+//! # example
+//! ```
+//! use faer::Par;
+//! use faer::linalg::cholesky::llt::factor::{cholesky_in_place, cholesky_in_place_scratch};
+//! use faer::linalg::scratch_pool;
+//!
+//! let n = 100;
+//! let mut A = faer::Mat::<f64>::identity(n, n);
+//!
+//! let mut mem = scratch_pool::scratch(cholesky_in_place_scratch::<f64>(
+//! 	n,
+//! 	Par::Seq,
+//! 	Default::default(),
+//! ));
+//! cholesky_in_place(
+//! 	A.as_mut(),
+//! 	Default::default(),
+//! 	Par::Seq,
+//! 	mem.stack(),
+//! 	Default::default(),
+//! )
+//! .unwrap();
+//! drop(mem);
+//!
+//! // the buffer was returned to the pool
+//! assert!(scratch_pool::pooled_bytes() > 0);
+//! scratch_pool::clear();
+//! assert!(scratch_pool::pooled_bytes() == 0);
+//! ```
+
+use dyn_stack::{MemBuffer, MemStack, StackReq};
+
+#[cfg(feature = "std")]
+std::thread_local! {
+	static POOL: core::cell::Cell<Option<(StackReq, MemBuffer)>> = const { core::cell::Cell::new(None) };
+}
+
+#[cfg(feature = "std")]
+static CAPACITY_LIMIT: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(usize::MAX);
+
+/// workspace memory taken from the thread-local pool, which is returned to it when dropped
+pub struct PooledBuffer {
+	req: StackReq,
+	buf: Option<MemBuffer>,
+}
+
+impl core::fmt::Debug for PooledBuffer {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("PooledBuffer").field("req", &self.req).finish()
+	}
+}
+
+impl PooledBuffer {
+	/// returns the memory stack backed by this buffer
+	#[inline]
+	pub fn stack(&mut self) -> &mut MemStack {
+		MemStack::new(self.buf.as_mut().unwrap())
+	}
+}
+
+/// takes a buffer that satisfies `req` from the thread-local pool, growing it if needed
+///
+/// if the pool is already in use, a new buffer is allocated
+#[track_caller]
+pub fn scratch(req: StackReq) -> PooledBuffer {
+	#[cfg(feature = "std")]
+	if let Some((pooled_req, buf)) = POOL.with(|pool| pool.take()) {
+		// the pooled buffer is large enough if taking the union with `req` doesn't change it
+		if pooled_req.or(req) == pooled_req {
+			return PooledBuffer {
+				req: pooled_req,
+				buf: Some(buf),
+			};
+		}
+		let req = pooled_req.or(req);
+		drop(buf);
+		return PooledBuffer {
+			req,
+			buf: Some(MemBuffer::new(req)),
+		};
+	}
+
+	PooledBuffer {
+		req,
+		buf: Some(MemBuffer::new(req)),
+	}
+}
+
+/// releases the memory held by the pool of the current thread
+pub fn clear() {
+	#[cfg(feature = "std")]
+	drop(POOL.with(|pool| pool.take()));
+}
+
+/// returns the size in bytes of the buffer held by the pool of the current thread
+///
+/// buffers that are currently borrowed are not counted
+pub fn pooled_bytes() -> usize {
+	#[cfg(feature = "std")]
+	{
+		POOL.with(|pool| {
+			let pooled = pool.take();
+			let bytes = pooled.as_ref().map(|(req, _)| req.unaligned_bytes_required()).unwrap_or(0);
+			pool.set(pooled);
+			bytes
+		})
+	}
+	#[cfg(not(feature = "std"))]
+	{
+		0
+	}
+}
+
+/// sets the largest buffer size in bytes that the pools keep between calls, for all threads
+///
+/// larger buffers are still allocated when needed, but are freed when they're returned instead of
+/// being kept. `None` removes the limit
+pub fn set_capacity_limit(limit: Option<usize>) {
+	#[cfg(feature = "std")]
+	CAPACITY_LIMIT.store(limit.unwrap_or(usize::MAX), core::sync::atomic::Ordering::Relaxed);
+	#[cfg(not(feature = "std"))]
+	let _ = limit;
+}
+
+/// returns the limit set by [`set_capacity_limit`]
+pub fn capacity_limit() -> Option<usize> {
+	#[cfg(feature = "std")]
+	{
+		match CAPACITY_LIMIT.load(core::sync::atomic::Ordering::Relaxed) {
+			usize::MAX => None,
+			limit => Some(limit),
+		}
+	}
+	#[cfg(not(feature = "std"))]
+	{
+		None
+	}
+}
+
+impl PooledBuffer {
+	/// returns the buffer to the pool, unless it is larger than `limit`
+	#[cfg(feature = "std")]
+	fn release(&mut self, limit: usize) {
+		let Some(buf) = self.buf.take() else { return };
+		let req = self.req;
+		if req.unaligned_bytes_required() > limit {
+			return;
+		}
+		// keep the largest of the two buffers if the pool was refilled in the meantime
+		let _ = POOL.try_with(|pool| match pool.take() {
+			Some((other_req, other)) if other_req.or(req) == other_req => pool.set(Some((other_req, other))),
+			_ => pool.set(Some((req, buf))),
+		});
+	}
+}
+
+impl Drop for PooledBuffer {
+	#[inline]
+	fn drop(&mut self) {
+		#[cfg(feature = "std")]
+		self.release(CAPACITY_LIMIT.load(core::sync::atomic::Ordering::Relaxed));
+	}
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+	use super::*;
+	use crate::assert;
+
+	#[test]
+	fn test_pool() {
+		clear();
+		let small = StackReq::new::<f64>(16);
+		let large = StackReq::new::<f64>(1024);
+
+		let ptr = scratch(large).stack().as_ptr();
+		assert!(pooled_bytes() == large.unaligned_bytes_required());
+
+		// smaller requests reuse the pooled buffer
+		for _ in 0..4 {
+			let mut mem = scratch(small);
+			assert!(mem.stack().as_ptr() == ptr);
+			assert!(pooled_bytes() == 0);
+
+			// the pool is in use, so a new buffer is allocated
+			let mut inner = scratch(small);
+			assert!(inner.stack().as_ptr() != ptr);
+		}
+		assert!(pooled_bytes() == large.unaligned_bytes_required());
+
+		// the pool keeps the union of the previous and current requirements
+		let larger = StackReq::new::<f32>(4096);
+		drop(scratch(larger));
+		assert!(pooled_bytes() == large.or(larger).unaligned_bytes_required());
+
+		clear();
+		assert!(pooled_bytes() == 0);
+
+		// buffers above the limit are freed instead of being kept. the limit is passed explicitly so
+		// that the global one, which is shared with the other tests, is left untouched
+		scratch(small).release(1024);
+		assert!(pooled_bytes() == small.unaligned_bytes_required());
+		clear();
+		scratch(large).release(1024);
+		assert!(pooled_bytes() == 0);
+	}
+}
@@ -0,0 +1,526 @@
+//! random sketching transforms
+//!
+//! a sketch is a random $k \times m$ matrix $S$, with $k \ll m$, such that $\|S x\| \approx \|x\|$
+//! with high probability, simultaneously for all the vectors $x$ in a fixed subspace of small
+//! dimension. this makes it possible to replace a tall least squares problem
+//! $\min \|A x - b\|$ by the much smaller one $\min \|S A x - S b\|$, or to find an approximate
+//! basis of the range of a low-rank matrix $A$ from the columns of $A S^\top$
+//!
+//! the following sketches are provided, all scaled so that $E[S^H S] = I$:
+//! - [`GaussianSketch`], a dense matrix with independent gaussian entries. it gives the best
+//! embeddings for a given $k$, but applying it to an $m \times n$ matrix costs $O(k m n)$
+//! operations,
+//! - [`Srht`], the subsampled randomized hadamard transform $S = \sqrt{1/k}\, R H D$, where $D$
+//! flips the signs of random rows, $H$ is the walsh-hadamard transform, and $R$ selects $k$ random
+//! rows. applying it costs $O(m n \log m)$ operations,
+//! - [`CountSketch`], which adds each row of the input, with a random sign, to a random row of the
+//! output. applying it costs $O(m n)$ operations, but it needs a larger $k$ than the others,
+//! typically of the order of the square of the dimension of the subspace
+//!
+//! all of them implement [`BiLinOp`], so they can be used anywhere a matrix-free operator is
+//! expected
+//!
+//! # example
+//! ```
+//! use faer::linalg::sketch::Srht;
+//! use faer::linalg::solvers::SolveLstsq;
+//! use faer::stats::prelude::*;
+//! use faer::{Col, Mat};
+//!
+//! let rng = &mut StdRng::seed_from_u64(0);
+//! let (m, n) = (2000, 10);
+//! let A = Mat::<f64>::random_normal(rng, m, n);
+//! let b = Col::<f64>::from_fn(m, |i| (i as f64).sin());
+//!
+//! // sketch-and-solve least squares
+//! let S = Srht::new(20 * n, m, rng);
+//! let SA = S.apply(A.as_ref());
+//! let Sb = S.apply(b.as_mat());
+//! let x = SA.qr().solve_lstsq(&Sb);
+//!
+//! let x_exact = A.qr().solve_lstsq(&b);
+//! let r = (&A * &x).col(0) - &b;
+//! let r_exact = &A * &x_exact - &b;
+//! assert!(r.norm_l2() < 1.5 * r_exact.norm_l2());
+//! ```
+
+use crate::internal_prelude::*;
+use crate::matrix_free::{BiLinOp, LinOp};
+use crate::{assert, get_global_parallelism};
+use alloc::vec::Vec;
+use dyn_stack::MemBuffer;
+use rand::Rng;
+
+/// dense gaussian sketch
+#[derive(Clone, Debug)]
+pub struct GaussianSketch<T: ComplexField> {
+	mat: Mat<T>,
+}
+
+/// subsampled randomized hadamard transform
+#[derive(Clone, Debug)]
+pub struct Srht {
+	dim: usize,
+	// `true` if the sign of the row is flipped
+	signs: Vec<bool>,
+	// sorted indices of the selected rows of the padded transform
+	rows: Vec<usize>,
+}
+
+/// sparse sign embedding, with a single nonzero per column
+#[derive(Clone, Debug)]
+pub struct CountSketch {
+	sketch_dim: usize,
+	buckets: Vec<usize>,
+	// `true` if the sign of the row is flipped
+	signs: Vec<bool>,
+}
+
+impl<T: ComplexField> GaussianSketch<T> {
+	/// returns a new `sketch_dim×dim` gaussian sketch
+	#[math]
+	pub fn new(sketch_dim: usize, dim: usize, rng: &mut (impl ?Sized + Rng)) -> Self {
+		// complex standard normal samples have a variance of 2
+		let var = if try_const! { T::IS_REAL } { 1.0 } else { 2.0 };
+		let scale = from_f64::<T::Real>(1.0 / (var * sketch_dim as f64).sqrt());
+
+		let mut mat = Mat::<T>::random_normal(rng, sketch_dim, dim);
+		zip!(&mut mat).for_each(|unzip!(s)| *s = mul_real(*s, scale));
+		Self { mat }
+	}
+
+	/// returns the number of rows of the sketch
+	#[inline]
+	pub fn sketch_dim(&self) -> usize {
+		self.mat.nrows()
+	}
+
+	/// returns the dimension of the sketched vectors
+	#[inline]
+	pub fn dim(&self) -> usize {
+		self.mat.ncols()
+	}
+
+	/// returns the sketch as a dense matrix
+	#[inline]
+	pub fn as_mat(&self) -> MatRef<'_, T> {
+		self.mat.as_ref()
+	}
+
+	/// returns $S A$
+	#[track_caller]
+	pub fn apply(&self, A: MatRef<'_, impl Conjugate<Canonical = T>>) -> Mat<T> {
+		assert!(A.nrows() == self.dim());
+		let mut out = Mat::zeros(self.sketch_dim(), A.ncols());
+		linalg::matmul::matmul(out.as_mut(), Accum::Replace, self.mat.as_ref(), A, one(), get_global_parallelism());
+		out
+	}
+}
+
+impl Srht {
+	/// returns a new `sketch_dim×dim` subsampled randomized hadamard transform
+	///
+	/// # panics
+	///
+	/// panics if `sketch_dim` is larger than the smallest power of two that is at least `dim`
+	#[track_caller]
+	pub fn new(sketch_dim: usize, dim: usize, rng: &mut (impl ?Sized + Rng)) -> Self {
+		let p = dim.next_power_of_two();
+		assert!(sketch_dim <= p);
+
+		let signs = (0..dim).map(|_| rng.r#gen::<bool>()).collect();
+		let mut rows = rand::seq::index::sample(rng, p, sketch_dim).into_vec();
+		rows.sort_unstable();
+		Self { dim, signs, rows }
+	}
+
+	/// returns the number of rows of the sketch
+	#[inline]
+	pub fn sketch_dim(&self) -> usize {
+		self.rows.len()
+	}
+
+	/// returns the dimension of the sketched vectors
+	#[inline]
+	pub fn dim(&self) -> usize {
+		self.dim
+	}
+
+	/// returns $S A$
+	#[track_caller]
+	pub fn apply<T: ComplexField, C: Conjugate<Canonical = T>>(&self, A: MatRef<'_, C>) -> Mat<T> {
+		assert!(A.nrows() == self.dim);
+		let mut out = Mat::zeros(self.sketch_dim(), A.ncols());
+		self.apply_imp(
+			out.as_mut(),
+			A.canonical(),
+			Conj::get::<C>(),
+			false,
+			MemStack::new(&mut MemBuffer::new(self.scratch::<T>(A.ncols()))),
+		);
+		out
+	}
+
+	fn scratch<T: ComplexField>(&self, rhs_ncols: usize) -> StackReq {
+		temp_mat_scratch::<T>(self.dim.next_power_of_two(), rhs_ncols)
+	}
+
+	/// computes `S * op(rhs)` or `S^T * op(rhs)`, where `op` optionally conjugates its input
+	#[math]
+	fn apply_imp<T: ComplexField>(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, conj_rhs: Conj, transpose: bool, stack: &mut MemStack) {
+		let mut out = out;
+		let (m, k, n) = (self.dim, self.sketch_dim(), rhs.ncols());
+		let p = m.next_power_of_two();
+		let scale = from_f64::<T::Real>(1.0 / (k as f64).sqrt());
+		let conj_rhs = conj_rhs == Conj::Yes;
+
+		let (mut z, _) = unsafe { temp_mat_uninit::<T, _, _>(p, n, stack) };
+		let mut z = z.as_mat_mut();
+		z.fill(zero());
+
+		if !transpose {
+			assert!(all(rhs.nrows() == m, out.nrows() == k, out.ncols() == n));
+			for j in 0..n {
+				for i in 0..m {
+					let v = if conj_rhs { conj(rhs[(i, j)]) } else { copy(rhs[(i, j)]) };
+					z[(i, j)] = if self.signs[i] { -v } else { v };
+				}
+			}
+			fwht(z.rb_mut());
+			for j in 0..n {
+				for (r, &i) in self.rows.iter().enumerate() {
+					out[(r, j)] = mul_real(z[(i, j)], scale);
+				}
+			}
+		} else {
+			assert!(all(rhs.nrows() == k, out.nrows() == m, out.ncols() == n));
+			for j in 0..n {
+				for (r, &i) in self.rows.iter().enumerate() {
+					z[(i, j)] = if conj_rhs { conj(rhs[(r, j)]) } else { copy(rhs[(r, j)]) };
+				}
+			}
+			fwht(z.rb_mut());
+			for j in 0..n {
+				for i in 0..m {
+					let v = mul_real(z[(i, j)], scale);
+					out[(i, j)] = if self.signs[i] { -v } else { v };
+				}
+			}
+		}
+	}
+}
+
+/// applies the unnormalized walsh-hadamard transform to the columns of `z`, whose number of rows
+/// must be a power of two
+#[math]
+fn fwht<T: ComplexField>(z: MatMut<'_, T>) {
+	let mut z = z;
+	let p = z.nrows();
+	let mut h = 1;
+	while h < p {
+		let mut i = 0;
+		while i < p {
+			let (top, bot) = z.rb_mut().subrows_mut(i, 2 * h).split_at_row_mut(h);
+			zip!(top, bot).for_each(|unzip!(a, b)| {
+				let (x, y) = (copy(*a), copy(*b));
+				*a = x + y;
+				*b = x - y;
+			});
+			i += 2 * h;
+		}
+		h *= 2;
+	}
+}
+
+impl CountSketch {
+	/// returns a new `sketch_dim×dim` count sketch
+	///
+	/// # panics
+	///
+	/// panics if `sketch_dim` is zero, unless `dim` is also zero
+	#[track_caller]
+	pub fn new(sketch_dim: usize, dim: usize, rng: &mut (impl ?Sized + Rng)) -> Self {
+		assert!(any(sketch_dim > 0, dim == 0));
+
+		let buckets = (0..dim).map(|_| rng.gen_range(0..sketch_dim)).collect();
+		let signs = (0..dim).map(|_| rng.r#gen::<bool>()).collect();
+		Self { sketch_dim, buckets, signs }
+	}
+
+	/// returns the number of rows of the sketch
+	#[inline]
+	pub fn sketch_dim(&self) -> usize {
+		self.sketch_dim
+	}
+
+	/// returns the dimension of the sketched vectors
+	#[inline]
+	pub fn dim(&self) -> usize {
+		self.buckets.len()
+	}
+
+	/// returns $S A$
+	#[track_caller]
+	pub fn apply<T: ComplexField, C: Conjugate<Canonical = T>>(&self, A: MatRef<'_, C>) -> Mat<T> {
+		assert!(A.nrows() == self.dim());
+		let mut out = Mat::zeros(self.sketch_dim, A.ncols());
+		self.apply_imp(out.as_mut(), A.canonical(), Conj::get::<C>(), false);
+		out
+	}
+
+	/// computes `S * op(rhs)` or `S^T * op(rhs)`, where `op` optionally conjugates its input
+	#[math]
+	fn apply_imp<T: ComplexField>(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, conj_rhs: Conj, transpose: bool) {
+		let mut out = out;
+		let (m, k, n) = (self.dim(), self.sketch_dim, rhs.ncols());
+		let conj_rhs = conj_rhs == Conj::Yes;
+
+		if !transpose {
+			assert!(all(rhs.nrows() == m, out.nrows() == k, out.ncols() == n));
+			out.fill(zero());
+			for j in 0..n {
+				for i in 0..m {
+					let b = self.buckets[i];
+					let v = if conj_rhs { conj(rhs[(i, j)]) } else { copy(rhs[(i, j)]) };
+					out[(b, j)] = if self.signs[i] { out[(b, j)] - v } else { out[(b, j)] + v };
+				}
+			}
+		} else {
+			assert!(all(rhs.nrows() == k, out.nrows() == m, out.ncols() == n));
+			for j in 0..n {
+				for i in 0..m {
+					let b = self.buckets[i];
+					let v = if conj_rhs { conj(rhs[(b, j)]) } else { copy(rhs[(b, j)]) };
+					out[(i, j)] = if self.signs[i] { -v } else { v };
+				}
+			}
+		}
+	}
+}
+
+impl<T: ComplexField> LinOp<T> for GaussianSketch<T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.sketch_dim()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.dim()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		linalg::matmul::matmul(out, Accum::Replace, self.mat.as_ref(), rhs, one(), par);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		linalg::matmul::matmul(out, Accum::Replace, self.mat.conjugate(), rhs, one(), par);
+	}
+}
+
+impl<T: ComplexField> BiLinOp<T> for GaussianSketch<T> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		linalg::matmul::matmul(out, Accum::Replace, self.mat.transpose(), rhs, one(), par);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		linalg::matmul::matmul(out, Accum::Replace, self.mat.adjoint(), rhs, one(), par);
+	}
+}
+
+// the srht and the count sketch have real entries, so their conjugate is themselves
+
+impl<T: ComplexField> LinOp<T> for Srht {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = par;
+		self.scratch::<T>(rhs_ncols)
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.sketch_dim()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.dim
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = par;
+		self.apply_imp(out, rhs, Conj::No, false, stack);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = par;
+		self.apply_imp(out, rhs, Conj::No, false, stack);
+	}
+}
+
+impl<T: ComplexField> BiLinOp<T> for Srht {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = par;
+		self.scratch::<T>(rhs_ncols)
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = par;
+		self.apply_imp(out, rhs, Conj::No, true, stack);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = par;
+		self.apply_imp(out, rhs, Conj::No, true, stack);
+	}
+}
+
+impl<T: ComplexField> LinOp<T> for CountSketch {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.sketch_dim
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.dim()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.apply_imp(out, rhs, Conj::No, false);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.apply_imp(out, rhs, Conj::No, false);
+	}
+}
+
+impl<T: ComplexField> BiLinOp<T> for CountSketch {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.apply_imp(out, rhs, Conj::No, true);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.apply_imp(out, rhs, Conj::No, true);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	/// materializes the operator and checks all of its products against the dense matrix
+	fn check_operator(S: &dyn BiLinOp<c64>, sketch: impl Fn(MatRef<'_, c64>) -> Mat<c64>, rng: &mut StdRng) {
+		let (k, m) = (S.nrows(), S.ncols());
+		let par = Par::Seq;
+		let stack = &mut MemBuffer::new(StackReq::any_of(&[S.apply_scratch(m, par), S.transpose_apply_scratch(m, par)]));
+		let stack = MemStack::new(stack);
+
+		let mut dense = Mat::<c64>::zeros(k, m);
+		S.apply(dense.as_mut(), Mat::<c64>::identity(m, m).as_ref(), par, stack);
+		let mut dense_t = Mat::<c64>::zeros(m, k);
+		S.transpose_apply(dense_t.as_mut(), Mat::<c64>::identity(k, k).as_ref(), par, stack);
+		assert!((dense.transpose() - &dense_t).norm_max() < 1e-12);
+
+		let A = Mat::<c64>::random_normal(rng, m, 3);
+		assert!((sketch(A.as_ref()) - &dense * &A).norm_max() < 1e-12);
+
+		let mut out = Mat::<c64>::zeros(k, 3);
+		S.conj_apply(out.as_mut(), A.as_ref(), par, stack);
+		assert!((&out - dense.conjugate() * &A).norm_max() < 1e-12);
+
+		let y = Mat::<c64>::random_normal(rng, k, 3);
+		let mut out = Mat::<c64>::zeros(m, 3);
+		S.adjoint_apply(out.as_mut(), y.as_ref(), par, stack);
+		assert!((&out - dense.adjoint() * &y).norm_max() < 1e-12);
+	}
+
+	#[test]
+	fn test_sketch_operators() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (k, m) = (8, 37);
+
+		let S = GaussianSketch::<c64>::new(k, m, rng);
+		check_operator(&S, |A| S.apply(A), rng);
+		let S = Srht::new(k, m, rng);
+		check_operator(&S, |A| S.apply(A), rng);
+		let S = CountSketch::new(k, m, rng);
+		check_operator(&S, |A| S.apply(A), rng);
+
+		// conjugated input
+		let A = Mat::<c64>::random_normal(rng, m, 2);
+		let S = Srht::new(k, m, rng);
+		assert!((S.apply(A.conjugate()) - S.apply(A.as_ref()).conjugate()).norm_max() < 1e-12);
+		let S = CountSketch::new(k, m, rng);
+		assert!((S.apply(A.conjugate()) - S.apply(A.as_ref()).conjugate()).norm_max() < 1e-12);
+	}
+
+	#[test]
+	fn test_subspace_embedding() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n) = (1000, 5);
+
+		// orthonormal basis of a random subspace, with a few coherent rows
+		let mut A = Mat::<c64>::random_normal(rng, m, n);
+		for j in 0..n {
+			A[(j, j)] = c64::new(100.0, 0.0);
+		}
+		let Q = A.qr().compute_thin_Q();
+
+		// the singular values of S Q are all close to one
+		let check = |SQ: Mat<c64>| {
+			let s = SQ.singular_values().unwrap();
+			assert!(all(s[0] < 1.6, s[n - 1] > 0.4));
+		};
+		check(GaussianSketch::<c64>::new(40, m, rng).apply(Q.as_ref()));
+		check(Srht::new(40, m, rng).apply(Q.as_ref()));
+		check(CountSketch::new(400, m, rng).apply(Q.as_ref()));
+	}
+}
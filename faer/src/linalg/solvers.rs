@@ -1,9 +1,10 @@
 use crate::internal_prelude::*;
+use crate::matrix_free::{BiLinOp, LinOp};
 use crate::{assert, get_global_parallelism};
 use alloc::vec;
 use alloc::vec::Vec;
-use dyn_stack::MemBuffer;
 use faer_traits::{ComplexConj, math_utils};
+use linalg::scratch_pool::scratch;
 use linalg::svd::ComputeSvdVectors;
 
 pub use linalg::cholesky::ldlt::factor::LdltError;
@@ -250,6 +251,12 @@ impl<C: Conjugate, Inner: for<'short> Reborrow<'short, Target = mat::Ref<'short,
 		ColPivQr::new(self.rb())
 	}
 
+	#[track_caller]
+	/// returns the complete orthogonal decomposition of `self`
+	pub fn cod(&self) -> Cod<C::Canonical> {
+		Cod::new(self.rb())
+	}
+
 	#[track_caller]
 	/// returns the svd of `self`
 	///
@@ -313,12 +320,13 @@ impl<C: Conjugate, Inner: for<'short> Reborrow<'short, Target = mat::Ref<'short,
 				s.as_mut(),
 				None,
 				par,
-				MemStack::new(&mut MemBuffer::new(linalg::evd::self_adjoint_evd_scratch::<T>(
+				scratch(linalg::evd::self_adjoint_evd_scratch::<T>(
 					n,
 					linalg::evd::ComputeEigenvectors::No,
 					par,
 					default(),
-				))),
+				))
+				.stack(),
 				default(),
 			)?;
 
@@ -346,14 +354,15 @@ impl<C: Conjugate, Inner: for<'short> Reborrow<'short, Target = mat::Ref<'short,
 				None,
 				None,
 				par,
-				MemStack::new(&mut MemBuffer::new(linalg::svd::svd_scratch::<T>(
+				scratch(linalg::svd::svd_scratch::<T>(
 					m,
 					n,
 					linalg::svd::ComputeSvdVectors::No,
 					linalg::svd::ComputeSvdVectors::No,
 					par,
 					default(),
-				))),
+				))
+				.stack(),
 				default(),
 			)?;
 
@@ -362,6 +371,161 @@ impl<C: Conjugate, Inner: for<'short> Reborrow<'short, Target = mat::Ref<'short,
 
 		imp(self.rb().canonical())
 	}
+
+	#[track_caller]
+	/// solves $A x = b$ using the $LU$ decomposition of `self` with partial (row) pivoting
+	///
+	/// the decomposition is stored in workspace memory taken from a thread-local pool, so that the
+	/// returned solution is the only allocation once the pool is large enough
+	pub fn solve_lu(&self, rhs: impl AsMatRef<T = C::Canonical, Rows = usize>) -> Mat<C::Canonical> {
+		#[track_caller]
+		fn imp<T: ComplexField>(A: MatRef<'_, T>, conj: Conj, rhs: MatRef<'_, T>) -> Mat<T> {
+			assert!(all(A.nrows() == A.ncols(), rhs.nrows() == A.nrows()));
+			let par = get_global_parallelism();
+			let n = A.nrows();
+			let k = rhs.ncols();
+
+			let mut mem = scratch(StackReq::all_of(&[
+				temp_mat_scratch::<T>(n, n),
+				StackReq::new::<usize>(n).array(2),
+				StackReq::any_of(&[
+					linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<usize, T>(n, n, par, default()),
+					linalg::lu::partial_pivoting::solve::solve_in_place_scratch::<usize, T>(n, k, par),
+				]),
+			]));
+			let stack = mem.stack();
+
+			let (mut LU, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+			let mut LU = LU.as_mat_mut();
+			LU.copy_from(A);
+			let (mut perm, stack) = stack.make_with(n, |_| 0usize);
+			let (mut perm_inv, stack) = stack.make_with(n, |_| 0usize);
+
+			let (_, P) = linalg::lu::partial_pivoting::factor::lu_in_place(LU.rb_mut(), &mut perm, &mut perm_inv, par, stack, default());
+
+			let mut out = rhs.to_owned();
+			linalg::lu::partial_pivoting::solve::solve_in_place_with_conj(LU.rb(), LU.rb(), P, conj, out.as_mut(), par, stack);
+			out
+		}
+
+		imp(self.rb().canonical(), Conj::get::<C>(), rhs.as_mat_ref().as_dyn_cols())
+	}
+
+	#[track_caller]
+	/// solves $A x = b$ using the $L L^\top$ decomposition of `self`
+	///
+	/// the decomposition is stored in workspace memory taken from a thread-local pool, so that the
+	/// returned solution is the only allocation once the pool is large enough
+	pub fn solve_llt(&self, side: Side, rhs: impl AsMatRef<T = C::Canonical, Rows = usize>) -> Result<Mat<C::Canonical>, LltError> {
+		#[track_caller]
+		fn imp<T: ComplexField>(A: MatRef<'_, T>, conj: Conj, side: Side, rhs: MatRef<'_, T>) -> Result<Mat<T>, LltError> {
+			assert!(all(A.nrows() == A.ncols(), rhs.nrows() == A.nrows()));
+			let par = get_global_parallelism();
+			let n = A.nrows();
+			let k = rhs.ncols();
+
+			let mut mem = scratch(StackReq::all_of(&[
+				temp_mat_scratch::<T>(n, n),
+				StackReq::any_of(&[
+					linalg::cholesky::llt::factor::cholesky_in_place_scratch::<T>(n, par, default()),
+					linalg::cholesky::llt::solve::solve_in_place_scratch::<T>(n, k, par),
+				]),
+			]));
+			let stack = mem.stack();
+
+			let (mut L, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+			let mut L = L.as_mat_mut();
+			match side {
+				Side::Lower => L.copy_from_triangular_lower(A),
+				Side::Upper => L.copy_from_triangular_lower(A.adjoint()),
+			}
+
+			linalg::cholesky::llt::factor::cholesky_in_place(L.rb_mut(), default(), par, stack, default())?;
+
+			let mut out = rhs.to_owned();
+			linalg::cholesky::llt::solve::solve_in_place_with_conj(L.rb(), conj, out.as_mut(), par, stack);
+			Ok(out)
+		}
+
+		imp(self.rb().canonical(), Conj::get::<C>(), side, rhs.as_mat_ref().as_dyn_cols())
+	}
+
+	#[track_caller]
+	/// solves $A x = b$ in the sense of least squares using the $QR$ decomposition of `self`
+	///
+	/// the decomposition is stored in workspace memory taken from a thread-local pool, so that the
+	/// returned solution is the only allocation once the pool is large enough
+	pub fn solve_lstsq_qr(&self, rhs: impl AsMatRef<T = C::Canonical, Rows = usize>) -> Mat<C::Canonical> {
+		#[track_caller]
+		fn imp<T: ComplexField>(A: MatRef<'_, T>, conj: Conj, rhs: MatRef<'_, T>) -> Mat<T> {
+			let (m, n) = A.shape();
+			assert!(all(m >= n, rhs.nrows() == m));
+			let par = get_global_parallelism();
+			let k = rhs.ncols();
+			let bs = linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(m, n);
+
+			let mut mem = scratch(StackReq::all_of(&[
+				temp_mat_scratch::<T>(m, n),
+				temp_mat_scratch::<T>(bs, n),
+				temp_mat_scratch::<T>(m, k),
+				StackReq::any_of(&[
+					linalg::qr::no_pivoting::factor::qr_in_place_scratch::<T>(m, n, bs, par, default()),
+					linalg::qr::no_pivoting::solve::solve_lstsq_in_place_scratch::<T>(m, n, bs, k, par),
+				]),
+			]));
+			let stack = mem.stack();
+
+			let (mut QR, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, n, stack) };
+			let (mut H, stack) = unsafe { temp_mat_uninit::<T, _, _>(bs, n, stack) };
+			let (mut X, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, k, stack) };
+			let mut QR = QR.as_mat_mut();
+			let mut H = H.as_mat_mut();
+			let mut X = X.as_mat_mut();
+			QR.copy_from(A);
+			X.copy_from(rhs);
+
+			linalg::qr::no_pivoting::factor::qr_in_place(QR.rb_mut(), H.rb_mut(), par, stack, default());
+			linalg::qr::no_pivoting::solve::solve_lstsq_in_place_with_conj(QR.rb(), H.rb(), QR.rb(), conj, X.rb_mut(), par, stack);
+
+			X.rb().get(..n, ..).to_owned()
+		}
+
+		imp(self.rb().canonical(), Conj::get::<C>(), rhs.as_mat_ref().as_dyn_cols())
+	}
+}
+
+/// solves $A x = b$ using the $LU$ decomposition of $A$ with partial (row) pivoting
+///
+/// see [`MatRef::solve_lu`]
+#[track_caller]
+pub fn solve_lu<C: Conjugate>(
+	A: impl AsMatRef<T = C, Rows = usize, Cols = usize>,
+	rhs: impl AsMatRef<T = C::Canonical, Rows = usize>,
+) -> Mat<C::Canonical> {
+	A.as_mat_ref().solve_lu(rhs)
+}
+
+/// solves $A x = b$ using the $L L^\top$ decomposition of $A$
+///
+/// see [`MatRef::solve_llt`]
+#[track_caller]
+pub fn solve_llt<C: Conjugate>(
+	A: impl AsMatRef<T = C, Rows = usize, Cols = usize>,
+	side: Side,
+	rhs: impl AsMatRef<T = C::Canonical, Rows = usize>,
+) -> Result<Mat<C::Canonical>, LltError> {
+	A.as_mat_ref().solve_llt(side, rhs)
+}
+
+/// solves $A x = b$ in the sense of least squares using the $QR$ decomposition of $A$
+///
+/// see [`MatRef::solve_lstsq_qr`]
+#[track_caller]
+pub fn solve_lstsq_qr<C: Conjugate>(
+	A: impl AsMatRef<T = C, Rows = usize, Cols = usize>,
+	rhs: impl AsMatRef<T = C::Canonical, Rows = usize>,
+) -> Mat<C::Canonical> {
+	A.as_mat_ref().solve_lstsq_qr(rhs)
 }
 
 impl<C: Conjugate> MatRef<'_, C> {
@@ -395,13 +559,14 @@ impl<C: Conjugate> MatRef<'_, C> {
 				None,
 				None,
 				par,
-				MemStack::new(&mut MemBuffer::new(linalg::evd::evd_scratch::<Real<C>>(
+				scratch(linalg::evd::evd_scratch::<Real<C>>(
 					n,
 					linalg::evd::ComputeEigenvectors::No,
 					linalg::evd::ComputeEigenvectors::No,
 					par,
 					default(),
-				))),
+				))
+				.stack(),
 				default(),
 			)?;
 
@@ -424,13 +589,14 @@ impl<C: Conjugate> MatRef<'_, C> {
 				None,
 				None,
 				par,
-				MemStack::new(&mut MemBuffer::new(linalg::evd::evd_scratch::<Complex<Real<C>>>(
+				scratch(linalg::evd::evd_scratch::<Complex<Real<C>>>(
 					n,
 					linalg::evd::ComputeEigenvectors::No,
 					linalg::evd::ComputeEigenvectors::No,
 					par,
 					default(),
-				))),
+				))
+				.stack(),
 				default(),
 			)?;
 
@@ -534,6 +700,9 @@ pub struct PartialPivLu<T> {
 }
 
 /// $LU$ decomposition with full pivoting
+///
+/// pivot ties are broken in favor of the lowest column, then the lowest row, so the decomposition
+/// doesn't depend on the memory layout of the input or on the simd width of the machine
 #[derive(Clone, Debug)]
 pub struct FullPivLu<T> {
 	L: Mat<T>,
@@ -559,6 +728,19 @@ pub struct ColPivQr<T> {
 	P: Perm<usize>,
 }
 
+/// complete orthogonal decomposition $A P = Q \begin{bmatrix} L & 0 \\ 0 & 0 \end{bmatrix} Z^H$,
+/// where $Q$ and $Z$ are unitary, $P$ is a column permutation, and $L$ is a lower triangular
+/// matrix whose size is the numerical rank of $A$
+#[derive(Clone, Debug)]
+pub struct Cod<T> {
+	Q_basis: Mat<T>,
+	Q_coeff: Mat<T>,
+	Z_basis: Mat<T>,
+	Z_coeff: Mat<T>,
+	L: Mat<T>,
+	P: Perm<usize>,
+}
+
 /// svd decomposition (either full or thin)
 #[derive(Clone, Debug)]
 pub struct Svd<T> {
@@ -603,8 +785,8 @@ impl<T: ComplexField> Llt<T> {
 
 		let n = L.nrows();
 
-		let mut mem = MemBuffer::new(linalg::cholesky::llt::factor::cholesky_in_place_scratch::<T>(n, par, default()));
-		let stack = MemStack::new(&mut mem);
+		let mut mem = scratch(linalg::cholesky::llt::factor::cholesky_in_place_scratch::<T>(n, par, default()));
+		let stack = mem.stack();
 
 		linalg::cholesky::llt::factor::cholesky_in_place(L.as_mut(), Default::default(), par, stack, default())?;
 		z!(&mut L).for_each_triangular_upper(linalg::zip::Diag::Skip, |uz!(x)| *x = zero());
@@ -616,6 +798,83 @@ impl<T: ComplexField> Llt<T> {
 	pub fn L(&self) -> MatRef<'_, T> {
 		self.L.as_ref()
 	}
+
+	/// extends the decomposition of $A$ to the decomposition of the block matrix
+	/// $\begin{bmatrix} A & B \\ B^H & C \end{bmatrix}$, reading only the lower triangular half of
+	/// $C$
+	///
+	/// only the schur complement $C - B^H A^{-1} B$ is factorized, so the cost is $O(n^2 k)$ for
+	/// $k$ new rows, instead of $O((n + k)^3)$ for refactorizing the whole matrix. this is the
+	/// standard incremental update of gaussian process regression and of square root kalman
+	/// filters, when new observations are added
+	///
+	/// # errors
+	///
+	/// returns an error if the block matrix is not positive definite, in which case the index of
+	/// the failed pivot refers to the block matrix, and `self` is left unchanged
+	///
+	/// # panics
+	///
+	/// panics if the dimensions of `B` and `C` don't match
+	#[track_caller]
+	pub fn extend<BT: Conjugate<Canonical = T>, CT: Conjugate<Canonical = T>>(
+		&mut self,
+		B: MatRef<'_, BT>,
+		C: MatRef<'_, CT>,
+	) -> Result<(), LltError> {
+		let n = self.L.nrows();
+		let k = C.nrows();
+		assert!(all(B.nrows() == n, B.ncols() == k, C.ncols() == k));
+
+		let (W, L_S) = schur_complement_llt(self.L.as_ref(), B, C)?;
+
+		let mut L = Mat::zeros(n + k, n + k);
+		L.get_mut(..n, ..n).copy_from(&self.L);
+		L.get_mut(n.., ..n).copy_from(W.adjoint());
+		L.get_mut(n.., n..).copy_from(&L_S);
+		self.L = L;
+
+		Ok(())
+	}
+}
+
+/// given the $L L^H$ decomposition of $A$, returns $W = L^{-1} B$ and the $L$ factor of the schur
+/// complement $C - W^H W$
+#[track_caller]
+fn schur_complement_llt<T: ComplexField>(
+	L: MatRef<'_, T>,
+	B: MatRef<'_, impl Conjugate<Canonical = T>>,
+	C: MatRef<'_, impl Conjugate<Canonical = T>>,
+) -> Result<(Mat<T>, Mat<T>), LltError> {
+	let par = get_global_parallelism();
+	let n = L.nrows();
+	let k = C.nrows();
+
+	let mut W = B.to_owned();
+	linalg::triangular_solve::solve_lower_triangular_in_place(L, W.as_mut(), par);
+
+	let mut S = Mat::zeros(k, k);
+	S.copy_from_triangular_lower(C);
+	linalg::matmul::triangular::matmul(
+		S.as_mut(),
+		linalg::matmul::triangular::BlockStructure::TriangularLower,
+		Accum::Add,
+		W.adjoint(),
+		linalg::matmul::triangular::BlockStructure::Rectangular,
+		&W,
+		linalg::matmul::triangular::BlockStructure::Rectangular,
+		neg(&one::<T>()),
+		par,
+	);
+
+	let mut mem = scratch(linalg::cholesky::llt::factor::cholesky_in_place_scratch::<T>(k, par, default()));
+	match linalg::cholesky::llt::factor::cholesky_in_place(S.as_mut(), default(), par, mem.stack(), default()) {
+		Ok(_) => {},
+		Err(LltError::NonPositivePivot { index }) => return Err(LltError::NonPositivePivot { index: n + index }),
+	}
+	z!(&mut S).for_each_triangular_upper(linalg::zip::Diag::Skip, |uz!(x)| *x = zero());
+
+	Ok((W, S))
 }
 
 impl<T: ComplexField> Ldlt<T> {
@@ -641,8 +900,8 @@ impl<T: ComplexField> Ldlt<T> {
 		let n = L.nrows();
 		let mut D = Diag::zeros(n);
 
-		let mut mem = MemBuffer::new(linalg::cholesky::ldlt::factor::cholesky_in_place_scratch::<T>(n, par, default()));
-		let stack = MemStack::new(&mut mem);
+		let mut mem = scratch(linalg::cholesky::ldlt::factor::cholesky_in_place_scratch::<T>(n, par, default()));
+		let stack = mem.stack();
 
 		linalg::cholesky::ldlt::factor::cholesky_in_place(L.as_mut(), Default::default(), par, stack, default())?;
 
@@ -690,8 +949,8 @@ impl<T: ComplexField> Lblt<T> {
 		let mut perm_fwd = vec![0usize; n];
 		let mut perm_bwd = vec![0usize; n];
 
-		let mut mem = MemBuffer::new(linalg::cholesky::lblt::factor::cholesky_in_place_scratch::<usize, T>(n, par, default()));
-		let stack = MemStack::new(&mut mem);
+		let mut mem = scratch(linalg::cholesky::lblt::factor::cholesky_in_place_scratch::<usize, T>(n, par, default()));
+		let stack = mem.stack();
 
 		linalg::cholesky::lblt::factor::cholesky_in_place(L.as_mut(), subdiag.as_mut(), &mut perm_fwd, &mut perm_bwd, par, stack, default());
 
@@ -777,9 +1036,13 @@ impl<T: ComplexField> PartialPivLu<T> {
 			&mut row_perm_fwd,
 			&mut row_perm_bwd,
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<usize, T>(m, n, par, default()),
-			)),
+			scratch(linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<usize, T>(
+				m,
+				n,
+				par,
+				default(),
+			))
+			.stack(),
 			default(),
 		);
 
@@ -806,6 +1069,22 @@ impl<T: ComplexField> PartialPivLu<T> {
 	pub fn P(&self) -> PermRef<'_, usize> {
 		self.P.as_ref()
 	}
+
+	/// returns the relative residual $\|PA - LU\|_F / \|A\|_F$ of the factorization, where
+	/// $A$ is the original matrix
+	#[track_caller]
+	pub fn residual_norm(&self, A: MatRef<'_, T>) -> T::Real {
+		let par = get_global_parallelism();
+		let (m, n) = A.shape();
+		linalg::lu::partial_pivoting::reconstruct::residual_norm(
+			A,
+			self.L(),
+			self.U(),
+			self.P(),
+			par,
+			scratch(linalg::lu::partial_pivoting::reconstruct::residual_norm_scratch::<usize, T>(m, n, par)).stack(),
+		)
+	}
 }
 
 impl<T: ComplexField> FullPivLu<T> {
@@ -833,12 +1112,7 @@ impl<T: ComplexField> FullPivLu<T> {
 			&mut col_perm_fwd,
 			&mut col_perm_bwd,
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::lu::full_pivoting::factor::lu_in_place_scratch::<usize, T>(
-				m,
-				n,
-				par,
-				default(),
-			))),
+			scratch(linalg::lu::full_pivoting::factor::lu_in_place_scratch::<usize, T>(m, n, par, default())).stack(),
 			default(),
 		);
 
@@ -871,6 +1145,23 @@ impl<T: ComplexField> FullPivLu<T> {
 	pub fn Q(&self) -> PermRef<'_, usize> {
 		self.Q.as_ref()
 	}
+
+	/// returns the relative residual $\|PAQ^\top - LU\|_F / \|A\|_F$ of the factorization,
+	/// where $A$ is the original matrix
+	#[track_caller]
+	pub fn residual_norm(&self, A: MatRef<'_, T>) -> T::Real {
+		let par = get_global_parallelism();
+		let (m, n) = A.shape();
+		linalg::lu::full_pivoting::reconstruct::residual_norm(
+			A,
+			self.L(),
+			self.U(),
+			self.P(),
+			self.Q(),
+			par,
+			scratch(linalg::lu::full_pivoting::reconstruct::residual_norm_scratch::<usize, T>(m, n, par)).stack(),
+		)
+	}
 }
 
 impl<T: ComplexField> Qr<T> {
@@ -895,13 +1186,7 @@ impl<T: ComplexField> Qr<T> {
 			QR.as_mut(),
 			Q_coeff.as_mut(),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::no_pivoting::factor::qr_in_place_scratch::<T>(
-				m,
-				n,
-				blocksize,
-				par,
-				default(),
-			))),
+			scratch(linalg::qr::no_pivoting::factor::qr_in_place_scratch::<T>(m, n, blocksize, par, default())).stack(),
 			default(),
 		);
 
@@ -941,13 +1226,12 @@ impl<T: ComplexField> Qr<T> {
 			Conj::No,
 			Q.rb_mut(),
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(
-					self.nrows(),
-					self.Q_coeff.nrows(),
-					self.nrows(),
-				),
-			)),
+			scratch(linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(
+				self.nrows(),
+				self.Q_coeff.nrows(),
+				self.nrows(),
+			))
+			.stack(),
 		);
 		Q
 	}
@@ -963,9 +1247,12 @@ impl<T: ComplexField> Qr<T> {
 			Conj::No,
 			Q.rb_mut(),
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(self.nrows(), self.Q_coeff.nrows(), size),
-			)),
+			scratch(linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(
+				self.nrows(),
+				self.Q_coeff.nrows(),
+				size,
+			))
+			.stack(),
 		);
 		Q
 	}
@@ -998,13 +1285,14 @@ impl<T: ComplexField> ColPivQr<T> {
 			&mut col_perm_fwd,
 			&mut col_perm_bwd,
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::col_pivoting::factor::qr_in_place_scratch::<usize, T>(
+			scratch(linalg::qr::col_pivoting::factor::qr_in_place_scratch::<usize, T>(
 				m,
 				n,
 				blocksize,
 				par,
 				default(),
-			))),
+			))
+			.stack(),
 			default(),
 		);
 
@@ -1049,13 +1337,12 @@ impl<T: ComplexField> ColPivQr<T> {
 			Conj::No,
 			Q.rb_mut(),
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(
-					self.nrows(),
-					self.Q_coeff.nrows(),
-					self.nrows(),
-				),
-			)),
+			scratch(linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(
+				self.nrows(),
+				self.Q_coeff.nrows(),
+				self.nrows(),
+			))
+			.stack(),
 		);
 		Q
 	}
@@ -1071,9 +1358,12 @@ impl<T: ComplexField> ColPivQr<T> {
 			Conj::No,
 			Q.rb_mut(),
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(self.nrows(), self.Q_coeff.nrows(), size),
-			)),
+			scratch(linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(
+				self.nrows(),
+				self.Q_coeff.nrows(),
+				size,
+			))
+			.stack(),
 		);
 		Q
 	}
@@ -1084,6 +1374,168 @@ impl<T: ComplexField> ColPivQr<T> {
 	}
 }
 
+impl<T: ComplexField> Cod<T> {
+	/// returns the complete orthogonal decomposition of $A$
+	///
+	/// the numerical rank is the number of diagonal entries of the $R$ factor of the column
+	/// pivoted $QR$ decomposition of $A$ whose magnitude is larger than
+	/// $\epsilon \max(m, n) |R_{00}|$
+	#[track_caller]
+	pub fn new<C: Conjugate<Canonical = T>>(A: MatRef<'_, C>) -> Self {
+		let tolerance = eps::<T::Real>() * from_f64::<T::Real>(Ord::max(A.nrows(), A.ncols()) as f64);
+		Self::new_with_tolerance(A, tolerance)
+	}
+
+	/// returns the complete orthogonal decomposition of $A$, where the numerical rank is the number
+	/// of diagonal entries of the $R$ factor of the column pivoted $QR$ decomposition of $A$ whose
+	/// magnitude is larger than `tolerance` $|R_{00}|$
+	#[track_caller]
+	#[math]
+	pub fn new_with_tolerance<C: Conjugate<Canonical = T>>(A: MatRef<'_, C>, tolerance: T::Real) -> Self {
+		let par = get_global_parallelism();
+		let ColPivQr { Q_basis, Q_coeff, R, P } = ColPivQr::new_imp(A.to_owned());
+
+		let n = R.ncols();
+		let size = Ord::min(R.nrows(), n);
+		let mut rank = 0;
+		if size > 0 {
+			let threshold = tolerance * abs(R[(0, 0)]);
+			while rank < size && abs(R[(rank, rank)]) > threshold {
+				rank += 1;
+			}
+		}
+
+		// the rq decomposition of the leading rows of R is computed as the qr decomposition of
+		// their adjoint, [R11 R12]^H = Z [S; 0], so that [R11 R12] = [S^H 0] Z^H
+		let mut Z_basis = R.get(..rank, ..).adjoint().to_owned();
+		let blocksize = Ord::max(1, linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(n, rank));
+		let mut Z_coeff = Mat::zeros(blocksize, rank);
+		if rank > 0 {
+			linalg::qr::no_pivoting::factor::qr_in_place(
+				Z_basis.as_mut(),
+				Z_coeff.as_mut(),
+				par,
+				scratch(linalg::qr::no_pivoting::factor::qr_in_place_scratch::<T>(
+					n,
+					rank,
+					blocksize,
+					par,
+					default(),
+				))
+				.stack(),
+				default(),
+			);
+		}
+		let L = Mat::from_fn(rank, rank, |i, j| if i >= j { conj(Z_basis[(j, i)]) } else { zero() });
+
+		Self {
+			Q_basis,
+			Q_coeff,
+			Z_basis,
+			Z_coeff,
+			L,
+			P,
+		}
+	}
+
+	/// returns the numerical rank of $A$
+	#[inline]
+	pub fn rank(&self) -> usize {
+		self.L.nrows()
+	}
+
+	/// returns the householder basis of $Q$
+	pub fn Q_basis(&self) -> MatRef<'_, T> {
+		self.Q_basis.as_ref()
+	}
+
+	/// returns the householder coefficients of $Q$
+	pub fn Q_coeff(&self) -> MatRef<'_, T> {
+		self.Q_coeff.as_ref()
+	}
+
+	/// returns the householder basis of $Z$
+	pub fn Z_basis(&self) -> MatRef<'_, T> {
+		self.Z_basis.as_ref()
+	}
+
+	/// returns the householder coefficients of $Z$
+	pub fn Z_coeff(&self) -> MatRef<'_, T> {
+		self.Z_coeff.as_ref()
+	}
+
+	/// returns the lower triangular factor $L$
+	pub fn L(&self) -> MatRef<'_, T> {
+		self.L.as_ref()
+	}
+
+	/// returns the column pivoting permutation $P$
+	pub fn P(&self) -> PermRef<'_, usize> {
+		self.P.as_ref()
+	}
+
+	/// computes $A^+ B$, where $A^+$ is the pseudoinverse of the truncated decomposition. this is
+	/// the least squares solution of $A X = B$ with minimal norm
+	#[track_caller]
+	pub fn apply_pseudoinverse(&self, rhs: MatRef<'_, impl Conjugate<Canonical = T>>) -> Mat<T> {
+		assert!(rhs.nrows() == self.nrows());
+		let mut rhs = rhs.to_owned();
+		let mut out = Mat::zeros(self.ncols(), rhs.ncols());
+		self.apply_pseudoinverse_with_conj(Conj::No, out.as_mut(), rhs.as_mut());
+		out
+	}
+
+	/// returns the pseudoinverse of the truncated decomposition
+	pub fn pseudoinverse(&self) -> Mat<T> {
+		let mut rhs = Mat::identity(self.nrows(), self.nrows());
+		let mut out = Mat::zeros(self.ncols(), self.nrows());
+		self.apply_pseudoinverse_with_conj(Conj::No, out.as_mut(), rhs.as_mut());
+		out
+	}
+
+	/// stores $A^+ B$ in `out`, clobbering `rhs`
+	#[track_caller]
+	fn apply_pseudoinverse_with_conj(&self, conj: Conj, out: MatMut<'_, T>, rhs: MatMut<'_, T>) {
+		let par = get_global_parallelism();
+		let (m, n) = (self.nrows(), self.ncols());
+		let r = self.rank();
+		let k = rhs.ncols();
+		let mut out = out;
+		let mut rhs = rhs;
+
+		let mut mem = scratch(StackReq::any_of(&[
+			linalg::householder::apply_block_householder_sequence_transpose_on_the_left_in_place_scratch::<T>(m, self.Q_coeff.nrows(), k),
+			linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(n, self.Z_coeff.nrows(), k),
+			crate::perm::permute_rows_in_place_scratch::<usize, T>(n, k),
+		]));
+		let stack = mem.stack();
+
+		linalg::householder::apply_block_householder_sequence_transpose_on_the_left_in_place_with_conj(
+			self.Q_basis(),
+			self.Q_coeff(),
+			conj.compose(Conj::Yes),
+			rhs.rb_mut(),
+			par,
+			stack,
+		);
+
+		out.fill(zero());
+		if r > 0 {
+			out.rb_mut().get_mut(..r, ..).copy_from(rhs.rb().get(..r, ..));
+			linalg::triangular_solve::solve_lower_triangular_in_place_with_conj(self.L(), conj, out.rb_mut().get_mut(..r, ..), par);
+			linalg::householder::apply_block_householder_sequence_on_the_left_in_place_with_conj(
+				self.Z_basis(),
+				self.Z_coeff(),
+				conj,
+				out.rb_mut(),
+				par,
+				stack,
+			);
+		}
+		crate::perm::permute_rows_in_place(out, self.P().inverse(), stack);
+	}
+}
+
 impl<T: ComplexField> Svd<T> {
 	/// returns the svd of $A$
 	#[track_caller]
@@ -1116,7 +1568,7 @@ impl<T: ComplexField> Svd<T> {
 			Some(U.as_mut()),
 			Some(V.as_mut()),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::svd::svd_scratch::<T>(m, n, compute, compute, par, default()))),
+			scratch(linalg::svd::svd_scratch::<T>(m, n, compute, compute, par, default())).stack(),
 			default(),
 		)?;
 
@@ -1157,9 +1609,9 @@ impl<T: ComplexField> Svd<T> {
 		let V = self.V();
 		let S = self.S();
 		let par = get_global_parallelism();
-		let stack = &mut MemBuffer::new(linalg::svd::pseudoinverse_from_svd_scratch::<T>(self.nrows(), self.ncols(), par));
+		let mut mem = scratch(linalg::svd::pseudoinverse_from_svd_scratch::<T>(self.nrows(), self.ncols(), par));
 		let mut pinv = Mat::zeros(self.nrows(), self.ncols());
-		linalg::svd::pseudoinverse_from_svd(pinv.rb_mut(), S, U, V, par, MemStack::new(stack));
+		linalg::svd::pseudoinverse_from_svd(pinv.rb_mut(), S, U, V, par, mem.stack());
 		pinv
 	}
 }
@@ -1190,12 +1642,13 @@ impl<T: ComplexField> SelfAdjointEigen<T> {
 			S.as_mut(),
 			Some(U.as_mut()),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::evd::self_adjoint_evd_scratch::<T>(
+			scratch(linalg::evd::self_adjoint_evd_scratch::<T>(
 				n,
 				linalg::evd::ComputeEigenvectors::Yes,
 				par,
 				default(),
-			))),
+			))
+			.stack(),
 			default(),
 		)?;
 
@@ -1249,13 +1702,14 @@ impl<T: RealField> Eigen<T> {
 			None,
 			Some(U_real.as_mut()),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::evd::evd_scratch::<T>(
+			scratch(linalg::evd::evd_scratch::<T>(
 				n,
 				linalg::evd::ComputeEigenvectors::No,
 				linalg::evd::ComputeEigenvectors::Yes,
 				par,
 				default(),
-			))),
+			))
+			.stack(),
 			default(),
 		)?;
 
@@ -1302,13 +1756,14 @@ impl<T: RealField> Eigen<T> {
 			None,
 			Some(U.as_mut()),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::evd::evd_scratch::<Complex<T>>(
+			scratch(linalg::evd::evd_scratch::<Complex<T>>(
 				n,
 				linalg::evd::ComputeEigenvectors::No,
 				linalg::evd::ComputeEigenvectors::Yes,
 				par,
 				default(),
-			))),
+			))
+			.stack(),
 			default(),
 		)?;
 
@@ -1411,6 +1866,17 @@ impl<T: ComplexField> ShapeCore for ColPivQr<T> {
 		self.R().ncols()
 	}
 }
+impl<T: ComplexField> ShapeCore for Cod<T> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.Q_basis().nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.Z_basis().nrows()
+	}
+}
 impl<T: ComplexField> ShapeCore for Svd<T> {
 	#[inline]
 	fn nrows(&self) -> usize {
@@ -1450,12 +1916,12 @@ impl<T: ComplexField> SolveCore<T> for Llt<T> {
 	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
 		let par = get_global_parallelism();
 
-		let mut mem = MemBuffer::new(linalg::cholesky::llt::solve::solve_in_place_scratch::<T>(
+		let mut mem = scratch(linalg::cholesky::llt::solve::solve_in_place_scratch::<T>(
 			self.L.nrows(),
 			rhs.ncols(),
 			par,
 		));
-		let stack = MemStack::new(&mut mem);
+		let stack = mem.stack();
 
 		linalg::cholesky::llt::solve::solve_in_place_with_conj(self.L.as_ref(), conj, rhs, par, stack);
 	}
@@ -1464,12 +1930,12 @@ impl<T: ComplexField> SolveCore<T> for Llt<T> {
 	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
 		let par = get_global_parallelism();
 
-		let mut mem = MemBuffer::new(linalg::cholesky::llt::solve::solve_in_place_scratch::<T>(
+		let mut mem = scratch(linalg::cholesky::llt::solve::solve_in_place_scratch::<T>(
 			self.L.nrows(),
 			rhs.ncols(),
 			par,
 		));
-		let stack = MemStack::new(&mut mem);
+		let stack = mem.stack();
 
 		linalg::cholesky::llt::solve::solve_in_place_with_conj(self.L.as_ref(), conj.compose(Conj::Yes), rhs, par, stack);
 	}
@@ -1495,8 +1961,8 @@ impl<T: ComplexField> DenseSolveCore<T> for Llt<T> {
 		let n = self.L.nrows();
 		let mut out = Mat::zeros(n, n);
 
-		let mut mem = MemBuffer::new(linalg::cholesky::llt::reconstruct::reconstruct_scratch::<T>(n, par));
-		let stack = MemStack::new(&mut mem);
+		let mut mem = scratch(linalg::cholesky::llt::reconstruct::reconstruct_scratch::<T>(n, par));
+		let stack = mem.stack();
 
 		linalg::cholesky::llt::reconstruct::reconstruct(out.as_mut(), self.L(), par, stack);
 
@@ -1511,8 +1977,8 @@ impl<T: ComplexField> DenseSolveCore<T> for Llt<T> {
 		let n = self.L.nrows();
 		let mut out = Mat::zeros(n, n);
 
-		let mut mem = MemBuffer::new(linalg::cholesky::llt::inverse::inverse_scratch::<T>(n, par));
-		let stack = MemStack::new(&mut mem);
+		let mut mem = scratch(linalg::cholesky::llt::inverse::inverse_scratch::<T>(n, par));
+		let stack = mem.stack();
 
 		linalg::cholesky::llt::inverse::inverse(out.as_mut(), self.L(), par, stack);
 
@@ -1526,12 +1992,12 @@ impl<T: ComplexField> SolveCore<T> for Ldlt<T> {
 	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
 		let par = get_global_parallelism();
 
-		let mut mem = MemBuffer::new(linalg::cholesky::ldlt::solve::solve_in_place_scratch::<T>(
+		let mut mem = scratch(linalg::cholesky::ldlt::solve::solve_in_place_scratch::<T>(
 			self.L.nrows(),
 			rhs.ncols(),
 			par,
 		));
-		let stack = MemStack::new(&mut mem);
+		let stack = mem.stack();
 
 		linalg::cholesky::ldlt::solve::solve_in_place_with_conj(self.L.as_ref(), self.D.as_ref(), conj, rhs, par, stack);
 	}
@@ -1540,12 +2006,12 @@ impl<T: ComplexField> SolveCore<T> for Ldlt<T> {
 	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
 		let par = get_global_parallelism();
 
-		let mut mem = MemBuffer::new(linalg::cholesky::ldlt::solve::solve_in_place_scratch::<T>(
+		let mut mem = scratch(linalg::cholesky::ldlt::solve::solve_in_place_scratch::<T>(
 			self.L.nrows(),
 			rhs.ncols(),
 			par,
 		));
-		let stack = MemStack::new(&mut mem);
+		let stack = mem.stack();
 
 		linalg::cholesky::ldlt::solve::solve_in_place_with_conj(self.L(), self.D(), conj.compose(Conj::Yes), rhs, par, stack);
 	}
@@ -1559,8 +2025,8 @@ impl<T: ComplexField> DenseSolveCore<T> for Ldlt<T> {
 		let n = self.L.nrows();
 		let mut out = Mat::zeros(n, n);
 
-		let mut mem = MemBuffer::new(linalg::cholesky::ldlt::reconstruct::reconstruct_scratch::<T>(n, par));
-		let stack = MemStack::new(&mut mem);
+		let mut mem = scratch(linalg::cholesky::ldlt::reconstruct::reconstruct_scratch::<T>(n, par));
+		let stack = mem.stack();
 
 		linalg::cholesky::ldlt::reconstruct::reconstruct(out.as_mut(), self.L(), self.D(), par, stack);
 
@@ -1575,8 +2041,8 @@ impl<T: ComplexField> DenseSolveCore<T> for Ldlt<T> {
 		let n = self.L.nrows();
 		let mut out = Mat::zeros(n, n);
 
-		let mut mem = MemBuffer::new(linalg::cholesky::ldlt::inverse::inverse_scratch::<T>(n, par));
-		let stack = MemStack::new(&mut mem);
+		let mut mem = scratch(linalg::cholesky::ldlt::inverse::inverse_scratch::<T>(n, par));
+		let stack = mem.stack();
 
 		linalg::cholesky::ldlt::inverse::inverse(out.as_mut(), self.L(), self.D(), par, stack);
 
@@ -1590,12 +2056,12 @@ impl<T: ComplexField> SolveCore<T> for Lblt<T> {
 	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
 		let par = get_global_parallelism();
 
-		let mut mem = MemBuffer::new(linalg::cholesky::lblt::solve::solve_in_place_scratch::<usize, T>(
+		let mut mem = scratch(linalg::cholesky::lblt::solve::solve_in_place_scratch::<usize, T>(
 			self.L.nrows(),
 			rhs.ncols(),
 			par,
 		));
-		let stack = MemStack::new(&mut mem);
+		let stack = mem.stack();
 
 		linalg::cholesky::lblt::solve::solve_in_place_with_conj(self.L.as_ref(), self.B_diag(), self.B_subdiag(), conj, self.P(), rhs, par, stack);
 	}
@@ -1604,12 +2070,12 @@ impl<T: ComplexField> SolveCore<T> for Lblt<T> {
 	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
 		let par = get_global_parallelism();
 
-		let mut mem = MemBuffer::new(linalg::cholesky::lblt::solve::solve_in_place_scratch::<usize, T>(
+		let mut mem = scratch(linalg::cholesky::lblt::solve::solve_in_place_scratch::<usize, T>(
 			self.L.nrows(),
 			rhs.ncols(),
 			par,
 		));
-		let stack = MemStack::new(&mut mem);
+		let stack = mem.stack();
 
 		linalg::cholesky::lblt::solve::solve_in_place_with_conj(
 			self.L(),
@@ -1632,8 +2098,8 @@ impl<T: ComplexField> DenseSolveCore<T> for Lblt<T> {
 		let n = self.L.nrows();
 		let mut out = Mat::zeros(n, n);
 
-		let mut mem = MemBuffer::new(linalg::cholesky::lblt::reconstruct::reconstruct_scratch::<usize, T>(n, par));
-		let stack = MemStack::new(&mut mem);
+		let mut mem = scratch(linalg::cholesky::lblt::reconstruct::reconstruct_scratch::<usize, T>(n, par));
+		let stack = mem.stack();
 
 		linalg::cholesky::lblt::reconstruct::reconstruct(out.as_mut(), self.L(), self.B_diag(), self.B_subdiag(), self.P(), par, stack);
 
@@ -1648,8 +2114,8 @@ impl<T: ComplexField> DenseSolveCore<T> for Lblt<T> {
 		let n = self.L.nrows();
 		let mut out = Mat::zeros(n, n);
 
-		let mut mem = MemBuffer::new(linalg::cholesky::lblt::inverse::inverse_scratch::<usize, T>(n, par));
-		let stack = MemStack::new(&mut mem);
+		let mut mem = scratch(linalg::cholesky::lblt::inverse::inverse_scratch::<usize, T>(n, par));
+		let stack = mem.stack();
 
 		linalg::cholesky::lblt::inverse::inverse(out.as_mut(), self.L(), self.B_diag(), self.B_subdiag(), self.P(), par, stack);
 
@@ -1674,9 +2140,12 @@ impl<T: ComplexField> SolveCore<T> for PartialPivLu<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::lu::partial_pivoting::solve::solve_in_place_scratch::<usize, T>(self.nrows(), k, par),
-			)),
+			scratch(linalg::lu::partial_pivoting::solve::solve_in_place_scratch::<usize, T>(
+				self.nrows(),
+				k,
+				par,
+			))
+			.stack(),
 		);
 	}
 
@@ -1695,9 +2164,12 @@ impl<T: ComplexField> SolveCore<T> for PartialPivLu<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::lu::partial_pivoting::solve::solve_transpose_in_place_scratch::<usize, T>(self.nrows(), k, par),
-			)),
+			scratch(linalg::lu::partial_pivoting::solve::solve_transpose_in_place_scratch::<usize, T>(
+				self.nrows(),
+				k,
+				par,
+			))
+			.stack(),
 		);
 	}
 }
@@ -1716,10 +2188,7 @@ impl<T: ComplexField> DenseSolveCore<T> for PartialPivLu<T> {
 			self.U(),
 			self.P(),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::lu::partial_pivoting::reconstruct::reconstruct_scratch::<
-				usize,
-				T,
-			>(m, n, par))),
+			scratch(linalg::lu::partial_pivoting::reconstruct::reconstruct_scratch::<usize, T>(m, n, par)).stack(),
 		);
 
 		out
@@ -1741,9 +2210,7 @@ impl<T: ComplexField> DenseSolveCore<T> for PartialPivLu<T> {
 			self.U(),
 			self.P(),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::lu::partial_pivoting::inverse::inverse_scratch::<usize, T>(
-				n, par,
-			))),
+			scratch(linalg::lu::partial_pivoting::inverse::inverse_scratch::<usize, T>(n, par)).stack(),
 		);
 
 		out
@@ -1767,11 +2234,7 @@ impl<T: ComplexField> SolveCore<T> for FullPivLu<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::lu::full_pivoting::solve::solve_in_place_scratch::<usize, T>(
-				self.nrows(),
-				k,
-				par,
-			))),
+			scratch(linalg::lu::full_pivoting::solve::solve_in_place_scratch::<usize, T>(self.nrows(), k, par)).stack(),
 		);
 	}
 
@@ -1791,10 +2254,12 @@ impl<T: ComplexField> SolveCore<T> for FullPivLu<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::lu::full_pivoting::solve::solve_transpose_in_place_scratch::<
-				usize,
-				T,
-			>(self.nrows(), k, par))),
+			scratch(linalg::lu::full_pivoting::solve::solve_transpose_in_place_scratch::<usize, T>(
+				self.nrows(),
+				k,
+				par,
+			))
+			.stack(),
 		);
 	}
 }
@@ -1814,9 +2279,7 @@ impl<T: ComplexField> DenseSolveCore<T> for FullPivLu<T> {
 			self.P(),
 			self.Q(),
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::lu::full_pivoting::reconstruct::reconstruct_scratch::<usize, T>(m, n, par),
-			)),
+			scratch(linalg::lu::full_pivoting::reconstruct::reconstruct_scratch::<usize, T>(m, n, par)).stack(),
 		);
 
 		out
@@ -1839,9 +2302,7 @@ impl<T: ComplexField> DenseSolveCore<T> for FullPivLu<T> {
 			self.P(),
 			self.Q(),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::lu::full_pivoting::inverse::inverse_scratch::<usize, T>(
-				n, par,
-			))),
+			scratch(linalg::lu::full_pivoting::inverse::inverse_scratch::<usize, T>(n, par)).stack(),
 		);
 
 		out
@@ -1866,9 +2327,7 @@ impl<T: ComplexField> SolveCore<T> for Qr<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::no_pivoting::solve::solve_in_place_scratch::<T>(
-				n, blocksize, k, par,
-			))),
+			scratch(linalg::qr::no_pivoting::solve::solve_in_place_scratch::<T>(n, blocksize, k, par)).stack(),
 		);
 	}
 
@@ -1889,9 +2348,10 @@ impl<T: ComplexField> SolveCore<T> for Qr<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::qr::no_pivoting::solve::solve_transpose_in_place_scratch::<T>(n, blocksize, k, par),
-			)),
+			scratch(linalg::qr::no_pivoting::solve::solve_transpose_in_place_scratch::<T>(
+				n, blocksize, k, par,
+			))
+			.stack(),
 		);
 	}
 }
@@ -1915,9 +2375,7 @@ impl<T: ComplexField> SolveLstsqCore<T> for Qr<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::no_pivoting::solve::solve_lstsq_in_place_scratch::<T>(
-				m, n, blocksize, k, par,
-			))),
+			scratch(linalg::qr::no_pivoting::solve::solve_lstsq_in_place_scratch::<T>(m, n, blocksize, k, par)).stack(),
 		);
 	}
 }
@@ -1937,9 +2395,7 @@ impl<T: ComplexField> DenseSolveCore<T> for Qr<T> {
 			self.Q_coeff(),
 			self.R(),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::no_pivoting::reconstruct::reconstruct_scratch::<T>(
-				m, n, blocksize, par,
-			))),
+			scratch(linalg::qr::no_pivoting::reconstruct::reconstruct_scratch::<T>(m, n, blocksize, par)).stack(),
 		);
 
 		out
@@ -1960,9 +2416,7 @@ impl<T: ComplexField> DenseSolveCore<T> for Qr<T> {
 			self.Q_coeff(),
 			self.R(),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::no_pivoting::inverse::inverse_scratch::<T>(
-				n, blocksize, par,
-			))),
+			scratch(linalg::qr::no_pivoting::inverse::inverse_scratch::<T>(n, blocksize, par)).stack(),
 		);
 
 		out
@@ -1988,9 +2442,7 @@ impl<T: ComplexField> SolveCore<T> for ColPivQr<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::col_pivoting::solve::solve_in_place_scratch::<usize, T>(
-				n, blocksize, k, par,
-			))),
+			scratch(linalg::qr::col_pivoting::solve::solve_in_place_scratch::<usize, T>(n, blocksize, k, par)).stack(),
 		);
 	}
 
@@ -2012,10 +2464,10 @@ impl<T: ComplexField> SolveCore<T> for ColPivQr<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::col_pivoting::solve::solve_transpose_in_place_scratch::<
-				usize,
-				T,
-			>(n, blocksize, k, par))),
+			scratch(linalg::qr::col_pivoting::solve::solve_transpose_in_place_scratch::<usize, T>(
+				n, blocksize, k, par,
+			))
+			.stack(),
 		);
 	}
 }
@@ -2040,10 +2492,10 @@ impl<T: ComplexField> SolveLstsqCore<T> for ColPivQr<T> {
 			conj,
 			rhs,
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::col_pivoting::solve::solve_lstsq_in_place_scratch::<
-				usize,
-				T,
-			>(m, n, blocksize, k, par))),
+			scratch(linalg::qr::col_pivoting::solve::solve_lstsq_in_place_scratch::<usize, T>(
+				m, n, blocksize, k, par,
+			))
+			.stack(),
 		);
 	}
 }
@@ -2064,9 +2516,10 @@ impl<T: ComplexField> DenseSolveCore<T> for ColPivQr<T> {
 			self.R(),
 			self.P(),
 			par,
-			MemStack::new(&mut MemBuffer::new(
-				linalg::qr::col_pivoting::reconstruct::reconstruct_scratch::<usize, T>(m, n, blocksize, par),
-			)),
+			scratch(linalg::qr::col_pivoting::reconstruct::reconstruct_scratch::<usize, T>(
+				m, n, blocksize, par,
+			))
+			.stack(),
 		);
 
 		out
@@ -2088,15 +2541,25 @@ impl<T: ComplexField> DenseSolveCore<T> for ColPivQr<T> {
 			self.R(),
 			self.P(),
 			par,
-			MemStack::new(&mut MemBuffer::new(linalg::qr::col_pivoting::inverse::inverse_scratch::<usize, T>(
-				n, blocksize, par,
-			))),
+			scratch(linalg::qr::col_pivoting::inverse::inverse_scratch::<usize, T>(n, blocksize, par)).stack(),
 		);
 
 		out
 	}
 }
 
+impl<T: ComplexField> SolveLstsqCore<T> for Cod<T> {
+	#[track_caller]
+	fn solve_lstsq_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(all(self.nrows() == rhs.nrows(), self.nrows() >= self.ncols(),));
+
+		let mut rhs = rhs;
+		let mut out = Mat::zeros(self.ncols(), rhs.ncols());
+		self.apply_pseudoinverse_with_conj(conj, out.as_mut(), rhs.rb_mut());
+		rhs.get_mut(..self.ncols(), ..).copy_from(&out);
+	}
+}
+
 impl<T: ComplexField> SolveCore<T> for Svd<T> {
 	#[track_caller]
 	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
@@ -2397,6 +2860,377 @@ impl<T: ComplexField> DenseSolveCore<T> for SelfAdjointEigen<T> {
 	}
 }
 
+/// factorization used by [`solve_spd`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpdSolveMethod {
+	/// $L L^\top$ decomposition, used when the matrix is numerically positive definite
+	Llt,
+	/// $LBL^\top$ decomposition with bunch-kaufman pivoting, used as a fallback when the $L L^\top$
+	/// decomposition fails
+	Lblt,
+}
+
+/// solution computed by [`solve_spd`]
+#[derive(Clone, Debug)]
+pub struct SpdSolution<T> {
+	/// solution of the linear system
+	pub x: Mat<T>,
+	/// factorization that was used to compute `x`
+	pub method: SpdSolveMethod,
+}
+
+impl<T> SpdSolution<T> {
+	/// returns `true` if the matrix was not numerically positive definite, and the solution was
+	/// computed with the fallback factorization
+	#[inline]
+	pub fn is_fallback(&self) -> bool {
+		self.method != SpdSolveMethod::Llt
+	}
+}
+
+/// solves $A x = b$ for a matrix $A$ that is expected to be self-adjoint positive definite, reading
+/// only its lower triangular half
+///
+/// the system is solved with an $L L^\top$ decomposition. if it fails because $A$ is not
+/// numerically positive definite (e.g., a covariance matrix with slightly negative eigenvalues due
+/// to rounding errors), it is solved with an $LBL^\top$ decomposition instead, which handles
+/// indefinite matrices. the factorization that was used is reported in the result
+///
+/// # panics
+///
+/// panics if $A$ is not square, or if the number of rows of $b$ doesn't match the dimension of $A$
+///
+/// # example
+/// ```
+/// use faer::linalg::solvers::{SpdSolveMethod, solve_spd};
+/// use faer::mat;
+///
+/// let A = mat![[4.0, 2.0], [2.0, 3.0]];
+/// let b = mat![[2.0], [1.0]];
+/// let sol = solve_spd(A.as_ref(), b.as_ref());
+/// assert!(sol.method == SpdSolveMethod::Llt);
+/// assert!((&A * &sol.x - &b).norm_max() < 1e-14);
+///
+/// // indefinite matrix
+/// let A = mat![[1.0, 2.0], [2.0, 1.0]];
+/// let sol = solve_spd(A.as_ref(), b.as_ref());
+/// assert!(sol.is_fallback());
+/// assert!((&A * &sol.x - &b).norm_max() < 1e-14);
+/// ```
+#[track_caller]
+pub fn solve_spd<T: ComplexField>(A: MatRef<'_, impl Conjugate<Canonical = T>>, rhs: MatRef<'_, impl Conjugate<Canonical = T>>) -> SpdSolution<T> {
+	assert!(all(A.nrows() == A.ncols(), rhs.nrows() == A.nrows()));
+
+	let mut x = rhs.to_owned();
+	let method = match Llt::new(A, Side::Lower) {
+		Ok(llt) => {
+			llt.solve_in_place(&mut x);
+			SpdSolveMethod::Llt
+		},
+		Err(_) => {
+			Lblt::new(A, Side::Lower).solve_in_place(&mut x);
+			SpdSolveMethod::Lblt
+		},
+	};
+
+	SpdSolution { x, method }
+}
+
+/// solves the block system $\begin{bmatrix} A & B \\ B^H & C \end{bmatrix} x = b$, where the
+/// block matrix is self-adjoint positive definite, reusing the $L L^H$ decomposition of $A$
+///
+/// the system is solved with schur complements: only $C - B^H A^{-1} B$ is factorized, and the
+/// full matrix is never formed. only the lower triangular half of $C$ is accessed. to solve
+/// several systems with the same blocks, or to keep adding rows and columns, use [`Llt::extend`]
+/// instead
+///
+/// # errors
+///
+/// returns an error if the schur complement is not positive definite, with the index of the
+/// failed pivot referring to the block matrix
+///
+/// # panics
+///
+/// panics if the dimensions of `B`, `C` and `rhs` don't match
+///
+/// # example
+/// ```
+/// use faer::linalg::solvers::{Llt, solve_block_spd};
+/// use faer::{Side, mat};
+///
+/// let A = mat![[4.0, 1.0], [1.0, 3.0]];
+/// let B = mat![[1.0], [0.5]];
+/// let C = mat![[2.0]];
+/// let rhs = mat![[1.0], [2.0], [3.0]];
+///
+/// let llt = Llt::new(A.as_ref(), Side::Lower).unwrap();
+/// let x = solve_block_spd(&llt, B.as_ref(), C.as_ref(), rhs.as_ref()).unwrap();
+///
+/// let M = mat![[4.0, 1.0, 1.0], [1.0, 3.0, 0.5], [1.0, 0.5, 2.0]];
+/// assert!((&M * &x - &rhs).norm_max() < 1e-14);
+/// ```
+#[track_caller]
+pub fn solve_block_spd<T: ComplexField>(
+	llt: &Llt<T>,
+	B: MatRef<'_, impl Conjugate<Canonical = T>>,
+	C: MatRef<'_, impl Conjugate<Canonical = T>>,
+	rhs: MatRef<'_, impl Conjugate<Canonical = T>>,
+) -> Result<Mat<T>, LltError> {
+	let L = llt.L();
+	let n = L.nrows();
+	let k = C.nrows();
+	assert!(all(B.nrows() == n, B.ncols() == k, C.ncols() == k, rhs.nrows() == n + k));
+	let par = get_global_parallelism();
+
+	let (W, L_S) = schur_complement_llt(L, B, C)?;
+
+	// forward substitution with [L, 0; W^H, L_S]
+	let mut x = rhs.to_owned();
+	let (mut x1, mut x2) = x.as_mut().split_at_row_mut(n);
+	linalg::triangular_solve::solve_lower_triangular_in_place(L, x1.rb_mut(), par);
+	linalg::matmul::matmul(x2.rb_mut(), Accum::Add, W.adjoint(), x1.rb(), neg(&one::<T>()), par);
+	linalg::triangular_solve::solve_lower_triangular_in_place(L_S.as_ref(), x2.rb_mut(), par);
+
+	// backward substitution with [L^H, W; 0, L_S^H]
+	linalg::triangular_solve::solve_upper_triangular_in_place(L_S.adjoint(), x2.rb_mut(), par);
+	linalg::matmul::matmul(x1.rb_mut(), Accum::Add, &W, x2.rb(), neg(&one::<T>()), par);
+	linalg::triangular_solve::solve_upper_triangular_in_place(L.adjoint(), x1.rb_mut(), par);
+
+	Ok(x)
+}
+
+/// solver for a low rank update $A + U C V^H$ of a matrix $A$, given a solver for $A$
+///
+/// the inverse of the updated matrix is represented implicitly with the woodbury identity
+/// $$(A + U C V^H)^{-1} = A^{-1} - A^{-1} U (I + C V^H A^{-1} U)^{-1} C V^H A^{-1},$$
+/// so that rank $k$ modifications of $A$ can be solved without refactorizing it. constructing the
+/// solver costs $k$ solves with $A$, and each solve then costs one solve with $A$ and $O(nk)$
+/// additional operations per right-hand side
+///
+/// the updated matrix is singular if and only if the $k \times k$ capacitance matrix
+/// $I + C V^H A^{-1} U$ is singular, in which case the solutions are not finite
+///
+/// the solver also implements [`LinOp`], whose `apply` applies the inverse of the updated matrix
+///
+/// # example
+/// ```
+/// use faer::linalg::solvers::{LowRankUpdate, PartialPivLu, Solve};
+/// use faer::mat;
+///
+/// let A = mat![[4.0, 1.0, 0.0], [1.0, 3.0, 1.0], [0.0, 1.0, 2.0]];
+/// let u = mat![[1.0], [0.0], [1.0]];
+/// let c = mat![[2.0]];
+///
+/// // solves with A + 2 u u^T, reusing the factorization of A
+/// let solver = LowRankUpdate::new(
+/// 	PartialPivLu::new(A.as_ref()),
+/// 	u.as_ref(),
+/// 	c.as_ref(),
+/// 	u.as_ref(),
+/// );
+/// let b = mat![[1.0], [2.0], [3.0]];
+/// let x = solver.solve(&b);
+///
+/// let updated = &A + &u * &c * u.transpose();
+/// assert!((&updated * &x - &b).norm_max() < 1e-14);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LowRankUpdate<T, S> {
+	inner: S,
+	C: Mat<T>,
+	V: Mat<T>,
+	// A^{-1} U
+	Z: Mat<T>,
+	// I + C V^H A^{-1} U
+	K: PartialPivLu<T>,
+}
+
+impl<T: ComplexField, S: SolveCore<T>> LowRankUpdate<T, S> {
+	/// returns a solver for $A + U C V^H$, where `inner` is a solver for $A$
+	///
+	/// # panics
+	///
+	/// panics if $A$ is not square, or if the dimensions of $U$ ($n \times k$), $C$ ($k \times l$)
+	/// and $V$ ($n \times l$) don't match
+	#[track_caller]
+	pub fn new(
+		inner: S,
+		U: MatRef<'_, impl Conjugate<Canonical = T>>,
+		C: MatRef<'_, impl Conjugate<Canonical = T>>,
+		V: MatRef<'_, impl Conjugate<Canonical = T>>,
+	) -> Self {
+		let n = inner.nrows();
+		let (k, l) = C.shape();
+		assert!(all(inner.ncols() == n, U.nrows() == n, U.ncols() == k, V.nrows() == n, V.ncols() == l));
+
+		let mut Z = U.to_owned();
+		inner.solve_in_place_with_conj(Conj::No, Z.as_mut());
+
+		let C = C.to_owned();
+		let V = V.to_owned();
+		let mut K = &C * (V.adjoint() * &Z);
+		for i in 0..k {
+			K[(i, i)] = add(&K[(i, i)], &one());
+		}
+		let K = PartialPivLu::new(K.as_ref());
+
+		Self { inner, C, V, Z, K }
+	}
+
+	/// returns the solver for the original matrix $A$
+	#[inline]
+	pub fn inner(&self) -> &S {
+		&self.inner
+	}
+
+	/// returns the rank of the update, i.e., the number of columns of $U$
+	#[inline]
+	pub fn rank(&self) -> usize {
+		self.Z.ncols()
+	}
+}
+
+impl<T: ComplexField, S: SolveCore<T>> ShapeCore for LowRankUpdate<T, S> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.Z.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.Z.nrows()
+	}
+}
+
+impl<T: ComplexField, S: SolveCore<T>> SolveCore<T> for LowRankUpdate<T, S> {
+	#[track_caller]
+	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		let par = get_global_parallelism();
+		let mut rhs = rhs;
+		let k = self.rank();
+		let l = self.C.ncols();
+		let m = rhs.ncols();
+
+		// y = A^{-1} b
+		self.inner.solve_in_place_with_conj(conj, rhs.rb_mut());
+
+		// w = K^{-1} C V^H y
+		let mut t = Mat::<T>::zeros(l, m);
+		linalg::matmul::matmul_with_conj(
+			t.as_mut(),
+			Accum::Replace,
+			self.V.transpose(),
+			Conj::Yes.compose(conj),
+			rhs.rb(),
+			Conj::No,
+			one(),
+			par,
+		);
+		let mut w = Mat::<T>::zeros(k, m);
+		linalg::matmul::matmul_with_conj(w.as_mut(), Accum::Replace, self.C.as_ref(), conj, t.as_ref(), Conj::No, one(), par);
+		self.K.solve_in_place_with_conj(conj, w.as_mut());
+
+		// x = y - Z w
+		linalg::matmul::matmul_with_conj(
+			rhs.rb_mut(),
+			Accum::Add,
+			self.Z.as_ref(),
+			conj,
+			w.as_ref(),
+			Conj::No,
+			neg(&one::<T>()),
+			par,
+		);
+	}
+
+	#[track_caller]
+	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		let par = get_global_parallelism();
+		let mut rhs = rhs;
+		let k = self.rank();
+		let l = self.C.ncols();
+		let m = rhs.ncols();
+
+		// w = C^T K^{-T} Z^T b
+		let mut t = Mat::<T>::zeros(k, m);
+		linalg::matmul::matmul_with_conj(t.as_mut(), Accum::Replace, self.Z.transpose(), conj, rhs.rb(), Conj::No, one(), par);
+		self.K.solve_transpose_in_place_with_conj(conj, t.as_mut());
+		let mut w = Mat::<T>::zeros(l, m);
+		linalg::matmul::matmul_with_conj(w.as_mut(), Accum::Replace, self.C.transpose(), conj, t.as_ref(), Conj::No, one(), par);
+
+		// x = A^{-T} (b - conj(V) w)
+		linalg::matmul::matmul_with_conj(
+			rhs.rb_mut(),
+			Accum::Add,
+			self.V.as_ref(),
+			Conj::Yes.compose(conj),
+			w.as_ref(),
+			Conj::No,
+			neg(&one::<T>()),
+			par,
+		);
+		self.inner.solve_transpose_in_place_with_conj(conj, rhs);
+	}
+}
+
+impl<T: ComplexField, S: SolveCore<T> + Sync + core::fmt::Debug> LinOp<T> for LowRankUpdate<T, S> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.Z.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.Z.nrows()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solve_in_place_with_conj(Conj::No, out);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solve_in_place_with_conj(Conj::Yes, out);
+	}
+}
+
+impl<T: ComplexField, S: SolveCore<T> + Sync + core::fmt::Debug> BiLinOp<T> for LowRankUpdate<T, S> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solve_transpose_in_place_with_conj(Conj::No, out);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solve_transpose_in_place_with_conj(Conj::Yes, out);
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -2458,6 +3292,8 @@ mod tests {
 
 		test_solver(A, A.partial_piv_lu());
 		test_solver(A, A.full_piv_lu());
+		assert!(A.partial_piv_lu().residual_norm(A) < 1e-14);
+		assert!(A.full_piv_lu().residual_norm(A) < 1e-14);
 		test_solver(A, A.qr());
 		test_solver(A, A.col_piv_qr());
 		test_solver(A, A.svd().unwrap());
@@ -2477,6 +3313,179 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_solve_spd() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 50;
+
+		let ref B = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let ref R = CwiseMatDistribution {
+			nrows: n,
+			ncols: 3,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0 * (n as f64));
+
+		let ref A = B * B.adjoint();
+		let sol = solve_spd(A.as_ref(), R.as_ref());
+		assert!(sol.method == SpdSolveMethod::Llt);
+		assert!(!sol.is_fallback());
+		assert!(A * &sol.x ~ R);
+
+		// rank deficient covariance matrix, shifted so that its smallest eigenvalues are negative
+		let ref C = B.get(.., ..n / 2) * B.get(.., ..n / 2).adjoint();
+		let ref A = C - Mat::<c64>::identity(n, n) * Scale(c64::new(1e-3, 0.0));
+		let sol = solve_spd(A.as_ref(), R.as_ref());
+		assert!(sol.method == SpdSolveMethod::Lblt);
+		assert!(A * &sol.x ~ R);
+
+		// only the lower triangular half is read
+		let mut A_lower = A.clone();
+		z!(&mut A_lower).for_each_triangular_upper(linalg::zip::Diag::Skip, |uz!(x)| *x = c64::new(f64::NAN, 0.0));
+		let sol = solve_spd(A_lower.as_ref(), R.as_ref().conjugate());
+		assert!(sol.is_fallback());
+		assert!(A * &sol.x ~ R.conjugate().to_owned());
+	}
+
+	#[test]
+	fn test_convenience_solve() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 50;
+
+		let ref B = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let ref R = CwiseMatDistribution {
+			nrows: n,
+			ncols: 3,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0 * (n as f64));
+
+		let x = B.solve_lu(R);
+		assert!(B * &x ~ R);
+		let x = B.conjugate().solve_lu(R);
+		assert!(B.conjugate() * &x ~ R);
+
+		let ref A = B * B.adjoint();
+		let x = A.solve_llt(Side::Lower, R).unwrap();
+		assert!(A * &x ~ R);
+		let x = A.conjugate().solve_llt(Side::Upper, R).unwrap();
+		assert!(A.conjugate() * &x ~ R);
+		assert!((-A).solve_llt(Side::Lower, R).is_err());
+
+		// the least squares solution has a residual orthogonal to the range of the matrix
+		let ref T = B.get(.., ..n / 2);
+		let x = T.solve_lstsq_qr(R);
+		assert!(x.shape() == (n / 2, 3));
+		let ref zero = Mat::<c64>::zeros(n / 2, 3);
+		assert!(T.adjoint() * (T * &x - R) ~ zero);
+		let x = T.conjugate().solve_lstsq_qr(R);
+		assert!(T.transpose() * (T.conjugate() * &x - R) ~ zero);
+
+		// the free functions from the prelude forward to the methods
+		assert!(super::solve_lu(B, R) == B.solve_lu(R));
+		assert!(super::solve_llt(A, Side::Lower, R).unwrap() == A.solve_llt(Side::Lower, R).unwrap());
+		assert!(super::solve_lstsq_qr(T, R) == T.solve_lstsq_qr(R));
+	}
+
+	#[test]
+	fn test_llt_extend() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (n, k) = (30, 7);
+
+		let ref G = CwiseMatDistribution {
+			nrows: n + 2 * k,
+			ncols: n + 2 * k,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let ref R = CwiseMatDistribution {
+			nrows: n + 2 * k,
+			ncols: 2,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let ref M = G * G.adjoint();
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0 * (n as f64));
+
+		// one-shot solve with the schur complement
+		let m = n + k;
+		let llt = Llt::new(M.get(..n, ..n), Side::Lower).unwrap();
+		let x = solve_block_spd(&llt, M.get(..n, n..m), M.get(n..m, n..m), R.get(..m, ..)).unwrap();
+		assert!(M.get(..m, ..m) * &x ~ R.get(..m, ..).to_owned());
+
+		// incremental updates, reading only the lower half of the new diagonal blocks
+		let mut M_lower = M.clone();
+		z!(&mut M_lower).for_each_triangular_upper(linalg::zip::Diag::Skip, |uz!(x)| *x = c64::new(f64::NAN, 0.0));
+
+		let mut llt = llt;
+		llt.extend(M.get(..n, n..m), M_lower.get(n..m, n..m)).unwrap();
+		llt.extend(M.get(..m, m..), M_lower.get(m.., m..)).unwrap();
+		assert!(llt.nrows() == n + 2 * k);
+
+		let full = Llt::new(M.as_ref(), Side::Lower).unwrap();
+		assert!(llt.L().to_owned() ~ full.L().to_owned());
+		assert!(llt.solve(R) ~ full.solve(R));
+
+		// indefinite schur complement
+		let mut llt = Llt::new(M.get(..n, ..n), Side::Lower).unwrap();
+		let C = Mat::<c64>::identity(k, k) * Scale(c64::new(-1.0, 0.0));
+		let err = llt.extend(M.get(..n, n..m), C.as_ref()).unwrap_err();
+		assert!(matches!(err, LltError::NonPositivePivot { index } if index == n));
+		assert!(llt.nrows() == n);
+		assert!(solve_block_spd(&llt, M.get(..n, n..m), C.as_ref(), R.get(..m, ..)).is_err());
+	}
+
+	#[test]
+	fn test_low_rank_update() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (n, k, l) = (40, 3, 5);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+
+		let A = CwiseMatDistribution { nrows: n, ncols: n, dist }.rand::<Mat<c64>>(rng);
+		let U = CwiseMatDistribution { nrows: n, ncols: k, dist }.rand::<Mat<c64>>(rng);
+		let C = CwiseMatDistribution { nrows: k, ncols: l, dist }.rand::<Mat<c64>>(rng);
+		let V = CwiseMatDistribution { nrows: n, ncols: l, dist }.rand::<Mat<c64>>(rng);
+		let ref R = CwiseMatDistribution { nrows: n, ncols: 2, dist }.rand::<Mat<c64>>(rng);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0 * (n as f64));
+
+		let ref M = &A + &U * &C * V.adjoint();
+		let solver = LowRankUpdate::new(A.partial_piv_lu(), U.as_ref(), C.as_ref(), V.as_ref());
+		assert!(solver.rank() == k);
+
+		assert!(M * solver.solve(R) ~ R);
+		assert!(M.conjugate() * solver.solve_conjugate(R) ~ R);
+		assert!(M.transpose() * solver.solve_transpose(R) ~ R);
+		assert!(M.adjoint() * solver.solve_adjoint(R) ~ R);
+
+		let mut out = Mat::<c64>::zeros(n, 2);
+		solver.apply(out.as_mut(), R.as_ref(), Par::Seq, scratch(StackReq::EMPTY).stack());
+		assert!(M * &out ~ R);
+		solver.adjoint_apply(out.as_mut(), R.as_ref(), Par::Seq, scratch(StackReq::EMPTY).stack());
+		assert!(M.adjoint() * &out ~ R);
+
+		// conjugated factors, with a reference to the inner solver
+		let lu = A.partial_piv_lu();
+		let solver = LowRankUpdate::new(&lu, U.conjugate(), C.as_ref(), V.conjugate());
+		let ref M = &A + U.conjugate() * &C * V.transpose();
+		assert!(M * solver.solve(R) ~ R);
+	}
+
 	#[test]
 	fn test_eigen_cplx() {
 		let rng = &mut StdRng::seed_from_u64(0);
@@ -2522,6 +3531,42 @@ mod tests {
 		assert!(evd.S().column_vector() ~ ColRef::from_slice(&e));
 	}
 
+	#[test]
+	fn test_cod() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+
+		for (m, n, rank) in [(40, 30, 12), (20, 35, 20), (30, 30, 30), (10, 15, 0)] {
+			let B = CwiseMatDistribution { nrows: m, ncols: rank, dist }.rand::<Mat<c64>>(rng);
+			let C = CwiseMatDistribution { nrows: rank, ncols: n, dist }.rand::<Mat<c64>>(rng);
+			let A = &B * &C;
+			let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+
+			let cod = A.cod();
+			assert!(cod.rank() == rank);
+
+			let svd = A.thin_svd().unwrap();
+			let S_pinv = Col::<c64>::from_fn(Ord::min(m, n), |i| {
+				if i < rank {
+					c64::new(1.0 / svd.S()[i].re, 0.0)
+				} else {
+					c64::new(0.0, 0.0)
+				}
+			});
+			let pinv = svd.V() * S_pinv.as_diagonal() * svd.U().adjoint();
+			assert!(cod.pseudoinverse() ~ pinv);
+
+			let rhs = CwiseMatDistribution { nrows: m, ncols: 3, dist }.rand::<Mat<c64>>(rng);
+			assert!(cod.apply_pseudoinverse(rhs.as_ref()) ~ &pinv * &rhs);
+			assert!(cod.apply_pseudoinverse(rhs.conjugate()) ~ &pinv * rhs.conjugate());
+
+			if m >= n {
+				assert!(cod.solve_lstsq(&rhs) ~ &pinv * &rhs);
+				assert!(cod.solve_conjugate_lstsq(&rhs) ~ pinv.conjugate() * &rhs);
+			}
+		}
+	}
+
 	#[test]
 	fn test_svd_solver_for_rectangular_matrix() {
 		#[rustfmt::skip]
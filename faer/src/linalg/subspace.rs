@@ -0,0 +1,195 @@
+//! orthonormal bases of the fundamental subspaces of a matrix
+//!
+//! the bases are computed from the svd $A = U S V^H$, which reveals the numerical rank $r$ of $A$
+//! as the number of singular values larger than a tolerance. the first $r$ columns of $U$ span the
+//! range of $A$, and the last $n - r$ columns of $V$ span its null space
+//!
+//! a set of orthonormal columns can also be completed to an orthonormal basis of the whole space
+//! with [`complete_orthonormal_basis`], which only needs a $QR$ decomposition
+//!
+//! # example
+//! ```
+//! use faer::linalg::subspace::null_space;
+//! use faer::mat;
+//!
+//! // the second column is twice the first one
+//! let A = mat![[1.0, 2.0, 0.0], [2.0, 4.0, 1.0]];
+//! let N = null_space(A.as_ref(), None).unwrap();
+//!
+//! assert!(N.rank == 2);
+//! assert!(N.basis.ncols() == 1);
+//! assert!((&A * &N.basis).norm_max() < 1e-14);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::linalg::solvers::{Qr, Svd, SvdError};
+
+/// orthonormal basis of a subspace, computed by [`null_space`] or [`range_space`]
+#[derive(Clone, Debug)]
+pub struct SubspaceBasis<T: ComplexField> {
+	/// matrix whose columns are an orthonormal basis of the subspace
+	pub basis: Mat<T>,
+	/// numerical rank of the original matrix
+	pub rank: usize,
+	/// tolerance that was used to determine the rank. singular values smaller than or equal to it
+	/// were treated as zero
+	pub tol: T::Real,
+}
+
+/// returns the default rank tolerance for a matrix of the given shape and largest singular value,
+/// $\max(m, n) \varepsilon \sigma_{\max}$
+#[math]
+fn default_tol<T: ComplexField>(nrows: usize, ncols: usize, smax: T::Real) -> T::Real {
+	eps::<T::Real>() * from_f64::<T::Real>(Ord::max(nrows, ncols) as f64) * smax
+}
+
+/// returns the numerical rank, and the tolerance used to compute it
+#[math]
+fn rank<T: ComplexField>(svd: &Svd<T>, nrows: usize, ncols: usize, tol: Option<T::Real>) -> (usize, T::Real) {
+	let s = svd.S().column_vector();
+	let smax = if s.nrows() == 0 { zero() } else { real(s[0]) };
+	let tol = match tol {
+		Some(tol) => tol,
+		None => default_tol::<T>(nrows, ncols, smax),
+	};
+
+	// singular values are sorted in nonincreasing order
+	let rank = s.iter().take_while(|s| real(**s) > tol).count();
+	(rank, tol)
+}
+
+/// computes an orthonormal basis of the null space of $A$, i.e., the vectors $x$ such that
+/// $A x = 0$
+///
+/// singular values smaller than or equal to `tol` are treated as zero. if `tol` is `None`, the
+/// tolerance $\max(m, n) \varepsilon \sigma_{\max}$ is used. the tolerance that was used is
+/// reported in the result
+#[track_caller]
+pub fn null_space<T: ComplexField>(A: MatRef<'_, impl Conjugate<Canonical = T>>, tol: Option<T::Real>) -> Result<SubspaceBasis<T>, SvdError> {
+	let (m, n) = A.shape();
+	let svd = Svd::new(A)?;
+	let (rank, tol) = rank(&svd, m, n, tol);
+
+	Ok(SubspaceBasis {
+		basis: svd.V().get(.., rank..).to_owned(),
+		rank,
+		tol,
+	})
+}
+
+/// computes an orthonormal basis of the range (column space) of $A$, i.e., the vectors $A x$
+///
+/// singular values smaller than or equal to `tol` are treated as zero. if `tol` is `None`, the
+/// tolerance $\max(m, n) \varepsilon \sigma_{\max}$ is used. the tolerance that was used is
+/// reported in the result
+#[track_caller]
+pub fn range_space<T: ComplexField>(A: MatRef<'_, impl Conjugate<Canonical = T>>, tol: Option<T::Real>) -> Result<SubspaceBasis<T>, SvdError> {
+	let (m, n) = A.shape();
+	let svd = Svd::new_thin(A)?;
+	let (rank, tol) = rank(&svd, m, n, tol);
+
+	Ok(SubspaceBasis {
+		basis: svd.U().get(.., ..rank).to_owned(),
+		rank,
+		tol,
+	})
+}
+
+/// extends the $k$ orthonormal columns of the $m \times k$ matrix $Q$ to an orthonormal basis of
+/// the whole space, and returns it as an $m \times m$ unitary matrix
+///
+/// the first $k$ columns of the result are the columns of $Q$, and the last $m - k$ columns are an
+/// orthonormal basis of their orthogonal complement. they are obtained from the householder $QR$
+/// decomposition of $Q$
+///
+/// # panics
+///
+/// panics if $k > m$
+#[track_caller]
+pub fn complete_orthonormal_basis<T: ComplexField>(Q: MatRef<'_, impl Conjugate<Canonical = T>>) -> Mat<T> {
+	let (m, k) = Q.shape();
+	assert!(k <= m);
+
+	let mut basis = Qr::new(Q).compute_Q();
+	basis.as_mut().subcols_mut(0, k).copy_from(Q.to_owned());
+	basis
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_subspaces() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+
+		for (m, n, r) in [(8, 5, 3), (5, 8, 3), (6, 6, 6), (4, 7, 0), (0, 3, 0)] {
+			let B = CwiseMatDistribution { nrows: m, ncols: r, dist }.rand::<Mat<c64>>(rng);
+			let C = CwiseMatDistribution { nrows: r, ncols: n, dist }.rand::<Mat<c64>>(rng);
+			let A = &B * &C;
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (Ord::max(m, n) as f64));
+
+			let N = null_space(A.as_ref(), None).unwrap();
+			assert!(N.rank == r);
+			assert!(N.basis.shape() == (n, n - r));
+			assert!(N.basis.adjoint() * &N.basis ~ Mat::<c64>::identity(n - r, n - r));
+			assert!(&A * &N.basis ~ Mat::<c64>::zeros(m, n - r));
+
+			let R = range_space(A.as_ref(), None).unwrap();
+			assert!(R.rank == r);
+			assert!(R.tol == N.tol);
+			assert!(R.basis.shape() == (m, r));
+			assert!(R.basis.adjoint() * &R.basis ~ Mat::<c64>::identity(r, r));
+			assert!(&R.basis * (R.basis.adjoint() * &A) ~ A);
+
+			// the conjugate of `A` has the conjugate subspaces
+			let N = null_space(A.conjugate(), None).unwrap();
+			assert!(N.rank == r);
+			assert!(A.conjugate() * &N.basis ~ Mat::<c64>::zeros(m, n - r));
+		}
+	}
+
+	#[test]
+	fn test_subspaces_tol() {
+		let A = Mat::<f64>::from_fn(4, 4, |i, j| if i == j { [4.0, 2.0, 1e-3, 1e-9][i] } else { 0.0 });
+
+		let N = null_space(A.as_ref(), None).unwrap();
+		assert!(N.rank == 4);
+		assert!(N.tol == 4.0 * 4.0 * f64::EPSILON);
+
+		let N = null_space(A.as_ref(), Some(1e-6)).unwrap();
+		assert!(all(N.rank == 3, N.tol == 1e-6, N.basis.ncols() == 1));
+		assert!(N.basis[(3, 0)].abs() == 1.0);
+
+		let R = range_space(A.as_ref(), Some(1e-2)).unwrap();
+		assert!(all(R.rank == 2, R.basis.ncols() == 2));
+		assert!(R.basis.get(2.., ..) == Mat::<f64>::zeros(2, 2));
+	}
+
+	#[test]
+	fn test_complete_orthonormal_basis() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+
+		for (m, k) in [(7, 3), (5, 5), (4, 0), (6, 1), (0, 0)] {
+			let A = CwiseMatDistribution { nrows: m, ncols: k, dist }.rand::<Mat<c64>>(rng);
+			let Q = range_space(A.as_ref(), None).unwrap().basis;
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+			let U = complete_orthonormal_basis(Q.as_ref());
+			assert!(U.shape() == (m, m));
+			assert!(U.get(.., ..k) == Q);
+			assert!(U.adjoint() * &U ~ Mat::<c64>::identity(m, m));
+
+			let U = complete_orthonormal_basis(Q.conjugate());
+			assert!(U.get(.., ..k) == Q.conjugate().to_owned());
+			assert!(U.adjoint() * &U ~ Mat::<c64>::identity(m, m));
+		}
+	}
+}
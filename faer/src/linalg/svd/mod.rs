@@ -15,6 +15,7 @@ use linalg::qr::no_pivoting::factor::QrParams;
 
 use crate::assert;
 use crate::internal_prelude::*;
+use linalg::progress::ProgressMonitor;
 
 /// bidiagonalization
 pub mod bidiag;
@@ -36,6 +37,8 @@ pub enum ComputeSvdVectors {
 pub enum SvdError {
 	/// reached max iterations
 	NoConvergence,
+	/// the progress monitor cancelled the computation
+	Cancelled,
 }
 
 /// svd tuning parameters
@@ -311,6 +314,9 @@ fn svd_imp<T: ComplexField>(
 		par: Par,
 		stack: &mut MemStack,
 	) -> Result<(), SvdError>,
+	stage: usize,
+	n_stages: usize,
+	progress: Option<&dyn ProgressMonitor>,
 	par: Par,
 	stack: &mut MemStack,
 	params: SvdParams,
@@ -331,7 +337,13 @@ fn svd_imp<T: ComplexField>(
 	let mut Hr = Hr.as_mat_mut();
 
 	bid.copy_from(matrix);
-	bidiag::bidiag_in_place(bid.rb_mut(), Hl.rb_mut(), Hr.rb_mut(), par, stack, params.bidiag.into());
+	{
+		crate::utils::trace::span!(DEBUG, "svd_bidiag", m = m, n = n);
+		bidiag::bidiag_in_place(bid.rb_mut(), Hl.rb_mut(), Hr.rb_mut(), par, stack, params.bidiag.into());
+	}
+	if linalg::progress::report(progress, stage + 1, n_stages) {
+		return Err(SvdError::Cancelled);
+	}
 
 	let (mut diag, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
 	let (mut subdiag, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
@@ -353,15 +365,21 @@ fn svd_imp<T: ComplexField>(
 		}
 	}
 
-	bidiag_svd(
-		diag.rb_mut(),
-		subdiag.rb_mut(),
-		v.rb().map(|_| ub.rb_mut()),
-		u.rb().map(|_| vb.rb_mut()),
-		params,
-		par,
-		stack,
-	)?;
+	{
+		crate::utils::trace::span!(DEBUG, "svd_bidiag_svd", n = n);
+		bidiag_svd(
+			diag.rb_mut(),
+			subdiag.rb_mut(),
+			v.rb().map(|_| ub.rb_mut()),
+			u.rb().map(|_| vb.rb_mut()),
+			params,
+			par,
+			stack,
+		)?;
+	}
+	if linalg::progress::report(progress, stage + 2, n_stages) {
+		return Err(SvdError::Cancelled);
+	}
 
 	{ s }.copy_from(diag);
 
@@ -401,6 +419,9 @@ fn compute_squareish_svd<T: ComplexField>(
 	s: ColMut<'_, T>,
 	u: Option<MatMut<'_, T>>,
 	v: Option<MatMut<'_, T>>,
+	stage: usize,
+	n_stages: usize,
+	progress: Option<&dyn ProgressMonitor>,
 	par: Par,
 	stack: &mut MemStack,
 	params: SvdParams,
@@ -412,12 +433,27 @@ fn compute_squareish_svd<T: ComplexField>(
 			unsafe { core::mem::transmute(u) },
 			unsafe { core::mem::transmute(v) },
 			compute_bidiag_real_svd::<T::Real>,
+			stage,
+			n_stages,
+			progress,
 			par,
 			stack,
 			params,
 		)
 	} else {
-		svd_imp::<T>(matrix, s, u, v, compute_bidiag_cplx_svd::<T>, par, stack, params)
+		svd_imp::<T>(
+			matrix,
+			s,
+			u,
+			v,
+			compute_bidiag_cplx_svd::<T>,
+			stage,
+			n_stages,
+			progress,
+			par,
+			stack,
+			params,
+		)
 	}
 }
 
@@ -481,7 +517,6 @@ pub fn svd_scratch<T: ComplexField>(
 ///
 /// the singular are stored in $S$, and the singular vectors in $U$ and $V$ such that the singular
 /// values are sorted in nonincreasing order
-#[math]
 pub fn svd<T: ComplexField>(
 	A: MatRef<'_, T>,
 	s: DiagMut<'_, T>,
@@ -490,6 +525,40 @@ pub fn svd<T: ComplexField>(
 	par: Par,
 	stack: &mut MemStack,
 	params: Spec<SvdParams, T>,
+) -> Result<(), SvdError> {
+	svd_with_progress_imp(A, s, u, v, par, stack, params, None)
+}
+
+/// computes the svd of $A$ like [`svd`], reporting its progress to `progress` after each stage of
+/// the computation
+///
+/// the stages are the optional $QR$ preconditioning of tall or wide matrices, the
+/// bidiagonalization, the bidiagonal svd and the computation of the singular vectors. if the
+/// monitor cancels the computation, [`SvdError::Cancelled`] is returned and the contents of the
+/// outputs are unspecified
+pub fn svd_with_progress<T: ComplexField>(
+	A: MatRef<'_, T>,
+	s: DiagMut<'_, T>,
+	u: Option<MatMut<'_, T>>,
+	v: Option<MatMut<'_, T>>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SvdParams, T>,
+	progress: &dyn ProgressMonitor,
+) -> Result<(), SvdError> {
+	svd_with_progress_imp(A, s, u, v, par, stack, params, Some(progress))
+}
+
+#[math]
+fn svd_with_progress_imp<T: ComplexField>(
+	A: MatRef<'_, T>,
+	s: DiagMut<'_, T>,
+	u: Option<MatMut<'_, T>>,
+	v: Option<MatMut<'_, T>>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SvdParams, T>,
+	progress: Option<&dyn ProgressMonitor>,
 ) -> Result<(), SvdError> {
 	let params = params.config;
 
@@ -497,6 +566,7 @@ pub fn svd<T: ComplexField>(
 	let size = Ord::min(m, n);
 	assert!(s.dim() == size);
 	let s = s.column_vector_mut();
+	crate::utils::trace::span!(DEBUG, "svd", m = m, n = n, compute_u = u.is_some(), compute_v = v.is_some());
 
 	if let Some(u) = u.rb() {
 		assert!(all(u.nrows() == A.nrows(), any(u.ncols() == A.nrows(), u.ncols() == size),));
@@ -538,7 +608,8 @@ pub fn svd<T: ComplexField>(
 	}
 
 	if m as f64 / n as f64 <= params.qr_ratio_threshold {
-		compute_squareish_svd(matrix, s, u.rb_mut(), v.rb_mut(), par, stack, params)?;
+		compute_squareish_svd(matrix, s, u.rb_mut(), v.rb_mut(), 0, 3, progress, par, stack, params)?;
+		_ = linalg::progress::report(progress, 3, 3);
 	} else {
 		let bs = linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(m, n);
 		let (mut qr, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, n, stack) };
@@ -550,6 +621,9 @@ pub fn svd<T: ComplexField>(
 			qr.copy_from(matrix.rb());
 			linalg::qr::no_pivoting::factor::qr_in_place(qr.rb_mut(), householder.rb_mut(), par, stack, params.qr.into());
 		}
+		if linalg::progress::report(progress, 1, 4) {
+			return Err(SvdError::Cancelled);
+		}
 
 		{
 			let (mut r, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
@@ -558,7 +632,18 @@ pub fn svd<T: ComplexField>(
 			z!(r.rb_mut(), qr.rb().submatrix(0, 0, n, n)).for_each_triangular_upper(linalg::zip::Diag::Include, |uz!(dst, src)| *dst = copy(*src));
 
 			// r = u s v
-			compute_squareish_svd(r.rb(), s, u.rb_mut().map(|u| u.submatrix_mut(0, 0, n, n)), v.rb_mut(), par, stack, params)?;
+			compute_squareish_svd(
+				r.rb(),
+				s,
+				u.rb_mut().map(|u| u.submatrix_mut(0, 0, n, n)),
+				v.rb_mut(),
+				1,
+				4,
+				progress,
+				par,
+				stack,
+				params,
+			)?;
 		}
 
 		// matrix = q u s v
@@ -577,6 +662,7 @@ pub fn svd<T: ComplexField>(
 				stack,
 			);
 		}
+		_ = linalg::progress::report(progress, 4, 4);
 	}
 
 	if do_transpose {
@@ -115,6 +115,67 @@ fn invert_unit_lower_triangular_impl<'N, T: ComplexField>(dst: MatMut<'_, T, Dim
 	linalg::triangular_solve::solve_unit_lower_triangular_in_place(src_br, dst_bl, par);
 }
 
+#[math]
+fn invert_lower_triangular_in_place_impl<'N, T: ComplexField>(mat: MatMut<'_, T, Dim<'N>, Dim<'N>>, unit: bool, par: Par) {
+	let N = mat.ncols();
+
+	if *N <= 2 {
+		let mut mat = mat;
+		match *N {
+			0 => {},
+			1 => {
+				let i0 = N.check(0);
+				if !unit {
+					let x00 = recip(mat[(i0, i0)]);
+					mat[(i0, i0)] = x00;
+				}
+			},
+			2 => {
+				let i0 = N.check(0);
+				let i1 = N.check(1);
+				if unit {
+					let x10 = -mat[(i1, i0)];
+					mat[(i1, i0)] = x10;
+				} else {
+					let x00 = recip(mat[(i0, i0)]);
+					let x11 = recip(mat[(i1, i1)]);
+					let x10 = -x11 * mat[(i1, i0)] * x00;
+
+					mat[(i0, i0)] = x00;
+					mat[(i1, i1)] = x11;
+					mat[(i1, i0)] = x10;
+				}
+			},
+			_ => unreachable!(),
+		}
+		return;
+	}
+
+	make_guard!(HEAD);
+	make_guard!(TAIL);
+	let mid = N.partition(N.checked_idx_inc(*N / 2), HEAD, TAIL);
+
+	let (mut tl, _, mut bl, mut br) = { mat.split_with_mut(mid, mid) };
+
+	// with A = [A00, 0; A10, A11], the bottom left block of the inverse is
+	// -A11^-1 A10 A00^-1, which is computed from the original diagonal blocks before they are
+	// overwritten with their inverses
+	if unit {
+		linalg::triangular_solve::solve_unit_lower_triangular_in_place(br.rb(), bl.rb_mut(), par);
+		linalg::triangular_solve::solve_unit_upper_triangular_in_place(tl.rb().transpose(), bl.rb_mut().transpose_mut(), par);
+	} else {
+		linalg::triangular_solve::solve_lower_triangular_in_place(br.rb(), bl.rb_mut(), par);
+		linalg::triangular_solve::solve_upper_triangular_in_place(tl.rb().transpose(), bl.rb_mut().transpose_mut(), par);
+	}
+	z!(bl).for_each(|uz!(x)| *x = -*x);
+
+	join_raw(
+		|par| invert_lower_triangular_in_place_impl(tl.rb_mut(), unit, par),
+		|par| invert_lower_triangular_in_place_impl(br.rb_mut(), unit, par),
+		par,
+	);
+}
+
 /// computes the inverse of the lower triangular matrix `src` (with implicit unit
 /// diagonal) and stores the strictly lower triangular part of the result to `dst`.
 ///
@@ -167,6 +228,66 @@ pub fn invert_upper_triangular<T: ComplexField>(dst: MatMut<'_, T>, src: MatRef<
 	invert_lower_triangular(dst.reverse_rows_and_cols_mut(), src.reverse_rows_and_cols(), par)
 }
 
+/// computes the inverse of the lower triangular matrix `mat` (with implicit unit diagonal) in
+/// place, overwriting its strictly lower triangular part
+///
+/// the diagonal and the strictly upper triangular part of `mat` are not accessed
+///
+/// # panics
+///
+/// panics if `mat` is not square
+#[track_caller]
+pub fn invert_unit_lower_triangular_in_place<T: ComplexField>(mat: MatMut<'_, T>, par: Par) {
+	Assert!(mat.nrows() == mat.ncols());
+
+	with_dim!(N, mat.nrows().unbound());
+
+	invert_lower_triangular_in_place_impl(mat.as_shape_mut(N, N).as_dyn_stride_mut(), true, par)
+}
+
+/// computes the inverse of the lower triangular matrix `mat` in place, overwriting its lower
+/// triangular part
+///
+/// the strictly upper triangular part of `mat` is not accessed
+///
+/// # panics
+///
+/// panics if `mat` is not square
+#[track_caller]
+pub fn invert_lower_triangular_in_place<T: ComplexField>(mat: MatMut<'_, T>, par: Par) {
+	Assert!(mat.nrows() == mat.ncols());
+
+	with_dim!(N, mat.nrows().unbound());
+
+	invert_lower_triangular_in_place_impl(mat.as_shape_mut(N, N).as_dyn_stride_mut(), false, par)
+}
+
+/// computes the inverse of the upper triangular matrix `mat` (with implicit unit diagonal) in
+/// place, overwriting its strictly upper triangular part
+///
+/// the diagonal and the strictly lower triangular part of `mat` are not accessed
+///
+/// # panics
+///
+/// panics if `mat` is not square
+#[track_caller]
+pub fn invert_unit_upper_triangular_in_place<T: ComplexField>(mat: MatMut<'_, T>, par: Par) {
+	invert_unit_lower_triangular_in_place(mat.reverse_rows_and_cols_mut(), par)
+}
+
+/// computes the inverse of the upper triangular matrix `mat` in place, overwriting its upper
+/// triangular part
+///
+/// the strictly lower triangular part of `mat` is not accessed
+///
+/// # panics
+///
+/// panics if `mat` is not square
+#[track_caller]
+pub fn invert_upper_triangular_in_place<T: ComplexField>(mat: MatMut<'_, T>, par: Par) {
+	invert_lower_triangular_in_place(mat.reverse_rows_and_cols_mut(), par)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -325,4 +446,64 @@ mod tests {
 			}
 		});
 	}
+
+	#[test]
+	fn test_invert_in_place() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		for n in (0..32).chain([63, 100, 257]) {
+			let mut a: Mat<f64> = crate::stats::CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.sample(rng);
+			// keep the matrix well conditioned so that both algorithms agree to high accuracy
+			a *= Scale(1.0 / (n as f64 + 1.0));
+			a += Mat::<f64>::identity(n, n);
+
+			for par in [Par::Seq, Par::rayon(4)] {
+				let mut expected = Mat::zeros(n, n);
+				let mut inv = a.clone();
+
+				invert_lower_triangular(expected.as_mut(), a.as_ref(), par);
+				invert_lower_triangular_in_place(inv.as_mut(), par);
+				for j in 0..n {
+					for i in 0..n {
+						let target = if i >= j { expected[(i, j)] } else { a[(i, j)] };
+						assert!((inv[(i, j)] - target).abs() <= 1e-10 * (1.0 + target.abs()));
+					}
+				}
+
+				let mut inv = a.clone();
+				invert_unit_lower_triangular(expected.as_mut(), a.as_ref(), par);
+				invert_unit_lower_triangular_in_place(inv.as_mut(), par);
+				for j in 0..n {
+					for i in 0..n {
+						let target = if i > j { expected[(i, j)] } else { a[(i, j)] };
+						assert!((inv[(i, j)] - target).abs() <= 1e-10 * (1.0 + target.abs()));
+					}
+				}
+
+				let mut inv = a.clone();
+				invert_upper_triangular(expected.as_mut(), a.as_ref(), par);
+				invert_upper_triangular_in_place(inv.as_mut(), par);
+				for j in 0..n {
+					for i in 0..n {
+						let target = if i <= j { expected[(i, j)] } else { a[(i, j)] };
+						assert!((inv[(i, j)] - target).abs() <= 1e-10 * (1.0 + target.abs()));
+					}
+				}
+
+				let mut inv = a.clone();
+				invert_unit_upper_triangular(expected.as_mut(), a.as_ref(), par);
+				invert_unit_upper_triangular_in_place(inv.as_mut(), par);
+				for j in 0..n {
+					for i in 0..n {
+						let target = if i < j { expected[(i, j)] } else { a[(i, j)] };
+						assert!((inv[(i, j)] - target).abs() <= 1e-10 * (1.0 + target.abs()));
+					}
+				}
+			}
+		}
+	}
 }
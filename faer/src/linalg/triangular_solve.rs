@@ -252,6 +252,7 @@ pub fn solve_lower_triangular_in_place_with_conj<T: ComplexField, N: Shape, K: S
 	make_guard!(K);
 	let N = rhs.nrows().bind(N);
 	let K = rhs.ncols().bind(K);
+	crate::utils::counters::count!(Solve, T, fma = *N * *N * *K / 2, elems = *N * *N / 2 + 2 * *N * *K);
 
 	solve_lower_triangular_in_place_imp(
 		triangular_lower.as_dyn_stride().as_shape(N, N),
@@ -292,6 +293,7 @@ pub fn solve_unit_lower_triangular_in_place_with_conj<T: ComplexField, N: Shape,
 	make_guard!(K);
 	let N = rhs.nrows().bind(N);
 	let K = rhs.ncols().bind(K);
+	crate::utils::counters::count!(Solve, T, fma = *N * *N * *K / 2, elems = *N * *N / 2 + 2 * *N * *K);
 
 	solve_unit_lower_triangular_in_place_imp(
 		triangular_unit_lower.as_dyn_stride().as_shape(N, N),
@@ -332,6 +334,7 @@ pub fn solve_upper_triangular_in_place_with_conj<T: ComplexField, N: Shape, K: S
 	make_guard!(K);
 	let N = rhs.nrows().bind(N);
 	let K = rhs.ncols().bind(K);
+	crate::utils::counters::count!(Solve, T, fma = *N * *N * *K / 2, elems = *N * *N / 2 + 2 * *N * *K);
 
 	solve_upper_triangular_in_place_imp(
 		triangular_upper.as_dyn_stride().as_shape(N, N),
@@ -372,6 +375,7 @@ pub fn solve_unit_upper_triangular_in_place_with_conj<T: ComplexField, N: Shape,
 	make_guard!(K);
 	let N = rhs.nrows().bind(N);
 	let K = rhs.ncols().bind(K);
+	crate::utils::counters::count!(Solve, T, fma = *N * *N * *K / 2, elems = *N * *N / 2 + 2 * *N * *K);
 
 	solve_unit_upper_triangular_in_place_imp(
 		triangular_unit_upper.as_dyn_stride().as_shape(N, N),
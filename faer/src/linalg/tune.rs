@@ -0,0 +1,474 @@
+//! cache-parameter autotuning
+//!
+//! the blocked kernels in `faer` rely on a few hard-coded thresholds (recursion cutoffs, size at
+//! which multithreading kicks in) that were chosen on a small set of machines. this module can
+//! benchmark a handful of candidates for some of these parameters on the current machine, and feed
+//! the winners into the default parameters of the corresponding algorithms
+//!
+//! the tuned parameters are the recursion threshold of the partial pivoting $LU$ factorization, the
+//! parallelism threshold of the full pivoting $LU$ factorization, and the global threading
+//! threshold of the matrix multiplication. the other thresholds keep their built-in values
+//!
+//! tuning can be triggered explicitly with [`tune`] and [`tune_matmul`], or automatically the first
+//! time default parameters are requested for a given scalar type, after calling
+//! [`set_tune_on_first_use`]. the results can be persisted with [`save`] and restored with
+//! [`load`], which keys them by the cpu model reported by [`cpu_model`]
+
+use crate::internal_prelude::*;
+use crate::linalg::lu::full_pivoting::factor::FullPivLuParams;
+use crate::linalg::lu::partial_pivoting::factor::PartialPivLuParams;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// tuned parameters for a given scalar type
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TunedParams {
+	/// size below which the partial pivoting $LU$ factorization switches to the unblocked
+	/// algorithm
+	pub lu_recursion_threshold: usize,
+	/// size of the trailing submatrix below which the full pivoting $LU$ factorization stops
+	/// using multiple threads
+	pub full_piv_lu_par_threshold: usize,
+}
+
+const KIND_COUNT: usize = 4;
+
+// 0 means that no value has been tuned
+static LU_RECURSION_THRESHOLD: [AtomicUsize; KIND_COUNT] = [const { AtomicUsize::new(0) }; KIND_COUNT];
+static FULL_PIV_LU_PAR_THRESHOLD: [AtomicUsize; KIND_COUNT] = [const { AtomicUsize::new(0) }; KIND_COUNT];
+static TUNE_ON_FIRST_USE: AtomicBool = AtomicBool::new(false);
+
+#[inline]
+fn kind<T: ComplexField>() -> Option<usize> {
+	if try_const! { T::IS_NATIVE_F32 } {
+		Some(0)
+	} else if try_const! { T::IS_NATIVE_F64 } {
+		Some(1)
+	} else if try_const! { T::IS_NATIVE_C32 } {
+		Some(2)
+	} else if try_const! { T::IS_NATIVE_C64 } {
+		Some(3)
+	} else {
+		None
+	}
+}
+
+/// returns the tuned parameters for the scalar type `T`, if any
+///
+/// only the native types `f32`, `f64`, `c32` and `c64` can be tuned
+#[inline]
+pub fn tuned_params<T: ComplexField>() -> Option<TunedParams> {
+	let kind = kind::<T>()?;
+	let lu_recursion_threshold = LU_RECURSION_THRESHOLD[kind].load(Ordering::Relaxed);
+	let full_piv_lu_par_threshold = FULL_PIV_LU_PAR_THRESHOLD[kind].load(Ordering::Relaxed);
+	if lu_recursion_threshold == 0 && full_piv_lu_par_threshold == 0 {
+		return None;
+	}
+
+	// a parameter that is missing from a loaded file keeps its built-in value
+	Some(TunedParams {
+		lu_recursion_threshold: if lu_recursion_threshold == 0 {
+			PartialPivLuParams::builtin().recursion_threshold
+		} else {
+			lu_recursion_threshold
+		},
+		full_piv_lu_par_threshold: if full_piv_lu_par_threshold == 0 {
+			FullPivLuParams::builtin().par_threshold
+		} else {
+			full_piv_lu_par_threshold
+		},
+	})
+}
+
+/// overrides the tuned parameters for the scalar type `T`
+///
+/// has no effect if `T` is not one of the native types `f32`, `f64`, `c32` or `c64`
+#[inline]
+pub fn set_tuned_params<T: ComplexField>(params: TunedParams) {
+	if let Some(kind) = kind::<T>() {
+		LU_RECURSION_THRESHOLD[kind].store(Ord::max(params.lu_recursion_threshold, 1), Ordering::Relaxed);
+		FULL_PIV_LU_PAR_THRESHOLD[kind].store(Ord::max(params.full_piv_lu_par_threshold, 1), Ordering::Relaxed);
+	}
+}
+
+/// clears the tuned parameters for all scalar types, restoring the built-in defaults
+pub fn clear() {
+	for x in LU_RECURSION_THRESHOLD.iter().chain(&FULL_PIV_LU_PAR_THRESHOLD) {
+		x.store(0, Ordering::Relaxed);
+	}
+	gemm::set_threading_threshold(gemm::DEFAULT_THREADING_THRESHOLD);
+}
+
+/// enables or disables tuning the first time default parameters are requested for a scalar type
+/// that hasn't been tuned yet
+///
+/// disabled by default. has no effect without the `std` feature
+pub fn set_tune_on_first_use(enable: bool) {
+	TUNE_ON_FIRST_USE.store(enable, Ordering::Relaxed);
+}
+
+/// returns the tuned parameters for `T`, running the tuner first if tuning on first use is
+/// enabled and `T` hasn't been tuned yet
+#[inline]
+pub(crate) fn params_or_tune<T: ComplexField>() -> Option<TunedParams> {
+	let params = tuned_params::<T>();
+	#[cfg(feature = "std")]
+	if params.is_none() && kind::<T>().is_some() && TUNE_ON_FIRST_USE.load(Ordering::Relaxed) {
+		return Some(tune::<T>());
+	}
+	params
+}
+
+#[cfg(feature = "std")]
+pub use imp::*;
+
+#[cfg(feature = "std")]
+mod imp {
+	use super::*;
+	#[cfg(feature = "rayon")]
+	use crate::linalg::lu::full_pivoting;
+	use crate::linalg::lu::partial_pivoting;
+	use dyn_stack::MemBuffer;
+	use std::string::String;
+	use std::time::{Duration, Instant};
+
+	const KIND_NAMES: [&str; KIND_COUNT] = ["f32", "f64", "c32", "c64"];
+
+	/// returns a string identifying the cpu model of the current machine
+	pub fn cpu_model() -> String {
+		#[cfg(target_arch = "x86_64")]
+		{
+			#[allow(unused_unsafe)]
+			let brand = unsafe {
+				use core::arch::x86_64::__cpuid;
+
+				if __cpuid(0x8000_0000).eax >= 0x8000_0004 {
+					let mut bytes = [0u8; 48];
+					for (i, leaf) in (0x8000_0002u32..=0x8000_0004).enumerate() {
+						let r = __cpuid(leaf);
+						for (j, reg) in [r.eax, r.ebx, r.ecx, r.edx].into_iter().enumerate() {
+							bytes[16 * i + 4 * j..][..4].copy_from_slice(&reg.to_le_bytes());
+						}
+					}
+					let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+					Some(String::from_utf8_lossy(&bytes[..len]).trim().into())
+				} else {
+					None
+				}
+			};
+			if let Some(brand) = brand {
+				return brand;
+			}
+		}
+		String::from(std::env::consts::ARCH)
+	}
+
+	fn best_time(mut f: impl FnMut()) -> Duration {
+		f();
+		let mut best = Duration::MAX;
+		for _ in 0..3 {
+			let now = Instant::now();
+			f();
+			best = Ord::min(best, now.elapsed());
+		}
+		best
+	}
+
+	fn test_mat<T: ComplexField>(nrows: usize, ncols: usize) -> Mat<T> {
+		// a cheap deterministic pseudo-random matrix, diagonally weighted so that pivoting is
+		// exercised without producing tiny pivots
+		let mut state = 0x2545_f491_4f6c_dd1du64;
+		Mat::from_fn(nrows, ncols, |i, j| {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			let x = (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5;
+			let x = if i == j { x + 2.0 } else { x };
+			from_f64::<T>(x)
+		})
+	}
+
+	/// benchmarks a few candidates for the tunable parameters of the scalar type `T`, installs the
+	/// fastest ones, and returns them
+	///
+	/// only the native types `f32`, `f64`, `c32` and `c64` can be tuned. for other types, the
+	/// built-in defaults are returned and nothing is installed
+	pub fn tune<T: ComplexField>() -> TunedParams {
+		let default = TunedParams {
+			lu_recursion_threshold: PartialPivLuParams::builtin().recursion_threshold,
+			full_piv_lu_par_threshold: FullPivLuParams::builtin().par_threshold,
+		};
+		let Some(_) = kind::<T>() else {
+			return default;
+		};
+
+		let params = TunedParams {
+			lu_recursion_threshold: tune_lu_recursion_threshold::<T>(default.lu_recursion_threshold),
+			full_piv_lu_par_threshold: tune_full_piv_lu_par_threshold::<T>(default.full_piv_lu_par_threshold),
+		};
+		set_tuned_params::<T>(params);
+		params
+	}
+
+	fn tune_lu_recursion_threshold<T: ComplexField>(default: usize) -> usize {
+		use partial_pivoting::factor::{lu_in_place, lu_in_place_scratch};
+
+		let n = 384;
+		let a = test_mat::<T>(n, n);
+		let mut lu = a.clone();
+		let perm = &mut *alloc::vec![0usize; n];
+		let perm_inv = &mut *alloc::vec![0usize; n];
+
+		let mut best = (Duration::MAX, default);
+		for recursion_threshold in [4, 8, 16, 32, 64] {
+			let params = PartialPivLuParams {
+				recursion_threshold,
+				..PartialPivLuParams::builtin()
+			};
+			let mut mem = MemBuffer::new(lu_in_place_scratch::<usize, T>(n, n, Par::Seq, params.into()));
+			let time = best_time(|| {
+				lu.copy_from(&a);
+				lu_in_place(lu.as_mut(), perm, perm_inv, Par::Seq, MemStack::new(&mut mem), params.into());
+			});
+			if time < best.0 {
+				best = (time, recursion_threshold);
+			}
+		}
+		best.1
+	}
+
+	/// finds the smallest size at which the parallel full pivoting $LU$ factorization beats the
+	/// sequential one. the threshold is passed through the parameters, so unlike
+	/// [`tune_matmul`], this doesn't modify any global state while benchmarking
+	fn tune_full_piv_lu_par_threshold<T: ComplexField>(default: usize) -> usize {
+		#[cfg(feature = "rayon")]
+		{
+			use full_pivoting::factor::{lu_in_place, lu_in_place_scratch};
+
+			let par = Par::rayon(0);
+			if par.degree() > 1 {
+				let params = FullPivLuParams {
+					par_threshold: 0,
+					..FullPivLuParams::builtin()
+				};
+				for n in [64, 96, 128, 192, 256, 384, 512] {
+					let a = test_mat::<T>(n, n);
+					let mut lu = a.clone();
+					let row_perm = &mut *alloc::vec![0usize; n];
+					let row_perm_inv = &mut *alloc::vec![0usize; n];
+					let col_perm = &mut *alloc::vec![0usize; n];
+					let col_perm_inv = &mut *alloc::vec![0usize; n];
+					let mut mem = MemBuffer::new(lu_in_place_scratch::<usize, T>(n, n, par, params.into()));
+
+					let mut time = |par: Par| {
+						best_time(|| {
+							lu.copy_from(&a);
+							lu_in_place(
+								lu.as_mut(),
+								row_perm,
+								row_perm_inv,
+								col_perm,
+								col_perm_inv,
+								par,
+								MemStack::new(&mut mem),
+								params.into(),
+							);
+						})
+					};
+					if time(par) < time(Par::Seq) {
+						return n * n;
+					}
+				}
+			}
+		}
+		default
+	}
+
+	/// benchmarks matrix multiplication at a few sizes to find the size at which multithreading
+	/// starts paying off, installs it as the global threading threshold, and returns it
+	///
+	/// the threshold is expressed as the product `m * n * k` of the matrix dimensions
+	///
+	/// the threshold is a global setting of the matrix multiplication backend, which has to be
+	/// lowered while benchmarking so that the small products actually run in parallel. matrix
+	/// multiplications running concurrently on other threads are affected by this, so this
+	/// function should be called while nothing else is running, e.g., at the start of the program
+	pub fn tune_matmul() -> usize {
+		#[cfg(feature = "rayon")]
+		{
+			let par = Par::rayon(0);
+			if par.degree() > 1 {
+				// restores the previous threshold if the benchmark panics
+				struct Restore(usize);
+				impl Drop for Restore {
+					fn drop(&mut self) {
+						gemm::set_threading_threshold(self.0);
+					}
+				}
+				let mut restore = Restore(gemm::get_threading_threshold());
+				gemm::set_threading_threshold(0);
+
+				for n in [32, 48, 64, 96, 128, 192, 256] {
+					let a = test_mat::<f64>(n, n);
+					let b = test_mat::<f64>(n, n);
+					let mut c = Mat::<f64>::zeros(n, n);
+
+					let seq = best_time(|| linalg::matmul::matmul(c.as_mut(), Accum::Replace, a.as_ref(), b.as_ref(), 1.0, Par::Seq));
+					let par = best_time(|| linalg::matmul::matmul(c.as_mut(), Accum::Replace, a.as_ref(), b.as_ref(), 1.0, par));
+					if par < seq {
+						restore.0 = n * n * n;
+						break;
+					}
+				}
+			}
+		}
+		gemm::get_threading_threshold()
+	}
+
+	/// writes the currently tuned parameters to `path`, keyed by the current cpu model
+	///
+	/// entries for other cpu models already present in the file are preserved
+	pub fn save(path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+		use std::fmt::Write;
+
+		let path = path.as_ref();
+		let model = cpu_model();
+
+		let mut out = String::new();
+		match std::fs::read_to_string(path) {
+			Ok(prev) => {
+				for line in prev.lines() {
+					if line.split('\t').next() != Some(model.as_str()) {
+						out.push_str(line);
+						out.push('\n');
+					}
+				}
+			},
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => {},
+			Err(e) => return Err(e),
+		}
+
+		for (kind, name) in KIND_NAMES.iter().enumerate() {
+			for (key, values) in [
+				("lu_recursion_threshold", &LU_RECURSION_THRESHOLD),
+				("full_piv_lu_par_threshold", &FULL_PIV_LU_PAR_THRESHOLD),
+			] {
+				let value = values[kind].load(Ordering::Relaxed);
+				if value != 0 {
+					writeln!(out, "{model}\t{name}\t{key}\t{value}").unwrap();
+				}
+			}
+		}
+		writeln!(out, "{model}\t-\tmatmul_threading_threshold\t{}", gemm::get_threading_threshold()).unwrap();
+
+		std::fs::write(path, out)
+	}
+
+	/// loads the tuned parameters for the current cpu model from `path`, and installs them
+	///
+	/// returns `false` if the file contains no entries for the current cpu model
+	pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<bool> {
+		let model = cpu_model();
+		let contents = std::fs::read_to_string(path)?;
+
+		let mut found = false;
+		for line in contents.lines() {
+			let mut fields = line.split('\t');
+			let (Some(line_model), Some(kind), Some(key), Some(value), None) =
+				(fields.next(), fields.next(), fields.next(), fields.next(), fields.next())
+			else {
+				return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed tuning entry"));
+			};
+			if line_model != model {
+				continue;
+			}
+			let value: usize = value
+				.parse()
+				.map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed tuning value"))?;
+
+			match (kind, key) {
+				("-", "matmul_threading_threshold") => gemm::set_threading_threshold(value),
+				(kind, key) => {
+					let values = match key {
+						"lu_recursion_threshold" => &LU_RECURSION_THRESHOLD,
+						"full_piv_lu_par_threshold" => &FULL_PIV_LU_PAR_THRESHOLD,
+						_ => continue,
+					};
+					if let Some(kind) = KIND_NAMES.iter().position(|&name| name == kind) {
+						values[kind].store(Ord::max(value, 1), Ordering::Relaxed);
+					}
+				},
+			}
+			found = true;
+		}
+		Ok(found)
+	}
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+	use super::*;
+	use crate::assert;
+
+	/// restores the tuned parameters of `f64` and the matmul threading threshold when dropped, so
+	/// that the tests don't leak their parameters into the rest of the suite
+	struct Restore {
+		lu_recursion_threshold: usize,
+		full_piv_lu_par_threshold: usize,
+		matmul_threading_threshold: usize,
+	}
+
+	impl Restore {
+		fn new() -> Self {
+			Self {
+				lu_recursion_threshold: LU_RECURSION_THRESHOLD[1].load(Ordering::Relaxed),
+				full_piv_lu_par_threshold: FULL_PIV_LU_PAR_THRESHOLD[1].load(Ordering::Relaxed),
+				matmul_threading_threshold: gemm::get_threading_threshold(),
+			}
+		}
+	}
+
+	impl Drop for Restore {
+		fn drop(&mut self) {
+			LU_RECURSION_THRESHOLD[1].store(self.lu_recursion_threshold, Ordering::Relaxed);
+			FULL_PIV_LU_PAR_THRESHOLD[1].store(self.full_piv_lu_par_threshold, Ordering::Relaxed);
+			gemm::set_threading_threshold(self.matmul_threading_threshold);
+		}
+	}
+
+	#[test]
+	fn test_save_load_roundtrip() {
+		let _restore = Restore::new();
+
+		// the built-in values, so that the tests running concurrently aren't affected
+		let params = TunedParams {
+			lu_recursion_threshold: PartialPivLuParams::builtin().recursion_threshold,
+			full_piv_lu_par_threshold: FullPivLuParams::builtin().par_threshold,
+		};
+		set_tuned_params::<f64>(params);
+		assert!(tuned_params::<f64>() == Some(params));
+		assert!(<PartialPivLuParams as Auto<f64>>::auto().recursion_threshold == params.lu_recursion_threshold);
+		assert!(<FullPivLuParams as Auto<f64>>::auto().par_threshold == params.full_piv_lu_par_threshold);
+		assert!(tuned_params::<crate::fx128>().is_none());
+
+		let path = std::env::temp_dir().join(std::format!("faer-tune-{}.txt", std::process::id()));
+		save(&path).unwrap();
+
+		LU_RECURSION_THRESHOLD[1].store(0, Ordering::Relaxed);
+		FULL_PIV_LU_PAR_THRESHOLD[1].store(0, Ordering::Relaxed);
+		assert!(tuned_params::<f64>().is_none());
+		assert!(load(&path).unwrap());
+		assert!(tuned_params::<f64>() == Some(params));
+		std::fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	#[ignore = "runs a benchmark"]
+	fn test_tune() {
+		let _restore = Restore::new();
+
+		let params = tune::<f64>();
+		assert!(tuned_params::<f64>() == Some(params));
+		assert!(<PartialPivLuParams as Auto<f64>>::auto().recursion_threshold == params.lu_recursion_threshold);
+		assert!(<FullPivLuParams as Auto<f64>>::auto().par_threshold == params.full_piv_lu_par_threshold);
+	}
+}
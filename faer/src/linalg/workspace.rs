@@ -0,0 +1,219 @@
+//! aggregation of the workspace requirements of a sequence of operations
+//!
+//! a [`ScratchPlan`] describes the operations that will be run one after the other on the same
+//! [`MemStack`], and computes the smallest [`StackReq`] that can serve all of them, so that the
+//! memory can be allocated once up front
+//!
+//! - [`ScratchPlan::step`] adds an operation whose workspace is released when it returns, and can
+//! be reused by the following operations
+//! - [`ScratchPlan::hold`] adds a buffer that is taken from the stack and stays alive for all the
+//! following operations, such as a temporary matrix created with [`temp_mat_zeroed`]
+//!
+//! # example
+//! ```
+//! use faer::dyn_stack::{MemBuffer, MemStack};
+//! use faer::linalg::lu::partial_pivoting::{factor, solve};
+//! use faer::linalg::temp_mat_zeroed;
+//! use faer::linalg::workspace::ScratchPlan;
+//! use faer::mat::AsMatMut;
+//! use faer::{Conj, Mat, Par};
+//!
+//! let n = 64;
+//! let k = 4;
+//! let par = Par::Seq;
+//!
+//! let plan = ScratchPlan::new()
+//! 	// the lu factors are stored in a temporary matrix
+//! 	.temp_mat::<f64>(n, n)
+//! 	.lu::<usize, f64>(n, n, par)
+//! 	.lu_solve::<usize, f64>(n, k, par);
+//!
+//! let mut mem = MemBuffer::new(plan.req());
+//! let stack = MemStack::new(&mut mem);
+//!
+//! let A = Mat::from_fn(n, n, |i, j| {
+//! 	if i == j {
+//! 		2.0
+//! 	} else {
+//! 		1.0 / (1.0 + i as f64 + j as f64)
+//! 	}
+//! });
+//! let mut B = Mat::from_fn(n, k, |i, j| (i + j) as f64);
+//!
+//! let (mut LU, stack) = temp_mat_zeroed::<f64, _, _>(n, n, stack);
+//! let mut LU = LU.as_mat_mut();
+//! LU.copy_from(&A);
+//!
+//! let perm = &mut *vec![0usize; n];
+//! let perm_inv = &mut *vec![0usize; n];
+//! let params = Default::default();
+//! let (_, p) = factor::lu_in_place(LU.as_mut(), perm, perm_inv, par, stack, params);
+//! solve::solve_in_place_with_conj(
+//! 	LU.as_ref(),
+//! 	LU.as_ref(),
+//! 	p,
+//! 	Conj::No,
+//! 	B.as_mut(),
+//! 	par,
+//! 	stack,
+//! );
+//! ```
+
+use crate::internal_prelude::*;
+use linalg::svd::ComputeSvdVectors;
+
+/// workspace requirements of a sequence of operations
+#[derive(Copy, Clone, Debug)]
+pub struct ScratchPlan {
+	held: StackReq,
+	peak: StackReq,
+}
+
+impl Default for ScratchPlan {
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl ScratchPlan {
+	/// returns an empty plan
+	#[inline]
+	pub fn new() -> Self {
+		Self {
+			held: StackReq::EMPTY,
+			peak: StackReq::EMPTY,
+		}
+	}
+
+	/// adds an operation with the given workspace requirements, which can be reused by the
+	/// following operations once it returns
+	#[inline]
+	pub fn step(self, req: StackReq) -> Self {
+		Self {
+			held: self.held,
+			peak: self.peak.or(self.held.and(req)),
+		}
+	}
+
+	/// adds a buffer with the given requirements, which stays alive for all the following
+	/// operations
+	#[inline]
+	pub fn hold(self, req: StackReq) -> Self {
+		Self {
+			held: self.held.and(req),
+			peak: self.peak,
+		}
+	}
+
+	/// adds a temporary matrix with the given dimensions, which stays alive for all the following
+	/// operations
+	#[inline]
+	pub fn temp_mat<T: ComplexField>(self, nrows: usize, ncols: usize) -> Self {
+		self.hold(temp_mat_scratch::<T>(nrows, ncols))
+	}
+
+	/// adds an $LU$ factorization with partial pivoting, with the default parameters
+	#[inline]
+	pub fn lu<I: Index, T: ComplexField>(self, nrows: usize, ncols: usize, par: Par) -> Self {
+		self.step(linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<I, T>(
+			nrows,
+			ncols,
+			par,
+			default(),
+		))
+	}
+
+	/// adds a linear solve using an $LU$ factorization with partial pivoting
+	#[inline]
+	pub fn lu_solve<I: Index, T: ComplexField>(self, dim: usize, rhs_ncols: usize, par: Par) -> Self {
+		self.step(linalg::lu::partial_pivoting::solve::solve_in_place_scratch::<I, T>(dim, rhs_ncols, par))
+	}
+
+	/// adds a cholesky factorization, with the default parameters
+	#[inline]
+	pub fn cholesky<T: ComplexField>(self, dim: usize, par: Par) -> Self {
+		self.step(linalg::cholesky::llt::factor::cholesky_in_place_scratch::<T>(dim, par, default()))
+	}
+
+	/// adds a linear solve using a cholesky factorization
+	#[inline]
+	pub fn cholesky_solve<T: ComplexField>(self, dim: usize, rhs_ncols: usize, par: Par) -> Self {
+		self.step(linalg::cholesky::llt::solve::solve_in_place_scratch::<T>(dim, rhs_ncols, par))
+	}
+
+	/// adds a $QR$ factorization with the given block size, with the default parameters
+	#[inline]
+	pub fn qr<T: ComplexField>(self, nrows: usize, ncols: usize, blocksize: usize, par: Par) -> Self {
+		self.step(linalg::qr::no_pivoting::factor::qr_in_place_scratch::<T>(
+			nrows,
+			ncols,
+			blocksize,
+			par,
+			default(),
+		))
+	}
+
+	/// adds a least squares solve using a $QR$ factorization with the given block size
+	#[inline]
+	pub fn qr_solve_lstsq<T: ComplexField>(self, nrows: usize, ncols: usize, blocksize: usize, rhs_ncols: usize, par: Par) -> Self {
+		self.step(linalg::qr::no_pivoting::solve::solve_lstsq_in_place_scratch::<T>(
+			nrows, ncols, blocksize, rhs_ncols, par,
+		))
+	}
+
+	/// adds a singular value decomposition, with the default parameters
+	#[inline]
+	pub fn svd<T: ComplexField>(self, nrows: usize, ncols: usize, compute_u: ComputeSvdVectors, compute_v: ComputeSvdVectors, par: Par) -> Self {
+		self.step(linalg::svd::svd_scratch::<T>(nrows, ncols, compute_u, compute_v, par, default()))
+	}
+
+	/// adds a self-adjoint eigendecomposition, with the default parameters
+	#[inline]
+	pub fn self_adjoint_evd<T: ComplexField>(self, dim: usize, compute_u: linalg::evd::ComputeEigenvectors, par: Par) -> Self {
+		self.step(linalg::evd::self_adjoint_evd_scratch::<T>(dim, compute_u, par, default()))
+	}
+
+	/// returns the workspace requirements of the whole sequence
+	#[inline]
+	pub fn req(&self) -> StackReq {
+		self.peak.or(self.held)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+
+	#[test]
+	fn test_plan() {
+		let a = StackReq::new::<f64>(100);
+		let b = StackReq::new::<f64>(50);
+		let c = StackReq::new::<f64>(30);
+
+		assert!(ScratchPlan::new().req() == StackReq::EMPTY);
+		// steps reuse the same memory
+		assert!(ScratchPlan::new().step(a).step(b).req() == a);
+		assert!(ScratchPlan::new().step(b).step(a).req() == a);
+		// held buffers are added to the later steps, but not to the earlier ones
+		assert!(ScratchPlan::new().step(a).hold(c).step(b).req() == a);
+		assert!(ScratchPlan::new().hold(c).step(a).step(b).req() == c.and(a));
+		assert!(ScratchPlan::new().step(b).hold(a).req() == a);
+
+		let n = 40;
+		let plan = ScratchPlan::new()
+			.temp_mat::<f64>(n, n)
+			.lu::<usize, f64>(n, n, Par::Seq)
+			.lu_solve::<usize, f64>(n, 3, Par::Seq)
+			.svd::<f64>(n, n, ComputeSvdVectors::Thin, ComputeSvdVectors::Thin, Par::Seq);
+		assert!(
+			plan.req()
+				== temp_mat_scratch::<f64>(n, n).and(StackReq::any_of(&[
+					linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<usize, f64>(n, n, Par::Seq, default()),
+					linalg::lu::partial_pivoting::solve::solve_in_place_scratch::<usize, f64>(n, 3, Par::Seq),
+					linalg::svd::svd_scratch::<f64>(n, n, ComputeSvdVectors::Thin, ComputeSvdVectors::Thin, Par::Seq, default()),
+				]))
+		);
+	}
+}
@@ -1,7 +1,9 @@
 use super::*;
 use crate::internal_prelude::*;
-use crate::into_range::IntoRange;
+use crate::into_range::{IntoRange, contiguous, strided};
 use crate::{Idx, IdxInc, assert, debug_assert};
+use core::iter::{Rev, StepBy};
+use core::ops::Range;
 
 impl<'a, R: Shape, C: Shape, T, Rs: Stride, Cs: Stride, RowRange: IntoRange<IdxInc<R>, Len<R>: 'a>, ColRange: IntoRange<IdxInc<C>, Len<C>: 'a>>
 	MatIndex<RowRange, ColRange> for MatRef<'a, T, R, C, Rs, Cs>
@@ -278,3 +280,116 @@ idx_impl!((usize), (usize));
 idx_impl!((usize), (Dim<'N>, 'N));
 idx_impl!((Dim<'M>, 'M), (usize));
 idx_impl!((Dim<'M>, 'M), (Dim<'N>, 'N));
+
+macro_rules! strided_impl {
+    ({$($gen: tt)*} $RowRange: ty => $row: ident, $ColRange: ty => $col: ident) => {
+        impl<'a, T, Rs: Stride, Cs: Stride $($gen)*> MatIndex<$RowRange, $ColRange> for MatRef<'a, T, usize, usize, Rs, Cs> {
+            type Target = MatRef<'a, T, usize, usize, isize, isize>;
+
+            #[track_caller]
+            #[inline]
+            fn get(this: Self, row: $RowRange, col: $ColRange) -> Self::Target {
+                let (row_start, nrows, row_step) = $row(row, this.nrows());
+                let (col_start, ncols, col_step) = $col(col, this.ncols());
+
+                let ptr = this.ptr_at(row_start, col_start);
+                unsafe {
+                    MatRef::from_raw_parts(
+                        ptr,
+                        nrows,
+                        ncols,
+                        this.row_stride().element_stride() * row_step,
+                        this.col_stride().element_stride() * col_step,
+                    )
+                }
+            }
+
+            #[track_caller]
+            #[inline]
+            unsafe fn get_unchecked(this: Self, row: $RowRange, col: $ColRange) -> Self::Target {
+                MatIndex::get(this, row, col)
+            }
+        }
+
+        impl<'a, T, Rs: Stride, Cs: Stride $($gen)*> MatIndex<$RowRange, $ColRange> for MatMut<'a, T, usize, usize, Rs, Cs> {
+            type Target = MatMut<'a, T, usize, usize, isize, isize>;
+
+            #[track_caller]
+            #[inline]
+            fn get(this: Self, row: $RowRange, col: $ColRange) -> Self::Target {
+                unsafe { MatIndex::get(this.into_const(), row, col).const_cast() }
+            }
+
+            #[track_caller]
+            #[inline]
+            unsafe fn get_unchecked(this: Self, row: $RowRange, col: $ColRange) -> Self::Target {
+                MatIndex::get(this, row, col)
+            }
+        }
+    };
+}
+
+macro_rules! strided_impls {
+    ($($S: ty),*) => {
+        $(
+            strided_impl!({, ColRange: IntoRange<usize>} $S => strided, ColRange => contiguous);
+            strided_impl!({, RowRange: IntoRange<usize>} RowRange => contiguous, $S => strided);
+        )*
+        strided_impls!(@product [$($S),*] [$($S),*]);
+    };
+    (@product [$($S: ty),*] $cols: tt) => {
+        $(strided_impls!(@row $S, $cols);)*
+    };
+    (@row $S: ty, [$($C: ty),*]) => {
+        $(strided_impl!({} $S => strided, $C => strided);)*
+    };
+}
+
+strided_impls!(
+	StepBy<Range<usize>>,
+	Rev<Range<usize>>,
+	StepBy<Rev<Range<usize>>>,
+	Rev<StepBy<Range<usize>>>
+);
+
+#[cfg(test)]
+mod tests {
+	use crate::{Mat, assert, col, mat, row};
+
+	#[test]
+	fn test_strided_index() {
+		let mut A = Mat::from_fn(5, 6, |i, j| (10 * i + j) as f64);
+
+		assert!(
+			A.get((0..5).step_by(2), ..)
+				== mat![
+					[0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+					[20.0, 21.0, 22.0, 23.0, 24.0, 25.0],
+					[40.0, 41.0, 42.0, 43.0, 44.0, 45.0]
+				]
+		);
+		assert!(A.get(1..3, (0..6).step_by(3)) == mat![[10.0, 13.0], [20.0, 23.0]]);
+		assert!(A.get((0..5).rev(), (1..6).step_by(2).rev()) == A.as_ref().reverse_rows().get(.., (1..6).step_by(2)).reverse_cols());
+		assert!(A.get((0..5).rev().step_by(3), (0..2).rev()) == mat![[41.0, 40.0], [11.0, 10.0]]);
+		assert!(A.get((2..2).step_by(4), 1..).shape() == (0, 5));
+		assert!(A.get((4..5).step_by(7), ..1) == mat![[40.0]]);
+
+		A.get_mut((0..5).step_by(2), (0..6).step_by(5)).fill(-1.0);
+		assert!(A.col(0) == col![-1.0, 10.0, -1.0, 30.0, -1.0]);
+		assert!(A.row(2) == row![-1.0, 21.0, 22.0, 23.0, 24.0, -1.0]);
+
+		let x = col![0.0, 1.0, 2.0, 3.0, 4.0];
+		assert!(x.get((1..5).step_by(2)) == col![1.0, 3.0]);
+		assert!(x.get((0..5).rev()) == col![4.0, 3.0, 2.0, 1.0, 0.0]);
+		let mut y = x.transpose().to_owned();
+		y.get_mut((0..5).rev().step_by(2)).fill(0.0);
+		assert!(y == row![0.0, 1.0, 0.0, 3.0, 0.0]);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_strided_index_out_of_bounds() {
+		let A = Mat::<f64>::zeros(5, 6);
+		_ = A.get((0..7).step_by(3), ..);
+	}
+}
@@ -1,4 +1,5 @@
 use super::{MatRef, *};
+use crate::col::AsColRef;
 use crate::internal_prelude::*;
 use crate::utils::bound::{Dim, Partition};
 use crate::{Conj, ContiguousFwd, Idx, IdxInc, unzip, zip};
@@ -197,6 +198,13 @@ impl<'a, T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride> MatMut<'
 		self.into_const().split_at(row, col)
 	}
 
+	#[inline]
+	#[track_caller]
+	/// see [`MatRef::split_quadrants`]
+	pub fn split_quadrants(self, row: IdxInc<Rows>, col: IdxInc<Cols>) -> super::Quadrants<MatRef<'a, T, usize, usize, RStride, CStride>> {
+		self.into_const().split_quadrants(row, col)
+	}
+
 	#[inline]
 	#[track_caller]
 	/// see [`MatRef::split_at_row`]
@@ -467,6 +475,38 @@ impl<'a, T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride> MatMut<'
 		self.into_const().par_row_partition(count)
 	}
 
+	#[inline]
+	#[track_caller]
+	/// see [`MatRef::tiles`]
+	pub fn tiles(
+		self,
+		tile_nrows: usize,
+		tile_ncols: usize,
+	) -> impl 'a + ExactSizeIterator + DoubleEndedIterator<Item = MatRef<'a, T, usize, usize, RStride, CStride>>
+	where
+		Rows: 'a,
+		Cols: 'a,
+	{
+		self.into_const().tiles(tile_nrows, tile_ncols)
+	}
+
+	#[inline]
+	#[track_caller]
+	#[cfg(feature = "rayon")]
+	/// see [`MatRef::par_tiles`]
+	pub fn par_tiles(
+		self,
+		tile_nrows: usize,
+		tile_ncols: usize,
+	) -> impl 'a + rayon::iter::IndexedParallelIterator<Item = MatRef<'a, T, usize, usize, RStride, CStride>>
+	where
+		T: Sync,
+		Rows: 'a,
+		Cols: 'a,
+	{
+		self.into_const().par_tiles(tile_nrows, tile_ncols)
+	}
+
 	#[inline]
 	/// see [`MatRef::try_as_col_major`]
 	pub fn try_as_col_major(self) -> Option<MatRef<'a, T, Rows, Cols, ContiguousFwd, CStride>> {
@@ -605,6 +645,29 @@ impl<
 		self.rb_mut()
 	}
 
+	/// returns a mutable reference to the element at the given index, or `None` if the index is
+	/// out of bounds
+	#[inline]
+	pub fn get_mut_checked(&mut self, row: usize, col: usize) -> Option<&mut T> {
+		let this = self.rb_mut();
+		let row = this.nrows().idx(row)?;
+		let col = this.ncols().idx(col)?;
+		Some(unsafe { this.at_mut_unchecked(row, col) })
+	}
+
+	/// writes `value` to the element at the given index, or hands it back if the index is out of
+	/// bounds
+	#[inline]
+	pub fn try_write(&mut self, row: usize, col: usize, value: T) -> Result<(), T> {
+		match self.get_mut_checked(row, col) {
+			Some(dst) => {
+				*dst = value;
+				Ok(())
+			},
+			None => Err(value),
+		}
+	}
+
 	/// copies the lower triangular half of `other`, including the diagonal, into `self`
 	#[inline]
 	#[track_caller]
@@ -745,6 +808,213 @@ impl<
 		}
 		z!(self.rb_mut().as_dyn_mut()).for_each(cloner::<T>(value));
 	}
+
+	/// replaces the elements of `self` that satisfy `pred` with `value`, and leaves the other
+	/// elements unchanged
+	///
+	/// # example
+	/// ```
+	/// use faer::mat;
+	///
+	/// let mut a = mat![[1.0, -2.0], [f64::NAN, 4.0]];
+	/// // threshold the negative entries, then fill in the missing ones
+	/// a.set_where(|&x| x < 0.0, 0.0);
+	/// a.set_where(|x| x.is_nan(), 1.0);
+	/// assert!(a == mat![[1.0, 0.0], [1.0, 4.0]]);
+	/// ```
+	#[inline]
+	pub fn set_where(&mut self, pred: impl FnMut(&T) -> bool, value: T)
+	where
+		T: Clone,
+	{
+		fn setter<T: Clone>(mut pred: impl FnMut(&T) -> bool, value: T) -> impl for<'a> FnMut(Last<&'a mut T>) {
+			#[inline]
+			move |x| {
+				if pred(x.0) {
+					*x.0 = value.clone()
+				}
+			}
+		}
+		z!(self.rb_mut().as_dyn_mut()).for_each(setter(pred, value));
+	}
+
+	/// copies the elements of `other` into `self` at the positions where `mask` is `true`, and
+	/// leaves the other elements unchanged
+	#[inline]
+	#[track_caller]
+	pub fn copy_from_where<RhsT: Conjugate<Canonical = T>>(
+		&mut self,
+		other: impl AsMatRef<T = RhsT, Rows = Rows, Cols = Cols>,
+		mask: impl AsMatRef<T = bool, Rows = Rows, Cols = Cols>,
+	) where
+		T: ComplexField,
+	{
+		let other = other.as_mat_ref();
+		let mask = mask.as_mat_ref();
+		let mut this = self.rb_mut();
+
+		assert!(all(
+			this.nrows() == other.nrows(),
+			this.ncols() == other.ncols(),
+			this.nrows() == mask.nrows(),
+			this.ncols() == mask.ncols(),
+		));
+		let (m, n) = this.shape();
+
+		make_guard!(M);
+		make_guard!(N);
+		let M = m.bind(M);
+		let N = n.bind(N);
+		let this = this.rb_mut().as_shape_mut(M, N).as_dyn_stride_mut();
+		let other = other.as_shape(M, N);
+		let mask = mask.as_shape(M, N).as_dyn_stride();
+		imp(this, other.canonical(), mask, Conj::get::<RhsT>());
+
+		pub fn imp<'M, 'N, T: ComplexField>(
+			this: MatMut<'_, T, Dim<'M>, Dim<'N>>,
+			other: MatRef<'_, T, Dim<'M>, Dim<'N>>,
+			mask: MatRef<'_, bool, Dim<'M>, Dim<'N>>,
+			conj_: Conj,
+		) {
+			match conj_ {
+				Conj::No => {
+					zip!(this, other, mask).for_each(|unzip!(dst, src, mask)| {
+						let x = if *mask { copy(src) } else { copy(dst) };
+						*dst = x;
+					});
+				},
+				Conj::Yes => {
+					zip!(this, other, mask).for_each(|unzip!(dst, src, mask)| {
+						let x = if *mask { conj(src) } else { copy(dst) };
+						*dst = x;
+					});
+				},
+			}
+		}
+	}
+
+	/// adds `row` to each row of `self`
+	#[inline]
+	#[track_caller]
+	pub fn add_row_broadcast<RhsT: Conjugate<Canonical = T>>(&mut self, row: impl AsRowRef<T = RhsT, Cols = Cols>)
+	where
+		T: ComplexField,
+	{
+		let row = row.as_row_ref();
+		let nrows = self.rb_mut().nrows();
+		broadcast_imp(self.rb_mut(), row.broadcast(nrows), BroadcastOp::Add);
+	}
+
+	/// subtracts `row` from each row of `self`
+	///
+	/// # example
+	/// ```
+	/// use faer::mat;
+	/// use faer::stats::row_mean;
+	///
+	/// let mut A = mat![[1.0, 2.0], [3.0, 6.0]];
+	/// let mut mean = faer::Row::zeros(2);
+	/// row_mean(mean.as_mut(), A.as_ref(), faer::stats::NanHandling::Propagate);
+	///
+	/// // center the columns of `A`
+	/// A.sub_row_broadcast(&mean);
+	/// assert!(A == mat![[-1.0, -2.0], [1.0, 2.0]]);
+	/// ```
+	#[inline]
+	#[track_caller]
+	pub fn sub_row_broadcast<RhsT: Conjugate<Canonical = T>>(&mut self, row: impl AsRowRef<T = RhsT, Cols = Cols>)
+	where
+		T: ComplexField,
+	{
+		let row = row.as_row_ref();
+		let nrows = self.rb_mut().nrows();
+		broadcast_imp(self.rb_mut(), row.broadcast(nrows), BroadcastOp::Sub);
+	}
+
+	/// adds `col` to each column of `self`
+	#[inline]
+	#[track_caller]
+	pub fn add_col_broadcast<RhsT: Conjugate<Canonical = T>>(&mut self, col: impl AsColRef<T = RhsT, Rows = Rows>)
+	where
+		T: ComplexField,
+	{
+		let col = col.as_col_ref();
+		let ncols = self.rb_mut().ncols();
+		broadcast_imp(self.rb_mut(), col.broadcast(ncols), BroadcastOp::Add);
+	}
+
+	/// subtracts `col` from each column of `self`
+	#[inline]
+	#[track_caller]
+	pub fn sub_col_broadcast<RhsT: Conjugate<Canonical = T>>(&mut self, col: impl AsColRef<T = RhsT, Rows = Rows>)
+	where
+		T: ComplexField,
+	{
+		let col = col.as_col_ref();
+		let ncols = self.rb_mut().ncols();
+		broadcast_imp(self.rb_mut(), col.broadcast(ncols), BroadcastOp::Sub);
+	}
+
+	/// multiplies the $j$-th column of `self` by `factors[j]`, i.e., computes $A D$ where $D$ is
+	/// the diagonal matrix with the entries of `factors`
+	#[inline]
+	#[track_caller]
+	pub fn scale_cols<RhsT: Conjugate<Canonical = T>>(&mut self, factors: impl AsRowRef<T = RhsT, Cols = Cols>)
+	where
+		T: ComplexField,
+	{
+		let factors = factors.as_row_ref();
+		let nrows = self.rb_mut().nrows();
+		broadcast_imp(self.rb_mut(), factors.broadcast(nrows), BroadcastOp::Mul);
+	}
+
+	/// multiplies the $i$-th row of `self` by `factors[i]`, i.e., computes $D A$ where $D$ is the
+	/// diagonal matrix with the entries of `factors`
+	#[inline]
+	#[track_caller]
+	pub fn scale_rows<RhsT: Conjugate<Canonical = T>>(&mut self, factors: impl AsColRef<T = RhsT, Rows = Rows>)
+	where
+		T: ComplexField,
+	{
+		let factors = factors.as_col_ref();
+		let ncols = self.rb_mut().ncols();
+		broadcast_imp(self.rb_mut(), factors.broadcast(ncols), BroadcastOp::Mul);
+	}
+}
+
+#[derive(Copy, Clone)]
+enum BroadcastOp {
+	Add,
+	Sub,
+	Mul,
+}
+
+#[track_caller]
+fn broadcast_imp<T: ComplexField, RhsT: Conjugate<Canonical = T>, Rows: Shape, Cols: Shape>(
+	this: MatMut<'_, T, Rows, Cols, impl Stride, impl Stride>,
+	other: MatRef<'_, RhsT, Rows, Cols, impl Stride, impl Stride>,
+	op: BroadcastOp,
+) {
+	fn imp<T: ComplexField>(this: MatMut<'_, T>, other: MatRef<'_, T>, conj_: Conj, op: BroadcastOp) {
+		// the contiguous dimension of `other` is the broadcast vector itself, which lets `zip!` use
+		// its vectorized path when `self` is stored in the same order
+		match (op, conj_) {
+			(BroadcastOp::Add, Conj::No) => zip!(this, other).for_each(|unzip!(dst, src)| *dst = add(dst, src)),
+			(BroadcastOp::Add, Conj::Yes) => zip!(this, other).for_each(|unzip!(dst, src)| *dst = add(dst, &conj(src))),
+			(BroadcastOp::Sub, Conj::No) => zip!(this, other).for_each(|unzip!(dst, src)| *dst = sub(dst, src)),
+			(BroadcastOp::Sub, Conj::Yes) => zip!(this, other).for_each(|unzip!(dst, src)| *dst = sub(dst, &conj(src))),
+			(BroadcastOp::Mul, Conj::No) => zip!(this, other).for_each(|unzip!(dst, src)| *dst = mul(dst, src)),
+			(BroadcastOp::Mul, Conj::Yes) => zip!(this, other).for_each(|unzip!(dst, src)| *dst = mul(dst, &conj(src))),
+		}
+	}
+
+	assert!(all(this.nrows() == other.nrows(), this.ncols() == other.ncols()));
+	imp(
+		this.as_dyn_mut().as_dyn_stride_mut(),
+		other.as_dyn().as_dyn_stride().canonical(),
+		Conj::get::<RhsT>(),
+		op,
+	);
 }
 
 impl<'a, T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride> MatMut<'a, T, Rows, Cols, RStride, CStride> {
@@ -784,6 +1054,13 @@ impl<'a, T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride> MatMut<'
 		unsafe { (a.const_cast(), b.const_cast(), c.const_cast(), d.const_cast()) }
 	}
 
+	#[inline]
+	#[track_caller]
+	/// see [`MatRef::split_quadrants`]
+	pub fn split_quadrants_mut(self, row: IdxInc<Rows>, col: IdxInc<Cols>) -> super::Quadrants<MatMut<'a, T, usize, usize, RStride, CStride>> {
+		self.split_at_mut(row, col).into()
+	}
+
 	#[inline]
 	#[track_caller]
 	/// see [`MatRef::split_at_row`]
@@ -1122,6 +1399,49 @@ impl<'a, T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride> MatMut<'
 		}
 	}
 
+	#[inline]
+	#[track_caller]
+	/// see [`MatRef::tiles`]
+	///
+	/// the tiles are disjoint, so they can be modified independently
+	pub fn tiles_mut(
+		self,
+		tile_nrows: usize,
+		tile_ncols: usize,
+	) -> impl 'a + ExactSizeIterator + DoubleEndedIterator<Item = MatMut<'a, T, usize, usize, RStride, CStride>>
+	where
+		Rows: 'a,
+		Cols: 'a,
+	{
+		self.into_const().tiles(tile_nrows, tile_ncols).map(|x| unsafe { x.const_cast() })
+	}
+
+	#[inline]
+	#[track_caller]
+	#[cfg(feature = "rayon")]
+	/// see [`MatRef::par_tiles`]
+	///
+	/// the tiles are disjoint, so they can be modified independently
+	pub fn par_tiles_mut(
+		self,
+		tile_nrows: usize,
+		tile_ncols: usize,
+	) -> impl 'a + rayon::iter::IndexedParallelIterator<Item = MatMut<'a, T, usize, usize, RStride, CStride>>
+	where
+		T: Send,
+		Rows: 'a,
+		Cols: 'a,
+	{
+		use rayon::prelude::*;
+		unsafe {
+			self.as_type::<SyncCell<T>>()
+				.into_const()
+				.par_tiles(tile_nrows, tile_ncols)
+				.map(|x| x.const_cast())
+				.map(|x| x.as_type())
+		}
+	}
+
 	#[inline]
 	/// see [`MatRef::split_first_row`]
 	pub fn split_first_row_mut(self) -> Option<(RowMut<'a, T, Cols, CStride>, MatMut<'a, T, usize, Cols, RStride, CStride>)> {
@@ -1506,6 +1826,7 @@ fn from_strided_row_major_slice_mut_assert(nrows: usize, ncols: usize, row_strid
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::assert;
 
 	#[test]
 	fn test_min() {
@@ -1534,4 +1855,102 @@ mod tests {
 		let mut empty: Mat<f64> = Mat::new();
 		assert_eq!(empty.as_mut().max(), None);
 	}
+
+	#[test]
+	fn test_masked() {
+		use crate::c64;
+
+		let mut A = mat![
+			[1.0, -5.0, f64::NAN],
+			[-4.0, 2.0, 9.0], //
+		];
+		A.set_where(|&x| x < 0.0, 0.0);
+		A.set_where(|x| x.is_nan(), -1.0);
+		assert!(A == mat![[1.0, 0.0, -1.0], [0.0, 2.0, 9.0]]);
+
+		let B = Mat::from_fn(5, 4, |i, j| c64::new(i as f64, j as f64));
+		let mask = Mat::from_fn(5, 4, |i, j| (i + 2 * j) % 3 == 0);
+
+		let mut C = Mat::<c64>::zeros(5, 4);
+		C.copy_from_where(&B, &mask);
+		assert!(C == Mat::from_fn(5, 4, |i, j| if mask[(i, j)] { B[(i, j)] } else { c64::ZERO }));
+
+		// row-major destination and conjugated source
+		let mut C = Mat::<c64>::zeros(4, 5);
+		C.as_mut().transpose_mut().copy_from_where(B.conjugate(), &mask);
+		assert!(C == Mat::from_fn(4, 5, |i, j| if mask[(j, i)] { B[(j, i)].conj() } else { c64::ZERO }));
+
+		let mut x = col![1.0, 2.0, 3.0];
+		x.set_where(|&x| x > 1.5, 0.0);
+		x.copy_from_where(col![4.0, 5.0, 6.0], col![false, false, true]);
+		assert!(x == col![1.0, 0.0, 6.0]);
+
+		let mut y = row![1.0, 2.0, 3.0];
+		y.set_where(|&x| x > 1.5, 0.0);
+		y.copy_from_where(row![4.0, 5.0, 6.0], row![false, false, true]);
+		assert!(y == row![1.0, 0.0, 6.0]);
+	}
+
+	#[test]
+	fn test_broadcast() {
+		use crate::stats::{NanHandling, col_mean, row_mean};
+		use crate::{c64, col, row};
+
+		let A = mat![
+			[1.0, 5.0, 3.0],
+			[4.0, 2.0, 9.0],
+			[7.0, 8.0, 6.0],
+			[0.0, 1.0, 2.0], //
+		];
+
+		let mut mean = Row::zeros(3);
+		row_mean(mean.as_mut(), A.as_ref(), NanHandling::Propagate);
+		let mut centered = A.clone();
+		centered.sub_row_broadcast(&mean);
+		assert!(centered == Mat::from_fn(4, 3, |i, j| A[(i, j)] - mean[j]));
+		centered.add_row_broadcast(mean.as_ref());
+		assert!(centered == A);
+
+		let mut mean = Col::zeros(4);
+		col_mean(mean.as_mut(), A.as_ref(), NanHandling::Propagate);
+		// row-major storage
+		let mut centered = A.transpose().to_owned();
+		let mut centered = centered.as_mut().transpose_mut();
+		centered.sub_col_broadcast(&mean);
+		assert!(centered == Mat::from_fn(4, 3, |i, j| A[(i, j)] - mean[i]));
+		centered.add_col_broadcast(&mean);
+		assert!(centered == A);
+
+		let mut scaled = A.clone();
+		scaled.scale_cols(row![2.0, -1.0, 0.5]);
+		scaled.scale_rows(col![1.0, 2.0, 3.0, 4.0]);
+		assert!(scaled == Mat::from_fn(4, 3, |i, j| A[(i, j)] * [2.0, -1.0, 0.5][j] * (i + 1) as f64));
+
+		let z = mat![[c64::new(1.0, 2.0), c64::new(-1.0, 0.5)], [c64::new(0.0, 1.0), c64::new(3.0, -2.0)]];
+		let d = col![c64::new(0.0, 1.0), c64::new(2.0, 1.0)];
+		let r = row![c64::new(1.0, -1.0), c64::new(0.5, 2.0)];
+
+		let mut out = z.clone();
+		out.scale_rows(d.conjugate());
+		assert!(out == Mat::from_fn(2, 2, |i, j| z[(i, j)] * d[i].conj()));
+
+		let mut out = z.clone();
+		out.sub_row_broadcast(r.conjugate());
+		assert!(out == Mat::from_fn(2, 2, |i, j| z[(i, j)] - r[j].conj()));
+
+		let mut out = z.clone();
+		out.add_col_broadcast(d.conjugate());
+		assert!(out == Mat::from_fn(2, 2, |i, j| z[(i, j)] + d[i].conj()));
+
+		let mut out = z.clone();
+		out.scale_cols(r.as_ref());
+		assert!(out == Mat::from_fn(2, 2, |i, j| z[(i, j)] * r[j]));
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_broadcast_mismatch() {
+		let mut A = Mat::<f64>::zeros(3, 2);
+		A.sub_col_broadcast(Col::<f64>::zeros(2));
+	}
 }
@@ -649,6 +649,13 @@ impl<T, Rows: Shape, Cols: Shape> Mat<T, Rows, Cols> {
 		self.as_ref().split_at(row, col)
 	}
 
+	#[inline]
+	#[track_caller]
+	/// see [`MatRef::split_quadrants`]
+	pub fn split_quadrants(&self, row: IdxInc<Rows>, col: IdxInc<Cols>) -> super::Quadrants<MatRef<'_, T, usize, usize>> {
+		self.as_ref().split_quadrants(row, col)
+	}
+
 	#[inline]
 	#[track_caller]
 	/// see [`MatRef::split_at_row`]
@@ -865,6 +872,24 @@ impl<T, Rows: Shape, Cols: Shape> Mat<T, Rows, Cols> {
 		self.as_ref().par_row_partition(count)
 	}
 
+	#[inline]
+	#[track_caller]
+	/// see [`MatRef::tiles`]
+	pub fn tiles(&self, tile_nrows: usize, tile_ncols: usize) -> impl '_ + ExactSizeIterator + DoubleEndedIterator<Item = MatRef<'_, T>> {
+		self.as_ref().tiles(tile_nrows, tile_ncols)
+	}
+
+	#[inline]
+	#[track_caller]
+	#[cfg(feature = "rayon")]
+	/// see [`MatRef::par_tiles`]
+	pub fn par_tiles(&self, tile_nrows: usize, tile_ncols: usize) -> impl '_ + rayon::iter::IndexedParallelIterator<Item = MatRef<'_, T>>
+	where
+		T: Sync,
+	{
+		self.as_ref().par_tiles(tile_nrows, tile_ncols)
+	}
+
 	#[inline]
 	/// see [`MatRef::try_as_col_major`]
 	pub fn try_as_col_major(&self) -> Option<MatRef<'_, T, Rows, Cols, ContiguousFwd>> {
@@ -968,6 +993,13 @@ impl<T, Rows: Shape, Cols: Shape> Mat<T, Rows, Cols> {
 		self.as_mut().split_at_mut(row, col)
 	}
 
+	#[inline]
+	#[track_caller]
+	/// see [`MatMut::split_quadrants_mut`]
+	pub fn split_quadrants_mut(&mut self, row: IdxInc<Rows>, col: IdxInc<Cols>) -> super::Quadrants<MatMut<'_, T, usize, usize>> {
+		self.as_mut().split_quadrants_mut(row, col)
+	}
+
 	#[inline]
 	#[track_caller]
 	/// see [`MatMut::split_at_row_mut`]
@@ -1184,6 +1216,24 @@ impl<T, Rows: Shape, Cols: Shape> Mat<T, Rows, Cols> {
 		self.as_mut().par_row_partition_mut(count)
 	}
 
+	#[inline]
+	#[track_caller]
+	/// see [`MatMut::tiles_mut`]
+	pub fn tiles_mut(&mut self, tile_nrows: usize, tile_ncols: usize) -> impl '_ + ExactSizeIterator + DoubleEndedIterator<Item = MatMut<'_, T>> {
+		self.as_mut().tiles_mut(tile_nrows, tile_ncols)
+	}
+
+	#[inline]
+	#[track_caller]
+	#[cfg(feature = "rayon")]
+	/// see [`MatMut::par_tiles_mut`]
+	pub fn par_tiles_mut(&mut self, tile_nrows: usize, tile_ncols: usize) -> impl '_ + rayon::iter::IndexedParallelIterator<Item = MatMut<'_, T>>
+	where
+		T: Send,
+	{
+		self.as_mut().par_tiles_mut(tile_nrows, tile_ncols)
+	}
+
 	#[inline]
 	/// see [`MatMut::split_first_row_mut`]
 	pub fn split_first_row_mut(&mut self) -> Option<(RowMut<'_, T, Cols>, MatMut<'_, T, usize, Cols>)> {
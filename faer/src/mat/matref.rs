@@ -328,6 +328,36 @@ impl<'a, T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride> MatRef<'
 		}
 	}
 
+	/// splits the matrix horizontally and vertically at the given index into four corners, like
+	/// [`MatRef::split_at`], but returns them as a [`Quadrants`](super::Quadrants) with named
+	/// fields
+	///
+	/// # example
+	/// ```
+	/// use faer::mat;
+	/// use faer::mat::Quadrants;
+	///
+	/// let a = mat![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+	/// let Quadrants {
+	/// 	top_left,
+	/// 	bottom_right,
+	/// 	..
+	/// } = a.split_quadrants(1, 2);
+	///
+	/// assert!(top_left == mat![[1.0, 2.0]]);
+	/// assert!(bottom_right == mat![[6.0]]);
+	/// ```
+	///
+	/// # panics
+	/// the function panics if any of the following conditions are violated:
+	/// * `row <= self.nrows()`
+	/// * `col <= self.ncols()`
+	#[inline]
+	#[track_caller]
+	pub fn split_quadrants(self, row: IdxInc<Rows>, col: IdxInc<Cols>) -> super::Quadrants<MatRef<'a, T, usize, usize, RStride, CStride>> {
+		self.split_at(row, col).into()
+	}
+
 	/// splits the matrix horizontally at the given row into two parts and returns an array of
 	/// each submatrix, in the following order:
 	/// * top
@@ -918,6 +948,68 @@ impl<'a, T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride> MatRef<'
 		self.transpose().par_col_partition(count).map(MatRef::transpose)
 	}
 
+	/// returns an iterator over the tiles of a grid that covers the matrix, where each tile has
+	/// `tile_nrows` rows and `tile_ncols` columns, except for the tiles in the last row or column
+	/// of the grid, which may be smaller
+	///
+	/// the tiles are visited in column-major order, so that the $k$-th tile is at position
+	/// `(k % r, k / r)` in the grid, where `r` is `nrows.div_ceil(tile_nrows)`
+	///
+	/// # panics
+	/// the function panics if `tile_nrows` or `tile_ncols` is zero
+	///
+	/// # example
+	/// ```
+	/// use faer::Mat;
+	///
+	/// let A = Mat::from_fn(5, 4, |i, j| (i + 10 * j) as f64);
+	/// let tiles = A.tiles(2, 3).collect::<Vec<_>>();
+	///
+	/// // 3 rows and 2 columns of tiles
+	/// assert!(tiles.len() == 6);
+	/// assert!(tiles[2].shape() == (1, 3));
+	/// assert!(tiles[3] == A.submatrix(0, 3, 2, 1));
+	/// ```
+	#[inline]
+	#[track_caller]
+	pub fn tiles(
+		self,
+		tile_nrows: usize,
+		tile_ncols: usize,
+	) -> impl 'a + ExactSizeIterator + DoubleEndedIterator<Item = MatRef<'a, T, usize, usize, RStride, CStride>>
+	where
+		Rows: 'a,
+		Cols: 'a,
+	{
+		let this = self.as_dyn();
+		let count = tile_count(this.nrows(), this.ncols(), tile_nrows, tile_ncols);
+		(0..count).map(move |k| tile_at(this, tile_nrows, tile_ncols, k))
+	}
+
+	/// returns a parallel iterator over the tiles of a grid that covers the matrix, in the same
+	/// order as [`MatRef::tiles`]
+	///
+	/// only available with the `rayon` feature
+	#[inline]
+	#[track_caller]
+	#[cfg(feature = "rayon")]
+	pub fn par_tiles(
+		self,
+		tile_nrows: usize,
+		tile_ncols: usize,
+	) -> impl 'a + rayon::iter::IndexedParallelIterator<Item = MatRef<'a, T, usize, usize, RStride, CStride>>
+	where
+		T: Sync,
+		Rows: 'a,
+		Cols: 'a,
+	{
+		use rayon::prelude::*;
+
+		let this = self.as_dyn();
+		let count = tile_count(this.nrows(), this.ncols(), tile_nrows, tile_ncols);
+		(0..count).into_par_iter().map(move |k| tile_at(this, tile_nrows, tile_ncols, k))
+	}
+
 	/// returns a reference to the first row and a view over the remaining ones if the matrix has
 	/// at least one row, otherwise `None`
 	#[inline]
@@ -1024,6 +1116,9 @@ impl<'a, T, Rows: Shape, Cols: Shape, RStride: Stride, CStride: Stride> MatRef<'
 	/// returns references to the element at the given index, or submatrices if either `row`
 	/// or `col` is a range, with bound checks
 	///
+	/// for matrices with dynamic dimensions, `row` and `col` can also be stepped or reversed
+	/// ranges, such as `(0..n).step_by(2)` or `(0..n).rev()`, which return strided views
+	///
 	/// # panics
 	/// the function panics if any of the following conditions are violated:
 	/// * `row` must be contained in `[0, self.nrows())`
@@ -1102,6 +1197,34 @@ impl<
 		self.rb()
 	}
 
+	/// returns a reference to the element at the given index, or `None` if the index is out of
+	/// bounds
+	///
+	/// # example
+	/// ```
+	/// use faer::mat;
+	///
+	/// let a = mat![[1.0, 2.0], [3.0, 4.0]];
+	/// assert!(a.get_checked(1, 0) == Some(&3.0));
+	/// assert!(a.get_checked(2, 0) == None);
+	/// ```
+	#[inline]
+	pub fn get_checked(&self, row: usize, col: usize) -> Option<&T> {
+		let this = self.rb();
+		let row = this.nrows().idx(row)?;
+		let col = this.ncols().idx(col)?;
+		Some(unsafe { this.at_unchecked(row, col) })
+	}
+
+	/// returns a clone of the element at the given index, or `None` if the index is out of bounds
+	#[inline]
+	pub fn try_read(&self, row: usize, col: usize) -> Option<T>
+	where
+		T: Clone,
+	{
+		self.get_checked(row, col).cloned()
+	}
+
 	/// returns a newly allocated matrix holding the cloned values of `self`
 	#[inline]
 	pub fn cloned(&self) -> Mat<T, Rows, Cols>
@@ -1142,6 +1265,36 @@ impl<
 		imp(this.as_shape(M, N)).into_shape(this.nrows(), this.ncols())
 	}
 
+	/// returns a newly allocated column-major matrix holding the (possibly conjugated) values of
+	/// `self`
+	///
+	/// this is equivalent to [`Self::to_owned`], but uses a cache blocked copy when `self` is
+	/// row-major. see [`linalg::relayout`](crate::linalg::relayout)
+	#[inline]
+	pub fn to_col_major(&self) -> Mat<T::Canonical, Rows, Cols>
+	where
+		T: Conjugate,
+	{
+		let this = self.rb();
+		let mut out = Mat::zeros(this.nrows(), this.ncols());
+		linalg::relayout::relayout_copy(out.as_mut().as_dyn_mut(), this.as_dyn().as_dyn_stride());
+		out
+	}
+
+	/// returns a newly allocated matrix holding the (possibly conjugated) values of `self` in
+	/// row-major order
+	///
+	/// since owned matrices are always column-major, the result is returned as the storage of the
+	/// transpose: `self.to_row_major().transpose()` is a row-major view with the same values as
+	/// `self`. see [`linalg::relayout`](crate::linalg::relayout)
+	#[inline]
+	pub fn to_row_major(&self) -> Mat<T::Canonical, Cols, Rows>
+	where
+		T: Conjugate,
+	{
+		self.rb().transpose().to_col_major()
+	}
+
 	/// returns the maximum norm of `self`
 	#[inline]
 	pub fn norm_max(&self) -> Real<T>
@@ -1493,9 +1646,32 @@ where
 	}
 }
 
+/// number of tiles in a grid covering an `nrows * ncols` matrix
+#[inline]
+#[track_caller]
+fn tile_count(nrows: usize, ncols: usize, tile_nrows: usize, tile_ncols: usize) -> usize {
+	assert!(all(tile_nrows > 0, tile_ncols > 0));
+	nrows.msrv_div_ceil(tile_nrows) * ncols.msrv_div_ceil(tile_ncols)
+}
+
+/// `k`-th tile of the grid, in column-major order
+#[inline]
+fn tile_at<T, RStride: Stride, CStride: Stride>(
+	this: MatRef<'_, T, usize, usize, RStride, CStride>,
+	tile_nrows: usize,
+	tile_ncols: usize,
+	k: usize,
+) -> MatRef<'_, T, usize, usize, RStride, CStride> {
+	let ntile_rows = this.nrows().msrv_div_ceil(tile_nrows);
+	let i = k % ntile_rows * tile_nrows;
+	let j = k / ntile_rows * tile_ncols;
+	this.submatrix(i, j, Ord::min(tile_nrows, this.nrows() - i), Ord::min(tile_ncols, this.ncols() - j))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::assert;
 
 	#[test]
 	fn test_min() {
@@ -1524,4 +1700,110 @@ mod tests {
 		let empty: Mat<f64> = Mat::new();
 		assert_eq!(empty.as_ref().max(), None);
 	}
+
+	#[test]
+	fn test_tiles() {
+		let A = Mat::from_fn(7, 5, |i, j| (i + 10 * j) as f64);
+
+		for (tm, tn) in [(1, 1), (2, 3), (3, 2), (7, 5), (10, 10)] {
+			let r = A.nrows().div_ceil(tm);
+			let c = A.ncols().div_ceil(tn);
+			let tiles = A.tiles(tm, tn).collect::<alloc::vec::Vec<_>>();
+			assert!(tiles.len() == r * c);
+
+			for (k, tile) in tiles.iter().enumerate() {
+				let (i, j) = (k % r * tm, k / r * tn);
+				assert!(*tile == A.submatrix(i, j, Ord::min(tm, 7 - i), Ord::min(tn, 5 - j)));
+			}
+
+			// the tiles of the transpose are the transposes of the tiles, in a different order
+			assert!(A.transpose().tiles(tn, tm).len() == r * c);
+
+			// every entry is covered exactly once
+			let mut B = Mat::<f64>::zeros(7, 5);
+			for mut tile in B.tiles_mut(tm, tn) {
+				tile += Mat::<f64>::ones(tile.nrows(), tile.ncols());
+			}
+			assert!(B == Mat::<f64>::ones(7, 5));
+
+			#[cfg(feature = "rayon")]
+			{
+				use rayon::prelude::*;
+				B.par_tiles_mut(tm, tn).for_each(|mut tile| tile *= 2.0);
+				assert!(B == Mat::<f64>::full(7, 5, 2.0));
+				assert!(A.par_tiles(tm, tn).map(|tile| tile.sum()).sum::<f64>() == A.sum());
+			}
+		}
+
+		assert!(Mat::<f64>::zeros(0, 3).tiles(2, 2).len() == 0);
+		assert!(Mat::<f64>::zeros(3, 0).tiles(2, 2).len() == 0);
+	}
+
+	#[test]
+	fn test_quadrants() {
+		use crate::mat::Quadrants;
+
+		let A = Mat::from_fn(5, 4, |i, j| (10 * i + j) as f64);
+		let (a, b, c, d) = A.split_at(2, 3);
+		let q = A.split_quadrants(2, 3);
+		assert!(all(q.top_left == a, q.top_right == b, q.bottom_left == c, q.bottom_right == d));
+
+		let mut B = Mat::<f64>::zeros(5, 4);
+		let Quadrants {
+			mut top_left,
+			mut bottom_right,
+			..
+		} = B.split_quadrants_mut(2, 3);
+		top_left.fill(1.0);
+		bottom_right.fill(2.0);
+		assert!(
+			B == Mat::from_fn(5, 4, |i, j| if i < 2 && j < 3 {
+				1.0
+			} else if i >= 2 && j >= 3 {
+				2.0
+			} else {
+				0.0
+			})
+		);
+
+		let (a, _, _, d) = A.as_ref().transpose().split_quadrants(0, 5).into();
+		assert!(all(a.shape() == (0, 5), d.shape() == (4, 0)));
+	}
+
+	#[test]
+	fn test_checked_access() {
+		use crate::{Col, Row};
+
+		let mut A = Mat::from_fn(3, 2, |i, j| (10 * i + j) as f64);
+		assert!(A.get_checked(2, 1) == Some(&21.0));
+		assert!(all(
+			A.get_checked(3, 0) == None,
+			A.get_checked(0, 2) == None,
+			A.get_checked(usize::MAX, 0) == None
+		));
+		assert!(A.as_ref().transpose().try_read(1, 2) == Some(21.0));
+
+		assert!(A.try_write(1, 1, -1.0) == Ok(()));
+		assert!(A.as_mut().try_write(3, 1, -2.0) == Err(-2.0));
+		*A.as_mut().reverse_rows_mut().get_mut_checked(0, 0).unwrap() = -3.0;
+		assert!(all(A[(1, 1)] == -1.0, A[(2, 0)] == -3.0));
+		assert!(A.get_mut_checked(0, 3).is_none());
+
+		let mut x = Col::from_fn(3, |i| i as f64);
+		assert!(all(
+			x.get_checked(2) == Some(&2.0),
+			x.get_checked(3) == None,
+			x.as_ref().try_read(1) == Some(1.0)
+		));
+		assert!(x.try_write(0, 5.0) == Ok(()));
+		assert!(x.try_write(3, 5.0) == Err(5.0));
+		assert!(x[0] == 5.0);
+
+		let mut y = Row::from_fn(2, |j| j as f64);
+		assert!(all(y.get_checked(1) == Some(&1.0), y.get_checked(2) == None, y.try_read(0) == Some(0.0)));
+		assert!(y.as_mut().try_write(1, 4.0) == Ok(()));
+		assert!(y.try_write(2, 4.0) == Err(4.0));
+		assert!(y[1] == 4.0);
+		assert!(A.col(0).get_checked(2) == Some(&-3.0));
+	}
 }
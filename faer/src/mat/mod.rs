@@ -49,6 +49,44 @@ pub use matmut::Mut;
 pub use matown::Own;
 pub use matref::Ref;
 
+#[cfg(feature = "rkyv")]
+pub use crate::rkyv::mat::{ArchivedMat, MatResolver, NativeArchived};
+
+/// the four corners of a matrix split at a given row and column, as returned by
+/// [`MatRef::split_quadrants`] and [`MatMut::split_quadrants_mut`]
+///
+/// unlike the tuple returned by [`MatRef::split_at`], the fields can be destructured by name
+#[derive(Copy, Clone, Debug)]
+pub struct Quadrants<M> {
+	/// rows `..row`, columns `..col`
+	pub top_left: M,
+	/// rows `..row`, columns `col..`
+	pub top_right: M,
+	/// rows `row..`, columns `..col`
+	pub bottom_left: M,
+	/// rows `row..`, columns `col..`
+	pub bottom_right: M,
+}
+
+impl<M> From<(M, M, M, M)> for Quadrants<M> {
+	#[inline]
+	fn from((top_left, top_right, bottom_left, bottom_right): (M, M, M, M)) -> Self {
+		Self {
+			top_left,
+			top_right,
+			bottom_left,
+			bottom_right,
+		}
+	}
+}
+
+impl<M> From<Quadrants<M>> for (M, M, M, M) {
+	#[inline]
+	fn from(q: Quadrants<M>) -> Self {
+		(q.top_left, q.top_right, q.bottom_left, q.bottom_right)
+	}
+}
+
 /// heap allocated resizable matrix, similar to a 2d [`alloc::vec::Vec`]
 ///
 /// # note
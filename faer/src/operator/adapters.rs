@@ -0,0 +1,901 @@
+use crate::assert;
+use crate::matrix_free::*;
+
+/// sum of two linear operators, $A + B$
+///
+/// the operators are applied one after the other, so the sum is never materialized
+#[derive(Copy, Clone, Debug)]
+pub struct SumOp<A, B> {
+	lhs: A,
+	rhs: B,
+}
+
+/// product of two linear operators, $A B$
+///
+/// applying the product applies $B$, then $A$ to the result, so the product is never materialized
+#[derive(Copy, Clone, Debug)]
+pub struct ProductOp<A, B> {
+	lhs: A,
+	rhs: B,
+}
+
+/// linear operator scaled by a factor, $\alpha A$
+#[derive(Copy, Clone, Debug)]
+pub struct ScaledOp<T, A> {
+	alpha: T,
+	op: A,
+}
+
+/// kronecker product of two linear operators, $A \otimes B$
+///
+/// with the same block structure as [`kron`](crate::linalg::kron::kron). applying the operator to
+/// a vector $x$, seen as a column-major matrix $X$, computes the vector form of $B X A^\top$, so
+/// that only products with $A$ and $B$ are needed
+#[derive(Copy, Clone, Debug)]
+pub struct KronOp<A, B> {
+	lhs: A,
+	rhs: B,
+}
+
+impl<A, B> SumOp<A, B> {
+	/// returns the sum of `lhs` and `rhs`
+	///
+	/// # panics
+	///
+	/// panics if the operators don't have the same dimensions
+	#[track_caller]
+	pub fn new<T: ComplexField>(lhs: A, rhs: B) -> Self
+	where
+		A: LinOp<T>,
+		B: LinOp<T>,
+	{
+		assert!(all(lhs.nrows() == rhs.nrows(), lhs.ncols() == rhs.ncols()));
+		Self { lhs, rhs }
+	}
+
+	/// returns the operands
+	#[inline]
+	pub fn operands(&self) -> (&A, &B) {
+		(&self.lhs, &self.rhs)
+	}
+}
+
+impl<A, B> ProductOp<A, B> {
+	/// returns the product of `lhs` and `rhs`
+	///
+	/// # panics
+	///
+	/// panics if the number of columns of `lhs` doesn't match the number of rows of `rhs`
+	#[track_caller]
+	pub fn new<T: ComplexField>(lhs: A, rhs: B) -> Self
+	where
+		A: LinOp<T>,
+		B: LinOp<T>,
+	{
+		assert!(lhs.ncols() == rhs.nrows());
+		Self { lhs, rhs }
+	}
+
+	/// returns the operands
+	#[inline]
+	pub fn operands(&self) -> (&A, &B) {
+		(&self.lhs, &self.rhs)
+	}
+}
+
+impl<T, A> ScaledOp<T, A> {
+	/// returns `op` scaled by `alpha`
+	#[inline]
+	pub fn new(alpha: T, op: A) -> Self {
+		Self { alpha, op }
+	}
+
+	/// returns the scaling factor
+	#[inline]
+	pub fn alpha(&self) -> &T {
+		&self.alpha
+	}
+
+	/// returns the scaled operator
+	#[inline]
+	pub fn op(&self) -> &A {
+		&self.op
+	}
+}
+
+impl<A, B> KronOp<A, B> {
+	/// returns the kronecker product of `lhs` and `rhs`
+	#[inline]
+	pub fn new(lhs: A, rhs: B) -> Self {
+		Self { lhs, rhs }
+	}
+
+	/// returns the operands
+	#[inline]
+	pub fn operands(&self) -> (&A, &B) {
+		(&self.lhs, &self.rhs)
+	}
+}
+
+impl<T: ComplexField, A: LinOp<T>, B: LinOp<T>> LinOp<T> for SumOp<A, B> {
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		temp_mat_scratch::<T>(self.nrows(), rhs_ncols).and(self.lhs.apply_scratch(rhs_ncols, par).or(self.rhs.apply_scratch(rhs_ncols, par)))
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.lhs.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.lhs.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		assert!(all(self.lhs.nrows() == self.rhs.nrows(), self.lhs.ncols() == self.rhs.ncols()));
+		let mut out = out;
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(self.nrows(), rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		self.lhs.apply(out.rb_mut(), rhs, par, stack);
+		self.rhs.apply(tmp.rb_mut(), rhs, par, stack);
+		out += tmp.rb();
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		assert!(all(self.lhs.nrows() == self.rhs.nrows(), self.lhs.ncols() == self.rhs.ncols()));
+		let mut out = out;
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(self.nrows(), rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		self.lhs.conj_apply(out.rb_mut(), rhs, par, stack);
+		self.rhs.conj_apply(tmp.rb_mut(), rhs, par, stack);
+		out += tmp.rb();
+	}
+}
+
+impl<T: ComplexField, A: BiLinOp<T>, B: BiLinOp<T>> BiLinOp<T> for SumOp<A, B> {
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		temp_mat_scratch::<T>(self.ncols(), rhs_ncols).and(
+			self.lhs
+				.transpose_apply_scratch(rhs_ncols, par)
+				.or(self.rhs.transpose_apply_scratch(rhs_ncols, par)),
+		)
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		assert!(all(self.lhs.nrows() == self.rhs.nrows(), self.lhs.ncols() == self.rhs.ncols()));
+		let mut out = out;
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(self.ncols(), rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		self.lhs.transpose_apply(out.rb_mut(), rhs, par, stack);
+		self.rhs.transpose_apply(tmp.rb_mut(), rhs, par, stack);
+		out += tmp.rb();
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		assert!(all(self.lhs.nrows() == self.rhs.nrows(), self.lhs.ncols() == self.rhs.ncols()));
+		let mut out = out;
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(self.ncols(), rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		self.lhs.adjoint_apply(out.rb_mut(), rhs, par, stack);
+		self.rhs.adjoint_apply(tmp.rb_mut(), rhs, par, stack);
+		out += tmp.rb();
+	}
+}
+
+impl<T: ComplexField, A: LinOp<T>, B: LinOp<T>> LinOp<T> for ProductOp<A, B> {
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		temp_mat_scratch::<T>(self.rhs.nrows(), rhs_ncols).and(self.lhs.apply_scratch(rhs_ncols, par).or(self.rhs.apply_scratch(rhs_ncols, par)))
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.lhs.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.rhs.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		assert!(self.lhs.ncols() == self.rhs.nrows());
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(self.rhs.nrows(), rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		self.rhs.apply(tmp.rb_mut(), rhs, par, stack);
+		self.lhs.apply(out, tmp.rb(), par, stack);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		assert!(self.lhs.ncols() == self.rhs.nrows());
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(self.rhs.nrows(), rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		self.rhs.conj_apply(tmp.rb_mut(), rhs, par, stack);
+		self.lhs.conj_apply(out, tmp.rb(), par, stack);
+	}
+}
+
+impl<T: ComplexField, A: BiLinOp<T>, B: BiLinOp<T>> BiLinOp<T> for ProductOp<A, B> {
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		temp_mat_scratch::<T>(self.lhs.ncols(), rhs_ncols).and(
+			self.lhs
+				.transpose_apply_scratch(rhs_ncols, par)
+				.or(self.rhs.transpose_apply_scratch(rhs_ncols, par)),
+		)
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		assert!(self.lhs.ncols() == self.rhs.nrows());
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(self.lhs.ncols(), rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		// (A B)^T = B^T A^T
+		self.lhs.transpose_apply(tmp.rb_mut(), rhs, par, stack);
+		self.rhs.transpose_apply(out, tmp.rb(), par, stack);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		assert!(self.lhs.ncols() == self.rhs.nrows());
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(self.lhs.ncols(), rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		self.lhs.adjoint_apply(tmp.rb_mut(), rhs, par, stack);
+		self.rhs.adjoint_apply(out, tmp.rb(), par, stack);
+	}
+}
+
+impl<T: ComplexField, A: LinOp<T>> LinOp<T> for ScaledOp<T, A> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.op.apply_scratch(rhs_ncols, par)
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.op.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.op.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		self.op.apply(out.rb_mut(), rhs, par, stack);
+		out *= Scale(copy(&self.alpha));
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		self.op.conj_apply(out.rb_mut(), rhs, par, stack);
+		out *= Scale(conj(&self.alpha));
+	}
+}
+
+impl<T: ComplexField, A: BiLinOp<T>> BiLinOp<T> for ScaledOp<T, A> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.op.transpose_apply_scratch(rhs_ncols, par)
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		self.op.transpose_apply(out.rb_mut(), rhs, par, stack);
+		out *= Scale(copy(&self.alpha));
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		self.op.adjoint_apply(out.rb_mut(), rhs, par, stack);
+		out *= Scale(conj(&self.alpha));
+	}
+}
+
+/// operator application, as seen by [`kron_apply`]
+type ApplyFn<'a, T> = &'a dyn Fn(MatMut<'_, T>, MatRef<'_, T>, Par, &mut MemStack);
+
+fn kron_apply_scratch<T: ComplexField>(
+	shape_a: (usize, usize),
+	shape_b: (usize, usize),
+	rhs_ncols: usize,
+	scratch_a: StackReq,
+	scratch_b: StackReq,
+) -> StackReq {
+	let (ma, na) = shape_a;
+	let (mb, nb) = shape_b;
+
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(nb, na * rhs_ncols),
+		temp_mat_scratch::<T>(mb, na * rhs_ncols),
+		temp_mat_scratch::<T>(na, mb * rhs_ncols),
+		temp_mat_scratch::<T>(ma, mb * rhs_ncols),
+		scratch_a.or(scratch_b),
+	])
+}
+
+/// computes the vector forms of $B X_j A^\top$ for the columns $x_j$ of `rhs`, with one application
+/// of each operator for all the right-hand sides
+#[track_caller]
+#[allow(clippy::too_many_arguments)]
+fn kron_apply<T: ComplexField>(
+	out: MatMut<'_, T>,
+	rhs: MatRef<'_, T>,
+	shape_a: (usize, usize),
+	shape_b: (usize, usize),
+	apply_a: ApplyFn<'_, T>,
+	apply_b: ApplyFn<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+) {
+	let (ma, na) = shape_a;
+	let (mb, nb) = shape_b;
+	let k = rhs.ncols();
+	assert!(all(out.nrows() == ma * mb, rhs.nrows() == na * nb, out.ncols() == k));
+
+	let mut out = out;
+	let (mut X, stack) = unsafe { temp_mat_uninit::<T, _, _>(nb, na * k, stack) };
+	let mut X = X.as_mat_mut();
+	let (mut W, stack) = unsafe { temp_mat_uninit::<T, _, _>(mb, na * k, stack) };
+	let mut W = W.as_mat_mut();
+	let (mut V, stack) = unsafe { temp_mat_uninit::<T, _, _>(na, mb * k, stack) };
+	let mut V = V.as_mat_mut();
+	let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(ma, mb * k, stack) };
+	let mut Z = Z.as_mat_mut();
+
+	// X_j = reshape(x_j)
+	for j in 0..k {
+		for c in 0..na {
+			X.rb_mut().col_mut(j * na + c).copy_from(rhs.col(j).subrows(c * nb, nb));
+		}
+	}
+
+	// W_j = B X_j
+	apply_b(W.rb_mut(), X.rb(), par, stack);
+
+	// Z_j = A W_j^T = (B X_j A^T)^T
+	for j in 0..k {
+		V.rb_mut()
+			.submatrix_mut(0, j * mb, na, mb)
+			.copy_from(W.rb().submatrix(0, j * na, mb, na).transpose());
+	}
+	apply_a(Z.rb_mut(), V.rb(), par, stack);
+
+	for j in 0..k {
+		for i in 0..ma {
+			out.rb_mut()
+				.col_mut(j)
+				.subrows_mut(i * mb, mb)
+				.copy_from(Z.rb().row(i).subcols(j * mb, mb).transpose());
+		}
+	}
+}
+
+impl<T: ComplexField, A: LinOp<T>, B: LinOp<T>> LinOp<T> for KronOp<A, B> {
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		let (a, b) = (&self.lhs, &self.rhs);
+		kron_apply_scratch::<T>(
+			(a.nrows(), a.ncols()),
+			(b.nrows(), b.ncols()),
+			rhs_ncols,
+			a.apply_scratch(b.nrows() * rhs_ncols, par),
+			b.apply_scratch(a.ncols() * rhs_ncols, par),
+		)
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.lhs.nrows() * self.rhs.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.lhs.ncols() * self.rhs.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let (a, b) = (&self.lhs, &self.rhs);
+		kron_apply(
+			out,
+			rhs,
+			(a.nrows(), a.ncols()),
+			(b.nrows(), b.ncols()),
+			&|out, rhs, par, stack| a.apply(out, rhs, par, stack),
+			&|out, rhs, par, stack| b.apply(out, rhs, par, stack),
+			par,
+			stack,
+		);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let (a, b) = (&self.lhs, &self.rhs);
+		kron_apply(
+			out,
+			rhs,
+			(a.nrows(), a.ncols()),
+			(b.nrows(), b.ncols()),
+			&|out, rhs, par, stack| a.conj_apply(out, rhs, par, stack),
+			&|out, rhs, par, stack| b.conj_apply(out, rhs, par, stack),
+			par,
+			stack,
+		);
+	}
+}
+
+impl<T: ComplexField, A: BiLinOp<T>, B: BiLinOp<T>> BiLinOp<T> for KronOp<A, B> {
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		let (a, b) = (&self.lhs, &self.rhs);
+		kron_apply_scratch::<T>(
+			(a.ncols(), a.nrows()),
+			(b.ncols(), b.nrows()),
+			rhs_ncols,
+			a.transpose_apply_scratch(b.ncols() * rhs_ncols, par),
+			b.transpose_apply_scratch(a.nrows() * rhs_ncols, par),
+		)
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		// (A ⊗ B)^T = A^T ⊗ B^T
+		let (a, b) = (&self.lhs, &self.rhs);
+		kron_apply(
+			out,
+			rhs,
+			(a.ncols(), a.nrows()),
+			(b.ncols(), b.nrows()),
+			&|out, rhs, par, stack| a.transpose_apply(out, rhs, par, stack),
+			&|out, rhs, par, stack| b.transpose_apply(out, rhs, par, stack),
+			par,
+			stack,
+		);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let (a, b) = (&self.lhs, &self.rhs);
+		kron_apply(
+			out,
+			rhs,
+			(a.ncols(), a.nrows()),
+			(b.ncols(), b.nrows()),
+			&|out, rhs, par, stack| a.adjoint_apply(out, rhs, par, stack),
+			&|out, rhs, par, stack| b.adjoint_apply(out, rhs, par, stack),
+			par,
+			stack,
+		);
+	}
+}
+
+/// adjoint of a linear operator, $A^H$
+///
+/// applying the adjoint uses [`BiLinOp::adjoint_apply`] of the inner operator, so the adjoint is
+/// never materialized
+#[derive(Copy, Clone, Debug)]
+pub struct AdjointOp<A> {
+	op: A,
+}
+
+/// transpose of a linear operator, $A^\top$
+///
+/// applying the transpose uses [`BiLinOp::transpose_apply`] of the inner operator, so the
+/// transpose is never materialized
+#[derive(Copy, Clone, Debug)]
+pub struct TransposeOp<A> {
+	op: A,
+}
+
+impl<A> AdjointOp<A> {
+	/// returns the adjoint of `op`
+	#[inline]
+	pub fn new(op: A) -> Self {
+		Self { op }
+	}
+
+	/// returns the inner operator
+	#[inline]
+	pub fn op(&self) -> &A {
+		&self.op
+	}
+}
+
+impl<A> TransposeOp<A> {
+	/// returns the transpose of `op`
+	#[inline]
+	pub fn new(op: A) -> Self {
+		Self { op }
+	}
+
+	/// returns the inner operator
+	#[inline]
+	pub fn op(&self) -> &A {
+		&self.op
+	}
+}
+
+impl<T: ComplexField, A: BiLinOp<T>> LinOp<T> for AdjointOp<A> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.op.transpose_apply_scratch(rhs_ncols, par)
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.op.ncols()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.op.nrows()
+	}
+
+	#[inline]
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.op.adjoint_apply(out, rhs, par, stack)
+	}
+
+	#[inline]
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.op.transpose_apply(out, rhs, par, stack)
+	}
+}
+
+impl<T: ComplexField, A: BiLinOp<T>> BiLinOp<T> for AdjointOp<A> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.op.apply_scratch(rhs_ncols, par)
+	}
+
+	#[inline]
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.op.conj_apply(out, rhs, par, stack)
+	}
+
+	#[inline]
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.op.apply(out, rhs, par, stack)
+	}
+}
+
+impl<T: ComplexField, A: BiLinOp<T>> LinOp<T> for TransposeOp<A> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.op.transpose_apply_scratch(rhs_ncols, par)
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.op.ncols()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.op.nrows()
+	}
+
+	#[inline]
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.op.transpose_apply(out, rhs, par, stack)
+	}
+
+	#[inline]
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.op.adjoint_apply(out, rhs, par, stack)
+	}
+}
+
+impl<T: ComplexField, A: BiLinOp<T>> BiLinOp<T> for TransposeOp<A> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.op.apply_scratch(rhs_ncols, par)
+	}
+
+	#[inline]
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.op.apply(out, rhs, par, stack)
+	}
+
+	#[inline]
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.op.conj_apply(out, rhs, par, stack)
+	}
+}
+
+/// wrapper enabling the arithmetic operators on linear operators
+///
+/// `Op(a) + Op(b)`, `Op(a) * Op(b)` and `Scale(alpha) * Op(a)` build lazy compositions out of
+/// [`SumOp`], [`ProductOp`] and [`ScaledOp`], and `Op(a).adjoint()` and `Op(a).transpose()` build
+/// an [`AdjointOp`] and a [`TransposeOp`]. applying the result applies the operands one after the
+/// other, and its workspace requirements are computed from the ones of the operands
+///
+/// since the operands are only known to be linear operators once the scalar type is known, their
+/// dimensions are checked when the composition is applied
+///
+/// # example
+/// ```
+/// use dyn_stack::{MemBuffer, MemStack};
+/// use faer::matrix_free::LinOp;
+/// use faer::matrix_free::adapters::Op;
+/// use faer::{Mat, Par, Scale, mat};
+///
+/// let A = mat![[1.0, 2.0], [3.0, 4.0]];
+/// let B = mat![[0.0, 1.0], [1.0, 0.0]];
+///
+/// // 2 A^H B + B, without forming any intermediate matrix
+/// let op = Scale(2.0) * Op(A.as_ref()).adjoint() * Op(B.as_ref()) + Op(B.as_ref());
+///
+/// let x = mat![[1.0], [-1.0]];
+/// let mut y = Mat::zeros(2, 1);
+/// let mut mem = MemBuffer::new(op.apply_scratch(1, Par::Seq));
+/// op.apply(y.as_mut(), x.as_ref(), Par::Seq, MemStack::new(&mut mem));
+///
+/// let expected = Scale(2.0) * A.adjoint() * &B * &x + &B * &x;
+/// assert!((&y - &expected).norm_max() < 1e-14);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Op<A>(pub A);
+
+impl<A> Op<A> {
+	/// returns the wrapped operator
+	#[inline]
+	pub fn into_inner(self) -> A {
+		self.0
+	}
+
+	/// returns the adjoint of the operator
+	#[inline]
+	pub fn adjoint(self) -> Op<AdjointOp<A>> {
+		Op(AdjointOp::new(self.0))
+	}
+
+	/// returns the transpose of the operator
+	#[inline]
+	pub fn transpose(self) -> Op<TransposeOp<A>> {
+		Op(TransposeOp::new(self.0))
+	}
+}
+
+impl<A, B> core::ops::Add<Op<B>> for Op<A> {
+	type Output = Op<SumOp<A, B>>;
+
+	#[inline]
+	fn add(self, rhs: Op<B>) -> Self::Output {
+		Op(SumOp { lhs: self.0, rhs: rhs.0 })
+	}
+}
+
+impl<A, B> core::ops::Mul<Op<B>> for Op<A> {
+	type Output = Op<ProductOp<A, B>>;
+
+	#[inline]
+	fn mul(self, rhs: Op<B>) -> Self::Output {
+		Op(ProductOp { lhs: self.0, rhs: rhs.0 })
+	}
+}
+
+impl<T, A> core::ops::Mul<Op<A>> for Scale<T> {
+	type Output = Op<ScaledOp<T, A>>;
+
+	#[inline]
+	fn mul(self, rhs: Op<A>) -> Self::Output {
+		Op(ScaledOp::new(self.0, rhs.0))
+	}
+}
+
+impl<T, A> core::ops::Mul<Scale<T>> for Op<A> {
+	type Output = Op<ScaledOp<T, A>>;
+
+	#[inline]
+	fn mul(self, rhs: Scale<T>) -> Self::Output {
+		Op(ScaledOp::new(rhs.0, self.0))
+	}
+}
+
+impl<T: ComplexField, A: LinOp<T>> LinOp<T> for Op<A> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.0.apply_scratch(rhs_ncols, par)
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.0.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.0.ncols()
+	}
+
+	#[inline]
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.0.apply(out, rhs, par, stack)
+	}
+
+	#[inline]
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.0.conj_apply(out, rhs, par, stack)
+	}
+}
+
+impl<T: ComplexField, A: BiLinOp<T>> BiLinOp<T> for Op<A> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.0.transpose_apply_scratch(rhs_ncols, par)
+	}
+
+	#[inline]
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.0.transpose_apply(out, rhs, par, stack)
+	}
+
+	#[inline]
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.0.adjoint_apply(out, rhs, par, stack)
+	}
+}
+
+impl<T: ComplexField, A: LinOp<T>, B: LinOp<T>> Precond<T> for SumOp<A, B> {}
+impl<T: ComplexField, A: BiLinOp<T>, B: BiLinOp<T>> BiPrecond<T> for SumOp<A, B> {}
+impl<T: ComplexField, A: LinOp<T>, B: LinOp<T>> Precond<T> for ProductOp<A, B> {}
+impl<T: ComplexField, A: BiLinOp<T>, B: BiLinOp<T>> BiPrecond<T> for ProductOp<A, B> {}
+impl<T: ComplexField, A: LinOp<T>> Precond<T> for ScaledOp<T, A> {}
+impl<T: ComplexField, A: BiLinOp<T>> BiPrecond<T> for ScaledOp<T, A> {}
+impl<T: ComplexField, A: LinOp<T>, B: LinOp<T>> Precond<T> for KronOp<A, B> {}
+impl<T: ComplexField, A: BiLinOp<T>, B: BiLinOp<T>> BiPrecond<T> for KronOp<A, B> {}
+impl<T: ComplexField, A: BiLinOp<T>> Precond<T> for AdjointOp<A> {}
+impl<T: ComplexField, A: BiLinOp<T>> BiPrecond<T> for AdjointOp<A> {}
+impl<T: ComplexField, A: BiLinOp<T>> Precond<T> for TransposeOp<A> {}
+impl<T: ComplexField, A: BiLinOp<T>> BiPrecond<T> for TransposeOp<A> {}
+impl<T: ComplexField, A: LinOp<T>> Precond<T> for Op<A> {}
+impl<T: ComplexField, A: BiLinOp<T>> BiPrecond<T> for Op<A> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	#[track_caller]
+	fn check_op(op: &dyn BiLinOp<c64>, dense: MatRef<'_, c64>) {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+		let (m, n) = dense.shape();
+		let k = 3;
+		assert!(all(op.nrows() == m, op.ncols() == n));
+
+		let x = CwiseMatDistribution { nrows: n, ncols: k, dist }.rand::<Mat<c64>>(rng);
+		let y = CwiseMatDistribution { nrows: m, ncols: k, dist }.rand::<Mat<c64>>(rng);
+		let mut out = Mat::<c64>::zeros(m, k);
+		let mut out_t = Mat::<c64>::zeros(n, k);
+
+		let par = Par::Seq;
+		op.apply(
+			out.as_mut(),
+			x.as_ref(),
+			par,
+			MemStack::new(&mut MemBuffer::new(op.apply_scratch(k, par))),
+		);
+		assert!(out ~ dense * &x);
+		op.conj_apply(
+			out.as_mut(),
+			x.as_ref(),
+			par,
+			MemStack::new(&mut MemBuffer::new(op.apply_scratch(k, par))),
+		);
+		assert!(out ~ dense.conjugate() * &x);
+		op.transpose_apply(
+			out_t.as_mut(),
+			y.as_ref(),
+			par,
+			MemStack::new(&mut MemBuffer::new(op.transpose_apply_scratch(k, par))),
+		);
+		assert!(out_t ~ dense.transpose() * &y);
+		op.adjoint_apply(
+			out_t.as_mut(),
+			y.as_ref(),
+			par,
+			MemStack::new(&mut MemBuffer::new(op.transpose_apply_scratch(k, par))),
+		);
+		assert!(out_t ~ dense.adjoint() * &y);
+	}
+
+	#[test]
+	fn test_adapters() {
+		let rng = &mut StdRng::seed_from_u64(1);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+
+		let A = CwiseMatDistribution { nrows: 4, ncols: 3, dist }.rand::<Mat<c64>>(rng);
+		let B = CwiseMatDistribution { nrows: 4, ncols: 3, dist }.rand::<Mat<c64>>(rng);
+		let C = CwiseMatDistribution { nrows: 3, ncols: 5, dist }.rand::<Mat<c64>>(rng);
+		let D = CwiseMatDistribution { nrows: 2, ncols: 7, dist }.rand::<Mat<c64>>(rng);
+		let d = CwiseColDistribution { nrows: 5, dist }.rand::<Col<c64>>(rng);
+		let alpha = c64::new(0.5, -2.0);
+
+		check_op(&SumOp::new(A.as_ref(), B.as_ref()), (&A + &B).as_ref());
+		check_op(&ProductOp::new(A.as_ref(), C.as_ref()), (&A * &C).as_ref());
+		check_op(&ScaledOp::new(alpha, A.as_ref()), (Scale(alpha) * &A).as_ref());
+		check_op(&ProductOp::new(C.as_ref(), d.as_diagonal()), (&C * d.as_diagonal()).as_ref());
+
+		let mut K = Mat::<c64>::zeros(8, 21);
+		linalg::kron::kron(K.as_mut(), A.as_ref(), D.as_ref());
+		check_op(&KronOp::new(A.as_ref(), D.as_ref()), K.as_ref());
+
+		// nested compositions
+		let E = CwiseMatDistribution { nrows: 7, ncols: 2, dist }.rand::<Mat<c64>>(rng);
+		let mut L = Mat::<c64>::zeros(21, 10);
+		linalg::kron::kron(L.as_mut(), C.as_ref(), E.as_ref());
+		check_op(
+			&ProductOp::new(KronOp::new(A.as_ref(), D.as_ref()), KronOp::new(C.as_ref(), E.as_ref())),
+			(&K * &L).as_ref(),
+		);
+		check_op(
+			&SumOp::new(
+				ProductOp::new(ScaledOp::new(alpha, A.as_ref()), C.as_ref()),
+				ProductOp::new(B.as_ref(), C.as_ref()),
+			),
+			(Scale(alpha) * &A * &C + &B * &C).as_ref(),
+		);
+	}
+
+	#[test]
+	fn test_op_algebra() {
+		let rng = &mut StdRng::seed_from_u64(2);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+
+		let A = CwiseMatDistribution { nrows: 4, ncols: 3, dist }.rand::<Mat<c64>>(rng);
+		let B = CwiseMatDistribution { nrows: 4, ncols: 3, dist }.rand::<Mat<c64>>(rng);
+		let C = CwiseMatDistribution { nrows: 4, ncols: 5, dist }.rand::<Mat<c64>>(rng);
+		let D = CwiseMatDistribution { nrows: 2, ncols: 2, dist }.rand::<Mat<c64>>(rng);
+		let alpha = c64::new(0.5, -2.0);
+
+		check_op(&Op(A.as_ref()).adjoint(), A.adjoint().to_owned().as_ref());
+		check_op(&Op(A.as_ref()).transpose(), A.transpose());
+		check_op(&(Op(A.as_ref()) + Op(B.as_ref())), (&A + &B).as_ref());
+		check_op(&(Op(A.as_ref()) * Scale(alpha)), (Scale(alpha) * &A).as_ref());
+
+		let op = Scale(alpha) * Op(A.as_ref()).adjoint() * Op(C.as_ref()) + Op(B.as_ref()).transpose() * Op(C.as_ref());
+		check_op(&op, (Scale(alpha) * A.adjoint() * &C + B.transpose() * &C).as_ref());
+
+		let mut K = Mat::<c64>::zeros(8, 6);
+		let mut L = Mat::<c64>::zeros(8, 10);
+		linalg::kron::kron(K.as_mut(), A.as_ref(), D.as_ref());
+		linalg::kron::kron(L.as_mut(), C.as_ref(), D.as_ref());
+		let op = Op(KronOp::new(A.as_ref(), D.as_ref())).adjoint() * Op(KronOp::new(C.as_ref(), D.as_ref()));
+		check_op(&op, (K.adjoint() * &L).as_ref());
+	}
+}
@@ -0,0 +1,766 @@
+//! block krylov methods for systems with many right-hand sides
+//!
+//! both methods build a single krylov subspace from all the residuals at once, so that each
+//! iteration applies the operator to a block of vectors, and the orthogonalization is done with
+//! matrix-matrix products. the search space is shared between the right-hand sides, which usually
+//! reduces the total number of iterations compared to solving each system independently
+//!
+//! the convergence of each column is tested separately, and the columns that have converged are
+//! deflated, i.e., removed from the block, so that the remaining iterations only work on the
+//! systems that still need it
+//!
+//! - [`block_cg`] is the breakdown-free block conjugate gradient of ji and li, for self-adjoint
+//! positive definite operators. the search directions are orthonormalized with a rank-revealing
+//! $QR$ decomposition at each step, which removes the directions that become linearly dependent,
+//! e.g., when some right-hand sides are linear combinations of the others
+//! - [`block_gmres`] is the restarted block gmres method with right preconditioning, for general
+//! operators
+
+use super::*;
+use crate::assert;
+use crate::linalg::solvers::{Solve, SolveLstsq};
+use crate::matrix_free::monitor::{BreakdownReason, IterationControl, IterationState, IterativeSolveResult};
+use alloc::vec::Vec;
+
+/// block conjugate gradient parameters
+#[derive(Copy, Clone, Debug)]
+pub struct BlockCgParams<T> {
+	/// whether the initial guess is implicitly zero or not
+	pub initial_guess: InitialGuessStatus,
+	/// absolute tolerance for convergence testing
+	pub abs_tolerance: T,
+	/// relative tolerance for convergence testing
+	pub rel_tolerance: T,
+	/// maximum number of iterations
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// block gmres parameters
+#[derive(Copy, Clone, Debug)]
+pub struct BlockGmresParams<T> {
+	/// whether the initial guess is implicitly zero or not
+	pub initial_guess: InitialGuessStatus,
+	/// absolute tolerance for convergence testing
+	pub abs_tolerance: T,
+	/// relative tolerance for convergence testing
+	pub rel_tolerance: T,
+	/// number of block iterations after which the method is restarted from the current solution
+	pub restart: usize,
+	/// maximum number of iterations
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// algorithm result
+///
+/// the residuals are the largest ones among all the columns of the right-hand side
+#[derive(Copy, Clone, Debug)]
+pub struct BlockKrylovInfo<T> {
+	/// absolute residual at the final step
+	pub abs_residual: T,
+	/// relative residual at the final step
+	pub rel_residual: T,
+	/// number of block iterations executed by the algorithm
+	pub iter_count: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// algorithm error
+#[derive(Copy, Clone, Debug)]
+pub enum BlockKrylovError<T> {
+	/// operator was detected to not be positive definite
+	NonPositiveDefiniteOperator,
+	/// convergence failure
+	NoConvergence {
+		/// absolute residual at the final step
+		abs_residual: T,
+		/// relative residual at the final step
+		rel_residual: T,
+	},
+}
+
+impl<T: RealField> Default for BlockCgParams<T> {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			initial_guess: InitialGuessStatus::MaybeNonZero,
+			abs_tolerance: zero(),
+			rel_tolerance: eps::<T>() * from_f64::<T>(128.0),
+			max_iters: usize::MAX,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+impl<T: RealField> Default for BlockGmresParams<T> {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			initial_guess: InitialGuessStatus::MaybeNonZero,
+			abs_tolerance: zero(),
+			rel_tolerance: eps::<T>() * from_f64::<T>(128.0),
+			restart: 30,
+			max_iters: usize::MAX,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// per-column convergence state, shared by both methods
+struct Columns<T: RealField> {
+	b_norm: Vec<T>,
+	threshold: Vec<T>,
+	residual: Vec<T>,
+	// indices of the columns that haven't converged yet
+	active: Vec<usize>,
+}
+
+impl<T: RealField> Columns<T> {
+	#[math]
+	fn new<U: ComplexField<Real = T>>(b: MatRef<'_, U>, abs_tolerance: T, rel_tolerance: T) -> Self {
+		let k = b.ncols();
+		let b_norm = (0..k).map(|j| b.col(j).norm_l2()).collect::<Vec<_>>();
+		let threshold = b_norm.iter().map(|b_norm| max(copy(abs_tolerance), rel_tolerance * *b_norm)).collect();
+		Self {
+			b_norm,
+			threshold,
+			residual: (0..k).map(|_| infinity()).collect(),
+			active: (0..k).collect(),
+		}
+	}
+
+	/// stores the norms of the residuals `r` of the active columns, and deflates the ones that
+	/// have converged
+	#[math]
+	fn update<U: ComplexField<Real = T>>(&mut self, r: MatRef<'_, U>) {
+		for (c, &j) in self.active.iter().enumerate() {
+			self.residual[j] = r.col(c).norm_l2();
+		}
+		let (residual, threshold) = (&self.residual, &self.threshold);
+		self.active.retain(|&j| !(residual[j] < threshold[j] || residual[j] == zero::<T>()));
+	}
+
+	/// returns the largest absolute and relative residuals
+	fn residuals(&self) -> (T, T) {
+		self.max_residuals(&self.residual)
+	}
+
+	/// returns the largest absolute and relative residuals, with the norms of the columns of `r`
+	/// as the residuals of the active columns
+	#[math]
+	fn estimated_residuals<U: ComplexField<Real = T>>(&self, r: MatRef<'_, U>) -> (T, T) {
+		let mut residual = self.residual.clone();
+		for (c, &j) in self.active.iter().enumerate() {
+			residual[j] = r.col(c).norm_l2();
+		}
+		self.max_residuals(&residual)
+	}
+
+	#[math]
+	fn max_residuals(&self, residual: &[T]) -> (T, T) {
+		let mut abs_residual = zero::<T>();
+		let mut rel_residual = zero::<T>();
+		for j in 0..residual.len() {
+			let abs = copy(residual[j]);
+			let rel = if self.b_norm[j] > zero::<T>() {
+				abs / self.b_norm[j]
+			} else if abs == zero::<T>() {
+				zero()
+			} else {
+				infinity()
+			};
+			abs_residual = max(abs_residual, abs);
+			rel_residual = max(rel_residual, rel);
+		}
+		(abs_residual, rel_residual)
+	}
+
+	fn converged(&self, iter_count: usize) -> IterativeSolveResult<T> {
+		let (abs_residual, rel_residual) = self.residuals();
+		IterativeSolveResult::converged(iter_count, abs_residual, rel_residual)
+	}
+
+	fn breakdown(&self, reason: BreakdownReason, iter_count: usize) -> IterativeSolveResult<T> {
+		let (abs_residual, rel_residual) = self.residuals();
+		IterativeSolveResult::breakdown(reason, iter_count, abs_residual, rel_residual)
+	}
+}
+
+/// maps the result of a monitored solve to the info and error types of the block methods
+fn block_krylov_result<T: RealField>(result: IterativeSolveResult<T>) -> Result<BlockKrylovInfo<T>, BlockKrylovError<T>> {
+	match result.breakdown {
+		None => Ok(BlockKrylovInfo {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+			iter_count: result.iter_count,
+			non_exhaustive: NonExhaustive(()),
+		}),
+		Some(BreakdownReason::NonPositiveDefiniteOperator) => Err(BlockKrylovError::NonPositiveDefiniteOperator),
+		Some(_) => Err(BlockKrylovError::NoConvergence {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+		}),
+	}
+}
+
+/// returns the columns of `mat` with the given indices
+fn gather<T: ComplexField>(mat: MatRef<'_, T>, cols: &[usize]) -> Mat<T> {
+	Mat::from_fn(mat.nrows(), cols.len(), |i, j| mat[(i, cols[j])].clone())
+}
+
+/// adds the columns of `src` to the columns of `dst` with the given indices
+#[math]
+fn scatter_add<T: ComplexField>(dst: MatMut<'_, T>, src: MatRef<'_, T>, cols: &[usize]) {
+	let mut dst = dst;
+	for (c, &j) in cols.iter().enumerate() {
+		zip!(dst.rb_mut().col_mut(j), src.col(c)).for_each(|unzip!(dst, src)| *dst = *dst + *src);
+	}
+}
+
+/// computes `b - A x` for the columns of `x` with the given indices
+#[math]
+fn residual<T: ComplexField>(A: &dyn LinOp<T>, x: MatRef<'_, T>, b: MatRef<'_, T>, cols: &[usize], par: Par, stack: &mut MemStack) -> Mat<T> {
+	let mut r = Mat::<T>::zeros(b.nrows(), cols.len());
+	A.apply(r.as_mut(), gather(x, cols).as_ref(), par, stack);
+	for (c, &j) in cols.iter().enumerate() {
+		zip!(r.col_mut(c), b.col(j)).for_each(|unzip!(r, b)| *r = *b - *r);
+	}
+	r
+}
+
+/// returns an orthonormal basis of the range of `Z`, without the numerically dependent directions
+#[math]
+fn orth<T: ComplexField>(Z: MatRef<'_, T>) -> Mat<T> {
+	if Z.ncols() == 0 {
+		return Mat::zeros(Z.nrows(), 0);
+	}
+	let qr = Z.col_piv_qr();
+	let R = qr.R();
+	let size = Ord::min(R.nrows(), R.ncols());
+	let tol = eps::<T::Real>() * from_f64::<T::Real>(128.0) * abs(R[(0, 0)]);
+	let rank = (0..size).take_while(|&i| abs(R[(i, i)]) > tol).count();
+	qr.compute_thin_Q().subcols(0, rank).to_owned()
+}
+
+/// computes the size and alignment of required workspace for executing the block conjugate
+/// gradient
+pub fn block_cg_scratch<T: ComplexField>(precond: impl Precond<T>, mat: impl LinOp<T>, rhs_ncols: usize, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(M: &dyn Precond<T>, A: &dyn LinOp<T>, k: usize, par: Par) -> StackReq {
+		StackReq::any_of(&[A.apply_scratch(k, par), M.apply_scratch(k, par)])
+	}
+	implementation(&precond, &mat, rhs_ncols, par)
+}
+
+/// computes the size and alignment of required workspace for executing block gmres
+pub fn block_gmres_scratch<T: ComplexField>(precond: impl Precond<T>, mat: impl LinOp<T>, rhs_ncols: usize, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(M: &dyn Precond<T>, A: &dyn LinOp<T>, k: usize, par: Par) -> StackReq {
+		StackReq::any_of(&[A.apply_scratch(k, par), M.apply_scratch(k, par)])
+	}
+	implementation(&precond, &mat, rhs_ncols, par)
+}
+
+/// executes the breakdown-free block conjugate gradient on the self-adjoint positive definite
+/// system $A x = b$, using the provided self-adjoint positive definite preconditioner
+///
+/// each column of $b$ is considered converged once its residual is smaller than the tolerance
+/// computed from its own norm, after which it is removed from the block
+#[track_caller]
+pub fn block_cg<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: BlockCgParams<T::Real>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<BlockKrylovInfo<T::Real>, BlockKrylovError<T::Real>> {
+	block_krylov_result(block_cg_imp(
+		out,
+		&precond,
+		&mat,
+		rhs,
+		params,
+		&mut |_| IterationControl::Continue,
+		par,
+		stack,
+	))
+}
+
+/// executes the breakdown-free block conjugate gradient using the provided preconditioner, and
+/// calls `monitor` at the end of every iteration
+///
+/// the residual norms passed to the monitor are the largest ones over the columns of the
+/// right-hand side. the residuals of the deflated columns aren't updated anymore, so
+/// [`IterationState::residual`] is `None`. the monitor can stop the iteration early, see
+/// [`IterationControl`]
+#[track_caller]
+pub fn block_cg_monitored<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: BlockCgParams<T::Real>,
+	monitor: impl FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	block_cg_imp(out, &precond, &mat, rhs, params, &mut { monitor }, par, stack)
+}
+
+#[track_caller]
+#[math]
+fn block_cg_imp<T: ComplexField>(
+	mut x: MatMut<'_, T>,
+	M: &dyn Precond<T>,
+	A: &dyn LinOp<T>,
+	b: MatRef<'_, T>,
+	params: BlockCgParams<T::Real>,
+	monitor: &mut dyn FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	let (n, k) = b.shape();
+	assert!(all(A.nrows() == n, A.ncols() == n, x.nrows() == n, x.ncols() == k));
+
+	let mut cols = Columns::new(b, copy(params.abs_tolerance), copy(params.rel_tolerance));
+	let all_cols = (0..k).collect::<Vec<_>>();
+	let mut r = if params.initial_guess == InitialGuessStatus::Zero {
+		x.fill(zero());
+		b.to_owned()
+	} else {
+		residual(A, x.rb(), b, &all_cols, par, stack)
+	};
+	cols.update(r.as_ref());
+	r = gather(r.as_ref(), &cols.active);
+	if cols.active.is_empty() {
+		return cols.converged(0);
+	}
+
+	let mut z = Mat::<T>::zeros(n, cols.active.len());
+	M.apply(z.as_mut(), r.as_ref(), par, stack);
+	let mut p = orth(z.as_ref());
+
+	for iter in 0..params.max_iters {
+		let s = p.ncols();
+		let ka = cols.active.len();
+
+		let mut q = Mat::<T>::zeros(n, s);
+		A.apply(q.as_mut(), p.as_ref(), par, stack);
+
+		// p^H A p is self-adjoint positive definite if A is
+		let mut ptq = Mat::<T>::zeros(s, s);
+		linalg::matmul::matmul(ptq.as_mut(), Accum::Replace, p.adjoint(), q.as_ref(), one(), par);
+		let llt = match ptq.llt(Side::Lower) {
+			Ok(llt) => llt,
+			Err(_) => return cols.breakdown(BreakdownReason::NonPositiveDefiniteOperator, iter),
+		};
+
+		// alpha = (p^H A p)^{-1} p^H r
+		let mut alpha = Mat::<T>::zeros(s, ka);
+		linalg::matmul::matmul(alpha.as_mut(), Accum::Replace, p.adjoint(), r.as_ref(), one(), par);
+		llt.solve_in_place(alpha.as_mut());
+
+		let mut dx = Mat::<T>::zeros(n, ka);
+		linalg::matmul::matmul(dx.as_mut(), Accum::Replace, p.as_ref(), alpha.as_ref(), one(), par);
+		scatter_add(x.rb_mut(), dx.as_ref(), &cols.active);
+		linalg::matmul::matmul(r.as_mut(), Accum::Add, q.as_ref(), alpha.as_ref(), -one::<T>(), par);
+
+		// deflate the converged columns
+		let prev = core::mem::take(&mut cols.active);
+		cols.active = prev.clone();
+		cols.update(r.as_ref());
+		let (abs_residual, rel_residual) = cols.residuals();
+		match monitor(IterationState {
+			iter_count: iter + 1,
+			solution: x.rb(),
+			residual: None,
+			abs_residual,
+			rel_residual,
+			rhs_norm: b.norm_l2(),
+			non_exhaustive: NonExhaustive(()),
+		}) {
+			IterationControl::Continue => {},
+			IterationControl::Converged => return cols.converged(iter + 1),
+			IterationControl::Stop => return cols.breakdown(BreakdownReason::Stopped, iter + 1),
+		}
+		if cols.active.is_empty() {
+			return cols.converged(iter + 1);
+		}
+		if cols.active.len() < prev.len() {
+			let keep = prev
+				.iter()
+				.enumerate()
+				.filter(|(_, j)| cols.active.contains(j))
+				.map(|(c, _)| c)
+				.collect::<Vec<_>>();
+			r = gather(r.as_ref(), &keep);
+		}
+
+		let ka = cols.active.len();
+		let mut z = Mat::<T>::zeros(n, ka);
+		M.apply(z.as_mut(), r.as_ref(), par, stack);
+
+		// p = orth(z + p beta), with beta = -(p^H A p)^{-1} (A p)^H z
+		let mut beta = Mat::<T>::zeros(s, ka);
+		linalg::matmul::matmul(beta.as_mut(), Accum::Replace, q.adjoint(), z.as_ref(), -one::<T>(), par);
+		llt.solve_in_place(beta.as_mut());
+		linalg::matmul::matmul(z.as_mut(), Accum::Add, p.as_ref(), beta.as_ref(), one(), par);
+		p = orth(z.as_ref());
+
+		if p.ncols() == 0 {
+			break;
+		}
+	}
+
+	cols.breakdown(BreakdownReason::MaxIterations, params.max_iters)
+}
+
+/// executes the restarted block gmres method on the system $A x = b$, using the provided right
+/// preconditioner $M$, i.e., the method is applied to $A M y = b$ with $x = M y$
+///
+/// each column of $b$ is considered converged once its residual is smaller than the tolerance
+/// computed from its own norm, after which it is removed from the block at the next restart. the
+/// inner iterations stop as soon as the residual estimates of all the active columns are below
+/// their tolerance
+#[track_caller]
+pub fn block_gmres<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: BlockGmresParams<T::Real>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<BlockKrylovInfo<T::Real>, BlockKrylovError<T::Real>> {
+	block_krylov_result(block_gmres_imp(
+		out,
+		&precond,
+		&mat,
+		rhs,
+		params,
+		&mut |_| IterationControl::Continue,
+		par,
+		stack,
+	))
+}
+
+/// executes the restarted block gmres method using the provided right preconditioner, and calls
+/// `monitor` at the end of every inner iteration
+///
+/// the residual norms passed to the monitor are the largest estimates over the columns of the
+/// right-hand side. the solution is only updated at the end of each restart cycle, so within a
+/// cycle [`IterationState::solution`] is the one from the start of the cycle, and
+/// [`IterationState::residual`] is `None`. when the monitor stops the iteration, the solution is
+/// updated before returning. see [`IterationControl`]
+#[track_caller]
+pub fn block_gmres_monitored<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: BlockGmresParams<T::Real>,
+	monitor: impl FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	block_gmres_imp(out, &precond, &mat, rhs, params, &mut { monitor }, par, stack)
+}
+
+#[track_caller]
+#[math]
+fn block_gmres_imp<T: ComplexField>(
+	mut x: MatMut<'_, T>,
+	M: &dyn Precond<T>,
+	A: &dyn LinOp<T>,
+	b: MatRef<'_, T>,
+	params: BlockGmresParams<T::Real>,
+	monitor: &mut dyn FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	let (n, k) = b.shape();
+	assert!(all(A.nrows() == n, A.ncols() == n, x.nrows() == n, x.ncols() == k));
+
+	let mut cols = Columns::new(b, copy(params.abs_tolerance), copy(params.rel_tolerance));
+	if params.initial_guess == InitialGuessStatus::Zero {
+		x.fill(zero());
+	}
+
+	let mut iter_count = 0;
+	loop {
+		// true residual of the active columns
+		let r = residual(A, x.rb(), b, &cols.active, par, stack);
+		let prev = cols.active.clone();
+		cols.update(r.as_ref());
+		if cols.active.is_empty() {
+			return cols.converged(iter_count);
+		}
+		if iter_count >= params.max_iters {
+			return cols.breakdown(BreakdownReason::MaxIterations, iter_count);
+		}
+		let keep = prev
+			.iter()
+			.enumerate()
+			.filter(|(_, j)| cols.active.contains(j))
+			.map(|(c, _)| c)
+			.collect::<Vec<_>>();
+		let r = gather(r.as_ref(), &keep);
+		let ka = cols.active.len();
+
+		// r = V_0 S_0
+		let qr = r.qr();
+		let V0 = qr.compute_thin_Q();
+		let S0 = qr.thin_R();
+		let p = V0.ncols();
+		// the basis can't have more than n columns
+		let steps = Ord::max(1, Ord::min(Ord::max(params.restart, 1), n / Ord::max(p, 1)));
+
+		let mut V = Mat::<T>::zeros(n, (steps + 1) * p);
+		let mut H = Mat::<T>::zeros((steps + 1) * p, steps * p);
+		let mut E = Mat::<T>::zeros((steps + 1) * p, ka);
+		V.as_mut().subcols_mut(0, p).copy_from(&V0);
+		E.as_mut().subrows_mut(0, p).copy_from(S0);
+
+		let mut Mv = Mat::<T>::zeros(n, p);
+		let mut W = Mat::<T>::zeros(n, p);
+		let mut Y = Mat::<T>::zeros(0, ka);
+		let mut control = IterationControl::Continue;
+		let mut estimates = cols.residuals();
+		let mut j = 0;
+		while j < steps {
+			M.apply(Mv.as_mut(), V.as_ref().subcols(j * p, p), par, stack);
+			A.apply(W.as_mut(), Mv.as_ref(), par, stack);
+			iter_count += 1;
+
+			// block classical gram-schmidt, with reorthogonalization
+			let Vj = V.as_ref().subcols(0, (j + 1) * p);
+			for _ in 0..2 {
+				let mut h = Mat::<T>::zeros((j + 1) * p, p);
+				linalg::matmul::matmul(h.as_mut(), Accum::Replace, Vj.adjoint(), W.as_ref(), one(), par);
+				linalg::matmul::matmul(W.as_mut(), Accum::Add, Vj, h.as_ref(), -one::<T>(), par);
+				let mut H_j = H.as_mut().submatrix_mut(0, j * p, (j + 1) * p, p);
+				H_j += &h;
+			}
+			let qr = W.qr();
+			V.as_mut().subcols_mut((j + 1) * p, p).copy_from(qr.compute_thin_Q());
+			H.as_mut().submatrix_mut((j + 1) * p, j * p, p, p).copy_from(qr.thin_R());
+			j += 1;
+
+			// min |E - H Y|
+			let H_j = H.as_ref().submatrix(0, 0, (j + 1) * p, j * p);
+			let E_j = E.as_ref().subrows(0, (j + 1) * p);
+			Y = H_j.qr().solve_lstsq(E_j);
+
+			let mut res = E_j.to_owned();
+			linalg::matmul::matmul(res.as_mut(), Accum::Add, H_j, Y.as_ref(), -one::<T>(), par);
+			estimates = cols.estimated_residuals(res.as_ref());
+			control = monitor(IterationState {
+				iter_count,
+				solution: x.rb(),
+				residual: None,
+				abs_residual: copy(estimates.0),
+				rel_residual: copy(estimates.1),
+				rhs_norm: b.norm_l2(),
+				non_exhaustive: NonExhaustive(()),
+			});
+			let converged = cols.active.iter().enumerate().all(|(c, &col)| res.col(c).norm_l2() < cols.threshold[col]);
+			if converged || control != IterationControl::Continue || iter_count >= params.max_iters {
+				break;
+			}
+		}
+
+		// x += M V Y
+		let mut VY = Mat::<T>::zeros(n, ka);
+		linalg::matmul::matmul(VY.as_mut(), Accum::Replace, V.as_ref().subcols(0, j * p), Y.as_ref(), one(), par);
+		let mut dx = Mat::<T>::zeros(n, ka);
+		M.apply(dx.as_mut(), VY.as_ref(), par, stack);
+		scatter_add(x.rb_mut(), dx.as_ref(), &cols.active);
+
+		match control {
+			IterationControl::Continue => {},
+			IterationControl::Converged => return IterativeSolveResult::converged(iter_count, estimates.0, estimates.1),
+			IterationControl::Stop => return IterativeSolveResult::breakdown(BreakdownReason::Stopped, iter_count, estimates.0, estimates.1),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::DenseSolveCore;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	/// 1d laplacian, with an optional convection term that makes it non-self-adjoint
+	fn laplacian(n: usize, convection: f64) -> Mat<c64> {
+		Mat::from_fn(n, n, |i, j| {
+			if i == j {
+				c64::new(2.0, 0.0)
+			} else if i == j + 1 {
+				c64::new(-1.0 - convection, 0.0)
+			} else if j == i + 1 {
+				c64::new(-1.0 + convection, 0.0)
+			} else {
+				c64::new(0.0, 0.0)
+			}
+		})
+	}
+
+	#[test]
+	fn test_block_cg() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 100;
+		let A = laplacian(n, 0.0) + Mat::<c64>::identity(n, n) * Scale(c64::new(0.01, 0.0));
+
+		// some columns are linear combinations of the others, one is an eigenvector, and one is zero
+		let mut b = Mat::<c64>::random_normal(rng, n, 6);
+		let b0 = b.col(0).to_owned();
+		let b1 = b.col(1).to_owned();
+		b.col_mut(2).copy_from(&b0 * Scale(c64::new(2.0, 1.0)) - &b1);
+		b.col_mut(3).copy_from(Col::<c64>::from_fn(n, |i| {
+			c64::new((core::f64::consts::PI * (i + 1) as f64 / (n + 1) as f64).sin(), 0.0)
+		}));
+		b.col_mut(4).fill(c64::new(0.0, 0.0));
+
+		let params = BlockCgParams {
+			rel_tolerance: 1e-10,
+			..Default::default()
+		};
+		let precond = IdentityPrecond { dim: n };
+		let mut x = Mat::<c64>::random_normal(rng, n, 6);
+		x.col_mut(4).fill(c64::new(0.0, 0.0));
+		let info = block_cg(
+			x.as_mut(),
+			precond,
+			A.as_ref(),
+			b.as_ref(),
+			params,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(block_cg_scratch(precond, A.as_ref(), 6, Par::Seq))),
+		)
+		.unwrap();
+
+		let r = &b - &A * &x;
+		for j in 0..6 {
+			assert!(r.col(j).norm_l2() <= 1e-10 * b.col(j).norm_l2() + 1e-14);
+		}
+		assert!(info.rel_residual <= 1e-10);
+		assert!(info.iter_count <= n);
+
+		// jacobi preconditioner, with a zero initial guess
+		let D = Mat::<c64>::from_fn(n, n, |i, j| if i == j { c64::new(1.0, 0.0) / A[(i, i)] } else { c64::new(0.0, 0.0) });
+		let params = BlockCgParams {
+			initial_guess: InitialGuessStatus::Zero,
+			..params
+		};
+		block_cg(
+			x.as_mut(),
+			D.as_ref(),
+			A.as_ref(),
+			b.as_ref(),
+			params,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(block_cg_scratch(D.as_ref(), A.as_ref(), 6, Par::Seq))),
+		)
+		.unwrap();
+		assert!((&b - &A * &x).norm_l2() <= 1e-9 * b.norm_l2());
+
+		// indefinite operator
+		let B = &A - Mat::<c64>::identity(n, n) * Scale(c64::new(1.0, 0.0));
+		assert!(matches!(
+			block_cg(
+				x.as_mut(),
+				precond,
+				B.as_ref(),
+				b.as_ref(),
+				params,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(block_cg_scratch(precond, B.as_ref(), 6, Par::Seq))),
+			),
+			Err(BlockKrylovError::NonPositiveDefiniteOperator)
+		));
+	}
+
+	#[test]
+	fn test_block_gmres() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 80;
+		let A = laplacian(n, 0.3) + Mat::<c64>::identity(n, n) * Scale(c64::new(1.0, 0.0));
+
+		let mut b = Mat::<c64>::random_normal(rng, n, 4);
+		b.col_mut(3).fill(c64::new(0.0, 0.0));
+
+		let precond = IdentityPrecond { dim: n };
+		for restart in [5, 100] {
+			let params = BlockGmresParams {
+				rel_tolerance: 1e-10,
+				restart,
+				initial_guess: InitialGuessStatus::Zero,
+				..Default::default()
+			};
+			let mut x = Mat::<c64>::zeros(n, 4);
+			let info = block_gmres(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				params,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(block_gmres_scratch(precond, A.as_ref(), 4, Par::Seq))),
+			)
+			.unwrap();
+
+			let r = &b - &A * &x;
+			for j in 0..4 {
+				assert!(r.col(j).norm_l2() <= 1e-10 * b.col(j).norm_l2());
+			}
+			assert!(info.rel_residual <= 1e-10);
+		}
+
+		// the exact inverse as a right preconditioner converges in a single iteration
+		let A_inv = A.partial_piv_lu().inverse();
+		let mut x = Mat::<c64>::random_normal(rng, n, 4);
+		x.col_mut(3).fill(c64::new(0.0, 0.0));
+		let info = block_gmres(
+			x.as_mut(),
+			A_inv.as_ref(),
+			A.as_ref(),
+			b.as_ref(),
+			BlockGmresParams {
+				rel_tolerance: 1e-10,
+				..Default::default()
+			},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(block_gmres_scratch(A_inv.as_ref(), A.as_ref(), 4, Par::Seq))),
+		)
+		.unwrap();
+		assert!(info.iter_count == 1);
+		assert!((&b - &A * &x).norm_l2() <= 1e-10 * b.norm_l2());
+
+		// not enough iterations
+		let mut x = Mat::<c64>::zeros(n, 4);
+		assert!(matches!(
+			block_gmres(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				BlockGmresParams {
+					max_iters: 3,
+					..Default::default()
+				},
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(block_gmres_scratch(precond, A.as_ref(), 4, Par::Seq))),
+			),
+			Err(BlockKrylovError::NoConvergence { .. })
+		));
+	}
+}
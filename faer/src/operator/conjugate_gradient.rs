@@ -1,4 +1,5 @@
 use crate::assert;
+use crate::matrix_free::monitor::{BreakdownReason, IterationControl, IterationState, IterativeSolveResult};
 use crate::matrix_free::*;
 use linalg::cholesky::llt_pivoting::factor as piv_llt;
 use linalg::matmul::triangular::BlockStructure;
@@ -117,218 +118,279 @@ pub fn conjugate_gradient<T: ComplexField>(
 	par: Par,
 	stack: &mut MemStack,
 ) -> Result<CgInfo<T::Real>, CgError<T::Real>> {
-	#[track_caller]
-	#[math]
-	fn implementation<T: ComplexField>(
-		mut x: MatMut<'_, T>,
-		M: &dyn Precond<T>,
-		A: &dyn LinOp<T>,
-		b: MatRef<'_, T>,
-
-		params: CgParams<T::Real>,
-		callback: &mut dyn FnMut(MatRef<'_, T>),
-		par: Par,
-		mut stack: &mut MemStack,
-	) -> Result<CgInfo<T::Real>, CgError<T::Real>> {
-		assert!(A.nrows() == A.ncols());
+	let mut callback = callback;
+	let result = conjugate_gradient_imp(
+		out,
+		&precond,
+		&mat,
+		rhs,
+		params,
+		&mut |state: IterationState<'_, T>| {
+			callback(state.solution);
+			IterationControl::Continue
+		},
+		par,
+		stack,
+	);
+	match result.breakdown {
+		None => Ok(CgInfo {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+			iter_count: result.iter_count,
+			non_exhaustive: NonExhaustive(()),
+		}),
+		Some(BreakdownReason::NonPositiveDefiniteOperator) => Err(CgError::NonPositiveDefiniteOperator),
+		Some(BreakdownReason::NonPositiveDefinitePreconditioner) => Err(CgError::NonPositiveDefinitePreconditioner),
+		Some(_) => Err(CgError::NoConvergence {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+		}),
+	}
+}
 
-		let n = A.nrows();
-		let k = b.ncols();
-		let b_norm = b.norm_l2();
-		if b_norm == zero::<T::Real>() {
-			x.fill(zero());
-			return Ok(CgInfo {
-				abs_residual: zero::<T::Real>(),
-				rel_residual: zero::<T::Real>(),
-				iter_count: 0,
-				non_exhaustive: NonExhaustive(()),
-			});
-		}
+/// executes the conjugate gradient using the provided preconditioner, and calls `monitor` at the
+/// end of every iteration
+///
+/// the monitor can stop the iteration early, see [`IterationControl`]
+#[track_caller]
+pub fn conjugate_gradient_monitored<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: CgParams<T::Real>,
+	monitor: impl FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	conjugate_gradient_imp(out, &precond, &mat, rhs, params, &mut { monitor }, par, stack)
+}
 
-		let rel_threshold = params.rel_tolerance * b_norm;
-		let abs_threshold = params.abs_tolerance;
-
-		let threshold = if abs_threshold > rel_threshold { abs_threshold } else { rel_threshold };
-
-		let (mut r, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
-		let mut r = r.as_mat_mut();
-		let (mut p, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
-		let mut p = p.as_mat_mut();
-		let (mut z, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
-		let mut z = z.as_mat_mut();
-		let (mut rtz, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
-		let mut rtz = rtz.as_mat_mut();
-
-		let (perm, mut stack) = unsafe { stack.rb_mut().make_raw::<usize>(k) };
-		let (perm_inv, mut stack) = unsafe { stack.rb_mut().make_raw::<usize>(k) };
-
-		let abs_residual = if params.initial_guess == InitialGuessStatus::MaybeNonZero {
-			A.apply(r.rb_mut(), x.rb(), par, stack.rb_mut());
-			z!(&mut r, &b).for_each(|uz!(res, rhs)| *res = *rhs - *res);
-			r.norm_l2()
-		} else {
-			copy(b_norm)
-		};
+#[track_caller]
+#[math]
+fn conjugate_gradient_imp<T: ComplexField>(
+	mut x: MatMut<'_, T>,
+	M: &dyn Precond<T>,
+	A: &dyn LinOp<T>,
+	b: MatRef<'_, T>,
 
-		if abs_residual < threshold {
-			return Ok(CgInfo {
-				rel_residual: abs_residual / b_norm,
-				abs_residual,
-				iter_count: 0,
-				non_exhaustive: NonExhaustive(()),
-			});
-		}
+	params: CgParams<T::Real>,
+	monitor: &mut dyn FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	mut stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	assert!(A.nrows() == A.ncols());
+
+	let n = A.nrows();
+	let k = b.ncols();
+	let b_norm = b.norm_l2();
+	if b_norm == zero::<T::Real>() {
+		x.fill(zero());
+		return IterativeSolveResult::converged(0, zero(), zero());
+	}
 
-		let tril = BlockStructure::TriangularLower;
+	let rel_threshold = params.rel_tolerance * b_norm;
+	let abs_threshold = params.abs_tolerance;
+
+	let threshold = if abs_threshold > rel_threshold { abs_threshold } else { rel_threshold };
+
+	let (mut r, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+	let mut r = r.as_mat_mut();
+	let (mut p, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+	let mut p = p.as_mat_mut();
+	let (mut z, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+	let mut z = z.as_mat_mut();
+	let (mut rtz, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
+	let mut rtz = rtz.as_mat_mut();
+
+	let (perm, mut stack) = unsafe { stack.rb_mut().make_raw::<usize>(k) };
+	let (perm_inv, mut stack) = unsafe { stack.rb_mut().make_raw::<usize>(k) };
+
+	let mut abs_residual = if params.initial_guess == InitialGuessStatus::MaybeNonZero {
+		A.apply(r.rb_mut(), x.rb(), par, stack.rb_mut());
+		z!(&mut r, &b).for_each(|uz!(res, rhs)| *res = *rhs - *res);
+		r.norm_l2()
+	} else {
+		copy(b_norm)
+	};
+
+	if abs_residual < threshold {
+		return IterativeSolveResult::converged(0, copy(abs_residual), abs_residual / b_norm);
+	}
 
+	let tril = BlockStructure::TriangularLower;
+
+	{
+		M.apply(p.rb_mut(), r.rb(), par, stack.rb_mut());
+
+		crate::linalg::matmul::triangular::matmul(
+			rtz.rb_mut(),
+			tril,
+			Accum::Replace,
+			r.rb().adjoint(),
+			BlockStructure::Rectangular,
+			p.rb(),
+			BlockStructure::Rectangular,
+			one::<T>(),
+			par,
+		);
+	}
+	for iter in 0..params.max_iters {
 		{
-			M.apply(p.rb_mut(), r.rb(), par, stack.rb_mut());
+			let (mut Ap, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+			let mut Ap = Ap.as_mat_mut();
+			let (mut ptAp, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
+			let mut ptAp = ptAp.as_mat_mut();
 
+			A.apply(Ap.rb_mut(), p.rb(), par, stack.rb_mut());
 			crate::linalg::matmul::triangular::matmul(
-				rtz.rb_mut(),
+				ptAp.rb_mut(),
 				tril,
 				Accum::Replace,
-				r.rb().adjoint(),
+				p.rb().adjoint(),
 				BlockStructure::Rectangular,
-				p.rb(),
+				Ap.rb(),
 				BlockStructure::Rectangular,
 				one::<T>(),
 				par,
 			);
-		}
-		for iter in 0..params.max_iters {
-			{
-				let (mut Ap, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
-				let mut Ap = Ap.as_mat_mut();
-				let (mut ptAp, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
-				let mut ptAp = ptAp.as_mat_mut();
-
-				A.apply(Ap.rb_mut(), p.rb(), par, stack.rb_mut());
-				crate::linalg::matmul::triangular::matmul(
-					ptAp.rb_mut(),
-					tril,
-					Accum::Replace,
-					p.rb().adjoint(),
-					BlockStructure::Rectangular,
-					Ap.rb(),
-					BlockStructure::Rectangular,
-					one::<T>(),
-					par,
-				);
-
-				let (info, llt_perm) = match piv_llt::cholesky_in_place(ptAp.rb_mut(), perm, perm_inv, par, stack.rb_mut(), Default::default()) {
-					Ok(ok) => ok,
-					Err(_) => return Err(CgError::NonPositiveDefiniteOperator),
-				};
-
-				let (mut alpha, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
-				let mut alpha = alpha.as_mat_mut();
-				let (mut alpha_perm, _) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
-				let mut alpha_perm = alpha_perm.as_mat_mut();
-				alpha.copy_from(&rtz);
-				for j in 0..k {
-					for i in 0..j {
-						alpha.write(i, j, conj(alpha[(j, i)]));
-					}
-				}
-				crate::perm::permute_rows(alpha_perm.rb_mut(), alpha.rb(), llt_perm);
-				crate::linalg::triangular_solve::solve_lower_triangular_in_place(
-					ptAp.rb().get(..info.rank, ..info.rank),
-					alpha_perm.rb_mut().get_mut(..info.rank, ..),
-					par,
-				);
-				crate::linalg::triangular_solve::solve_upper_triangular_in_place(
-					ptAp.rb().get(..info.rank, ..info.rank).adjoint(),
-					alpha_perm.rb_mut().get_mut(..info.rank, ..),
-					par,
-				);
-				alpha_perm.rb_mut().get_mut(info.rank.., ..).fill(zero());
-				crate::perm::permute_rows(alpha.rb_mut(), alpha_perm.rb(), llt_perm.inverse());
-
-				crate::linalg::matmul::matmul(
-					x.rb_mut(),
-					if iter == 0 && params.initial_guess == InitialGuessStatus::Zero {
-						Accum::Replace
-					} else {
-						Accum::Add
-					},
-					p.rb(),
-					alpha.rb(),
-					one::<T>(),
-					par,
-				);
-				crate::linalg::matmul::matmul(r.rb_mut(), Accum::Add, Ap.rb(), alpha.rb(), -one::<T>(), par);
-				callback(x.rb());
-			}
 
-			let abs_residual = r.norm_l2();
-			if abs_residual < threshold {
-				return Ok(CgInfo {
-					rel_residual: abs_residual / b_norm,
-					abs_residual,
-					iter_count: iter + 1,
-					non_exhaustive: NonExhaustive(()),
-				});
+			let (info, llt_perm) = match piv_llt::cholesky_in_place(ptAp.rb_mut(), perm, perm_inv, par, stack.rb_mut(), Default::default()) {
+				Ok(ok) => ok,
+				Err(_) => {
+					return IterativeSolveResult::breakdown(
+						BreakdownReason::NonPositiveDefiniteOperator,
+						iter,
+						copy(abs_residual),
+						abs_residual / b_norm,
+					);
+				},
+			};
+
+			let (mut alpha, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
+			let mut alpha = alpha.as_mat_mut();
+			let (mut alpha_perm, _) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
+			let mut alpha_perm = alpha_perm.as_mat_mut();
+			alpha.copy_from(&rtz);
+			for j in 0..k {
+				for i in 0..j {
+					alpha.write(i, j, conj(alpha[(j, i)]));
+				}
 			}
-
-			M.apply(z.rb_mut(), r.rb(), par, stack.rb_mut());
-
-			let (mut rtz_new, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
-			let mut rtz_new = rtz_new.as_mat_mut();
-			crate::linalg::matmul::triangular::matmul(
-				rtz_new.rb_mut(),
-				tril,
-				Accum::Replace,
-				r.rb().adjoint(),
-				BlockStructure::Rectangular,
-				z.rb(),
-				BlockStructure::Rectangular,
+			crate::perm::permute_rows(alpha_perm.rb_mut(), alpha.rb(), llt_perm);
+			crate::linalg::triangular_solve::solve_lower_triangular_in_place(
+				ptAp.rb().get(..info.rank, ..info.rank),
+				alpha_perm.rb_mut().get_mut(..info.rank, ..),
+				par,
+			);
+			crate::linalg::triangular_solve::solve_upper_triangular_in_place(
+				ptAp.rb().get(..info.rank, ..info.rank).adjoint(),
+				alpha_perm.rb_mut().get_mut(..info.rank, ..),
+				par,
+			);
+			alpha_perm.rb_mut().get_mut(info.rank.., ..).fill(zero());
+			crate::perm::permute_rows(alpha.rb_mut(), alpha_perm.rb(), llt_perm.inverse());
+
+			crate::linalg::matmul::matmul(
+				x.rb_mut(),
+				if iter == 0 && params.initial_guess == InitialGuessStatus::Zero {
+					Accum::Replace
+				} else {
+					Accum::Add
+				},
+				p.rb(),
+				alpha.rb(),
 				one::<T>(),
 				par,
 			);
+			crate::linalg::matmul::matmul(r.rb_mut(), Accum::Add, Ap.rb(), alpha.rb(), -one::<T>(), par);
+		}
+
+		abs_residual = r.norm_l2();
+		let rel_residual = abs_residual / b_norm;
+		match monitor(IterationState {
+			iter_count: iter + 1,
+			solution: x.rb(),
+			residual: Some(r.rb()),
+			abs_residual: copy(abs_residual),
+			rel_residual: copy(rel_residual),
+			rhs_norm: copy(b_norm),
+			non_exhaustive: NonExhaustive(()),
+		}) {
+			IterationControl::Continue => {},
+			IterationControl::Converged => return IterativeSolveResult::converged(iter + 1, copy(abs_residual), rel_residual),
+			IterationControl::Stop => {
+				return IterativeSolveResult::breakdown(BreakdownReason::Stopped, iter + 1, copy(abs_residual), rel_residual);
+			},
+		}
+		if abs_residual < threshold {
+			return IterativeSolveResult::converged(iter + 1, copy(abs_residual), rel_residual);
+		}
+
+		M.apply(z.rb_mut(), r.rb(), par, stack.rb_mut());
+
+		let (mut rtz_new, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
+		let mut rtz_new = rtz_new.as_mat_mut();
+		crate::linalg::matmul::triangular::matmul(
+			rtz_new.rb_mut(),
+			tril,
+			Accum::Replace,
+			r.rb().adjoint(),
+			BlockStructure::Rectangular,
+			z.rb(),
+			BlockStructure::Rectangular,
+			one::<T>(),
+			par,
+		);
 
-			{
-				let (info, llt_perm) = match piv_llt::cholesky_in_place(rtz.rb_mut(), perm, perm_inv, par, stack.rb_mut(), Default::default()) {
-					Ok(ok) => ok,
-					Err(_) => return Err(CgError::NonPositiveDefiniteOperator),
-				};
-				let (mut beta, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
-				let mut beta = beta.as_mat_mut();
-				let (mut beta_perm, _) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
-				let mut beta_perm = beta_perm.as_mat_mut();
-				beta.copy_from(&rtz_new);
-				for j in 0..k {
-					for i in 0..j {
-						beta.write(i, j, conj(beta[(j, i)]));
-					}
+		{
+			let (info, llt_perm) = match piv_llt::cholesky_in_place(rtz.rb_mut(), perm, perm_inv, par, stack.rb_mut(), Default::default()) {
+				Ok(ok) => ok,
+				Err(_) => {
+					return IterativeSolveResult::breakdown(
+						BreakdownReason::NonPositiveDefiniteOperator,
+						iter,
+						copy(abs_residual),
+						abs_residual / b_norm,
+					);
+				},
+			};
+			let (mut beta, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
+			let mut beta = beta.as_mat_mut();
+			let (mut beta_perm, _) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack.rb_mut()) };
+			let mut beta_perm = beta_perm.as_mat_mut();
+			beta.copy_from(&rtz_new);
+			for j in 0..k {
+				for i in 0..j {
+					beta.write(i, j, conj(beta[(j, i)]));
 				}
-				crate::perm::permute_rows(beta_perm.rb_mut(), beta.rb(), llt_perm);
-				crate::linalg::triangular_solve::solve_lower_triangular_in_place(
-					rtz.rb().get(..info.rank, ..info.rank),
-					beta_perm.rb_mut().get_mut(..info.rank, ..),
-					par,
-				);
-				crate::linalg::triangular_solve::solve_upper_triangular_in_place(
-					rtz.rb().get(..info.rank, ..info.rank).adjoint(),
-					beta_perm.rb_mut().get_mut(..info.rank, ..),
-					par,
-				);
-				beta_perm.rb_mut().get_mut(info.rank.., ..).fill(zero());
-				crate::perm::permute_rows(beta.rb_mut(), beta_perm.rb(), llt_perm.inverse());
-				rtz.copy_from(&rtz_new);
-
-				crate::linalg::matmul::matmul(z.rb_mut(), Accum::Add, p.rb(), beta.rb(), one::<T>(), par);
-				p.copy_from(&z);
 			}
-		}
+			crate::perm::permute_rows(beta_perm.rb_mut(), beta.rb(), llt_perm);
+			crate::linalg::triangular_solve::solve_lower_triangular_in_place(
+				rtz.rb().get(..info.rank, ..info.rank),
+				beta_perm.rb_mut().get_mut(..info.rank, ..),
+				par,
+			);
+			crate::linalg::triangular_solve::solve_upper_triangular_in_place(
+				rtz.rb().get(..info.rank, ..info.rank).adjoint(),
+				beta_perm.rb_mut().get_mut(..info.rank, ..),
+				par,
+			);
+			beta_perm.rb_mut().get_mut(info.rank.., ..).fill(zero());
+			crate::perm::permute_rows(beta.rb_mut(), beta_perm.rb(), llt_perm.inverse());
+			rtz.copy_from(&rtz_new);
 
-		Err(CgError::NoConvergence {
-			rel_residual: abs_residual / b_norm,
-			abs_residual,
-		})
+			crate::linalg::matmul::matmul(z.rb_mut(), Accum::Add, p.rb(), beta.rb(), one::<T>(), par);
+			p.copy_from(&z);
+		}
 	}
 
-	implementation(out, &precond, &mat, rhs, params, &mut { callback }, par, stack)
+	IterativeSolveResult::breakdown(
+		BreakdownReason::MaxIterations,
+		params.max_iters,
+		copy(abs_residual),
+		abs_residual / b_norm,
+	)
 }
 
 #[cfg(test)]
@@ -428,4 +490,113 @@ mod tests {
 		assert!((A * out - rhs).norm_l2() <= params.rel_tolerance * rhs.norm_l2());
 		assert!(result.iter_count <= 1);
 	}
+
+	#[test]
+	fn test_cg_monitored() {
+		let ref mut rng = StdRng::seed_from_u64(0);
+		let n = 50;
+		let ref B: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+		let ref A = B * B.transpose() + Mat::<f64>::identity(n, n) * Scale(n as f64);
+		let ref rhs: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+		let precond = matrix_free::IdentityPrecond { dim: n };
+		let params = CgParams {
+			rel_tolerance: 1e-12,
+			..Default::default()
+		};
+		let ref mut mem = MemBuffer::new(conjugate_gradient_scratch(precond, A.as_ref(), 2, Par::Seq));
+
+		// convergence history, and consistency of the reported residual
+		let mut history = alloc::vec::Vec::new();
+		let ref mut out = Mat::<f64>::zeros(n, 2);
+		let result = conjugate_gradient_monitored(
+			out.as_mut(),
+			precond,
+			A.as_ref(),
+			rhs.as_ref(),
+			params,
+			|state| {
+				let residual = state.residual.unwrap();
+				assert!((rhs - A * state.solution - residual).norm_l2() <= 1e-10 * rhs.norm_l2());
+				assert!(state.abs_residual == residual.norm_l2());
+				history.push(state.rel_residual);
+				IterationControl::Continue
+			},
+			Par::Seq,
+			MemStack::new(mem),
+		);
+		assert!(result.converged);
+		assert!(result.breakdown.is_none());
+		assert!(history.len() == result.iter_count);
+		assert!(*history.last().unwrap() == result.rel_residual);
+		assert!(result.rel_residual < 1e-12);
+
+		// custom stopping rule
+		let ref mut out = Mat::<f64>::zeros(n, 2);
+		let loose = conjugate_gradient_monitored(
+			out.as_mut(),
+			precond,
+			A.as_ref(),
+			rhs.as_ref(),
+			params,
+			|state| {
+				if state.rel_residual < 1e-3 {
+					IterationControl::Converged
+				} else {
+					IterationControl::Continue
+				}
+			},
+			Par::Seq,
+			MemStack::new(mem),
+		);
+		assert!(loose.converged);
+		assert!(loose.rel_residual < 1e-3);
+		assert!(loose.iter_count < result.iter_count);
+
+		// aborted solve
+		let ref mut out = Mat::<f64>::zeros(n, 2);
+		let stopped = conjugate_gradient_monitored(
+			out.as_mut(),
+			precond,
+			A.as_ref(),
+			rhs.as_ref(),
+			params,
+			|state| {
+				if state.iter_count == 2 {
+					IterationControl::Stop
+				} else {
+					IterationControl::Continue
+				}
+			},
+			Par::Seq,
+			MemStack::new(mem),
+		);
+		assert!(!stopped.converged);
+		assert!(stopped.breakdown == Some(BreakdownReason::Stopped));
+		assert!(stopped.iter_count == 2);
+
+		// not enough iterations
+		let ref mut out = Mat::<f64>::zeros(n, 2);
+		let result = conjugate_gradient_monitored(
+			out.as_mut(),
+			precond,
+			A.as_ref(),
+			rhs.as_ref(),
+			CgParams { max_iters: 1, ..params },
+			|_| IterationControl::Continue,
+			Par::Seq,
+			MemStack::new(mem),
+		);
+		assert!(result.breakdown == Some(BreakdownReason::MaxIterations));
+		assert!(((rhs - A * &*out).norm_l2() - result.abs_residual).abs() <= 1e-10 * rhs.norm_l2());
+	}
 }
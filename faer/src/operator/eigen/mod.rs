@@ -1,3 +1,4 @@
+use super::monitor::{EigenIterationState, IterationControl};
 use super::*;
 use crate::assert;
 use crate::linalg::matmul::matmul;
@@ -301,6 +302,7 @@ fn partial_schur_real_imp<T: RealField>(
 	n_eigval: usize,
 	tol: T,
 	restarts: usize,
+	monitor: &mut dyn FnMut(EigenIterationState) -> IterationControl,
 	par: Par,
 	stack: &mut MemStack,
 ) -> usize {
@@ -348,8 +350,6 @@ fn partial_schur_real_imp<T: RealField>(
 		let mut k = min_dim;
 
 		for iter in 0..restarts {
-			_ = iter;
-
 			iterate_arnoldi(A, H.as_mut(), V.as_mut(), k + 1, max_dim, par, stack);
 
 			let Hmm = abs(H[(max_dim, max_dim - 1)]);
@@ -570,6 +570,16 @@ fn partial_schur_real_imp<T: RealField>(
 			if nlock >= n_eigval {
 				break;
 			}
+
+			let control = monitor(EigenIterationState {
+				restart_count: iter + 1,
+				n_converged_eigen: nlock,
+				n_requested_eigen: n_eigval,
+				non_exhaustive: NonExhaustive(()),
+			});
+			if control != IterationControl::Continue {
+				break;
+			}
 		}
 	} else {
 		let mut H = H.rb_mut().get_mut(..n, ..n);
@@ -718,6 +728,7 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 	n_eigval: usize,
 	tol: T::Real,
 	restarts: usize,
+	monitor: &mut dyn FnMut(EigenIterationState) -> IterationControl,
 	par: Par,
 	stack: &mut MemStack,
 ) -> usize {
@@ -765,8 +776,6 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 		let mut k = min_dim;
 
 		for iter in 0..restarts {
-			_ = iter;
-
 			iterate_arnoldi(A, H.as_mut(), V.as_mut(), k + 1, max_dim, par, stack);
 
 			let Hmm = copy(H[(max_dim, max_dim - 1)]);
@@ -935,6 +944,16 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 			if nlock >= n_eigval {
 				break;
 			}
+
+			let control = monitor(EigenIterationState {
+				restart_count: iter + 1,
+				n_converged_eigen: nlock,
+				n_requested_eigen: n_eigval,
+				non_exhaustive: NonExhaustive(()),
+			});
+			if control != IterationControl::Continue {
+				break;
+			}
 		}
 	} else {
 		let mut H = H.rb_mut().get_mut(..n, ..n);
@@ -990,19 +1009,22 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 	let perm = &mut *perm;
 	let norms = &mut *norms;
 
-	perm.sort_unstable_by(|&i, &j| {
-		if norms[i] > norms[j] {
-			core::cmp::Ordering::Less
-		} else if norms[i] < norms[j] {
-			core::cmp::Ordering::Greater
-		} else {
-			core::cmp::Ordering::Equal
-		}
-	});
-
 	let mut eigvecs = eigvecs;
 	let limit = Ord::min(n, n_eigval);
 
+	// only the eigenvalues with the largest magnitude are kept
+	unsafe {
+		crate::sort::top_k_by(perm.as_mut_ptr(), n, limit, |i, j| {
+			if norms[*i] > norms[*j] {
+				core::cmp::Ordering::Less
+			} else if norms[*i] < norms[*j] {
+				core::cmp::Ordering::Greater
+			} else {
+				core::cmp::Ordering::Equal
+			}
+		})
+	};
+
 	for idx in 0..limit {
 		let j = perm[idx];
 		let w = &H[(j, j)];
@@ -1070,6 +1092,26 @@ pub fn partial_eigen<T: ComplexField>(
 	par: Par,
 	stack: &mut MemStack,
 	params: PartialEigenParams,
+) -> PartialEigenInfo {
+	partial_eigen_monitored(eigvecs, eigvals, A, v0, tolerance, |_| IterationControl::Continue, par, stack, params)
+}
+
+/// computes an estimate of the eigenvalues (and corresponding eigenvectors) of $A$ with the largest
+/// magnitude until the provided outputs are full, the maximum number of algorithm restarts is
+/// reached, or `monitor` requests the algorithm to stop.
+///
+/// `monitor` is called after every restart. when it stops the algorithm, the eigenvalues that have
+/// converged so far are returned.
+pub fn partial_eigen_monitored<T: ComplexField>(
+	eigvecs: MatMut<'_, Complex<T::Real>>,
+	eigvals: &mut [Complex<T::Real>],
+	A: &dyn LinOp<T>,
+	v0: ColRef<'_, T>,
+	tolerance: T::Real,
+	monitor: impl FnMut(EigenIterationState) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+	params: PartialEigenParams,
 ) -> PartialEigenInfo {
 	let n = v0.nrows();
 	assert!(all(
@@ -1102,6 +1144,7 @@ pub fn partial_eigen<T: ComplexField>(
 			n_eigval,
 			tolerance,
 			params.max_restarts,
+			&mut { monitor },
 			par,
 			stack,
 		)
@@ -1116,6 +1159,7 @@ pub fn partial_eigen<T: ComplexField>(
 			n_eigval,
 			tolerance,
 			params.max_restarts,
+			&mut { monitor },
 			par,
 			stack,
 		)
@@ -0,0 +1,647 @@
+//! krylov subspace recycling for sequences of linear systems
+//!
+//! when solving a sequence of slowly varying systems $A_i x_i = b_i$, e.g., the linear systems of
+//! the successive steps of a newton method, restarted gmres throws away the krylov subspace at the
+//! end of each cycle and at the end of each solve, and has to rediscover the directions that slow
+//! down the convergence every time
+//!
+//! [`gcrodr`] implements gcro-dr, the generalized conjugate residual method with inner
+//! orthogonalization and deflated restarting of parks, de sturler, mackey, johnson and maiti. a
+//! small subspace $U$ is kept from one cycle to the next, and from one system to the next through
+//! [`RecycleSpace`], and the krylov subspace of each cycle is built orthogonally to $C = A M U$,
+//! where $M$ is the right preconditioner. the recycled subspace is updated at the end of each
+//! cycle with the directions $z$ of the search space that minimize $\|A M z\| / \|z\|$, which are
+//! approximations of the right singular vectors of $A M$ associated with its smallest singular
+//! values
+//!
+//! when the operator changes between two solves, $C$ is recomputed from $U$ at the start of the
+//! next solve, which costs `recycle_dim` applications of the operator
+
+use super::*;
+use crate::assert;
+use crate::linalg::solvers::{SelfAdjointEigen, SolveLstsq};
+use crate::matrix_free::monitor::{BreakdownReason, IterationControl, IterationState, IterativeSolveResult};
+use alloc::vec::Vec;
+
+/// subspace recycled across the cycles of a solve, and across successive solves
+#[derive(Clone, Debug)]
+pub struct RecycleSpace<T> {
+	U: Mat<T>,
+	C: Mat<T>,
+}
+
+impl<T: ComplexField> RecycleSpace<T> {
+	/// returns an empty recycle space for systems of dimension `dim`
+	pub fn new(dim: usize) -> Self {
+		Self {
+			U: Mat::zeros(dim, 0),
+			C: Mat::zeros(dim, 0),
+		}
+	}
+
+	/// returns the dimension of the systems
+	#[inline]
+	pub fn dim(&self) -> usize {
+		self.U.nrows()
+	}
+
+	/// returns the number of vectors in the recycle space
+	#[inline]
+	pub fn rank(&self) -> usize {
+		self.U.ncols()
+	}
+
+	/// returns a basis of the recycle space
+	#[inline]
+	pub fn U(&self) -> MatRef<'_, T> {
+		self.U.as_ref()
+	}
+
+	/// returns the image of the basis of the recycle space by the last preconditioned operator it
+	/// was used with, which has orthonormal columns
+	#[inline]
+	pub fn C(&self) -> MatRef<'_, T> {
+		self.C.as_ref()
+	}
+
+	/// empties the recycle space
+	pub fn clear(&mut self) {
+		let n = self.dim();
+		*self = Self::new(n);
+	}
+}
+
+/// algorithm parameters
+#[derive(Copy, Clone, Debug)]
+pub struct GcroDrParams<T> {
+	/// whether the initial guess is implicitly zero or not
+	pub initial_guess: InitialGuessStatus,
+	/// absolute tolerance for convergence testing
+	pub abs_tolerance: T,
+	/// relative tolerance for convergence testing
+	pub rel_tolerance: T,
+	/// dimension of the search space of each cycle, including the recycled vectors
+	pub restart: usize,
+	/// maximum number of recycled vectors, must be smaller than `restart`
+	pub recycle_dim: usize,
+	/// maximum number of iterations
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for GcroDrParams<T> {
+	#[inline]
+	#[math]
+	fn default() -> Self {
+		Self {
+			initial_guess: InitialGuessStatus::MaybeNonZero,
+			abs_tolerance: zero(),
+			rel_tolerance: eps::<T>() * from_f64::<T>(128.0),
+			restart: 30,
+			recycle_dim: 10,
+			max_iters: usize::MAX,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// algorithm result
+#[derive(Copy, Clone, Debug)]
+pub struct GcroDrInfo<T> {
+	/// absolute residual at the final step
+	pub abs_residual: T,
+	/// relative residual at the final step
+	pub rel_residual: T,
+	/// number of applications of the operator, including the ones needed to update the recycle
+	/// space after a change of the operator
+	pub iter_count: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// algorithm error
+#[derive(Copy, Clone, Debug)]
+pub enum GcroDrError<T> {
+	/// convergence failure
+	NoConvergence {
+		/// absolute residual at the final step
+		abs_residual: T,
+		/// relative residual at the final step
+		rel_residual: T,
+	},
+}
+
+/// computes the size and alignment of required workspace for executing gcro-dr
+pub fn gcrodr_scratch<T: ComplexField>(precond: impl Precond<T>, mat: impl LinOp<T>, params: GcroDrParams<T::Real>, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(M: &dyn Precond<T>, A: &dyn LinOp<T>, k: usize, par: Par) -> StackReq {
+		StackReq::any_of(&[A.apply_scratch(k, par), M.apply_scratch(k, par)])
+	}
+	implementation(&precond, &mat, Ord::max(params.recycle_dim, 1), par)
+}
+
+/// computes `A M rhs`
+fn apply_precond_op<T: ComplexField>(A: &dyn LinOp<T>, M: &dyn Precond<T>, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+	let mut tmp = Mat::<T>::zeros(rhs.nrows(), rhs.ncols());
+	M.apply(tmp.as_mut(), rhs, par, stack);
+	A.apply(out, tmp.as_ref(), par, stack);
+}
+
+/// computes `x += M z`
+fn update_solution<T: ComplexField>(M: &dyn Precond<T>, x: ColMut<'_, T>, z: ColRef<'_, T>, par: Par, stack: &mut MemStack) {
+	let mut tmp = Col::<T>::zeros(z.nrows());
+	M.apply(tmp.as_mat_mut(), z.as_mat(), par, stack);
+	let mut x = x;
+	x += &tmp;
+}
+
+/// replaces `C` by an orthonormal basis of its range and updates `U` so that `C = A M U` still
+/// holds, or returns `false` if `C` is numerically rank deficient
+#[math]
+fn orthonormalize<T: ComplexField>(U: &mut Mat<T>, C: &mut Mat<T>, par: Par) -> bool {
+	let k = C.ncols();
+	if k == 0 {
+		return true;
+	}
+	let qr = C.qr();
+	let R = qr.thin_R();
+	let mut r_max = zero::<T::Real>();
+	let mut r_min = infinity::<T::Real>();
+	for i in 0..k {
+		r_max = max(r_max, abs(R[(i, i)]));
+		r_min = min(r_min, abs(R[(i, i)]));
+	}
+	if !(r_min > eps::<T::Real>() * from_f64::<T::Real>(128.0) * r_max) {
+		return false;
+	}
+
+	// U R^{-1}
+	linalg::triangular_solve::solve_lower_triangular_in_place(R.transpose(), U.as_mut().transpose_mut(), par);
+	*C = qr.compute_thin_Q();
+	true
+}
+
+#[math]
+fn residuals<T: RealField>(abs_residual: T, b_norm: T) -> (T, T) {
+	let rel_residual = if b_norm > zero::<T>() {
+		abs_residual / b_norm
+	} else if abs_residual == zero::<T>() {
+		zero()
+	} else {
+		infinity()
+	};
+	(abs_residual, rel_residual)
+}
+
+#[math]
+fn threshold<T: RealField>(params: &GcroDrParams<T>, b_norm: T) -> T {
+	max(copy(params.abs_tolerance), params.rel_tolerance * b_norm)
+}
+
+#[math]
+fn scale_col<T: ComplexField>(x: ColMut<'_, T>, s: T::Real) {
+	zip!(x).for_each(|unzip!(x)| *x = mul_real(*x, s));
+}
+
+/// computes the `k` directions `P` in the search space `W_hat` that minimize the rayleigh
+/// quotient `|G p| / |W_hat p|`, where `A M W_hat = W G`
+fn recycled_directions<T: ComplexField>(W_hat: MatRef<'_, T>, G: MatRef<'_, T>, k: usize) -> Option<Mat<T>> {
+	// solve the generalized eigenvalue problem G^H G p = theta W_hat^H W_hat p
+	let WhW = W_hat.adjoint() * W_hat;
+	let GhG = G.adjoint() * G;
+	let llt = WhW.llt(Side::Lower).ok()?;
+	let L = llt.L();
+	let par = Par::Seq;
+
+	let mut T1 = GhG;
+	linalg::triangular_solve::solve_lower_triangular_in_place(L, T1.as_mut(), par);
+	let mut T2 = T1.adjoint().to_owned();
+	linalg::triangular_solve::solve_lower_triangular_in_place(L, T2.as_mut(), par);
+
+	let evd = SelfAdjointEigen::new(T2.as_ref(), Side::Lower).ok()?;
+	let S = evd.S().column_vector();
+	let mut order = (0..S.nrows()).collect::<Vec<_>>();
+	order.sort_by(|&i, &j| real(&S[i]).partial_cmp(&real(&S[j])).unwrap_or(core::cmp::Ordering::Equal));
+
+	let U = evd.U();
+	let mut P = Mat::from_fn(U.nrows(), k, |i, j| U[(i, order[j])].clone());
+	linalg::triangular_solve::solve_upper_triangular_in_place(L.adjoint(), P.as_mut(), par);
+	Some(P)
+}
+
+/// executes gcro-dr on the system $A x = b$, using the provided right preconditioner $M$, and
+/// reusing the subspace stored in `recycle`, which is updated with the subspace of the current
+/// solve
+///
+/// `recycle` should be initialized with [`RecycleSpace::new`] before the first system of the
+/// sequence, and passed unchanged to the following solves. the operator and the preconditioner
+/// may change between two solves
+///
+/// # panics
+///
+/// panics if `params.recycle_dim` is not smaller than `params.restart`, or if the dimensions of
+/// the operator, the right-hand side, the solution or the recycle space don't match
+#[track_caller]
+pub fn gcrodr<T: ComplexField>(
+	out: ColMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: ColRef<'_, T>,
+	recycle: &mut RecycleSpace<T>,
+	params: GcroDrParams<T::Real>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<GcroDrInfo<T::Real>, GcroDrError<T::Real>> {
+	let result = gcrodr_imp(out, &precond, &mat, rhs, recycle, params, &mut |_| IterationControl::Continue, par, stack);
+	if result.converged {
+		Ok(GcroDrInfo {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+			iter_count: result.iter_count,
+			non_exhaustive: NonExhaustive(()),
+		})
+	} else {
+		Err(GcroDrError::NoConvergence {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+		})
+	}
+}
+
+/// executes gcro-dr using the provided right preconditioner and recycle space, and calls
+/// `monitor` at the end of every inner iteration
+///
+/// the residual norm passed to the monitor is the estimate from the least squares problem of the
+/// cycle. the solution is only updated at the end of each cycle, so within a cycle
+/// [`IterationState::solution`] is the one from the start of the cycle, and
+/// [`IterationState::residual`] is `None`. when the monitor stops the iteration, the solution and
+/// the recycle space are updated before returning. see [`IterationControl`]
+///
+/// # panics
+///
+/// see [`gcrodr`]
+#[track_caller]
+pub fn gcrodr_monitored<T: ComplexField>(
+	out: ColMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: ColRef<'_, T>,
+	recycle: &mut RecycleSpace<T>,
+	params: GcroDrParams<T::Real>,
+	monitor: impl FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	gcrodr_imp(out, &precond, &mat, rhs, recycle, params, &mut { monitor }, par, stack)
+}
+
+#[track_caller]
+fn gcrodr_imp<T: ComplexField>(
+	mut x: ColMut<'_, T>,
+	M: &dyn Precond<T>,
+	A: &dyn LinOp<T>,
+	b: ColRef<'_, T>,
+	recycle: &mut RecycleSpace<T>,
+	params: GcroDrParams<T::Real>,
+	monitor: &mut dyn FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	let n = b.nrows();
+	let m = params.restart;
+	assert!(all(
+		A.nrows() == n,
+		A.ncols() == n,
+		x.nrows() == n,
+		recycle.dim() == n,
+		params.recycle_dim < m,
+	));
+
+	let b_norm = b.norm_l2();
+	let threshold = threshold(&params, b_norm.clone());
+	let converged = |r_norm: &T::Real| *r_norm < threshold || *r_norm == zero::<T::Real>();
+
+	if params.initial_guess == InitialGuessStatus::Zero {
+		x.fill(zero());
+	}
+	let mut r = b.to_owned();
+	if params.initial_guess == InitialGuessStatus::MaybeNonZero {
+		let mut Ax = Col::<T>::zeros(n);
+		A.apply(Ax.as_mat_mut(), x.rb().as_mat(), par, stack);
+		r -= &Ax;
+	}
+	let mut iter_count = 0;
+
+	// the operator may have changed since the recycle space was computed, so C = A M U needs
+	// to be recomputed
+	if recycle.rank() > 0 && !converged(&r.norm_l2()) {
+		let k = recycle.rank();
+		let mut C = Mat::<T>::zeros(n, k);
+		apply_precond_op(A, M, C.as_mut(), recycle.U.as_ref(), par, stack);
+		iter_count += k;
+		recycle.C = C;
+		if orthonormalize(&mut recycle.U, &mut recycle.C, par) {
+			// remove the component of the residual in the range of C
+			let c = recycle.C.adjoint() * &r;
+			update_solution(M, x.rb_mut(), (&recycle.U * &c).as_ref(), par, stack);
+			r -= &recycle.C * &c;
+		} else {
+			recycle.clear();
+		}
+	}
+
+	loop {
+		let r_norm = r.norm_l2();
+		if converged(&r_norm) || iter_count >= params.max_iters {
+			let (abs_residual, rel_residual) = residuals(r_norm, b_norm.clone());
+			if converged(&abs_residual) {
+				return IterativeSolveResult::converged(iter_count, abs_residual, rel_residual);
+			} else {
+				return IterativeSolveResult::breakdown(BreakdownReason::MaxIterations, iter_count, abs_residual, rel_residual);
+			}
+		}
+
+		let k = recycle.rank();
+		let s = m - k;
+
+		// unit norm basis of the recycle space, with A M U_tilde = C D
+		let d = (0..k).map(|j| recip(&recycle.U.col(j).norm_l2())).collect::<Vec<_>>();
+		let mut U_tilde = recycle.U.clone();
+		for j in 0..k {
+			scale_col(U_tilde.col_mut(j), d[j].clone());
+		}
+
+		let mut V = Mat::<T>::zeros(n, s + 1);
+		V.col_mut(0).copy_from(&r);
+		scale_col(V.col_mut(0), recip(&r_norm));
+
+		// A M [U_tilde, V_j] = [C, V_{j+1}] G_j, with G_j = [D, B_j; 0, H_j]
+		let mut G = Mat::<T>::zeros(k + s + 1, k + s);
+		for j in 0..k {
+			G[(j, j)] = from_real(&d[j]);
+		}
+		let mut e = Col::<T>::zeros(k + s + 1);
+		e.subrows_mut(0, k).copy_from(recycle.C.adjoint() * &r);
+		e[k] = from_real(&r_norm);
+
+		let mut w = Col::<T>::zeros(n);
+		let mut y = Col::<T>::zeros(0);
+		let mut control = IterationControl::Continue;
+		let mut j = 0;
+		while j < s {
+			apply_precond_op(A, M, w.as_mat_mut(), V.col(j).as_mat(), par, stack);
+			iter_count += 1;
+
+			// orthogonalize against C, then against the krylov basis, with reorthogonalization
+			for _ in 0..2 {
+				let c = recycle.C.adjoint() * &w;
+				w -= &recycle.C * &c;
+				let mut G_c = G.as_mut().col_mut(k + j).subrows_mut(0, k);
+				G_c += &c;
+			}
+			let Vj = V.as_ref().subcols(0, j + 1);
+			for _ in 0..2 {
+				let h = Vj.adjoint() * &w;
+				w -= Vj * &h;
+				let mut G_h = G.as_mut().col_mut(k + j).subrows_mut(k, j + 1);
+				G_h += &h;
+			}
+			let h_next = w.norm_l2();
+			G[(k + j + 1, k + j)] = from_real(&h_next);
+			let breakdown = h_next == zero::<T::Real>();
+			if !breakdown {
+				V.col_mut(j + 1).copy_from(&w);
+				scale_col(V.col_mut(j + 1), recip(&h_next));
+			}
+			j += 1;
+
+			let G_j = G.as_ref().submatrix(0, 0, k + j + 1, k + j);
+			let e_j = e.as_ref().subrows(0, k + j + 1);
+			y = G_j.qr().solve_lstsq(e_j);
+			let estimate = (e_j - G_j * &y).norm_l2();
+			let (abs_residual, rel_residual) = residuals(estimate.clone(), b_norm.clone());
+			control = monitor(IterationState {
+				iter_count,
+				solution: x.rb().as_mat(),
+				residual: None,
+				abs_residual,
+				rel_residual,
+				rhs_norm: b_norm.clone(),
+				non_exhaustive: NonExhaustive(()),
+			});
+			if breakdown || converged(&estimate) || control != IterationControl::Continue || iter_count >= params.max_iters {
+				break;
+			}
+		}
+
+		let G_j = G.as_ref().submatrix(0, 0, k + j + 1, k + j);
+		let W_hat = crate::concat![[U_tilde, V.as_ref().subcols(0, j)]];
+		let W = crate::concat![[recycle.C, V.as_ref().subcols(0, j + 1)]];
+
+		// x += M W_hat y, r -= W G y
+		update_solution(M, x.rb_mut(), (&W_hat * &y).as_ref(), par, stack);
+		r -= &W * (G_j * &y);
+
+		// keep the directions of the search space that are the least amplified by A M
+		let kk = Ord::min(params.recycle_dim, k + j);
+		if kk > 0 {
+			if let Some(P) = recycled_directions(W_hat.as_ref(), G_j, kk) {
+				let mut U = &W_hat * &P;
+				let mut C = &W * (G_j * &P);
+				if orthonormalize(&mut U, &mut C, par) {
+					recycle.U = U;
+					recycle.C = C;
+				}
+			}
+		}
+
+		if control != IterationControl::Continue {
+			let (abs_residual, rel_residual) = residuals(r.norm_l2(), b_norm.clone());
+			return match control {
+				IterationControl::Converged => IterativeSolveResult::converged(iter_count, abs_residual, rel_residual),
+				_ => IterativeSolveResult::breakdown(BreakdownReason::Stopped, iter_count, abs_residual, rel_residual),
+			};
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	/// 1d convection-diffusion operator, shifted by `shift`
+	fn operator(n: usize, convection: f64, shift: f64) -> Mat<c64> {
+		Mat::from_fn(n, n, |i, j| {
+			if i == j {
+				c64::new(2.0 + shift, 0.0)
+			} else if i == j + 1 {
+				c64::new(-1.0 - convection, 0.0)
+			} else if j == i + 1 {
+				c64::new(-1.0 + convection, 0.0)
+			} else {
+				c64::new(0.0, 0.0)
+			}
+		})
+	}
+
+	#[test]
+	fn test_gcrodr() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 200;
+		let params = GcroDrParams {
+			rel_tolerance: 1e-10,
+			restart: 40,
+			recycle_dim: 10,
+			max_iters: 10000,
+			..Default::default()
+		};
+		let precond = IdentityPrecond { dim: n };
+		let mut mem = MemBuffer::new(gcrodr_scratch(precond, Mat::<c64>::zeros(n, n).as_ref(), params, Par::Seq));
+
+		// sequence of slowly varying systems
+		let mut recycle = RecycleSpace::<c64>::new(n);
+		for step in 0..4 {
+			let A = operator(n, 0.2, 0.001 * (1.0 + 0.1 * step as f64));
+			let b = Col::<c64>::from_fn(n, |i| c64::new(1.0 + 0.01 * step as f64 * i as f64 / n as f64, 0.0));
+
+			let mut x = Col::<c64>::zeros(n);
+			let info = gcrodr(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				&mut recycle,
+				params,
+				Par::Seq,
+				MemStack::new(&mut mem),
+			)
+			.unwrap();
+			assert!((&b - &A * &x).norm_l2() <= 1e-9 * b.norm_l2());
+			assert!(info.rel_residual <= 1e-10);
+
+			// C = A U, with orthonormal columns
+			let k = recycle.rank();
+			assert!(all(k > 0, k <= params.recycle_dim));
+			assert!((&A * recycle.U() - recycle.C()).norm_l2() <= 1e-8 * recycle.C().norm_l2());
+			assert!((recycle.C().adjoint() * recycle.C() - Mat::<c64>::identity(k, k)).norm_max() <= 1e-10);
+
+			// the same system solved without recycling
+			let mut x = Col::<c64>::zeros(n);
+			let cold = gcrodr(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				&mut RecycleSpace::new(n),
+				params,
+				Par::Seq,
+				MemStack::new(&mut mem),
+			)
+			.unwrap();
+
+			if step > 0 {
+				assert!(info.iter_count < cold.iter_count);
+			}
+
+			// plain restarted gmres, without deflation
+			let mut x = Col::<c64>::zeros(n);
+			let gmres = gcrodr(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				&mut RecycleSpace::new(n),
+				GcroDrParams { recycle_dim: 0, ..params },
+				Par::Seq,
+				MemStack::new(&mut mem),
+			)
+			.unwrap();
+			assert!(info.iter_count < gmres.iter_count);
+			if step > 0 {
+				assert!(2 * info.iter_count < gmres.iter_count);
+			}
+		}
+
+		// right preconditioner and nonzero initial guess
+		let A = operator(n, 0.2, 0.01);
+		let D = Mat::<c64>::from_fn(n, n, |i, j| if i == j { c64::new(1.0, 0.0) / A[(i, i)] } else { c64::new(0.0, 0.0) });
+		let b = Col::<c64>::from_fn(n, |_| c64::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)));
+		let mut x = Col::<c64>::from_fn(n, |_| c64::new(rng.gen_range(-1.0..1.0), 0.0));
+		gcrodr(
+			x.as_mut(),
+			D.as_ref(),
+			A.as_ref(),
+			b.as_ref(),
+			&mut recycle,
+			params,
+			Par::Seq,
+			MemStack::new(&mut mem),
+		)
+		.unwrap();
+		assert!((&b - &A * &x).norm_l2() <= 1e-9 * b.norm_l2());
+
+		// not enough iterations
+		let mut x = Col::<c64>::zeros(n);
+		assert!(matches!(
+			gcrodr(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				&mut RecycleSpace::new(n),
+				GcroDrParams { max_iters: 5, ..params },
+				Par::Seq,
+				MemStack::new(&mut mem),
+			),
+			Err(GcroDrError::NoConvergence { .. })
+		));
+	}
+
+	#[test]
+	fn test_gcrodr_real() {
+		let n = 100;
+		let A = Mat::<f64>::from_fn(n, n, |i, j| {
+			if i == j {
+				2.0 + 0.02
+			} else if i.abs_diff(j) == 1 {
+				-1.0
+			} else {
+				0.0
+			}
+		});
+		let b = Col::<f64>::from_fn(n, |i| (i as f64).sin());
+		let precond = IdentityPrecond { dim: n };
+		let params = GcroDrParams {
+			rel_tolerance: 1e-10,
+			restart: 15,
+			recycle_dim: 5,
+			..Default::default()
+		};
+		let mut mem = MemBuffer::new(gcrodr_scratch(precond, A.as_ref(), params, Par::Seq));
+
+		let mut recycle = RecycleSpace::new(n);
+		let mut iters = Vec::new();
+		for _ in 0..2 {
+			let mut x = Col::<f64>::zeros(n);
+			let info = gcrodr(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				&mut recycle,
+				params,
+				Par::Seq,
+				MemStack::new(&mut mem),
+			)
+			.unwrap();
+			assert!((&b - &A * &x).norm_l2() <= 1e-9 * b.norm_l2());
+			iters.push(info.iter_count);
+		}
+		assert!(iters[1] < iters[0]);
+	}
+}
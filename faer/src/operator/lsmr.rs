@@ -1,4 +1,5 @@
 use super::*;
+use crate::matrix_free::monitor::{BreakdownReason, IterationControl, IterationState, IterativeSolveResult};
 use crate::{assert, debug_assert};
 use linalg::matmul::matmul;
 use linalg::{householder, qr};
@@ -150,121 +151,267 @@ pub fn lsmr<T: ComplexField>(
 	par: Par,
 	stack: &mut MemStack,
 ) -> Result<LsmrInfo<T::Real>, LsmrError<T::Real>> {
-	#[track_caller]
-	#[math]
-	fn implementation<T: ComplexField>(
-		mut x: MatMut<'_, T>,
-		M: &impl BiPrecond<T>,
-		A: &impl BiLinOp<T>,
-		b: MatRef<'_, T>,
-		params: LsmrParams<T::Real>,
-		callback: &mut dyn FnMut(MatRef<'_, T>),
-		par: Par,
-		stack: &mut MemStack,
-	) -> Result<LsmrInfo<T::Real>, LsmrError<T::Real>> {
-		fn thin_qr<T: ComplexField>(mut Q: MatMut<'_, T>, mut R: MatMut<'_, T>, mut mat: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
-			let k = R.nrows();
-			let bs = qr::no_pivoting::factor::recommended_blocksize::<T>(mat.nrows(), mat.ncols());
-			let (mut house, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(bs, Ord::min(mat.nrows(), mat.ncols()), stack) };
-			let mut house = house.as_mat_mut();
-
-			qr::no_pivoting::factor::qr_in_place(mat.rb_mut(), house.rb_mut(), par, stack.rb_mut(), Default::default());
-
-			R.fill(zero());
-			R.copy_from_triangular_upper(mat.rb().get(..k, ..k));
-			Q.fill(zero());
-			Q.rb_mut().diagonal_mut().column_vector_mut().fill(one::<T>());
-			householder::apply_block_householder_sequence_on_the_left_in_place_with_conj(
-				mat.rb(),
-				house.rb(),
-				Conj::No,
-				Q.rb_mut(),
-				par,
-				stack.rb_mut(),
-			);
-		}
-
-		let m = A.nrows();
-		let n = A.ncols();
-		let mut k = b.ncols();
-		{
-			let out = x.rb();
-			let mat = A;
-			let right_precond = M;
-			let rhs = b;
-			assert!(all(
-				right_precond.nrows() == mat.ncols(),
-				right_precond.ncols() == mat.ncols(),
-				rhs.nrows() == mat.nrows(),
-				out.nrows() == mat.ncols(),
-				out.ncols() == rhs.ncols(),
-			));
-		}
+	let mut callback = callback;
+	let result = lsmr_imp(
+		out,
+		&right_precond,
+		&mat,
+		rhs,
+		params,
+		&mut |state: IterationState<'_, T>| {
+			callback(state.solution);
+			IterationControl::Continue
+		},
+		par,
+		stack,
+	);
+	if result.converged {
+		Ok(LsmrInfo {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+			iter_count: result.iter_count,
+			non_exhaustive: NonExhaustive(()),
+		})
+	} else {
+		Err(LsmrError::NoConvergence {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+		})
+	}
+}
 
-		if m == 0 || n == 0 || k == 0 || core::mem::size_of::<T::Unit>() == 0 {
-			x.fill(zero());
-			return Ok(LsmrInfo {
-				abs_residual: zero::<T::Real>(),
-				rel_residual: zero::<T::Real>(),
-				iter_count: 0,
-				non_exhaustive: NonExhaustive(()),
-			});
-		}
+/// executes lsmr using the provided preconditioner, and calls `monitor` at the end of every
+/// iteration
+///
+/// the residual norms passed to the monitor are the estimates tracked by the algorithm, and the
+/// residual vector isn't formed, so [`IterationState::residual`] is `None`. the monitor can stop
+/// the iteration early, see [`IterationControl`]
+#[track_caller]
+pub fn lsmr_monitored<T: ComplexField>(
+	out: MatMut<'_, T>,
+	right_precond: impl BiPrecond<T>,
+	mat: impl BiLinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: LsmrParams<T::Real>,
+	monitor: impl FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	lsmr_imp(out, &right_precond, &mat, rhs, params, &mut { monitor }, par, stack)
+}
 
-		debug_assert!(all(m < isize::MAX as usize, n < isize::MAX as usize, k < isize::MAX as usize));
-		let actual_k = k;
-		if k > n {
-			// pad to avoid last block slowing down the rest
-			k = k.msrv_checked_next_multiple_of(n).unwrap();
-		}
-		debug_assert!(k < isize::MAX as usize);
+#[track_caller]
+#[math]
+fn lsmr_imp<T: ComplexField>(
+	mut x: MatMut<'_, T>,
+	M: &impl BiPrecond<T>,
+	A: &impl BiLinOp<T>,
+	b: MatRef<'_, T>,
+	params: LsmrParams<T::Real>,
+	monitor: &mut dyn FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	fn thin_qr<T: ComplexField>(mut Q: MatMut<'_, T>, mut R: MatMut<'_, T>, mut mat: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		let k = R.nrows();
+		let bs = qr::no_pivoting::factor::recommended_blocksize::<T>(mat.nrows(), mat.ncols());
+		let (mut house, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(bs, Ord::min(mat.nrows(), mat.ncols()), stack) };
+		let mut house = house.as_mat_mut();
+
+		qr::no_pivoting::factor::qr_in_place(mat.rb_mut(), house.rb_mut(), par, stack.rb_mut(), Default::default());
+
+		R.fill(zero());
+		R.copy_from_triangular_upper(mat.rb().get(..k, ..k));
+		Q.fill(zero());
+		Q.rb_mut().diagonal_mut().column_vector_mut().fill(one::<T>());
+		householder::apply_block_householder_sequence_on_the_left_in_place_with_conj(mat.rb(), house.rb(), Conj::No, Q.rb_mut(), par, stack.rb_mut());
+	}
 
-		let s = Ord::min(k, Ord::min(n, m));
+	let m = A.nrows();
+	let n = A.ncols();
+	let mut k = b.ncols();
+	{
+		let out = x.rb();
+		let mat = A;
+		let right_precond = M;
+		let rhs = b;
+		assert!(all(
+			right_precond.nrows() == mat.ncols(),
+			right_precond.ncols() == mat.ncols(),
+			rhs.nrows() == mat.nrows(),
+			out.nrows() == mat.ncols(),
+			out.ncols() == rhs.ncols(),
+		));
+	}
 
-		let mut stack = stack;
+	if m == 0 || n == 0 || k == 0 || core::mem::size_of::<T::Unit>() == 0 {
+		x.fill(zero());
+		return IterativeSolveResult::converged(0, zero(), zero());
+	}
 
-		let (mut u, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(m, k, stack.rb_mut()) };
-		let mut u = u.as_mat_mut();
-		let (mut beta, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
-		let mut beta = beta.as_mat_mut();
+	debug_assert!(all(m < isize::MAX as usize, n < isize::MAX as usize, k < isize::MAX as usize));
+	let actual_k = k;
+	if k > n {
+		// pad to avoid last block slowing down the rest
+		k = k.msrv_checked_next_multiple_of(n).unwrap();
+	}
+	debug_assert!(k < isize::MAX as usize);
+
+	let s = Ord::min(k, Ord::min(n, m));
+
+	let mut stack = stack;
+
+	let (mut u, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(m, k, stack.rb_mut()) };
+	let mut u = u.as_mat_mut();
+	let (mut beta, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
+	let mut beta = beta.as_mat_mut();
+
+	let (mut v, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+	let mut v = v.as_mat_mut();
+	let (mut alpha, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
+	let mut alpha = alpha.as_mat_mut();
+
+	let (mut zetabar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
+	let mut zetabar = zetabar.as_mat_mut();
+	let (mut alphabar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
+	let mut alphabar = alphabar.as_mat_mut();
+	let (mut theta, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
+	let mut theta = theta.as_mat_mut();
+	let (mut pbar_adjoint, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * s, 2 * k, stack.rb_mut()) };
+	let mut pbar_adjoint = pbar_adjoint.as_mat_mut();
+
+	let (mut w, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+	let mut w = w.as_mat_mut();
+	let (mut wbar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+	let mut wbar = wbar.as_mat_mut();
+
+	{
+		let (mut qr, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(m, k, stack.rb_mut()) };
+		let mut qr = qr.as_mat_mut();
+		if params.initial_guess == InitialGuessStatus::Zero {
+			qr.rb_mut().get_mut(.., ..actual_k).copy_from(b);
+			qr.rb_mut().get_mut(.., actual_k..).fill(zero());
+		} else {
+			A.apply(qr.rb_mut().rb_mut().get_mut(.., ..actual_k), x.rb(), par, stack.rb_mut());
+			z!(qr.rb_mut().get_mut(.., ..actual_k), &b).for_each(|uz!(ax, b)| *ax = *b - *ax);
+			qr.rb_mut().get_mut(.., actual_k..).fill(zero());
+		}
+		let mut start = 0;
+		while start < k {
+			let end = Ord::min(k - start, s) + start;
+			let len = end - start;
+			thin_qr(
+				u.rb_mut().get_mut(.., start..end),
+				beta.rb_mut().get_mut(..len, start..end),
+				qr.rb_mut().get_mut(.., start..end),
+				par,
+				stack.rb_mut(),
+			);
+			start = end;
+		}
+	}
 
-		let (mut v, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
-		let mut v = v.as_mat_mut();
-		let (mut alpha, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
-		let mut alpha = alpha.as_mat_mut();
+	{
+		let (mut qr, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+		let mut qr = qr.as_mat_mut();
+		A.adjoint_apply(qr.rb_mut(), u.rb(), par, stack.rb_mut());
+		M.adjoint_apply_in_place(qr.rb_mut(), par, stack.rb_mut());
+		let mut start = 0;
+		while start < k {
+			let end = Ord::min(k - start, s) + start;
+			let len = end - start;
+			thin_qr(
+				v.rb_mut().get_mut(.., start..end),
+				alpha.rb_mut().get_mut(..len, start..end),
+				qr.rb_mut().get_mut(.., start..end),
+				par,
+				stack.rb_mut(),
+			);
+			start = end;
+		}
+	}
 
-		let (mut zetabar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
-		let mut zetabar = zetabar.as_mat_mut();
-		let (mut alphabar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
-		let mut alphabar = alphabar.as_mat_mut();
-		let (mut theta, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, k, stack.rb_mut()) };
-		let mut theta = theta.as_mat_mut();
-		let (mut pbar_adjoint, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * s, 2 * k, stack.rb_mut()) };
-		let mut pbar_adjoint = pbar_adjoint.as_mat_mut();
+	zetabar.fill(zero());
+	let mut start = 0;
+	while start < k {
+		let end = Ord::min(k - start, s) + start;
+		let len = end - start;
+		matmul(
+			zetabar.rb_mut().get_mut(..len, start..end),
+			Accum::Replace,
+			alpha.rb().get(..len, start..end),
+			beta.rb().get(..len, start..end),
+			one::<T>(),
+			par,
+		);
+		start = end;
+	}
+	alphabar.copy_from(&alpha);
+	pbar_adjoint.fill(zero());
+	let mut start = 0;
+	while start < k {
+		let end = Ord::min(k - start, s) + start;
+		let len = end - start;
+		pbar_adjoint
+			.rb_mut()
+			.get_mut(..2 * len, 2 * start..2 * end)
+			.diagonal_mut()
+			.column_vector_mut()
+			.fill(one());
+		start = end;
+	}
+	theta.fill(zero());
+	w.fill(zero());
+	wbar.fill(zero());
+
+	let mut norm;
+	let norm_ref = if params.initial_guess == InitialGuessStatus::Zero {
+		norm = zetabar.norm_l2();
+		copy(norm)
+	} else {
+		norm = zetabar.norm_l2();
+		let (mut tmp, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, actual_k, stack.rb_mut()) };
+		let mut tmp = tmp.as_mat_mut();
+		A.adjoint_apply(tmp.rb_mut(), b, par, stack.rb_mut());
+		M.adjoint_apply_in_place(tmp.rb_mut(), par, stack.rb_mut());
+		tmp.norm_l2()
+	};
+	let threshold = norm_ref * params.rel_tolerance;
+
+	if norm_ref == zero::<T::Real>() {
+		x.fill(zero());
+		return IterativeSolveResult::converged(0, zero(), zero());
+	}
 
-		let (mut w, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
-		let mut w = w.as_mat_mut();
-		let (mut wbar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
-		let mut wbar = wbar.as_mat_mut();
+	if norm <= threshold {
+		return IterativeSolveResult::converged(0, zero(), zero());
+	}
 
+	for iter in 0..params.max_iters {
+		let (mut vold, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+		let mut vold = vold.as_mat_mut();
 		{
 			let (mut qr, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(m, k, stack.rb_mut()) };
 			let mut qr = qr.as_mat_mut();
-			if params.initial_guess == InitialGuessStatus::Zero {
-				qr.rb_mut().get_mut(.., ..actual_k).copy_from(b);
-				qr.rb_mut().get_mut(.., actual_k..).fill(zero());
-			} else {
-				A.apply(qr.rb_mut().rb_mut().get_mut(.., ..actual_k), x.rb(), par, stack.rb_mut());
-				z!(qr.rb_mut().get_mut(.., ..actual_k), &b).for_each(|uz!(ax, b)| *ax = *b - *ax);
-				qr.rb_mut().get_mut(.., actual_k..).fill(zero());
-			}
+			vold.copy_from(&v);
+			M.apply_in_place(v.rb_mut(), par, stack.rb_mut());
+			A.apply(qr.rb_mut(), v.rb(), par, stack.rb_mut());
+
 			let mut start = 0;
 			while start < k {
-				let end = Ord::min(k - start, s) + start;
-				let len = end - start;
+				let s = Ord::min(k - start, s);
+				let end = start + s;
+				matmul(
+					qr.rb_mut().get_mut(.., start..end),
+					Accum::Add,
+					u.rb().get(.., start..end),
+					alpha.rb().get(..s, start..end).adjoint(),
+					-one::<T>(),
+					par,
+				);
 				thin_qr(
 					u.rb_mut().get_mut(.., start..end),
-					beta.rb_mut().get_mut(..len, start..end),
+					beta.rb_mut().get_mut(..s, start..end),
 					qr.rb_mut().get_mut(.., start..end),
 					par,
 					stack.rb_mut(),
@@ -278,13 +425,26 @@ pub fn lsmr<T: ComplexField>(
 			let mut qr = qr.as_mat_mut();
 			A.adjoint_apply(qr.rb_mut(), u.rb(), par, stack.rb_mut());
 			M.adjoint_apply_in_place(qr.rb_mut(), par, stack.rb_mut());
+
 			let mut start = 0;
 			while start < k {
-				let end = Ord::min(k - start, s) + start;
-				let len = end - start;
+				let s = Ord::min(k - start, s);
+				let end = start + s;
+				matmul(
+					qr.rb_mut().get_mut(.., start..end),
+					Accum::Add,
+					vold.rb().get(.., start..end),
+					beta.rb().get(..s, start..end).adjoint(),
+					-one::<T>(),
+					par,
+				);
+
+				// now contains M v_old
+				vold.rb_mut().get_mut(.., start..end).copy_from(v.rb().get(.., start..end));
+
 				thin_qr(
 					v.rb_mut().get_mut(.., start..end),
-					alpha.rb_mut().get_mut(..len, start..end),
+					alpha.rb_mut().get_mut(..s, start..end),
 					qr.rb_mut().get_mut(.., start..end),
 					par,
 					stack.rb_mut(),
@@ -293,274 +453,145 @@ pub fn lsmr<T: ComplexField>(
 			}
 		}
 
-		zetabar.fill(zero());
+		let mut Mvold = vold;
+
 		let mut start = 0;
 		while start < k {
-			let end = Ord::min(k - start, s) + start;
-			let len = end - start;
+			let s = Ord::min(k - start, s);
+			let end = start + s;
+
+			let mut x = x.rb_mut().get_mut(.., start..Ord::min(actual_k, end));
+			let mut Mvold = Mvold.rb_mut().get_mut(.., start..end);
+			let mut w = w.rb_mut().get_mut(.., start..end);
+			let mut wbar = wbar.rb_mut().get_mut(.., start..end);
+
+			let alpha = alpha.rb_mut().get_mut(..s, start..end);
+			let beta = beta.rb_mut().get_mut(..s, start..end);
+			let mut zetabar = zetabar.rb_mut().get_mut(..s, start..end);
+			let mut alphabar = alphabar.rb_mut().get_mut(..s, start..end);
+			let mut theta = theta.rb_mut().get_mut(..s, start..end);
+			let mut pbar_adjoint = pbar_adjoint.rb_mut().get_mut(..2 * s, 2 * start..2 * end);
+
+			let (mut p_adjoint, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * s, 2 * s, stack.rb_mut()) };
+			let mut p_adjoint = p_adjoint.as_mat_mut();
+
+			let (mut rho, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
+			let mut rho = rho.as_mat_mut();
+			let (mut thetaold, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
+			let mut thetaold = thetaold.as_mat_mut();
+			let (mut rhobar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
+			let mut rhobar = rhobar.as_mat_mut();
+			let (mut thetabar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
+			let mut thetabar = thetabar.as_mat_mut();
+			let (mut zeta, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
+			let mut zeta = zeta.as_mat_mut();
+			let (mut zetabar_tmp, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
+			let mut zetabar_tmp = zetabar_tmp.as_mat_mut();
+
+			{
+				let (mut qr, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * s, s, stack.rb_mut()) };
+				let mut qr = qr.as_mat_mut();
+				qr.rb_mut().get_mut(..s, ..).copy_from(alphabar.rb().adjoint());
+				qr.rb_mut().get_mut(s.., ..).copy_from(&beta);
+				thin_qr(p_adjoint.rb_mut(), rho.rb_mut(), qr.rb_mut(), par, stack.rb_mut());
+			}
+
+			thetaold.copy_from(&theta);
+			matmul(theta.rb_mut(), Accum::Replace, alpha.rb(), p_adjoint.rb().get(s.., ..s), one::<T>(), par);
 			matmul(
-				zetabar.rb_mut().get_mut(..len, start..end),
+				alphabar.rb_mut(),
 				Accum::Replace,
-				alpha.rb().get(..len, start..end),
-				beta.rb().get(..len, start..end),
+				alpha.rb(),
+				p_adjoint.rb().get(s.., s..),
 				one::<T>(),
 				par,
 			);
-			start = end;
-		}
-		alphabar.copy_from(&alpha);
-		pbar_adjoint.fill(zero());
-		let mut start = 0;
-		while start < k {
-			let end = Ord::min(k - start, s) + start;
-			let len = end - start;
-			pbar_adjoint
-				.rb_mut()
-				.get_mut(..2 * len, 2 * start..2 * end)
-				.diagonal_mut()
-				.column_vector_mut()
-				.fill(one());
-			start = end;
-		}
-		theta.fill(zero());
-		w.fill(zero());
-		wbar.fill(zero());
-
-		let mut norm;
-		let norm_ref = if params.initial_guess == InitialGuessStatus::Zero {
-			norm = zetabar.norm_l2();
-			copy(norm)
-		} else {
-			norm = zetabar.norm_l2();
-			let (mut tmp, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, actual_k, stack.rb_mut()) };
-			let mut tmp = tmp.as_mat_mut();
-			A.adjoint_apply(tmp.rb_mut(), b, par, stack.rb_mut());
-			M.adjoint_apply_in_place(tmp.rb_mut(), par, stack.rb_mut());
-			tmp.norm_l2()
-		};
-		let threshold = norm_ref * params.rel_tolerance;
-
-		if norm_ref == zero::<T::Real>() {
-			x.fill(zero());
-			return Ok(LsmrInfo {
-				abs_residual: zero::<T::Real>(),
-				rel_residual: zero::<T::Real>(),
-				iter_count: 0,
-				non_exhaustive: NonExhaustive(()),
-			});
-		}
-
-		if norm <= threshold {
-			return Ok(LsmrInfo {
-				abs_residual: zero::<T::Real>(),
-				rel_residual: zero::<T::Real>(),
-				iter_count: 0,
-				non_exhaustive: NonExhaustive(()),
-			});
-		}
-
-		for iter in 0..params.max_iters {
-			let (mut vold, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
-			let mut vold = vold.as_mat_mut();
-			{
-				let (mut qr, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(m, k, stack.rb_mut()) };
-				let mut qr = qr.as_mat_mut();
-				vold.copy_from(&v);
-				M.apply_in_place(v.rb_mut(), par, stack.rb_mut());
-				A.apply(qr.rb_mut(), v.rb(), par, stack.rb_mut());
-
-				let mut start = 0;
-				while start < k {
-					let s = Ord::min(k - start, s);
-					let end = start + s;
-					matmul(
-						qr.rb_mut().get_mut(.., start..end),
-						Accum::Add,
-						u.rb().get(.., start..end),
-						alpha.rb().get(..s, start..end).adjoint(),
-						-one::<T>(),
-						par,
-					);
-					thin_qr(
-						u.rb_mut().get_mut(.., start..end),
-						beta.rb_mut().get_mut(..s, start..end),
-						qr.rb_mut().get_mut(.., start..end),
-						par,
-						stack.rb_mut(),
-					);
-					start = end;
-				}
-			}
 
+			matmul(
+				thetabar.rb_mut(),
+				Accum::Replace,
+				rho.rb(),
+				pbar_adjoint.rb().get(s.., ..s),
+				one::<T>(),
+				par,
+			);
 			{
-				let (mut qr, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(n, k, stack.rb_mut()) };
+				let (mut qr, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * s, s, stack.rb_mut()) };
 				let mut qr = qr.as_mat_mut();
-				A.adjoint_apply(qr.rb_mut(), u.rb(), par, stack.rb_mut());
-				M.adjoint_apply_in_place(qr.rb_mut(), par, stack.rb_mut());
-
-				let mut start = 0;
-				while start < k {
-					let s = Ord::min(k - start, s);
-					let end = start + s;
-					matmul(
-						qr.rb_mut().get_mut(.., start..end),
-						Accum::Add,
-						vold.rb().get(.., start..end),
-						beta.rb().get(..s, start..end).adjoint(),
-						-one::<T>(),
-						par,
-					);
-
-					// now contains M v_old
-					vold.rb_mut().get_mut(.., start..end).copy_from(v.rb().get(.., start..end));
-
-					thin_qr(
-						v.rb_mut().get_mut(.., start..end),
-						alpha.rb_mut().get_mut(..s, start..end),
-						qr.rb_mut().get_mut(.., start..end),
-						par,
-						stack.rb_mut(),
-					);
-					start = end;
-				}
-			}
-
-			let mut Mvold = vold;
-
-			let mut start = 0;
-			while start < k {
-				let s = Ord::min(k - start, s);
-				let end = start + s;
-
-				let mut x = x.rb_mut().get_mut(.., start..Ord::min(actual_k, end));
-				let mut Mvold = Mvold.rb_mut().get_mut(.., start..end);
-				let mut w = w.rb_mut().get_mut(.., start..end);
-				let mut wbar = wbar.rb_mut().get_mut(.., start..end);
-
-				let alpha = alpha.rb_mut().get_mut(..s, start..end);
-				let beta = beta.rb_mut().get_mut(..s, start..end);
-				let mut zetabar = zetabar.rb_mut().get_mut(..s, start..end);
-				let mut alphabar = alphabar.rb_mut().get_mut(..s, start..end);
-				let mut theta = theta.rb_mut().get_mut(..s, start..end);
-				let mut pbar_adjoint = pbar_adjoint.rb_mut().get_mut(..2 * s, 2 * start..2 * end);
-
-				let (mut p_adjoint, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * s, 2 * s, stack.rb_mut()) };
-				let mut p_adjoint = p_adjoint.as_mat_mut();
-
-				let (mut rho, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
-				let mut rho = rho.as_mat_mut();
-				let (mut thetaold, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
-				let mut thetaold = thetaold.as_mat_mut();
-				let (mut rhobar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
-				let mut rhobar = rhobar.as_mat_mut();
-				let (mut thetabar, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
-				let mut thetabar = thetabar.as_mat_mut();
-				let (mut zeta, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
-				let mut zeta = zeta.as_mat_mut();
-				let (mut zetabar_tmp, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack.rb_mut()) };
-				let mut zetabar_tmp = zetabar_tmp.as_mat_mut();
-
-				{
-					let (mut qr, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * s, s, stack.rb_mut()) };
-					let mut qr = qr.as_mat_mut();
-					qr.rb_mut().get_mut(..s, ..).copy_from(alphabar.rb().adjoint());
-					qr.rb_mut().get_mut(s.., ..).copy_from(&beta);
-					thin_qr(p_adjoint.rb_mut(), rho.rb_mut(), qr.rb_mut(), par, stack.rb_mut());
-				}
-
-				thetaold.copy_from(&theta);
-				matmul(theta.rb_mut(), Accum::Replace, alpha.rb(), p_adjoint.rb().get(s.., ..s), one::<T>(), par);
-				matmul(
-					alphabar.rb_mut(),
-					Accum::Replace,
-					alpha.rb(),
-					p_adjoint.rb().get(s.., s..),
-					one::<T>(),
-					par,
-				);
-
 				matmul(
-					thetabar.rb_mut(),
+					qr.rb_mut().get_mut(..s, ..),
 					Accum::Replace,
-					rho.rb(),
-					pbar_adjoint.rb().get(s.., ..s),
+					pbar_adjoint.rb().adjoint().get(s.., s..),
+					rho.rb().adjoint(),
 					one::<T>(),
 					par,
 				);
-				{
-					let (mut qr, mut stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * s, s, stack.rb_mut()) };
-					let mut qr = qr.as_mat_mut();
-					matmul(
-						qr.rb_mut().get_mut(..s, ..),
-						Accum::Replace,
-						pbar_adjoint.rb().adjoint().get(s.., s..),
-						rho.rb().adjoint(),
-						one::<T>(),
-						par,
-					);
-					qr.rb_mut().get_mut(s.., ..).copy_from(&theta);
-					thin_qr(pbar_adjoint.rb_mut(), rhobar.rb_mut(), qr.rb_mut(), par, stack.rb_mut());
-				}
+				qr.rb_mut().get_mut(s.., ..).copy_from(&theta);
+				thin_qr(pbar_adjoint.rb_mut(), rhobar.rb_mut(), qr.rb_mut(), par, stack.rb_mut());
+			}
 
-				matmul(
-					zeta.rb_mut(),
-					Accum::Replace,
-					pbar_adjoint.rb().adjoint().get(..s, ..s),
-					zetabar.rb(),
-					one::<T>(),
-					par,
-				);
-				matmul(
-					zetabar_tmp.rb_mut(),
-					Accum::Replace,
-					pbar_adjoint.rb().adjoint().get(s.., ..s),
-					zetabar.rb(),
-					one::<T>(),
-					par,
-				);
-				zetabar.copy_from(&zetabar_tmp);
+			matmul(
+				zeta.rb_mut(),
+				Accum::Replace,
+				pbar_adjoint.rb().adjoint().get(..s, ..s),
+				zetabar.rb(),
+				one::<T>(),
+				par,
+			);
+			matmul(
+				zetabar_tmp.rb_mut(),
+				Accum::Replace,
+				pbar_adjoint.rb().adjoint().get(s.., ..s),
+				zetabar.rb(),
+				one::<T>(),
+				par,
+			);
+			zetabar.copy_from(&zetabar_tmp);
 
-				matmul(Mvold.rb_mut(), Accum::Add, w.rb(), thetaold.rb().adjoint(), -one::<T>(), par);
-				crate::linalg::triangular_solve::solve_lower_triangular_in_place(rho.rb().transpose(), Mvold.rb_mut().transpose_mut(), par);
-				w.copy_from(&Mvold);
+			matmul(Mvold.rb_mut(), Accum::Add, w.rb(), thetaold.rb().adjoint(), -one::<T>(), par);
+			crate::linalg::triangular_solve::solve_lower_triangular_in_place(rho.rb().transpose(), Mvold.rb_mut().transpose_mut(), par);
+			w.copy_from(&Mvold);
 
-				matmul(Mvold.rb_mut(), Accum::Add, wbar.rb(), thetabar.rb().adjoint(), -one::<T>(), par);
-				crate::linalg::triangular_solve::solve_lower_triangular_in_place(rhobar.rb().transpose(), Mvold.rb_mut().transpose_mut(), par);
-				wbar.copy_from(&Mvold);
+			matmul(Mvold.rb_mut(), Accum::Add, wbar.rb(), thetabar.rb().adjoint(), -one::<T>(), par);
+			crate::linalg::triangular_solve::solve_lower_triangular_in_place(rhobar.rb().transpose(), Mvold.rb_mut().transpose_mut(), par);
+			wbar.copy_from(&Mvold);
 
-				let actual_s = x.ncols();
-				matmul(
-					x.rb_mut(),
-					if iter == 0 && params.initial_guess == InitialGuessStatus::Zero {
-						Accum::Replace
-					} else {
-						Accum::Add
-					},
-					wbar.rb(),
-					zeta.rb().get(.., ..actual_s),
-					one::<T>(),
-					par,
-				);
-				start = end;
-			}
-			norm = zetabar.norm_l2();
-			callback(x.rb());
-			if norm <= threshold {
-				return Ok(LsmrInfo {
-					rel_residual: norm / norm_ref,
-					abs_residual: norm,
-					iter_count: iter + 1,
-					non_exhaustive: NonExhaustive(()),
-				});
-			}
+			let actual_s = x.ncols();
+			matmul(
+				x.rb_mut(),
+				if iter == 0 && params.initial_guess == InitialGuessStatus::Zero {
+					Accum::Replace
+				} else {
+					Accum::Add
+				},
+				wbar.rb(),
+				zeta.rb().get(.., ..actual_s),
+				one::<T>(),
+				par,
+			);
+			start = end;
+		}
+		norm = zetabar.norm_l2();
+		let rel_residual = norm / norm_ref;
+		match monitor(IterationState {
+			iter_count: iter + 1,
+			solution: x.rb(),
+			residual: None,
+			abs_residual: copy(norm),
+			rel_residual: copy(rel_residual),
+			rhs_norm: b.norm_l2(),
+			non_exhaustive: NonExhaustive(()),
+		}) {
+			IterationControl::Continue => {},
+			IterationControl::Converged => return IterativeSolveResult::converged(iter + 1, norm, rel_residual),
+			IterationControl::Stop => return IterativeSolveResult::breakdown(BreakdownReason::Stopped, iter + 1, norm, rel_residual),
+		}
+		if norm <= threshold {
+			return IterativeSolveResult::converged(iter + 1, norm, rel_residual);
 		}
-
-		Err(LsmrError::NoConvergence {
-			rel_residual: norm / norm_ref,
-			abs_residual: norm,
-		})
 	}
-	implementation(out, &right_precond, &mat, rhs, params, &mut { callback }, par, stack)
+
+	IterativeSolveResult::breakdown(BreakdownReason::MaxIterations, params.max_iters, copy(norm), norm / norm_ref)
 }
 
 #[cfg(test)]
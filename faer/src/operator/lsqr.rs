@@ -0,0 +1,556 @@
+use super::*;
+use crate::assert;
+use crate::matrix_free::monitor::{BreakdownReason, IterationControl, IterationState, IterativeSolveResult};
+
+/// algorithm parameters
+#[derive(Copy, Clone, Debug)]
+pub struct LsqrParams<T> {
+	/// whether the initial guess is implicitly zero or not
+	pub initial_guess: InitialGuessStatus,
+	/// absolute tolerance for convergence testing
+	pub abs_tolerance: T,
+	/// relative tolerance for convergence testing
+	pub rel_tolerance: T,
+	/// damping factor $\lambda$. the algorithm minimizes $\|A x - b\|^2 + \lambda^2 \|x\|^2$
+	pub damp: T,
+	/// maximum number of iterations
+	pub max_iters: usize,
+	/// number of previous krylov vectors that each new one is reorthogonalized against
+	pub reorthogonalization: usize,
+	/// number of iterations after which the residual is recomputed from the current solution and
+	/// the bidiagonalization is restarted
+	pub restart: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for LsqrParams<T> {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			initial_guess: InitialGuessStatus::MaybeNonZero,
+			abs_tolerance: zero(),
+			rel_tolerance: eps::<T>() * from_f64::<T>(128.0),
+			damp: zero(),
+			max_iters: usize::MAX,
+			reorthogonalization: 0,
+			restart: usize::MAX,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// algorithm result
+///
+/// when the right-hand side has multiple columns, the residuals are the largest ones among all
+/// the columns, and the iteration count is the largest one
+#[derive(Copy, Clone, Debug)]
+pub struct LsqrInfo<T> {
+	/// estimate of the absolute residual $\|b - A x\|$ at the final step, including the damping
+	/// term
+	pub abs_residual: T,
+	/// estimate of the relative residual $\|b - A x\| / \|b\|$ at the final step
+	pub rel_residual: T,
+	/// estimate of the residual of the normal equations $\|A^H (b - A x) - \lambda^2 x\|$ at the
+	/// final step
+	pub normal_residual: T,
+	/// number of iterations executed by the algorithm
+	pub iter_count: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// algorithm error
+#[derive(Copy, Clone, Debug)]
+pub enum LsqrError<T> {
+	/// convergence failure
+	NoConvergence {
+		/// absolute residual at the final step
+		abs_residual: T,
+		/// relative residual at the final step
+		rel_residual: T,
+	},
+}
+
+/// computes the size and alignment of required workspace for executing the lsqr algorithm
+pub fn lsqr_scratch<T: ComplexField>(mat: impl BiLinOp<T>, params: LsqrParams<T::Real>, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(A: &dyn BiLinOp<T>, l: usize, par: Par) -> StackReq {
+		let m = A.nrows();
+		let n = A.ncols();
+		let l = Ord::min(l, n);
+
+		StackReq::all_of(&[
+			temp_mat_scratch::<T>(m + n, 1), // u
+			temp_mat_scratch::<T>(n, 1),     // v
+			temp_mat_scratch::<T>(n, 1),     // w
+			temp_mat_scratch::<T>(n, l),     // previous v
+			temp_mat_scratch::<T::Real>(n, 1),
+			temp_mat_scratch::<T>(m, 1),
+			temp_mat_scratch::<T>(n, 1),
+			StackReq::any_of(&[A.apply_scratch(1, par), A.transpose_apply_scratch(1, par)]),
+		])
+	}
+
+	implementation(&mat, params.reorthogonalization, par)
+}
+
+/// executes lsqr on the least squares problem $\min_x \|A x - b\|^2 + \lambda^2 \|x\|^2$
+///
+/// `mat` can be a sparse matrix in either csc or csr format, a dense matrix, or any other
+/// [`BiLinOp`]. each column of the right-hand side is solved independently
+///
+/// if `std_err` is provided, it is filled with estimates of the standard errors of the entries of
+/// the solution, $\sigma_j = \sqrt{v_j} \|b - A x\| / \sqrt{d}$, where $v_j$ is the estimate of
+/// the $j$-th diagonal entry of $(A^H A + \lambda^2 I)^{-1}$ accumulated during the last restart
+/// cycle, and $d$ is the number of degrees of freedom, equal to $m - n$ if $\lambda = 0$, and $m$
+/// otherwise
+///
+/// # note
+/// the estimates of the standard errors are only meaningful when the bidiagonalization isn't
+/// restarted, and the problem is overdetermined or damped. they only account for the directions
+/// spanned by the krylov subspace, so they underestimate the true values if the algorithm
+/// converges in fewer than $n$ iterations
+#[track_caller]
+pub fn lsqr<T: ComplexField>(
+	out: MatMut<'_, T>,
+	std_err: Option<MatMut<'_, T::Real>>,
+	mat: impl BiLinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: LsqrParams<T::Real>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<LsqrInfo<T::Real>, LsqrError<T::Real>> {
+	let (result, normal_residual) = lsqr_imp(out, std_err, &mat, rhs, params, &mut |_| IterationControl::Continue, par, stack);
+	if result.converged {
+		Ok(LsqrInfo {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+			normal_residual,
+			iter_count: result.iter_count,
+			non_exhaustive: NonExhaustive(()),
+		})
+	} else {
+		Err(LsqrError::NoConvergence {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+		})
+	}
+}
+
+/// executes lsqr, and calls `monitor` at the end of every iteration
+///
+/// the columns of the right-hand side are solved one after the other, and the residual norms
+/// passed to the monitor are the largest estimates over the columns solved so far, including the
+/// current one. the residual vector isn't formed, so [`IterationState::residual`] is `None`.
+/// [`IterationControl::Converged`] accepts the current column and moves to the next one, while
+/// [`IterationControl::Stop`] stops the whole solve
+///
+/// see [`lsqr`] for the meaning of the other parameters
+#[track_caller]
+pub fn lsqr_monitored<T: ComplexField>(
+	out: MatMut<'_, T>,
+	std_err: Option<MatMut<'_, T::Real>>,
+	mat: impl BiLinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: LsqrParams<T::Real>,
+	monitor: impl FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	lsqr_imp(out, std_err, &mat, rhs, params, &mut { monitor }, par, stack).0
+}
+
+/// returns the result of the solve, and the largest estimate of the residual of the normal
+/// equations over the columns
+#[track_caller]
+#[math]
+fn lsqr_imp<T: ComplexField>(
+	mut x: MatMut<'_, T>,
+	mut std_err: Option<MatMut<'_, T::Real>>,
+	A: &dyn BiLinOp<T>,
+	b: MatRef<'_, T>,
+	params: LsqrParams<T::Real>,
+	monitor: &mut dyn FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> (IterativeSolveResult<T::Real>, T::Real) {
+	let m = A.nrows();
+	let n = A.ncols();
+	let k = b.ncols();
+
+	assert!(all(b.nrows() == m, x.nrows() == n, x.ncols() == k));
+	if let Some(std_err) = std_err.rb() {
+		assert!(all(std_err.nrows() == n, std_err.ncols() == k));
+	}
+
+	let damp = copy(params.damp);
+	let damped = damp != zero::<T::Real>();
+	let l = Ord::min(params.reorthogonalization, n);
+	let dof = if damped { m } else { m.saturating_sub(n) };
+
+	let (mut u, stack) = unsafe { temp_mat_uninit::<T, _, _>(m + n, 1, stack) };
+	let u = u.as_mat_mut();
+	let (mut u, mut u_damp) = u.col_mut(0).split_at_row_mut(m);
+	let (mut v, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+	let mut v = v.as_mat_mut().col_mut(0);
+	let (mut w, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+	let mut w = w.as_mat_mut().col_mut(0);
+	let (mut V, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, l, stack) };
+	let mut V = V.as_mat_mut();
+	let (mut var, stack) = unsafe { temp_mat_uninit::<T::Real, _, _>(n, 1, stack) };
+	let mut var = var.as_mat_mut().col_mut(0);
+	let (mut tmp_m, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, 1, stack) };
+	let mut tmp_m = tmp_m.as_mat_mut().col_mut(0);
+	let (mut tmp_n, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+	let mut tmp_n = tmp_n.as_mat_mut().col_mut(0);
+
+	// largest residuals and iteration count over the columns solved so far
+	let mut abs_residual = zero::<T::Real>();
+	let mut rel_residual = zero::<T::Real>();
+	let mut normal_residual = zero::<T::Real>();
+	let mut iter_count = 0usize;
+	let mut converged_all = true;
+	let rhs_norm = b.norm_l2();
+
+	for j in 0..k {
+		let b = b.col(j);
+
+		let b_norm = b.norm_l2();
+		let rel = |rnorm: &T::Real| {
+			if b_norm == zero::<T::Real>() {
+				zero::<T::Real>()
+			} else {
+				*rnorm / b_norm
+			}
+		};
+		let r_threshold = max(params.abs_tolerance, params.rel_tolerance * b_norm);
+
+		let mut zero_guess = params.initial_guess == InitialGuessStatus::Zero;
+		if zero_guess {
+			x.rb_mut().col_mut(j).fill(zero());
+		}
+		var.fill(zero());
+
+		let mut anorm = zero::<T::Real>();
+		let mut rnorm = zero::<T::Real>();
+		let mut arnorm = zero::<T::Real>();
+		let mut iter = 0usize;
+		let mut converged = false;
+
+		while !converged {
+			// residual of the augmented system [A; damp I] x = [b; 0]
+			if zero_guess {
+				u.copy_from(b);
+				u_damp.fill(zero());
+			} else {
+				A.apply(u.rb_mut().as_mat_mut(), x.rb().col(j).as_mat(), par, stack);
+				zip!(u.rb_mut(), b).for_each(|unzip!(u, b)| *u = *b - *u);
+				zip!(u_damp.rb_mut(), x.rb().col(j)).for_each(|unzip!(u, x)| *u = -mul_real(*x, damp));
+			}
+			zero_guess = false;
+
+			let mut beta = hypot(u.norm_l2(), u_damp.norm_l2());
+			rnorm = copy(beta);
+			if beta == zero::<T::Real>() {
+				arnorm = zero();
+				converged = true;
+				break;
+			}
+			let beta_inv = recip(beta);
+			zip!(u.rb_mut()).for_each(|unzip!(u)| *u = mul_real(*u, beta_inv));
+			zip!(u_damp.rb_mut()).for_each(|unzip!(u)| *u = mul_real(*u, beta_inv));
+
+			A.adjoint_apply(v.rb_mut().as_mat_mut(), u.rb().as_mat(), par, stack);
+			zip!(v.rb_mut(), u_damp.rb()).for_each(|unzip!(v, u)| *v = *v + mul_real(*u, damp));
+			let mut alpha = v.norm_l2();
+			arnorm = alpha * beta;
+			if alpha == zero::<T::Real>() {
+				converged = true;
+				break;
+			}
+			let alpha_inv = recip(alpha);
+			zip!(v.rb_mut()).for_each(|unzip!(v)| *v = mul_real(*v, alpha_inv));
+
+			// the standard error estimates are only valid within a single cycle
+			var.fill(zero());
+			let mut stored = 0usize;
+			let mut pos = 0usize;
+			if l > 0 {
+				V.rb_mut().col_mut(0).copy_from(&v);
+				stored = 1;
+				pos = 1 % l;
+			}
+
+			w.copy_from(&v);
+			let mut phibar = copy(beta);
+			let mut rhobar = copy(alpha);
+
+			if rnorm <= r_threshold || arnorm <= max(params.abs_tolerance, params.rel_tolerance * anorm * rnorm) {
+				converged = true;
+				break;
+			}
+
+			let mut cycle_iter = 0usize;
+			while cycle_iter < params.restart && iter < params.max_iters {
+				// u = [A; damp I] v - alpha u
+				A.apply(tmp_m.rb_mut().as_mat_mut(), v.rb().as_mat(), par, stack);
+				zip!(u.rb_mut(), tmp_m.rb()).for_each(|unzip!(u, av)| *u = *av - mul_real(*u, alpha));
+				zip!(u_damp.rb_mut(), v.rb()).for_each(|unzip!(u, v)| *u = mul_real(*v, damp) - mul_real(*u, alpha));
+				beta = hypot(u.norm_l2(), u_damp.norm_l2());
+				if beta > zero::<T::Real>() {
+					let beta_inv = recip(beta);
+					zip!(u.rb_mut()).for_each(|unzip!(u)| *u = mul_real(*u, beta_inv));
+					zip!(u_damp.rb_mut()).for_each(|unzip!(u)| *u = mul_real(*u, beta_inv));
+				}
+				anorm = sqrt(anorm * anorm + alpha * alpha + beta * beta);
+
+				// v = [A; damp I]^H u - beta v
+				A.adjoint_apply(tmp_n.rb_mut().as_mat_mut(), u.rb().as_mat(), par, stack);
+				zip!(v.rb_mut(), tmp_n.rb(), u_damp.rb()).for_each(|unzip!(v, atu, u)| *v = *atu + mul_real(*u, damp) - mul_real(*v, beta));
+				for i in 0..stored {
+					let r = V.rb().col(i).adjoint() * v.rb();
+					zip!(v.rb_mut(), V.rb().col(i)).for_each(|unzip!(y, x)| *y = *y - r * *x);
+				}
+				alpha = v.norm_l2();
+				if alpha > zero::<T::Real>() {
+					let alpha_inv = recip(alpha);
+					zip!(v.rb_mut()).for_each(|unzip!(v)| *v = mul_real(*v, alpha_inv));
+				}
+				if l > 0 {
+					V.rb_mut().col_mut(pos).copy_from(&v);
+					stored = Ord::min(stored + 1, l);
+					pos = (pos + 1) % l;
+				}
+
+				// plane rotation eliminating the subdiagonal of the bidiagonal matrix
+				let rho = hypot(rhobar, beta);
+				let c = rhobar / rho;
+				let s = beta / rho;
+				let theta = s * alpha;
+				rhobar = -c * alpha;
+				let phi = c * phibar;
+				phibar = s * phibar;
+
+				let rho_inv = recip(rho);
+				let t1 = phi * rho_inv;
+				let t2 = -theta * rho_inv;
+				zip!(var.rb_mut(), w.rb()).for_each(|unzip!(var, w)| *var = *var + abs2(*w) * rho_inv * rho_inv);
+				zip!(x.rb_mut().col_mut(j), w.rb()).for_each(|unzip!(x, w)| *x = *x + mul_real(*w, t1));
+				zip!(w.rb_mut(), v.rb()).for_each(|unzip!(w, v)| *w = *v + mul_real(*w, t2));
+
+				rnorm = copy(phibar);
+				arnorm = phibar * alpha * abs(c);
+				iter += 1;
+				cycle_iter += 1;
+
+				let state_abs_residual = max(copy(abs_residual), copy(rnorm));
+				let state_rel_residual = max(copy(rel_residual), rel(&rnorm));
+				match monitor(IterationState {
+					iter_count: Ord::max(iter_count, iter),
+					solution: x.rb(),
+					residual: None,
+					abs_residual: copy(state_abs_residual),
+					rel_residual: copy(state_rel_residual),
+					rhs_norm: copy(rhs_norm),
+					non_exhaustive: NonExhaustive(()),
+				}) {
+					IterationControl::Continue => {},
+					IterationControl::Converged => {
+						converged = true;
+						break;
+					},
+					IterationControl::Stop => {
+						return (
+							IterativeSolveResult::breakdown(
+								BreakdownReason::Stopped,
+								Ord::max(iter_count, iter),
+								state_abs_residual,
+								state_rel_residual,
+							),
+							max(normal_residual, arnorm),
+						);
+					},
+				}
+
+				if rnorm <= r_threshold || arnorm <= max(params.abs_tolerance, params.rel_tolerance * anorm * rnorm) || alpha == zero::<T::Real>() {
+					converged = true;
+					break;
+				}
+			}
+
+			if iter >= params.max_iters {
+				break;
+			}
+		}
+
+		if let Some(std_err) = std_err.rb_mut() {
+			let f = if dof == 0 {
+				zero::<T::Real>()
+			} else {
+				rnorm / sqrt(from_f64::<T::Real>(dof as f64))
+			};
+			zip!(std_err.col_mut(j), var.rb()).for_each(|unzip!(se, var)| *se = sqrt(*var) * f);
+		}
+
+		converged_all = converged_all && converged;
+		rel_residual = max(rel_residual, rel(&rnorm));
+		abs_residual = max(abs_residual, rnorm);
+		normal_residual = max(normal_residual, arnorm);
+		iter_count = Ord::max(iter_count, iter);
+	}
+
+	let result = if converged_all {
+		IterativeSolveResult::converged(iter_count, abs_residual, rel_residual)
+	} else {
+		IterativeSolveResult::breakdown(BreakdownReason::MaxIterations, iter_count, abs_residual, rel_residual)
+	};
+	(result, normal_residual)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::linalg::solvers::DenseSolveCore;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_lsqr_dense() {
+		let ref mut rng = StdRng::seed_from_u64(0);
+		let (m, n, k) = (120, 50, 3);
+		let A: Mat<c64> = CwiseMatDistribution {
+			nrows: m,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+		let b: Mat<c64> = CwiseMatDistribution {
+			nrows: m,
+			ncols: k,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e6);
+		for damp in [0.0, 3.0] {
+			for (reorthogonalization, restart) in [(0, usize::MAX), (8, 10), (n, usize::MAX)] {
+				// the variance estimates are only exact once the krylov subspace spans the whole
+				// space, and the krylov vectors stay orthogonal
+				let full = reorthogonalization == n;
+				let params = LsqrParams {
+					initial_guess: InitialGuessStatus::Zero,
+					rel_tolerance: if full { 0.0 } else { 1e-13 },
+					damp,
+					reorthogonalization,
+					restart,
+					max_iters: if full { n } else { 10 * n },
+					..Default::default()
+				};
+
+				let mut x = Mat::<c64>::zeros(n, k);
+				let mut se = Mat::<f64>::zeros(n, k);
+				let result = lsqr(
+					x.as_mut(),
+					Some(se.as_mut()),
+					A.as_ref(),
+					b.as_ref(),
+					params,
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(lsqr_scratch(A.as_ref(), params, Par::Seq))),
+				);
+				if !full {
+					assert!(result.unwrap().iter_count <= 10 * n);
+				}
+
+				// normal equations of the damped problem
+				let AtA = A.adjoint() * &A + Scale(c64::new(damp * damp, 0.0)) * Mat::<c64>::identity(n, n);
+				let x_target = AtA.llt(Side::Lower).unwrap().solve(A.adjoint() * &b);
+				assert!(x ~ x_target);
+
+				if full {
+					let inv = AtA.llt(Side::Lower).unwrap().inverse();
+					let dof = if damp == 0.0 { m - n } else { m };
+					let r = &b - &A * &x;
+					for j in 0..k {
+						let r_norm = (r.col(j).squared_norm_l2() + damp * damp * x.col(j).squared_norm_l2()).sqrt();
+						for i in 0..n {
+							let target = inv[(i, i)].re.sqrt() * r_norm / (dof as f64).sqrt();
+							assert!((se[(i, j)] - target).abs() < 1e-2 * target);
+						}
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_lsqr_sparse() {
+		use crate::sparse::{SparseColMat, Triplet};
+
+		let ref mut rng = StdRng::seed_from_u64(1);
+		let (m, n) = (400, 150);
+
+		// tomography-like system, where each measurement sees a few unknowns
+		let mut triplets = alloc::vec::Vec::new();
+		for i in 0..m {
+			for _ in 0..4 {
+				let j = rng.gen_range(0..n);
+				triplets.push(Triplet::new(i, j, rng.gen_range(0.5..1.5f64)));
+			}
+		}
+		for j in 0..n {
+			triplets.push(Triplet::new(j, j, 1.0));
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(m, n, &triplets).unwrap();
+		let A_csr = A.to_row_major().unwrap();
+		let x_true = CwiseColDistribution {
+			nrows: n,
+			dist: StandardNormal,
+		}
+		.rand::<Col<f64>>(rng);
+		let b: Col<f64> = &A * &x_true;
+
+		let params = LsqrParams {
+			initial_guess: InitialGuessStatus::Zero,
+			rel_tolerance: 1e-13,
+			reorthogonalization: 4,
+			..Default::default()
+		};
+
+		let mut x = Mat::<f64>::zeros(n, 1);
+		lsqr(
+			x.as_mut(),
+			None,
+			A.as_ref(),
+			b.as_mat(),
+			params,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(lsqr_scratch(A.as_ref(), params, Par::Seq))),
+		)
+		.unwrap();
+		assert!((x.col(0) - &x_true).norm_l2() < 1e-9 * x_true.norm_l2());
+
+		// same system in row major format, with a nonzero initial guess and restarts
+		let params = LsqrParams {
+			initial_guess: InitialGuessStatus::MaybeNonZero,
+			restart: 20,
+			..params
+		};
+		let mut x = Mat::<f64>::zeros(n, 1);
+		lsqr(
+			x.as_mut(),
+			None,
+			A_csr.as_ref(),
+			b.as_mat(),
+			params,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(lsqr_scratch(A_csr.as_ref(), params, Par::Seq))),
+		)
+		.unwrap();
+		assert!((x.col(0) - &x_true).norm_l2() < 1e-9 * x_true.norm_l2());
+	}
+}
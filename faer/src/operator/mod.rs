@@ -6,8 +6,21 @@ use crate::internal_prelude_sp::*;
 pub mod bicgstab;
 /// conjugate gradient method.
 pub mod conjugate_gradient;
+/// pipelined and s-step conjugate gradient, with fewer synchronization points.
+pub mod pipelined_cg;
 /// least squares minimal residual.
 pub mod lsmr;
+/// least squares qr, with damping and standard error estimates.
+pub mod lsqr;
+/// block conjugate gradient and block gmres, with deflation of converged right-hand sides.
+pub mod block_krylov;
+/// gcro-dr, a gmres variant that recycles a subspace across a sequence of linear systems.
+pub mod gcrodr;
+/// convergence monitoring and common result type for the iterative solvers.
+pub mod monitor;
+
+/// sums, products, scalings and kronecker products of linear operators.
+pub mod adapters;
 
 /// krylov-schur eigensolvers.
 pub mod eigen;
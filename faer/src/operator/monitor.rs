@@ -0,0 +1,568 @@
+//! convergence monitoring for the iterative solvers
+//!
+//! the `*_monitored` variants of the iterative solvers call a monitor at the end of every
+//! iteration with an [`IterationState`], which exposes the current solution and residual. the
+//! monitor can be used to log or plot the convergence history, and the returned
+//! [`IterationControl`] lets it stop the solver early, either because a custom convergence test is
+//! satisfied, or to abort the solve
+//!
+//! the outcome of a monitored solve is reported as an [`IterativeSolveResult`], which is common to
+//! all the solvers, instead of the solver specific info and error types
+//!
+//! every solver in [`crate::matrix_free`] has a monitored variant. the solvers that only track an
+//! estimate of the residual norm, or that solve the columns of the right-hand side one after the
+//! other, document what the state they pass to the monitor contains
+//!
+//! common stopping rules are provided as implementations of [`StoppingCriterion`], which can be
+//! combined with tuples, and used as a monitor with `|state| criterion.check(&state)`. the
+//! [`CancellationToken`] and [`TimeBudget`] criteria also apply to the eigensolvers, through
+//! [`StoppingCriterion::check_eigen`]
+//!
+//! # example
+//! ```
+//! use faer::dyn_stack::{MemBuffer, MemStack};
+//! use faer::matrix_free::IdentityPrecond;
+//! use faer::matrix_free::conjugate_gradient::{
+//! 	CgParams, conjugate_gradient_monitored, conjugate_gradient_scratch,
+//! };
+//! use faer::matrix_free::monitor::{BackwardError, CancellationToken, StoppingCriterion};
+//! use faer::{Par, mat};
+//!
+//! let A = mat![[4.0, 1.0, 0.0], [1.0, 4.0, 1.0], [0.0, 1.0, 4.0]];
+//! let b = mat![[1.0], [2.0], [3.0]];
+//! let mut x = mat![[0.0], [0.0], [0.0]];
+//! let precond = IdentityPrecond { dim: 3 };
+//!
+//! // the token can be cloned and sent to another thread, which cancels the solve by calling
+//! // `token.cancel()`
+//! let token = CancellationToken::new();
+//! let mut criterion = (BackwardError::new(A.norm_l2(), 1e-12), token.clone());
+//!
+//! let result = conjugate_gradient_monitored(
+//! 	x.as_mut(),
+//! 	precond,
+//! 	A.as_ref(),
+//! 	b.as_ref(),
+//! 	CgParams::default(),
+//! 	|state| criterion.check(&state),
+//! 	Par::Seq,
+//! 	MemStack::new(&mut MemBuffer::new(conjugate_gradient_scratch(
+//! 		precond,
+//! 		A.as_ref(),
+//! 		1,
+//! 		Par::Seq,
+//! 	))),
+//! );
+//! assert!(result.converged);
+//! assert!((&b - &A * &x).norm_l2() < 1e-10);
+//! ```
+
+use super::*;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// state of an iterative solver at the end of an iteration
+#[derive(Copy, Clone, Debug)]
+pub struct IterationState<'a, T: ComplexField> {
+	/// number of iterations executed so far
+	pub iter_count: usize,
+	/// current approximate solution
+	pub solution: MatRef<'a, T>,
+	/// current residual $b - A x$, as computed by the recurrences of the solver
+	///
+	/// this is `None` for the solvers that only track an estimate of the residual norm, such as
+	/// lsqr, lsmr and gmres, or that don't keep the residual of every column
+	pub residual: Option<MatRef<'a, T>>,
+	/// norm of the residual
+	pub abs_residual: T::Real,
+	/// norm of the residual, relative to the norm of the right-hand side
+	pub rel_residual: T::Real,
+	/// norm of the right-hand side
+	pub rhs_norm: T::Real,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// action requested by a monitor after an iteration
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IterationControl {
+	/// continue with the default convergence test of the solver
+	Continue,
+	/// accept the current solution as converged
+	Converged,
+	/// stop the solver without convergence
+	Stop,
+}
+
+/// reason why an iterative solver stopped without converging
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BreakdownReason {
+	/// operator was detected to not be positive definite
+	NonPositiveDefiniteOperator,
+	/// preconditioner was detected to not be positive definite
+	NonPositiveDefinitePreconditioner,
+	/// the maximum number of iterations was reached
+	MaxIterations,
+	/// the monitor requested the solver to stop
+	Stopped,
+}
+
+/// outcome of an iterative solve
+#[derive(Copy, Clone, Debug)]
+pub struct IterativeSolveResult<T: RealField> {
+	/// whether the solver converged
+	pub converged: bool,
+	/// number of iterations executed by the solver
+	pub iter_count: usize,
+	/// absolute residual at the final step
+	pub abs_residual: T,
+	/// relative residual at the final step
+	pub rel_residual: T,
+	/// reason why the solver stopped, if it didn't converge
+	pub breakdown: Option<BreakdownReason>,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> IterativeSolveResult<T> {
+	#[inline]
+	pub(crate) fn converged(iter_count: usize, abs_residual: T, rel_residual: T) -> Self {
+		Self {
+			converged: true,
+			iter_count,
+			abs_residual,
+			rel_residual,
+			breakdown: None,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+
+	#[inline]
+	pub(crate) fn breakdown(reason: BreakdownReason, iter_count: usize, abs_residual: T, rel_residual: T) -> Self {
+		Self {
+			converged: false,
+			iter_count,
+			abs_residual,
+			rel_residual,
+			breakdown: Some(reason),
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// state of an eigensolver at the end of a restart
+#[derive(Copy, Clone, Debug)]
+pub struct EigenIterationState {
+	/// number of restarts executed so far
+	pub restart_count: usize,
+	/// number of eigenvalues that have converged so far
+	pub n_converged_eigen: usize,
+	/// number of requested eigenvalues
+	pub n_requested_eigen: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// stopping rule for the iterative solvers
+pub trait StoppingCriterion<T: ComplexField> {
+	/// checks the state of a linear solver at the end of an iteration
+	fn check(&mut self, state: &IterationState<'_, T>) -> IterationControl;
+
+	/// checks the state of an eigensolver at the end of a restart
+	///
+	/// the eigensolvers stop as soon as this returns anything other than
+	/// [`IterationControl::Continue`], and return the eigenvalues that have converged so far
+	fn check_eigen(&mut self, state: &EigenIterationState) -> IterationControl {
+		_ = state;
+		IterationControl::Continue
+	}
+}
+
+impl<T: ComplexField, C: ?Sized + StoppingCriterion<T>> StoppingCriterion<T> for &mut C {
+	#[inline]
+	fn check(&mut self, state: &IterationState<'_, T>) -> IterationControl {
+		(**self).check(state)
+	}
+
+	#[inline]
+	fn check_eigen(&mut self, state: &EigenIterationState) -> IterationControl {
+		(**self).check_eigen(state)
+	}
+}
+
+/// combines two criteria. every criterion is checked at each iteration, and the first one that
+/// doesn't return [`IterationControl::Continue`] decides the result
+impl<T: ComplexField, A: StoppingCriterion<T>, B: StoppingCriterion<T>> StoppingCriterion<T> for (A, B) {
+	#[inline]
+	fn check(&mut self, state: &IterationState<'_, T>) -> IterationControl {
+		match self.0.check(state) {
+			IterationControl::Continue => self.1.check(state),
+			control => control,
+		}
+	}
+
+	#[inline]
+	fn check_eigen(&mut self, state: &EigenIterationState) -> IterationControl {
+		match self.0.check_eigen(state) {
+			IterationControl::Continue => self.1.check_eigen(state),
+			control => control,
+		}
+	}
+}
+
+/// accepts the solution once the residual is below either tolerance
+///
+/// the tolerances are checked against the norm of the residual as computed by the recurrences of
+/// the solver
+#[derive(Copy, Clone, Debug)]
+pub struct ResidualTolerance<T: RealField> {
+	/// absolute tolerance on $\|b - A x\|$
+	pub abs_tolerance: T,
+	/// relative tolerance on $\|b - A x\| / \|b\|$
+	pub rel_tolerance: T,
+}
+
+impl<T: ComplexField> StoppingCriterion<T> for ResidualTolerance<T::Real> {
+	fn check(&mut self, state: &IterationState<'_, T>) -> IterationControl {
+		if state.abs_residual <= self.abs_tolerance || state.rel_residual <= self.rel_tolerance {
+			IterationControl::Converged
+		} else {
+			IterationControl::Continue
+		}
+	}
+}
+
+/// accepts the solution once its normwise backward error is below the tolerance
+///
+/// the backward error is $\|b - A x\| / (\|A\| \|x\| + \|b\|)$, which measures the smallest
+/// relative perturbation of $A$ and $b$ for which $x$ is an exact solution. it is a better
+/// convergence test than the relative residual when the solution has a large norm
+#[derive(Copy, Clone, Debug)]
+pub struct BackwardError<T: RealField> {
+	/// estimate of the norm of the operator
+	pub mat_norm: T,
+	/// tolerance on the backward error
+	pub tolerance: T,
+}
+
+impl<T: RealField> BackwardError<T> {
+	/// creates a new criterion from an estimate of the norm of the operator and a tolerance
+	#[inline]
+	pub fn new(mat_norm: T, tolerance: T) -> Self {
+		Self { mat_norm, tolerance }
+	}
+}
+
+impl<T: ComplexField> StoppingCriterion<T> for BackwardError<T::Real> {
+	#[math]
+	fn check(&mut self, state: &IterationState<'_, T>) -> IterationControl {
+		let denom = self.mat_norm * state.solution.norm_l2() + state.rhs_norm;
+		if state.abs_residual <= self.tolerance * denom {
+			IterationControl::Converged
+		} else {
+			IterationControl::Continue
+		}
+	}
+}
+
+/// stops the solver once the wall-clock time budget is exhausted
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug)]
+pub struct TimeBudget {
+	/// instant after which the solver is stopped
+	pub deadline: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl TimeBudget {
+	/// creates a new budget that expires after `duration`, starting from now
+	#[inline]
+	pub fn new(duration: std::time::Duration) -> Self {
+		Self {
+			deadline: std::time::Instant::now() + duration,
+		}
+	}
+
+	/// returns whether the deadline has passed
+	#[inline]
+	pub fn is_expired(&self) -> bool {
+		std::time::Instant::now() >= self.deadline
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T: ComplexField> StoppingCriterion<T> for TimeBudget {
+	#[inline]
+	fn check(&mut self, _: &IterationState<'_, T>) -> IterationControl {
+		if self.is_expired() {
+			IterationControl::Stop
+		} else {
+			IterationControl::Continue
+		}
+	}
+
+	#[inline]
+	fn check_eigen(&mut self, _: &EigenIterationState) -> IterationControl {
+		if self.is_expired() {
+			IterationControl::Stop
+		} else {
+			IterationControl::Continue
+		}
+	}
+}
+
+/// shared flag that stops the solver once it's set
+///
+/// the token can be cloned and sent to other threads, which can cancel the solve with
+/// [`CancellationToken::cancel`]. the solver checks the flag once per iteration
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+	flag: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+	/// creates a new token that isn't cancelled
+	#[inline]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// requests the cancellation of the solvers that check this token
+	#[inline]
+	pub fn cancel(&self) {
+		self.flag.store(true, Ordering::Relaxed);
+	}
+
+	/// returns whether the cancellation was requested
+	#[inline]
+	pub fn is_cancelled(&self) -> bool {
+		self.flag.load(Ordering::Relaxed)
+	}
+}
+
+impl<T: ComplexField> StoppingCriterion<T> for CancellationToken {
+	#[inline]
+	fn check(&mut self, _: &IterationState<'_, T>) -> IterationControl {
+		if self.is_cancelled() {
+			IterationControl::Stop
+		} else {
+			IterationControl::Continue
+		}
+	}
+
+	#[inline]
+	fn check_eigen(&mut self, _: &EigenIterationState) -> IterationControl {
+		if self.is_cancelled() {
+			IterationControl::Stop
+		} else {
+			IterationControl::Continue
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::matrix_free::block_krylov::{BlockGmresParams, block_gmres_monitored, block_gmres_scratch};
+	use crate::matrix_free::conjugate_gradient::{CgParams, conjugate_gradient_monitored, conjugate_gradient_scratch};
+	use crate::matrix_free::eigen::{PartialEigenParams, partial_eigen_monitored, partial_eigen_scratch};
+	use crate::matrix_free::lsqr::{LsqrParams, lsqr_monitored, lsqr_scratch};
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_stopping_criteria() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 60;
+		let B = Mat::<f64>::random_normal(rng, n, n);
+		let A = &B * B.transpose() + Mat::<f64>::identity(n, n);
+		let b = Mat::<f64>::random_normal(rng, n, 1);
+		let precond = IdentityPrecond { dim: n };
+		let params = CgParams {
+			rel_tolerance: 0.0,
+			max_iters: 10 * n,
+			..Default::default()
+		};
+		let mem = &mut MemBuffer::new(conjugate_gradient_scratch(precond, A.as_ref(), 1, Par::Seq));
+
+		let mut solve = |criterion: &mut dyn StoppingCriterion<f64>| {
+			let mut x = Mat::<f64>::zeros(n, 1);
+			let result = conjugate_gradient_monitored(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				params,
+				|state| criterion.check(&state),
+				Par::Seq,
+				MemStack::new(mem),
+			);
+			(x, result)
+		};
+
+		let (x, result) = solve(&mut ResidualTolerance {
+			abs_tolerance: 0.0,
+			rel_tolerance: 1e-6,
+		});
+		assert!(result.converged);
+		assert!((&b - &A * &x).norm_l2() <= 1e-5 * b.norm_l2());
+
+		let A_norm = A.norm_l2();
+		let (x, result) = solve(&mut BackwardError::new(A_norm, 1e-13));
+		assert!(result.converged);
+		assert!((&b - &A * &x).norm_l2() <= 1e-12 * (A_norm * x.norm_l2() + b.norm_l2()));
+
+		let (_, result) = solve(&mut TimeBudget::new(std::time::Duration::ZERO));
+		assert!(result.breakdown == Some(BreakdownReason::Stopped));
+		assert!(result.iter_count == 1);
+
+		// cancelled from another handle after a few iterations
+		let mut token = CancellationToken::new();
+		let remote = token.clone();
+		let mut x = Mat::<f64>::zeros(n, 1);
+		let result = conjugate_gradient_monitored(
+			x.as_mut(),
+			precond,
+			A.as_ref(),
+			b.as_ref(),
+			params,
+			|state| {
+				if state.iter_count == 3 {
+					remote.cancel();
+				}
+				(
+					ResidualTolerance {
+						abs_tolerance: 0.0,
+						rel_tolerance: 1e-6,
+					},
+					&mut token,
+				)
+					.check(&state)
+			},
+			Par::Seq,
+			MemStack::new(mem),
+		);
+		assert!(result.breakdown == Some(BreakdownReason::Stopped));
+		assert!(result.iter_count == 3);
+
+		// eigensolver
+		let A = Mat::<c64>::random_normal(rng, 200, 200);
+		let v0 = Col::<c64>::random_normal(rng, 200);
+		let eig_params = PartialEigenParams {
+			min_dim: 20,
+			max_dim: 40,
+			..Default::default()
+		};
+		let mut V = Mat::zeros(200, 10);
+		let mut w = alloc::vec![c64::ZERO; 10];
+		let mut restarts = 0usize;
+		let mut token = CancellationToken::new();
+		token.cancel();
+		partial_eigen_monitored(
+			V.as_mut(),
+			&mut w,
+			&A.as_ref(),
+			v0.as_ref(),
+			f64::EPSILON * 128.0,
+			|state| {
+				restarts = state.restart_count;
+				StoppingCriterion::<c64>::check_eigen(&mut token, &state)
+			},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(partial_eigen_scratch(&A.as_ref(), 10, Par::Seq, eig_params))),
+			eig_params,
+		);
+		assert!(restarts == 1);
+	}
+
+	#[test]
+	fn test_cancellation() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		// least squares solver, solving the columns one after the other
+		let (m, n) = (120, 50);
+		let A = Mat::<f64>::random_normal(rng, m, n);
+		let b = Mat::<f64>::random_normal(rng, m, 2);
+		let params = LsqrParams {
+			rel_tolerance: 0.0,
+			max_iters: 10 * n,
+			..Default::default()
+		};
+		let mut token = CancellationToken::new();
+		let remote = token.clone();
+		let mut x = Mat::<f64>::zeros(n, 2);
+		let result = lsqr_monitored(
+			x.as_mut(),
+			None,
+			A.as_ref(),
+			b.as_ref(),
+			params,
+			|state| {
+				if state.iter_count == 5 {
+					remote.cancel();
+				}
+				token.check(&state)
+			},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(lsqr_scratch(A.as_ref(), params, Par::Seq))),
+		);
+		assert!(!result.converged);
+		assert!(result.breakdown == Some(BreakdownReason::Stopped));
+		assert!(result.iter_count == 5);
+
+		// block solver, cancelled in the middle of a restart cycle
+		let n = 100;
+		let A = Mat::<f64>::from_fn(n, n, |i, j| {
+			if i == j {
+				2.0
+			} else if i == j + 1 {
+				-1.2
+			} else if j == i + 1 {
+				-0.8
+			} else {
+				0.0
+			}
+		});
+		let b = Mat::<f64>::random_normal(rng, n, 3);
+		let precond = IdentityPrecond { dim: n };
+		let params = BlockGmresParams {
+			initial_guess: InitialGuessStatus::Zero,
+			rel_tolerance: 1e-12,
+			restart: 5,
+			..Default::default()
+		};
+		let mut token = CancellationToken::new();
+		let remote = token.clone();
+		let mut x = Mat::<f64>::zeros(n, 3);
+		let result = block_gmres_monitored(
+			x.as_mut(),
+			precond,
+			A.as_ref(),
+			b.as_ref(),
+			params,
+			|state| {
+				if state.iter_count == 7 {
+					remote.cancel();
+				}
+				(
+					ResidualTolerance {
+						abs_tolerance: 0.0,
+						rel_tolerance: 1e-12,
+					},
+					&mut token,
+				)
+					.check(&state)
+			},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(block_gmres_scratch(precond, A.as_ref(), 3, Par::Seq))),
+		);
+		assert!(result.breakdown == Some(BreakdownReason::Stopped));
+		assert!(result.iter_count == 7);
+		// the solution includes the progress of the interrupted cycle
+		assert!((&b - &A * &x).norm_l2() < b.norm_l2());
+	}
+}
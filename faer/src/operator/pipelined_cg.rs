@@ -0,0 +1,852 @@
+//! communication reducing variants of the conjugate gradient
+//!
+//! each iteration of the standard conjugate gradient computes two inner products that depend on
+//! each other, and on the result of the operator application that precedes them. on many-core
+//! machines, every inner product is a global reduction that requires all the threads to
+//! synchronize, which limits the strong scaling of the method
+//!
+//! - [`pipelined_cg`] implements the pipelined conjugate gradient of ghysels and vanroose, which
+//! rearranges the recurrences so that all the inner products of an iteration are computed in a
+//! single fused reduction, at the cost of a few more vector updates. the recurrences are
+//! mathematically equivalent to the ones of the preconditioned conjugate gradient, but the
+//! attainable accuracy can be slightly lower
+//! - [`s_step_cg`] implements the s-step conjugate gradient of chronopoulos and gear, which builds
+//! a basis of `s` krylov vectors at once, and computes all the inner products of the `s` steps
+//! with a single matrix product. the basis is built with normalized monomials, so `s` should stay
+//! small, usually between 2 and 8
+//!
+//! both methods solve each column of the right-hand side independently, and stop once the residual
+//! of every column is below the tolerance computed from the norm of the same column of the
+//! right-hand side
+
+use super::*;
+use crate::assert;
+use crate::linalg::solvers::SelfAdjointEigen;
+use crate::matrix_free::conjugate_gradient::{CgError, CgInfo, CgParams};
+use crate::matrix_free::monitor::{BreakdownReason, IterationControl, IterationState, IterativeSolveResult};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// s-step conjugate gradient parameters
+#[derive(Copy, Clone, Debug)]
+pub struct SStepCgParams<T: RealField> {
+	/// whether the initial guess is implicitly zero or not
+	pub initial_guess: InitialGuessStatus,
+	/// absolute tolerance for convergence testing
+	pub abs_tolerance: T,
+	/// relative tolerance for convergence testing
+	pub rel_tolerance: T,
+	/// number of krylov vectors generated between two reductions
+	pub s: usize,
+	/// maximum number of iterations, counted as the number of operator applications
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for SStepCgParams<T> {
+	#[inline]
+	#[math]
+	fn default() -> Self {
+		Self {
+			initial_guess: InitialGuessStatus::MaybeNonZero,
+			abs_tolerance: zero::<T>(),
+			rel_tolerance: eps::<T>() * from_f64::<T>(128.0),
+			s: 4,
+			max_iters: usize::MAX,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// computes the size and alignment of required workspace for executing the pipelined conjugate
+/// gradient
+pub fn pipelined_cg_scratch<T: ComplexField>(precond: impl Precond<T>, mat: impl LinOp<T>, rhs_ncols: usize, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(M: &dyn Precond<T>, A: &dyn LinOp<T>, k: usize, par: Par) -> StackReq {
+		StackReq::any_of(&[A.apply_scratch(k, par), M.apply_scratch(k, par)])
+	}
+	implementation(&precond, &mat, rhs_ncols, par)
+}
+
+/// computes the size and alignment of required workspace for executing the s-step conjugate
+/// gradient
+pub fn s_step_cg_scratch<T: ComplexField>(precond: impl Precond<T>, mat: impl LinOp<T>, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(M: &dyn Precond<T>, A: &dyn LinOp<T>, par: Par) -> StackReq {
+		StackReq::any_of(&[A.apply_scratch(1, par), M.apply_scratch(1, par)])
+	}
+	implementation(&precond, &mat, par)
+}
+
+/// computes the inner products `lhs[p].col(j)^H rhs[p].col(j)` for all the pairs `p` and columns
+/// `j`, in a single pass over the rows
+///
+/// with parallelism, each thread reduces a contiguous chunk of the rows, and the partial results
+/// are summed in a fixed order once all the threads are done
+#[math]
+fn fused_dots<T: ComplexField>(pairs: &[(MatRef<'_, T>, MatRef<'_, T>)], par: Par) -> Mat<T> {
+	let n = pairs[0].0.nrows();
+	let k = pairs[0].0.ncols();
+
+	let partial = |start: usize, len: usize| {
+		let mut out = Mat::<T>::zeros(pairs.len(), k);
+		for j in 0..k {
+			for i in start..start + len {
+				for (p, (lhs, rhs)) in pairs.iter().enumerate() {
+					out[(p, j)] = out[(p, j)] + conj(lhs[(i, j)]) * rhs[(i, j)];
+				}
+			}
+		}
+		out
+	};
+
+	let n_threads = crate::utils::thread::parallelism_degree(par);
+	if n_threads <= 1 || n * k < 4096 {
+		return partial(0, n);
+	}
+
+	match par {
+		Par::Seq => partial(0, n),
+		#[cfg(feature = "rayon")]
+		Par::Rayon(_) => {
+			use rayon::prelude::*;
+			let mut partials = (0..n_threads).map(|_| Mat::<T>::zeros(0, 0)).collect::<Vec<_>>();
+			partials.par_iter_mut().enumerate().for_each(|(tid, out)| {
+				let (start, len) = crate::utils::thread::par_split_indices(n, tid, n_threads);
+				*out = partial(start, len);
+			});
+			let mut out = Mat::<T>::zeros(pairs.len(), k);
+			for p in &partials {
+				out += p;
+			}
+			out
+		},
+	}
+}
+
+/// per column thresholds
+#[math]
+fn thresholds<T: ComplexField>(b: MatRef<'_, T>, abs_tolerance: T::Real, rel_tolerance: T::Real) -> (Vec<T::Real>, Vec<T::Real>) {
+	let b_norm = (0..b.ncols()).map(|j| b.col(j).norm_l2()).collect::<Vec<_>>();
+	let threshold = b_norm.iter().map(|b_norm| max(copy(abs_tolerance), rel_tolerance * *b_norm)).collect();
+	(b_norm, threshold)
+}
+
+/// returns the largest residuals over the columns
+#[math]
+fn max_residuals<T: RealField>(residual: &[T], b_norm: &[T]) -> (T, T) {
+	let mut abs_residual = zero::<T>();
+	let mut rel_residual = zero::<T>();
+	for (r, b) in residual.iter().zip(b_norm) {
+		let rel = if *b > zero::<T>() {
+			*r / *b
+		} else if *r == zero::<T>() {
+			zero()
+		} else {
+			infinity()
+		};
+		abs_residual = max(abs_residual, copy(*r));
+		rel_residual = max(rel_residual, rel);
+	}
+	(abs_residual, rel_residual)
+}
+
+fn is_converged<T: RealField>(residual: &T, threshold: &T) -> bool {
+	*residual < *threshold || *residual == zero::<T>()
+}
+
+/// maps the result of a monitored solve to the info and error types of the conjugate gradient
+fn cg_result<T: RealField>(result: IterativeSolveResult<T>) -> Result<CgInfo<T>, CgError<T>> {
+	match result.breakdown {
+		None => Ok(CgInfo {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+			iter_count: result.iter_count,
+			non_exhaustive: NonExhaustive(()),
+		}),
+		Some(BreakdownReason::NonPositiveDefiniteOperator) => Err(CgError::NonPositiveDefiniteOperator),
+		Some(BreakdownReason::NonPositiveDefinitePreconditioner) => Err(CgError::NonPositiveDefinitePreconditioner),
+		Some(_) => Err(CgError::NoConvergence {
+			abs_residual: result.abs_residual,
+			rel_residual: result.rel_residual,
+		}),
+	}
+}
+
+/// executes the pipelined conjugate gradient using the provided preconditioner
+///
+/// every iteration applies the operator and the preconditioner once, and computes all its inner
+/// products with a single reduction
+#[track_caller]
+pub fn pipelined_cg<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: CgParams<T::Real>,
+	callback: impl FnMut(MatRef<'_, T>),
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<CgInfo<T::Real>, CgError<T::Real>> {
+	let mut callback = callback;
+	cg_result(pipelined_cg_imp(
+		out,
+		&precond,
+		&mat,
+		rhs,
+		params,
+		&mut |state: IterationState<'_, T>| {
+			callback(state.solution);
+			IterationControl::Continue
+		},
+		par,
+		stack,
+	))
+}
+
+/// executes the pipelined conjugate gradient using the provided preconditioner, and calls
+/// `monitor` at the end of every iteration
+///
+/// the residual norms passed to the monitor are the largest ones over the columns of the
+/// right-hand side. the monitor can stop the iteration early, see [`IterationControl`]
+#[track_caller]
+pub fn pipelined_cg_monitored<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: CgParams<T::Real>,
+	monitor: impl FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	pipelined_cg_imp(out, &precond, &mat, rhs, params, &mut { monitor }, par, stack)
+}
+
+#[track_caller]
+#[math]
+fn pipelined_cg_imp<T: ComplexField>(
+	mut x: MatMut<'_, T>,
+	M: &dyn Precond<T>,
+	A: &dyn LinOp<T>,
+	b: MatRef<'_, T>,
+	params: CgParams<T::Real>,
+	monitor: &mut dyn FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	let (n, k) = b.shape();
+	assert!(all(A.nrows() == n, A.ncols() == n, x.nrows() == n, x.ncols() == k));
+
+	let (b_norm, threshold) = thresholds(b, copy(params.abs_tolerance), copy(params.rel_tolerance));
+
+	let mut r = b.to_owned();
+	if params.initial_guess == InitialGuessStatus::Zero {
+		x.fill(zero());
+	} else {
+		let mut Ax = Mat::<T>::zeros(n, k);
+		A.apply(Ax.as_mut(), x.rb(), par, stack);
+		r -= &Ax;
+	}
+
+	// u = M r, w = A u
+	let mut u = Mat::<T>::zeros(n, k);
+	let mut w = Mat::<T>::zeros(n, k);
+	M.apply(u.as_mut(), r.as_ref(), par, stack);
+	A.apply(w.as_mut(), u.as_ref(), par, stack);
+
+	let mut m = Mat::<T>::zeros(n, k);
+	let mut nn = Mat::<T>::zeros(n, k);
+	let mut z = Mat::<T>::zeros(n, k);
+	let mut q = Mat::<T>::zeros(n, k);
+	let mut s = Mat::<T>::zeros(n, k);
+	let mut p = Mat::<T>::zeros(n, k);
+
+	let mut gamma_old = vec![zero::<T::Real>(); k];
+	let mut alpha_old = vec![zero::<T::Real>(); k];
+	let mut residual = vec![zero::<T::Real>(); k];
+	let mut converged = vec![false; k];
+
+	let mut iter = 0;
+	loop {
+		// the single reduction of the iteration
+		let dots = fused_dots(&[(r.as_ref(), u.as_ref()), (w.as_ref(), u.as_ref()), (r.as_ref(), r.as_ref())], par);
+		for j in 0..k {
+			residual[j] = sqrt(max(real(dots[(2, j)]), zero::<T::Real>()));
+			converged[j] = converged[j] || is_converged(&residual[j], &threshold[j]);
+		}
+
+		let (abs_residual, rel_residual) = max_residuals(&residual, &b_norm);
+		if iter > 0 {
+			match monitor(IterationState {
+				iter_count: iter,
+				solution: x.rb(),
+				residual: Some(r.as_ref()),
+				abs_residual: copy(abs_residual),
+				rel_residual: copy(rel_residual),
+				rhs_norm: b.norm_l2(),
+				non_exhaustive: NonExhaustive(()),
+			}) {
+				IterationControl::Continue => {},
+				IterationControl::Converged => return IterativeSolveResult::converged(iter, abs_residual, rel_residual),
+				IterationControl::Stop => return IterativeSolveResult::breakdown(BreakdownReason::Stopped, iter, abs_residual, rel_residual),
+			}
+		}
+		if converged.iter().all(|c| *c) {
+			return IterativeSolveResult::converged(iter, abs_residual, rel_residual);
+		}
+		if iter >= params.max_iters {
+			return IterativeSolveResult::breakdown(BreakdownReason::MaxIterations, iter, abs_residual, rel_residual);
+		}
+
+		// these applications don't depend on the reduction, so they can overlap with it in a
+		// distributed setting
+		M.apply(m.as_mut(), w.as_ref(), par, stack);
+		A.apply(nn.as_mut(), m.as_ref(), par, stack);
+
+		let mut alpha = vec![zero::<T>(); k];
+		let mut beta = vec![zero::<T>(); k];
+		for j in 0..k {
+			if converged[j] {
+				continue;
+			}
+			let gamma = real(dots[(0, j)]);
+			let delta = real(dots[(1, j)]);
+			if !(gamma > zero::<T::Real>()) {
+				return IterativeSolveResult::breakdown(BreakdownReason::NonPositiveDefinitePreconditioner, iter, abs_residual, rel_residual);
+			}
+			let (a, bt) = if iter == 0 || gamma_old[j] == zero::<T::Real>() {
+				(gamma / delta, zero::<T::Real>())
+			} else {
+				let bt = gamma / gamma_old[j];
+				(gamma / (delta - bt * gamma / alpha_old[j]), bt)
+			};
+			if !(a > zero::<T::Real>()) {
+				return IterativeSolveResult::breakdown(BreakdownReason::NonPositiveDefiniteOperator, iter, abs_residual, rel_residual);
+			}
+			gamma_old[j] = gamma;
+			alpha_old[j] = copy(a);
+			alpha[j] = from_real(a);
+			beta[j] = from_real(bt);
+		}
+
+		for j in 0..k {
+			let (a, bt) = (&alpha[j], &beta[j]);
+			zip!(z.col_mut(j), nn.col(j)).for_each(|unzip!(z, n)| *z = *n + *bt * *z);
+			zip!(q.col_mut(j), m.col(j)).for_each(|unzip!(q, m)| *q = *m + *bt * *q);
+			zip!(s.col_mut(j), w.col(j)).for_each(|unzip!(s, w)| *s = *w + *bt * *s);
+			zip!(p.col_mut(j), u.col(j)).for_each(|unzip!(p, u)| *p = *u + *bt * *p);
+
+			zip!(x.rb_mut().col_mut(j), p.col(j)).for_each(|unzip!(x, p)| *x = *x + *a * *p);
+			zip!(r.col_mut(j), s.col(j)).for_each(|unzip!(r, s)| *r = *r - *a * *s);
+			zip!(u.col_mut(j), q.col(j)).for_each(|unzip!(u, q)| *u = *u - *a * *q);
+			zip!(w.col_mut(j), z.col(j)).for_each(|unzip!(w, z)| *w = *w - *a * *z);
+		}
+		iter += 1;
+	}
+}
+
+/// solves `G a = g` for the self-adjoint positive semidefinite matrix `G`, ignoring the numerically
+/// zero eigenvalues, or returns `None` if `G` has a negative eigenvalue
+#[math]
+fn psd_solve<T: ComplexField>(G: MatRef<'_, T>, g: MatRef<'_, T>) -> Option<Mat<T>> {
+	if G.nrows() == 0 {
+		return Some(Mat::zeros(0, g.ncols()));
+	}
+	let evd = SelfAdjointEigen::new(G, Side::Lower).ok()?;
+	let S = evd.S().column_vector();
+	let U = evd.U();
+
+	let mut s_max = zero::<T::Real>();
+	for i in 0..S.nrows() {
+		s_max = max(s_max, abs(S[i]));
+	}
+	let tol = eps::<T::Real>() * from_f64::<T::Real>(1024.0) * s_max;
+
+	let mut Utg = Mat::<T>::zeros(U.ncols(), g.ncols());
+	linalg::matmul::matmul(Utg.as_mut(), Accum::Replace, U.adjoint(), g, one(), Par::Seq);
+	for i in 0..S.nrows() {
+		let s = real(S[i]);
+		if s < -tol {
+			return None;
+		}
+		let inv = if s > tol { recip(s) } else { zero() };
+		zip!(Utg.row_mut(i)).for_each(|unzip!(v)| *v = mul_real(*v, inv));
+	}
+	let mut out = Mat::<T>::zeros(G.nrows(), g.ncols());
+	linalg::matmul::matmul(out.as_mut(), Accum::Replace, U, Utg.as_ref(), one(), Par::Seq);
+	Some(out)
+}
+
+#[math]
+fn normalize<T: ComplexField>(x: ColMut<'_, T>) {
+	let norm = x.norm_l2();
+	if norm > zero::<T::Real>() {
+		let inv = recip(norm);
+		zip!(x).for_each(|unzip!(x)| *x = mul_real(*x, inv));
+	}
+}
+
+/// executes the s-step conjugate gradient using the provided preconditioner
+///
+/// every outer iteration applies the operator and the preconditioner `params.s` times, and
+/// computes all the inner products with a single matrix product
+///
+/// # panics
+///
+/// panics if `params.s` is zero
+#[track_caller]
+pub fn s_step_cg<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: SStepCgParams<T::Real>,
+	callback: impl FnMut(MatRef<'_, T>),
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<CgInfo<T::Real>, CgError<T::Real>> {
+	let mut callback = callback;
+	cg_result(s_step_cg_imp(
+		out,
+		&precond,
+		&mat,
+		rhs,
+		params,
+		&mut |state: IterationState<'_, T>| {
+			callback(state.solution);
+			IterationControl::Continue
+		},
+		par,
+		stack,
+	))
+}
+
+/// executes the s-step conjugate gradient using the provided preconditioner, and calls `monitor`
+/// at the end of every outer iteration
+///
+/// the columns of the right-hand side are solved one after the other, and the residual norms
+/// passed to the monitor are the largest ones over the columns solved so far, including the
+/// current one. [`IterationControl::Converged`] accepts the current column and moves to the next
+/// one, while [`IterationControl::Stop`] stops the whole solve
+///
+/// # panics
+///
+/// panics if `params.s` is zero
+#[track_caller]
+pub fn s_step_cg_monitored<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: SStepCgParams<T::Real>,
+	monitor: impl FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	s_step_cg_imp(out, &precond, &mat, rhs, params, &mut { monitor }, par, stack)
+}
+
+#[track_caller]
+#[math]
+fn s_step_cg_imp<T: ComplexField>(
+	mut x: MatMut<'_, T>,
+	M: &dyn Precond<T>,
+	A: &dyn LinOp<T>,
+	b: MatRef<'_, T>,
+	params: SStepCgParams<T::Real>,
+	monitor: &mut dyn FnMut(IterationState<'_, T>) -> IterationControl,
+	par: Par,
+	stack: &mut MemStack,
+) -> IterativeSolveResult<T::Real> {
+	let (n, k) = b.shape();
+	let s = params.s;
+	assert!(all(A.nrows() == n, A.ncols() == n, x.nrows() == n, x.ncols() == k, s > 0));
+
+	let (b_norm, threshold) = thresholds(b, copy(params.abs_tolerance), copy(params.rel_tolerance));
+	if params.initial_guess == InitialGuessStatus::Zero {
+		x.fill(zero());
+	}
+
+	let mut residual = vec![zero::<T::Real>(); k];
+	let mut iter_count = 0;
+	let mut all_converged = true;
+
+	for c in 0..k {
+		let mut r = b.col(c).to_owned();
+		if params.initial_guess == InitialGuessStatus::MaybeNonZero {
+			let mut Ax = Col::<T>::zeros(n);
+			A.apply(Ax.as_mat_mut(), x.rb().col(c).as_mat(), par, stack);
+			r -= &Ax;
+		}
+		residual[c] = r.norm_l2();
+
+		// previous block of search directions, with P^H A P = G
+		let mut P = Mat::<T>::zeros(n, 0);
+		let mut AP = Mat::<T>::zeros(n, 0);
+		let mut G = Mat::<T>::zeros(0, 0);
+
+		let mut S = Mat::<T>::zeros(n, s);
+		let mut AS = Mat::<T>::zeros(n, s);
+		let mut iter = 0;
+		let mut accepted = false;
+		while !accepted && !is_converged(&residual[c], &threshold[c]) {
+			if iter >= params.max_iters {
+				all_converged = false;
+				break;
+			}
+
+			// S = [z, (M A) z, ..., (M A)^{s-1} z] with z = M r, with normalized columns
+			M.apply(S.as_mut().col_mut(0).as_mat_mut(), r.as_mat(), par, stack);
+			normalize(S.as_mut().col_mut(0));
+			for j in 0..s {
+				A.apply(AS.as_mut().col_mut(j).as_mat_mut(), S.as_ref().col(j).as_mat(), par, stack);
+				if j + 1 < s {
+					M.apply(S.as_mut().col_mut(j + 1).as_mat_mut(), AS.as_ref().col(j).as_mat(), par, stack);
+					normalize(S.as_mut().col_mut(j + 1));
+				}
+			}
+			iter += s;
+
+			// the single reduction of the outer iteration: [P, S]^H [A S, r]
+			let PS = crate::concat![[P.as_ref(), S.as_ref()]];
+			let ASr = crate::concat![[AS.as_ref(), r.as_mat()]];
+			let mut F = Mat::<T>::zeros(PS.ncols(), s + 1);
+			linalg::matmul::matmul(F.as_mut(), Accum::Replace, PS.adjoint(), ASr.as_ref(), one(), par);
+			let p = P.ncols();
+			let PtAS = F.as_ref().submatrix(0, 0, p, s);
+			let Ptr = F.as_ref().submatrix(0, s, p, 1);
+			let StAS = F.as_ref().submatrix(p, 0, s, s);
+			let Str = F.as_ref().submatrix(p, s, s, 1);
+
+			// A-orthogonalize S against the previous directions
+			let B = match psd_solve(G.as_ref(), PtAS) {
+				Some(B) => B,
+				None => {
+					let (abs_residual, rel_residual) = max_residuals(&residual[..c + 1], &b_norm[..c + 1]);
+					return IterativeSolveResult::breakdown(
+						BreakdownReason::NonPositiveDefiniteOperator,
+						Ord::max(iter_count, iter),
+						abs_residual,
+						rel_residual,
+					);
+				},
+			};
+			let mut P_new = S.clone();
+			let mut AP_new = AS.clone();
+			linalg::matmul::matmul(P_new.as_mut(), Accum::Add, P.as_ref(), B.as_ref(), -one::<T>(), par);
+			linalg::matmul::matmul(AP_new.as_mut(), Accum::Add, AP.as_ref(), B.as_ref(), -one::<T>(), par);
+
+			let mut G_new = StAS.to_owned();
+			linalg::matmul::matmul(G_new.as_mut(), Accum::Add, PtAS.adjoint(), B.as_ref(), -one::<T>(), par);
+			let G_new = Mat::<T>::from_fn(s, s, |i, j| mul_real(G_new[(i, j)] + conj(G_new[(j, i)]), from_f64::<T::Real>(0.5)));
+			let mut g = Str.to_owned();
+			linalg::matmul::matmul(g.as_mut(), Accum::Add, B.adjoint(), Ptr, -one::<T>(), par);
+
+			let alpha = match psd_solve(G_new.as_ref(), g.as_ref()) {
+				Some(alpha) => alpha,
+				None => {
+					let (abs_residual, rel_residual) = max_residuals(&residual[..c + 1], &b_norm[..c + 1]);
+					return IterativeSolveResult::breakdown(
+						BreakdownReason::NonPositiveDefiniteOperator,
+						Ord::max(iter_count, iter),
+						abs_residual,
+						rel_residual,
+					);
+				},
+			};
+
+			linalg::matmul::matmul(x.rb_mut().col_mut(c).as_mat_mut(), Accum::Add, P_new.as_ref(), alpha.as_ref(), one(), par);
+			linalg::matmul::matmul(r.as_mat_mut(), Accum::Add, AP_new.as_ref(), alpha.as_ref(), -one::<T>(), par);
+			residual[c] = r.norm_l2();
+
+			let (abs_residual, rel_residual) = max_residuals(&residual[..c + 1], &b_norm[..c + 1]);
+			match monitor(IterationState {
+				iter_count: Ord::max(iter_count, iter),
+				solution: x.rb(),
+				residual: None,
+				abs_residual: copy(abs_residual),
+				rel_residual: copy(rel_residual),
+				rhs_norm: b.norm_l2(),
+				non_exhaustive: NonExhaustive(()),
+			}) {
+				IterationControl::Continue => {},
+				IterationControl::Converged => accepted = true,
+				IterationControl::Stop => {
+					return IterativeSolveResult::breakdown(BreakdownReason::Stopped, Ord::max(iter_count, iter), abs_residual, rel_residual);
+				},
+			}
+
+			P = P_new;
+			AP = AP_new;
+			G = G_new;
+		}
+		iter_count = Ord::max(iter_count, iter);
+	}
+
+	let (abs_residual, rel_residual) = max_residuals(&residual, &b_norm);
+	if all_converged {
+		IterativeSolveResult::converged(iter_count, abs_residual, rel_residual)
+	} else {
+		IterativeSolveResult::breakdown(BreakdownReason::MaxIterations, iter_count, abs_residual, rel_residual)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::matrix_free::conjugate_gradient::{conjugate_gradient, conjugate_gradient_scratch};
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	/// self-adjoint positive definite matrix with the given eigenvalues
+	fn spd(rng: &mut StdRng, eigvals: &[f64]) -> Mat<c64> {
+		let n = eigvals.len();
+		let Q = Mat::<c64>::random_normal(rng, n, n).qr().compute_thin_Q();
+		let D = Mat::<c64>::from_fn(n, n, |i, j| if i == j { c64::new(eigvals[i], 0.0) } else { c64::new(0.0, 0.0) });
+		&Q * &D * Q.adjoint()
+	}
+
+	#[test]
+	fn test_pipelined_cg() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 100;
+		let A = spd(rng, &(0..n).map(|i| 1.0 + i as f64).collect::<Vec<_>>());
+		let mut b = Mat::<c64>::random_normal(rng, n, 3);
+		b.col_mut(2).fill(c64::new(0.0, 0.0));
+
+		let params = CgParams {
+			rel_tolerance: 1e-10,
+			..Default::default()
+		};
+		let precond = IdentityPrecond { dim: n };
+		let D = Mat::<c64>::from_fn(n, n, |i, j| if i == j { c64::new(1.0, 0.0) / A[(i, i)] } else { c64::new(0.0, 0.0) });
+
+		for par in [Par::Seq, Par::rayon(4)] {
+			let mut x = Mat::<c64>::zeros(n, 3);
+			let info = pipelined_cg(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				params,
+				|_| {},
+				par,
+				MemStack::new(&mut MemBuffer::new(pipelined_cg_scratch(precond, A.as_ref(), 3, par))),
+			)
+			.unwrap();
+			let r = &b - &A * &x;
+			for j in 0..3 {
+				assert!(r.col(j).norm_l2() <= 1e-9 * b.col(j).norm_l2());
+			}
+
+			// same iteration count as the standard conjugate gradient, up to rounding errors
+			let mut y = Mat::<c64>::zeros(n, 1);
+			let cg = conjugate_gradient(
+				y.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref().subcols(0, 1),
+				params,
+				|_| {},
+				par,
+				MemStack::new(&mut MemBuffer::new(conjugate_gradient_scratch(precond, A.as_ref(), 1, par))),
+			)
+			.unwrap();
+			assert!(info.iter_count <= cg.iter_count + 5);
+
+			// jacobi preconditioner, with a nonzero initial guess
+			let mut x = Mat::<c64>::random_normal(rng, n, 3);
+			x.col_mut(2).fill(c64::new(0.0, 0.0));
+			pipelined_cg(
+				x.as_mut(),
+				D.as_ref(),
+				A.as_ref(),
+				b.as_ref(),
+				CgParams {
+					initial_guess: InitialGuessStatus::MaybeNonZero,
+					..params
+				},
+				|_| {},
+				par,
+				MemStack::new(&mut MemBuffer::new(pipelined_cg_scratch(D.as_ref(), A.as_ref(), 3, par))),
+			)
+			.unwrap();
+			assert!((&b - &A * &x).norm_l2() <= 1e-9 * b.norm_l2());
+		}
+
+		// indefinite operator
+		let B = spd(rng, &(0..n).map(|i| i as f64 - 10.5).collect::<Vec<_>>());
+		let mut x = Mat::<c64>::zeros(n, 3);
+		assert!(matches!(
+			pipelined_cg(
+				x.as_mut(),
+				precond,
+				B.as_ref(),
+				b.as_ref(),
+				params,
+				|_| {},
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(pipelined_cg_scratch(precond, B.as_ref(), 3, Par::Seq))),
+			),
+			Err(CgError::NonPositiveDefiniteOperator)
+		));
+	}
+
+	#[test]
+	fn test_pipelined_cg_monitored() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 100;
+		let A = spd(rng, &(0..n).map(|i| 1.0 + i as f64).collect::<Vec<_>>());
+		let b = Mat::<c64>::random_normal(rng, n, 2);
+		let params = CgParams {
+			rel_tolerance: 1e-10,
+			..Default::default()
+		};
+		let precond = IdentityPrecond { dim: n };
+		let mem = &mut MemBuffer::new(pipelined_cg_scratch(precond, A.as_ref(), 2, Par::Seq));
+
+		let mut history = Vec::new();
+		let mut x = Mat::<c64>::zeros(n, 2);
+		let result = pipelined_cg_monitored(
+			x.as_mut(),
+			precond,
+			A.as_ref(),
+			b.as_ref(),
+			params,
+			|state| {
+				let residual = state.residual.unwrap();
+				assert!((&b - &A * state.solution - residual).norm_l2() <= 1e-8 * b.norm_l2());
+				history.push(state.rel_residual);
+				IterationControl::Continue
+			},
+			Par::Seq,
+			MemStack::new(mem),
+		);
+		assert!(result.converged);
+		assert!(history.len() == result.iter_count);
+		assert!(*history.last().unwrap() == result.rel_residual);
+
+		// custom convergence test
+		let mut x = Mat::<c64>::zeros(n, 2);
+		let loose = pipelined_cg_monitored(
+			x.as_mut(),
+			precond,
+			A.as_ref(),
+			b.as_ref(),
+			params,
+			|state| {
+				if state.rel_residual < 1e-3 {
+					IterationControl::Converged
+				} else {
+					IterationControl::Continue
+				}
+			},
+			Par::Seq,
+			MemStack::new(mem),
+		);
+		assert!(loose.converged);
+		assert!(loose.rel_residual < 1e-3);
+		assert!(loose.iter_count < result.iter_count);
+
+		// the s-step variant accepts the columns one at a time
+		let mut x = Mat::<c64>::zeros(n, 2);
+		let mut calls = 0;
+		let result = s_step_cg_monitored(
+			x.as_mut(),
+			precond,
+			A.as_ref(),
+			b.as_ref(),
+			SStepCgParams {
+				rel_tolerance: 1e-10,
+				..Default::default()
+			},
+			|_| {
+				calls += 1;
+				IterationControl::Converged
+			},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(s_step_cg_scratch(precond, A.as_ref(), Par::Seq))),
+		);
+		assert!(result.converged);
+		assert!(calls == 2);
+		assert!(result.iter_count == 4);
+	}
+
+	#[test]
+	fn test_s_step_cg() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 100;
+		let A = spd(rng, &(0..n).map(|i| 1.0 + i as f64).collect::<Vec<_>>());
+		let mut b = Mat::<c64>::random_normal(rng, n, 3);
+		b.col_mut(2).fill(c64::new(0.0, 0.0));
+		let precond = IdentityPrecond { dim: n };
+		let D = Mat::<c64>::from_fn(n, n, |i, j| if i == j { c64::new(1.0, 0.0) / A[(i, i)] } else { c64::new(0.0, 0.0) });
+
+		for s in [1, 3, 5] {
+			let params = SStepCgParams {
+				rel_tolerance: 1e-10,
+				s,
+				..Default::default()
+			};
+			for par in [Par::Seq, Par::rayon(4)] {
+				let mut x = Mat::<c64>::random_normal(rng, n, 3);
+				x.col_mut(2).fill(c64::new(0.0, 0.0));
+				let info = s_step_cg(
+					x.as_mut(),
+					precond,
+					A.as_ref(),
+					b.as_ref(),
+					params,
+					|_| {},
+					par,
+					MemStack::new(&mut MemBuffer::new(s_step_cg_scratch(precond, A.as_ref(), par))),
+				)
+				.unwrap();
+				let r = &b - &A * &x;
+				for j in 0..2 {
+					assert!(r.col(j).norm_l2() <= 1e-9 * b.col(j).norm_l2());
+				}
+				assert!(r.col(2).norm_l2() == 0.0);
+				assert!(info.iter_count <= 200);
+
+				let mut x = Mat::<c64>::zeros(n, 3);
+				s_step_cg(
+					x.as_mut(),
+					D.as_ref(),
+					A.as_ref(),
+					b.as_ref(),
+					SStepCgParams {
+						initial_guess: InitialGuessStatus::Zero,
+						..params
+					},
+					|_| {},
+					par,
+					MemStack::new(&mut MemBuffer::new(s_step_cg_scratch(D.as_ref(), A.as_ref(), par))),
+				)
+				.unwrap();
+				assert!((&b - &A * &x).norm_l2() <= 1e-9 * b.norm_l2());
+			}
+		}
+
+		// not enough iterations
+		let mut x = Mat::<c64>::zeros(n, 3);
+		assert!(matches!(
+			s_step_cg(
+				x.as_mut(),
+				precond,
+				A.as_ref(),
+				b.as_ref(),
+				SStepCgParams {
+					max_iters: 8,
+					..Default::default()
+				},
+				|_| {},
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(s_step_cg_scratch(precond, A.as_ref(), Par::Seq))),
+			),
+			Err(CgError::NoConvergence { .. })
+		));
+	}
+}
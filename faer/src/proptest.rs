@@ -0,0 +1,300 @@
+//! [`proptest`](::proptest) strategies for generating matrices
+//!
+//! [`Mat<T>`] implements [`Arbitrary`] for any scalar type, so `any::<Mat<f64>>()` can be used
+//! directly in property tests. the shape and values of the generated matrices are controlled with
+//! [`MatParams`], using `any_with::<Mat<T>>(params)` or [`mat`]
+//!
+//! # example
+//! ```
+//! use faer::prelude::*;
+//! use faer::proptest::{MatParams, Structure};
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn transpose_is_involutive(A in any::<Mat<f64>>()) {
+//!         prop_assert!(A.transpose().transpose() == A);
+//!     }
+//!
+//!     fn norm_is_scale_invariant(A in any_with::<Mat<c64>>(MatParams {
+//!         nrows: (1..=6).into(),
+//!         structure: Structure::Adversarial,
+//!         ..Default::default()
+//!     })) {
+//!         prop_assert!((A.norm_max() * 2.0 - (&A * Scale(c64::new(2.0, 0.0))).norm_max()).abs() <= A.norm_max() * 1e-15);
+//!     }
+//! }
+//! # fn main() {
+//! # 	transpose_is_involutive();
+//! # 	norm_is_scale_invariant();
+//! # }
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use ::proptest::arbitrary::Arbitrary;
+use ::proptest::collection::{SizeRange, vec};
+use ::proptest::prelude::*;
+use ::proptest::sample::Index;
+use ::proptest::strategy::BoxedStrategy;
+
+/// distribution of the generated matrix entries
+///
+/// for complex scalars, the real and imaginary parts are generated independently
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Values {
+	/// values uniformly distributed over $[-1, 1]$
+	Uniform,
+	/// values of the form $\pm m 2^e$, where $m$ is in $[1, 2)$ and the exponent $e$ is uniformly
+	/// distributed over `min_exponent..=max_exponent`
+	LogUniform {
+		/// smallest exponent
+		min_exponent: i32,
+		/// largest exponent
+		max_exponent: i32,
+	},
+	/// integers uniformly distributed over `min..=max`. arithmetic on small integers is often
+	/// exact, which makes these convenient for comparing against a reference implementation
+	Integer {
+		/// smallest value
+		min: i32,
+		/// largest value
+		max: i32,
+	},
+}
+
+/// structure of the generated matrices
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Structure {
+	/// matrices whose entries are sampled independently
+	Generic,
+	/// matrices whose entries are sampled independently, mixed with difficult inputs: the zero
+	/// matrix, zero rows and columns, duplicated columns, and matrices or columns scaled close to
+	/// the overflow or underflow thresholds
+	Adversarial,
+}
+
+/// matrix generation parameters
+#[derive(Clone, Debug)]
+pub struct MatParams {
+	/// range of the number of rows
+	pub nrows: SizeRange,
+	/// range of the number of columns
+	pub ncols: SizeRange,
+	/// distribution of the entries
+	pub values: Values,
+	/// structure of the generated matrices
+	pub structure: Structure,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for MatParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			nrows: (0..=8).into(),
+			ncols: (0..=8).into(),
+			values: Values::Uniform,
+			structure: Structure::Generic,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// transformation applied to a generated matrix to make it adversarial
+#[derive(Copy, Clone, Debug)]
+enum Perturbation {
+	None,
+	Zero,
+	ZeroCol(Index),
+	ZeroRow(Index),
+	DuplicateCol(Index, Index),
+	Scale(bool),
+	ScaleCol(Index, bool),
+}
+
+fn real_values(values: Values) -> BoxedStrategy<f64> {
+	match values {
+		Values::Uniform => (-1.0..=1.0f64).boxed(),
+		Values::LogUniform { min_exponent, max_exponent } => {
+			assert!(min_exponent <= max_exponent);
+			(any::<bool>(), 1.0..2.0f64, min_exponent..=max_exponent)
+				.prop_map(|(neg, mantissa, exp)| {
+					let value = mantissa * libm::exp2(exp as f64);
+					if neg { -value } else { value }
+				})
+				.boxed()
+		},
+		Values::Integer { min, max } => {
+			assert!(min <= max);
+			(min..=max).prop_map(|x| x as f64).boxed()
+		},
+	}
+}
+
+fn perturbation() -> BoxedStrategy<Perturbation> {
+	prop_oneof![
+		4 => Just(Perturbation::None),
+		1 => Just(Perturbation::Zero),
+		1 => any::<Index>().prop_map(Perturbation::ZeroCol),
+		1 => any::<Index>().prop_map(Perturbation::ZeroRow),
+		2 => (any::<Index>(), any::<Index>()).prop_map(|(src, dst)| Perturbation::DuplicateCol(src, dst)),
+		1 => any::<bool>().prop_map(Perturbation::Scale),
+		1 => (any::<Index>(), any::<bool>()).prop_map(|(j, huge)| Perturbation::ScaleCol(j, huge)),
+	]
+	.boxed()
+}
+
+fn perturb<T: ComplexField>(mut A: Mat<T>, perturbation: Perturbation) -> Mat<T> {
+	let (m, n) = A.shape();
+	if m == 0 || n == 0 {
+		return A;
+	}
+
+	let factor = |huge: bool| {
+		if huge {
+			sqrt_max_positive::<T::Real>()
+		} else {
+			sqrt_min_positive::<T::Real>()
+		}
+	};
+
+	match perturbation {
+		Perturbation::None => {},
+		Perturbation::Zero => A.fill(zero()),
+		Perturbation::ZeroCol(j) => A.col_mut(j.index(n)).fill(zero()),
+		Perturbation::ZeroRow(i) => A.row_mut(i.index(m)).fill(zero()),
+		Perturbation::DuplicateCol(src, dst) => {
+			let (src, dst) = (src.index(n), dst.index(n));
+			if src != dst {
+				let col = A.col(src).to_owned();
+				A.col_mut(dst).copy_from(&col);
+			}
+		},
+		Perturbation::Scale(huge) => {
+			let factor = factor(huge);
+			z!(A.as_mut()).for_each(|uz!(a)| *a = mul_real(a, &factor));
+		},
+		Perturbation::ScaleCol(j, huge) => {
+			let factor = factor(huge);
+			z!(A.col_mut(j.index(n))).for_each(|uz!(a)| *a = mul_real(a, &factor));
+		},
+	}
+	A
+}
+
+/// returns a strategy generating matrices with the given parameters
+pub fn mat<T: ComplexField>(params: MatParams) -> BoxedStrategy<Mat<T>> {
+	let MatParams {
+		nrows,
+		ncols,
+		values,
+		structure,
+		..
+	} = params;
+
+	let nparts = if try_const! { T::IS_REAL } { 1 } else { 2 };
+	let perturbation = match structure {
+		Structure::Generic => Just(Perturbation::None).boxed(),
+		Structure::Adversarial => perturbation(),
+	};
+
+	let nrows = nrows.start()..=nrows.end_incl();
+	let ncols = ncols.start()..=ncols.end_incl();
+
+	(nrows, ncols)
+		.prop_flat_map(move |(m, n)| (Just(m), Just(n), vec(real_values(values), nparts * m * n), perturbation.clone()))
+		.prop_map(move |(m, n, parts, perturbation)| {
+			let A = Mat::from_fn(m, n, |i, j| {
+				let k = nparts * (i + m * j);
+				let re = from_f64::<T>(parts[k]);
+				if nparts == 1 {
+					re
+				} else {
+					// the square root of `-1` is exact, so this only sets the imaginary part
+					add(&re, &mul_real(&sqrt(&neg(&one::<T>())), &from_f64::<T::Real>(parts[k + 1])))
+				}
+			});
+			perturb(A, perturbation)
+		})
+		.boxed()
+}
+
+impl<T: ComplexField> Arbitrary for Mat<T> {
+	type Parameters = MatParams;
+	type Strategy = BoxedStrategy<Mat<T>>;
+
+	#[inline]
+	fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+		mat(params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{assert, c32, c64};
+	use ::proptest::strategy::ValueTree;
+	use ::proptest::test_runner::TestRunner;
+
+	fn samples<T: ComplexField>(params: MatParams, count: usize) -> Vec<Mat<T>> {
+		let mut runner = TestRunner::deterministic();
+		let strategy = any_with::<Mat<T>>(params);
+		(0..count).map(|_| strategy.new_tree(&mut runner).unwrap().current()).collect()
+	}
+
+	#[test]
+	fn test_shapes_and_values() {
+		let params = MatParams {
+			nrows: (2..=5).into(),
+			ncols: (3..7).into(),
+			..Default::default()
+		};
+
+		for A in samples::<f64>(params.clone(), 100) {
+			assert!(all((2..=5).contains(&A.nrows()), (3..7).contains(&A.ncols())));
+			assert!(A.norm_max() <= 1.0);
+		}
+		let complex = samples::<c64>(params.clone(), 100);
+		assert!(complex.iter().any(|A| A.col_iter().any(|col| col.iter().any(|x| x.im != 0.0))));
+		assert!(samples::<f32>(params.clone(), 10).len() == 10);
+		assert!(samples::<c32>(params, 10).len() == 10);
+
+		let params = MatParams {
+			values: Values::Integer { min: -3, max: 3 },
+			..Default::default()
+		};
+		for A in samples::<f64>(params, 100) {
+			assert!(A.col_iter().all(|col| col.iter().all(|x| x.fract() == 0.0 && x.abs() <= 3.0)));
+		}
+
+		let params = MatParams {
+			values: Values::LogUniform {
+				min_exponent: -20,
+				max_exponent: 20,
+			},
+			..Default::default()
+		};
+		for A in samples::<f64>(params, 100) {
+			assert!(A.col_iter().all(|col| col.iter().all(|x| (0.5e-6..2.1e6).contains(&x.abs()))));
+		}
+	}
+
+	#[test]
+	fn test_adversarial() {
+		let params = MatParams {
+			nrows: (2..=4).into(),
+			ncols: (2..=4).into(),
+			structure: Structure::Adversarial,
+			..Default::default()
+		};
+
+		let samples = samples::<f64>(params, 500);
+		assert!(samples.iter().all(|A| A.norm_max().is_finite()));
+		assert!(samples.iter().any(|A| A.norm_max() == 0.0));
+		assert!(samples.iter().any(|A| A.norm_max() > 1e100));
+		assert!(samples.iter().any(|A| A.norm_max() != 0.0 && A.norm_max() < 1e-100));
+		assert!(samples.iter().any(|A| (0..A.ncols()).any(|j| (0..j).any(|k| A.col(j) == A.col(k)))));
+	}
+}
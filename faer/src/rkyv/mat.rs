@@ -0,0 +1,208 @@
+use crate::internal_prelude::*;
+use rkyv::bytecheck::{CheckBytes, Verify};
+use rkyv::rancor::{Fallible, Source};
+use rkyv::ser::{Allocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+/// archived form of a [`Mat`], with the entries stored in column-major order
+///
+/// when accessed through [`rkyv::access`], the length of the data is checked against the
+/// dimensions, so that the matrix can be viewed in place with [`ArchivedMat::as_ref`]
+#[derive(Portable, CheckBytes)]
+#[rkyv(crate = ::rkyv)]
+#[bytecheck(crate = ::rkyv::bytecheck, verify)]
+#[repr(C)]
+pub struct ArchivedMat<T> {
+	nrows: rkyv::Archived<usize>,
+	ncols: rkyv::Archived<usize>,
+	data: ArchivedVec<T>,
+}
+
+/// resolver for an [`ArchivedMat`]
+pub struct MatResolver {
+	data: VecResolver,
+}
+
+impl<T> ArchivedMat<T> {
+	/// returns the number of rows of the matrix
+	#[inline]
+	pub fn nrows(&self) -> usize {
+		self.nrows.to_native() as usize
+	}
+
+	/// returns the number of columns of the matrix
+	#[inline]
+	pub fn ncols(&self) -> usize {
+		self.ncols.to_native() as usize
+	}
+
+	/// returns a view over the archived entries of the matrix, without copying them
+	#[inline]
+	pub fn as_ref(&self) -> MatRef<'_, T> {
+		MatRef::from_column_major_slice(self.data.as_slice(), self.nrows(), self.ncols())
+	}
+}
+
+/// archived entries that have the same representation as a native type on the current target
+///
+/// # safety
+/// `Self` and `Self::Native` must have the same layout, and the same bit representation
+pub unsafe trait NativeArchived: Sized {
+	/// native type
+	type Native;
+}
+
+#[cfg(target_endian = "little")]
+unsafe impl NativeArchived for rkyv::rend::f32_le {
+	type Native = f32;
+}
+#[cfg(target_endian = "little")]
+unsafe impl NativeArchived for rkyv::rend::f64_le {
+	type Native = f64;
+}
+
+impl<T: NativeArchived> ArchivedMat<T> {
+	/// returns a view over the matrix with native entries, without copying them
+	#[inline]
+	pub fn as_native(&self) -> MatRef<'_, T::Native> {
+		let data = unsafe { core::slice::from_raw_parts(self.data.as_ptr() as *const T::Native, self.data.len()) };
+		MatRef::from_column_major_slice(data, self.nrows(), self.ncols())
+	}
+}
+
+#[derive(Debug)]
+struct DimensionMismatch;
+
+impl core::fmt::Display for DimensionMismatch {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.write_str("the length of the archived data doesn't match the matrix dimensions")
+	}
+}
+
+impl core::error::Error for DimensionMismatch {}
+
+unsafe impl<T, C: Fallible + ?Sized> Verify<C> for ArchivedMat<T>
+where
+	C::Error: Source,
+{
+	fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+		if self.nrows().checked_mul(self.ncols()) == Some(self.data.len()) {
+			Ok(())
+		} else {
+			Err(C::Error::new(DimensionMismatch))
+		}
+	}
+}
+
+impl<T: Archive> Archive for MatRef<'_, T> {
+	type Archived = ArchivedMat<T::Archived>;
+	type Resolver = MatResolver;
+
+	fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+		rkyv::munge::munge!(let ArchivedMat { nrows, ncols, data } = out);
+		self.nrows().resolve((), nrows);
+		self.ncols().resolve((), ncols);
+		ArchivedVec::resolve_from_len(self.nrows() * self.ncols(), resolver.data, data);
+	}
+}
+
+impl<T: Serialize<S>, S: Fallible + Allocator + Writer + ?Sized> Serialize<S> for MatRef<'_, T> {
+	fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+		let (m, n) = self.shape();
+		let data = match self.try_as_col_major() {
+			// contiguous storage
+			Some(this) if n <= 1 || this.col_stride() == m as isize => {
+				let slice = unsafe { core::slice::from_raw_parts(this.as_ptr(), m * n) };
+				ArchivedVec::<T::Archived>::serialize_from_slice(slice, serializer)?
+			},
+			_ => ArchivedVec::<T::Archived>::serialize_from_iter::<T, _, _>((0..m * n).map(|k| &self[(k % m, k / m)]), serializer)?,
+		};
+		Ok(MatResolver { data })
+	}
+}
+
+impl<T: Archive> Archive for Mat<T> {
+	type Archived = ArchivedMat<T::Archived>;
+	type Resolver = MatResolver;
+
+	#[inline]
+	fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+		self.as_ref().resolve(resolver, out)
+	}
+}
+
+impl<T: Serialize<S>, S: Fallible + Allocator + Writer + ?Sized> Serialize<S> for Mat<T> {
+	#[inline]
+	fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+		self.as_ref().serialize(serializer)
+	}
+}
+
+impl<T: Archive, D: Fallible + ?Sized> Deserialize<Mat<T>, D> for ArchivedMat<T::Archived>
+where
+	T::Archived: Deserialize<T, D>,
+{
+	fn deserialize(&self, deserializer: &mut D) -> Result<Mat<T>, D::Error> {
+		let (m, n) = (self.nrows(), self.ncols());
+		let mut data = alloc::vec::Vec::with_capacity(m * n);
+		for x in self.data.iter() {
+			data.push(x.deserialize(deserializer)?);
+		}
+
+		let mat = Mat::from_fn(m, n, |i, j| unsafe { core::ptr::read(&data[i + j * m]) });
+		unsafe { data.set_len(0) };
+		Ok(mat)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use rkyv::rancor::Error;
+
+	#[derive(Archive, Serialize, Deserialize)]
+	#[rkyv(crate = ::rkyv)]
+	struct State {
+		step: u32,
+		weights: Mat<f64>,
+	}
+
+	#[test]
+	fn test_rkyv() {
+		let weights = Mat::from_fn(5, 3, |i, j| (i + 10 * j) as f64);
+		let bytes = rkyv::to_bytes::<Error>(&State {
+			step: 7,
+			weights: weights.clone(),
+		})
+		.unwrap();
+
+		let archived = rkyv::access::<ArchivedState, Error>(&bytes).unwrap();
+		assert!(archived.step == 7);
+		assert!(all(archived.weights.nrows() == 5, archived.weights.ncols() == 3));
+		assert!(archived.weights.as_native() == weights);
+		assert!(archived.weights.as_ref()[(4, 2)] == 24.0);
+
+		let state = rkyv::deserialize::<State, Error>(archived).unwrap();
+		assert!(state.weights == weights);
+
+		// strided views are archived in column-major order
+		for view in [weights.transpose(), weights.as_ref().reverse_rows().get(1..4, ..), weights.get(.., 1..2)] {
+			let bytes = rkyv::to_bytes::<Error>(&view).unwrap();
+			let archived = rkyv::access::<ArchivedMat<rkyv::Archived<f64>>, Error>(&bytes).unwrap();
+			assert!(archived.as_native() == view);
+			assert!(rkyv::deserialize::<Mat<f64>, Error>(archived).unwrap() == view);
+		}
+
+		let empty = rkyv::to_bytes::<Error>(&Mat::<f64>::zeros(0, 4)).unwrap();
+		let archived = rkyv::access::<ArchivedMat<rkyv::Archived<f64>>, Error>(&empty).unwrap();
+		assert!(archived.as_native().shape() == (0, 4));
+
+		// the dimensions are validated against the length of the data
+		let mut bytes = rkyv::to_bytes::<Error>(&weights).unwrap();
+		let root = bytes.len() - core::mem::size_of::<ArchivedMat<rkyv::Archived<f64>>>();
+		bytes[root] = 6;
+		assert!(rkyv::access::<ArchivedMat<rkyv::Archived<f64>>, Error>(&bytes).is_err());
+	}
+}
@@ -1,6 +1,8 @@
 use super::*;
-use crate::into_range::IntoRange;
+use crate::into_range::{IntoRange, strided};
 use crate::{Idx, IdxInc, assert, debug_assert};
+use core::iter::{Rev, StepBy};
+use core::ops::Range;
 
 impl<'a, C: Shape, T, Cs: Stride, ColRange: IntoRange<IdxInc<C>, Len<C>: 'a>> RowIndex<ColRange> for RowRef<'a, T, C, Cs> {
 	type Target = RowRef<'a, T, ColRange::Len<C>, Cs>;
@@ -90,3 +92,48 @@ macro_rules! idx_impl {
 
 idx_impl!(usize);
 idx_impl!(Dim<'N>, 'N);
+
+macro_rules! strided_impl {
+    ($($S: ty),*) => {$(
+        impl<'a, T, Cs: Stride> RowIndex<$S> for RowRef<'a, T, usize, Cs> {
+            type Target = RowRef<'a, T, usize, isize>;
+
+            #[track_caller]
+            #[inline]
+            fn get(this: Self, col: $S) -> Self::Target {
+                let (start, len, step) = strided(col, this.ncols());
+                let ptr = this.ptr_at(start);
+                unsafe { RowRef::from_raw_parts(ptr, len, this.col_stride().element_stride() * step) }
+            }
+
+            #[track_caller]
+            #[inline]
+            unsafe fn get_unchecked(this: Self, col: $S) -> Self::Target {
+                RowIndex::get(this, col)
+            }
+        }
+
+        impl<'a, T, Cs: Stride> RowIndex<$S> for RowMut<'a, T, usize, Cs> {
+            type Target = RowMut<'a, T, usize, isize>;
+
+            #[track_caller]
+            #[inline]
+            fn get(this: Self, col: $S) -> Self::Target {
+                unsafe { RowIndex::get(this.into_const(), col).const_cast() }
+            }
+
+            #[track_caller]
+            #[inline]
+            unsafe fn get_unchecked(this: Self, col: $S) -> Self::Target {
+                RowIndex::get(this, col)
+            }
+        }
+    )*};
+}
+
+strided_impl!(
+	StepBy<Range<usize>>,
+	Rev<Range<usize>>,
+	StepBy<Rev<Range<usize>>>,
+	Rev<StepBy<Range<usize>>>
+);
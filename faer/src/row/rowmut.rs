@@ -273,6 +273,28 @@ impl<T, Cols: Shape, CStride: Stride, Inner: for<'short> ReborrowMut<'short, Tar
 		self.rb_mut()
 	}
 
+	/// returns a mutable reference to the element at the given index, or `None` if the index is
+	/// out of bounds
+	#[inline]
+	pub fn get_mut_checked(&mut self, col: usize) -> Option<&mut T> {
+		let this = self.rb_mut();
+		let col = this.ncols().idx(col)?;
+		Some(unsafe { this.at_mut_unchecked(col) })
+	}
+
+	/// writes `value` to the element at the given index, or hands it back if the index is out of
+	/// bounds
+	#[inline]
+	pub fn try_write(&mut self, col: usize, value: T) -> Result<(), T> {
+		match self.get_mut_checked(col) {
+			Some(dst) => {
+				*dst = value;
+				Ok(())
+			},
+			None => Err(value),
+		}
+	}
+
 	#[inline]
 	/// copies `other` into `self`
 	pub fn copy_from<RhsT: Conjugate<Canonical = T>>(&mut self, other: impl AsRowRef<T = RhsT, Cols = Cols>)
@@ -289,6 +311,32 @@ impl<T, Cols: Shape, CStride: Stride, Inner: for<'short> ReborrowMut<'short, Tar
 	{
 		self.rb_mut().transpose_mut().fill(value)
 	}
+
+	/// replaces the elements of `self` that satisfy `pred` with `value`, and leaves the other
+	/// elements unchanged
+	#[inline]
+	pub fn set_where(&mut self, pred: impl FnMut(&T) -> bool, value: T)
+	where
+		T: Clone,
+	{
+		self.rb_mut().transpose_mut().set_where(pred, value)
+	}
+
+	/// copies the elements of `other` into `self` at the positions where `mask` is `true`, and
+	/// leaves the other elements unchanged
+	#[inline]
+	#[track_caller]
+	pub fn copy_from_where<RhsT: Conjugate<Canonical = T>>(
+		&mut self,
+		other: impl AsRowRef<T = RhsT, Cols = Cols>,
+		mask: impl AsRowRef<T = bool, Cols = Cols>,
+	) where
+		T: ComplexField,
+	{
+		self.rb_mut()
+			.transpose_mut()
+			.copy_from_where(other.as_row_ref().transpose(), mask.as_row_ref().transpose())
+	}
 }
 
 impl<'a, T, Cols: Shape, CStride: Stride> RowMut<'a, T, Cols, CStride> {
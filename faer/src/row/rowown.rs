@@ -484,6 +484,24 @@ impl<T> FromIterator<T> for Row<T> {
 	}
 }
 
+impl<T> Extend<T> for Row<T> {
+	fn extend<I>(&mut self, iter: I)
+	where
+		I: IntoIterator<Item = T>,
+	{
+		self.0.trans.extend(iter)
+	}
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for Row<T> {
+	fn extend<I>(&mut self, iter: I)
+	where
+		I: IntoIterator<Item = &'a T>,
+	{
+		self.0.trans.extend(iter)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::Row;
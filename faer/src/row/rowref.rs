@@ -347,6 +347,15 @@ impl<'a, T, Cols: Shape, CStride: Stride> RowRef<'a, T, Cols, CStride> {
 		self.transpose().as_mat().transpose()
 	}
 
+	/// returns a matrix view over `self`, repeated `nrows` times as the rows of the matrix
+	///
+	/// the view has a row stride of zero, so it can be combined with other matrices of the same
+	/// shape in [`zip!`](crate::zip!) without any copies
+	#[inline]
+	pub fn broadcast<Rows: Shape>(self, nrows: Rows) -> MatRef<'a, T, Rows, Cols, isize, CStride> {
+		self.transpose().broadcast(nrows).transpose()
+	}
+
 	/// interprets the row as a diagonal matrix
 	#[inline]
 	pub fn as_diagonal(self) -> DiagRef<'a, T, Cols, CStride> {
@@ -368,6 +377,24 @@ impl<T, Cols: Shape, CStride: Stride, Inner: for<'short> Reborrow<'short, Target
 		self.rb()
 	}
 
+	/// returns a reference to the element at the given index, or `None` if the index is out of
+	/// bounds
+	#[inline]
+	pub fn get_checked(&self, col: usize) -> Option<&T> {
+		let this = self.rb();
+		let col = this.ncols().idx(col)?;
+		Some(unsafe { this.at_unchecked(col) })
+	}
+
+	/// returns a clone of the element at the given index, or `None` if the index is out of bounds
+	#[inline]
+	pub fn try_read(&self, col: usize) -> Option<T>
+	where
+		T: Clone,
+	{
+		self.get_checked(col).cloned()
+	}
+
 	/// returns the maximum norm of `self`
 	#[inline]
 	pub fn norm_max(&self) -> Real<T>
@@ -428,6 +455,49 @@ impl<T, Cols: Shape, CStride: Stride, Inner: for<'short> Reborrow<'short, Target
 		imp(self.rb().as_mat().as_dyn().as_dyn_stride(), rhs.as_mat_ref().as_dyn().as_dyn_stride())
 	}
 
+	/// returns the dot product $x y^\top$ of `self` and `rhs`
+	///
+	/// # panics
+	/// panics if `self` and `rhs` don't have the same number of columns
+	#[inline]
+	#[track_caller]
+	pub fn dot(&self, rhs: impl AsRowRef<T: Conjugate<Canonical = T::Canonical>>) -> T::Canonical
+	where
+		T: Conjugate,
+	{
+		crate::col::colref::dot_imp(
+			self.rb().as_dyn_cols().as_dyn_stride().transpose(),
+			rhs.as_row_ref().as_dyn_cols().as_dyn_stride().transpose(),
+			Conj::No,
+		)
+	}
+
+	/// returns the dot product $\bar x y^\top$ of `self` and `rhs`, conjugating `self`
+	///
+	/// # panics
+	/// panics if `self` and `rhs` don't have the same number of columns
+	#[inline]
+	#[track_caller]
+	pub fn conj_dot(&self, rhs: impl AsRowRef<T: Conjugate<Canonical = T::Canonical>>) -> T::Canonical
+	where
+		T: Conjugate,
+	{
+		crate::col::colref::dot_imp(
+			self.rb().as_dyn_cols().as_dyn_stride().transpose(),
+			rhs.as_row_ref().as_dyn_cols().as_dyn_stride().transpose(),
+			Conj::Yes,
+		)
+	}
+
+	/// returns the outer product $x^\top y$ of `self` and `rhs`
+	#[inline]
+	pub fn outer(&self, rhs: impl AsRowRef<T: Conjugate<Canonical = T::Canonical>>) -> Mat<T::Canonical>
+	where
+		T: Conjugate,
+	{
+		self.rb().as_dyn_cols().as_dyn_stride().transpose() * rhs.as_row_ref().as_dyn_cols().as_dyn_stride()
+	}
+
 	/// returns `true` if all of the elements of `self` are finite.
 	/// otherwise returns `false`.
 	#[inline]
@@ -566,7 +636,7 @@ where
 
 #[cfg(test)]
 mod tests {
-	use crate::Row;
+	use crate::{Row, c64, mat, row};
 
 	#[test]
 	fn test_row_min() {
@@ -589,4 +659,19 @@ mod tests {
 		let emptyref = empty.as_ref();
 		assert_eq!(emptyref.max(), None);
 	}
+
+	#[test]
+	fn test_row_dot_outer() {
+		let x = row![1.0, 2.0, 3.0];
+		let y = row![4.0, -5.0, 6.0];
+		assert_eq!(x.dot(&y), 12.0);
+		assert_eq!(x.outer(row![1.0, 2.0]), mat![[1.0, 2.0], [2.0, 4.0], [3.0, 6.0]]);
+		assert_eq!(-(&x - &y), row![3.0, -7.0, 3.0]);
+
+		let x = row![c64::new(1.0, 2.0), c64::new(0.0, 1.0)];
+		let y = row![c64::new(3.0, -1.0), c64::new(2.0, 2.0)];
+		assert_eq!(x.dot(&y), c64::new(3.0, 7.0));
+		assert_eq!(x.conj_dot(y.as_ref()), c64::new(3.0, -9.0));
+		assert_eq!(x.adjoint().transpose().dot(&y), x.conj_dot(&y));
+	}
 }
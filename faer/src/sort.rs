@@ -1,7 +1,9 @@
 // code taken from the rust standard library
 
+use crate::Par;
 use crate::debug_assert;
 use core::mem::MaybeUninit;
+use dyn_stack::{MemStack, StackReq};
 
 pub unsafe trait Ptr: Sized + Copy {
 	type Item;
@@ -21,6 +23,11 @@ pub unsafe trait Ptr: Sized + Copy {
 	unsafe fn copy_nonoverlapping(src: Self, dst: Self, len: usize);
 	unsafe fn reverse(ptr: Self, len: usize);
 
+	/// returns the memory requirements of a buffer that can hold `len` items
+	fn buffer_scratch(len: usize) -> StackReq;
+	/// allocates an uninitialized buffer that can hold `len` items from `stack`
+	fn make_buffer(len: usize, stack: &mut MemStack) -> (Self, &mut MemStack);
+
 	unsafe fn swap(a: Self, b: Self) {
 		let a_item = a.read();
 		let b_item = b.read();
@@ -81,63 +88,88 @@ unsafe impl<T> Ptr for *mut T {
 	unsafe fn reverse(ptr: Self, len: usize) {
 		core::slice::from_raw_parts_mut(ptr, len).reverse()
 	}
-}
-
-unsafe impl<P: Ptr, Q: Ptr> Ptr for (P, Q) {
-	type Item = (P::Item, Q::Item);
 
 	#[inline]
-	fn get_ptr(ptr: *mut Self::Item) -> Self {
-		unsafe {
-			(
-				P::get_ptr(core::ptr::addr_of_mut!((*ptr).0)),
-				Q::get_ptr(core::ptr::addr_of_mut!((*ptr).1)),
-			)
-		}
+	fn buffer_scratch(len: usize) -> StackReq {
+		StackReq::new::<T>(len)
 	}
 
 	#[inline]
-	fn null() -> Self {
-		(P::null(), Q::null())
+	fn make_buffer(len: usize, stack: &mut MemStack) -> (Self, &mut MemStack) {
+		// the buffer is allocated as bytes, since `T` isn't required to outlive `stack`
+		let (buf, stack) = stack.make_aligned_uninit::<u8>(len.checked_mul(core::mem::size_of::<T>()).unwrap(), core::mem::align_of::<T>());
+		(buf.as_mut_ptr() as *mut T, stack)
 	}
+}
 
-	#[inline]
-	unsafe fn offset_from(self, origin: Self) -> isize {
-		self.0.offset_from(origin.0)
-	}
+macro_rules! impl_ptr_tuple {
+	($(($($P: ident $p: ident $idx: tt),*);)*) => {$(
+		unsafe impl<$($P: Ptr),*> Ptr for ($($P,)*) {
+			type Item = ($($P::Item,)*);
 
-	#[inline]
-	unsafe fn add(self, offset: usize) -> Self {
-		(self.0.add(offset), self.1.add(offset))
-	}
+			#[inline]
+			fn get_ptr(ptr: *mut Self::Item) -> Self {
+				unsafe { ($($P::get_ptr(core::ptr::addr_of_mut!((*ptr).$idx)),)*) }
+			}
 
-	#[inline]
-	unsafe fn sub(self, offset: usize) -> Self {
-		(self.0.sub(offset), self.1.sub(offset))
-	}
+			#[inline]
+			fn null() -> Self {
+				($($P::null(),)*)
+			}
 
-	#[inline]
-	unsafe fn read(self) -> Self::Item {
-		(self.0.read(), self.1.read())
-	}
+			#[inline]
+			unsafe fn offset_from(self, origin: Self) -> isize {
+				self.0.offset_from(origin.0)
+			}
 
-	#[inline]
-	unsafe fn write(self, item: Self::Item) {
-		self.0.write(item.0);
-		self.1.write(item.1);
-	}
+			#[inline]
+			unsafe fn add(self, offset: usize) -> Self {
+				($(self.$idx.add(offset),)*)
+			}
 
-	#[inline]
-	unsafe fn copy_nonoverlapping(src: Self, dst: Self, len: usize) {
-		P::copy_nonoverlapping(src.0, dst.0, len);
-		Q::copy_nonoverlapping(src.1, dst.1, len);
-	}
+			#[inline]
+			unsafe fn sub(self, offset: usize) -> Self {
+				($(self.$idx.sub(offset),)*)
+			}
 
-	#[inline]
-	unsafe fn reverse(ptr: Self, len: usize) {
-		P::reverse(ptr.0, len);
-		Q::reverse(ptr.1, len);
-	}
+			#[inline]
+			unsafe fn read(self) -> Self::Item {
+				($(self.$idx.read(),)*)
+			}
+
+			#[inline]
+			unsafe fn write(self, item: Self::Item) {
+				$(self.$idx.write(item.$idx);)*
+			}
+
+			#[inline]
+			unsafe fn copy_nonoverlapping(src: Self, dst: Self, len: usize) {
+				$($P::copy_nonoverlapping(src.$idx, dst.$idx, len);)*
+			}
+
+			#[inline]
+			unsafe fn reverse(ptr: Self, len: usize) {
+				$($P::reverse(ptr.$idx, len);)*
+			}
+
+			#[inline]
+			fn buffer_scratch(len: usize) -> StackReq {
+				StackReq::all_of(&[$($P::buffer_scratch(len)),*])
+			}
+
+			#[inline]
+			fn make_buffer(len: usize, stack: &mut MemStack) -> (Self, &mut MemStack) {
+				$(let ($p, stack) = $P::make_buffer(len, stack);)*
+				(($($p,)*), stack)
+			}
+		}
+	)*};
+}
+
+impl_ptr_tuple! {
+	(P p 0, Q q 1);
+	(P p 0, Q q 1, R r 2);
+	(P p 0, Q q 1, R r 2, S s 3);
 }
 
 struct InsertionHole<P: Ptr> {
@@ -991,6 +1023,228 @@ unsafe fn recurse<P: Ptr, F: FnMut(P, P) -> bool>(mut v: P, mut v_len: usize, is
 	}
 }
 
+/// reorders `v` so that the element at `index` is at its final sorted position, every element
+/// before it is not greater, and every element after it is not smaller
+///
+/// this uses the same pivot selection and block partitioning as [`quicksort`], and only recurses
+/// into the side containing `index`, which is *O*(*n*) on average
+pub unsafe fn partition_at_index<P: Ptr, F>(v: P, v_len: usize, index: usize, mut is_less: F)
+where
+	F: FnMut(P, P) -> bool,
+{
+	debug_assert!(index < v_len);
+
+	// Sorting has no meaningful behavior on zero-sized types.
+	if core::mem::size_of::<P::Item>() == 0 {
+		return;
+	}
+
+	// Limit the number of imbalanced partitions to `floor(log2(len)) + 1`.
+	let limit = usize::BITS - v_len.leading_zeros();
+
+	partition_at_index_loop(v, v_len, index, &mut is_less, None, limit);
+}
+
+unsafe fn partition_at_index_loop<P: Ptr, F: FnMut(P, P) -> bool>(
+	mut v: P,
+	mut v_len: usize,
+	mut index: usize,
+	is_less: &mut F,
+	mut pred: Option<P>,
+	mut limit: u32,
+) {
+	// Slices of up to this length get sorted using insertion sort.
+	const MAX_INSERTION: usize = 16;
+
+	// True if the last partitioning was reasonably balanced.
+	let mut was_balanced = true;
+
+	loop {
+		let len = v_len;
+
+		// Very short slices get sorted using insertion sort.
+		if len <= MAX_INSERTION {
+			if len >= 2 {
+				insertion_sort_shift_left(v, v_len, 1, is_less);
+			}
+			return;
+		}
+
+		// If too many bad pivot choices were made, simply fall back to heapsort in order to
+		// guarantee `O(n * log(n))` worst-case.
+		if limit == 0 {
+			heapsort(v, v_len, is_less);
+			return;
+		}
+
+		// If the last partitioning was imbalanced, try breaking patterns in the slice by shuffling
+		// some elements around. Hopefully we'll choose a better pivot this time.
+		if !was_balanced {
+			break_patterns(v, v_len);
+			limit -= 1;
+		}
+
+		// Choose a pivot.
+		let (pivot, _) = choose_pivot(v, v_len, is_less);
+
+		// If the chosen pivot is equal to the predecessor, then it's the smallest element in the
+		// slice. Partition the slice into elements equal to and elements greater than the pivot.
+		// This case is usually hit when the slice contains many duplicate elements.
+		if let Some(p) = pred {
+			if !is_less(p, v.add(pivot)) {
+				let mid = partition_equal(v, v_len, pivot, is_less);
+
+				// If we've passed our index, we're good.
+				if mid > index {
+					return;
+				}
+
+				// Otherwise, continue sorting elements greater than the pivot.
+				v = v.add(mid);
+				v_len -= mid;
+				index -= mid;
+				pred = None;
+				continue;
+			}
+		}
+
+		let (mid, _) = partition(v, v_len, pivot, is_less);
+		was_balanced = Ord::min(mid, len - mid) >= len / 8;
+
+		// Split the slice into `left`, `pivot`, and `right`, and only continue with the side that
+		// contains `index`.
+		if mid < index {
+			pred = Some(v.add(mid));
+			v = v.add(mid + 1);
+			v_len -= mid + 1;
+			index -= mid + 1;
+		} else if mid > index {
+			v_len = mid;
+		} else {
+			// If mid == index, then we're done, since partition() guaranteed that all elements
+			// after mid are greater than or equal to mid.
+			return;
+		}
+	}
+}
+
+/// sorts `v` using a parallel pattern-defeating quicksort
+///
+/// each partitioning step is sequential, after which both sides are sorted concurrently. slices
+/// that are short enough, or once the available parallelism is exhausted, are sorted with the
+/// sequential [`quicksort`]
+pub unsafe fn par_quicksort<P: Ptr, F>(v: P, v_len: usize, is_less: F, par: Par)
+where
+	F: Sync + Fn(P, P) -> bool,
+{
+	// Sorting has no meaningful behavior on zero-sized types.
+	if core::mem::size_of::<P::Item>() == 0 {
+		return;
+	}
+
+	// Limit the number of imbalanced partitions to `floor(log2(len)) + 1`.
+	let limit = usize::BITS - v_len.leading_zeros();
+
+	par_recurse(v, v_len, &is_less, None, limit, (true, true), par);
+}
+
+/// pointer that can be sent across threads, used to hand disjoint subslices to the two sides of a
+/// parallel join
+struct SendPtr<P>(P);
+unsafe impl<P> Send for SendPtr<P> {}
+
+unsafe fn par_recurse<P: Ptr, F: Sync + Fn(P, P) -> bool>(
+	mut v: P,
+	mut v_len: usize,
+	is_less: &F,
+	pred: Option<P>,
+	mut limit: u32,
+	last_partition: (bool, bool),
+	par: Par,
+) {
+	// Whether the last partitioning was reasonably balanced, and whether it didn't shuffle
+	// elements (the slice was already partitioned).
+	let (was_balanced, was_partitioned) = last_partition;
+
+	// Slices of up to this length get sorted sequentially.
+	const MAX_SEQUENTIAL: usize = 4096;
+
+	loop {
+		let len = v_len;
+
+		if len <= MAX_SEQUENTIAL || par.degree() <= 1 || limit == 0 {
+			let is_less = &mut |a, b| is_less(a, b);
+			if limit == 0 {
+				heapsort(v, v_len, is_less);
+			} else {
+				recurse(v, v_len, is_less, pred, limit);
+			}
+			return;
+		}
+
+		let is_less_mut = &mut |a, b| is_less(a, b);
+
+		// If the last partitioning was imbalanced, try breaking patterns in the slice by shuffling
+		// some elements around. Hopefully we'll choose a better pivot this time.
+		if !was_balanced {
+			break_patterns(v, v_len);
+			limit -= 1;
+		}
+
+		// Choose a pivot and try guessing whether the slice is already sorted.
+		let (pivot, likely_sorted) = choose_pivot(v, v_len, is_less_mut);
+
+		// If the last partitioning was decently balanced and didn't shuffle elements, and if pivot
+		// selection predicts the slice is likely already sorted...
+		if was_balanced && was_partitioned && likely_sorted {
+			// Try identifying several out-of-order elements and shifting them to correct
+			// positions. If the slice ends up being completely sorted, we're done.
+			if partial_insertion_sort(v, v_len, is_less_mut) {
+				return;
+			}
+		}
+
+		// If the chosen pivot is equal to the predecessor, then it's the smallest element in the
+		// slice. Partition the slice into elements equal to and elements greater than the pivot.
+		if let Some(p) = pred {
+			if !is_less(p, v.add(pivot)) {
+				let mid = partition_equal(v, v_len, pivot, is_less_mut);
+
+				// Continue sorting elements greater than the pivot.
+				v = v.add(mid);
+				v_len -= mid;
+				continue;
+			}
+		}
+
+		// Partition the slice.
+		let (mid, was_p) = partition(v, v_len, pivot, is_less_mut);
+		let was_balanced = Ord::min(mid, len - mid) >= len / 8;
+
+		// Split the slice into `left`, `pivot`, and `right`, then sort both sides concurrently.
+		// They are disjoint, and the pivot is only read by the right side.
+		let left = SendPtr(v);
+		let left_len = mid;
+		let pivot = SendPtr(v.add(mid));
+		let right = SendPtr(v.add(mid + 1));
+		let right_len = v_len - mid - 1;
+		let pred = pred.map(SendPtr);
+
+		crate::utils::thread::join_raw(
+			|par| {
+				let (left, pred) = (left, pred);
+				par_recurse(left.0, left_len, is_less, pred.map(|p| p.0), limit, (was_balanced, was_p), par)
+			},
+			|par| {
+				let (right, pivot) = (right, pivot);
+				par_recurse(right.0, right_len, is_less, Some(pivot.0), limit, (was_balanced, was_p), par)
+			},
+			par,
+		);
+		return;
+	}
+}
+
 pub unsafe fn sort_unstable_by<P: Ptr>(ptr: P, len: usize, compare: impl FnMut(P, P) -> core::cmp::Ordering) {
 	let mut compare = compare;
 	quicksort(
@@ -1008,6 +1262,185 @@ pub unsafe fn sort_indices<I: crate::Index, T>(indices: &mut [I], values: &mut [
 	sort_unstable_by((indices.as_mut_ptr(), values.as_mut_ptr()), len, |(i, _), (j, _)| (*i).cmp(&*j));
 }
 
+/// sorts `len` elements starting at `ptr` with respect to `compare`, using the given parallelism
+///
+/// the sort is unstable, and produces the same result as [`sort_unstable_by`] when `par` is
+/// [`Par::Seq`]
+pub unsafe fn par_sort_unstable_by<P: Ptr>(ptr: P, len: usize, compare: impl Sync + Fn(P, P) -> core::cmp::Ordering, par: Par) {
+	par_quicksort(
+		ptr,
+		len,
+		#[inline(always)]
+		|a, b| compare(a, b) == core::cmp::Ordering::Less,
+		par,
+	);
+}
+
+/// sorts `indices` in increasing order, applying the same permutation to `values`, using the given
+/// parallelism
+pub unsafe fn par_sort_indices<I: crate::Index, T: Send>(indices: &mut [I], values: &mut [T], par: Par) {
+	let len = indices.len();
+	debug_assert!(values.len() == len);
+
+	par_sort_unstable_by((indices.as_mut_ptr(), values.as_mut_ptr()), len, |(i, _), (j, _)| (*i).cmp(&*j), par);
+}
+
+/// when dropped, copies the remaining `len` buffered items from `src` into `dst`
+///
+/// this makes sure that every item ends up back in the slice if the comparison panics during a
+/// merge
+struct MergeHole<P: Ptr> {
+	src: P,
+	dst: P,
+	len: usize,
+}
+
+impl<P: Ptr> Drop for MergeHole<P> {
+	#[inline(always)]
+	fn drop(&mut self) {
+		// SAFETY: `src` points into the merge buffer, which never overlaps with the slice.
+		unsafe {
+			P::copy_nonoverlapping(self.src, self.dst, self.len);
+		}
+	}
+}
+
+/// merges the sorted runs `v[..mid]` and `v[mid..]`, using `buf` as temporary storage for the
+/// first run
+unsafe fn merge<P: Ptr, F: FnMut(P, P) -> bool>(v: P, v_len: usize, mid: usize, buf: P, is_less: &mut F) {
+	P::copy_nonoverlapping(v, buf, mid);
+
+	let mut hole = MergeHole { src: buf, dst: v, len: mid };
+	let mut right = v.add(mid);
+	let end = v.add(v_len);
+
+	// The write position never catches up with `right`, since it lags behind it by the number of
+	// buffered items that are left.
+	while hole.len > 0 && right.offset_from(end) < 0 {
+		// Take from the right run only if it's strictly smaller, to keep the merge stable.
+		if is_less(right, hole.src) {
+			P::copy_nonoverlapping(right, hole.dst, 1);
+			right = right.add(1);
+		} else {
+			P::copy_nonoverlapping(hole.src, hole.dst, 1);
+			hole.src = hole.src.add(1);
+			hole.len -= 1;
+		}
+		hole.dst = hole.dst.add(1);
+	}
+	// The remaining buffered items are copied back when `hole` is dropped, and the remaining
+	// items of the right run are already in place.
+}
+
+unsafe fn merge_sort<P: Ptr, F: FnMut(P, P) -> bool>(v: P, v_len: usize, buf: P, is_less: &mut F) {
+	// Slices of up to this length get sorted using insertion sort.
+	const MAX_INSERTION: usize = 20;
+
+	if v_len <= MAX_INSERTION {
+		if v_len >= 2 {
+			insertion_sort_shift_left(v, v_len, 1, is_less);
+		}
+		return;
+	}
+
+	let mid = v_len / 2;
+	merge_sort(v, mid, buf, is_less);
+	merge_sort(v.add(mid), v_len - mid, buf, is_less);
+
+	// Skip the merge if the two runs are already in order.
+	if is_less(v.add(mid), v.add(mid - 1)) {
+		merge(v, v_len, mid, buf, is_less);
+	}
+}
+
+/// reorders `len` elements starting at `ptr` so that the element at `index` is at its final
+/// position with respect to `compare`, with no greater element before it and no smaller element
+/// after it
+pub unsafe fn select_nth_unstable_by<P: Ptr>(ptr: P, len: usize, index: usize, compare: impl FnMut(P, P) -> core::cmp::Ordering) {
+	let mut compare = compare;
+	partition_at_index(
+		ptr,
+		len,
+		index,
+		#[inline(always)]
+		|a, b| compare(a, b) == core::cmp::Ordering::Less,
+	);
+}
+
+/// moves the `k` smallest of the `len` elements starting at `ptr` with respect to `compare` to
+/// the front, in sorted order. the order of the remaining elements is unspecified
+///
+/// with a reversed comparison, this selects the `k` largest elements instead
+pub unsafe fn top_k_by<P: Ptr>(ptr: P, len: usize, k: usize, compare: impl FnMut(P, P) -> core::cmp::Ordering) {
+	let mut compare = compare;
+	let k = Ord::min(k, len);
+	if k == 0 {
+		return;
+	}
+	if k < len {
+		select_nth_unstable_by(ptr, len, k - 1, &mut compare);
+	}
+	sort_unstable_by(ptr, k, compare);
+}
+
+/// returns the memory requirements of [`sort_stable_by`]
+pub fn sort_stable_scratch<P: Ptr>(len: usize) -> StackReq {
+	P::buffer_scratch(len / 2)
+}
+
+/// sorts `len` elements starting at `ptr` with respect to `compare`, using a merge sort
+///
+/// the sort is stable, i.e., it preserves the relative order of elements that compare equal
+pub unsafe fn sort_stable_by<P: Ptr>(ptr: P, len: usize, compare: impl FnMut(P, P) -> core::cmp::Ordering, stack: &mut MemStack) {
+	// Sorting has no meaningful behavior on zero-sized types.
+	if core::mem::size_of::<P::Item>() == 0 {
+		return;
+	}
+
+	let mut compare = compare;
+	let (buf, _) = P::make_buffer(len / 2, stack);
+	merge_sort(ptr, len, buf, &mut |a, b| compare(a, b) == core::cmp::Ordering::Less);
+}
+
+/// returns the memory requirements of [`sort_indices_stable`]
+pub fn sort_indices_stable_scratch<I: crate::Index, T>(len: usize) -> StackReq {
+	sort_stable_scratch::<(*mut I, *mut T)>(len)
+}
+
+pub unsafe fn sort_indices_stable<I: crate::Index, T>(indices: &mut [I], values: &mut [T], stack: &mut MemStack) {
+	let len = indices.len();
+	debug_assert!(values.len() == len);
+
+	sort_stable_by((indices.as_mut_ptr(), values.as_mut_ptr()), len, |(i, _), (j, _)| (*i).cmp(&*j), stack);
+}
+
+/// sorts `major`, `minor` and `values` simultaneously so that the pairs `(major, minor)` are in
+/// lexicographic order
+pub unsafe fn sort_indices3<I: crate::Index, T>(major: &mut [I], minor: &mut [I], values: &mut [T]) {
+	let len = major.len();
+	debug_assert!(all(minor.len() == len, values.len() == len));
+
+	sort_unstable_by(
+		(major.as_mut_ptr(), minor.as_mut_ptr(), values.as_mut_ptr()),
+		len,
+		|(i0, i1, _), (j0, j1, _)| (*i0, *i1).cmp(&(*j0, *j1)),
+	);
+}
+
+/// sorts `major`, `minor` and `values` simultaneously so that the pairs `(major, minor)` are in
+/// lexicographic order, using the given parallelism
+pub unsafe fn par_sort_indices3<I: crate::Index, T: Send>(major: &mut [I], minor: &mut [I], values: &mut [T], par: Par) {
+	let len = major.len();
+	debug_assert!(all(minor.len() == len, values.len() == len));
+
+	par_sort_unstable_by(
+		(major.as_mut_ptr(), minor.as_mut_ptr(), values.as_mut_ptr()),
+		len,
+		|(i0, i1, _), (j0, j1, _)| (*i0, *i1).cmp(&(*j0, *j1)),
+		par,
+	);
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1051,4 +1484,134 @@ mod tests {
 			assert!(b_orig[perm[i]] == b[i]);
 		}
 	}
+
+	#[test]
+	fn test_par_quicksort() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 100_000;
+
+		let random = (0..n).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+		let duplicates = (0..n).map(|_| rng.gen_range(0..16u32)).collect::<Vec<_>>();
+		let sorted = (0..n as u32).collect::<Vec<_>>();
+		let reversed = (0..n as u32).rev().collect::<Vec<_>>();
+
+		for a_orig in [random, duplicates, sorted, reversed] {
+			let b_orig = (0..n).collect::<Vec<_>>();
+
+			let mut expected = a_orig.clone();
+			expected.sort_unstable();
+
+			for par in [Par::Seq, Par::rayon(4)] {
+				let a = &mut *a_orig.clone();
+				let b = &mut *b_orig.clone();
+				unsafe { par_sort_indices(a, b, par) };
+
+				assert!(*a == *expected);
+				for i in 0..n {
+					assert!(a_orig[b[i]] == a[i]);
+				}
+				let mut perm = b.to_vec();
+				perm.sort_unstable();
+				assert!(perm == b_orig);
+			}
+		}
+	}
+
+	#[test]
+	fn test_sort_stable() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 5, 20, 21, 100, 1000, 10_000] {
+			for nkeys in [1u32, 4, 1000] {
+				let a_orig = (0..n).map(|_| rng.gen_range(0..nkeys)).collect::<Vec<_>>();
+				let b_orig = (0..n).collect::<Vec<_>>();
+
+				let mut expected = a_orig.iter().copied().zip(b_orig.iter().copied()).collect::<Vec<_>>();
+				expected.sort_by_key(|&(a, _)| a);
+
+				let a = &mut *a_orig.clone();
+				let b = &mut *b_orig.clone();
+				let mut mem = dyn_stack::MemBuffer::new(sort_indices_stable_scratch::<u32, usize>(n));
+				unsafe { sort_indices_stable(a, b, MemStack::new(&mut mem)) };
+
+				assert!(a.iter().copied().zip(b.iter().copied()).collect::<Vec<_>>() == expected);
+			}
+		}
+	}
+
+	#[test]
+	fn test_sort_indices3() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 1000;
+
+		let row_orig = (0..n).map(|_| rng.gen_range(0..8u32)).collect::<Vec<_>>();
+		let col_orig = (0..n).map(|_| rng.gen_range(0..8u32)).collect::<Vec<_>>();
+		let val_orig = (0..n).collect::<Vec<_>>();
+
+		let row = &mut *row_orig.clone();
+		let col = &mut *col_orig.clone();
+		let val = &mut *val_orig.clone();
+		unsafe { sort_indices3(col, row, val) };
+
+		for k in 0..n {
+			assert!(all(row_orig[val[k]] == row[k], col_orig[val[k]] == col[k]));
+			if k > 0 {
+				assert!((col[k - 1], row[k - 1]) <= (col[k], row[k]));
+			}
+		}
+
+		// four arrays, sorted stably by the first one
+		let a = &mut *row_orig.clone();
+		let b = &mut *col_orig.clone();
+		let c = &mut *val_orig.iter().map(|&k| k as f64).collect::<Vec<_>>();
+		let d = &mut *val_orig.clone();
+		let mut mem = dyn_stack::MemBuffer::new(sort_stable_scratch::<(*mut u32, *mut u32, *mut f64, *mut usize)>(n));
+		unsafe {
+			sort_stable_by(
+				(a.as_mut_ptr(), b.as_mut_ptr(), c.as_mut_ptr(), d.as_mut_ptr()),
+				n,
+				|p, q| (*p.0).cmp(&*q.0),
+				MemStack::new(&mut mem),
+			)
+		};
+		for k in 0..n {
+			assert!(all(a[k] == row_orig[d[k]], b[k] == col_orig[d[k]], c[k] == d[k] as f64));
+			if k > 0 {
+				assert!(a[k - 1] < a[k] || (a[k - 1] == a[k] && d[k - 1] < d[k]));
+			}
+		}
+	}
+
+	#[test]
+	fn test_select_nth() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [1, 5, 16, 17, 100, 10_000] {
+			for nkeys in [1u32, 4, u32::MAX] {
+				let a_orig = (0..n).map(|_| rng.gen_range(0..nkeys)).collect::<Vec<_>>();
+				let mut sorted = a_orig.clone();
+				sorted.sort_unstable();
+
+				for index in [0, n / 3, n / 2, n - 1] {
+					let a = &mut *a_orig.clone();
+					let b = &mut *(0..n).collect::<Vec<_>>();
+					unsafe { select_nth_unstable_by((a.as_mut_ptr(), b.as_mut_ptr()), n, index, |p, q| (*p.0).cmp(&*q.0)) };
+
+					assert!(a[index] == sorted[index]);
+					assert!(a[..index].iter().all(|&x| x <= a[index]));
+					assert!(a[index..].iter().all(|&x| x >= a[index]));
+					assert!((0..n).all(|i| a_orig[b[i]] == a[i]));
+				}
+
+				for k in [0, 1, n / 2, n, n + 1] {
+					let a = &mut *a_orig.clone();
+					// the k largest elements
+					unsafe { top_k_by(a.as_mut_ptr(), n, k, |p, q| (*q).cmp(&*p)) };
+
+					let k = Ord::min(k, n);
+					assert!(a[..k].iter().copied().eq(sorted.iter().rev().take(k).copied()));
+				}
+			}
+		}
+	}
 }
@@ -0,0 +1,375 @@
+//! parallel assembly of sparse matrices from triplets
+//!
+//! finite element and finite volume discretizations produce their matrices as a large number of
+//! `(row, col, value)` contributions, many of which target the same entry. a [`TripletAccumulator`]
+//! owns one [`TripletBuffer`] per worker, so that the contributions can be generated concurrently
+//! without any synchronization. the buffers are only merged when the matrix is built: the triplets
+//! are sorted with a parallel quicksort, after which the values of duplicate entries are summed
+//!
+//! # example
+//! ```
+//! use faer::Par;
+//! use faer::sparse::assembly::TripletAccumulator;
+//!
+//! // one dimensional laplacian, assembled from the 2x2 element matrices
+//! let n_elems = 100;
+//! let mut acc = TripletAccumulator::<usize, f64>::new(n_elems + 1, n_elems + 1);
+//! acc.for_each(n_elems, Par::Seq, |e, buf| {
+//!     for (i, j, v) in [(e, e, 1.0), (e, e + 1, -1.0), (e + 1, e, -1.0), (e + 1, e + 1, 1.0)] {
+//!         buf.push(i, j, v);
+//!     }
+//! });
+//!
+//! let A = acc.try_to_col_major(Par::Seq).unwrap();
+//! assert!(A.compute_nnz() == 3 * n_elems + 1);
+//! assert!(A[(1, 1)] == 2.0);
+//! assert!(A[(0, 0)] == 1.0);
+//! ```
+
+use crate::internal_prelude_sp::*;
+use crate::utils::thread::{join_raw, parallelism_degree};
+use crate::{assert, sparse::try_zeroed};
+use core::ops::Range;
+
+/// buffer of `(row, col, value)` contributions to a sparse matrix, filled by a single worker
+#[derive(Debug, Clone)]
+pub struct TripletBuffer<I, T> {
+	nrows: usize,
+	ncols: usize,
+	row: alloc::vec::Vec<I>,
+	col: alloc::vec::Vec<I>,
+	val: alloc::vec::Vec<T>,
+}
+
+/// accumulator of `(row, col, value)` contributions to a sparse matrix, split into independent
+/// buffers that can be filled concurrently
+///
+/// the values of the contributions to the same entry are summed when the matrix is built
+#[derive(Debug, Clone)]
+pub struct TripletAccumulator<I, T> {
+	nrows: usize,
+	ncols: usize,
+	buffers: alloc::vec::Vec<TripletBuffer<I, T>>,
+}
+
+/// compressed storage of the merged contributions
+struct Merged<I, T> {
+	/// start of each major index
+	ptr: alloc::vec::Vec<I>,
+	/// minor indices
+	idx: alloc::vec::Vec<I>,
+	/// values
+	val: alloc::vec::Vec<T>,
+}
+
+impl<I: Index, T> TripletBuffer<I, T> {
+	/// returns an empty buffer for a matrix with the given dimensions
+	///
+	/// # panics
+	/// panics if the dimensions can't be represented by the signed counterpart of `I`
+	#[track_caller]
+	pub fn new(nrows: usize, ncols: usize) -> Self {
+		assert!(all(nrows <= I::Signed::MAX.zx(), ncols <= I::Signed::MAX.zx()));
+		Self {
+			nrows,
+			ncols,
+			row: alloc::vec::Vec::new(),
+			col: alloc::vec::Vec::new(),
+			val: alloc::vec::Vec::new(),
+		}
+	}
+
+	/// returns the number of rows of the matrix
+	#[inline]
+	pub fn nrows(&self) -> usize {
+		self.nrows
+	}
+
+	/// returns the number of columns of the matrix
+	#[inline]
+	pub fn ncols(&self) -> usize {
+		self.ncols
+	}
+
+	/// returns the number of contributions in the buffer
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.val.len()
+	}
+
+	/// returns `true` if the buffer contains no contributions
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.val.is_empty()
+	}
+
+	/// reserves capacity for at least `additional` more contributions
+	#[inline]
+	pub fn reserve(&mut self, additional: usize) {
+		self.row.reserve(additional);
+		self.col.reserve(additional);
+		self.val.reserve(additional);
+	}
+
+	/// removes all the contributions, keeping the allocated capacity
+	#[inline]
+	pub fn clear(&mut self) {
+		self.row.clear();
+		self.col.clear();
+		self.val.clear();
+	}
+
+	/// adds `val` to the entry at position `(row, col)`
+	///
+	/// # panics
+	/// panics if `row >= self.nrows()` or `col >= self.ncols()`
+	#[inline]
+	#[track_caller]
+	pub fn push(&mut self, row: usize, col: usize, val: T) {
+		assert!(all(row < self.nrows, col < self.ncols));
+		self.row.push(I::truncate(row));
+		self.col.push(I::truncate(col));
+		self.val.push(val);
+	}
+}
+
+impl<I: Index, T> TripletAccumulator<I, T> {
+	/// returns an empty accumulator for a matrix with the given dimensions
+	///
+	/// # panics
+	/// panics if the dimensions can't be represented by the signed counterpart of `I`
+	#[track_caller]
+	pub fn new(nrows: usize, ncols: usize) -> Self {
+		assert!(all(nrows <= I::Signed::MAX.zx(), ncols <= I::Signed::MAX.zx()));
+		Self {
+			nrows,
+			ncols,
+			buffers: alloc::vec::Vec::new(),
+		}
+	}
+
+	/// returns the number of rows of the matrix
+	#[inline]
+	pub fn nrows(&self) -> usize {
+		self.nrows
+	}
+
+	/// returns the number of columns of the matrix
+	#[inline]
+	pub fn ncols(&self) -> usize {
+		self.ncols
+	}
+
+	/// returns the total number of contributions in all the buffers
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.buffers.iter().map(TripletBuffer::len).sum()
+	}
+
+	/// returns `true` if the buffers contain no contributions
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.buffers.iter().all(TripletBuffer::is_empty)
+	}
+
+	/// removes all the contributions, keeping the buffers and their allocated capacity
+	#[inline]
+	pub fn clear(&mut self) {
+		for buffer in &mut self.buffers {
+			buffer.clear();
+		}
+	}
+
+	/// returns `count` buffers, creating the missing ones if needed
+	///
+	/// each buffer can be filled by a different thread, and keeps its contributions until the
+	/// accumulator is cleared
+	pub fn buffers_mut(&mut self, count: usize) -> &mut [TripletBuffer<I, T>] {
+		while self.buffers.len() < count {
+			self.buffers.push(TripletBuffer::new(self.nrows, self.ncols));
+		}
+		&mut self.buffers[..count]
+	}
+
+	/// adds a buffer that was filled independently of the accumulator, e.g., as the result of a
+	/// parallel fold
+	///
+	/// # panics
+	/// panics if the dimensions of the buffer don't match those of the accumulator
+	#[track_caller]
+	pub fn push_buffer(&mut self, buffer: TripletBuffer<I, T>) {
+		assert!(all(buffer.nrows == self.nrows, buffer.ncols == self.ncols));
+		self.buffers.push(buffer);
+	}
+
+	/// calls `f(task, buffer)` for every task in `0..n_tasks`, using the given parallelism
+	///
+	/// the tasks are split into contiguous ranges, one for each thread, and all the tasks of the same
+	/// range push their contributions to the same buffer
+	pub fn for_each(&mut self, n_tasks: usize, par: Par, f: impl Sync + Fn(usize, &mut TripletBuffer<I, T>))
+	where
+		T: Send,
+	{
+		fn imp<I: Index, T: Send>(
+			buffers: &mut [TripletBuffer<I, T>],
+			tasks: Range<usize>,
+			f: &(dyn Sync + Fn(usize, &mut TripletBuffer<I, T>)),
+			par: Par,
+		) {
+			if let [buffer] = buffers {
+				for task in tasks {
+					f(task, buffer);
+				}
+				return;
+			}
+
+			let mid = buffers.len() / 2;
+			let task_mid = tasks.start + tasks.len() * mid / buffers.len();
+			let (left, right) = buffers.split_at_mut(mid);
+			join_raw(
+				|par| imp(left, tasks.start..task_mid, f, par),
+				|par| imp(right, task_mid..tasks.end, f, par),
+				par,
+			);
+		}
+
+		let count = Ord::max(Ord::min(parallelism_degree(par), n_tasks), 1);
+		imp(self.buffers_mut(count), 0..n_tasks, &f, par);
+	}
+
+	/// merges the buffers, sorted by `(major, minor)`, and sums the duplicate entries
+	///
+	fn merge(&self, row_major: bool, par: Par) -> Result<Merged<I, T>, FaerError>
+	where
+		T: ComplexField,
+	{
+		let len = self.len();
+		if len > I::Signed::MAX.zx() {
+			return Err(FaerError::IndexOverflow);
+		}
+		let n_major = if row_major { self.nrows } else { self.ncols };
+
+		let mut major = alloc::vec::Vec::new();
+		let mut minor = alloc::vec::Vec::new();
+		let mut val = alloc::vec::Vec::new();
+		major.try_reserve_exact(len)?;
+		minor.try_reserve_exact(len)?;
+		val.try_reserve_exact(len)?;
+		for buffer in &self.buffers {
+			let (maj, min) = if row_major {
+				(&buffer.row, &buffer.col)
+			} else {
+				(&buffer.col, &buffer.row)
+			};
+			major.extend_from_slice(maj);
+			minor.extend_from_slice(min);
+			val.extend_from_slice(&buffer.val);
+		}
+
+		unsafe { crate::sort::par_sort_indices3(&mut major, &mut minor, &mut val, par) };
+
+		// sum the duplicates, which are now adjacent
+		let mut nnz = 0usize;
+		for k in 0..len {
+			if nnz > 0 && major[k] == major[nnz - 1] && minor[k] == minor[nnz - 1] {
+				val[nnz - 1] = add(&val[nnz - 1], &val[k]);
+			} else {
+				major[nnz] = major[k];
+				minor[nnz] = minor[k];
+				val.swap(nnz, k);
+				nnz += 1;
+			}
+		}
+		minor.truncate(nnz);
+		val.truncate(nnz);
+
+		let mut ptr = try_zeroed::<I>(n_major + 1)?;
+		let mut k = 0usize;
+		for (j, ptr) in ptr[..n_major].iter_mut().enumerate() {
+			*ptr = I::truncate(k);
+			while k < nnz && major[k].zx() == j {
+				k += 1;
+			}
+		}
+		ptr[n_major] = I::truncate(nnz);
+
+		Ok(Merged { ptr, idx: minor, val })
+	}
+
+	/// builds the sparse column-major matrix, summing the contributions to the same entry
+	pub fn try_to_col_major(&self, par: Par) -> Result<SparseColMat<I, T>, FaerError>
+	where
+		T: ComplexField,
+	{
+		let Merged { ptr: col_ptr, idx: row_idx, val } = self.merge(false, par)?;
+		Ok(SparseColMat::new(
+			unsafe { SymbolicSparseColMat::new_unchecked(self.nrows, self.ncols, col_ptr, None, row_idx) },
+			val,
+		))
+	}
+
+	/// builds the sparse row-major matrix, summing the contributions to the same entry
+	pub fn try_to_row_major(&self, par: Par) -> Result<SparseRowMat<I, T>, FaerError>
+	where
+		T: ComplexField,
+	{
+		let Merged { ptr: row_ptr, idx: col_idx, val } = self.merge(true, par)?;
+		Ok(SparseRowMat::new(
+			unsafe { SymbolicSparseRowMat::new_unchecked(self.nrows, self.ncols, row_ptr, None, col_idx) },
+			val,
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use rand::rngs::StdRng;
+	use rand::{Rng, SeedableRng};
+
+	#[test]
+	fn test_triplet_accumulator() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n) = (70, 50);
+
+		// few distinct positions, so that most of them are duplicated
+		let triplets = (0..20_000)
+			.map(|_| Triplet::new(rng.gen_range(0..m), rng.gen_range(0..n), rng.gen_range(-4..4) as f64))
+			.collect::<alloc::vec::Vec<_>>();
+		let expected = SparseColMat::<usize, f64>::try_new_from_triplets(m, n, &triplets).unwrap();
+
+		for par in [Par::Seq, Par::rayon(4)] {
+			let mut acc = TripletAccumulator::<usize, f64>::new(m, n);
+			acc.for_each(triplets.len(), par, |k, buf| {
+				let t = &triplets[k];
+				buf.push(t.row, t.col, t.val);
+			});
+			assert!(acc.len() == triplets.len());
+
+			let A = acc.try_to_col_major(par).unwrap();
+			assert!(A.symbolic().col_ptr() == expected.symbolic().col_ptr());
+			assert!(A.symbolic().row_idx() == expected.symbolic().row_idx());
+			assert!(A.val() == expected.val());
+
+			let A = acc.try_to_row_major(par).unwrap();
+			assert!(A.to_dense() == expected.to_dense());
+		}
+
+		// buffers filled independently
+		let mut acc = TripletAccumulator::<u32, f64>::new(m, n);
+		for chunk in triplets.chunks(3000) {
+			let mut buf = TripletBuffer::new(m, n);
+			for t in chunk {
+				buf.push(t.row, t.col, t.val);
+			}
+			acc.push_buffer(buf);
+		}
+		assert!(acc.try_to_col_major(Par::Seq).unwrap().to_dense() == expected.to_dense());
+
+		acc.clear();
+		assert!(acc.is_empty());
+		let A = acc.try_to_col_major(Par::Seq).unwrap();
+		assert!(A.compute_nnz() == 0);
+	}
+}
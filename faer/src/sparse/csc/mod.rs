@@ -2068,6 +2068,119 @@ impl<Rows: Shape, Cols: Shape, I: Index, T> SparseColMat<I, T, Rows, Cols> {
 		)?)
 	}
 
+	/// returns a new identity matrix, with ones on the diagonal and zeros everywhere else
+	///
+	/// only the diagonal entries are stored
+	#[track_caller]
+	pub fn try_identity(nrows: Rows, ncols: Cols) -> Result<Self, FaerError>
+	where
+		T: ComplexField,
+	{
+		let (m, n) = (nrows.unbound(), ncols.unbound());
+		if Ord::max(m, n) > I::Signed::MAX.zx() {
+			return Err(FaerError::IndexOverflow);
+		}
+		let len = Ord::min(m, n);
+
+		let col_ptr = try_collect((0..n + 1).map(|j| I::truncate(Ord::min(j, len))))?;
+		let row_idx = try_collect((0..len).map(I::truncate))?;
+		let val = try_collect((0..len).map(|_| T::one_impl()))?;
+
+		Ok(Self::new(
+			unsafe { SymbolicSparseColMat::new_unchecked(nrows, ncols, col_ptr, None, row_idx) },
+			val,
+		))
+	}
+
+	/// returns a new matrix with the given diagonals, and zeros everywhere else
+	///
+	/// each diagonal is given by its offset $k$ and its values, such that the $t$-th value is
+	/// stored at the position $(t, t + k)$ if $k \geq 0$, or $(t - k, t)$ otherwise. positive
+	/// offsets refer to the diagonals above the main diagonal, and negative offsets to the ones
+	/// below it
+	///
+	/// # panics
+	/// panics if an offset is repeated, or if the length of a diagonal doesn't match the number of
+	/// entries it has in a matrix with the given dimensions
+	///
+	/// # example
+	/// ```
+	/// use faer::col;
+	/// use faer::sparse::SparseColMat;
+	///
+	/// // one dimensional laplacian
+	/// let A = SparseColMat::<usize, f64>::try_from_diagonals(
+	/// 	4,
+	/// 	4,
+	/// 	&[
+	/// 		(-1, col![-1.0, -1.0, -1.0].as_ref()),
+	/// 		(0, col![2.0, 2.0, 2.0, 2.0].as_ref()),
+	/// 		(1, col![-1.0, -1.0, -1.0].as_ref()),
+	/// 	],
+	/// )
+	/// .unwrap();
+	///
+	/// assert!(A.compute_nnz() == 10);
+	/// assert!(A[(2, 1)] == -1.0);
+	/// assert!(A[(2, 2)] == 2.0);
+	/// ```
+	#[track_caller]
+	pub fn try_from_diagonals(nrows: Rows, ncols: Cols, diags: &[(isize, ColRef<'_, T>)]) -> Result<Self, FaerError>
+	where
+		T: ComplexField,
+	{
+		let (m, n) = (nrows.unbound(), ncols.unbound());
+		if Ord::max(m, n) > I::Signed::MAX.zx() {
+			return Err(FaerError::IndexOverflow);
+		}
+
+		// number of entries of the diagonal with offset `k`
+		let diag_len = |k: isize| {
+			if k >= 0 {
+				Ord::min(m, n.saturating_sub(k.unsigned_abs()))
+			} else {
+				Ord::min(m.saturating_sub(k.unsigned_abs()), n)
+			}
+		};
+
+		// the diagonals are sorted by decreasing offset, so that the row indices are sorted
+		let mut order = try_collect(0..diags.len())?;
+		order.sort_unstable_by_key(|&d| core::cmp::Reverse(diags[d].0));
+		for w in order.windows(2) {
+			assert!(diags[w[0]].0 != diags[w[1]].0);
+		}
+		let mut nnz = 0usize;
+		for &(k, ref diag) in diags {
+			assert!(diag.nrows() == diag_len(k));
+			nnz += diag.nrows();
+		}
+
+		let mut col_ptr = try_zeroed::<I>(n + 1)?;
+		let mut row_idx = alloc::vec::Vec::new();
+		let mut val = alloc::vec::Vec::new();
+		row_idx.try_reserve_exact(nnz)?;
+		val.try_reserve_exact(nnz)?;
+
+		for j in 0..n {
+			for &d in &order {
+				let (k, diag) = diags[d];
+				// row of the entry of the diagonal `k` in the column `j`, if any
+				let i = j as isize - k;
+				if i >= 0 && (i as usize) < m {
+					let t = if k >= 0 { i as usize } else { j };
+					row_idx.push(I::truncate(i as usize));
+					val.push(diag[t].clone());
+				}
+			}
+			col_ptr[j + 1] = I::truncate(row_idx.len());
+		}
+
+		Ok(Self::new(
+			unsafe { SymbolicSparseColMat::new_unchecked(nrows, ncols, col_ptr, None, row_idx) },
+			val,
+		))
+	}
+
 	/// see [`SparseColMatRef::get`]
 	#[track_caller]
 	#[inline]
@@ -0,0 +1,536 @@
+//! graph algorithms on the sparsity pattern of a matrix
+//!
+//! a sparse matrix can be seen as the adjacency matrix of a graph, or as a bipartite graph between
+//! its rows and its columns. this module provides:
+//! - [`laplacian`], which builds the (normalized) laplacian of a weighted graph,
+//! - [`connected_components`], which finds the connected components of the graph of $A + A^\top$,
+//!   and the permutation $P$ such that $P A P^\top$ is block diagonal,
+//! - [`maximum_transversal`], which finds a maximum matching between the rows and the columns of
+//!   $A$, and a row permutation $P$ such that $P A$ has a zero-free diagonal if $A$ is structurally
+//!   nonsingular. this is typically applied before a sparse $LU$ factorization without pivoting
+//!
+//! # example
+//! ```
+//! use faer::sparse::graph::maximum_transversal;
+//! use faer::sparse::{SparseColMat, Triplet};
+//!
+//! // the diagonal of the matrix is zero
+//! let A = SparseColMat::<usize, f64>::try_new_from_triplets(
+//! 	3,
+//! 	3,
+//! 	&[
+//! 		Triplet::new(1, 0, 1.0),
+//! 		Triplet::new(2, 0, 2.0),
+//! 		Triplet::new(0, 1, 3.0),
+//! 		Triplet::new(0, 2, 4.0),
+//! 		Triplet::new(1, 2, 5.0),
+//! 	],
+//! )
+//! .unwrap();
+//!
+//! let matching = maximum_transversal(A.symbolic()).unwrap();
+//! assert!(matching.structural_rank() == 3);
+//!
+//! // the diagonal of P A is zero-free
+//! let (fwd, _) = matching.row_perm().arrays();
+//! for j in 0..3 {
+//! 	assert!(A.get(fwd[j], j).is_some());
+//! }
+//! ```
+
+use crate::assert;
+use crate::internal_prelude_sp::*;
+use crate::sparse::ordering::Adjacency;
+use alloc::vec::Vec;
+
+/// kind of graph laplacian computed by [`laplacian`]
+///
+/// $W$ denotes the weighted adjacency matrix, and $D$ the diagonal matrix of the weighted degrees
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum LaplacianKind {
+	/// $L = D - W$
+	#[default]
+	Combinatorial,
+	/// $L = I - D^{-1/2} W D^{-1/2}$
+	Normalized,
+	/// $L = I - D^{-1} W$
+	RandomWalk,
+}
+
+/// connected components of a graph, computed by [`connected_components`]
+#[derive(Debug, Clone)]
+pub struct Components<I: Index> {
+	id: Vec<I>,
+	ptr: Vec<usize>,
+	perm: Perm<I>,
+}
+
+impl<I: Index> Components<I> {
+	/// returns the number of connected components
+	#[inline]
+	pub fn count(&self) -> usize {
+		self.ptr.len() - 1
+	}
+
+	/// returns the component of each node
+	///
+	/// the components are numbered in increasing order of their smallest node
+	#[inline]
+	pub fn component_id(&self) -> &[I] {
+		&self.id
+	}
+
+	/// returns the start of each component in the permuted order, with the last element being
+	/// equal to the number of nodes
+	///
+	/// the nodes of the `k`-th component are `perm.arrays().0[ptr[k]..ptr[k + 1]]`
+	#[inline]
+	pub fn component_ptr(&self) -> &[usize] {
+		&self.ptr
+	}
+
+	/// returns the permutation $P$ that groups the nodes of each component, so that $P A P^\top$ is
+	/// block diagonal
+	///
+	/// the nodes of each component keep their relative order
+	#[inline]
+	pub fn perm(&self) -> PermRef<'_, I> {
+		self.perm.as_ref()
+	}
+}
+
+/// maximum matching between the rows and the columns of a matrix, computed by
+/// [`maximum_transversal`]
+#[derive(Debug, Clone)]
+pub struct Matching<I: Index> {
+	col_to_row: Vec<usize>,
+	rank: usize,
+	row_perm: Perm<I>,
+}
+
+impl<I: Index> Matching<I> {
+	/// returns the size of the matching, which is the structural rank of the matrix
+	#[inline]
+	pub fn structural_rank(&self) -> usize {
+		self.rank
+	}
+
+	/// returns the row matched with the column `j`, if any
+	#[inline]
+	#[track_caller]
+	pub fn matched_row(&self, j: usize) -> Option<usize> {
+		let i = self.col_to_row[j];
+		if i == NONE { None } else { Some(i) }
+	}
+
+	/// returns the row permutation $P$ such that $(P A)_{jj}$ is structurally nonzero for every
+	/// matched column $j < m$, where $m$ is the number of rows
+	///
+	/// the unmatched rows fill the remaining positions in increasing order
+	#[inline]
+	pub fn row_perm(&self) -> PermRef<'_, I> {
+		self.row_perm.as_ref()
+	}
+}
+
+/// computes the laplacian of the graph with the weighted adjacency matrix $W$
+///
+/// the diagonal of $W$ is ignored, and both of its triangular halves must be provided. nodes with
+/// a zero degree have a zero row and column in the normalized laplacians
+///
+/// # panics
+/// panics if $W$ is not square
+#[track_caller]
+#[math]
+pub fn laplacian<I: Index, T: RealField>(W: SparseColMatRef<'_, I, T>, kind: LaplacianKind) -> Result<SparseColMat<I, T>, FaerError> {
+	assert!(W.nrows() == W.ncols());
+	let n = W.ncols();
+
+	let mut degree = try_collect((0..n).map(|_| zero::<T>()))?;
+	let mut nnz = 0usize;
+	for j in 0..n {
+		for (i, w) in iter::zip(W.row_idx_of_col(j), W.val_of_col(j)) {
+			if i != j {
+				degree[j] = degree[j] + *w;
+				nnz += 1;
+			}
+		}
+	}
+	nnz = nnz.checked_add(n).ok_or(FaerError::IndexOverflow)?;
+	if nnz > I::Signed::MAX.zx() {
+		return Err(FaerError::IndexOverflow);
+	}
+
+	// scaling applied to the rows and the columns of -W
+	let inv = |d: &T| if *d == zero::<T>() { zero::<T>() } else { recip(*d) };
+	let (row_scale, col_scale) = match kind {
+		LaplacianKind::Combinatorial => (try_collect((0..n).map(|_| one::<T>()))?, try_collect((0..n).map(|_| one::<T>()))?),
+		LaplacianKind::Normalized => {
+			let s = try_collect(degree.iter().map(|d| sqrt(inv(d))))?;
+			(try_collect(s.iter().cloned())?, s)
+		},
+		LaplacianKind::RandomWalk => (try_collect(degree.iter().map(inv))?, try_collect((0..n).map(|_| one::<T>()))?),
+	};
+
+	let mut col_ptr = try_zeroed::<I>(n + 1)?;
+	let mut row_idx = Vec::new();
+	let mut val = Vec::new();
+	row_idx.try_reserve_exact(nnz)?;
+	val.try_reserve_exact(nnz)?;
+
+	let mut col = Vec::<(usize, T)>::new();
+	for j in 0..n {
+		col.clear();
+		let diag = match kind {
+			LaplacianKind::Combinatorial => copy(degree[j]),
+			_ if degree[j] == zero::<T>() => zero::<T>(),
+			_ => one::<T>(),
+		};
+		col.try_reserve(W.row_idx_of_col_raw(j).len() + 1)?;
+		col.push((j, diag));
+		for (i, w) in iter::zip(W.row_idx_of_col(j), W.val_of_col(j)) {
+			if i != j {
+				col.push((i, -(row_scale[i] * *w * col_scale[j])));
+			}
+		}
+		col.sort_unstable_by_key(|(i, _)| *i);
+
+		for (i, v) in col.drain(..) {
+			row_idx.push(I::truncate(i));
+			val.push(v);
+		}
+		col_ptr[j + 1] = I::truncate(row_idx.len());
+	}
+
+	Ok(SparseColMat::new(
+		unsafe { SymbolicSparseColMat::new_unchecked(n, n, col_ptr, None, row_idx) },
+		val,
+	))
+}
+
+/// computes the connected components of the graph of $A + A^\top$
+///
+/// # panics
+/// panics if $A$ is not square
+#[track_caller]
+pub fn connected_components<I: Index>(A: SymbolicSparseColMatRef<'_, I>) -> Result<Components<I>, FaerError> {
+	let adj = Adjacency::new(A)?;
+	let n = adj.n();
+
+	let mut id = try_collect((0..n).map(|_| NONE))?;
+	let mut queue = Vec::new();
+	queue.try_reserve_exact(n)?;
+	let mut count = 0usize;
+
+	for root in 0..n {
+		if id[root] != NONE {
+			continue;
+		}
+		queue.clear();
+		queue.push(root);
+		id[root] = count;
+
+		let mut head = 0;
+		while head < queue.len() {
+			let i = queue[head];
+			head += 1;
+			for &j in adj.neighbors(i) {
+				if id[j] == NONE {
+					id[j] = count;
+					queue.push(j);
+				}
+			}
+		}
+		count += 1;
+	}
+
+	// counting sort of the nodes by component, which preserves their relative order
+	let mut ptr = try_zeroed::<usize>(count + 1)?;
+	for &c in &id {
+		ptr[c + 1] += 1;
+	}
+	for c in 0..count {
+		ptr[c + 1] += ptr[c];
+	}
+	let mut pos = try_collect(ptr[..count].iter().copied())?;
+	let mut fwd = try_zeroed::<I>(n)?;
+	let mut inv = try_zeroed::<I>(n)?;
+	for (i, &c) in id.iter().enumerate() {
+		fwd[pos[c]] = I::truncate(i);
+		inv[i] = I::truncate(pos[c]);
+		pos[c] += 1;
+	}
+
+	Ok(Components {
+		id: try_collect(id.iter().map(|&c| I::truncate(c)))?,
+		ptr,
+		perm: unsafe { Perm::new_unchecked(fwd.into_boxed_slice(), inv.into_boxed_slice()) },
+	})
+}
+
+/// computes a maximum matching between the rows and the columns of $A$, with the depth first
+/// search algorithm of duff, which looks ahead for an unmatched row before extending each
+/// augmenting path
+///
+/// the size of the matching is the structural rank of $A$, i.e., the largest rank that a matrix
+/// with the same sparsity pattern can have
+#[track_caller]
+pub fn maximum_transversal<I: Index>(A: SymbolicSparseColMatRef<'_, I>) -> Result<Matching<I>, FaerError> {
+	let m = A.nrows();
+	let n = A.ncols();
+
+	let mut col_to_row = try_collect((0..n).map(|_| NONE))?;
+	let mut row_to_col = try_collect((0..m).map(|_| NONE))?;
+
+	// position of the next row to inspect in the lookahead search of each column
+	let mut cheap = try_zeroed::<usize>(n)?;
+	// last augmenting path search that visited each column
+	let mut visited = try_collect((0..n).map(|_| NONE))?;
+
+	// augmenting path, with the column, the row that is followed from it, and the position of the
+	// next row to inspect in the depth first search, for each level
+	let mut path_col = try_zeroed::<usize>(n)?;
+	let mut path_row = try_zeroed::<usize>(n)?;
+	let mut path_pos = try_zeroed::<usize>(n)?;
+
+	let mut rank = 0usize;
+	for k in 0..n {
+		path_col[0] = k;
+		let mut head = 0usize;
+		let mut found = false;
+
+		loop {
+			let j = path_col[head];
+			let rows = A.row_idx_of_col_raw(j);
+
+			if visited[j] != k {
+				visited[j] = k;
+				// lookahead for an unmatched row
+				while cheap[j] < rows.len() {
+					let i = rows[cheap[j]].zx();
+					cheap[j] += 1;
+					if row_to_col[i] == NONE {
+						path_row[head] = i;
+						found = true;
+						break;
+					}
+				}
+				if found {
+					break;
+				}
+				path_pos[head] = 0;
+			}
+
+			// extend the path through a matched row whose column wasn't visited yet
+			let mut extended = false;
+			while path_pos[head] < rows.len() {
+				let i = rows[path_pos[head]].zx();
+				path_pos[head] += 1;
+				let jj = row_to_col[i];
+				if visited[jj] != k {
+					path_row[head] = i;
+					head += 1;
+					path_col[head] = jj;
+					extended = true;
+					break;
+				}
+			}
+
+			if !extended {
+				if head == 0 {
+					break;
+				}
+				head -= 1;
+			}
+		}
+
+		if found {
+			for p in 0..head + 1 {
+				col_to_row[path_col[p]] = path_row[p];
+				row_to_col[path_row[p]] = path_col[p];
+			}
+			rank += 1;
+		}
+	}
+
+	let mut fwd = try_collect((0..m).map(|_| NONE))?;
+	for j in 0..Ord::min(m, n) {
+		fwd[j] = col_to_row[j];
+	}
+	let mut placed = try_zeroed::<u8>(m)?;
+	for &i in &fwd {
+		if i != NONE {
+			placed[i] = 1;
+		}
+	}
+	let mut free = (0..m).filter(|&i| placed[i] == 0);
+	for i in &mut fwd {
+		if *i == NONE {
+			*i = free.next().unwrap();
+		}
+	}
+
+	let mut inv = try_zeroed::<I>(m)?;
+	for (k, &i) in fwd.iter().enumerate() {
+		inv[i] = I::truncate(k);
+	}
+	let fwd = try_collect(fwd.iter().map(|&i| I::truncate(i)))?;
+
+	Ok(Matching {
+		col_to_row,
+		rank,
+		row_perm: unsafe { Perm::new_unchecked(fwd.into_boxed_slice(), inv.into_boxed_slice()) },
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::utils::approx::*;
+	use rand::rngs::StdRng;
+	use rand::seq::SliceRandom;
+	use rand::{Rng, SeedableRng};
+
+	#[test]
+	fn test_laplacian() {
+		// weighted triangle, with an isolated node and a self loop
+		let W = SparseColMat::<usize, f64>::try_new_from_triplets(
+			4,
+			4,
+			&[
+				Triplet::new(0, 1, 1.0),
+				Triplet::new(1, 0, 1.0),
+				Triplet::new(1, 2, 2.0),
+				Triplet::new(2, 1, 2.0),
+				Triplet::new(0, 2, 3.0),
+				Triplet::new(2, 0, 3.0),
+				Triplet::new(1, 1, 5.0),
+			],
+		)
+		.unwrap();
+		let d = [4.0, 3.0, 5.0, 0.0f64];
+		let w = W.to_dense();
+		let approx_eq = CwiseMat(ApproxEq::<f64>::eps() * 8.0);
+
+		let L = laplacian(W.rb(), LaplacianKind::Combinatorial).unwrap().to_dense();
+		let expected = Mat::from_fn(4, 4, |i, j| if i == j { d[i] } else { -w[(i, j)] });
+		assert!(L ~ expected);
+
+		let L = laplacian(W.rb(), LaplacianKind::Normalized).unwrap().to_dense();
+		let expected = Mat::from_fn(4, 4, |i, j| {
+			if i == 3 || j == 3 {
+				0.0
+			} else if i == j {
+				1.0
+			} else {
+				-w[(i, j)] / (d[i] * d[j]).sqrt()
+			}
+		});
+		assert!(L ~ expected);
+
+		let L = laplacian(W.rb(), LaplacianKind::RandomWalk).unwrap().to_dense();
+		let expected = Mat::from_fn(4, 4, |i, j| {
+			if i == 3 || j == 3 {
+				0.0
+			} else if i == j {
+				1.0
+			} else {
+				-w[(i, j)] / d[i]
+			}
+		});
+		assert!(L ~ expected);
+	}
+
+	#[test]
+	fn test_connected_components() {
+		// {0, 3, 5}, {1, 4}, {2}
+		let edges = [(0, 5), (3, 5), (4, 1)];
+		let mut triplets = edges.iter().map(|&(i, j)| Triplet::new(i, j, 1.0)).collect::<Vec<_>>();
+		triplets.push(Triplet::new(2, 2, 1.0));
+		let A = SparseColMat::<u32, f64>::try_new_from_triplets(6, 6, &triplets).unwrap();
+
+		let components = connected_components(A.symbolic()).unwrap();
+		assert!(components.count() == 3);
+		assert!(components.component_id() == &[0, 1, 2, 0, 1, 0]);
+		assert!(components.component_ptr() == &[0, 3, 5, 6]);
+
+		let (fwd, inv) = components.perm().arrays();
+		assert!(fwd == &[0, 3, 5, 1, 4, 2]);
+		PermRef::new_checked(fwd, inv, 6);
+
+		// P A P^T is block diagonal
+		let ptr = components.component_ptr();
+		let block = |k: usize| ptr.iter().rposition(|&p| p <= k).unwrap();
+		for j in 0..6 {
+			for i in A.row_idx_of_col(j) {
+				assert!(block(inv[i] as usize) == block(inv[j] as usize));
+			}
+		}
+	}
+
+	#[test]
+	fn test_maximum_transversal() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 200;
+
+		// structurally nonsingular matrix, with its rows scrambled
+		let mut scramble = (0..n).collect::<Vec<_>>();
+		scramble.shuffle(rng);
+		let mut triplets = Vec::new();
+		for j in 0..n {
+			triplets.push(Triplet::new(scramble[j], j, 1.0));
+			for _ in 0..2 {
+				triplets.push(Triplet::new(rng.gen_range(0..n), j, 1.0));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+
+		let matching = maximum_transversal(A.symbolic()).unwrap();
+		assert!(matching.structural_rank() == n);
+		let (fwd, inv) = matching.row_perm().arrays();
+		PermRef::new_checked(fwd, inv, n);
+		for j in 0..n {
+			assert!(matching.matched_row(j) == Some(fwd[j]));
+			assert!(A.get(fwd[j], j).is_some());
+		}
+
+		// the first three columns only have nonzeros in the first two rows
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(
+			4,
+			4,
+			&[
+				Triplet::new(0, 0, 1.0),
+				Triplet::new(1, 0, 1.0),
+				Triplet::new(0, 1, 1.0),
+				Triplet::new(1, 2, 1.0),
+				Triplet::new(2, 3, 1.0),
+				Triplet::new(3, 3, 1.0),
+			],
+		)
+		.unwrap();
+		let matching = maximum_transversal(A.symbolic()).unwrap();
+		assert!(matching.structural_rank() == 3);
+		let (fwd, inv) = matching.row_perm().arrays();
+		PermRef::new_checked(fwd, inv, 4);
+		assert!((0..3).filter(|&j| matching.matched_row(j).is_none()).count() == 1);
+
+		// wide matrix
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(
+			2,
+			3,
+			&[
+				Triplet::new(0, 0, 1.0),
+				Triplet::new(0, 1, 1.0),
+				Triplet::new(1, 1, 1.0),
+				Triplet::new(0, 2, 1.0),
+			],
+		)
+		.unwrap();
+		let matching = maximum_transversal(A.symbolic()).unwrap();
+		assert!(matching.structural_rank() == 2);
+		assert!(matching.matched_row(1) == Some(1));
+		let (fwd, inv) = matching.row_perm().arrays();
+		PermRef::new_checked(fwd, inv, 2);
+	}
+}
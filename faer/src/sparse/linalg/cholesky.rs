@@ -1536,7 +1536,7 @@ pub mod supernodal {
 	}
 
 	/// cholesky factor structure containing its symbolic structure
-	#[derive(Debug)]
+	#[derive(Debug, Clone)]
 	pub struct SymbolicSupernodalCholesky<I> {
 		pub(crate) dimension: usize,
 		pub(crate) supernode_postorder: alloc::vec::Vec<I>,
@@ -3536,7 +3536,7 @@ pub struct CholeskySymbolicParams<'a> {
 }
 
 /// the inner factorization used for the symbolic cholesky, either simplicial or symbolic
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SymbolicCholeskyRaw<I> {
 	/// simplicial structure
 	Simplicial(simplicial::SymbolicSimplicialCholesky<I>),
@@ -3545,7 +3545,7 @@ pub enum SymbolicCholeskyRaw<I> {
 }
 
 /// the symbolic structure of a sparse cholesky decomposition
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SymbolicCholesky<I> {
 	raw: SymbolicCholeskyRaw<I>,
 	perm_fwd: Option<alloc::vec::Vec<I>>,
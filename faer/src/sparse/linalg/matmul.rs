@@ -1,5 +1,6 @@
 use crate::assert;
 use crate::internal_prelude_sp::*;
+use crate::utils::thread::{join_raw, parallelism_degree};
 use core::cell::UnsafeCell;
 
 /// info about the matrix multiplication operation to help split the workload between multiple
@@ -194,9 +195,15 @@ pub fn sparse_sparse_matmul<I: Index, T: ComplexField, LhsT: Conjugate<Canonical
 	Ok(SparseColMat::new(symbolic, val))
 }
 
+/// below this number of flops, the mixed sparse-dense products are computed sequentially
+const SPARSE_DENSE_PAR_THRESHOLD: usize = 1 << 14;
+
 /// multiplies a sparse matrix `lhs` by a dense matrix `rhs`, and stores or adds the result to
 /// `dst`. see [`faer::linalg::matmul::matmul`](crate::linalg::matmul::matmul) for more details.
 ///
+/// the columns of `dst` are split between the threads. if there are fewer columns than threads,
+/// the columns of `lhs` are split instead, and the partial products are summed
+///
 /// # note
 /// allows unsorted matrices.
 #[track_caller]
@@ -211,13 +218,59 @@ pub fn sparse_dense_matmul<I: Index, T: ComplexField, LhsT: Conjugate<Canonical
 ) {
 	assert!(all(dst.nrows() == lhs.nrows(), dst.ncols() == rhs.ncols(), lhs.ncols() == rhs.nrows()));
 
-	// TODO: parallelize this
-	let _ = par;
 	let mut dst = dst;
-
 	if let Accum::Replace = beta {
 		dst.fill(zero());
 	}
+
+	let par = if lhs.compute_nnz().saturating_mul(dst.ncols()) < SPARSE_DENSE_PAR_THRESHOLD {
+		Par::Seq
+	} else {
+		par
+	};
+	sparse_dense_matmul_imp(dst, lhs, rhs, 0..lhs.ncols(), &alpha, par);
+}
+
+/// adds the product of the columns `depth` of `lhs` by the corresponding rows of `rhs` to `dst`
+#[math]
+fn sparse_dense_matmul_imp<I: Index, T: ComplexField, LhsT: Conjugate<Canonical = T>, RhsT: Conjugate<Canonical = T>>(
+	dst: MatMut<'_, T>,
+	lhs: SparseColMatRef<'_, I, LhsT>,
+	rhs: MatRef<'_, RhsT>,
+	depth: core::ops::Range<usize>,
+	alpha: &T,
+	par: Par,
+) {
+	let mut dst = dst;
+
+	if parallelism_degree(par) > 1 {
+		if dst.ncols() > 1 {
+			let mid = dst.ncols() / 2;
+			let (dst_left, dst_right) = dst.split_at_col_mut(mid);
+			let (rhs_left, rhs_right) = rhs.split_at_col(mid);
+			let depth_ = depth.clone();
+			join_raw(
+				|par| sparse_dense_matmul_imp(dst_left, lhs, rhs_left, depth_, alpha, par),
+				|par| sparse_dense_matmul_imp(dst_right, lhs, rhs_right, depth, alpha, par),
+				par,
+			);
+			return;
+		}
+		if depth.len() > 1 {
+			// the columns of lhs update the same rows of dst, so the right half is accumulated in a
+			// separate buffer
+			let mid = depth.start + depth.len() / 2;
+			let mut tmp = Mat::<T>::zeros(dst.nrows(), dst.ncols());
+			join_raw(
+				|par| sparse_dense_matmul_imp(dst.rb_mut(), lhs, rhs, depth.start..mid, alpha, par),
+				|par| sparse_dense_matmul_imp(tmp.as_mut(), lhs, rhs, mid..depth.end, alpha, par),
+				par,
+			);
+			zip!(dst, tmp.as_ref()).for_each(|unzip!(dst, tmp)| *dst = *dst + *tmp);
+			return;
+		}
+	}
+
 	with_dim!(M, dst.nrows());
 	with_dim!(N, dst.ncols());
 	with_dim!(K, lhs.ncols());
@@ -227,8 +280,9 @@ pub fn sparse_dense_matmul<I: Index, T: ComplexField, LhsT: Conjugate<Canonical
 	let rhs = rhs.as_shape(K, N);
 
 	for j in N.indices() {
-		for depth in K.indices() {
-			let rhs_kj = Conj::apply(&rhs[(depth, j)]) * alpha;
+		for depth in depth.clone() {
+			let depth = K.idx(depth);
+			let rhs_kj = Conj::apply(&rhs[(depth, j)]) * *alpha;
 			for (i, lhs_ik) in iter::zip(lhs.row_idx_of_col(depth), lhs.val_of_col(depth)) {
 				dst[(i, j)] = dst[(i, j)] + Conj::apply(lhs_ik) * rhs_kj;
 			}
@@ -239,6 +293,9 @@ pub fn sparse_dense_matmul<I: Index, T: ComplexField, LhsT: Conjugate<Canonical
 /// multiplies a dense matrix `lhs` by a sparse matrix `rhs`, and stores or adds the result to
 /// `dst`. see [`faer::linalg::matmul::matmul`](crate::linalg::matmul::matmul) for more details.
 ///
+/// the columns of `dst` are split between the threads. if there are fewer columns than threads,
+/// the rows of `dst` are split instead
+///
 /// # note
 /// allows unsorted matrices.
 #[track_caller]
@@ -253,28 +310,71 @@ pub fn dense_sparse_matmul<I: Index, T: ComplexField, LhsT: Conjugate<Canonical
 ) {
 	assert!(all(dst.nrows() == lhs.nrows(), dst.ncols() == rhs.ncols(), lhs.ncols() == rhs.nrows()));
 
-	// TODO: parallelize this
-	let _ = par;
+	let par = if rhs.compute_nnz().saturating_mul(dst.nrows()) < SPARSE_DENSE_PAR_THRESHOLD {
+		Par::Seq
+	} else {
+		par
+	};
+	dense_sparse_matmul_imp(dst, beta, lhs, rhs, 0..rhs.ncols(), &alpha, par);
+}
+
+/// computes the product of `lhs` by the columns `cols` of `rhs`
+#[math]
+fn dense_sparse_matmul_imp<I: Index, T: ComplexField, LhsT: Conjugate<Canonical = T>, RhsT: Conjugate<Canonical = T>>(
+	dst: MatMut<'_, T>,
+	beta: Accum,
+	lhs: MatRef<'_, LhsT>,
+	rhs: SparseColMatRef<'_, I, RhsT>,
+	cols: core::ops::Range<usize>,
+	alpha: &T,
+	par: Par,
+) {
+	if parallelism_degree(par) > 1 {
+		if dst.ncols() > 1 {
+			let mid = dst.ncols() / 2;
+			let (dst_left, dst_right) = dst.split_at_col_mut(mid);
+			let mid = cols.start + mid;
+			join_raw(
+				|par| dense_sparse_matmul_imp(dst_left, beta, lhs, rhs, cols.start..mid, alpha, par),
+				|par| dense_sparse_matmul_imp(dst_right, beta, lhs, rhs, mid..cols.end, alpha, par),
+				par,
+			);
+			return;
+		}
+		if dst.nrows() > 1 {
+			let mid = dst.nrows() / 2;
+			let (dst_top, dst_bot) = dst.split_at_row_mut(mid);
+			let (lhs_top, lhs_bot) = lhs.split_at_row(mid);
+			let cols_ = cols.clone();
+			join_raw(
+				|par| dense_sparse_matmul_imp(dst_top, beta, lhs_top, rhs, cols_, alpha, par),
+				|par| dense_sparse_matmul_imp(dst_bot, beta, lhs_bot, rhs, cols, alpha, par),
+				par,
+			);
+			return;
+		}
+	}
 
 	with_dim!(M, dst.nrows());
 	with_dim!(N, dst.ncols());
 	with_dim!(K, lhs.ncols());
+	with_dim!(NR, rhs.ncols());
 
 	let mut dst = dst.as_shape_mut(M, N);
 	let lhs = lhs.as_shape(M, K);
-	let rhs = rhs.as_shape(K, N);
+	let rhs = rhs.as_shape(K, NR);
 
-	for i in M.indices() {
-		for j in N.indices() {
-			let mut acc = zero::<T>();
-			for (depth, rhs_kj) in iter::zip(rhs.row_idx_of_col(j), rhs.val_of_col(j)) {
-				let l = Conj::apply(&lhs[(i, depth)]);
-				let r = Conj::apply(rhs_kj);
-				acc = acc + l * r;
+	for (j, rhs_j) in iter::zip(N.indices(), cols) {
+		let rhs_j = NR.idx(rhs_j);
+		if let Accum::Replace = beta {
+			for i in M.indices() {
+				dst[(i, j)] = zero();
 			}
-			match beta {
-				Accum::Replace => dst[(i, j)] = alpha * acc,
-				Accum::Add => dst[(i, j)] = dst[(i, j)] + alpha * acc,
+		}
+		for (depth, rhs_kj) in iter::zip(rhs.row_idx_of_col(rhs_j), rhs.val_of_col(rhs_j)) {
+			let rhs_kj = Conj::apply(rhs_kj) * *alpha;
+			for i in M.indices() {
+				dst[(i, j)] = dst[(i, j)] + Conj::apply(&lhs[(i, depth)]) * rhs_kj;
 			}
 		}
 	}
@@ -339,4 +439,60 @@ mod tests {
 
 		assert!(c.to_dense() == Scale(2.0) * a.to_dense() * b.to_dense());
 	}
+
+	#[test]
+	fn test_sparse_dense_matmul() {
+		use crate::stats::prelude::*;
+		use crate::utils::approx::*;
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, k) = (150, 130);
+
+		let mut triplets = alloc::vec::Vec::new();
+		for _ in 0..10000 {
+			let val = c64::new(rng.gen(), rng.gen());
+			triplets.push(Triplet::new(rng.gen_range(0..m), rng.gen_range(0..k), val));
+		}
+		let A = SparseColMat::<usize, c64>::try_new_from_triplets(m, k, &triplets).unwrap();
+		let A_dense = A.to_dense();
+		let alpha = c64::new(0.5, -2.0);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e3);
+
+		for n in [1, 2, 7, 40] {
+			let mut rand = |nrows: usize, ncols: usize| {
+				CwiseMatDistribution {
+					nrows,
+					ncols,
+					dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+				}
+				.rand::<Mat<c64>>(rng)
+			};
+			let B = rand(k, n);
+			let Bt = rand(n, m);
+			let C = rand(m, n);
+			let Ct = rand(n, k);
+
+			for par in [Par::Seq, Par::rayon(4)] {
+				// dst += alpha * conj(A) * B
+				let mut dst = C.clone();
+				sparse_dense_matmul(dst.as_mut(), Accum::Add, A.conjugate(), B.as_ref(), alpha, par);
+				assert!(dst ~ &C + Scale(alpha) * A_dense.conjugate() * &B);
+
+				// dst = alpha * A * conj(B)
+				let mut dst = C.clone();
+				sparse_dense_matmul(dst.as_mut(), Accum::Replace, A.rb(), B.conjugate(), alpha, par);
+				assert!(dst ~ Scale(alpha) * &A_dense * B.conjugate());
+
+				// dst += alpha * conj(B^T) * A
+				let mut dst = Ct.clone();
+				dense_sparse_matmul(dst.as_mut(), Accum::Add, Bt.conjugate(), A.rb(), alpha, par);
+				assert!(dst ~ &Ct + Scale(alpha) * Bt.conjugate() * &A_dense);
+
+				// dst = alpha * B^T * conj(A)
+				let mut dst = Ct.clone();
+				dense_sparse_matmul(dst.as_mut(), Accum::Replace, Bt.as_ref(), A.conjugate(), alpha, par);
+				assert!(dst ~ Scale(alpha) * &Bt * A_dense.conjugate());
+			}
+		}
+	}
 }
@@ -115,6 +115,11 @@ pub mod triangular_solve;
 #[path = "../solvers.rs"]
 pub mod solvers;
 
+/// shift-and-invert eigensolver
+pub mod shift_invert;
+/// overlapping additive schwarz preconditioner
+pub mod schwarz;
+
 pub mod amd;
 pub mod colamd;
 
@@ -319,7 +319,7 @@ pub mod supernodal {
 	/// such that:
 	/// $$ Q = (i - H_1 t_1^{-1} H_1^H) \cdot (i - H_2 t_2^{-1} H_2^H) \dots (i - H_k t_k^{-1}
 	/// H_k^H)$$
-	#[derive(Debug)]
+	#[derive(Debug, Clone)]
 	pub struct SymbolicSupernodalHouseholder<I> {
 		col_ptr_for_row_idx: alloc::vec::Vec<I>,
 		col_ptr_for_tau_val: alloc::vec::Vec<I>,
@@ -382,7 +382,7 @@ pub mod supernodal {
 		}
 	}
 	/// symbolic structure of the $QR$ decomposition,
-	#[derive(Debug)]
+	#[derive(Debug, Clone)]
 	pub struct SymbolicSupernodalQr<I> {
 		L: SymbolicSupernodalCholesky<I>,
 		H: SymbolicSupernodalHouseholder<I>,
@@ -1326,7 +1326,7 @@ pub mod simplicial {
 	use crate::assert;
 
 	/// symbolic structure of the $QR$ decomposition
-	#[derive(Debug)]
+	#[derive(Debug, Clone)]
 	pub struct SymbolicSimplicialQr<I> {
 		nrows: usize,
 		ncols: usize,
@@ -1795,7 +1795,7 @@ pub struct QrSymbolicParams<'a> {
 }
 
 /// the inner factorization used for the symbolic $QR$, either simplicial or symbolic
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SymbolicQrRaw<I> {
 	/// simplicial structure
 	Simplicial(simplicial::SymbolicSimplicialQr<I>),
@@ -1804,7 +1804,7 @@ pub enum SymbolicQrRaw<I> {
 }
 
 /// the symbolic structure of a sparse $QR$ decomposition
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SymbolicQr<I> {
 	raw: SymbolicQrRaw<I>,
 	col_perm_fwd: alloc::vec::Vec<I>,
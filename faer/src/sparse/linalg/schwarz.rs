@@ -0,0 +1,533 @@
+//! overlapping additive schwarz domain decomposition preconditioner
+//!
+//! the unknowns of the system are partitioned into subdomains, which are then extended with the
+//! unknowns that are at most `overlap` edges away in the adjacency graph of $A$. the
+//! preconditioner is
+//! $$M^{-1} = \sum_i R_i^\top A_i^{-1} R_i,$$
+//! where $R_i$ restricts a vector to the $i$-th extended subdomain and $A_i = R_i A R_i^\top$ is
+//! the corresponding principal submatrix of $A$
+//!
+//! each $A_i$ is factorized independently, with a dense $LU$ decomposition for small subdomains
+//! and with a [`SparseSolver`] for the larger ones, which selects a banded ordering when the
+//! subdomain matrix is narrow banded. the subdomain solves are independent, and are executed in
+//! parallel when the preconditioner is applied with [`Par::Rayon`]
+//!
+//! the restricted variant only adds the values of each local solution that belong to the
+//! non-overlapping part of its subdomain. it usually converges faster for non-self-adjoint systems,
+//! but the preconditioner is no longer self-adjoint, so it can't be used with the conjugate
+//! gradient
+//!
+//! # example
+//! ```
+//! use dyn_stack::{MemBuffer, MemStack};
+//! use faer::matrix_free::conjugate_gradient::{
+//! 	CgParams, conjugate_gradient, conjugate_gradient_scratch,
+//! };
+//! use faer::sparse::SparseColMat;
+//! use faer::sparse::linalg::schwarz::{AdditiveSchwarz, SchwarzParams};
+//! use faer::{Mat, Par};
+//!
+//! // one dimensional laplacian
+//! let n = 1000;
+//! let A = SparseColMat::<usize, f64>::try_from_diagonals(
+//! 	n,
+//! 	n,
+//! 	&[
+//! 		(-1, Mat::full(n - 1, 1, -1.0).col(0)),
+//! 		(0, Mat::full(n, 1, 2.0).col(0)),
+//! 		(1, Mat::full(n - 1, 1, -1.0).col(0)),
+//! 	],
+//! )
+//! .unwrap();
+//! let b = Mat::<f64>::full(n, 1, 1.0);
+//!
+//! let M = AdditiveSchwarz::new(
+//! 	A.as_ref(),
+//! 	SchwarzParams {
+//! 		subdomain_size: 100,
+//! 		overlap: 4,
+//! 		..Default::default()
+//! 	},
+//! )
+//! .unwrap();
+//!
+//! let mut x = Mat::<f64>::zeros(n, 1);
+//! conjugate_gradient(
+//! 	x.as_mut(),
+//! 	&M,
+//! 	A.as_ref(),
+//! 	b.as_ref(),
+//! 	CgParams::default(),
+//! 	|_| {},
+//! 	Par::Seq,
+//! 	MemStack::new(&mut MemBuffer::new(conjugate_gradient_scratch(
+//! 		&M,
+//! 		A.as_ref(),
+//! 		1,
+//! 		Par::Seq,
+//! 	))),
+//! )
+//! .unwrap();
+//! assert!((&b - &A * &x).norm_l2() < 1e-8 * b.norm_l2());
+//! ```
+
+use crate::assert;
+use crate::internal_prelude_sp::*;
+use crate::linalg::solvers::{PartialPivLu, SolveCore};
+use crate::matrix_free::{BiLinOp, BiPrecond, LinOp, Precond};
+use alloc::vec;
+use alloc::vec::Vec;
+use linalg_sp::solvers::{SparseSolver, SparseSolverError, SparseSolverParams};
+
+/// [`AdditiveSchwarz`] parameters
+#[derive(Copy, Clone, Debug)]
+pub struct SchwarzParams {
+	/// number of unknowns of each subdomain before the overlap is added, when the partition is
+	/// computed by [`AdditiveSchwarz::new`]
+	pub subdomain_size: usize,
+	/// number of layers of neighboring unknowns added to each subdomain
+	pub overlap: usize,
+	/// whether the restricted variant of the preconditioner is used
+	pub restricted: bool,
+	/// subdomains with at most this many unknowns are factorized with a dense $LU$ decomposition
+	pub dense_threshold: usize,
+	/// parameters of the sparse factorizations of the larger subdomains
+	pub solver: SparseSolverParams,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for SchwarzParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			subdomain_size: 1024,
+			overlap: 1,
+			restricted: false,
+			dense_threshold: 64,
+			solver: SparseSolverParams::default(),
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+enum SubdomainSolver<I, T> {
+	Dense(PartialPivLu<T>),
+	Sparse(SparseSolver<I, T>),
+}
+
+#[derive(Clone, Debug)]
+struct Subdomain<I, T> {
+	// global indices of the extended subdomain, in increasing order
+	idx: Vec<usize>,
+	// whether each unknown belongs to the subdomain before it was extended
+	owned: Vec<bool>,
+	solver: SubdomainSolver<I, T>,
+}
+
+/// overlapping additive schwarz preconditioner
+#[derive(Clone, Debug)]
+pub struct AdditiveSchwarz<I, T> {
+	dim: usize,
+	restricted: bool,
+	subdomains: Vec<Subdomain<I, T>>,
+}
+
+/// returns the neighbors of each unknown in the adjacency graph of $A + A^\top$
+fn adjacency<I: Index, T>(A: SparseColMatRef<'_, I, T>) -> Vec<Vec<usize>> {
+	let n = A.ncols();
+	let mut adj = vec![Vec::new(); n];
+	for j in 0..n {
+		for i in A.row_idx_of_col(j) {
+			if i != j {
+				adj[i].push(j);
+				adj[j].push(i);
+			}
+		}
+	}
+	for a in &mut adj {
+		a.sort_unstable();
+		a.dedup();
+	}
+	adj
+}
+
+/// returns the principal submatrix of $A$ with the given sorted indices
+fn submatrix<I: Index, T: ComplexField>(A: SparseColMatRef<'_, I, T>, idx: &[usize], local: &mut [usize]) -> Result<SparseColMat<I, T>, FaerError> {
+	for (l, &i) in idx.iter().enumerate() {
+		local[i] = l;
+	}
+	let mut triplets = Vec::new();
+	for (c, &j) in idx.iter().enumerate() {
+		for (i, v) in A.row_idx_of_col(j).zip(A.val_of_col(j)) {
+			if local[i] != usize::MAX {
+				triplets.push(Triplet::new(I::truncate(local[i]), I::truncate(c), v.clone()));
+			}
+		}
+	}
+	for &i in idx {
+		local[i] = usize::MAX;
+	}
+	SparseColMat::try_new_from_triplets(idx.len(), idx.len(), &triplets).map_err(|_| FaerError::OutOfMemory)
+}
+
+impl<I: Index, T: ComplexField> AdditiveSchwarz<I, T> {
+	/// partitions the unknowns of $A$ into contiguous blocks of `params.subdomain_size` unknowns,
+	/// and factorizes the extended subdomains
+	///
+	/// this partition is suited to matrices whose unknowns are numbered so that neighboring
+	/// unknowns have close indices, e.g., after a bandwidth reducing ordering
+	///
+	/// if $A$ is self-adjoint, both of its triangular halves must be provided
+	#[track_caller]
+	pub fn new(A: SparseColMatRef<'_, I, T>, params: SchwarzParams) -> Result<Self, SparseSolverError> {
+		let size = Ord::max(params.subdomain_size, 1);
+		let partition = (0..A.nrows()).map(|i| i / size).collect::<Vec<_>>();
+		Self::new_with_partition(A, &partition, params)
+	}
+
+	/// factorizes the extended subdomains of the partition of the unknowns of $A$, where
+	/// `partition[i]` is the subdomain of the $i$-th unknown
+	///
+	/// if $A$ is self-adjoint, both of its triangular halves must be provided
+	///
+	/// # panics
+	///
+	/// panics if $A$ is not square, or if the length of `partition` is not equal to its dimension
+	#[track_caller]
+	pub fn new_with_partition(A: SparseColMatRef<'_, I, T>, partition: &[usize], params: SchwarzParams) -> Result<Self, SparseSolverError> {
+		let n = A.nrows();
+		assert!(all(A.ncols() == n, partition.len() == n));
+
+		let n_parts = partition.iter().map(|&p| p + 1).max().unwrap_or(0);
+		let mut parts = vec![Vec::new(); n_parts];
+		for (i, &p) in partition.iter().enumerate() {
+			parts[p].push(i);
+		}
+
+		let adj = if params.overlap > 0 { adjacency(A) } else { Vec::new() };
+		let mut mark = vec![usize::MAX; n];
+		let mut local = vec![usize::MAX; n];
+
+		let mut subdomains = Vec::new();
+		for (p, owned) in parts.into_iter().enumerate() {
+			if owned.is_empty() {
+				continue;
+			}
+
+			// breadth first extension of the subdomain
+			let mut idx = owned.clone();
+			for &i in &idx {
+				mark[i] = p;
+			}
+			let mut start = 0;
+			for _ in 0..params.overlap {
+				let end = idx.len();
+				for k in start..end {
+					for &j in &adj[idx[k]] {
+						if mark[j] != p {
+							mark[j] = p;
+							idx.push(j);
+						}
+					}
+				}
+				start = end;
+			}
+			idx.sort_unstable();
+			let owned = idx.iter().map(|&i| partition[i] == p).collect();
+
+			let A_i = submatrix(A, &idx, &mut local)?;
+			let solver = if idx.len() <= params.dense_threshold {
+				SubdomainSolver::Dense(A_i.to_dense().partial_piv_lu())
+			} else {
+				SubdomainSolver::Sparse(SparseSolver::new(A_i.as_ref(), params.solver)?)
+			};
+			subdomains.push(Subdomain { idx, owned, solver });
+		}
+
+		Ok(Self {
+			dim: n,
+			restricted: params.restricted,
+			subdomains,
+		})
+	}
+
+	/// returns the number of subdomains
+	#[inline]
+	pub fn n_subdomains(&self) -> usize {
+		self.subdomains.len()
+	}
+
+	/// returns the global indices of the unknowns of the `i`-th extended subdomain
+	#[inline]
+	pub fn subdomain(&self, i: usize) -> &[usize] {
+		&self.subdomains[i].idx
+	}
+
+	#[math]
+	fn apply_imp(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, conj: Conj, transpose: bool, par: Par) {
+		let k = rhs.ncols();
+		let restrict_rhs = self.restricted && transpose;
+		let restrict_out = self.restricted && !transpose;
+
+		let mut local = self
+			.subdomains
+			.iter()
+			.map(|s| {
+				Mat::<T>::from_fn(s.idx.len(), k, |i, j| {
+					if restrict_rhs && !s.owned[i] { zero() } else { copy(rhs[(s.idx[i], j)]) }
+				})
+			})
+			.collect::<Vec<_>>();
+
+		let solve = |(s, x): (&Subdomain<I, T>, &mut Mat<T>)| {
+			let x = x.as_mut();
+			match (&s.solver, transpose) {
+				(SubdomainSolver::Dense(lu), false) => lu.solve_in_place_with_conj(conj, x),
+				(SubdomainSolver::Dense(lu), true) => lu.solve_transpose_in_place_with_conj(conj, x),
+				(SubdomainSolver::Sparse(solver), false) => solver.solve_in_place_with_conj(conj, x),
+				(SubdomainSolver::Sparse(solver), true) => solver.solve_transpose_in_place_with_conj(conj, x),
+			}
+		};
+		match par {
+			Par::Seq => self.subdomains.iter().zip(local.iter_mut()).for_each(solve),
+			#[cfg(feature = "rayon")]
+			Par::Rayon(_) => {
+				use rayon::prelude::*;
+				self.subdomains.par_iter().zip(local.par_iter_mut()).for_each(solve);
+			},
+		}
+
+		// the local solutions are accumulated in a fixed order, so that the result doesn't depend
+		// on the parallelism
+		let mut out = out;
+		out.fill(zero());
+		for (s, x) in self.subdomains.iter().zip(local.iter()) {
+			for (l, &i) in s.idx.iter().enumerate() {
+				if restrict_out && !s.owned[l] {
+					continue;
+				}
+				for j in 0..k {
+					out[(i, j)] = out[(i, j)] + x[(l, j)];
+				}
+			}
+		}
+	}
+}
+
+impl<I: Index, T: ComplexField> LinOp<T> for AdditiveSchwarz<I, T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.dim
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.dim
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		self.apply_imp(out, rhs, Conj::No, false, par);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		self.apply_imp(out, rhs, Conj::Yes, false, par);
+	}
+}
+
+impl<I: Index, T: ComplexField> BiLinOp<T> for AdditiveSchwarz<I, T> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		self.apply_imp(out, rhs, Conj::No, true, par);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		self.apply_imp(out, rhs, Conj::Yes, true, par);
+	}
+}
+
+impl<I: Index, T: ComplexField> Precond<T> for AdditiveSchwarz<I, T> {}
+impl<I: Index, T: ComplexField> BiPrecond<T> for AdditiveSchwarz<I, T> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::DenseSolveCore;
+	use crate::matrix_free::IdentityPrecond;
+	use crate::matrix_free::conjugate_gradient::{CgParams, conjugate_gradient, conjugate_gradient_scratch};
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	/// five point laplacian on a `k x k` grid, with a convection term along the first axis
+	fn laplacian_2d(k: usize, convection: f64) -> SparseColMat<usize, c64> {
+		let idx = |i: usize, j: usize| i + k * j;
+		let mut triplets = Vec::new();
+		for j in 0..k {
+			for i in 0..k {
+				triplets.push(Triplet::new(idx(i, j), idx(i, j), c64::new(4.0, 0.0)));
+				if i > 0 {
+					triplets.push(Triplet::new(idx(i, j), idx(i - 1, j), c64::new(-1.0 - convection, 0.0)));
+				}
+				if i + 1 < k {
+					triplets.push(Triplet::new(idx(i, j), idx(i + 1, j), c64::new(-1.0 + convection, 0.0)));
+				}
+				if j > 0 {
+					triplets.push(Triplet::new(idx(i, j), idx(i, j - 1), c64::new(-1.0, 0.0)));
+				}
+				if j + 1 < k {
+					triplets.push(Triplet::new(idx(i, j), idx(i, j + 1), c64::new(-1.0, 0.0)));
+				}
+			}
+		}
+		SparseColMat::try_new_from_triplets(k * k, k * k, &triplets).unwrap()
+	}
+
+	/// dense reference implementation of the preconditioner
+	fn reference(A: &SparseColMat<usize, c64>, M: &AdditiveSchwarz<usize, c64>, restricted: bool, partition: &[usize]) -> Mat<c64> {
+		let n = A.nrows();
+		let A = A.to_dense();
+		let mut out = Mat::<c64>::zeros(n, n);
+		for s in 0..M.n_subdomains() {
+			let idx = M.subdomain(s);
+			let A_i = Mat::from_fn(idx.len(), idx.len(), |i, j| A[(idx[i], idx[j])]);
+			let A_i_inv = A_i.partial_piv_lu().inverse();
+			let p = partition[idx.iter().copied().find(|&i| partition[i] == s).unwrap()];
+			for (i, &gi) in idx.iter().enumerate() {
+				if restricted && partition[gi] != p {
+					continue;
+				}
+				for (j, &gj) in idx.iter().enumerate() {
+					out[(gi, gj)] += A_i_inv[(i, j)];
+				}
+			}
+		}
+		out
+	}
+
+	#[test]
+	fn test_additive_schwarz() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let k = 12;
+		let n = k * k;
+		let A = laplacian_2d(k, 0.3);
+		let rhs = Mat::<c64>::random_normal(rng, n, 3);
+
+		for restricted in [false, true] {
+			for (subdomain_size, overlap, dense_threshold) in [(n, 0, 0), (30, 0, 64), (30, 2, 64), (50, 1, 0)] {
+				let params = SchwarzParams {
+					subdomain_size,
+					overlap,
+					restricted,
+					dense_threshold,
+					..Default::default()
+				};
+				let M = AdditiveSchwarz::new(A.as_ref(), params).unwrap();
+				assert!(M.n_subdomains() == n.div_ceil(subdomain_size));
+				for s in 0..M.n_subdomains() {
+					assert!(M.subdomain(s).len() >= Ord::min(subdomain_size, n - s * subdomain_size));
+					if overlap == 0 {
+						assert!(M.subdomain(s).len() == Ord::min(subdomain_size, n - s * subdomain_size));
+					}
+				}
+
+				let partition = (0..n).map(|i| i / subdomain_size).collect::<Vec<_>>();
+				let M_dense = reference(&A, &M, restricted, &partition);
+
+				let mut out = Mat::<c64>::zeros(n, 3);
+				let stack = MemStack::new(&mut []);
+				M.apply(out.as_mut(), rhs.as_ref(), Par::Seq, stack);
+				assert!((&out - &M_dense * &rhs).norm_max() < 1e-10);
+
+				M.conj_apply(out.as_mut(), rhs.as_ref(), Par::Seq, stack);
+				assert!((&out - M_dense.conjugate() * &rhs).norm_max() < 1e-10);
+				M.transpose_apply(out.as_mut(), rhs.as_ref(), Par::Seq, stack);
+				assert!((&out - M_dense.transpose() * &rhs).norm_max() < 1e-10);
+				M.adjoint_apply(out.as_mut(), rhs.as_ref(), Par::Seq, stack);
+				assert!((&out - M_dense.adjoint() * &rhs).norm_max() < 1e-10);
+
+				// with no overlap, the preconditioner is the block jacobi preconditioner
+				if subdomain_size == n {
+					let A_inv = A.to_dense().partial_piv_lu().inverse();
+					assert!((&M_dense - &A_inv).norm_max() < 1e-10);
+				}
+
+				#[cfg(feature = "rayon")]
+				{
+					let mut out_par = Mat::<c64>::zeros(n, 3);
+					M.apply(out.as_mut(), rhs.as_ref(), Par::Seq, stack);
+					M.apply(out_par.as_mut(), rhs.as_ref(), Par::rayon(4), stack);
+					assert!(out == out_par);
+				}
+			}
+		}
+
+		// user provided partition, with an empty subdomain
+		let partition = (0..n).map(|i| if (i % k) < k / 2 { 0 } else { 2 }).collect::<Vec<_>>();
+		let M = AdditiveSchwarz::new_with_partition(A.as_ref(), &partition, Default::default()).unwrap();
+		assert!(M.n_subdomains() == 2);
+		assert!(M.subdomain(0).len() == n / 2 + k);
+	}
+
+	#[test]
+	fn test_additive_schwarz_cg() {
+		let k = 40;
+		let n = k * k;
+		let A = laplacian_2d(k, 0.0);
+		let b = Mat::<c64>::from_fn(n, 1, |i, _| c64::new(1.0, (i % 7) as f64));
+
+		fn solve(A: &SparseColMat<usize, c64>, b: &Mat<c64>, M: impl Precond<c64>) -> usize {
+			let n = A.nrows();
+			let mut x = Mat::<c64>::zeros(n, 1);
+			let info = conjugate_gradient(
+				x.as_mut(),
+				&M,
+				A.as_ref(),
+				b.as_ref(),
+				CgParams {
+					rel_tolerance: 1e-10,
+					..Default::default()
+				},
+				|_| {},
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(conjugate_gradient_scratch(&M, A.as_ref(), 1, Par::Seq))),
+			)
+			.unwrap();
+			assert!((b - A * &x).norm_l2() < 1e-9 * b.norm_l2());
+			info.iter_count
+		}
+
+		let plain = solve(&A, &b, IdentityPrecond { dim: n });
+		let M = AdditiveSchwarz::new(
+			A.as_ref(),
+			SchwarzParams {
+				subdomain_size: 4 * k,
+				overlap: 2,
+				..Default::default()
+			},
+		)
+		.unwrap();
+		let schwarz = solve(&A, &b, &M);
+		assert!(2 * schwarz < plain);
+	}
+}
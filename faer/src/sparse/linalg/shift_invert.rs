@@ -0,0 +1,304 @@
+//! shift-and-invert eigensolver for the interior eigenvalues of sparse matrices
+//!
+//! krylov methods such as [`partial_eigen`] converge to the eigenvalues of largest magnitude. the
+//! eigenvalues $\lambda$ of $A$ that are closest to a shift $\sigma$ are the eigenvalues of
+//! largest magnitude of $(A - \sigma I)^{-1}$, which are equal to $1 / (\lambda - \sigma)$, with
+//! the same eigenvectors. [`ShiftInvert`] factorizes $A - \sigma I$ once with a [`SparseSolver`],
+//! and applies its inverse as a [`LinOp`], so that the krylov-schur iteration only requires
+//! triangular solves
+//!
+//! # example
+//! ```
+//! use faer::sparse::SparseColMat;
+//! use faer::sparse::linalg::shift_invert::eigen_nearest;
+//! use faer::{Mat, Par, c64};
+//!
+//! // one dimensional laplacian, with the eigenvalues 2 - 2 cos(k pi / (n + 1))
+//! let n = 100;
+//! let A = SparseColMat::<usize, f64>::try_from_diagonals(
+//! 	n,
+//! 	n,
+//! 	&[
+//! 		(-1, Mat::full(n - 1, 1, -1.0).col(0)),
+//! 		(0, Mat::full(n, 1, 2.0).col(0)),
+//! 		(1, Mat::full(n - 1, 1, -1.0).col(0)),
+//! 	],
+//! )
+//! .unwrap();
+//!
+//! // eigenvalues nearest to 1.1
+//! let mut eigvecs = Mat::<c64>::zeros(n, 4);
+//! let mut eigvals = vec![c64::ZERO; 4];
+//! let info = eigen_nearest(
+//! 	eigvecs.as_mut(),
+//! 	&mut eigvals,
+//! 	A.as_ref(),
+//! 	1.1,
+//! 	1e-12,
+//! 	Par::Seq,
+//! 	Default::default(),
+//! )
+//! .unwrap();
+//!
+//! assert!(info.n_converged_eigen == 4);
+//! assert!((eigvals[0].re - 1.1).abs() < 0.05);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude_sp::*;
+use crate::linalg::solvers::{ShapeCore, SolveCore};
+use crate::matrix_free::LinOp;
+use crate::matrix_free::eigen::{PartialEigenInfo, PartialEigenParams, partial_eigen, partial_eigen_scratch};
+use linalg_sp::solvers::{SparseSolver, SparseSolverError, SparseSolverParams};
+
+/// [`eigen_nearest`] parameters
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ShiftInvertParams {
+	/// parameters of the factorization of $A - \sigma I$
+	pub solver: SparseSolverParams,
+	/// parameters of the krylov-schur iteration
+	pub eigen: PartialEigenParams,
+}
+
+/// shift-and-invert operator $(A - \sigma I)^{-1}$, applied through a sparse factorization of
+/// $A - \sigma I$
+#[derive(Debug, Clone)]
+pub struct ShiftInvert<I, T> {
+	solver: SparseSolver<I, T>,
+	shift: T,
+}
+
+/// returns $A - \sigma I$, whose sparsity pattern is the one of $A$ with the full diagonal
+#[math]
+fn shifted<I: Index, T: ComplexField>(A: SparseColMatRef<'_, I, T>, shift: &T) -> Result<SparseColMat<I, T>, FaerError> {
+	let mut S = SparseColMat::<I, T>::try_identity(A.nrows(), A.ncols())?;
+	for s in S.val_mut() {
+		*s = -*shift;
+	}
+	crate::sparse::ops::add(A, S.rb())
+}
+
+impl<I: Index, T: ComplexField> ShiftInvert<I, T> {
+	/// analyzes and factorizes $A - \sigma I$, where $\sigma$ is `shift`
+	///
+	/// if $A - \sigma I$ is self-adjoint, both of the triangular halves of $A$ must be provided
+	#[track_caller]
+	pub fn new(A: SparseColMatRef<'_, I, T>, shift: T, params: SparseSolverParams) -> Result<Self, SparseSolverError> {
+		assert!(A.nrows() == A.ncols());
+		let S = shifted(A, &shift)?;
+		Ok(Self {
+			solver: SparseSolver::new(S.rb(), params)?,
+			shift,
+		})
+	}
+
+	/// factorizes $A - \sigma I$ for a new shift $\sigma$, reusing the symbolic analysis
+	///
+	/// $A$ must have the same sparsity pattern as the matrix passed to [`ShiftInvert::new`]
+	#[track_caller]
+	pub fn set_shift(&mut self, A: SparseColMatRef<'_, I, T>, shift: T) -> Result<(), SparseSolverError> {
+		let S = shifted(A, &shift)?;
+		self.solver.factorize(S.rb())?;
+		self.shift = shift;
+		Ok(())
+	}
+
+	/// returns the shift $\sigma$
+	#[inline]
+	pub fn shift(&self) -> &T {
+		&self.shift
+	}
+
+	/// returns the factorization of $A - \sigma I$
+	#[inline]
+	pub fn solver(&self) -> &SparseSolver<I, T> {
+		&self.solver
+	}
+
+	/// computes an estimate of the eigenvalues (and corresponding eigenvectors) of $A$ that are
+	/// closest to $\sigma$, sorted by increasing distance, until the provided outputs are full or
+	/// the maximum number of algorithm restarts is reached
+	///
+	/// the convergence `tolerance` applies to the eigenvalues of $(A - \sigma I)^{-1}$
+	#[track_caller]
+	#[math]
+	pub fn eigen(
+		&self,
+		eigvecs: MatMut<'_, Complex<T::Real>>,
+		eigvals: &mut [Complex<T::Real>],
+		tolerance: T::Real,
+		par: Par,
+		params: PartialEigenParams,
+	) -> PartialEigenInfo {
+		let n = self.nrows();
+
+		// deterministic starting vector, with no particular structure
+		let mut v0 = Col::<T>::from_fn(n, |i| {
+			// splitmix64 finalizer
+			let mut h = (i as u64).wrapping_add(1).wrapping_mul(0x9E3779B97F4A7C15);
+			h = (h ^ (h >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+			h = (h ^ (h >> 27)).wrapping_mul(0x94D049BB133111EB);
+			h ^= h >> 31;
+			from_f64::<T>((h >> 11) as f64 / (1u64 << 53) as f64 - 0.5)
+		});
+		let norm = v0.norm_l2();
+		if norm > zero::<T::Real>() {
+			let inv_norm: T::Real = recip(norm);
+			v0 = v0 * Scale(from_real::<T>(&inv_norm));
+		}
+
+		let mut mem = MemBuffer::new(partial_eigen_scratch(self, eigvals.len(), par, params));
+		let info = partial_eigen(eigvecs, eigvals, self, v0.as_ref(), tolerance, par, MemStack::new(&mut mem), params);
+
+		// the eigenvalues of the inverse are 1 / (lambda - shift)
+		let shift = Complex::new(real(self.shift), imag(self.shift));
+		for w in eigvals.iter_mut() {
+			if *w != zero::<Complex<T::Real>>() {
+				*w = shift + recip(*w);
+			}
+		}
+		info
+	}
+}
+
+impl<I: Index, T: ComplexField> LinOp<T> for ShiftInvert<I, T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.solver.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.solver.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solver.solve_in_place_with_conj(Conj::No, out);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solver.solve_in_place_with_conj(Conj::Yes, out);
+	}
+}
+
+/// computes an estimate of the eigenvalues (and corresponding eigenvectors) of the sparse matrix
+/// $A$ that are closest to `shift`, sorted by increasing distance
+///
+/// see [`ShiftInvert::eigen`] for more details
+#[track_caller]
+pub fn eigen_nearest<I: Index, T: ComplexField>(
+	eigvecs: MatMut<'_, Complex<T::Real>>,
+	eigvals: &mut [Complex<T::Real>],
+	A: SparseColMatRef<'_, I, T>,
+	shift: T,
+	tolerance: T::Real,
+	par: Par,
+	params: ShiftInvertParams,
+) -> Result<PartialEigenInfo, SparseSolverError> {
+	let op = ShiftInvert::new(A, shift, params.solver)?;
+	Ok(op.eigen(eigvecs, eigvals, tolerance, par, params.eigen))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::sparse::linalg::solvers::SparseMethod;
+
+	fn check(A: &SparseColMat<usize, f64>, eigvecs: MatRef<'_, c64>, eigvals: &[c64], n_converged: usize) {
+		let A = A.to_dense();
+		let A = Mat::from_fn(A.nrows(), A.ncols(), |i, j| c64::from(A[(i, j)]));
+		for j in 0..n_converged {
+			let v = eigvecs.col(j);
+			assert!((&A * v - Scale(eigvals[j]) * v).norm_l2() < 1e-8);
+		}
+	}
+
+	#[test]
+	fn test_shift_invert_self_adjoint() {
+		let n = 300;
+		let A = SparseColMat::<usize, f64>::try_from_diagonals(
+			n,
+			n,
+			&[
+				(-1, Col::full(n - 1, -1.0).as_ref()),
+				(0, Col::full(n, 2.0).as_ref()),
+				(1, Col::full(n - 1, -1.0).as_ref()),
+			],
+		)
+		.unwrap();
+		let exact = (1..n + 1)
+			.map(|k| 2.0 - 2.0 * (k as f64 * core::f64::consts::PI / (n + 1) as f64).cos())
+			.collect::<alloc::vec::Vec<_>>();
+
+		let n_eigval = 6;
+		let mut op = ShiftInvert::new(A.rb(), 1.3, Default::default()).unwrap();
+		assert!(op.solver().method() != SparseMethod::Lu);
+
+		for shift in [1.3, 0.0, 3.9] {
+			if shift != *op.shift() {
+				op.set_shift(A.rb(), shift).unwrap();
+			}
+			let mut eigvecs = Mat::<c64>::zeros(n, n_eigval);
+			let mut eigvals = alloc::vec![c64::ZERO; n_eigval];
+			let info = op.eigen(eigvecs.as_mut(), &mut eigvals, 1e-12, Par::Seq, Default::default());
+			assert!(info.n_converged_eigen == n_eigval);
+			check(&A, eigvecs.as_ref(), &eigvals, n_eigval);
+
+			// the eigenvalues nearest to the shift, in order of increasing distance
+			let mut nearest = exact.clone();
+			nearest.sort_by(|x, y| (x - shift).abs().total_cmp(&(y - shift).abs()));
+			for j in 0..n_eigval {
+				assert!((eigvals[j].re - nearest[j]).abs() < 1e-10);
+				assert!(eigvals[j].im.abs() < 1e-10);
+			}
+		}
+	}
+
+	#[test]
+	fn test_shift_invert_general() {
+		// convection-diffusion operator, with real eigenvalues 2 - 2 sqrt(bc) cos(k pi / (n + 1))
+		let n = 100;
+		let (b, c) = (-0.9, -1.1f64);
+		let A = SparseColMat::<usize, f64>::try_from_diagonals(
+			n,
+			n,
+			&[
+				(-1, Col::full(n - 1, c).as_ref()),
+				(0, Col::full(n, 2.0).as_ref()),
+				(1, Col::full(n - 1, b).as_ref()),
+			],
+		)
+		.unwrap();
+
+		let n_eigval = 4;
+		let mut eigvecs = Mat::<c64>::zeros(n, n_eigval);
+		let mut eigvals = alloc::vec![c64::ZERO; n_eigval];
+		let shift = 2.5;
+		let info = eigen_nearest(eigvecs.as_mut(), &mut eigvals, A.rb(), shift, 1e-12, Par::Seq, Default::default()).unwrap();
+		assert!(info.n_converged_eigen == n_eigval);
+		check(&A, eigvecs.as_ref(), &eigvals, n_eigval);
+
+		let mut nearest = (1..n + 1)
+			.map(|k| 2.0 - 2.0 * (b * c).sqrt() * (k as f64 * core::f64::consts::PI / (n + 1) as f64).cos())
+			.collect::<alloc::vec::Vec<_>>();
+		nearest.sort_by(|x, y| (x - shift).abs().total_cmp(&(y - shift).abs()));
+		for j in 0..n_eigval {
+			assert!((eigvals[j] - c64::from(nearest[j])).norm() < 1e-8);
+		}
+	}
+}
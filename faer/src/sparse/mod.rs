@@ -32,11 +32,17 @@ mod csr;
 
 pub(crate) const NONE: usize = usize::MAX;
 
+/// parallel assembly of sparse matrices from triplets
+pub mod assembly;
+/// graph algorithms on sparsity patterns
+pub mod graph;
 /// sparse linear algebra module.
 /// contains low level routines and the implementation of their corresponding high level wrappers
 pub mod linalg;
 /// sparse matrix binary and ternary operation implementations
 pub mod ops;
+/// bandwidth and profile reducing orderings
+pub mod ordering;
 
 use crate::internal_prelude_sp::Index;
 use reborrow::*;
@@ -50,6 +56,8 @@ pub use csr::symbolic as csr_symbolic;
 pub use csc::numeric as csc_numeric;
 pub use csr::numeric as csr_numeric;
 
+pub use ops::kron;
+
 extern crate alloc;
 
 /// pair of indices with `C`-compatible layout
@@ -282,7 +290,7 @@ impl<I: Index, T> core::ops::IndexMut<(usize, usize)> for SparseRowMat<I, T> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::assert;
+	use crate::{Col, Mat, assert, c64};
 
 	#[test]
 	fn test_from_indices() {
@@ -495,4 +503,58 @@ mod tests {
 		let err = err.unwrap_err();
 		assert!(err == CreationError::OutOfBounds { row: 2, col: 4 });
 	}
+
+	#[test]
+	fn test_identity_diagonals() {
+		let I = SparseColMat::<u32, f64>::try_identity(3, 5).unwrap();
+		assert!(I.compute_nnz() == 3);
+		assert!(I.to_dense() == Mat::<f64>::identity(3, 5));
+
+		let d = |k: isize, len: usize| Col::from_fn(len, |t| (10 * k + t as isize) as f64);
+		let (d0, d1, d2, dm1) = (d(0, 3), d(1, 3), d(3, 1), d(-1, 2));
+		let A =
+			SparseColMat::<u32, f64>::try_from_diagonals(3, 4, &[(1, d1.as_ref()), (-1, dm1.as_ref()), (0, d0.as_ref()), (3, d2.as_ref())]).unwrap();
+		let expected = Mat::from_fn(3, 4, |i, j| match j as isize - i as isize {
+			0 => d0[i],
+			1 => d1[i],
+			3 => d2[i],
+			-1 => dm1[j],
+			_ => 0.0,
+		});
+		assert!(A.compute_nnz() == 9);
+		assert!(A.to_dense() == expected);
+		SymbolicSparseColMatRef::new_checked(3, 4, A.symbolic().col_ptr(), None, A.symbolic().row_idx());
+	}
+
+	#[test]
+	fn test_kron() {
+		let A = SparseColMat::<usize, c64>::try_new_from_triplets(
+			3,
+			2,
+			&[
+				Triplet::new(0, 0, c64::new(1.0, 2.0)),
+				Triplet::new(2, 0, c64::new(-1.0, 0.5)),
+				Triplet::new(1, 1, c64::new(3.0, -1.0)),
+			],
+		)
+		.unwrap();
+		let B = SparseColMat::<usize, c64>::try_new_from_triplets(
+			2,
+			3,
+			&[
+				Triplet::new(1, 0, c64::new(2.0, 1.0)),
+				Triplet::new(0, 2, c64::new(0.5, -3.0)),
+				Triplet::new(1, 2, c64::new(-2.0, 1.0)),
+			],
+		)
+		.unwrap();
+
+		let C = kron(A.rb(), B.conjugate()).unwrap();
+		let mut expected = Mat::zeros(6, 6);
+		crate::linalg::kron::kron(expected.as_mut(), A.to_dense().as_ref(), B.to_dense().conjugate());
+
+		assert!(C.compute_nnz() == 9);
+		assert!(C.to_dense() == expected);
+		SymbolicSparseColMatRef::new_checked(6, 6, C.symbolic().col_ptr(), None, C.symbolic().row_idx());
+	}
 }
@@ -276,6 +276,79 @@ pub fn union_symbolic<I: Index>(
 	.0)
 }
 
+/// returns the kronecker product of `lhs` and `rhs`
+///
+/// see [`faer::linalg::kron::kron`](crate::linalg::kron::kron) for the structure of the result. if
+/// `lhs` and `rhs` are sorted, the result is sorted too
+///
+/// # example
+/// ```
+/// use faer::col;
+/// use faer::sparse::{SparseColMat, kron, ops};
+///
+/// // two dimensional laplacian on an n x n grid
+/// let n = 4;
+/// let I = SparseColMat::<usize, f64>::try_identity(n, n).unwrap();
+/// let T = SparseColMat::<usize, f64>::try_from_diagonals(
+/// 	n,
+/// 	n,
+/// 	&[
+/// 		(-1, col![-1.0, -1.0, -1.0].as_ref()),
+/// 		(0, col![2.0, 2.0, 2.0, 2.0].as_ref()),
+/// 		(1, col![-1.0, -1.0, -1.0].as_ref()),
+/// 	],
+/// )
+/// .unwrap();
+///
+/// let L = ops::add(
+/// 	kron(I.as_ref(), T.as_ref()).unwrap().as_ref(),
+/// 	kron(T.as_ref(), I.as_ref()).unwrap().as_ref(),
+/// )
+/// .unwrap();
+/// assert!(L.nrows() == n * n);
+/// assert!(L[(5, 5)] == 4.0);
+/// assert!(L[(5, 1)] == -1.0);
+/// assert!(L[(5, 4)] == -1.0);
+/// assert!(L.get(4, 3).is_none());
+/// ```
+#[track_caller]
+pub fn kron<I: Index, T: ComplexField, LhsT: Conjugate<Canonical = T>, RhsT: Conjugate<Canonical = T>>(
+	lhs: SparseColMatRef<'_, I, LhsT>,
+	rhs: SparseColMatRef<'_, I, RhsT>,
+) -> Result<SparseColMat<I, T>, FaerError> {
+	let overflow = FaerError::IndexOverflow;
+	let nrows = lhs.nrows().checked_mul(rhs.nrows()).ok_or(overflow)?;
+	let ncols = lhs.ncols().checked_mul(rhs.ncols()).ok_or(overflow)?;
+	let nnz = lhs.compute_nnz().checked_mul(rhs.compute_nnz()).ok_or(overflow)?;
+	if Ord::max(Ord::max(nrows, ncols), nnz) > I::Signed::MAX.zx() {
+		return Err(overflow);
+	}
+
+	let mut col_ptr = try_zeroed::<I>(ncols + 1)?;
+	let mut row_idx = alloc::vec::Vec::new();
+	let mut val = alloc::vec::Vec::new();
+	row_idx.try_reserve_exact(nnz)?;
+	val.try_reserve_exact(nnz)?;
+
+	for lhs_j in 0..lhs.ncols() {
+		for rhs_j in 0..rhs.ncols() {
+			for (lhs_i, lhs_val) in core::iter::zip(lhs.row_idx_of_col(lhs_j), lhs.val_of_col(lhs_j)) {
+				let lhs_val = Conj::apply(lhs_val);
+				for (rhs_i, rhs_val) in core::iter::zip(rhs.row_idx_of_col(rhs_j), rhs.val_of_col(rhs_j)) {
+					row_idx.push(I::truncate(lhs_i * rhs.nrows() + rhs_i));
+					val.push(faer_traits::math_utils::mul(&lhs_val, &Conj::apply(rhs_val)));
+				}
+			}
+			col_ptr[lhs_j * rhs.ncols() + rhs_j + 1] = I::truncate(row_idx.len());
+		}
+	}
+
+	Ok(SparseColMat::new(
+		unsafe { SymbolicSparseColMat::new_unchecked(nrows, ncols, col_ptr, None, row_idx) },
+		val,
+	))
+}
+
 /// returns the sum of `lhs` and `rhs`.
 ///
 /// # panics
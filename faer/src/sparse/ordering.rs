@@ -0,0 +1,493 @@
+//! bandwidth and profile reducing orderings
+//!
+//! the fill reducing orderings used by the sparse factorizations, such as
+//! [`amd`](crate::sparse::linalg::amd), minimize the number of nonzeros of the factors. the
+//! orderings of this module instead gather the nonzeros of a symmetric matrix $P A P^\top$ close
+//! to the diagonal, so that banded and envelope (skyline) storage schemes become efficient:
+//! - [`rcm`] computes the reverse cuthill-mckee ordering, which reduces the bandwidth,
+//! - [`sloan`] computes the ordering of sloan, which reduces the profile and the wavefront.
+//!
+//! both orderings only depend on the sparsity pattern of $A + A^\top$, and handle disconnected
+//! graphs by ordering each connected component separately. the quality of an ordering can be
+//! measured with [`bandwidth`] and [`profile`]
+//!
+//! # example
+//! ```
+//! use faer::sparse::{SparseColMat, Triplet, ordering};
+//!
+//! // tridiagonal matrix, with its rows and columns scrambled
+//! let n = 50;
+//! let scramble = |i: usize| (7 * i) % n;
+//! let mut triplets = vec![];
+//! for i in 0..n {
+//! 	triplets.push(Triplet::new(scramble(i), scramble(i), 2.0));
+//! 	if i + 1 < n {
+//! 		triplets.push(Triplet::new(scramble(i), scramble(i + 1), -1.0));
+//! 		triplets.push(Triplet::new(scramble(i + 1), scramble(i), -1.0));
+//! 	}
+//! }
+//! let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+//! assert!(ordering::bandwidth(A.symbolic(), None).lower > 1);
+//!
+//! let perm = ordering::rcm(A.symbolic()).unwrap();
+//! assert!(ordering::bandwidth(A.symbolic(), Some(perm.as_ref())).lower == 1);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude_sp::*;
+use crate::sparse::{try_collect, try_zeroed};
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+/// lower and upper bandwidth of a matrix
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Bandwidth {
+	/// largest value of $i - j$ over the nonzero entries $(i, j)$ below the diagonal
+	pub lower: usize,
+	/// largest value of $j - i$ over the nonzero entries $(i, j)$ above the diagonal
+	pub upper: usize,
+}
+
+/// [`sloan`] parameters
+#[derive(Copy, Clone, Debug)]
+pub struct SloanParams {
+	/// weight of the distance to the end of the ordering in the priority of a node
+	pub distance_weight: usize,
+	/// weight of the growth of the front in the priority of a node
+	pub degree_weight: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for SloanParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			distance_weight: 1,
+			degree_weight: 2,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// adjacency structure of the graph of $A + A^\top$, excluding the self loops, with the neighbors
+/// of each node sorted in increasing order
+pub(crate) struct Adjacency {
+	ptr: Vec<usize>,
+	idx: Vec<usize>,
+}
+
+impl Adjacency {
+	#[track_caller]
+	pub(crate) fn new<I: Index>(A: SymbolicSparseColMatRef<'_, I>) -> Result<Self, FaerError> {
+		assert!(A.nrows() == A.ncols());
+		let n = A.ncols();
+
+		let mut ptr = try_zeroed::<usize>(n + 1)?;
+		for j in 0..n {
+			for i in A.row_idx_of_col(j) {
+				if i != j {
+					ptr[i + 1] += 1;
+					ptr[j + 1] += 1;
+				}
+			}
+		}
+		for j in 0..n {
+			ptr[j + 1] += ptr[j];
+		}
+
+		let mut idx = try_zeroed::<usize>(ptr[n])?;
+		let mut pos = try_collect(ptr[..n].iter().copied())?;
+		for j in 0..n {
+			for i in A.row_idx_of_col(j) {
+				if i != j {
+					idx[pos[i]] = j;
+					pos[i] += 1;
+					idx[pos[j]] = i;
+					pos[j] += 1;
+				}
+			}
+		}
+
+		// sort the neighbors and remove the duplicates
+		let mut len = 0usize;
+		for i in 0..n {
+			let (start, end) = (ptr[i], ptr[i + 1]);
+			idx[start..end].sort_unstable();
+			ptr[i] = len;
+			for k in start..end {
+				if k == start || idx[k] != idx[k - 1] {
+					idx[len] = idx[k];
+					len += 1;
+				}
+			}
+		}
+		ptr[n] = len;
+		idx.truncate(len);
+
+		Ok(Self { ptr, idx })
+	}
+
+	#[inline]
+	pub(crate) fn n(&self) -> usize {
+		self.ptr.len() - 1
+	}
+
+	#[inline]
+	pub(crate) fn neighbors(&self, i: usize) -> &[usize] {
+		&self.idx[self.ptr[i]..self.ptr[i + 1]]
+	}
+
+	#[inline]
+	pub(crate) fn degree(&self, i: usize) -> usize {
+		self.ptr[i + 1] - self.ptr[i]
+	}
+}
+
+/// workspace of the breadth first searches
+struct Search {
+	mark: Vec<usize>,
+	stamp: usize,
+	order: Vec<usize>,
+	dist: Vec<usize>,
+}
+
+impl Search {
+	fn new(n: usize) -> Result<Self, FaerError> {
+		let mut order = Vec::new();
+		order.try_reserve_exact(n)?;
+		Ok(Self {
+			mark: try_zeroed(n)?,
+			stamp: 0,
+			order,
+			dist: try_zeroed(n)?,
+		})
+	}
+
+	/// visits the connected component of `root` in breadth first order, storing the visited nodes
+	/// in `self.order` and their distances to `root` in `self.dist`
+	///
+	/// returns the eccentricity of `root`
+	fn bfs(&mut self, adj: &Adjacency, root: usize) -> usize {
+		self.stamp += 1;
+		let stamp = self.stamp;
+
+		self.order.clear();
+		self.order.push(root);
+		self.mark[root] = stamp;
+		self.dist[root] = 0;
+
+		let mut head = 0;
+		while head < self.order.len() {
+			let i = self.order[head];
+			head += 1;
+			for &j in adj.neighbors(i) {
+				if self.mark[j] != stamp {
+					self.mark[j] = stamp;
+					self.dist[j] = self.dist[i] + 1;
+					self.order.push(j);
+				}
+			}
+		}
+		self.dist[self.order[self.order.len() - 1]]
+	}
+
+	/// finds a pair of pseudo-peripheral nodes in the connected component of `seed`, with the
+	/// algorithm of george and liu
+	///
+	/// on exit, `self.order` and `self.dist` hold the breadth first search from the second node
+	fn pseudo_peripheral(&mut self, adj: &Adjacency, seed: usize) -> (usize, usize) {
+		let mut start = seed;
+		let mut ecc = self.bfs(adj, start);
+		loop {
+			// node of minimum degree in the last level
+			let end = self
+				.order
+				.iter()
+				.rev()
+				.take_while(|&&i| self.dist[i] == ecc)
+				.copied()
+				.min_by_key(|&i| adj.degree(i))
+				.unwrap();
+
+			let end_ecc = self.bfs(adj, end);
+			if end_ecc > ecc {
+				start = end;
+				ecc = end_ecc;
+			} else {
+				return (start, end);
+			}
+		}
+	}
+}
+
+/// returns the nodes sorted by increasing degree, used as the seeds of the connected components
+fn seeds(adj: &Adjacency) -> Result<Vec<usize>, FaerError> {
+	let mut seeds = try_collect(0..adj.n())?;
+	seeds.sort_by_key(|&i| adj.degree(i));
+	Ok(seeds)
+}
+
+fn to_perm<I: Index>(fwd: &[usize]) -> Result<Perm<I>, FaerError> {
+	let n = fwd.len();
+	let mut inv = try_zeroed::<usize>(n)?;
+	for (k, &i) in fwd.iter().enumerate() {
+		inv[i] = k;
+	}
+	let fwd = try_collect(fwd.iter().map(|&i| I::truncate(i)))?;
+	let inv = try_collect(inv.iter().map(|&i| I::truncate(i)))?;
+	Ok(unsafe { Perm::new_unchecked(fwd.into_boxed_slice(), inv.into_boxed_slice()) })
+}
+
+/// computes the reverse cuthill-mckee ordering of the matrix $A + A^\top$
+///
+/// the permutation $P$ is such that $P A P^\top$ has a small bandwidth. each connected component is
+/// numbered starting from a pseudo-peripheral node, by a breadth first search that visits the
+/// neighbors of each node in order of increasing degree
+///
+/// # panics
+/// panics if $A$ is not square
+#[track_caller]
+pub fn rcm<I: Index>(A: SymbolicSparseColMatRef<'_, I>) -> Result<Perm<I>, FaerError> {
+	let adj = Adjacency::new(A)?;
+	let n = adj.n();
+
+	let mut search = Search::new(n)?;
+	let mut numbered = try_zeroed::<u8>(n)?;
+	let mut fwd = Vec::new();
+	fwd.try_reserve_exact(n)?;
+
+	for seed in seeds(&adj)? {
+		if numbered[seed] != 0 {
+			continue;
+		}
+		let (start, _) = search.pseudo_peripheral(&adj, seed);
+
+		let mut head = fwd.len();
+		fwd.push(start);
+		numbered[start] = 1;
+		while head < fwd.len() {
+			let i = fwd[head];
+			head += 1;
+
+			let first = fwd.len();
+			for &j in adj.neighbors(i) {
+				if numbered[j] == 0 {
+					numbered[j] = 1;
+					fwd.push(j);
+				}
+			}
+			fwd[first..].sort_by_key(|&j| adj.degree(j));
+		}
+	}
+
+	fwd.reverse();
+	to_perm(&fwd)
+}
+
+/// computes the ordering of sloan of the matrix $A + A^\top$
+///
+/// the permutation $P$ is such that $P A P^\top$ has a small profile and wavefront. each connected
+/// component is numbered from one end of a pseudo-diameter to the other, and the next node is
+/// chosen by a priority that favors the nodes far from the end of the ordering, and the nodes whose
+/// numbering adds few nodes to the front
+///
+/// # panics
+/// panics if $A$ is not square
+#[track_caller]
+pub fn sloan<I: Index>(A: SymbolicSparseColMatRef<'_, I>, params: SloanParams) -> Result<Perm<I>, FaerError> {
+	const INACTIVE: u8 = 0;
+	const PREACTIVE: u8 = 1;
+	const ACTIVE: u8 = 2;
+	const POSTACTIVE: u8 = 3;
+
+	let adj = Adjacency::new(A)?;
+	let n = adj.n();
+	let w1 = params.distance_weight as isize;
+	let w2 = params.degree_weight as isize;
+
+	let mut search = Search::new(n)?;
+	let mut status = try_zeroed::<u8>(n)?;
+	let mut priority = try_zeroed::<isize>(n)?;
+	let mut fwd = Vec::new();
+	fwd.try_reserve_exact(n)?;
+
+	// the queue may contain stale entries, which are skipped when their priority is outdated
+	let mut queue = BinaryHeap::<(isize, Reverse<usize>)>::new();
+
+	for seed in seeds(&adj)? {
+		if status[seed] != INACTIVE {
+			continue;
+		}
+		let (start, _) = search.pseudo_peripheral(&adj, seed);
+		for &i in &search.order {
+			priority[i] = w1 * search.dist[i] as isize - w2 * (adj.degree(i) as isize + 1);
+		}
+
+		status[start] = PREACTIVE;
+		queue.push((priority[start], Reverse(start)));
+
+		// increases the priority of `j`, which becomes a candidate if it wasn't already
+		let bump = |j: usize, status: &mut [u8], priority: &mut [isize], queue: &mut BinaryHeap<(isize, Reverse<usize>)>| {
+			priority[j] += w2;
+			if status[j] == INACTIVE {
+				status[j] = PREACTIVE;
+			}
+			queue.push((priority[j], Reverse(j)));
+		};
+
+		while let Some((p, Reverse(i))) = queue.pop() {
+			if status[i] == POSTACTIVE || p != priority[i] {
+				continue;
+			}
+
+			if status[i] == PREACTIVE {
+				for &j in adj.neighbors(i) {
+					if status[j] != POSTACTIVE {
+						bump(j, &mut status, &mut priority, &mut queue);
+					}
+				}
+			}
+			status[i] = POSTACTIVE;
+			fwd.push(i);
+
+			for &j in adj.neighbors(i) {
+				if status[j] == PREACTIVE {
+					status[j] = ACTIVE;
+					bump(j, &mut status, &mut priority, &mut queue);
+					for &k in adj.neighbors(j) {
+						if status[k] != POSTACTIVE {
+							bump(k, &mut status, &mut priority, &mut queue);
+						}
+					}
+				}
+			}
+		}
+	}
+
+	to_perm(&fwd)
+}
+
+/// returns the lower and upper bandwidth of the matrix $P A P^\top$, or of $A$ if no permutation is
+/// provided
+#[track_caller]
+pub fn bandwidth<I: Index>(A: SymbolicSparseColMatRef<'_, I>, perm: Option<PermRef<'_, I>>) -> Bandwidth {
+	let inv = perm.map(|perm| {
+		assert!(all(perm.len() == A.nrows(), perm.len() == A.ncols()));
+		perm.arrays().1
+	});
+	let new = |i: usize| inv.map(|inv| inv[i].zx()).unwrap_or(i);
+
+	let mut bw = Bandwidth { lower: 0, upper: 0 };
+	for j in 0..A.ncols() {
+		let jj = new(j);
+		for i in A.row_idx_of_col(j) {
+			let ii = new(i);
+			if ii > jj {
+				bw.lower = Ord::max(bw.lower, ii - jj);
+			} else {
+				bw.upper = Ord::max(bw.upper, jj - ii);
+			}
+		}
+	}
+	bw
+}
+
+/// returns the profile of the matrix $P (A + A^\top) P^\top$, or of $A + A^\top$ if no permutation
+/// is provided
+///
+/// the profile is the number of entries that lie between the first nonzero of each row and the
+/// diagonal, i.e., the size of the lower envelope of the matrix, excluding the diagonal
+#[track_caller]
+pub fn profile<I: Index>(A: SymbolicSparseColMatRef<'_, I>, perm: Option<PermRef<'_, I>>) -> Result<usize, FaerError> {
+	assert!(A.nrows() == A.ncols());
+	let n = A.nrows();
+	let inv = perm.map(|perm| {
+		assert!(perm.len() == n);
+		perm.arrays().1
+	});
+	let new = |i: usize| inv.map(|inv| inv[i].zx()).unwrap_or(i);
+
+	let mut first = try_collect(0..n)?;
+	for j in 0..n {
+		let jj = new(j);
+		for i in A.row_idx_of_col(j) {
+			let ii = new(i);
+			let (hi, lo) = (Ord::max(ii, jj), Ord::min(ii, jj));
+			first[hi] = Ord::min(first[hi], lo);
+		}
+	}
+	Ok(first.iter().enumerate().map(|(i, &f)| i - f).sum())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use rand::rngs::StdRng;
+	use rand::seq::SliceRandom;
+	use rand::{Rng, SeedableRng};
+
+	fn banded(n: usize, b: usize, scramble: &[usize]) -> SparseColMat<usize, f64> {
+		let rng = &mut StdRng::seed_from_u64(1);
+		let mut triplets = alloc::vec::Vec::new();
+		for i in 0..n {
+			triplets.push(Triplet::new(scramble[i], scramble[i], 1.0));
+			for j in i + 1..Ord::min(i + b + 1, n) {
+				if j == i + 1 || rng.gen_bool(0.5) {
+					triplets.push(Triplet::new(scramble[i], scramble[j], 1.0));
+				}
+			}
+		}
+		SparseColMat::try_new_from_triplets(n, n, &triplets).unwrap()
+	}
+
+	#[test]
+	fn test_rcm_sloan() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 300;
+		let b = 4;
+
+		let mut scramble = (0..n).collect::<alloc::vec::Vec<_>>();
+		scramble.shuffle(rng);
+		let A = banded(n, b, &scramble);
+		let A = A.symbolic();
+
+		let original = banded(n, b, &(0..n).collect::<alloc::vec::Vec<_>>());
+		let original_profile = profile(original.symbolic(), None).unwrap();
+		assert!(bandwidth(A, None).upper > 10 * b);
+
+		// only the upper triangular half is stored, the orderings use the pattern of A + A^T
+		let perm = rcm(A).unwrap();
+		let bw = bandwidth(A, Some(perm.as_ref()));
+		assert!(Ord::max(bw.lower, bw.upper) <= 2 * b);
+		assert!(profile(A, Some(perm.as_ref())).unwrap() <= 2 * original_profile);
+
+		let perm = sloan(A, Default::default()).unwrap();
+		assert!(profile(A, Some(perm.as_ref())).unwrap() <= 2 * original_profile);
+		assert!(profile(A, Some(perm.as_ref())).unwrap() * 10 < profile(A, None).unwrap());
+	}
+
+	#[test]
+	fn test_disconnected() {
+		// two paths and an isolated node
+		let n = 9;
+		let edges = [(0, 4), (4, 2), (2, 7), (1, 8), (8, 5), (5, 3)];
+		let mut triplets = edges.iter().map(|&(i, j)| Triplet::new(i, j, 1.0)).collect::<alloc::vec::Vec<_>>();
+		triplets.push(Triplet::new(6, 6, 1.0));
+		let A = SparseColMat::<u32, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let A = A.symbolic();
+
+		for perm in [rcm(A).unwrap(), sloan(A, Default::default()).unwrap()] {
+			let (fwd, inv) = perm.as_ref().arrays();
+			PermRef::new_checked(fwd, inv, n);
+			assert!(
+				bandwidth(A, Some(perm.as_ref())) == Bandwidth { lower: 0, upper: 1 }
+					|| bandwidth(A, Some(perm.as_ref())) == Bandwidth { lower: 1, upper: 0 }
+			);
+			assert!(profile(A, Some(perm.as_ref())).unwrap() == edges.len());
+		}
+	}
+}
@@ -4,6 +4,9 @@ use crate::linalg::solvers::{ShapeCore, SolveCore, SolveLstsqCore};
 use linalg_sp::{LltError, LuError};
 
 /// reference-counted sparse symbolic $LL^\top$ factorization
+///
+/// cloning only increments a reference count, so the same analysis can be shared between threads
+/// and reused for all the matrices with the same sparsity pattern
 #[derive(Debug, Clone)]
 pub struct SymbolicLlt<I> {
 	inner: alloc::sync::Arc<linalg_sp::cholesky::SymbolicCholesky<I>>,
@@ -17,6 +20,9 @@ pub struct Llt<I, T> {
 }
 
 /// reference-counted sparse symbolic $QR$ factorization
+///
+/// cloning only increments a reference count, so the same analysis can be shared between threads
+/// and reused for all the matrices with the same sparsity pattern
 #[derive(Debug, Clone)]
 pub struct SymbolicQr<I> {
 	inner: alloc::sync::Arc<linalg_sp::qr::SymbolicQr<I>>,
@@ -31,12 +37,15 @@ pub struct Qr<I, T> {
 }
 
 /// reference-counted sparse symbolic $LU$ factorization
+///
+/// cloning only increments a reference count, so the same analysis can be shared between threads
+/// and reused for all the matrices with the same sparsity pattern
 #[derive(Debug, Clone)]
 pub struct SymbolicLu<I> {
 	inner: alloc::sync::Arc<linalg_sp::lu::SymbolicLu<I>>,
 }
 
-/// sparse $QR$ factorization
+/// sparse $LU$ factorization
 #[derive(Debug, Clone)]
 pub struct Lu<I, T> {
 	symbolic: SymbolicLu<I>,
@@ -80,6 +89,57 @@ impl<I: Index> SymbolicLu<I> {
 	}
 }
 
+impl<I: Index> SymbolicLlt<I> {
+	/// returns the underlying symbolic $LL^\top$ factorization
+	#[inline]
+	pub fn inner(&self) -> &linalg_sp::cholesky::SymbolicCholesky<I> {
+		&self.inner
+	}
+}
+
+impl<I: Index> From<linalg_sp::cholesky::SymbolicCholesky<I>> for SymbolicLlt<I> {
+	#[inline]
+	fn from(inner: linalg_sp::cholesky::SymbolicCholesky<I>) -> Self {
+		Self {
+			inner: alloc::sync::Arc::new(inner),
+		}
+	}
+}
+
+impl<I: Index> SymbolicQr<I> {
+	/// returns the underlying symbolic $QR$ factorization
+	#[inline]
+	pub fn inner(&self) -> &linalg_sp::qr::SymbolicQr<I> {
+		&self.inner
+	}
+}
+
+impl<I: Index> From<linalg_sp::qr::SymbolicQr<I>> for SymbolicQr<I> {
+	#[inline]
+	fn from(inner: linalg_sp::qr::SymbolicQr<I>) -> Self {
+		Self {
+			inner: alloc::sync::Arc::new(inner),
+		}
+	}
+}
+
+impl<I: Index> SymbolicLu<I> {
+	/// returns the underlying symbolic $LU$ factorization
+	#[inline]
+	pub fn inner(&self) -> &linalg_sp::lu::SymbolicLu<I> {
+		&self.inner
+	}
+}
+
+impl<I: Index> From<linalg_sp::lu::SymbolicLu<I>> for SymbolicLu<I> {
+	#[inline]
+	fn from(inner: linalg_sp::lu::SymbolicLu<I>) -> Self {
+		Self {
+			inner: alloc::sync::Arc::new(inner),
+		}
+	}
+}
+
 impl<I: Index, T: ComplexField> Llt<I, T> {
 	/// returns the $LL^\top$ factorization of the input matrix with the same sparsity pattern as
 	/// the original one used to construct the symbolic factorization
@@ -91,19 +151,38 @@ impl<I: Index, T: ComplexField> Llt<I, T> {
 		let mut numeric = alloc::vec::Vec::new();
 		numeric.try_reserve_exact(len_val).map_err(|_| FaerError::OutOfMemory)?;
 		numeric.resize(len_val, zero::<T>());
+		let mut this = Self { symbolic, numeric };
+		this.refactorize(mat, side)?;
+		Ok(this)
+	}
+
+	/// recomputes the $LL^\top$ factorization for an input matrix with the same sparsity pattern as
+	/// the original one used to construct the symbolic factorization, reusing the storage of the
+	/// numeric factorization
+	///
+	/// only the provided side is accessed. if an error is returned, the factorization must be
+	/// recomputed before being used
+	#[track_caller]
+	pub fn refactorize(&mut self, mat: SparseColMatRef<'_, I, T>, side: Side) -> Result<(), LltError> {
 		let par = get_global_parallelism();
-		symbolic.inner.factorize_numeric_llt::<T>(
-			&mut numeric,
+		self.symbolic.inner.factorize_numeric_llt::<T>(
+			&mut self.numeric,
 			mat,
 			side,
 			Default::default(),
 			par,
 			MemStack::new(&mut MemBuffer::try_new(
-				symbolic.inner.factorize_numeric_llt_scratch::<T>(par, Default::default()),
+				self.symbolic.inner.factorize_numeric_llt_scratch::<T>(par, Default::default()),
 			)?),
 			Default::default(),
 		)?;
-		Ok(Self { symbolic, numeric })
+		Ok(())
+	}
+
+	/// returns the symbolic factorization
+	#[inline]
+	pub fn symbolic(&self) -> &SymbolicLlt<I> {
+		&self.symbolic
 	}
 }
 
@@ -112,18 +191,38 @@ impl<I: Index, T: ComplexField> Lu<I, T> {
 	/// original one used to construct the symbolic factorization
 	#[track_caller]
 	pub fn try_new_with_symbolic(symbolic: SymbolicLu<I>, mat: SparseColMatRef<'_, I, T>) -> Result<Self, LuError> {
-		let mut numeric = linalg_sp::lu::NumericLu::new();
+		let mut this = Self {
+			symbolic,
+			numeric: linalg_sp::lu::NumericLu::new(),
+		};
+		this.refactorize(mat)?;
+		Ok(this)
+	}
+
+	/// recomputes the $LU$ factorization for an input matrix with the same sparsity pattern as the
+	/// original one used to construct the symbolic factorization, reusing the storage of the
+	/// numeric factorization
+	///
+	/// if an error is returned, the factorization must be recomputed before being used
+	#[track_caller]
+	pub fn refactorize(&mut self, mat: SparseColMatRef<'_, I, T>) -> Result<(), LuError> {
 		let par = get_global_parallelism();
-		symbolic.inner.factorize_numeric_lu::<T>(
-			&mut numeric,
+		self.symbolic.inner.factorize_numeric_lu::<T>(
+			&mut self.numeric,
 			mat,
 			par,
 			MemStack::new(&mut MemBuffer::try_new(
-				symbolic.inner.factorize_numeric_lu_scratch::<T>(par, Default::default()),
+				self.symbolic.inner.factorize_numeric_lu_scratch::<T>(par, Default::default()),
 			)?),
 			Default::default(),
 		)?;
-		Ok(Self { symbolic, numeric })
+		Ok(())
+	}
+
+	/// returns the symbolic factorization
+	#[inline]
+	pub fn symbolic(&self) -> &SymbolicLu<I> {
+		&self.symbolic
 	}
 }
 
@@ -142,19 +241,35 @@ impl<I: Index, T: ComplexField> Qr<I, T> {
 
 		indices.try_reserve_exact(len_idx).map_err(|_| FaerError::OutOfMemory)?;
 		indices.resize(len_idx, I::truncate(0));
-		let par = get_global_parallelism();
 
-		symbolic.inner.factorize_numeric_qr::<T>(
-			&mut indices,
-			&mut numeric,
+		let mut this = Self { symbolic, indices, numeric };
+		this.refactorize(mat)?;
+		Ok(this)
+	}
+
+	/// recomputes the $QR$ factorization for an input matrix with the same sparsity pattern as the
+	/// original one used to construct the symbolic factorization, reusing the storage of the
+	/// numeric factorization
+	#[track_caller]
+	pub fn refactorize(&mut self, mat: SparseColMatRef<'_, I, T>) -> Result<(), FaerError> {
+		let par = get_global_parallelism();
+		self.symbolic.inner.factorize_numeric_qr::<T>(
+			&mut self.indices,
+			&mut self.numeric,
 			mat,
 			par,
 			MemStack::new(&mut MemBuffer::try_new(
-				symbolic.inner.factorize_numeric_qr_scratch::<T>(par, Default::default()),
+				self.symbolic.inner.factorize_numeric_qr_scratch::<T>(par, Default::default()),
 			)?),
 			Default::default(),
 		);
-		Ok(Self { symbolic, indices, numeric })
+		Ok(())
+	}
+
+	/// returns the symbolic factorization
+	#[inline]
+	pub fn symbolic(&self) -> &SymbolicQr<I> {
+		&self.symbolic
 	}
 }
 
@@ -283,6 +398,457 @@ impl<I: Index, T: ComplexField> SolveCore<T> for Lu<I, T> {
 	}
 }
 
+/// factorization selected by [`SparseSolver`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SparseMethod {
+	/// $LL^H$ factorization, for self-adjoint positive definite matrices
+	Llt,
+	/// $LBL^\top$ factorization with bunch-kaufman pivoting within each supernode, for self-adjoint
+	/// indefinite matrices
+	Lblt,
+	/// $LU$ factorization with partial pivoting, for general matrices
+	Lu,
+}
+
+/// fill reducing ordering selected by [`SparseSolver`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SparseOrdering {
+	/// no reordering, which is selected for narrow banded self-adjoint matrices
+	Identity,
+	/// reverse cuthill-mckee ordering of $A$, which is selected for self-adjoint matrices that
+	/// become narrow banded once reordered
+	Rcm,
+	/// approximate minimum degree ordering of $A$
+	Amd,
+	/// column approximate minimum degree ordering of $A^\top A$
+	Colamd,
+}
+
+/// definiteness of a matrix, as known by the user
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Definiteness {
+	/// nothing is known about the definiteness of the matrix
+	#[default]
+	Unknown,
+	/// the matrix is known to be self-adjoint and positive definite
+	PositiveDefinite,
+	/// the matrix is known to be self-adjoint and indefinite
+	Indefinite,
+}
+
+/// [`SparseSolver`] parameters
+#[derive(Copy, Clone, Debug)]
+pub struct SparseSolverParams {
+	/// definiteness hint
+	pub definiteness: Definiteness,
+	/// factorization to use, overriding the automatic selection
+	pub method: Option<SparseMethod>,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for SparseSolverParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			definiteness: Definiteness::Unknown,
+			method: None,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// [`SparseSolver`] error
+#[derive(Copy, Clone, Debug)]
+pub enum SparseSolverError {
+	/// the matrix was declared positive definite, but a non positive pivot was encountered
+	NonPositivePivot {
+		/// index of the pivot
+		index: usize,
+	},
+	/// the sparsity structure of the matrix is singular
+	SymbolicSingular {
+		/// iteration at which a pivot could not be found
+		index: usize,
+	},
+	/// the matrix is not square, or was declared self-adjoint but isn't
+	InvalidMatrix,
+	/// non algorithmic error
+	Generic(FaerError),
+}
+
+impl core::fmt::Display for SparseSolverError {
+	#[inline]
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl core::error::Error for SparseSolverError {}
+
+impl From<FaerError> for SparseSolverError {
+	fn from(value: FaerError) -> Self {
+		Self::Generic(value)
+	}
+}
+
+impl From<LuError> for SparseSolverError {
+	fn from(value: LuError) -> Self {
+		match value {
+			LuError::SymbolicSingular { index } => Self::SymbolicSingular { index },
+			LuError::Generic(err) => Self::Generic(err),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+enum SparseSymbolic<I> {
+	Cholesky(SymbolicLlt<I>),
+	Lu(SymbolicLu<I>),
+}
+
+#[derive(Debug, Clone)]
+struct Lblt<I, T> {
+	symbolic: SymbolicLlt<I>,
+	numeric: alloc::vec::Vec<T>,
+	subdiag: alloc::vec::Vec<T>,
+	perm_fwd: alloc::vec::Vec<I>,
+	perm_inv: alloc::vec::Vec<I>,
+}
+
+#[derive(Debug, Clone)]
+enum SparseNumeric<I, T> {
+	Llt(Llt<I, T>),
+	Lblt(Lblt<I, T>),
+	Lu(Lu<I, T>),
+}
+
+/// sparse direct solver with automatic selection of the factorization and the fill reducing
+/// ordering
+///
+/// the matrix is inspected once by [`SparseSolver::analyze`]:
+/// - self-adjoint matrices are factorized with $LL^H$ if they are declared positive definite, or if
+///   nothing is known and their diagonal is positive, with a fallback to $LBL^\top$ if a non
+///   positive pivot is encountered. other self-adjoint matrices are factorized with $LBL^\top$,
+/// - other matrices are factorized with $LU$ and a colamd ordering,
+/// - for self-adjoint matrices, the amd ordering is replaced with the identity or the reverse
+///   cuthill-mckee ordering if the matrix is banded, or can be reordered to be banded, and the fill
+///   of the banded factor is lower.
+///
+/// the symbolic analysis is then reused by [`SparseSolver::factorize`] for each new matrix with the
+/// same sparsity pattern, and the factorization is used through the
+/// [`Solve`](crate::linalg::solvers::Solve) trait
+#[derive(Debug, Clone)]
+pub struct SparseSolver<I, T> {
+	symbolic: SparseSymbolic<I>,
+	method: SparseMethod,
+	ordering: SparseOrdering,
+	definiteness: Definiteness,
+	numeric: Option<SparseNumeric<I, T>>,
+}
+
+/// returns whether `A` is self-adjoint, and its bandwidth
+fn inspect<I: Index, T: ComplexField>(A: SparseColMatRef<'_, I, T>) -> Result<(bool, usize), FaerError> {
+	let n = A.ncols();
+
+	let mut bandwidth = 0usize;
+	for j in 0..n {
+		for i in A.row_idx_of_col(j) {
+			bandwidth = Ord::max(bandwidth, i.abs_diff(j));
+		}
+	}
+
+	let At = A.adjoint().to_col_major()?;
+	fn sorted_col<'a, I: Index, T>(A: SparseColMatRef<'a, I, T>, j: usize) -> alloc::vec::Vec<(usize, &'a T)> {
+		let mut col = A.row_idx_of_col(j).zip(A.val_of_col(j)).collect::<alloc::vec::Vec<_>>();
+		col.sort_by_key(|(i, _)| *i);
+		col
+	}
+
+	let mut self_adjoint = true;
+	for j in 0..n {
+		let a = sorted_col(A, j);
+		let b = sorted_col(At.rb(), j);
+		if a.len() != b.len() || a.iter().zip(&b).any(|((i0, v0), (i1, v1))| i0 != i1 || v0 != v1) {
+			self_adjoint = false;
+			break;
+		}
+	}
+
+	Ok((self_adjoint, bandwidth))
+}
+
+impl<I: Index, T: ComplexField> SparseSolver<I, T> {
+	/// inspects the matrix $A$, selects the factorization and the ordering, and computes the
+	/// symbolic factorization
+	///
+	/// if $A$ is self-adjoint, both of its triangular halves must be provided
+	#[track_caller]
+	pub fn analyze(A: SparseColMatRef<'_, I, T>, params: SparseSolverParams) -> Result<Self, SparseSolverError> {
+		if A.nrows() != A.ncols() {
+			return Err(SparseSolverError::InvalidMatrix);
+		}
+		let n = A.nrows();
+		let (self_adjoint, bandwidth) = inspect(A)?;
+
+		let method = match params.method {
+			Some(method) => method,
+			None => match (self_adjoint, params.definiteness) {
+				(false, _) => SparseMethod::Lu,
+				(true, Definiteness::PositiveDefinite) => SparseMethod::Llt,
+				(true, Definiteness::Indefinite) => SparseMethod::Lblt,
+				(true, Definiteness::Unknown) => {
+					let positive_diag = (0..n).all(|j| {
+						A.row_idx_of_col(j)
+							.zip(A.val_of_col(j))
+							.any(|(i, v)| i == j && imag(v) == zero::<T::Real>() && real(v) > zero::<T::Real>())
+					});
+					if positive_diag { SparseMethod::Llt } else { SparseMethod::Lblt }
+				},
+			},
+		};
+		if method != SparseMethod::Lu && !self_adjoint {
+			return Err(SparseSolverError::InvalidMatrix);
+		}
+
+		if method == SparseMethod::Lu {
+			return Ok(Self {
+				symbolic: SparseSymbolic::Lu(SymbolicLu::try_new(A.symbolic())?),
+				method,
+				ordering: SparseOrdering::Colamd,
+				definiteness: params.definiteness,
+				numeric: None,
+			});
+		}
+
+		// the pivoting of the LBL^T factorization only happens within the supernodes
+		let may_be_indefinite = method == SparseMethod::Lblt || params.definiteness != Definiteness::PositiveDefinite;
+		let cholesky_params = linalg_sp::cholesky::CholeskySymbolicParams {
+			supernodal_flop_ratio_threshold: if may_be_indefinite {
+				linalg_sp::SupernodalThreshold::FORCE_SUPERNODAL
+			} else {
+				linalg_sp::SupernodalThreshold::AUTO
+			},
+			..Default::default()
+		};
+		let symbolic = |ord| -> Result<SymbolicLlt<I>, FaerError> {
+			Ok(SymbolicLlt {
+				inner: alloc::sync::Arc::new(linalg_sp::cholesky::factorize_symbolic_cholesky(
+					A.symbolic(),
+					Side::Lower,
+					ord,
+					cholesky_params,
+				)?),
+			})
+		};
+
+		let mut ordering = SparseOrdering::Amd;
+		let mut llt = symbolic(linalg_sp::cholesky::SymmetricOrdering::Amd)?;
+		let rcm = crate::sparse::ordering::rcm(A.symbolic())?;
+		let rcm_bandwidth = crate::sparse::ordering::bandwidth(A.symbolic(), Some(rcm.as_ref()));
+		let rcm_bandwidth = Ord::max(rcm_bandwidth.lower, rcm_bandwidth.upper);
+
+		// the factor of a banded matrix with bandwidth b has at most n (b + 1) nonzeros
+		for (ord, bandwidth, banded_ordering) in [
+			(linalg_sp::cholesky::SymmetricOrdering::Identity, bandwidth, SparseOrdering::Identity),
+			(
+				linalg_sp::cholesky::SymmetricOrdering::Custom(rcm.as_ref()),
+				rcm_bandwidth,
+				SparseOrdering::Rcm,
+			),
+		] {
+			if n.saturating_mul(bandwidth + 1) < llt.inner.len_val() {
+				let banded = symbolic(ord)?;
+				if banded.inner.len_val() < llt.inner.len_val() {
+					llt = banded;
+					ordering = banded_ordering;
+				}
+			}
+		}
+
+		Ok(Self {
+			symbolic: SparseSymbolic::Cholesky(llt),
+			method,
+			ordering,
+			definiteness: params.definiteness,
+			numeric: None,
+		})
+	}
+
+	/// analyzes and factorizes the matrix $A$
+	///
+	/// if $A$ is self-adjoint, both of its triangular halves must be provided
+	#[track_caller]
+	pub fn new(A: SparseColMatRef<'_, I, T>, params: SparseSolverParams) -> Result<Self, SparseSolverError> {
+		let mut this = Self::analyze(A, params)?;
+		this.factorize(A)?;
+		Ok(this)
+	}
+
+	/// computes the numerical factorization of the matrix $A$, which must have the same sparsity
+	/// pattern as the one passed to [`SparseSolver::analyze`]
+	///
+	/// if the $LL^H$ factorization was selected without a definiteness hint and fails, the
+	/// $LBL^\top$ factorization is used instead, for this call and the following ones
+	#[track_caller]
+	pub fn factorize(&mut self, A: SparseColMatRef<'_, I, T>) -> Result<(), SparseSolverError> {
+		// the storage of the previous factorization is reused when possible
+		let numeric = self.numeric.take();
+		match &self.symbolic {
+			SparseSymbolic::Lu(symbolic) => {
+				let lu = match numeric {
+					Some(SparseNumeric::Lu(mut lu)) => {
+						lu.refactorize(A)?;
+						lu
+					},
+					_ => Lu::try_new_with_symbolic(symbolic.clone(), A)?,
+				};
+				self.numeric = Some(SparseNumeric::Lu(lu));
+			},
+			SparseSymbolic::Cholesky(symbolic) => {
+				if self.method == SparseMethod::Llt {
+					let llt = match numeric {
+						Some(SparseNumeric::Llt(mut llt)) => llt.refactorize(A, Side::Lower).map(|()| llt),
+						_ => Llt::try_new_with_symbolic(symbolic.clone(), A, Side::Lower),
+					};
+					match llt {
+						Ok(llt) => {
+							self.numeric = Some(SparseNumeric::Llt(llt));
+							return Ok(());
+						},
+						Err(LltError::Generic(err)) => return Err(err.into()),
+						Err(LltError::Numeric(linalg::cholesky::llt::factor::LltError::NonPositivePivot { index })) => {
+							if self.definiteness == Definiteness::PositiveDefinite {
+								return Err(SparseSolverError::NonPositivePivot { index });
+							}
+							self.method = SparseMethod::Lblt;
+						},
+					}
+				}
+
+				let symbolic = symbolic.clone();
+				let inner = &*symbolic.inner;
+				let n = inner.nrows();
+				let try_vec = |len: usize, value: T| -> Result<alloc::vec::Vec<T>, FaerError> {
+					let mut v = alloc::vec::Vec::new();
+					v.try_reserve_exact(len).map_err(|_| FaerError::OutOfMemory)?;
+					v.resize(len, value);
+					Ok(v)
+				};
+				let mut numeric = try_vec(inner.len_val(), zero())?;
+				let mut subdiag = try_vec(n, zero())?;
+				let mut perm_fwd = alloc::vec![I::truncate(0); n];
+				let mut perm_inv = alloc::vec![I::truncate(0); n];
+
+				let par = get_global_parallelism();
+				inner.factorize_numeric_intranode_lblt::<T>(
+					&mut numeric,
+					&mut subdiag,
+					&mut perm_fwd,
+					&mut perm_inv,
+					A,
+					Side::Lower,
+					par,
+					MemStack::new(
+						&mut MemBuffer::try_new(inner.factorize_numeric_intranode_lblt_scratch::<T>(par, Default::default()))
+							.map_err(|_| FaerError::OutOfMemory)?,
+					),
+					Default::default(),
+				);
+
+				self.numeric = Some(SparseNumeric::Lblt(Lblt {
+					symbolic,
+					numeric,
+					subdiag,
+					perm_fwd,
+					perm_inv,
+				}));
+			},
+		}
+		Ok(())
+	}
+
+	/// returns the selected factorization
+	#[inline]
+	pub fn method(&self) -> SparseMethod {
+		self.method
+	}
+
+	/// returns the selected fill reducing ordering
+	#[inline]
+	pub fn ordering(&self) -> SparseOrdering {
+		self.ordering
+	}
+
+	/// returns whether the numerical factorization has been computed
+	#[inline]
+	pub fn is_factorized(&self) -> bool {
+		self.numeric.is_some()
+	}
+
+	#[track_caller]
+	fn numeric(&self) -> &SparseNumeric<I, T> {
+		self.numeric
+			.as_ref()
+			.expect("the matrix must be factorized with SparseSolver::factorize before solving")
+	}
+}
+
+impl<I: Index, T: ComplexField> ShapeCore for SparseSolver<I, T> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		match &self.symbolic {
+			SparseSymbolic::Cholesky(symbolic) => symbolic.inner.nrows(),
+			SparseSymbolic::Lu(symbolic) => symbolic.inner.nrows(),
+		}
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.nrows()
+	}
+}
+
+impl<I: Index, T: ComplexField> Lblt<I, T> {
+	#[track_caller]
+	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		let par = get_global_parallelism();
+		let rhs_ncols = rhs.ncols();
+		let n = self.subdiag.len();
+		let inner = &*self.symbolic.inner;
+		linalg_sp::cholesky::IntranodeLbltRef::<'_, I, T>::new(inner, &self.numeric, &self.subdiag, unsafe {
+			PermRef::new_unchecked(&self.perm_fwd, &self.perm_inv, n)
+		})
+		.solve_in_place_with_conj(
+			conj,
+			rhs,
+			par,
+			MemStack::new(&mut MemBuffer::new(inner.solve_in_place_scratch::<T>(rhs_ncols, par))),
+		);
+	}
+}
+
+impl<I: Index, T: ComplexField> SolveCore<T> for SparseSolver<I, T> {
+	#[track_caller]
+	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		match self.numeric() {
+			SparseNumeric::Llt(llt) => llt.solve_in_place_with_conj(conj, rhs),
+			SparseNumeric::Lblt(lblt) => lblt.solve_in_place_with_conj(conj, rhs),
+			SparseNumeric::Lu(lu) => lu.solve_in_place_with_conj(conj, rhs),
+		}
+	}
+
+	#[track_caller]
+	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		match self.numeric() {
+			SparseNumeric::Llt(llt) => llt.solve_transpose_in_place_with_conj(conj, rhs),
+			SparseNumeric::Lblt(lblt) => lblt.solve_in_place_with_conj(conj.compose(Conj::Yes), rhs),
+			SparseNumeric::Lu(lu) => lu.solve_transpose_in_place_with_conj(conj, rhs),
+		}
+	}
+}
+
 impl<I: Index, T: ComplexField, Inner: for<'short> Reborrow<'short, Target = csc_numeric::Ref<'short, I, T>>>
 	csc_numeric::generic::SparseColMat<Inner>
 {
@@ -465,3 +1031,194 @@ impl<I: Index, T: ComplexField, Inner: for<'short> Reborrow<'short, Target = csr
 		Qr::try_new_with_symbolic(SymbolicQr::try_new(this.symbolic())?, this)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::linalg::solvers::Solve;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+
+	fn matrix(n: usize, f: impl Fn(usize, usize) -> Option<f64>) -> SparseColMat<usize, f64> {
+		let mut triplets = alloc::vec::Vec::new();
+		for j in 0..n {
+			for i in 0..n {
+				if let Some(v) = f(i, j) {
+					triplets.push(Triplet::new(i, j, v));
+				}
+			}
+		}
+		SparseColMat::try_new_from_triplets(n, n, &triplets).unwrap()
+	}
+
+	fn check(solver: &SparseSolver<usize, f64>, A: &SparseColMat<usize, f64>) {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = A.nrows();
+		let B = CwiseMatDistribution {
+			nrows: n,
+			ncols: 3,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+
+		let A = A.to_dense();
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+		assert!(&A * solver.solve(&B) ~ B);
+		assert!(A.transpose() * solver.solve_transpose(&B) ~ B);
+	}
+
+	#[test]
+	fn test_sparse_solver_self_adjoint() {
+		let n = 40;
+		let pattern = |i: usize, j: usize| i == j || i.abs_diff(j) == 1 || (i + j) % 7 == 0;
+
+		// positive definite
+		let A = matrix(n, |i, j| {
+			if i == j {
+				Some(8.0)
+			} else if pattern(i, j) {
+				Some(-1.0 / (1.0 + (i + j) as f64))
+			} else {
+				None
+			}
+		});
+		let solver = SparseSolver::new(A.rb(), Default::default()).unwrap();
+		assert!(solver.method() == SparseMethod::Llt);
+		check(&solver, &A);
+
+		// positive diagonal, but indefinite: falls back to the LBL^T factorization
+		let A = matrix(n, |i, j| {
+			if i == j {
+				Some(1.0)
+			} else if pattern(i, j) {
+				Some(3.0 + (i + j) as f64 / n as f64)
+			} else {
+				None
+			}
+		});
+		let mut solver = SparseSolver::analyze(A.rb(), Default::default()).unwrap();
+		assert!(solver.method() == SparseMethod::Llt);
+		solver.factorize(A.rb()).unwrap();
+		assert!(solver.method() == SparseMethod::Lblt);
+		check(&solver, &A);
+
+		// the definiteness hint is honored
+		let params = SparseSolverParams {
+			definiteness: Definiteness::PositiveDefinite,
+			..Default::default()
+		};
+		assert!(matches!(
+			SparseSolver::new(A.rb(), params),
+			Err(SparseSolverError::NonPositivePivot { .. })
+		));
+	}
+
+	#[test]
+	fn test_sparse_solver_rcm() {
+		// scrambled sparse banded matrix
+		let n = 200;
+		let b = 4;
+		let scramble = |i: usize| (37 * i) % n;
+		let A = matrix(n, |i, j| {
+			let (i, j) = (scramble(i), scramble(j));
+			let (lo, hi) = (Ord::min(i, j), Ord::max(i, j));
+			if i == j {
+				Some(4.0 * b as f64)
+			} else if hi - lo == 1 || (hi - lo <= b && lo % 3 == 0) {
+				Some(-1.0)
+			} else {
+				None
+			}
+		});
+		let solver = SparseSolver::new(A.rb(), Default::default()).unwrap();
+		assert!(solver.method() == SparseMethod::Llt);
+		assert!(solver.ordering() == SparseOrdering::Rcm);
+		check(&solver, &A);
+	}
+
+	#[test]
+	fn test_sparse_solver_general() {
+		let n = 40;
+		let values = |shift: f64| {
+			matrix(n, move |i, j| {
+				if i == j {
+					Some(4.0 + shift)
+				} else if j == i + 1 || (i * 3 + j) % 11 == 0 {
+					Some(1.0 + shift * i as f64 / n as f64)
+				} else {
+					None
+				}
+			})
+		};
+
+		let A = values(0.0);
+		let mut solver = SparseSolver::analyze(A.rb(), Default::default()).unwrap();
+		assert!(solver.method() == SparseMethod::Lu);
+		assert!(solver.ordering() == SparseOrdering::Colamd);
+		assert!(!solver.is_factorized());
+
+		// the symbolic analysis is reused when only the values change
+		for shift in [0.0, 0.5, 2.0] {
+			let A = values(shift);
+			solver.factorize(A.rb()).unwrap();
+			check(&solver, &A);
+		}
+
+		// self-adjoint factorizations are rejected for matrices that are not self-adjoint
+		let params = SparseSolverParams {
+			method: Some(SparseMethod::Llt),
+			..Default::default()
+		};
+		assert!(matches!(SparseSolver::analyze(A.rb(), params), Err(SparseSolverError::InvalidMatrix)));
+	}
+
+	#[test]
+	fn test_symbolic_reuse() {
+		fn assert_send_sync<T: Send + Sync>() {}
+		assert_send_sync::<SymbolicLlt<usize>>();
+		assert_send_sync::<SymbolicLu<usize>>();
+		assert_send_sync::<SymbolicQr<usize>>();
+		assert_send_sync::<SparseSolver<usize, f64>>();
+
+		let n = 30;
+		let values = |shift: f64| {
+			matrix(n, move |i, j| {
+				if i == j {
+					Some(6.0 + shift)
+				} else if i.abs_diff(j) == 1 || (i + j) % 5 == 0 {
+					Some(-0.5 - 0.1 * shift * (i + j) as f64 / n as f64)
+				} else {
+					None
+				}
+			})
+		};
+
+		let A = values(0.0);
+		let llt = SymbolicLlt::try_new(A.symbolic(), Side::Lower).unwrap();
+		let lu = SymbolicLu::try_new(A.symbolic()).unwrap();
+
+		// each thread factorizes its own matrices with the shared symbolic factorizations
+		std::thread::scope(|scope| {
+			for t in 0..4 {
+				let llt = llt.clone();
+				let lu = lu.clone();
+				scope.spawn(move || {
+					let mut llt = Llt::try_new_with_symbolic(llt, values(t as f64).rb(), Side::Lower).unwrap();
+					let mut lu = Lu::try_new_with_symbolic(lu, values(t as f64).rb()).unwrap();
+					for step in 1..4 {
+						let A = values(t as f64 + step as f64 / 4.0);
+						llt.refactorize(A.rb(), Side::Lower).unwrap();
+						lu.refactorize(A.rb()).unwrap();
+
+						let B = Mat::from_fn(n, 2, |i, j| (i + j) as f64);
+						let A = A.to_dense();
+						let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+						assert!(&A * llt.solve(&B) ~ B);
+						assert!(&A * lu.solve(&B) ~ B);
+					}
+				});
+			}
+		});
+	}
+}
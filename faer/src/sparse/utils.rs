@@ -13,6 +13,49 @@ pub fn sort_indices<I: Index, T>(col_ptr: &[I], col_nnz: Option<&[I]>, row_idx:
 	}
 }
 
+/// sorts `indices` and `values` simultaneously so that `indices` is nondecreasing, using the given
+/// parallelism.
+///
+/// this is useful for ordering large sets of triplets, e.g., by their linearized position, before
+/// assembling a sparse matrix.
+///
+/// # panics
+/// panics if `indices` and `values` don't have the same length.
+pub fn par_sort_indices<I: Index, T: Send>(indices: &mut [I], values: &mut [T], par: Par) {
+	assert!(indices.len() == values.len());
+	unsafe { crate::sort::par_sort_indices(indices, values, par) };
+}
+
+/// sorts `major`, `minor` and `values` simultaneously so that the pairs `(major, minor)` are in
+/// lexicographic order.
+///
+/// for example, passing the column indices, row indices and values of a list of triplets orders
+/// them by column, then by row.
+///
+/// # panics
+/// panics if `major`, `minor` and `values` don't have the same length.
+pub fn sort_indices3<I: Index, T>(major: &mut [I], minor: &mut [I], values: &mut [T]) {
+	assert!(all(major.len() == minor.len(), major.len() == values.len()));
+	unsafe { crate::sort::sort_indices3(major, minor, values) };
+}
+
+/// returns the memory requirements of [`sort_indices_stable`].
+pub fn sort_indices_stable_scratch<I: Index, T>(len: usize) -> StackReq {
+	crate::sort::sort_indices_stable_scratch::<I, T>(len)
+}
+
+/// sorts `indices` and `values` simultaneously so that `indices` is nondecreasing, preserving the
+/// relative order of entries with equal indices.
+///
+/// this is useful when duplicate entries must be combined in the order they were inserted.
+///
+/// # panics
+/// panics if `indices` and `values` don't have the same length.
+pub fn sort_indices_stable<I: Index, T>(indices: &mut [I], values: &mut [T], stack: &mut MemStack) {
+	assert!(indices.len() == values.len());
+	unsafe { crate::sort::sort_indices_stable(indices, values, stack) };
+}
+
 /// sorts and deduplicates `row_indices` and `values` simultaneously so that `row_indices` is
 /// nonincreasing and contains no duplicate indices.
 pub fn sort_dedup_indices<I: Index, T: ComplexField>(col_ptr: &[I], col_nnz: &mut [I], row_idx: &mut [I], val: &mut [T]) {
@@ -0,0 +1,362 @@
+//! low-rank matrix completion
+//!
+//! given the observed entries of a matrix $X$, indexed by a set $\Omega$, [`soft_impute`] looks for
+//! a low-rank matrix $Z$ that minimizes
+//! $$\frac{1}{2} \sum_{(i, j) \in \Omega} |X_{ij} - Z_{ij}|^2 + \lambda \|Z\|_*,$$
+//! where $\|Z\|_*$ is the nuclear norm, i.e., the sum of the singular values of $Z$. this is the
+//! usual model for recommender systems, where the rows are users, the columns are items, and only
+//! a small fraction of the ratings are known
+//!
+//! the soft-impute algorithm of mazumder, hastie and tibshirani repeatedly fills the missing
+//! entries with the current estimate, and replaces the estimate with the soft-thresholded singular
+//! value decomposition of the filled matrix
+//! $$Z_{k+1} = S_\lambda(P_\Omega(X) + P_\Omega^\perp(Z_k)),$$
+//! where $S_\lambda$ subtracts $\lambda$ from the singular values and drops the ones that become
+//! negative. only the leading `max_rank` singular triplets are computed at each step, with the
+//! randomized range finder, so larger values of $\lambda$ give lower rank solutions that are
+//! cheaper to compute
+//!
+//! # example
+//! ```
+//! use faer::Mat;
+//! use faer::stats::completion::{SoftImputeParams, soft_impute};
+//! use faer::stats::prelude::*;
+//!
+//! let rng = &mut StdRng::seed_from_u64(0);
+//! let (m, n) = (100, 80);
+//!
+//! // rank 2 ratings, of which about half are observed
+//! let X = Mat::<f64>::random_normal(rng, m, 2) * Mat::<f64>::random_normal(rng, 2, n);
+//! let observed = Mat::from_fn(m, n, |_, _| rng.r#gen::<bool>());
+//!
+//! let params = SoftImputeParams {
+//! 	lambda: 1.0,
+//! 	max_rank: 5,
+//! 	..Default::default()
+//! };
+//! let fit = soft_impute(X.as_ref(), observed.as_ref(), params, rng, faer::Par::Seq).unwrap();
+//! assert!(fit.rank() == 2);
+//!
+//! // the missing ratings are recovered, up to the bias caused by the regularization
+//! let Z = fit.complete();
+//! assert!((&Z - &X).norm_l2() < 0.05 * X.norm_l2());
+//! ```
+
+use crate::internal_prelude::*;
+use crate::linalg::svd::SvdError;
+use crate::{assert, get_global_parallelism};
+use rand::Rng;
+
+/// soft-impute parameters
+#[derive(Copy, Clone, Debug)]
+pub struct SoftImputeParams<T: RealField> {
+	/// regularization parameter, subtracted from the singular values at each step. a value of zero
+	/// fits the observed entries as closely as possible with a matrix of rank `max_rank`
+	pub lambda: T,
+	/// number of singular triplets computed at each step, which bounds the rank of the solution
+	pub max_rank: usize,
+	/// number of additional random samples used by the range finder
+	pub oversampling: usize,
+	/// number of power iterations used by the range finder, which improve the accuracy of the
+	/// singular triplets when the singular values decay slowly
+	pub power_iters: usize,
+	/// the iteration stops once $\|Z_{k+1} - Z_k\|_F^2 \le \text{rel\_tolerance} \cdot \|Z_k\|_F^2$
+	pub rel_tolerance: T,
+	/// maximum number of iterations
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for SoftImputeParams<T> {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			lambda: zero(),
+			max_rank: 10,
+			oversampling: 8,
+			power_iters: 2,
+			rel_tolerance: from_f64(1e-10),
+			max_iters: 500,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// algorithm error
+#[derive(Copy, Clone, Debug)]
+pub enum SoftImputeError<T> {
+	/// convergence failure
+	NoConvergence {
+		/// relative change of the estimate at the final step
+		rel_change: T,
+	},
+	/// failure of the singular value decomposition of the filled matrix
+	Svd(SvdError),
+}
+
+/// low-rank completion $Z = U S V^H$ of a partially observed matrix
+#[derive(Clone, Debug)]
+pub struct SoftImpute<T: ComplexField> {
+	U: Mat<T>,
+	S: Col<T::Real>,
+	V: Mat<T>,
+	iter_count: usize,
+}
+
+impl<T: ComplexField> SoftImpute<T> {
+	/// returns the rank of the completed matrix
+	#[inline]
+	pub fn rank(&self) -> usize {
+		self.S.nrows()
+	}
+
+	/// returns the left singular vectors of the completed matrix
+	#[inline]
+	pub fn U(&self) -> MatRef<'_, T> {
+		self.U.as_ref()
+	}
+
+	/// returns the singular values of the completed matrix, in nonincreasing order
+	#[inline]
+	pub fn S(&self) -> ColRef<'_, T::Real> {
+		self.S.as_ref()
+	}
+
+	/// returns the right singular vectors of the completed matrix
+	#[inline]
+	pub fn V(&self) -> MatRef<'_, T> {
+		self.V.as_ref()
+	}
+
+	/// returns the number of iterations that were performed
+	#[inline]
+	pub fn iter_count(&self) -> usize {
+		self.iter_count
+	}
+
+	/// returns the entry of the completed matrix at position $(i, j)$
+	#[track_caller]
+	#[math]
+	pub fn get(&self, i: usize, j: usize) -> T {
+		assert!(all(i < self.U.nrows(), j < self.V.nrows()));
+		let mut acc = zero::<T>();
+		for k in 0..self.rank() {
+			acc = acc + mul_real(self.U[(i, k)] * conj(self.V[(j, k)]), self.S[k]);
+		}
+		acc
+	}
+
+	/// returns the completed matrix
+	pub fn complete(&self) -> Mat<T> {
+		low_rank_product(self.U.as_ref(), self.S.as_ref(), self.V.as_ref(), get_global_parallelism())
+	}
+}
+
+/// returns $U \operatorname{diag}(S) V^H$
+#[math]
+fn low_rank_product<T: ComplexField>(U: MatRef<'_, T>, S: ColRef<'_, T::Real>, V: MatRef<'_, T>, par: Par) -> Mat<T> {
+	let mut US = U.to_owned();
+	for k in 0..S.nrows() {
+		zip!(US.col_mut(k)).for_each(|unzip!(u)| *u = mul_real(*u, S[k]));
+	}
+	let mut Z = Mat::<T>::zeros(U.nrows(), V.nrows());
+	linalg::matmul::matmul(Z.as_mut(), Accum::Replace, US.as_ref(), V.adjoint(), one(), par);
+	Z
+}
+
+/// returns the leading `rank` singular triplets of `M`, computed with the randomized range finder
+/// if `rank + oversampling` is smaller than the dimensions of `M`, and with a dense singular value
+/// decomposition otherwise
+fn truncated_svd<T: ComplexField>(
+	M: MatRef<'_, T>,
+	rank: usize,
+	oversampling: usize,
+	power_iters: usize,
+	rng: &mut dyn rand::RngCore,
+	par: Par,
+) -> Result<(Mat<T>, Col<T::Real>, Mat<T>), SvdError> {
+	let (m, n) = M.shape();
+	let size = Ord::min(m, n);
+	let rank = Ord::min(rank, size);
+	let s = Ord::min(rank.saturating_add(oversampling), size);
+
+	if s == size {
+		let svd = M.thin_svd()?;
+		let S = svd.S().column_vector();
+		return Ok((
+			svd.U().subcols(0, rank).to_owned(),
+			Col::from_fn(rank, |k| real(&S[k])),
+			svd.V().subcols(0, rank).to_owned(),
+		));
+	}
+
+	let Omega = Mat::<T>::random_normal(rng, n, s);
+	let mut Y = Mat::<T>::zeros(m, s);
+	let mut W = Mat::<T>::zeros(n, s);
+
+	linalg::matmul::matmul(Y.as_mut(), Accum::Replace, M, Omega.as_ref(), one(), par);
+	let mut Q = Y.qr().compute_thin_Q();
+	for _ in 0..power_iters {
+		linalg::matmul::matmul(W.as_mut(), Accum::Replace, M.adjoint(), Q.as_ref(), one(), par);
+		let P = W.qr().compute_thin_Q();
+		linalg::matmul::matmul(Y.as_mut(), Accum::Replace, M, P.as_ref(), one(), par);
+		Q = Y.qr().compute_thin_Q();
+	}
+
+	// M ≈ Q Q^H M = Q W^H, with W = M^H Q = X S Y^H, so that M ≈ (Q Y) S X^H
+	linalg::matmul::matmul(W.as_mut(), Accum::Replace, M.adjoint(), Q.as_ref(), one(), par);
+	let svd = W.thin_svd()?;
+	let S = svd.S().column_vector();
+
+	let mut U = Mat::<T>::zeros(m, rank);
+	linalg::matmul::matmul(U.as_mut(), Accum::Replace, Q.as_ref(), svd.V().subcols(0, rank), one(), par);
+	Ok((U, Col::from_fn(rank, |k| real(&S[k])), svd.U().subcols(0, rank).to_owned()))
+}
+
+/// computes a low-rank completion of the matrix $X$, whose entries are only known at the positions
+/// where `observed` is `true`, with the soft-impute algorithm
+///
+/// the entries of $X$ at the unobserved positions are ignored
+#[track_caller]
+pub fn soft_impute<T: ComplexField, C: Conjugate<Canonical = T>>(
+	X: MatRef<'_, C>,
+	observed: MatRef<'_, bool>,
+	params: SoftImputeParams<T::Real>,
+	rng: &mut (impl ?Sized + Rng),
+	par: Par,
+) -> Result<SoftImpute<T>, SoftImputeError<T::Real>> {
+	#[math]
+	fn implementation<T: ComplexField>(
+		X: MatRef<'_, T>,
+		conj_X: Conj,
+		observed: MatRef<'_, bool>,
+		params: &SoftImputeParams<T::Real>,
+		rng: &mut dyn rand::RngCore,
+		par: Par,
+	) -> Result<SoftImpute<T>, SoftImputeError<T::Real>> {
+		let (m, n) = X.shape();
+		let lambda = copy(params.lambda);
+
+		let mut Z = Mat::<T>::zeros(m, n);
+		let mut M = Mat::<T>::zeros(m, n);
+		let mut rel_change = infinity::<T::Real>();
+
+		for iter in 0..params.max_iters {
+			// fill the missing entries with the current estimate
+			for j in 0..n {
+				for i in 0..m {
+					M[(i, j)] = if observed[(i, j)] {
+						if conj_X == Conj::Yes { conj(X[(i, j)]) } else { copy(X[(i, j)]) }
+					} else {
+						copy(Z[(i, j)])
+					};
+				}
+			}
+
+			let (U, S, V) =
+				truncated_svd(M.as_ref(), params.max_rank, params.oversampling, params.power_iters, rng, par).map_err(SoftImputeError::Svd)?;
+
+			// soft thresholding of the singular values
+			let mut rank = 0;
+			while rank < S.nrows() && S[rank] > lambda {
+				rank += 1;
+			}
+			let fit = SoftImpute {
+				U: U.subcols(0, rank).to_owned(),
+				S: Col::from_fn(rank, |k| S[k] - lambda),
+				V: V.subcols(0, rank).to_owned(),
+				iter_count: iter + 1,
+			};
+
+			let Z_new = low_rank_product(fit.U.as_ref(), fit.S.as_ref(), fit.V.as_ref(), par);
+			let mut diff = zero::<T::Real>();
+			zip!(&Z_new, &Z).for_each(|unzip!(new, old)| diff = diff + abs2(*new - *old));
+			let norm = Z.squared_norm_l2();
+			Z = Z_new;
+
+			rel_change = diff / max(norm, min_positive::<T::Real>());
+			if rel_change <= params.rel_tolerance {
+				return Ok(fit);
+			}
+		}
+
+		Err(SoftImputeError::NoConvergence { rel_change })
+	}
+
+	assert!(all(observed.nrows() == X.nrows(), observed.ncols() == X.ncols()));
+	implementation(
+		X.canonical(),
+		Conj::get::<C>(),
+		observed,
+		&params,
+		&mut { rng } as &mut dyn rand::RngCore,
+		par,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_soft_impute() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n, r) = (60, 40, 3);
+
+		let X = Mat::<c64>::random_normal(rng, m, r) * Mat::<c64>::random_normal(rng, r, n);
+		let observed = Mat::from_fn(m, n, |_, _| rng.gen_range(0.0..1.0) < 0.6);
+
+		let params = SoftImputeParams {
+			lambda: 1.0,
+			max_rank: 6,
+			rel_tolerance: 1e-14,
+			..Default::default()
+		};
+
+		// randomized and dense singular value decompositions
+		for max_rank in [6, n] {
+			let params = SoftImputeParams { max_rank, ..params };
+			let fit = soft_impute(X.as_ref(), observed.as_ref(), params, rng, Par::Seq).unwrap();
+			assert!(fit.rank() == r);
+
+			// the regularization biases the solution slightly
+			let Z = fit.complete();
+			assert!((&Z - &X).norm_l2() < 0.05 * X.norm_l2());
+			assert!((fit.get(5, 7) - Z[(5, 7)]).norm() < 1e-12);
+
+			// the solution is a fixed point of the iteration
+			let M = Mat::from_fn(m, n, |i, j| if observed[(i, j)] { X[(i, j)] } else { Z[(i, j)] });
+			let svd = M.thin_svd().unwrap();
+			let S = Col::<f64>::from_fn(r, |k| svd.S()[k].re - params.lambda);
+			let Z_next = low_rank_product(svd.U().subcols(0, r), S.as_ref(), svd.V().subcols(0, r), Par::Seq);
+			assert!(svd.S()[r].re < params.lambda);
+			assert!((&Z_next - &Z).norm_l2() < 1e-4 * Z.norm_l2());
+
+			// the singular vectors are orthonormal
+			let I = Mat::<c64>::identity(r, r);
+			assert!((fit.U().adjoint() * fit.U() - &I).norm_max() < 1e-10);
+			assert!((fit.V().adjoint() * fit.V() - &I).norm_max() < 1e-10);
+
+			// conjugated input
+			let fit = soft_impute(X.conjugate(), observed.as_ref(), params, rng, Par::Seq).unwrap();
+			assert!((fit.complete() - Z.conjugate()).norm_l2() < 1e-4 * Z.norm_l2());
+		}
+
+		// strong regularization shrinks the solution to zero
+		let params = SoftImputeParams { lambda: 1e6, ..params };
+		let fit = soft_impute(X.as_ref(), observed.as_ref(), params, rng, Par::Seq).unwrap();
+		assert!(fit.rank() == 0);
+		assert!(fit.complete().norm_max() == 0.0);
+
+		let params = SoftImputeParams { max_iters: 1, ..params };
+		let params = SoftImputeParams { lambda: 0.0, ..params };
+		assert!(matches!(
+			soft_impute(X.as_ref(), observed.as_ref(), params, rng, Par::Seq),
+			Err(SoftImputeError::NoConvergence { .. })
+		));
+	}
+}
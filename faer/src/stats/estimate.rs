@@ -0,0 +1,811 @@
+//! randomized norm, condition number and trace estimators
+//!
+//! the estimators only access the matrix through its action on vectors, so they can be used with
+//! any [`LinOp`], including implicit operators such as the inverse of a factorized matrix (see
+//! [`InverseOp`]) or other functions of a matrix, whose entries are never formed explicitly
+//!
+//! the norm estimates are lower bounds on the exact values, that are usually within a small factor
+//! of them, and often exact
+//!
+//! # example
+//! estimating the $1$-norm condition number of a matrix
+//! ```
+//! use faer::prelude::*;
+//! use faer::stats::estimate::{InverseOp, operator_norm_l1_est, operator_norm_l1_est_scratch};
+//! use faer::{Par, mat};
+//! use dyn_stack::{MemBuffer, MemStack};
+//! use rand::prelude::*;
+//!
+//! let A = mat![[4.0, 1.0, 0.0], [1.0, 3.0, 1.0], [0.0, 1.0, 2.0]];
+//! let lu = A.partial_piv_lu();
+//! let A_inv = InverseOp(&lu);
+//!
+//! let rng = &mut StdRng::seed_from_u64(0);
+//! let mut mem = MemBuffer::new(operator_norm_l1_est_scratch(A_inv, default(), Par::Seq));
+//!
+//! let norm = operator_norm_l1_est(A.as_ref(), default(), rng, Par::Seq, MemStack::new(&mut mem));
+//! let inv_norm = operator_norm_l1_est(A_inv, default(), rng, Par::Seq, MemStack::new(&mut mem));
+//! let cond = norm * inv_norm;
+//! # assert!(cond > 1.0);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::linalg::solvers::SolveCore;
+use crate::matrix_free::{BiLinOp, LinOp};
+use rand::Rng;
+
+/// spectral norm estimator parameters
+#[derive(Copy, Clone, Debug)]
+pub struct SpectralNormEstParams<T: RealField> {
+	/// the iteration stops once the relative change of the estimate between two steps is below
+	/// this threshold
+	pub rel_tolerance: T,
+	/// maximum number of iterations
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// $1$-norm estimator parameters
+#[derive(Copy, Clone, Debug)]
+pub struct NormL1EstParams {
+	/// number of vectors that are iterated simultaneously. larger values give more reliable
+	/// estimates, at the cost of more operator applications per iteration
+	pub block_size: usize,
+	/// maximum number of iterations
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// trace and frobenius norm estimator parameters
+#[derive(Copy, Clone, Debug)]
+pub struct TraceEstParams {
+	/// number of random probe vectors. the standard deviation of the estimate decreases like the
+	/// inverse square root of this number
+	pub n_samples: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// log-determinant estimator parameters
+#[derive(Copy, Clone, Debug)]
+pub struct LogdetEstParams {
+	/// number of random probe vectors. the standard deviation of the estimate decreases like the
+	/// inverse square root of this number
+	pub n_samples: usize,
+	/// number of lanczos steps per probe vector, i.e., the number of nodes of the gauss quadrature
+	/// rule that approximates each quadratic form
+	pub lanczos_steps: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for SpectralNormEstParams<T> {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			rel_tolerance: from_f64::<T>(1e-4),
+			max_iters: 100,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+impl Default for NormL1EstParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			block_size: 2,
+			max_iters: 5,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+impl Default for TraceEstParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			n_samples: 64,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+impl Default for LogdetEstParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			n_samples: 32,
+			lanczos_steps: 32,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// linear operator applying the inverse of a matrix, through one of its factorizations
+///
+/// this can be used to estimate the norm of the inverse, and therefore the condition number of a
+/// matrix
+#[derive(Debug)]
+pub struct InverseOp<'a, S: ?Sized>(pub &'a S);
+
+impl<S: ?Sized> Copy for InverseOp<'_, S> {}
+impl<S: ?Sized> Clone for InverseOp<'_, S> {
+	#[inline]
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<T: ComplexField, S: ?Sized + Sync + core::fmt::Debug + SolveCore<T>> LinOp<T> for InverseOp<'_, S> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.0.ncols()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.0.nrows()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.0.solve_in_place_with_conj(Conj::No, out);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.0.solve_in_place_with_conj(Conj::Yes, out);
+	}
+}
+
+impl<T: ComplexField, S: ?Sized + Sync + core::fmt::Debug + SolveCore<T>> BiLinOp<T> for InverseOp<'_, S> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.0.solve_transpose_in_place_with_conj(Conj::No, out);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.0.solve_transpose_in_place_with_conj(Conj::Yes, out);
+	}
+}
+
+/// fills `dst` with independent random signs
+#[math]
+fn rademacher<T: ComplexField>(dst: MatMut<'_, T>, rng: &mut (impl ?Sized + Rng)) {
+	let mut dst = dst;
+	for j in 0..dst.ncols() {
+		for i in 0..dst.nrows() {
+			dst[(i, j)] = if rng.r#gen::<bool>() { one() } else { -one::<T>() };
+		}
+	}
+}
+
+/// computes the size and alignment of required workspace for estimating the spectral norm of an
+/// operator
+pub fn spectral_norm_est_scratch<T: ComplexField>(op: impl BiLinOp<T>, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(A: &dyn BiLinOp<T>, par: Par) -> StackReq {
+		let (m, n) = (A.nrows(), A.ncols());
+		StackReq::all_of(&[
+			temp_mat_scratch::<T>(n, 1), // x
+			temp_mat_scratch::<T>(m, 1), // y
+			StackReq::any_of(&[A.apply_scratch(1, par), A.transpose_apply_scratch(1, par)]),
+		])
+	}
+	implementation(&op, par)
+}
+
+/// estimates the spectral norm $\|A\|_2$ of the operator, i.e., its largest singular value
+///
+/// the estimate is computed with the power method applied to $A^H A$, starting from a random
+/// vector, and is a lower bound on the exact value
+#[track_caller]
+pub fn spectral_norm_est<T: ComplexField>(
+	op: impl BiLinOp<T>,
+	params: SpectralNormEstParams<T::Real>,
+	rng: &mut (impl ?Sized + Rng),
+	par: Par,
+	stack: &mut MemStack,
+) -> T::Real {
+	#[math]
+	fn implementation<T: ComplexField>(
+		A: &dyn BiLinOp<T>,
+		params: SpectralNormEstParams<T::Real>,
+		rng: &mut dyn rand::RngCore,
+		par: Par,
+		stack: &mut MemStack,
+	) -> T::Real {
+		let (m, n) = (A.nrows(), A.ncols());
+		if m == 0 || n == 0 {
+			return zero();
+		}
+
+		let (mut x, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut x = x.as_mat_mut();
+		let (mut y, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, 1, stack) };
+		let mut y = y.as_mat_mut();
+
+		rademacher(x.rb_mut(), rng);
+		let norm = x.norm_l2();
+		x *= Scale(from_real::<T>(&recip(norm)));
+
+		let mut est = zero::<T::Real>();
+		for iter in 0..params.max_iters {
+			A.apply(y.rb_mut(), x.rb(), par, stack);
+			let y_norm = y.norm_l2();
+			if y_norm == zero::<T::Real>() {
+				// `x` is in the null space of the operator, which can only happen by chance if
+				// the operator is zero
+				break;
+			}
+
+			A.adjoint_apply(x.rb_mut(), y.rb(), par, stack);
+			let x_norm = x.norm_l2();
+			x *= Scale(from_real::<T>(&recip(x_norm)));
+
+			// `‖A^H A x‖ / ‖A x‖` is a tighter lower bound than `‖A x‖` for the unit vector `x`
+			let new_est = x_norm / y_norm;
+			let converged = iter > 0 && abs(new_est - est) <= params.rel_tolerance * new_est;
+			est = new_est;
+			if converged {
+				break;
+			}
+		}
+		est
+	}
+
+	implementation(&op, params, &mut { rng } as &mut dyn rand::RngCore, par, stack)
+}
+
+/// computes the size and alignment of required workspace for estimating the $1$-norm of an
+/// operator
+pub fn operator_norm_l1_est_scratch<T: ComplexField>(op: impl BiLinOp<T>, params: NormL1EstParams, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(A: &dyn BiLinOp<T>, params: NormL1EstParams, par: Par) -> StackReq {
+		let (m, n) = (A.nrows(), A.ncols());
+		let t = Ord::max(1, Ord::min(params.block_size, n));
+		StackReq::all_of(&[
+			temp_mat_scratch::<T>(n, t),       // x | z
+			temp_mat_scratch::<T>(m, t),       // y | s
+			temp_mat_scratch::<T::Real>(n, 1), // h
+			StackReq::new::<usize>(n),         // ind
+			StackReq::new::<bool>(n),          // hist
+			StackReq::any_of(&[A.apply_scratch(t, par), A.transpose_apply_scratch(t, par)]),
+		])
+	}
+	implementation(&op, params, par)
+}
+
+/// estimates the $1$-norm $\|A\|_1$ of the operator, i.e., its maximum absolute column sum
+///
+/// the estimate is computed with the block algorithm of higham and tisseur, where the columns of
+/// the starting block other than the first one are chosen randomly, and is a lower bound on the
+/// exact value. it is exact in most cases, and rarely more than a factor of $3$ away from it
+///
+/// to estimate $\|A^{-1}\|_1$, the operator can be an [`InverseOp`] wrapping a factorization of
+/// $A$
+#[track_caller]
+pub fn operator_norm_l1_est<T: ComplexField>(
+	op: impl BiLinOp<T>,
+	params: NormL1EstParams,
+	rng: &mut (impl ?Sized + Rng),
+	par: Par,
+	stack: &mut MemStack,
+) -> T::Real {
+	#[math]
+	fn implementation<T: ComplexField>(
+		A: &dyn BiLinOp<T>,
+		params: NormL1EstParams,
+		rng: &mut dyn rand::RngCore,
+		par: Par,
+		stack: &mut MemStack,
+	) -> T::Real {
+		let (m, n) = (A.nrows(), A.ncols());
+		if m == 0 || n == 0 {
+			return zero();
+		}
+		let t = Ord::max(1, Ord::min(params.block_size, n));
+
+		let (mut x, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, t, stack) };
+		let mut x = x.as_mat_mut();
+		let (mut y, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, t, stack) };
+		let mut y = y.as_mat_mut();
+		let (mut h, stack) = unsafe { temp_mat_uninit::<T::Real, _, _>(n, 1, stack) };
+		let mut h = h.as_mat_mut().col_mut(0);
+		let (mut ind, stack) = stack.make_with(n, |i| i);
+		let (mut hist, stack) = stack.make_with(n, |_| false);
+
+		// the first column is the uniform vector, and the other ones are random sign vectors,
+		// resampled if they're parallel to a previous one since they wouldn't bring any new
+		// information
+		x.rb_mut().col_mut(0).fill(from_f64::<T>(1.0));
+		for j in 1..t {
+			for _ in 0..8 {
+				rademacher(x.rb_mut().get_mut(.., j..j + 1), rng);
+				let parallel = (0..j).any(|k| {
+					let dot = x.rb().col(k).adjoint() * x.rb().col(j);
+					abs(dot) == from_f64::<T::Real>(n as f64)
+				});
+				if !parallel {
+					break;
+				}
+			}
+		}
+		x *= Scale(from_f64::<T>(1.0 / n as f64));
+
+		let mut est_old = zero::<T::Real>();
+		let mut ind_best = 0usize;
+		let mut chosen = 0;
+
+		for k in 0..params.max_iters {
+			A.apply(y.rb_mut(), x.rb(), par, stack);
+
+			let mut est = zero::<T::Real>();
+			let mut best = 0;
+			for j in 0..t {
+				let norm = y.rb().col(j).norm_l1();
+				if norm > est {
+					est = norm;
+					best = j;
+				}
+			}
+
+			if k > 0 && est <= est_old {
+				break;
+			}
+			est_old = copy(est);
+			if k > 0 {
+				ind_best = ind[best];
+			}
+			if k + 1 == params.max_iters {
+				break;
+			}
+
+			// s := sign(y)
+			for j in 0..t {
+				for i in 0..m {
+					let v = copy(y[(i, j)]);
+					let a = abs(v);
+					y[(i, j)] = if a == zero::<T::Real>() { one() } else { mul_real(v, recip(a)) };
+				}
+			}
+
+			// z := A^H s
+			A.adjoint_apply(x.rb_mut(), y.rb(), par, stack);
+
+			for i in 0..n {
+				let mut max = zero::<T::Real>();
+				for j in 0..t {
+					let a = abs(x[(i, j)]);
+					if a > max {
+						max = a;
+					}
+				}
+				h[i] = max;
+			}
+
+			if k > 0 {
+				let mut h_max = zero::<T::Real>();
+				for i in 0..n {
+					if h[i] > h_max {
+						h_max = copy(h[i]);
+					}
+				}
+				if h[ind_best] == h_max {
+					break;
+				}
+			}
+
+			for (i, p) in ind.iter_mut().enumerate() {
+				*p = i;
+			}
+			ind.sort_unstable_by(|&a, &b| {
+				if h[a] > h[b] {
+					core::cmp::Ordering::Less
+				} else if h[a] < h[b] {
+					core::cmp::Ordering::Greater
+				} else {
+					a.cmp(&b)
+				}
+			});
+
+			// the next block is made of the unit vectors with the largest gradient that haven't
+			// been tried yet
+			if ind[..t].iter().all(|&i| hist[i]) {
+				break;
+			}
+			chosen = 0;
+			for idx in 0..n {
+				if chosen == t {
+					break;
+				}
+				let i = ind[idx];
+				if !hist[i] {
+					ind[chosen] = i;
+					hist[i] = true;
+					chosen += 1;
+				}
+			}
+
+			x.fill(zero());
+			for j in 0..chosen {
+				x[(ind[j], j)] = one();
+			}
+		}
+		_ = chosen;
+
+		est_old
+	}
+
+	implementation(&op, params, &mut { rng } as &mut dyn rand::RngCore, par, stack)
+}
+
+/// computes the size and alignment of required workspace for estimating the trace or the
+/// frobenius norm of an operator
+pub fn trace_est_scratch<T: ComplexField>(op: impl LinOp<T>, params: TraceEstParams, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(A: &dyn LinOp<T>, params: TraceEstParams, par: Par) -> StackReq {
+		let (m, n) = (A.nrows(), A.ncols());
+		let s = params.n_samples;
+		StackReq::all_of(&[
+			temp_mat_scratch::<T>(n, s), // z
+			temp_mat_scratch::<T>(m, s), // A z
+			A.apply_scratch(s, par),
+		])
+	}
+	implementation(&op, params, par)
+}
+
+/// estimates the trace of the operator with hutchinson's method, using random sign vectors
+///
+/// the estimate is unbiased, and its variance is $2 \sum_{i \neq j} |a_{ij}|^2$ divided by the
+/// number of samples
+///
+/// # panics
+/// panics if the operator is not square
+#[track_caller]
+pub fn trace_est<T: ComplexField>(op: impl LinOp<T>, params: TraceEstParams, rng: &mut (impl ?Sized + Rng), par: Par, stack: &mut MemStack) -> T {
+	#[math]
+	fn implementation<T: ComplexField>(A: &dyn LinOp<T>, params: TraceEstParams, rng: &mut dyn rand::RngCore, par: Par, stack: &mut MemStack) -> T {
+		let n = A.nrows();
+		assert!(A.nrows() == A.ncols());
+		let s = params.n_samples;
+		if n == 0 || s == 0 {
+			return zero();
+		}
+
+		let (mut z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, s, stack) };
+		let mut z = z.as_mat_mut();
+		let (mut Az, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, s, stack) };
+		let mut Az = Az.as_mat_mut();
+
+		rademacher(z.rb_mut(), rng);
+		A.apply(Az.rb_mut(), z.rb(), par, stack);
+
+		let mut acc = zero::<T>();
+		for j in 0..s {
+			acc = acc + z.rb().col(j).adjoint() * Az.rb().col(j);
+		}
+		mul_real(acc, from_f64::<T::Real>(1.0 / s as f64))
+	}
+
+	implementation(&op, params, &mut { rng } as &mut dyn rand::RngCore, par, stack)
+}
+
+/// estimates the frobenius norm of the operator with hutchinson's method, using random sign
+/// vectors
+///
+/// this is useful for estimating the norm of a function of a matrix $f(A)$ when only its action
+/// on vectors is available, e.g., $A^{-1}$ through an [`InverseOp`]
+///
+/// uses the same workspace as [`trace_est`]
+#[track_caller]
+pub fn frobenius_norm_est<T: ComplexField>(
+	op: impl LinOp<T>,
+	params: TraceEstParams,
+	rng: &mut (impl ?Sized + Rng),
+	par: Par,
+	stack: &mut MemStack,
+) -> T::Real {
+	#[math]
+	fn implementation<T: ComplexField>(
+		A: &dyn LinOp<T>,
+		params: TraceEstParams,
+		rng: &mut dyn rand::RngCore,
+		par: Par,
+		stack: &mut MemStack,
+	) -> T::Real {
+		let (m, n) = (A.nrows(), A.ncols());
+		let s = params.n_samples;
+		if m == 0 || n == 0 || s == 0 {
+			return zero();
+		}
+
+		let (mut z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, s, stack) };
+		let mut z = z.as_mat_mut();
+		let (mut Az, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, s, stack) };
+		let mut Az = Az.as_mat_mut();
+
+		rademacher(z.rb_mut(), rng);
+		A.apply(Az.rb_mut(), z.rb(), par, stack);
+
+		// E[‖A z‖²] = ‖A‖_F²
+		sqrt(Az.squared_norm_l2() * from_f64::<T::Real>(1.0 / s as f64))
+	}
+
+	implementation(&op, params, &mut { rng } as &mut dyn rand::RngCore, par, stack)
+}
+
+/// computes the size and alignment of required workspace for estimating the log-determinant of
+/// an operator
+pub fn logdet_est_scratch<T: ComplexField>(op: impl LinOp<T>, params: LogdetEstParams, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(A: &dyn LinOp<T>, params: LogdetEstParams, par: Par) -> StackReq {
+		_ = params;
+		let n = A.nrows();
+		StackReq::all_of(&[
+			temp_mat_scratch::<T>(n, 1), // previous lanczos vector
+			temp_mat_scratch::<T>(n, 1), // current lanczos vector
+			temp_mat_scratch::<T>(n, 1), // A v
+			A.apply_scratch(1, par),
+		])
+	}
+	implementation(&op, params, par)
+}
+
+/// estimates the log-determinant $\log \det A = \operatorname{tr} \log A$ of a self-adjoint
+/// positive definite operator with stochastic lanczos quadrature
+///
+/// for each random sign vector $z$, the quadratic form $z^H \log(A) z$ is approximated by a gauss
+/// quadrature rule, whose nodes and weights are the eigenvalues and the squared first components
+/// of the eigenvectors of the tridiagonal matrix computed by the lanczos process started from $z$.
+/// the estimate is the average of these approximations, as in hutchinson's method (see
+/// [`trace_est`])
+///
+/// this is useful when the operator is too large to be factorized, otherwise
+/// [`logdet_spd`](crate::stats::logdet::logdet_spd) computes the exact value from the cholesky
+/// factor
+///
+/// # panics
+/// panics if the operator is not square
+#[track_caller]
+pub fn logdet_est<T: ComplexField>(
+	op: impl LinOp<T>,
+	params: LogdetEstParams,
+	rng: &mut (impl ?Sized + Rng),
+	par: Par,
+	stack: &mut MemStack,
+) -> T::Real {
+	#[math]
+	fn implementation<T: ComplexField>(
+		A: &dyn LinOp<T>,
+		params: LogdetEstParams,
+		rng: &mut dyn rand::RngCore,
+		par: Par,
+		stack: &mut MemStack,
+	) -> T::Real {
+		let n = A.nrows();
+		assert!(A.nrows() == A.ncols());
+		let s = params.n_samples;
+		let steps = Ord::min(params.lanczos_steps, n);
+		if n == 0 || s == 0 || steps == 0 {
+			return zero();
+		}
+
+		let (mut v_prev, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut v_prev = v_prev.as_mat_mut();
+		let (mut v, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut v = v.as_mat_mut();
+		let (mut w, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut w = w.as_mat_mut();
+
+		let mut alpha = alloc::vec::Vec::<T::Real>::with_capacity(steps);
+		let mut beta = alloc::vec::Vec::<T::Real>::with_capacity(steps);
+
+		let mut acc = zero::<T::Real>();
+		for _ in 0..s {
+			alpha.clear();
+			beta.clear();
+
+			// unit starting vector, ‖z‖² = n is accounted for in the final scaling
+			rademacher(v.rb_mut(), rng);
+			v *= Scale(from_real::<T>(&recip(sqrt(from_f64::<T::Real>(n as f64)))));
+			v_prev.fill(zero());
+
+			let mut beta_prev = zero::<T::Real>();
+			let mut anorm = zero::<T::Real>();
+			for j in 0..steps {
+				A.apply(w.rb_mut(), v.rb(), par, stack);
+				let a = real(v.rb().col(0).adjoint() * w.rb().col(0));
+				alpha.push(copy(a));
+
+				z!(w.rb_mut(), v.rb(), v_prev.rb()).for_each(|uz!(w, v, v_prev)| *w = *w - mul_real(*v, a) - mul_real(*v_prev, beta_prev));
+
+				if j + 1 == steps {
+					break;
+				}
+				let b = w.norm_l2();
+				anorm = max(anorm, abs(a) + b + beta_prev);
+
+				// the krylov subspace is invariant, and the quadrature rule is exact
+				if b <= eps::<T::Real>() * anorm {
+					break;
+				}
+
+				beta.push(copy(b));
+				v_prev.copy_from(v.rb());
+				z!(v.rb_mut(), w.rb()).for_each(|uz!(v, w)| *v = mul_real(*w, recip(b)));
+				beta_prev = b;
+			}
+
+			let k = alpha.len();
+			let tridiag = Mat::<T::Real>::from_fn(k, k, |i, j| {
+				if i == j {
+					copy(alpha[i])
+				} else if i == j + 1 {
+					copy(beta[j])
+				} else if j == i + 1 {
+					copy(beta[i])
+				} else {
+					zero()
+				}
+			});
+
+			let Ok(evd) = crate::linalg::solvers::SelfAdjointEigen::new(tridiag.as_ref(), Side::Lower) else {
+				return nan();
+			};
+			let (U, S) = (evd.U(), evd.S().column_vector());
+			for i in 0..k {
+				acc = acc + abs2(U[(0, i)]) * crate::stats::logdet::ln(copy(S[i]));
+			}
+		}
+
+		acc * from_f64::<T::Real>(n as f64 / s as f64)
+	}
+
+	implementation(&op, params, &mut { rng } as &mut dyn rand::RngCore, par, stack)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::DenseSolveCore;
+	use crate::stats::prelude::*;
+	use crate::{Mat, assert, c64};
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_spectral_norm() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(1, 1), (10, 4), (4, 10), (60, 60)] {
+			let A = Mat::<c64>::random_normal(rng, m, n);
+			let exact = A.singular_values().unwrap()[0];
+
+			let est = spectral_norm_est(
+				A.as_ref(),
+				default(),
+				rng,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(spectral_norm_est_scratch(A.as_ref(), Par::Seq))),
+			);
+			assert!(est <= exact * (1.0 + 1e-12));
+			assert!(est >= exact * 0.95);
+		}
+	}
+
+	#[test]
+	fn test_norm_l1() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [1, 2, 9, 50] {
+			for block_size in [1, 2, 4] {
+				let A = Mat::<f64>::random_normal(rng, n, n) + Mat::<f64>::identity(n, n) * Scale(2.0 * (n as f64).sqrt());
+				let A_inv = A.partial_piv_lu().inverse();
+
+				let params = NormL1EstParams { block_size, ..default() };
+				let lu = A.partial_piv_lu();
+
+				let est = est_l1(A.as_ref(), params, rng);
+				let exact = max_col_sum(A.as_ref());
+				assert!(est <= exact * (1.0 + 1e-12));
+				assert!(est >= exact / 3.0);
+
+				let est = est_l1(InverseOp(&lu), params, rng);
+				let exact = max_col_sum(A_inv.as_ref());
+				assert!(est <= exact * (1.0 + 1e-12));
+				assert!(est >= exact / 3.0);
+			}
+		}
+	}
+
+	fn est_l1(op: impl BiLinOp<f64>, params: NormL1EstParams, rng: &mut StdRng) -> f64 {
+		let mut mem = MemBuffer::new(operator_norm_l1_est_scratch(&op, params, Par::Seq));
+		operator_norm_l1_est(&op, params, rng, Par::Seq, MemStack::new(&mut mem))
+	}
+
+	fn max_col_sum(A: MatRef<'_, f64>) -> f64 {
+		(0..A.ncols()).map(|j| A.col(j).norm_l1()).fold(0.0, f64::max)
+	}
+
+	#[test]
+	fn test_trace_and_frobenius() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 40;
+
+		let B = Mat::<f64>::random_normal(rng, n, n);
+		let A = &B * B.transpose() * Scale(0.1) + Mat::<f64>::identity(n, n) * Scale(10.0);
+
+		let params = TraceEstParams {
+			n_samples: 2000,
+			..default()
+		};
+		let mut mem = MemBuffer::new(trace_est_scratch(A.as_ref(), params, Par::Seq));
+
+		let exact = (0..n).map(|i| A[(i, i)]).sum::<f64>();
+		let est = trace_est(A.as_ref(), params, rng, Par::Seq, MemStack::new(&mut mem));
+		assert!((est - exact).abs() <= 0.05 * exact);
+
+		let exact = A.norm_l2();
+		let est = frobenius_norm_est(A.as_ref(), params, rng, Par::Seq, MemStack::new(&mut mem));
+		assert!((est - exact).abs() <= 0.05 * exact);
+	}
+
+	#[test]
+	fn test_logdet() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		// for a diagonal operator, each quadratic form is exactly tr(log(A))
+		let n = 20;
+		let A = Mat::<f64>::from_fn(n, n, |i, j| if i == j { (i + 1) as f64 } else { 0.0 });
+		let exact = (1..=n).map(|i| (i as f64).ln()).sum::<f64>();
+		let params = LogdetEstParams { n_samples: 3, ..default() };
+		let mut mem = MemBuffer::new(logdet_est_scratch(A.as_ref(), params, Par::Seq));
+		let est = logdet_est(A.as_ref(), params, rng, Par::Seq, MemStack::new(&mut mem));
+		assert!((est - exact).abs() < 1e-10);
+
+		let n = 200;
+		let eigs = Col::<f64>::from_fn(n, |i| 1.0 + 99.0 * (i as f64 / n as f64).powi(2));
+		let A = crate::stats::testmat::random_hpd_with_eigenvalues::<c64>(rng, eigs.as_ref());
+		let exact = crate::stats::logdet::logdet_spd(crate::linalg::solvers::Llt::new(A.as_ref(), Side::Lower).unwrap().L());
+		assert!((exact - eigs.iter().map(|e| e.ln()).sum::<f64>()).abs() < 1e-8);
+
+		let params = LogdetEstParams {
+			n_samples: 64,
+			lanczos_steps: 20,
+			..default()
+		};
+		let mut mem = MemBuffer::new(logdet_est_scratch(A.as_ref(), params, Par::Seq));
+		let est = logdet_est(A.as_ref(), params, rng, Par::Seq, MemStack::new(&mut mem));
+		assert!((est - exact).abs() <= 0.02 * exact);
+	}
+}
@@ -0,0 +1,264 @@
+//! streaming accumulation of gram matrices, column sums and covariances
+//!
+//! [`GramAccumulator`] consumes a data matrix one batch of rows at a time, and maintains the gram
+//! matrix $A^H A$ and the column sums of all the rows seen so far, as well as the centered second
+//! moment $\sum_i (x_i - \mu)^H (x_i - \mu)$, which is updated with the pairwise formula of chan,
+//! golub and leveque so that the covariance doesn't suffer from cancellation. each batch costs a
+//! rank-k update of the lower halves of both matrices
+//!
+//! accumulators of disjoint parts of the data can be combined with [`GramAccumulator::merge`], in
+//! any order, which allows computing the statistics of a large dataset in a map-reduce fashion
+//!
+//! # example
+//! ```
+//! use faer::mat;
+//! use faer::stats::gram::GramAccumulator;
+//! use faer::stats::pca::covariance;
+//!
+//! let X = mat![[1.0, 2.0], [2.0, 4.1], [3.0, 5.9], [4.0, 8.0], [5.0, 9.7]];
+//!
+//! // two workers each see part of the rows
+//! let mut left = GramAccumulator::<f64>::new(2);
+//! left.push(X.get(..2, ..));
+//! let mut right = GramAccumulator::<f64>::new(2);
+//! right.push(X.get(2..4, ..));
+//! right.push(X.get(4.., ..));
+//!
+//! left.merge(&right);
+//! assert!(left.count() == 5);
+//! assert!((left.gram() - X.transpose() * &X).norm_max() < 1e-10);
+//! assert!((left.covariance() - covariance(X.as_ref())).norm_max() < 1e-10);
+//! ```
+
+use crate::internal_prelude::*;
+use crate::linalg::matmul::triangular::BlockStructure;
+use crate::{assert, get_global_parallelism};
+
+/// batch of observations, stored as the rows of a matrix
+pub type RowBatch<'a, T> = MatRef<'a, T>;
+
+/// streaming accumulator of the gram matrix, the column sums and the covariance of a data matrix
+/// whose rows are observations
+#[derive(Clone, Debug)]
+pub struct GramAccumulator<T: ComplexField> {
+	count: usize,
+	sum: Row<T>,
+	mean: Row<T>,
+	// only the lower halves are stored
+	gram: Mat<T>,
+	m2: Mat<T>,
+}
+
+/// `dst += alpha * lhs * rhs`, only updating the lower half of `dst`
+fn syrk_lower<T: ComplexField>(dst: MatMut<'_, T>, lhs: MatRef<'_, impl Conjugate<Canonical = T>>, rhs: MatRef<'_, T>, alpha: T) {
+	linalg::matmul::triangular::matmul(
+		dst,
+		BlockStructure::TriangularLower,
+		Accum::Add,
+		lhs,
+		BlockStructure::Rectangular,
+		rhs,
+		BlockStructure::Rectangular,
+		alpha,
+		get_global_parallelism(),
+	);
+}
+
+/// fills the upper half of `mat` from its lower half
+#[math]
+fn hermitian_from_lower<T: ComplexField>(mat: &mut Mat<T>) {
+	let p = mat.nrows();
+	for j in 0..p {
+		for i in 0..j {
+			mat[(i, j)] = conj(mat[(j, i)]);
+		}
+	}
+}
+
+impl<T: ComplexField> GramAccumulator<T> {
+	/// returns an empty accumulator for observations with `ncols` variables
+	pub fn new(ncols: usize) -> Self {
+		Self {
+			count: 0,
+			sum: Row::zeros(ncols),
+			mean: Row::zeros(ncols),
+			gram: Mat::zeros(ncols, ncols),
+			m2: Mat::zeros(ncols, ncols),
+		}
+	}
+
+	/// returns the number of variables
+	#[inline]
+	pub fn ncols(&self) -> usize {
+		self.sum.ncols()
+	}
+
+	/// returns the number of observations seen so far
+	#[inline]
+	pub fn count(&self) -> usize {
+		self.count
+	}
+
+	/// returns the sum of each column of the observations
+	#[inline]
+	pub fn sum(&self) -> RowRef<'_, T> {
+		self.sum.as_ref()
+	}
+
+	/// returns the mean of each column of the observations
+	#[inline]
+	pub fn mean(&self) -> RowRef<'_, T> {
+		self.mean.as_ref()
+	}
+
+	/// returns the gram matrix $A^H A$, where the rows of $A$ are the observations
+	pub fn gram(&self) -> Mat<T> {
+		let mut gram = self.gram.clone();
+		hermitian_from_lower(&mut gram);
+		gram
+	}
+
+	/// returns the sample covariance matrix of the observations, using $n - 1$ as the
+	/// normalization factor
+	///
+	/// # panics
+	///
+	/// panics if fewer than two observations were seen
+	#[track_caller]
+	#[math]
+	pub fn covariance(&self) -> Mat<T> {
+		assert!(self.count >= 2);
+		let f = from_f64::<T::Real>(1.0 / (self.count - 1) as f64);
+		let mut cov = Mat::from_fn(self.ncols(), self.ncols(), |i, j| mul_real(self.m2[(i, j)], f));
+		hermitian_from_lower(&mut cov);
+		cov
+	}
+
+	/// adds the rows of `batch` to the observations
+	#[track_caller]
+	#[math]
+	pub fn push(&mut self, batch: RowBatch<'_, impl Conjugate<Canonical = T>>) {
+		let p = self.ncols();
+		assert!(batch.ncols() == p);
+		let nb = batch.nrows();
+		if nb == 0 {
+			return;
+		}
+
+		let mut batch = batch.to_owned();
+		syrk_lower(self.gram.as_mut(), batch.adjoint(), batch.as_ref(), one());
+
+		let sum = Row::<T>::from_fn(p, |j| batch.col(j).sum());
+		let f = from_f64::<T::Real>(1.0 / nb as f64);
+		let mean = Row::<T>::from_fn(p, |j| mul_real(sum[j], f));
+
+		batch.as_mut().sub_row_broadcast(mean.as_ref());
+		syrk_lower(self.m2.as_mut(), batch.adjoint(), batch.as_ref(), one());
+
+		self.sum += &sum;
+		self.combine_means(nb, mean.as_ref());
+	}
+
+	/// adds the observations of `other` to the observations of `self`
+	#[track_caller]
+	pub fn merge(&mut self, other: &Self) {
+		assert!(other.ncols() == self.ncols());
+		if other.count == 0 {
+			return;
+		}
+		self.gram += &other.gram;
+		self.m2 += &other.m2;
+		self.sum += &other.sum;
+		self.combine_means(other.count, other.mean.as_ref());
+	}
+
+	/// updates the mean with the one of `nb` new observations, and adds the correction to the
+	/// centered second moment
+	#[math]
+	fn combine_means(&mut self, nb: usize, mean: RowRef<'_, T>) {
+		let na = self.count;
+		let n = na + nb;
+
+		let delta = mean - &self.mean;
+		let f = from_f64::<T>(na as f64 * nb as f64 / n as f64);
+		syrk_lower(self.m2.as_mut(), delta.adjoint().as_mat(), delta.as_mat(), f);
+
+		let g = from_f64::<T::Real>(nb as f64 / n as f64);
+		zip!(&mut self.mean, &delta).for_each(|unzip!(m, d)| *m = *m + mul_real(*d, g));
+		self.count = n;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::pca::covariance;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_gram_accumulator() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (n, p) = (60, 5);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0);
+
+		let mut X = CwiseMatDistribution {
+			nrows: n,
+			ncols: p,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		// large offset, which would cause cancellation in the naive covariance formula
+		X.as_mut()
+			.add_row_broadcast(Row::<c64>::from_fn(p, |j| c64::new(1e4 * (j + 1) as f64, 0.0)).as_ref());
+
+		let gram = X.adjoint() * &X;
+		let cov = covariance(X.as_ref());
+		let sum = Row::<c64>::from_fn(p, |j| X.col(j).sum());
+
+		// single stream, with batches of varying sizes
+		let mut acc = GramAccumulator::<c64>::new(p);
+		let mut start = 0;
+		for (k, len) in [1, 7, 0, 13, 2, 37].into_iter().enumerate() {
+			if k % 2 == 0 {
+				acc.push(X.get(start..start + len, ..));
+			} else {
+				acc.push(X.get(start..start + len, ..).conjugate().conjugate());
+			}
+			start += len;
+		}
+		assert!(start == n);
+		assert!(acc.count() == n);
+		assert!(acc.gram() ~ gram);
+		assert!(acc.covariance() ~ cov);
+		assert!((acc.sum() - &sum).norm_max() < 1e-8);
+
+		// map-reduce over chunks, merged in a tree
+		let mut parts = (0..6)
+			.map(|i| {
+				let mut acc = GramAccumulator::<c64>::new(p);
+				acc.push(X.get(10 * i..10 * (i + 1), ..));
+				acc
+			})
+			.collect::<alloc::vec::Vec<_>>();
+		while parts.len() > 1 {
+			let rhs = parts.pop().unwrap();
+			let lhs = parts.remove(0);
+			let mut lhs = lhs;
+			lhs.merge(&rhs);
+			parts.push(lhs);
+		}
+		let mut acc = parts.pop().unwrap();
+		acc.merge(&GramAccumulator::new(p));
+		assert!(acc.count() == n);
+		assert!(acc.gram() ~ gram);
+		assert!(acc.covariance() ~ cov);
+		assert!((acc.mean() - &sum * Scale(c64::new(1.0 / n as f64, 0.0))).norm_max() < 1e-10);
+
+		// conjugated input
+		let mut acc = GramAccumulator::<c64>::new(p);
+		acc.push(X.conjugate());
+		assert!(acc.covariance() ~ cov.conjugate().to_owned());
+	}
+}
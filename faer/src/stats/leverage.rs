@@ -0,0 +1,196 @@
+//! statistical leverage scores and column subset selection
+//!
+//! the leverage score of the $i$-th row of an $m \times n$ matrix $A$ is the squared norm of the
+//! $i$-th row of an orthonormal basis of the column space of $A$, or equivalently, the $i$-th
+//! diagonal entry of the orthogonal projector $A A^+$. the scores are between $0$ and $1$, and sum
+//! to the rank of $A$. observations with large leverage scores have a large influence on the least
+//! squares fit, and sampling the rows of a least squares problem with probabilities proportional
+//! to their leverage scores yields a small problem whose solution is close to the original one
+//!
+//! [`select_columns`] picks a subset of the columns of a matrix that is well-conditioned, using
+//! the pivots of the $QR$ decomposition with column pivoting
+//!
+//! # example
+//! ```
+//! use faer::mat;
+//! use faer::stats::leverage::{leverage_scores, select_columns};
+//!
+//! // the last observation is far from the others, so it has a large influence on the fit
+//! let A = mat![[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [1.0, 10.0]];
+//! let h = leverage_scores::<f64>(A.as_ref());
+//! assert!((h.sum() - 2.0).abs() < 1e-12);
+//! assert!(h[3] > 0.9);
+//!
+//! // the third column is the sum of the first two, so any two columns span the column space
+//! let B = mat![[1.0, 0.0, 1.0], [0.0, 1.0, 1.0], [1.0, 1.0, 2.0]];
+//! let cols = select_columns(B.as_ref(), 2);
+//! assert!(cols.len() == 2);
+//! ```
+
+use crate::internal_prelude::*;
+#[cfg(feature = "rand")]
+use crate::linalg::sketch::GaussianSketch;
+use crate::linalg::solvers::Cod;
+use crate::{assert, get_global_parallelism};
+use alloc::vec::Vec;
+use dyn_stack::MemBuffer;
+
+/// computes the leverage scores of the rows of $A$
+///
+/// the numerical rank of $A$ is determined with a complete orthogonal decomposition (see
+/// [`Cod`]), so that the scores are also meaningful when $A$ is rank deficient
+pub fn leverage_scores<T: ComplexField>(A: MatRef<'_, impl Conjugate<Canonical = T>>) -> Col<T::Real> {
+	let m = A.nrows();
+	let cod = Cod::new(A);
+	let r = cod.rank();
+
+	// first r columns of Q, which are an orthonormal basis of the column space of A
+	let mut Q = Mat::<T>::identity(m, r);
+	let par = get_global_parallelism();
+	linalg::householder::apply_block_householder_sequence_on_the_left_in_place_with_conj(
+		cod.Q_basis(),
+		cod.Q_coeff(),
+		Conj::No,
+		Q.as_mut(),
+		par,
+		MemStack::new(&mut MemBuffer::new(
+			linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(m, cod.Q_coeff().nrows(), r),
+		)),
+	);
+
+	row_squared_norms(Q.as_ref())
+}
+
+/// computes an estimate of the leverage scores of the rows of $A$, which must have full column
+/// rank, using random sketches
+///
+/// the $R$ factor of the $QR$ decomposition of $S A$, where $S$ is a gaussian sketch with
+/// `sketch_rows` rows, is used to approximately orthogonalize $A$. the squared row norms of
+/// $A R^{-1}$ are then estimated with a gaussian projection of dimension `projection_dim`. the
+/// cost is $O(\text{sketch\_rows} \cdot m n + m n \cdot \text{projection\_dim})$ instead of the
+/// $O(m n^2)$ of [`leverage_scores`]
+///
+/// a few times $n$ rows for the sketch and a few tens of columns for the projection usually give
+/// estimates within a small constant factor of the exact scores
+///
+/// # panics
+///
+/// panics if `sketch_rows` is smaller than the number of columns of $A$
+#[cfg(feature = "rand")]
+#[track_caller]
+pub fn approx_leverage_scores<T: ComplexField>(
+	A: MatRef<'_, impl Conjugate<Canonical = T>>,
+	sketch_rows: usize,
+	projection_dim: usize,
+	rng: &mut (impl ?Sized + rand::Rng),
+) -> Col<T::Real> {
+	let (m, n) = A.shape();
+	assert!(sketch_rows >= n);
+
+	let S = GaussianSketch::<T>::new(sketch_rows, m, rng);
+	let R = S.apply(A).qr().thin_R().to_owned();
+
+	let mut Omega = GaussianSketch::<T>::new(projection_dim, n, rng).as_mat().adjoint().to_owned();
+	linalg::triangular_solve::solve_upper_triangular_in_place(R.as_ref(), Omega.as_mut(), get_global_parallelism());
+
+	let AOmega = A * &Omega;
+	row_squared_norms(AOmega.as_ref())
+}
+
+/// selects `k` columns of $A$ that are well-conditioned, and returns their indices, by order of
+/// selection
+///
+/// the columns are the first `k` pivots of the $QR$ decomposition of $A$ with column pivoting.
+/// each selected column is the one with the largest component orthogonal to the span of the
+/// previously selected ones
+///
+/// # panics
+///
+/// panics if `k` is larger than the number of rows or the number of columns of $A$
+#[track_caller]
+pub fn select_columns<T: ComplexField>(A: MatRef<'_, impl Conjugate<Canonical = T>>, k: usize) -> Vec<usize> {
+	assert!(k <= Ord::min(A.nrows(), A.ncols()));
+	let qr = A.col_piv_qr();
+	qr.P().arrays().0[..k].to_vec()
+}
+
+#[math]
+fn row_squared_norms<T: ComplexField>(A: MatRef<'_, T>) -> Col<T::Real> {
+	let mut out = Col::<T::Real>::zeros(A.nrows());
+	for j in 0..A.ncols() {
+		zip!(&mut out, A.col(j)).for_each(|unzip!(o, a)| *o = *o + abs2(*a));
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::DenseSolveCore;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_leverage_scores() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n) = (200, 8);
+
+		let mut A = Mat::<c64>::random_normal(rng, m, n);
+		// a few high leverage rows
+		for i in 0..4 {
+			for j in 0..n {
+				A[(i, j)] *= 20.0;
+			}
+		}
+
+		let h = leverage_scores(A.as_ref());
+		let hat = &A * (A.adjoint() * &A).partial_piv_lu().inverse() * A.adjoint();
+		for i in 0..m {
+			assert!((h[i] - hat[(i, i)].re).abs() < 1e-10);
+		}
+		assert!((h.sum() - n as f64).abs() < 1e-10);
+		for i in 0..4 {
+			assert!(h[i] > 0.5);
+		}
+
+		let h_approx = approx_leverage_scores(A.as_ref(), 8 * n, 64, rng);
+		for i in 0..m {
+			let ratio = h_approx[i] / h[i];
+			assert!(all(ratio > 0.3, ratio < 3.0));
+		}
+
+		// rank deficient matrix, whose last columns are combinations of the first ones
+		let B = Mat::<c64>::random_normal(rng, m, 3);
+		let C = Mat::<c64>::random_normal(rng, 3, n - 3);
+		let A = crate::concat![[B, &B * &C]];
+		let h = leverage_scores(A.as_ref());
+		assert!((h.sum() - 3.0).abs() < 1e-8);
+		let h_B = leverage_scores(B.as_ref());
+		assert!((&h - &h_B).norm_max() < 1e-8);
+	}
+
+	#[test]
+	fn test_select_columns() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let m = 30;
+
+		// columns 0 and 1 are almost parallel, and the last ones are combinations of the first ones
+		let mut B = Mat::<f64>::random_normal(rng, m, 4);
+		let b1 = B.col(0) + Scale(1e-10) * B.col(1);
+		B.col_mut(1).copy_from(&b1);
+		let C = Mat::<f64>::random_normal(rng, 4, 3);
+		let A = crate::concat![[B, &B * &C]];
+
+		let cols = select_columns(A.as_ref(), 3);
+		assert!(cols.len() == 3);
+		assert!(!(cols.contains(&0) && cols.contains(&1)));
+
+		let norms = (0..A.ncols()).map(|j| A.col(j).norm_l2()).collect::<Vec<_>>();
+		let max = norms.iter().copied().fold(0.0, f64::max);
+		assert!(norms[cols[0]] == max);
+
+		let sub = Mat::from_fn(m, 3, |i, j| A[(i, cols[j])]);
+		let s = sub.singular_values().unwrap();
+		assert!(s[2] > 1e-3 * s[0]);
+	}
+}
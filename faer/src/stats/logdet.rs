@@ -0,0 +1,154 @@
+//! log-determinant of self-adjoint positive definite matrices
+//!
+//! the log-determinant appears in the log-likelihood of a multivariate gaussian distribution, and
+//! is computed directly from the cholesky factor, since the determinant itself overflows or
+//! underflows for all but the smallest matrices. for matrices that are too large to be factorized,
+//! see [`logdet_est`](crate::stats::estimate::logdet_est), which only needs matrix-vector products
+//!
+//! # example
+//! ```
+//! use faer::linalg::solvers::Llt;
+//! use faer::stats::logdet::logdet_spd;
+//! use faer::{Side, mat};
+//!
+//! let A = mat![[4.0, 2.0], [2.0, 5.0]];
+//! let llt = Llt::new(A.as_ref(), Side::Lower).unwrap();
+//!
+//! let logdet: f64 = logdet_spd(llt.L());
+//! assert!((logdet - 16.0f64.ln()).abs() < 1e-14);
+//! ```
+
+use crate::assert;
+use crate::internal_prelude::*;
+
+/// returns $2 \operatorname{atanh}(z) = 2 \sum_k z^{2k + 1} / (2k + 1)$, for small $|z|$
+#[math]
+fn atanh_series<T: RealField>(z: T) -> T {
+	let z2 = z * z;
+	let mut term = copy(z);
+	let mut sum = zero::<T>();
+	let mut k = 0usize;
+	loop {
+		let t = term / from_f64::<T>((2 * k + 1) as f64);
+		sum = sum + t;
+		if abs(t) <= eps::<T>() * abs(sum) {
+			break;
+		}
+		term = term * z2;
+		k += 1;
+	}
+	sum + sum
+}
+
+/// returns the natural logarithm of `x`
+///
+/// the argument is first reduced to $[1/\sqrt{2}, \sqrt{2}]$ by powers of two, and the logarithm
+/// of the reduced value is computed from $\log y = 2 \operatorname{atanh}((y - 1) / (y + 1))$
+#[math]
+pub(crate) fn ln<T: RealField>(x: T) -> T {
+	if x == zero::<T>() {
+		return -infinity::<T>();
+	}
+	if x < zero::<T>() || is_nan(x) {
+		return nan();
+	}
+	if !is_finite(x) {
+		return x;
+	}
+
+	let two = from_f64::<T>(2.0);
+	let half = from_f64::<T>(0.5);
+	let hi = sqrt(two);
+	let lo = recip(hi);
+
+	let mut y = x;
+	let mut exponent = 0i64;
+	while y > hi {
+		y = y * half;
+		exponent += 1;
+	}
+	while y < lo {
+		y = y * two;
+		exponent -= 1;
+	}
+
+	let one = one::<T>();
+	let ln2 = atanh_series(recip(from_f64::<T>(3.0)));
+	from_f64::<T>(exponent as f64) * ln2 + atanh_series((y - one) / (y + one))
+}
+
+/// returns $\log \det A$, where $A = L L^H$ is self-adjoint positive definite, given its cholesky
+/// factor $L$
+///
+/// only the diagonal of `L` is accessed. the logarithms of the diagonal entries are summed, so
+/// that the result doesn't overflow even when $\det A$ does
+///
+/// # panics
+///
+/// panics if `L` is not square
+#[track_caller]
+#[math]
+pub fn logdet_spd<T: ComplexField>(L: MatRef<'_, T>) -> T::Real {
+	assert!(L.nrows() == L.ncols());
+
+	let mut acc = zero::<T::Real>();
+	for i in 0..L.nrows() {
+		acc = acc + ln(real(L[(i, i)]));
+	}
+	acc + acc
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::Llt;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_ln() {
+		for x in [
+			1.0,
+			2.0,
+			0.5,
+			3.0,
+			1e-300,
+			1e300,
+			5e-324,
+			f64::MAX,
+			0.7071,
+			1.4142,
+			10.0,
+			core::f64::consts::E,
+		] {
+			assert!((ln(x) - x.ln()).abs() <= 4.0 * f64::EPSILON * x.ln().abs().max(1.0));
+		}
+		assert!(ln(0.0f64) == f64::NEG_INFINITY);
+		assert!(ln(f64::INFINITY) == f64::INFINITY);
+		assert!(ln(-1.0f64).is_nan());
+		assert!(ln(f64::NAN).is_nan());
+
+		assert!((ln(3.0f32) - 3.0f32.ln()).abs() <= 4.0 * f32::EPSILON);
+	}
+
+	#[test]
+	fn test_logdet_spd() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 40;
+
+		let eigs = Col::<f64>::from_fn(n, |i| 10.0f64.powf(i as f64 / 8.0 - 2.5));
+		let A = crate::stats::testmat::random_hpd_with_eigenvalues::<c64>(rng, eigs.as_ref());
+		let llt = Llt::new(A.as_ref(), Side::Lower).unwrap();
+
+		let expected: f64 = eigs.iter().map(|e| e.ln()).sum();
+		assert!((logdet_spd(llt.L()) - expected).abs() < 1e-8);
+
+		// the determinant itself underflows
+		let A = Mat::<f64>::identity(400, 400) * Scale(1e-2);
+		let llt = Llt::new(A.as_ref(), Side::Lower).unwrap();
+		assert!(A.determinant() == 0.0);
+		assert!((logdet_spd(llt.L()) - 400.0 * 1e-2f64.ln()).abs() < 1e-10);
+
+		assert!(logdet_spd(Mat::<f64>::zeros(0, 0).as_ref()) == 0.0);
+	}
+}
@@ -0,0 +1,170 @@
+//! quadratic forms and mahalanobis distances
+//!
+//! the mahalanobis distance of an observation $x$ from a distribution with mean $\mu$ and
+//! covariance matrix $\Sigma$ is $\sqrt{(x - \mu)^H \Sigma^{-1} (x - \mu)}$. with the $L L^H$
+//! decomposition of $\Sigma$, it is the euclidean norm of $L^{-1} (x - \mu)$, so the same factor
+//! can be reused for any number of observations, e.g., when evaluating a gaussian log-likelihood
+//!
+//! # example
+//! ```
+//! use faer::linalg::solvers::Llt;
+//! use faer::stats::mahalanobis::mahalanobis_distance;
+//! use faer::{Side, mat, row};
+//!
+//! let cov = mat![[4.0, 0.0], [0.0, 1.0]];
+//! let mean = row![1.0, 1.0];
+//! let llt = Llt::new(cov.as_ref(), Side::Lower).unwrap();
+//!
+//! // each row is an observation
+//! let data = mat![[3.0, 1.0], [1.0, 3.0], [1.0, 1.0]];
+//! let d: faer::Col<f64> = mahalanobis_distance(&llt, mean.as_ref(), data.as_ref());
+//!
+//! assert!((d[0] - 1.0).abs() < 1e-14);
+//! assert!((d[1] - 2.0).abs() < 1e-14);
+//! assert!(d[2] == 0.0);
+//! ```
+
+use crate::internal_prelude::*;
+use crate::linalg::relayout::relayout_copy;
+use crate::linalg::solvers::Llt;
+use crate::{assert, get_global_parallelism};
+
+/// computes the quadratic form $x^H A y$
+///
+/// # panics
+///
+/// panics if the dimensions of `x`, `A` and `y` don't match
+#[track_caller]
+pub fn quadratic_form<T: ComplexField>(
+	x: ColRef<'_, impl Conjugate<Canonical = T>>,
+	A: MatRef<'_, impl Conjugate<Canonical = T>>,
+	y: ColRef<'_, impl Conjugate<Canonical = T>>,
+) -> T {
+	assert!(all(x.nrows() == A.nrows(), y.nrows() == A.ncols()));
+	x.conj_dot(A * y)
+}
+
+/// computes the squared mahalanobis distances $(x_i - \mu)^H \Sigma^{-1} (x_i - \mu)$ of the rows
+/// $x_i$ of `data`, given the $L L^H$ decomposition of the covariance matrix $\Sigma$
+///
+/// the whole batch is solved with a single triangular solve, instead of one per observation
+///
+/// # panics
+///
+/// panics if the number of columns of `data` or `mean` doesn't match the dimension of the
+/// covariance matrix
+#[track_caller]
+pub fn mahalanobis_distance_squared<T: ComplexField>(
+	llt: &Llt<T>,
+	mean: RowRef<'_, impl Conjugate<Canonical = T>>,
+	data: MatRef<'_, impl Conjugate<Canonical = T>>,
+) -> Col<T::Real> {
+	let L = llt.L();
+	let (n, p) = data.shape();
+	assert!(all(L.nrows() == p, mean.ncols() == p));
+
+	// store the observations as the columns of a column-major matrix, so that the triangular
+	// solve and the norm computations work on contiguous data
+	let mut Z = Mat::<T>::zeros(p, n);
+	relayout_copy(Z.as_mut(), data.transpose());
+	Z.as_mut().sub_col_broadcast(mean.transpose());
+
+	linalg::triangular_solve::solve_lower_triangular_in_place(L, Z.as_mut(), get_global_parallelism());
+
+	Col::from_fn(n, |i| Z.col(i).squared_norm_l2())
+}
+
+/// computes the mahalanobis distances $\sqrt{(x_i - \mu)^H \Sigma^{-1} (x_i - \mu)}$ of the rows
+/// $x_i$ of `data`, given the $L L^H$ decomposition of the covariance matrix $\Sigma$
+///
+/// see [`mahalanobis_distance_squared`]
+///
+/// # panics
+///
+/// panics if the number of columns of `data` or `mean` doesn't match the dimension of the
+/// covariance matrix
+#[track_caller]
+pub fn mahalanobis_distance<T: ComplexField>(
+	llt: &Llt<T>,
+	mean: RowRef<'_, impl Conjugate<Canonical = T>>,
+	data: MatRef<'_, impl Conjugate<Canonical = T>>,
+) -> Col<T::Real> {
+	let mut d = mahalanobis_distance_squared(llt, mean, data);
+	for x in d.iter_mut() {
+		*x = sqrt(x);
+	}
+	d
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::{DenseSolveCore, Solve};
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_quadratic_form() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		let (m, n) = (5, 3);
+
+		let x = CwiseColDistribution { nrows: m, dist }.rand::<Col<c64>>(rng);
+		let A = CwiseMatDistribution { nrows: m, ncols: n, dist }.rand::<Mat<c64>>(rng);
+		let y = CwiseColDistribution { nrows: n, dist }.rand::<Col<c64>>(rng);
+
+		let mut sum = c64::new(0.0, 0.0);
+		for j in 0..n {
+			for i in 0..m {
+				sum += x[i].conj() * A[(i, j)] * y[j];
+			}
+		}
+		assert!((quadratic_form(x.as_ref(), A.as_ref(), y.as_ref()) - sum).norm() < 1e-12);
+
+		// conjugating all the inputs conjugates the result
+		assert!((quadratic_form(x.conjugate(), A.conjugate(), y.conjugate()) - sum.conj()).norm() < 1e-12);
+	}
+
+	#[test]
+	fn test_mahalanobis() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		let (n, p) = (50, 7);
+
+		let B = CwiseMatDistribution { nrows: p, ncols: p, dist }.rand::<Mat<c64>>(rng);
+		let cov = &B * B.adjoint() + Mat::<c64>::identity(p, p);
+		let mean = CwiseRowDistribution { ncols: p, dist }.rand::<Row<c64>>(rng);
+		let data = CwiseMatDistribution { nrows: n, ncols: p, dist }.rand::<Mat<c64>>(rng);
+
+		let llt = Llt::new(cov.as_ref(), Side::Lower).unwrap();
+		let inv = llt.inverse();
+
+		let d2 = mahalanobis_distance_squared(&llt, mean.as_ref(), data.as_ref());
+		let d = mahalanobis_distance(&llt, mean.as_ref(), data.as_ref());
+		assert!(d2.nrows() == n);
+
+		for i in 0..n {
+			let v = (data.row(i) - &mean).transpose().to_owned();
+			let expected = quadratic_form(v.as_ref(), inv.as_ref(), v.as_ref());
+			assert!((d2[i] - expected.re).abs() < 1e-10 * expected.re);
+			assert!(expected.im.abs() < 1e-10 * expected.re);
+			assert!((d[i] - expected.re.sqrt()).abs() < 1e-10 * d[i]);
+
+			let solved = llt.solve(&v);
+			assert!((v.conj_dot(&solved).re - d2[i]).abs() < 1e-10 * d2[i]);
+		}
+
+		// row-major data and conjugated inputs
+		let data_rm = data.transpose().to_owned();
+		let d2_rm = mahalanobis_distance_squared(&llt, mean.as_ref(), data_rm.transpose());
+		for i in 0..n {
+			assert!((d2_rm[i] - d2[i]).abs() < 1e-10 * d2[i]);
+		}
+		let d2_conj = mahalanobis_distance_squared(&llt, mean.conjugate(), data.conjugate());
+		for i in 0..n {
+			let v = (data.row(i) - &mean).adjoint().to_owned();
+			let expected = quadratic_form(v.as_ref(), inv.as_ref(), v.as_ref()).re;
+			assert!((d2_conj[i] - expected).abs() < 1e-10 * expected);
+		}
+	}
+}
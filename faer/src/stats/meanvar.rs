@@ -13,12 +13,12 @@ pub enum NanHandling {
 
 #[inline(always)]
 #[math]
-fn from_usize<T: RealField>(n: usize) -> T {
+pub(super) fn from_usize<T: RealField>(n: usize) -> T {
 	from_f64::<T>(n as u32 as f64) + (from_f64::<T>((n as u64 - (n as u32 as u64)) as f64))
 }
 
 #[inline(always)]
-fn reduce<T: ComplexField, S: pulp::Simd>(non_nan_count: T::SimdIndex<S>) -> usize {
+pub(super) fn reduce<T: ComplexField, S: pulp::Simd>(non_nan_count: T::SimdIndex<S>) -> usize {
 	let slice: &[T::Index] = bytemuck::cast_slice(core::slice::from_ref(&non_nan_count));
 
 	let mut acc = 0usize;
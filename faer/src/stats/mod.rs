@@ -3,6 +3,38 @@
 mod meanvar;
 pub use meanvar::{NanHandling, col_mean, col_varm, row_mean, row_varm};
 
+/// randomized norm and trace estimators
+#[cfg(feature = "rand")]
+pub mod estimate;
+
+/// covariance, principal component analysis and whitening
+pub mod pca;
+
+/// reductions and covariances that skip missing values
+pub mod nan;
+
+/// streaming accumulation of gram matrices and covariances
+pub mod gram;
+
+/// leverage scores and column subset selection
+pub mod leverage;
+
+/// low-rank matrix completion with soft-impute
+#[cfg(feature = "rand")]
+pub mod completion;
+
+/// quadratic forms and mahalanobis distances
+pub mod mahalanobis;
+
+/// log-determinant of self-adjoint positive definite matrices
+pub mod logdet;
+
+/// positive definiteness tests and the nearest correlation matrix
+pub mod posdef;
+
+/// hilbert, vandermonde and random test matrices with prescribed properties
+pub mod testmat;
+
 pub mod prelude {
 	#[cfg(feature = "rand")]
 	pub use num_complex::ComplexDistribution;
@@ -12,7 +44,9 @@ pub mod prelude {
 	pub use rand_distr::{Standard, StandardNormal};
 
 	#[cfg(feature = "rand")]
-	pub use super::{CwiseColDistribution, CwiseMatDistribution, CwiseRowDistribution, DistributionExt, UnitaryMat};
+	pub use super::{
+		CwiseColDistribution, CwiseMatDistribution, CwiseRowDistribution, DistributionExt, StandardNormalScalar, StandardUniformScalar, UnitaryMat,
+	};
 }
 
 #[cfg(feature = "rand")]
@@ -52,12 +86,51 @@ mod rand {
 		pub dist: D,
 	}
 
+	/// standard normal distribution over any scalar type
+	///
+	/// for complex types, the real and imaginary parts are independent and standard normal
+	#[derive(Copy, Clone, Debug)]
+	pub struct StandardNormalScalar;
+
+	/// uniform distribution over $[0, 1)$ for any scalar type
+	///
+	/// for complex types, the real and imaginary parts are independent and uniform over $[0, 1)$
+	#[derive(Copy, Clone, Debug)]
+	pub struct StandardUniformScalar;
+
 	#[derive(Copy, Clone, Debug)]
 	pub struct UnitaryMat<Dim: Shape, D> {
 		pub dim: Dim,
 		pub standard_normal: D,
 	}
 
+	#[inline]
+	fn from_parts<T: ComplexField>(re: f64, im: impl FnOnce() -> f64) -> T {
+		let re = from_f64::<T>(re);
+		if try_const! { T::IS_REAL } {
+			re
+		} else {
+			// the square root of `-1` is exact, so this only sets the imaginary part
+			add(&re, &mul_real(&sqrt(&neg(&one::<T>())), &from_f64::<T::Real>(im())))
+		}
+	}
+
+	impl<T: ComplexField> Distribution<T> for StandardNormalScalar {
+		#[inline]
+		fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> T {
+			let re = rng.sample(rand_distr::StandardNormal);
+			from_parts(re, || rng.sample(rand_distr::StandardNormal))
+		}
+	}
+
+	impl<T: ComplexField> Distribution<T> for StandardUniformScalar {
+		#[inline]
+		fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> T {
+			let re = rng.gen::<f64>();
+			from_parts(re, || rng.gen::<f64>())
+		}
+	}
+
 	impl<T, Rows: Shape, Cols: Shape, D: Distribution<T>> Distribution<Mat<T, Rows, Cols>> for CwiseMatDistribution<Rows, Cols, D> {
 		#[inline]
 		fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Mat<T, Rows, Cols> {
@@ -105,4 +178,115 @@ mod rand {
 			q
 		}
 	}
+
+	impl<T: ComplexField, Rows: Shape, Cols: Shape> Mat<T, Rows, Cols> {
+		/// returns a matrix with independent entries sampled from the standard normal distribution
+		/// (see [`StandardNormalScalar`])
+		pub fn random_normal(rng: &mut (impl ?Sized + rand::Rng), nrows: Rows, ncols: Cols) -> Self {
+			CwiseMatDistribution {
+				nrows,
+				ncols,
+				dist: StandardNormalScalar,
+			}
+			.sample(rng)
+		}
+
+		/// returns a matrix with independent entries sampled uniformly from $[0, 1)$ (see
+		/// [`StandardUniformScalar`])
+		pub fn random_uniform(rng: &mut (impl ?Sized + rand::Rng), nrows: Rows, ncols: Cols) -> Self {
+			CwiseMatDistribution {
+				nrows,
+				ncols,
+				dist: StandardUniformScalar,
+			}
+			.sample(rng)
+		}
+	}
+
+	impl<T: ComplexField, Rows: Shape> Col<T, Rows> {
+		/// returns a column with independent entries sampled from the standard normal distribution
+		/// (see [`StandardNormalScalar`])
+		pub fn random_normal(rng: &mut (impl ?Sized + rand::Rng), nrows: Rows) -> Self {
+			CwiseColDistribution {
+				nrows,
+				dist: StandardNormalScalar,
+			}
+			.sample(rng)
+		}
+
+		/// returns a column with independent entries sampled uniformly from $[0, 1)$ (see
+		/// [`StandardUniformScalar`])
+		pub fn random_uniform(rng: &mut (impl ?Sized + rand::Rng), nrows: Rows) -> Self {
+			CwiseColDistribution {
+				nrows,
+				dist: StandardUniformScalar,
+			}
+			.sample(rng)
+		}
+	}
+
+	impl<T: ComplexField, Cols: Shape> Row<T, Cols> {
+		/// returns a row with independent entries sampled from the standard normal distribution
+		/// (see [`StandardNormalScalar`])
+		pub fn random_normal(rng: &mut (impl ?Sized + rand::Rng), ncols: Cols) -> Self {
+			CwiseRowDistribution {
+				ncols,
+				dist: StandardNormalScalar,
+			}
+			.sample(rng)
+		}
+
+		/// returns a row with independent entries sampled uniformly from $[0, 1)$ (see
+		/// [`StandardUniformScalar`])
+		pub fn random_uniform(rng: &mut (impl ?Sized + rand::Rng), ncols: Cols) -> Self {
+			CwiseRowDistribution {
+				ncols,
+				dist: StandardUniformScalar,
+			}
+			.sample(rng)
+		}
+	}
+}
+
+#[cfg(all(test, feature = "rand"))]
+mod tests {
+	use super::prelude::*;
+	use crate::{Col, Mat, Row, assert, c64};
+
+	#[test]
+	fn test_random() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		let A = Mat::<f64>::random_uniform(rng, 20, 30);
+		assert!(A.shape() == (20, 30));
+		assert!(A.col_iter().all(|col| col.iter().all(|x| (0.0..1.0).contains(x))));
+
+		let A = Mat::<c64>::random_uniform(rng, 20, 30);
+		assert!(
+			A.col_iter()
+				.all(|col| col.iter().all(|x| (0.0..1.0).contains(&x.re) && (0.0..1.0).contains(&x.im)))
+		);
+		assert!(A.col_iter().any(|col| col.iter().any(|x| x.im != 0.0)));
+
+		let x = Col::<f64>::random_normal(rng, 10_000);
+		let mean = x.sum() / 10_000.0;
+		let var = x.squared_norm_l2() / 10_000.0 - mean * mean;
+		assert!(mean.abs() < 0.05);
+		assert!((var - 1.0).abs() < 0.05);
+
+		let x = Row::<c64>::random_normal(rng, 10_000);
+		assert!((x.squared_norm_l2() / 10_000.0 - 2.0).abs() < 0.1);
+
+		// the new constructors sample the same values as the generic distributions
+		let rng = &mut StdRng::seed_from_u64(1);
+		let A = Mat::<c64>::random_normal(rng, 4, 3);
+		let rng = &mut StdRng::seed_from_u64(1);
+		let B = CwiseMatDistribution {
+			nrows: 4,
+			ncols: 3,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		assert!(A == B);
+	}
 }
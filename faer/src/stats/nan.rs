@@ -0,0 +1,394 @@
+//! reductions and covariances that skip missing values
+//!
+//! missing values are encoded as `NaN`. a complex entry is missing if either of its parts is `NaN`.
+//! the reductions over a whole matrix are vectorized, with the missing entries masked out of the
+//! accumulators. for the means and variances of individual rows or columns, see
+//! [`col_mean`](super::col_mean) and [`col_varm`](super::col_varm) with [`NanHandling::Ignore`]
+//!
+//! # example
+//! ```
+//! use faer::mat;
+//! use faer::stats::nan::{nan_covariance, nan_max, nan_mean};
+//!
+//! let nan = f64::NAN;
+//! let X = mat![[1.0, 2.0], [2.0, nan], [3.0, 6.0], [nan, 8.0]];
+//!
+//! assert!(nan_mean(X.as_ref()) == 22.0 / 6.0);
+//! assert!(nan_max(X.as_ref()) == Some(8.0));
+//!
+//! // the covariance of the two columns only uses the first and third rows, where both are present
+//! let C = nan_covariance(X.as_ref());
+//! assert!(C[(0, 1)] == 4.0);
+//! assert!(C[(1, 1)] == 28.0 / 3.0);
+//! ```
+
+use super::meanvar::{from_usize, reduce};
+use crate::internal_prelude::*;
+use crate::linalg::matmul::matmul;
+use crate::get_global_parallelism;
+use faer_traits::RealReg;
+
+/// returns a view over the same entries as `mat`, with unit row stride if possible and positive
+/// strides otherwise, since the reductions don't depend on the order of the entries
+fn normalize_layout<T>(mat: MatRef<'_, T>) -> MatRef<'_, T> {
+	let mat = if mat.ncols() > 1 && mat.col_stride().unsigned_abs() == 1 {
+		mat.transpose()
+	} else {
+		mat
+	};
+	let mat = if mat.col_stride() >= 0 { mat } else { mat.reverse_cols() };
+	if mat.row_stride() >= 0 { mat } else { mat.reverse_rows() }
+}
+
+/// returns the sum and the number of the non-missing entries of `mat`
+fn sum_count<T: ComplexField>(mat: MatRef<'_, T>) -> (T, usize) {
+	struct Impl<'a, T: ComplexField> {
+		mat: MatRef<'a, T, usize, usize, ContiguousFwd>,
+	}
+
+	impl<T: ComplexField> pulp::WithSimd for Impl<'_, T> {
+		type Output = (T, usize);
+
+		#[inline(always)]
+		fn with_simd<S: pulp::Simd>(self, simd: S) -> Self::Output {
+			let Self { mat } = self;
+			with_dim!(M, mat.nrows());
+
+			let simd = SimdCtx::<T, S>::new(T::simd_ctx(simd), M);
+			let nan = simd.splat(&nan::<T>());
+			let one = simd.isplat(T::Index::truncate(1));
+
+			#[inline(always)]
+			fn process<'M, T: ComplexField, S: pulp::Simd>(
+				simd: SimdCtx<'M, T, S>,
+				acc: T::SimdVec<S>,
+				count: T::SimdIndex<S>,
+				one: T::SimdIndex<S>,
+				val: T::SimdVec<S>,
+			) -> (T::SimdVec<S>, T::SimdIndex<S>) {
+				let is_not_nan = (*simd).eq(val, val);
+				(
+					simd.select(is_not_nan, simd.add(acc, val), acc),
+					simd.iselect(is_not_nan, simd.iadd(count, one), count),
+				)
+			}
+
+			let mut acc = simd.splat(&zero::<T>());
+			let mut count_total = 0usize;
+
+			for j in 0..mat.ncols() {
+				let col = mat.col(j).as_row_shape(M);
+				let (head, body, tail) = simd.indices();
+
+				// the lane counts are flushed after each column, so that they can't overflow
+				let mut count = simd.isplat(T::Index::truncate(0));
+				if let Some(i) = head {
+					(acc, count) = process(simd, acc, count, one, simd.select(simd.head_mask(), simd.read(col, i), nan));
+				}
+				for i in body {
+					(acc, count) = process(simd, acc, count, one, simd.read(col, i));
+				}
+				if let Some(i) = tail {
+					(acc, count) = process(simd, acc, count, one, simd.select(simd.tail_mask(), simd.read(col, i), nan));
+				}
+				count_total += reduce::<T, S>(count);
+			}
+
+			(simd.reduce_sum(acc), count_total)
+		}
+	}
+
+	let mat = normalize_layout(mat);
+
+	if try_const! { T::SIMD_CAPABILITIES.is_simd() } {
+		if let Some(mat) = mat.try_as_col_major() {
+			return T::Arch::default().dispatch(Impl { mat });
+		}
+	}
+
+	let mut sum = zero::<T>();
+	let mut count = 0usize;
+	for j in 0..mat.ncols() {
+		for i in 0..mat.nrows() {
+			let val = &mat[(i, j)];
+			if !is_nan(val) {
+				sum = add(&sum, val);
+				count += 1;
+			}
+		}
+	}
+	(sum, count)
+}
+
+/// returns the largest non-missing entry of `mat`, or the smallest one if `min` is `true`
+fn extremum<T: RealField>(mat: MatRef<'_, T>, min: bool) -> Option<T> {
+	struct Impl<'a, T: RealField> {
+		mat: MatRef<'a, T, usize, usize, ContiguousFwd>,
+		seed: T,
+		min: bool,
+	}
+
+	impl<T: RealField> pulp::WithSimd for Impl<'_, T> {
+		type Output = T;
+
+		#[inline(always)]
+		fn with_simd<S: pulp::Simd>(self, simd: S) -> Self::Output {
+			let Self { mat, seed, min } = self;
+			with_dim!(M, mat.nrows());
+
+			let simd = SimdCtx::<T, S>::new(T::simd_ctx(simd), M);
+
+			// the minimum is computed as the negated maximum of the negated entries. the
+			// accumulator starts at a non-missing entry, and the missing entries never compare
+			// greater than it, so they're skipped
+			let seed = if min { neg(&seed) } else { seed };
+			let seed = simd.splat(&seed);
+
+			#[inline(always)]
+			fn process<'M, T: RealField, S: pulp::Simd>(simd: SimdCtx<'M, T, S>, acc: T::SimdVec<S>, val: T::SimdVec<S>, min: bool) -> T::SimdVec<S> {
+				let val = if min { simd.neg(val) } else { val };
+				simd.select(simd.gt(RealReg(val), RealReg(acc)), val, acc)
+			}
+
+			let mut acc = seed;
+			for j in 0..mat.ncols() {
+				let col = mat.col(j).as_row_shape(M);
+				let (head, body, tail) = simd.indices();
+
+				if let Some(i) = head {
+					let val = simd.read(col, i);
+					let val = if min { simd.neg(val) } else { val };
+					acc = process(simd, acc, simd.select(simd.head_mask(), val, seed), false);
+				}
+				for i in body {
+					acc = process(simd, acc, simd.read(col, i), min);
+				}
+				if let Some(i) = tail {
+					let val = simd.read(col, i);
+					let val = if min { simd.neg(val) } else { val };
+					acc = process(simd, acc, simd.select(simd.tail_mask(), val, seed), false);
+				}
+			}
+
+			let max = simd.reduce_max(RealReg(acc));
+			if min { neg(&max) } else { max }
+		}
+	}
+
+	let mat = normalize_layout(mat);
+
+	// first non-missing entry, in storage order
+	let mut seed = None;
+	'seed: for j in 0..mat.ncols() {
+		for i in 0..mat.nrows() {
+			if !is_nan(&mat[(i, j)]) {
+				seed = Some(copy(&mat[(i, j)]));
+				break 'seed;
+			}
+		}
+	}
+	let seed = seed?;
+
+	if try_const! { T::SIMD_CAPABILITIES.is_simd() } {
+		if let Some(mat) = mat.try_as_col_major() {
+			return Some(T::Arch::default().dispatch(Impl { mat, seed, min }));
+		}
+	}
+
+	let mut acc = seed;
+	for j in 0..mat.ncols() {
+		for i in 0..mat.nrows() {
+			let val = &mat[(i, j)];
+			if (min && *val < acc) || (!min && *val > acc) {
+				acc = copy(val);
+			}
+		}
+	}
+	Some(acc)
+}
+
+/// returns the number of non-missing entries of `mat`
+pub fn nan_count<T: ComplexField>(mat: MatRef<'_, T>) -> usize {
+	sum_count(mat).1
+}
+
+/// returns the sum of the non-missing entries of `mat`, or zero if all the entries are missing
+pub fn nan_sum<T: ComplexField>(mat: MatRef<'_, T>) -> T {
+	sum_count(mat).0
+}
+
+/// returns the mean of the non-missing entries of `mat`, or `NaN` if all the entries are missing
+#[math]
+pub fn nan_mean<T: ComplexField>(mat: MatRef<'_, T>) -> T {
+	let (sum, count) = sum_count(mat);
+	if count == 0 {
+		nan()
+	} else {
+		mul_real(sum, recip(from_usize::<T::Real>(count)))
+	}
+}
+
+/// returns the largest non-missing entry of `mat`, or `None` if all the entries are missing
+pub fn nan_max<T: RealField>(mat: MatRef<'_, T>) -> Option<T> {
+	extremum(mat, false)
+}
+
+/// returns the smallest non-missing entry of `mat`, or `None` if all the entries are missing
+pub fn nan_min<T: RealField>(mat: MatRef<'_, T>) -> Option<T> {
+	extremum(mat, true)
+}
+
+/// computes the pairwise-complete sample covariance matrix of the columns of `data`, whose rows
+/// are observations
+///
+/// the covariance of the columns $i$ and $j$ is computed from the rows where both entries are
+/// present, with the means of the two columns over these same rows, and $n_{ij} - 1$ as the
+/// normalization factor, where $n_{ij}$ is the number of such rows. the entries with fewer than two
+/// complete rows are `NaN`. without missing values, this matches
+/// [`covariance`](super::pca::covariance)
+///
+/// the result is self-adjoint, but unlike the covariance of complete data, it isn't necessarily
+/// positive semidefinite
+#[math]
+pub fn nan_covariance<T: ComplexField>(data: MatRef<'_, T>) -> Mat<T> {
+	let (n, p) = data.shape();
+	let par = get_global_parallelism();
+
+	// the covariance is invariant under a shift of each column, so the columns are first centered
+	// with their means over the non-missing entries, which avoids cancellation in the sums of
+	// products below
+	let mut mean = Row::<T>::zeros(p);
+	super::row_mean(mean.as_mut(), data, super::NanHandling::Ignore);
+
+	// missing entries are set to zero in `X`, and to zero in the indicator matrix `W`
+	let mut X = Mat::<T>::zeros(n, p);
+	let mut W = Mat::<T>::zeros(n, p);
+	for j in 0..p {
+		let mean = if is_nan(mean[j]) { zero() } else { copy(mean[j]) };
+		zip!(X.col_mut(j), W.col_mut(j), data.col(j)).for_each(|unzip!(x, w, d)| {
+			if !is_nan(*d) {
+				*x = *d - mean;
+				*w = one();
+			}
+		});
+	}
+
+	// for each pair of columns, the number of complete rows, the sums of each column over these
+	// rows, and the sum of their products
+	let mut count = Mat::<T>::zeros(p, p);
+	let mut sum = Mat::<T>::zeros(p, p);
+	let mut prod = Mat::<T>::zeros(p, p);
+	matmul(count.as_mut(), Accum::Replace, W.transpose(), W.as_ref(), one::<T>(), par);
+	matmul(sum.as_mut(), Accum::Replace, X.transpose(), W.as_ref(), one::<T>(), par);
+	matmul(prod.as_mut(), Accum::Replace, X.adjoint(), X.as_ref(), one::<T>(), par);
+
+	// $\sum (x_i - \mu_i)^* (x_j - \mu_j) = \sum x_i^* x_j - n_{ij} \mu_i^* \mu_j$, with
+	// $\mu_i = s_{ij} / n_{ij}$ and $\mu_j = s_{ji} / n_{ij}$
+	Mat::from_fn(p, p, |i, j| {
+		let n = real(count[(i, j)]);
+		if n < from_f64::<T::Real>(2.0) {
+			nan::<T>()
+		} else {
+			let cross = mul_real(conj(sum[(i, j)]) * sum[(j, i)], recip(n));
+			mul_real(prod[(i, j)] - cross, recip(n - one::<T::Real>()))
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{assert, c64};
+	use crate::stats::pca::covariance;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+
+	#[test]
+	fn test_nan_reductions() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let nan = f64::NAN;
+
+		for (m, n) in [(0, 0), (1, 1), (3, 2), (17, 5), (64, 3)] {
+			let A = Mat::<f64>::random_normal(rng, m, n);
+			let mut B = A.clone();
+			let missing = Mat::from_fn(m, n, |i, j| (3 * i + j) % 4 == 1);
+			B.copy_from_where(Mat::<f64>::full(m, n, nan), &missing);
+
+			let kept = (0..n).flat_map(|j| (0..m).map(move |i| (i, j))).filter(|&(i, j)| !missing[(i, j)]);
+			let count = kept.clone().count();
+			let sum = kept.clone().map(|(i, j)| A[(i, j)]).sum::<f64>();
+			let max = kept.clone().map(|(i, j)| A[(i, j)]).reduce(f64::max);
+			let min = kept.clone().map(|(i, j)| A[(i, j)]).reduce(f64::min);
+
+			// column-major, row-major and strided layouts
+			let Bt = B.transpose().to_owned();
+			let mut storage = Mat::<f64>::zeros(2 * m, n);
+			for i in 0..m {
+				storage.row_mut(2 * i).copy_from(B.row(i));
+			}
+			let strided = storage.as_ref().subrows(0, 2 * m);
+			let strided = unsafe { MatRef::from_raw_parts(strided.as_ptr(), m, n, 2, strided.col_stride()) };
+
+			for B in [B.as_ref(), Bt.transpose(), strided, B.as_ref().reverse_rows_and_cols()] {
+				assert!(nan_count(B) == count);
+				assert!((nan_sum(B) - sum).abs() < 1e-12);
+				if count == 0 {
+					assert!(nan_mean(B).is_nan());
+				} else {
+					assert!((nan_mean(B) - sum / count as f64).abs() < 1e-12);
+				}
+				assert!(nan_max(B) == max);
+				assert!(nan_min(B) == min);
+			}
+		}
+
+		assert!(nan_max(Mat::<f64>::full(3, 3, nan).as_ref()) == None);
+		assert!(nan_sum(Mat::<f64>::full(3, 3, nan).as_ref()) == 0.0);
+
+		let z = Mat::<c64>::random_normal(rng, 5, 5);
+		let mut w = z.clone();
+		w[(1, 2)] = c64::new(1.0, nan);
+		w[(3, 0)] = c64::new(nan, 0.0);
+		assert!(nan_count(w.as_ref()) == 23);
+		assert!((nan_sum(w.as_ref()) - (z.as_ref().sum() - z[(1, 2)] - z[(3, 0)])).norm() < 1e-12);
+	}
+
+	#[test]
+	fn test_nan_covariance() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+		let nan = f64::NAN;
+
+		// without missing values, this is the usual covariance
+		let X = Mat::<c64>::random_normal(rng, 20, 4);
+		let C = nan_covariance(X.as_ref());
+		assert!(C ~ covariance(X.as_ref()));
+
+		let mut X = Mat::<f64>::random_normal(rng, 30, 4) + Mat::full(30, 4, 100.0);
+		for i in 0..30 {
+			if i % 3 == 0 {
+				X[(i, 0)] = nan;
+			}
+			if i % 5 == 1 {
+				X[(i, 2)] = nan;
+			}
+		}
+		// the last column has a single non-missing entry
+		X.col_mut(3).fill(nan);
+		X[(7, 3)] = 1.0;
+
+		let C = nan_covariance(X.as_ref());
+		for i in 0..3 {
+			for j in 0..3 {
+				let rows = (0..30)
+					.filter(|&k| !X[(k, i)].is_nan() && !X[(k, j)].is_nan())
+					.collect::<alloc::vec::Vec<_>>();
+				let complete = Mat::from_fn(rows.len(), 2, |k, c| X[(rows[k], [i, j][c])]);
+				let expected = covariance(complete.as_ref())[(0, 1)];
+				assert!((C[(i, j)] - expected).abs() < 1e-12);
+			}
+			assert!(C[(i, 3)].is_nan());
+			assert!(C[(3, i)].is_nan());
+		}
+	}
+}
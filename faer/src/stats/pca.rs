@@ -0,0 +1,385 @@
+//! covariance, principal component analysis and whitening
+//!
+//! the functions in this module take a data matrix whose rows are observations and whose columns
+//! are variables. the sample covariance of the $n \times p$ data matrix $X$ with column means
+//! $\mu$ is $C = (X - 1 \mu)^H (X - 1 \mu) / (n - 1)$
+//!
+//! # example
+//! ```
+//! use faer::mat;
+//! use faer::stats::pca::{Whitening, covariance, pca, whitening};
+//!
+//! let X = mat![[1.0, 2.0], [2.0, 4.1], [3.0, 5.9], [4.0, 8.0]];
+//!
+//! // the two variables are almost perfectly correlated, so the first component explains almost
+//! // all the variance
+//! let p = pca(X.as_ref(), 1).unwrap();
+//! assert!(p.explained_variance_ratio[0] > 0.99);
+//!
+//! // the whitened data has identity covariance
+//! let w = whitening(X.as_ref(), Whitening::Zca, 0.0).unwrap();
+//! let Y: faer::Mat<f64> = w.apply(X.as_ref());
+//! let C: faer::Mat<f64> = covariance(Y.as_ref());
+//! assert!((C - faer::Mat::<f64>::identity(2, 2)).norm_max() < 1e-10);
+//! ```
+
+use crate::internal_prelude::*;
+use crate::linalg::matmul::triangular::BlockStructure;
+use crate::linalg::solvers::{SelfAdjointEigen, Svd, SvdError};
+use crate::stats::{NanHandling, row_mean};
+use crate::{assert, get_global_parallelism};
+use linalg::evd::EvdError;
+
+/// subtracts the mean of each column of `data` from that column, and returns the means
+pub fn center_columns<T: ComplexField>(data: MatMut<'_, T>) -> Row<T> {
+	let mut data = data;
+	let mut mean = Row::zeros(data.ncols());
+	row_mean(mean.as_mut(), data.rb(), NanHandling::Propagate);
+	data.sub_row_broadcast(mean.as_ref());
+	mean
+}
+
+/// computes the covariance matrix of the columns of `data`, assuming they are already centered
+#[math]
+fn covariance_of_centered<T: ComplexField>(centered: MatRef<'_, T>) -> Mat<T> {
+	let (n, p) = centered.shape();
+	let mut cov = Mat::zeros(p, p);
+
+	// only the lower half is computed, the upper half is filled by symmetry
+	linalg::matmul::triangular::matmul(
+		cov.as_mut(),
+		BlockStructure::TriangularLower,
+		Accum::Replace,
+		centered.adjoint(),
+		BlockStructure::Rectangular,
+		centered,
+		BlockStructure::Rectangular,
+		from_f64::<T>(1.0 / (n - 1) as f64),
+		get_global_parallelism(),
+	);
+
+	for j in 0..p {
+		for i in 0..j {
+			cov[(i, j)] = conj(cov[(j, i)]);
+		}
+	}
+	cov
+}
+
+/// computes the sample covariance matrix of the columns of `data`, using $n - 1$ as the
+/// normalization factor
+///
+/// # panics
+///
+/// panics if `data` has fewer than two rows
+#[track_caller]
+pub fn covariance<T: ComplexField>(data: MatRef<'_, impl Conjugate<Canonical = T>>) -> Mat<T> {
+	assert!(data.nrows() >= 2);
+
+	let mut centered = data.to_owned();
+	center_columns(centered.as_mut());
+	covariance_of_centered(centered.as_ref())
+}
+
+/// computes the correlation matrix of the columns of `data`
+///
+/// the entries corresponding to a column with zero variance are `NaN`
+///
+/// # panics
+///
+/// panics if `data` has fewer than two rows
+#[track_caller]
+#[math]
+pub fn correlation<T: ComplexField>(data: MatRef<'_, impl Conjugate<Canonical = T>>) -> Mat<T> {
+	let mut cov = covariance(data);
+	let p = cov.nrows();
+
+	let inv_std = Col::<T::Real>::from_fn(p, |i| recip(sqrt(real(cov[(i, i)]))));
+	for j in 0..p {
+		for i in 0..p {
+			cov[(i, j)] = mul_real(cov[(i, j)], inv_std[i] * inv_std[j]);
+		}
+	}
+	cov
+}
+
+/// principal component analysis of a data matrix, computed by [`pca`]
+#[derive(Clone, Debug)]
+pub struct Pca<T: ComplexField> {
+	/// mean of each column of the data
+	pub mean: Row<T>,
+	/// matrix whose columns are the principal axes, sorted by decreasing explained variance
+	pub components: Mat<T>,
+	/// variance of the data along each principal axis
+	pub explained_variance: Col<T::Real>,
+	/// fraction of the total variance of the data that is explained by each principal axis
+	pub explained_variance_ratio: Col<T::Real>,
+}
+
+impl<T: ComplexField> Pca<T> {
+	/// projects the rows of `data` onto the principal axes, after subtracting the mean
+	///
+	/// # panics
+	///
+	/// panics if `data` doesn't have the same number of columns as the data the analysis was
+	/// computed from
+	#[track_caller]
+	pub fn transform(&self, data: MatRef<'_, impl Conjugate<Canonical = T>>) -> Mat<T> {
+		assert!(data.ncols() == self.mean.ncols());
+
+		let mut centered = data.to_owned();
+		centered.as_mut().sub_row_broadcast(self.mean.as_ref());
+		centered * &self.components
+	}
+}
+
+/// computes the principal component analysis of `data`, keeping the first `n_components` axes
+///
+/// the principal axes are the right singular vectors of the centered data, and the variance along
+/// each axis is $\sigma_i^2 / (n - 1)$
+///
+/// # panics
+///
+/// panics if `data` has fewer than two rows, or if `n_components` is larger than the number of
+/// rows or columns of `data`
+#[track_caller]
+#[math]
+pub fn pca<T: ComplexField>(data: MatRef<'_, impl Conjugate<Canonical = T>>, n_components: usize) -> Result<Pca<T>, SvdError> {
+	let (n, p) = data.shape();
+	assert!(all(n >= 2, n_components <= Ord::min(n, p)));
+
+	let mut centered = data.to_owned();
+	let mean = center_columns(centered.as_mut());
+	let svd = Svd::new_thin(centered.as_ref())?;
+	let s = svd.S().column_vector();
+
+	let scale = from_f64::<T::Real>(1.0 / (n - 1) as f64);
+	let variance = Col::<T::Real>::from_fn(s.nrows(), |i| abs2(s[i]) * scale);
+	let mut total = zero::<T::Real>();
+	for v in variance.iter() {
+		total = total + *v;
+	}
+
+	let explained_variance = variance.get(..n_components).to_owned();
+	let explained_variance_ratio = Col::from_fn(n_components, |i| explained_variance[i] / total);
+
+	Ok(Pca {
+		mean,
+		components: svd.V().get(.., ..n_components).to_owned(),
+		explained_variance,
+		explained_variance_ratio,
+	})
+}
+
+/// kind of whitening transform
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Whitening {
+	/// $W = V \Lambda^{-1/2}$, which rotates the data onto its principal axes before rescaling them
+	Pca,
+	/// $W = V \Lambda^{-1/2} V^H$, the whitening transform that keeps the whitened data as close as
+	/// possible to the original data
+	Zca,
+}
+
+/// whitening transform of a data matrix, computed by [`whitening`]
+#[derive(Clone, Debug)]
+pub struct WhiteningTransform<T: ComplexField> {
+	/// mean of each column of the data
+	pub mean: Row<T>,
+	/// matrix $W$ such that $(X - 1 \mu) W$ has identity covariance
+	pub matrix: Mat<T>,
+}
+
+impl<T: ComplexField> WhiteningTransform<T> {
+	/// whitens the rows of `data`, i.e., returns $(X - 1 \mu) W$
+	///
+	/// # panics
+	///
+	/// panics if `data` doesn't have the same number of columns as the data the transform was
+	/// computed from
+	#[track_caller]
+	pub fn apply(&self, data: MatRef<'_, impl Conjugate<Canonical = T>>) -> Mat<T> {
+		assert!(data.ncols() == self.mean.ncols());
+
+		let mut centered = data.to_owned();
+		centered.as_mut().sub_row_broadcast(self.mean.as_ref());
+		centered * &self.matrix
+	}
+}
+
+/// computes the whitening transform of `data`, from the eigendecomposition $V \Lambda V^H$ of its
+/// covariance matrix
+///
+/// `eps` is added to the eigenvalues before they are inverted, which regularizes the transform
+/// when the covariance matrix is singular or ill-conditioned. for [`Whitening::Pca`], the columns
+/// of the transform are sorted by decreasing variance
+///
+/// # panics
+///
+/// panics if `data` has fewer than two rows
+#[track_caller]
+pub fn whitening<T: ComplexField>(
+	data: MatRef<'_, impl Conjugate<Canonical = T>>,
+	kind: Whitening,
+	eps: T::Real,
+) -> Result<WhiteningTransform<T>, EvdError> {
+	let (n, p) = data.shape();
+	assert!(n >= 2);
+
+	let mut centered = data.to_owned();
+	let mean = center_columns(centered.as_mut());
+	let cov = covariance_of_centered(centered.as_ref());
+
+	let evd = SelfAdjointEigen::new(cov.as_ref(), Side::Lower)?;
+	let (V, S) = (evd.U(), evd.S().column_vector());
+
+	// eigenvalues are sorted in nondecreasing order, so the columns are reversed
+	let W = Mat::from_fn(p, p, |i, j| {
+		let k = p - 1 - j;
+		mul_real(&V[(i, k)], &recip(&sqrt(&add(&real(&S[k]), &eps))))
+	});
+
+	let matrix = match kind {
+		Whitening::Pca => W,
+		Whitening::Zca => &W * V.reverse_cols().adjoint(),
+	};
+
+	Ok(WhiteningTransform { mean, matrix })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	fn rand_data(rng: &mut StdRng, n: usize, p: usize) -> Mat<c64> {
+		let X = CwiseMatDistribution {
+			nrows: n,
+			ncols: p,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+
+		// correlate the variables and shift their means
+		let M = CwiseMatDistribution {
+			nrows: p,
+			ncols: p,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let mut X = X * M;
+		X.as_mut().add_row_broadcast(Row::<c64>::from_fn(p, |j| c64::new(j as f64, 1.0)).as_ref());
+		X
+	}
+
+	#[test]
+	fn test_covariance() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (n, p) = (30, 5);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 256.0);
+		let X = rand_data(rng, n, p);
+
+		let mut centered = X.clone();
+		let mean = center_columns(centered.as_mut());
+		for j in 0..p {
+			let mut sum = c64::new(0.0, 0.0);
+			for i in 0..n {
+				sum += X[(i, j)];
+			}
+			assert!((mean[j] - sum / n as f64).norm() < 1e-12);
+		}
+
+		let C = covariance(X.as_ref());
+		let expected = centered.adjoint() * &centered * Scale(c64::new(1.0 / (n - 1) as f64, 0.0));
+		assert!(C ~ expected);
+
+		let C = covariance(X.conjugate());
+		assert!(C ~ expected.conjugate().to_owned());
+
+		let R = correlation(X.as_ref());
+		for i in 0..p {
+			assert!((R[(i, i)] - c64::new(1.0, 0.0)).norm() < 1e-12);
+			for j in 0..p {
+				let expected = expected[(i, j)] / (expected[(i, i)].re * expected[(j, j)].re).sqrt();
+				assert!((R[(i, j)] - expected).norm() < 1e-12);
+			}
+		}
+	}
+
+	#[test]
+	fn test_pca() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (n, p) = (40, 6);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0);
+		let X = rand_data(rng, n, p);
+		let C = covariance(X.as_ref());
+
+		let full = pca(X.as_ref(), p).unwrap();
+		assert!(full.components.adjoint() * &full.components ~ Mat::<c64>::identity(p, p));
+
+		// the principal axes diagonalize the covariance matrix
+		let D = full.components.adjoint() * &C * &full.components;
+		let expected = Mat::from_fn(p, p, |i, j| {
+			if i == j {
+				c64::new(full.explained_variance[i], 0.0)
+			} else {
+				c64::new(0.0, 0.0)
+			}
+		});
+		assert!(D ~ expected);
+
+		let mut total = 0.0;
+		for i in 0..p {
+			total += full.explained_variance_ratio[i];
+			if i > 0 {
+				assert!(full.explained_variance[i] <= full.explained_variance[i - 1]);
+			}
+		}
+		assert!((total - 1.0).abs() < 1e-12);
+
+		let k = 2;
+		let truncated = pca(X.as_ref(), k).unwrap();
+		assert!(truncated.components.shape() == (p, k));
+		assert!(truncated.explained_variance_ratio.nrows() == k);
+		assert!((truncated.explained_variance_ratio[1] - full.explained_variance_ratio[1]).abs() < 1e-12);
+
+		// the transformed data is uncorrelated, with the explained variances on the diagonal
+		let Y = truncated.transform(X.as_ref());
+		let expected = Mat::from_fn(k, k, |i, j| {
+			if i == j {
+				c64::new(truncated.explained_variance[i], 0.0)
+			} else {
+				c64::new(0.0, 0.0)
+			}
+		});
+		assert!(covariance(Y.as_ref()) ~ expected);
+	}
+
+	#[test]
+	fn test_whitening() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (n, p) = (40, 6);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0);
+		let X = rand_data(rng, n, p);
+
+		for kind in [Whitening::Pca, Whitening::Zca] {
+			let w = whitening(X.as_ref(), kind, 0.0).unwrap();
+			let Y = w.apply(X.as_ref());
+			assert!(covariance(Y.as_ref()) ~ Mat::<c64>::identity(p, p));
+		}
+
+		// the zca transform is self-adjoint
+		let w = whitening(X.as_ref(), Whitening::Zca, 0.0).unwrap();
+		assert!(w.matrix.adjoint().to_owned() ~ w.matrix);
+
+		// the pca whitened data is a rescaling of the principal components, up to their phases
+		let w = whitening(X.as_ref(), Whitening::Pca, 0.0).unwrap();
+		let full = pca(X.as_ref(), p).unwrap();
+		for j in 0..p {
+			let dot = full.components.col(j).adjoint() * w.matrix.col(j);
+			assert!((dot.norm() - 1.0 / full.explained_variance[j].sqrt()).abs() < 1e-10);
+		}
+	}
+}
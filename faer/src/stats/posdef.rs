@@ -0,0 +1,395 @@
+//! positive definiteness tests and the nearest correlation matrix
+//!
+//! empirical covariance and correlation matrices (e.g., computed from incomplete data, or assembled
+//! from separately estimated entries) often fail to be positive semidefinite, which makes them
+//! unusable for a cholesky factorization. [`is_positive_definite`] detects this case, and
+//! [`nearest_correlation`] repairs a correlation matrix by finding the closest one, in the
+//! frobenius norm, that is positive semidefinite with a unit diagonal
+//!
+//! # example
+//! ```
+//! use faer::stats::posdef::{NearestCorrelationParams, is_positive_definite, nearest_correlation};
+//! use faer::{Mat, mat};
+//!
+//! // the pairwise correlations are inconsistent
+//! let A = mat![[1.0, 0.9, -0.9], [0.9, 1.0, 0.9], [-0.9, 0.9, 1.0]];
+//! assert!(!is_positive_definite(A.as_ref(), 0.0));
+//!
+//! // make the result strictly positive definite, so that it can be factorized
+//! let params = NearestCorrelationParams { min_eigenvalue: 1e-8, ..Default::default() };
+//! let mut C = Mat::<f64>::zeros(3, 3);
+//! nearest_correlation(C.as_mut(), A.as_ref(), params).unwrap();
+//!
+//! assert!(is_positive_definite(C.as_ref(), 0.0));
+//! assert!(C[(0, 0)] == 1.0);
+//! ```
+
+use crate::internal_prelude::*;
+use crate::linalg::cholesky::llt::factor::{LltError, cholesky_in_place, cholesky_in_place_scratch};
+use crate::linalg::solvers::SelfAdjointEigen;
+use crate::{assert, get_global_parallelism};
+use dyn_stack::MemBuffer;
+use linalg::evd::EvdError;
+
+/// result of [`positive_definite_check`]
+#[derive(Copy, Clone, Debug)]
+pub struct PositiveDefiniteCheck<T: RealField> {
+	/// index $k$ of the pivot at which the factorization of $A - \tau I$ broke down, if any. in that
+	/// case, the leading $(k + 1) \times (k + 1)$ principal submatrix of $A - \tau I$ is not
+	/// positive definite
+	pub failed_pivot: Option<usize>,
+	/// smallest pivot of the factorization of $A - \tau I$, among the ones that were computed
+	/// before the breakdown, or `None` if there were none
+	pub min_pivot: Option<T>,
+}
+
+impl<T: RealField> PositiveDefiniteCheck<T> {
+	/// returns `true` if the factorization succeeded
+	#[inline]
+	pub fn is_positive_definite(&self) -> bool {
+		self.failed_pivot.is_none()
+	}
+}
+
+/// attempts the $L L^H$ factorization of $A - \tau I$, where $\tau$ is `tol`, and reports where
+/// it broke down
+///
+/// this succeeds if and only if the smallest eigenvalue of the self-adjoint matrix $A$ is
+/// (numerically) larger than $\tau$. only the lower triangular half of $A$ is accessed
+///
+/// # panics
+///
+/// panics if `A` is not square
+#[track_caller]
+#[math]
+pub fn positive_definite_check<T: ComplexField>(A: MatRef<'_, impl Conjugate<Canonical = T>>, tol: T::Real) -> PositiveDefiniteCheck<T::Real> {
+	assert!(A.nrows() == A.ncols());
+	let n = A.nrows();
+	let par = get_global_parallelism();
+
+	let mut L = Mat::<T>::zeros(n, n);
+	L.copy_from_triangular_lower(A);
+	for i in 0..n {
+		L[(i, i)] = from_real(real(L[(i, i)]) - tol);
+	}
+
+	let result = cholesky_in_place(
+		L.as_mut(),
+		default(),
+		par,
+		MemStack::new(&mut MemBuffer::new(cholesky_in_place_scratch::<T>(n, par, default()))),
+		default(),
+	);
+
+	let failed_pivot = match result {
+		Ok(_) => None,
+		Err(LltError::NonPositivePivot { index }) => Some(index),
+	};
+
+	// the columns to the left of the failed pivot are fully factorized
+	let mut min_pivot = None;
+	for i in 0..failed_pivot.unwrap_or(n) {
+		let pivot = abs2(L[(i, i)]);
+		min_pivot = match min_pivot {
+			Some(min) if min <= pivot => Some(min),
+			_ => Some(pivot),
+		};
+	}
+
+	PositiveDefiniteCheck { failed_pivot, min_pivot }
+}
+
+/// returns `true` if the smallest eigenvalue of the self-adjoint matrix $A$ is (numerically)
+/// larger than `tol`
+///
+/// see [`positive_definite_check`] for more details about why the test fails
+///
+/// # panics
+///
+/// panics if `A` is not square
+#[track_caller]
+pub fn is_positive_definite<T: ComplexField>(A: MatRef<'_, impl Conjugate<Canonical = T>>, tol: T::Real) -> bool {
+	positive_definite_check(A, tol).is_positive_definite()
+}
+
+/// nearest correlation matrix parameters
+#[derive(Copy, Clone, Debug)]
+pub struct NearestCorrelationParams<T: RealField> {
+	/// the iteration stops once the relative change of the iterates between two steps is below
+	/// this threshold
+	pub rel_tolerance: T,
+	/// maximum number of iterations
+	pub max_iters: usize,
+	/// lower bound on the eigenvalues of the positive semidefinite projection. a positive value
+	/// makes the result positive definite, so that it can be factorized with a cholesky
+	/// decomposition
+	pub min_eigenvalue: T,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// nearest correlation matrix result
+#[derive(Copy, Clone, Debug)]
+pub struct NearestCorrelationInfo<T: RealField> {
+	/// relative change of the iterates at the final step
+	pub rel_change: T,
+	/// number of iterations executed by the algorithm
+	pub iter_count: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// nearest correlation matrix error
+#[derive(Copy, Clone, Debug)]
+pub enum NearestCorrelationError<T: RealField> {
+	/// an eigendecomposition failed to converge
+	Evd(EvdError),
+	/// convergence failure
+	NoConvergence {
+		/// relative change of the iterates at the final step
+		rel_change: T,
+	},
+}
+
+impl<T: RealField> Default for NearestCorrelationParams<T> {
+	#[inline]
+	#[math]
+	fn default() -> Self {
+		Self {
+			rel_tolerance: sqrt(eps::<T>()),
+			max_iters: 1000,
+			min_eigenvalue: zero(),
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+impl<T: RealField> From<EvdError> for NearestCorrelationError<T> {
+	#[inline]
+	fn from(value: EvdError) -> Self {
+		Self::Evd(value)
+	}
+}
+
+/// projects the self-adjoint matrix `A` onto the matrices whose eigenvalues are larger than or
+/// equal to `min_eigenvalue`. only the lower triangular half of `A` is accessed
+#[math]
+fn project_eigenvalues<T: ComplexField>(A: MatRef<'_, T>, min_eigenvalue: T::Real) -> Result<Mat<T>, EvdError> {
+	let evd = SelfAdjointEigen::new(A, Side::Lower)?;
+	let U = evd.U();
+	let S = evd.S().column_vector();
+
+	let mut US = U.to_owned();
+	US.as_mut()
+		.scale_cols(Row::<T>::from_fn(S.nrows(), |i| from_real(max(real(S[i]), min_eigenvalue))).as_ref());
+	Ok(US * U.adjoint())
+}
+
+/// computes the nearest correlation matrix to the self-adjoint matrix $A$ in the frobenius norm,
+/// i.e., the closest positive semidefinite matrix with a unit diagonal, and stores it in `out`
+///
+/// the algorithm is the alternating projections method of higham, with dykstra's correction,
+/// which alternates between the projection onto the positive semidefinite matrices and the
+/// projection onto the matrices with a unit diagonal. the result is then rescaled to have an exact
+/// unit diagonal. only the lower triangular half of $A$ is accessed
+///
+/// # errors
+///
+/// returns an error if an eigendecomposition fails, or if the iteration doesn't converge within
+/// the maximum number of iterations
+///
+/// # panics
+///
+/// panics if `A` is not square, or if `out` doesn't have the same shape as `A`
+#[track_caller]
+pub fn nearest_correlation<T: ComplexField>(
+	out: MatMut<'_, T>,
+	A: MatRef<'_, impl Conjugate<Canonical = T>>,
+	params: NearestCorrelationParams<T::Real>,
+) -> Result<NearestCorrelationInfo<T::Real>, NearestCorrelationError<T::Real>> {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, out.nrows() == n, out.ncols() == n));
+	let mut out = out;
+
+	// self-adjoint copy of the input
+	let mut Y = Mat::<T>::zeros(n, n);
+	Y.copy_from_triangular_lower(A);
+	for j in 0..n {
+		for i in j + 1..n {
+			Y[(j, i)] = conj(&Y[(i, j)]);
+		}
+	}
+
+	let mut X = Y.clone();
+	let mut dS = Mat::<T>::zeros(n, n);
+	let mut rel_change = infinity::<T::Real>();
+	let mut iter_count = 0;
+	let mut converged = false;
+
+	while iter_count < params.max_iters {
+		iter_count += 1;
+
+		let R = &Y - &dS;
+		let X_new = project_eigenvalues(R.as_ref(), copy(&params.min_eigenvalue))?;
+		dS = &X_new - &R;
+
+		let mut Y_new = X_new.clone();
+		for i in 0..n {
+			Y_new[(i, i)] = one();
+		}
+
+		let norm_x = X_new.norm_l2();
+		let norm_y = Y_new.norm_l2();
+		rel_change = max(
+			&max(&div(&(&X_new - &X).norm_l2(), &norm_x), &div(&(&Y_new - &Y).norm_l2(), &norm_y)),
+			&div(&(&Y_new - &X_new).norm_l2(), &norm_y),
+		);
+
+		X = X_new;
+		Y = Y_new;
+
+		if rel_change <= params.rel_tolerance {
+			converged = true;
+			break;
+		}
+	}
+
+	if !converged {
+		return Err(NearestCorrelationError::NoConvergence { rel_change });
+	}
+
+	// scale the positive semidefinite iterate to an exact unit diagonal, which preserves its
+	// definiteness
+	let d = Col::<T::Real>::from_fn(n, |i| recip(&sqrt(&real(&X[(i, i)]))));
+	for j in 0..n {
+		for i in j..n {
+			let value = if i == j { one() } else { mul_real(&X[(i, j)], &mul(&d[i], &d[j])) };
+			out[(j, i)] = conj(&value);
+			out[(i, j)] = value;
+		}
+	}
+
+	Ok(NearestCorrelationInfo {
+		rel_change,
+		iter_count,
+		non_exhaustive: NonExhaustive(()),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_positive_definite_check() {
+		let A = Mat::<f64>::from_fn(4, 4, |i, j| if i == j { [4.0, 1.0, 2.0, 3.0][i] } else { 0.0 });
+
+		let check = positive_definite_check(A.as_ref(), 0.0);
+		assert!(check.is_positive_definite());
+		assert!(check.failed_pivot.is_none());
+		assert!(check.min_pivot == Some(1.0));
+
+		assert!(is_positive_definite(A.as_ref(), 0.5));
+		let check = positive_definite_check(A.as_ref(), 1.5);
+		assert!(check.failed_pivot == Some(1));
+		assert!((check.min_pivot.unwrap() - 2.5).abs() < 1e-14);
+
+		let mut B = A.clone();
+		B[(3, 3)] = -1.0;
+		assert!(positive_definite_check(B.as_ref(), 0.0).failed_pivot == Some(3));
+
+		// only the lower half is accessed
+		let mut C = Mat::<c64>::from_fn(3, 3, |i, j| if i == j { c64::new(2.0, 0.0) } else { c64::new(0.0, 0.0) });
+		C[(0, 1)] = c64::new(100.0, 0.0);
+		assert!(is_positive_definite(C.as_ref(), 0.0));
+		C[(1, 0)] = c64::new(0.0, 3.0);
+		assert!(!is_positive_definite(C.as_ref(), 0.0));
+		assert!(!is_positive_definite(C.conjugate(), 0.0));
+
+		assert!(is_positive_definite(Mat::<f64>::zeros(0, 0).as_ref(), 0.0));
+		assert!(positive_definite_check(Mat::<f64>::zeros(0, 0).as_ref(), 0.0).min_pivot.is_none());
+	}
+
+	#[test]
+	fn test_nearest_correlation() {
+		// example from higham's paper, with a known solution
+		let A = Mat::<f64>::from_fn(4, 4, |i, j| match i.abs_diff(j) {
+			0 => 2.0,
+			1 => -1.0,
+			_ => 0.0,
+		});
+		let expected = [
+			[1.0000, -0.8084, 0.1916, 0.1068],
+			[-0.8084, 1.0000, -0.6562, 0.1916],
+			[0.1916, -0.6562, 1.0000, -0.8084],
+			[0.1068, 0.1916, -0.8084, 1.0000],
+		];
+
+		let params = NearestCorrelationParams {
+			rel_tolerance: 1e-12,
+			..Default::default()
+		};
+		let mut C = Mat::<f64>::zeros(4, 4);
+		let info = nearest_correlation(C.as_mut(), A.as_ref(), params).unwrap();
+		assert!(info.rel_change <= 1e-12);
+
+		for i in 0..4 {
+			for j in 0..4 {
+				assert!((C[(i, j)] - expected[i][j]).abs() < 1e-4);
+			}
+		}
+		assert!(is_positive_definite(C.as_ref(), -1e-10));
+
+		// a valid correlation matrix is left unchanged
+		let mut D = Mat::<f64>::zeros(4, 4);
+		nearest_correlation(D.as_mut(), C.as_ref(), params).unwrap();
+		assert!((&D - &C).norm_max() < 1e-8);
+
+		let params = NearestCorrelationParams { max_iters: 1, ..params };
+		assert!(matches!(
+			nearest_correlation(D.as_mut(), A.as_ref(), params),
+			Err(NearestCorrelationError::NoConvergence { .. })
+		));
+	}
+
+	#[test]
+	fn test_nearest_correlation_pd() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 8;
+
+		// random hermitian matrix with a unit diagonal and large off-diagonal entries
+		let B = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let A = Mat::from_fn(n, n, |i, j| {
+			if i == j {
+				c64::new(1.0, 0.0)
+			} else {
+				(B[(i, j)] + B[(j, i)].conj()) * 0.5
+			}
+		});
+		assert!(!is_positive_definite(A.as_ref(), 0.0));
+
+		let params = NearestCorrelationParams {
+			min_eigenvalue: 1e-6,
+			..Default::default()
+		};
+		let mut C = Mat::<c64>::zeros(n, n);
+		nearest_correlation(C.as_mut(), A.as_ref(), params).unwrap();
+
+		assert!(is_positive_definite(C.as_ref(), 0.0));
+		for i in 0..n {
+			assert!(C[(i, i)] == c64::new(1.0, 0.0));
+			for j in 0..n {
+				assert!(C[(i, j)] == C[(j, i)].conj());
+			}
+		}
+	}
+}
@@ -0,0 +1,203 @@
+//! test matrix generators
+//!
+//! the deterministic generators ([`hilbert`], [`vandermonde`]) only depend on their arguments,
+//! while the random ones take a random number generator. seeding it (e.g., with
+//! `StdRng::seed_from_u64`) makes the generated matrices reproducible
+//!
+//! # example
+//! ```
+//! use faer::prelude::*;
+//! use faer::stats::testmat;
+//! use rand::prelude::*;
+//!
+//! let rng = &mut StdRng::seed_from_u64(0);
+//! let A: Mat<f64> = testmat::random_with_condition_number(rng, 6, 4, 1e8);
+//!
+//! let s = A.singular_values().unwrap();
+//! assert!((s[0] / s[3] / 1e8 - 1.0).abs() < 1e-4);
+//! ```
+
+use crate::internal_prelude::*;
+
+/// returns the $n\times n$ hilbert matrix, whose entries are $1 / (i + j + 1)$
+///
+/// the hilbert matrix is symmetric positive definite, and notoriously ill-conditioned, with a
+/// condition number growing like $e^{3.5 n}$
+pub fn hilbert<T: ComplexField>(n: usize) -> Mat<T> {
+	Mat::from_fn(n, n, |i, j| recip(&from_f64::<T>((i + j + 1) as f64)))
+}
+
+/// returns the $m\times n$ vandermonde matrix with the nodes $x$, whose entries are $x_i^j$,
+/// where $m$ is the number of nodes
+pub fn vandermonde<T: ComplexField>(x: ColRef<'_, T>, ncols: usize) -> Mat<T> {
+	let m = x.nrows();
+	let mut A = Mat::<T>::zeros(m, ncols);
+
+	for j in 0..ncols {
+		for i in 0..m {
+			A[(i, j)] = if j == 0 { one() } else { mul(&A[(i, j - 1)], &x[i]) };
+		}
+	}
+	A
+}
+
+#[cfg(feature = "rand")]
+pub use self::random::*;
+
+#[cfg(feature = "rand")]
+mod random {
+	use super::*;
+	use crate::assert;
+	use crate::stats::{StandardNormalScalar, UnitaryMat};
+	use rand::Rng;
+	use rand::distributions::Distribution;
+
+	/// returns a random $n\times n$ unitary matrix (orthogonal if `T` is real), distributed
+	/// according to the haar measure
+	pub fn random_unitary<T: ComplexField>(rng: &mut (impl ?Sized + Rng), n: usize) -> Mat<T> {
+		UnitaryMat {
+			dim: n,
+			standard_normal: StandardNormalScalar,
+		}
+		.sample(rng)
+	}
+
+	/// returns a random hermitian positive definite matrix $Q \Lambda Q^H$, where $\Lambda$ is the
+	/// diagonal matrix of the provided eigenvalues and $Q$ is a haar distributed unitary matrix
+	///
+	/// # panics
+	/// panics if any of the eigenvalues is not positive
+	pub fn random_hpd_with_eigenvalues<T: ComplexField>(rng: &mut (impl ?Sized + Rng), eigenvalues: ColRef<'_, T::Real>) -> Mat<T> {
+		assert!(eigenvalues.iter().all(|e| *e > zero::<T::Real>()));
+		random_hermitian_with_eigenvalues(rng, eigenvalues)
+	}
+
+	/// returns a random hermitian matrix $Q \Lambda Q^H$, where $\Lambda$ is the diagonal matrix
+	/// of the provided eigenvalues and $Q$ is a haar distributed unitary matrix
+	pub fn random_hermitian_with_eigenvalues<T: ComplexField>(rng: &mut (impl ?Sized + Rng), eigenvalues: ColRef<'_, T::Real>) -> Mat<T> {
+		let n = eigenvalues.nrows();
+		let Q = random_unitary::<T>(rng, n);
+		let QL = Mat::from_fn(n, n, |i, j| mul_real(&Q[(i, j)], &eigenvalues[j]));
+		let A = &QL * Q.adjoint();
+
+		// make the result exactly hermitian
+		Mat::from_fn(n, n, |i, j| {
+			if i == j {
+				from_real(&real(&A[(i, i)]))
+			} else if i > j {
+				copy(&A[(i, j)])
+			} else {
+				conj(&A[(j, i)])
+			}
+		})
+	}
+
+	/// returns a random $m\times n$ matrix $U \Sigma V^H$, where $\Sigma$ is the diagonal matrix of
+	/// the provided singular values and $U$, $V$ have haar distributed orthonormal columns
+	///
+	/// # panics
+	/// panics if the number of singular values is not $\min(m, n)$, or any of them is negative
+	pub fn random_with_singular_values<T: ComplexField>(
+		rng: &mut (impl ?Sized + Rng),
+		nrows: usize,
+		ncols: usize,
+		singular_values: ColRef<'_, T::Real>,
+	) -> Mat<T> {
+		let size = Ord::min(nrows, ncols);
+		assert!(all(
+			singular_values.nrows() == size,
+			singular_values.iter().all(|s| *s >= zero::<T::Real>())
+		));
+
+		let U = random_unitary::<T>(rng, nrows);
+		let V = random_unitary::<T>(rng, ncols);
+		let U = U.get(.., ..size);
+		let V = V.get(.., ..size);
+
+		let US = Mat::from_fn(nrows, size, |i, j| mul_real(&U[(i, j)], &singular_values[j]));
+		&US * V.adjoint()
+	}
+
+	/// returns a random $m\times n$ matrix whose $2$-norm condition number is `cond`
+	///
+	/// the singular values are geometrically spaced between $1$ and $1 / \text{cond}$
+	///
+	/// # panics
+	/// panics if `cond` is less than $1$
+	pub fn random_with_condition_number<T: ComplexField>(rng: &mut (impl ?Sized + Rng), nrows: usize, ncols: usize, cond: f64) -> Mat<T> {
+		assert!(cond >= 1.0);
+		let size = Ord::min(nrows, ncols);
+		let s = Col::<T::Real>::from_fn(size, |k| {
+			if k + 1 == size {
+				recip(&from_f64::<T::Real>(cond))
+			} else {
+				from_f64::<T::Real>(libm::pow(cond, -(k as f64) / ((size - 1) as f64)))
+			}
+		});
+		random_with_singular_values(rng, nrows, ncols, s.as_ref())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{Side, assert, c64, mat};
+
+	#[test]
+	fn test_hilbert_vandermonde() {
+		let H = hilbert::<f64>(3);
+		assert!(
+			H == mat![
+				[1.0, 1.0 / 2.0, 1.0 / 3.0],
+				[1.0 / 2.0, 1.0 / 3.0, 1.0 / 4.0],
+				[1.0 / 3.0, 1.0 / 4.0, 1.0 / 5.0]
+			]
+		);
+
+		let x = crate::col![2.0, -1.0, 3.0];
+		let V = vandermonde::<f64>(x.as_ref(), 4);
+		assert!(V == mat![[1.0, 2.0, 4.0, 8.0], [1.0, -1.0, 1.0, -1.0], [1.0, 3.0, 9.0, 27.0]]);
+	}
+
+	#[test]
+	fn test_random_unitary() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		let Q = random_unitary::<f64>(rng, 20);
+		assert!((Q.adjoint() * &Q - Mat::<f64>::identity(20, 20)).norm_max() < 1e-12);
+
+		let Q = random_unitary::<c64>(rng, 20);
+		assert!((Q.adjoint() * &Q - Mat::<c64>::identity(20, 20)).norm_max() < 1e-12);
+		assert!(Q[(0, 0)].im != 0.0);
+
+		// same seed, same matrix
+		let rng = &mut StdRng::seed_from_u64(1);
+		let Q0 = random_unitary::<c64>(rng, 5);
+		let rng = &mut StdRng::seed_from_u64(1);
+		let Q1 = random_unitary::<c64>(rng, 5);
+		assert!(Q0 == Q1);
+	}
+
+	#[test]
+	fn test_random_spectrum() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		let eigs = Col::<f64>::from_fn(8, |i| (i + 1) as f64);
+		let A = random_hpd_with_eigenvalues::<c64>(rng, eigs.as_ref());
+		assert!(A == A.adjoint().to_owned());
+		let computed = A.self_adjoint_eigenvalues(Side::Lower).unwrap();
+		for (e, c) in eigs.iter().zip(computed.iter()) {
+			assert!((e - c).abs() < 1e-12);
+		}
+		assert!(A.llt(Side::Lower).is_ok());
+
+		for (m, n) in [(7, 4), (4, 7), (5, 5)] {
+			let A = random_with_condition_number::<f64>(rng, m, n, 1e6);
+			let s = A.singular_values().unwrap();
+			let (hi, lo) = (s.iter().copied().fold(0.0, f64::max), s.iter().copied().fold(f64::INFINITY, f64::min));
+			assert!((hi - 1.0).abs() < 1e-12);
+			assert!((hi / lo / 1e6 - 1.0).abs() < 1e-6);
+		}
+	}
+}
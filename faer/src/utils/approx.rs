@@ -4,6 +4,51 @@ use faer_traits::Real;
 
 extern crate alloc;
 
+/// asserts that two matrices (or columns, or rows) are approximately equal, and panics with a
+/// summary of the mismatched entries otherwise
+///
+/// the comparison uses [`ApproxEq::eps`](crate::utils::approx::ApproxEq::eps) by default. the
+/// tolerances can be given as `abs = .., rel = ..`, as a maximum distance in units in the last
+/// place with `ulps = ..`, or as any comparator
+///
+/// # example
+/// ```
+/// use faer::utils::approx::ApproxEq;
+/// use faer::{assert_matrix_eq, mat};
+///
+/// let A = mat![[1.0, 2.0], [3.0, 4.0]];
+/// let B = mat![[1.0, 2.0], [3.0, 4.0 + 1e-14]];
+///
+/// assert_matrix_eq!(A, B);
+/// assert_matrix_eq!(A, B, abs = 1e-12, rel = 0.0);
+/// assert_matrix_eq!(A.col(0), B.col(0), ulps = 0);
+/// assert_matrix_eq!(A, B, ApproxEq::eps() * 2.0);
+/// ```
+#[macro_export]
+macro_rules! assert_matrix_eq {
+	($lhs: expr, $rhs: expr $(,)?) => {
+		$crate::assert_matrix_eq!($lhs, $rhs, $crate::utils::approx::ApproxEq::eps())
+	};
+	($lhs: expr, $rhs: expr, abs = $abs: expr, rel = $rel: expr $(,)?) => {
+		$crate::assert_matrix_eq!(
+			$lhs,
+			$rhs,
+			$crate::utils::approx::ApproxEq {
+				abs_tol: $abs,
+				rel_tol: $rel
+			}
+		)
+	};
+	($lhs: expr, $rhs: expr, ulps = $ulps: expr $(,)?) => {
+		$crate::assert_matrix_eq!($lhs, $rhs, $crate::utils::approx::UlpEq { max_ulps: $ulps })
+	};
+	($lhs: expr, $rhs: expr, $cmp: expr $(,)?) => {
+		if let ::core::result::Result::Err(err) = $crate::utils::approx::approx_eq_report(&$lhs, &$rhs, &$cmp) {
+			::core::panic!("assertion failed: {} ~ {}\n{}", ::core::stringify!($lhs), ::core::stringify!($rhs), err);
+		}
+	};
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct ApproxEq<T> {
 	pub abs_tol: T,
@@ -187,6 +232,22 @@ impl<
 					write!(f, "\n\n")?;
 					prefix = "__skip_prologue"
 				}
+
+				let mut worst = None;
+				for (i, j, _) in indices {
+					let distance = abs(*lhs.at(*i, *j) - *rhs.at(*i, *j));
+					match &worst {
+						Some((_, _, max)) if is_nan(*max) || distance <= *max => {},
+						_ => worst = Some((*i, *j, distance)),
+					}
+				}
+				if let Some((i, j, distance)) = worst {
+					write!(
+						f,
+						"{} mismatches, the worst being at ({i:?}, {j:?}) with distance = {distance:?}",
+						indices.len()
+					)?;
+				}
 			},
 		}
 		Ok(())
@@ -230,3 +291,256 @@ impl<
 		}
 	}
 }
+
+/// comparator accepting floating point values that are at most `max_ulps` representable values
+/// apart. complex values are compared componentwise
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UlpEq {
+	/// maximum distance in units in the last place
+	pub max_ulps: u64,
+}
+
+/// error returned by [`UlpEq`]
+#[derive(Copy, Clone, Debug)]
+pub struct UlpEqError;
+
+/// floating point types whose values can be compared in units in the last place
+pub trait Ulps {
+	/// returns the number of representable values between `lhs` and `rhs` (the maximum over the
+	/// components for complex values), or `None` if either of them is nan
+	fn ulps(lhs: &Self, rhs: &Self) -> Option<u64>;
+}
+
+macro_rules! impl_ulps {
+	($($real: ty, $int: ty;)*) => {$(
+		impl Ulps for $real {
+			#[inline]
+			fn ulps(lhs: &Self, rhs: &Self) -> Option<u64> {
+				if lhs.is_nan() || rhs.is_nan() {
+					return None;
+				}
+				// maps the floating point values to integers with the same ordering, with both
+				// zeros mapped to zero
+				let ordered = |x: $real| {
+					let bits = x.to_bits() as $int;
+					if bits < 0 { <$int>::MIN.wrapping_sub(bits) } else { bits }
+				};
+				Some(ordered(*lhs).abs_diff(ordered(*rhs)) as u64)
+			}
+		}
+
+		impl Ulps for num_complex::Complex<$real> {
+			#[inline]
+			fn ulps(lhs: &Self, rhs: &Self) -> Option<u64> {
+				Some(Ord::max(Ulps::ulps(&lhs.re, &rhs.re)?, Ulps::ulps(&lhs.im, &rhs.im)?))
+			}
+		}
+	)*};
+}
+
+impl_ulps! {
+	f32, i32;
+	f64, i64;
+}
+
+impl<T: Ulps> equator::CmpError<UlpEq, T, T> for UlpEq {
+	type Error = UlpEqError;
+}
+
+impl<T: Ulps> equator::CmpDisplay<UlpEq, T, T> for UlpEqError {
+	fn fmt(
+		&self,
+		cmp: &UlpEq,
+		lhs: &T,
+		mut lhs_source: &str,
+		lhs_debug: &dyn core::fmt::Debug,
+		rhs: &T,
+		rhs_source: &str,
+		rhs_debug: &dyn core::fmt::Debug,
+		f: &mut core::fmt::Formatter,
+	) -> core::fmt::Result {
+		let UlpEq { max_ulps } = cmp;
+
+		if let Some(source) = lhs_source.strip_prefix("__skip_prologue") {
+			lhs_source = source;
+		} else {
+			writeln!(f, "Assertion failed: {lhs_source} ~ {rhs_source}\nwith maximum ulp distance = {max_ulps}")?;
+		}
+
+		writeln!(f, "- {lhs_source} = {lhs_debug:?}")?;
+		writeln!(f, "- {rhs_source} = {rhs_debug:?}")?;
+		match Ulps::ulps(lhs, rhs) {
+			Some(distance) => write!(f, "- ulp distance = {distance}"),
+			None => write!(f, "- ulp distance = nan"),
+		}
+	}
+}
+
+impl<T: Ulps> equator::Cmp<T, T> for UlpEq {
+	#[inline]
+	fn test(&self, lhs: &T, rhs: &T) -> Result<(), Self::Error> {
+		match Ulps::ulps(lhs, rhs) {
+			Some(distance) if distance <= self.max_ulps => Ok(()),
+			_ => Err(UlpEqError),
+		}
+	}
+}
+
+/// summary of the entries of two matrices that failed an approximate comparison
+#[derive(Clone, Debug)]
+pub struct Mismatch<T> {
+	/// number of mismatched entries
+	pub count: usize,
+	/// row and column of the mismatched entry with the largest distance
+	pub worst_idx: (usize, usize),
+	/// absolute distance between the entries at `worst_idx`
+	pub worst_error: T,
+}
+
+/// error returned by [`approx_eq_report`]
+#[derive(Clone, Debug)]
+pub enum ApproxEqReportError<T> {
+	/// the matrices have different dimensions
+	DimMismatch {
+		/// dimensions of the left hand side
+		lhs: (usize, usize),
+		/// dimensions of the right hand side
+		rhs: (usize, usize),
+	},
+	/// some entries are not approximately equal
+	Elements(Mismatch<T>),
+}
+
+impl<T: core::fmt::Debug> core::fmt::Display for ApproxEqReportError<T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			Self::DimMismatch { lhs, rhs } => write!(f, "dimension mismatch: {lhs:?} vs {rhs:?}"),
+			Self::Elements(Mismatch {
+				count,
+				worst_idx,
+				worst_error,
+			}) => write!(
+				f,
+				"{count} mismatched entries, the worst being at {worst_idx:?} with distance {worst_error:?}"
+			),
+		}
+	}
+}
+
+/// compares two matrices entrywise with the given comparator (e.g., [`ApproxEq`] or [`UlpEq`]),
+/// and returns a summary of the mismatched entries on failure
+///
+/// columns and rows are compared as matrices with one column or row
+pub fn approx_eq_report<T: ComplexField, Cmp: equator::Cmp<T, T>>(
+	lhs: &impl AsMatRef<T = T>,
+	rhs: &impl AsMatRef<T = T>,
+	cmp: &Cmp,
+) -> Result<(), ApproxEqReportError<T::Real>> {
+	let lhs = lhs.as_mat_ref().as_dyn().as_dyn_stride();
+	let rhs = rhs.as_mat_ref().as_dyn().as_dyn_stride();
+
+	if lhs.shape() != rhs.shape() {
+		return Err(ApproxEqReportError::DimMismatch {
+			lhs: lhs.shape(),
+			rhs: rhs.shape(),
+		});
+	}
+
+	let mut worst: Option<Mismatch<T::Real>> = None;
+	for j in 0..lhs.ncols() {
+		for i in 0..lhs.nrows() {
+			if cmp.test(&lhs[(i, j)], &rhs[(i, j)]).is_ok() {
+				continue;
+			}
+			let error = abs(&sub(&lhs[(i, j)], &rhs[(i, j)]));
+			worst = Some(match worst {
+				None => Mismatch {
+					count: 1,
+					worst_idx: (i, j),
+					worst_error: error,
+				},
+				Some(worst) => {
+					// nan distances are considered the worst
+					if is_nan(&error) || (!is_nan(&worst.worst_error) && error > worst.worst_error) {
+						Mismatch {
+							count: worst.count + 1,
+							worst_idx: (i, j),
+							worst_error: error,
+						}
+					} else {
+						Mismatch {
+							count: worst.count + 1,
+							..worst
+						}
+					}
+				},
+			});
+		}
+	}
+
+	match worst {
+		None => Ok(()),
+		Some(worst) => Err(ApproxEqReportError::Elements(worst)),
+	}
+}
+
+/// returns `true` if the two matrices have the same dimensions, and their entries pass the
+/// comparator (e.g., [`ApproxEq`] or [`UlpEq`])
+pub fn approx_eq<T: ComplexField, Cmp: equator::Cmp<T, T>>(lhs: &impl AsMatRef<T = T>, rhs: &impl AsMatRef<T = T>, cmp: &Cmp) -> bool {
+	approx_eq_report(lhs, rhs, cmp).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{assert, c64, col, mat};
+
+	#[test]
+	fn test_ulps() {
+		assert!(Ulps::ulps(&1.0f64, &1.0f64) == Some(0));
+		assert!(Ulps::ulps(&1.0f64, &(1.0f64 + f64::EPSILON)) == Some(1));
+		assert!(Ulps::ulps(&0.0f64, &-0.0f64) == Some(0));
+		assert!(Ulps::ulps(&f32::from_bits(1), &-f32::from_bits(1)) == Some(2));
+		assert!(Ulps::ulps(&f64::NAN, &1.0) == None);
+		assert!(Ulps::ulps(&c64::new(1.0, 2.0), &c64::new(1.0, 2.0 + 3.0 * 2.0 * f64::EPSILON)) == Some(3));
+	}
+
+	#[test]
+	fn test_report() {
+		let A = mat![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+		let mut B = A.clone();
+		B[(0, 1)] += 1e-3;
+		B[(1, 2)] += 1e-1;
+
+		assert!(approx_eq(&A, &A, &ApproxEq::eps()));
+		assert!(approx_eq(&A, &A, &UlpEq { max_ulps: 0 }));
+		assert!(!approx_eq(&A, &B, &ApproxEq::eps()));
+		assert!(!approx_eq(&A, &A.transpose(), &ApproxEq::eps()));
+
+		let err = approx_eq_report(&A, &B, &ApproxEq::<f64>::eps()).unwrap_err();
+		let ApproxEqReportError::Elements(mismatch) = err else { panic!() };
+		assert!(mismatch.count == 2);
+		assert!(mismatch.worst_idx == (1, 2));
+		let worst_error: f64 = mismatch.worst_error;
+		assert!((worst_error - 1e-1).abs() < 1e-12);
+
+		assert!(approx_eq(&A.col(0), &col![1.0, 4.0 + 1e-15], &ApproxEq::eps()));
+	}
+
+	#[test]
+	fn test_assert_matrix_eq() {
+		let A = mat![[1.0, 2.0], [3.0, 4.0]];
+		let B = mat![[1.0, 2.0], [3.0, 4.0 + 1e-14]];
+		crate::assert_matrix_eq!(A, B);
+		crate::assert_matrix_eq!(A.row(0), B.row(0), ulps = 0);
+		crate::assert_matrix_eq!(&A, &B, abs = 1e-12, rel = 0.0);
+	}
+
+	#[test]
+	#[should_panic(expected = "1 mismatched entries, the worst being at (1, 1)")]
+	fn test_assert_matrix_eq_fail() {
+		let A = mat![[1.0, 2.0], [3.0, 4.0]];
+		let B = mat![[1.0, 2.0], [3.0, 4.0 + 1e-14]];
+		crate::assert_matrix_eq!(A, B, ulps = 4);
+	}
+}
@@ -0,0 +1,211 @@
+//! flop and memory traffic accounting, enabled by the `counters` feature
+//!
+//! the matrix multiplication, the $LU$ factorizations and the
+//! triangular and $LU$/cholesky solvers add an estimate of the number of floating point operations
+//! they perform and of the number of bytes they move to a thread-local [`Report`], which can be
+//! queried with [`report`] and cleared with [`reset`] or [`take`]
+//!
+//! only the outermost call made by the current thread is accounted for, so that the operations
+//! performed by a factorization aren't also counted as matrix multiplications. the flop counts use
+//! the leading order term of the usual operation counts, where a complex multiply-add counts as
+//! eight real flops, and the memory traffic assumes that each operand is read or written once
+//!
+//! # example
+//! ```
+//! use faer::utils::counters;
+//! use faer::{Mat, Par};
+//!
+//! let a = Mat::<f64>::identity(64, 32);
+//! let b = Mat::<f64>::identity(32, 16);
+//!
+//! counters::reset();
+//! let _c = &a * &b;
+//! let report = counters::take();
+//!
+//! assert!(report.matmul.calls == 1);
+//! assert!(report.matmul.flops == 2 * 64 * 32 * 16);
+//! assert!(report.matmul.bytes == 8 * (64 * 32 + 32 * 16 + 64 * 16));
+//! ```
+
+use core::cell::Cell;
+
+/// accumulated counts of a category of calls
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+	/// number of calls
+	pub calls: u64,
+	/// estimated number of real floating point operations
+	pub flops: u64,
+	/// estimated number of bytes read and written
+	pub bytes: u64,
+}
+
+/// per-category counts accumulated by the current thread
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+	/// matrix multiplications
+	pub matmul: Counts,
+	/// $LU$ factorizations, with partial or full pivoting
+	pub lu: Counts,
+	/// triangular, $LU$ and cholesky solves
+	pub solve: Counts,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Kind {
+	Matmul,
+	Lu,
+	Solve,
+}
+
+std::thread_local! {
+	static REPORT: Cell<Report> = const { Cell::new(Report {
+		matmul: Counts { calls: 0, flops: 0, bytes: 0 },
+		lu: Counts { calls: 0, flops: 0, bytes: 0 },
+		solve: Counts { calls: 0, flops: 0, bytes: 0 },
+	}) };
+	static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// returns the counts accumulated by the current thread
+pub fn report() -> Report {
+	REPORT.with(Cell::get)
+}
+
+/// clears the counts accumulated by the current thread
+pub fn reset() {
+	REPORT.with(|report| report.set(Report::default()));
+}
+
+/// returns the counts accumulated by the current thread, and clears them
+pub fn take() -> Report {
+	REPORT.with(Cell::take)
+}
+
+/// marks the current thread as being inside an accounted call until it's dropped
+pub(crate) struct Scope(());
+
+impl Scope {
+	#[inline]
+	pub(crate) fn new(kind: Kind, flops: u64, bytes: u64) -> Self {
+		if DEPTH.with(|depth| depth.replace(depth.get() + 1)) == 0 {
+			REPORT.with(|report| {
+				let mut r = report.get();
+				let counts = match kind {
+					Kind::Matmul => &mut r.matmul,
+					Kind::Lu => &mut r.lu,
+					Kind::Solve => &mut r.solve,
+				};
+				counts.calls += 1;
+				counts.flops = counts.flops.saturating_add(flops);
+				counts.bytes = counts.bytes.saturating_add(bytes);
+				report.set(r);
+			});
+		}
+		Self(())
+	}
+}
+
+impl Drop for Scope {
+	#[inline]
+	fn drop(&mut self) {
+		DEPTH.with(|depth| depth.set(depth.get() - 1));
+	}
+}
+
+/// real flops per multiply-add of `T`
+#[inline]
+pub(crate) fn fma_flops<T: faer_traits::ComplexField>() -> u64 {
+	if T::IS_REAL { 2 } else { 8 }
+}
+
+/// number of multiply-adds of the $LU$ factorization of an `m×n` matrix
+#[inline]
+pub(crate) fn lu_fma(m: usize, n: usize) -> u64 {
+	let (m, n) = (m as u64, n as u64);
+	let k = Ord::min(m, n);
+	m * n * k - (m + n) * k * k / 2 + k * k * k / 3
+}
+
+/// size of `n` elements of `T`, in bytes
+#[inline]
+pub(crate) fn bytes<T>(n: usize) -> u64 {
+	(n as u64).saturating_mul(core::mem::size_of::<T>() as u64)
+}
+
+/// accounts for the enclosing scope as a call of the given kind, with the given number of
+/// multiply-adds and elements moved
+macro_rules! count {
+	($kind: ident, $T: ty, fma = $fma: expr, elems = $elems: expr $(,)?) => {
+		let __counter__ = $crate::utils::counters::Scope::new(
+			$crate::utils::counters::Kind::$kind,
+			($fma as u64).saturating_mul($crate::utils::counters::fma_flops::<$T>()),
+			$crate::utils::counters::bytes::<$T>($elems),
+		);
+	};
+}
+
+pub(crate) use count;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::internal_prelude::*;
+	use crate::linalg::lu::partial_pivoting::factor::{lu_in_place, lu_in_place_scratch};
+	use crate::stats::prelude::*;
+	use crate::{assert, c64};
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_counters() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let (m, n, k) = (30, 20, 10);
+
+		let A = Mat::<c64>::random_normal(rng, m, k);
+		let B = Mat::<c64>::random_normal(rng, k, n);
+		let mut C = Mat::<c64>::zeros(m, n);
+
+		reset();
+		crate::linalg::matmul::matmul(C.as_mut(), Accum::Add, A.as_ref(), B.as_ref(), c64::ONE, Par::rayon(4));
+		let report = take();
+		assert!(report.matmul.calls == 1);
+		assert!(report.matmul.flops == 8 * (m * n * k) as u64);
+		assert!(report.matmul.bytes == 16 * (m * k + k * n + 2 * m * n) as u64);
+		assert!(take() == Report::default());
+
+		// the multiplications performed by the factorization are not counted separately
+		let n = 100;
+		let mut LU = Mat::<f64>::random_normal(rng, n, n);
+		let perm = &mut *alloc::vec![0usize; n];
+		let perm_inv = &mut *alloc::vec![0usize; n];
+		let params = default();
+		let (_, p) = lu_in_place(
+			LU.as_mut(),
+			perm,
+			perm_inv,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, f64>(n, n, Par::Seq, params))),
+			params,
+		);
+		let mut x = Mat::<f64>::random_normal(rng, n, 3);
+		crate::linalg::lu::partial_pivoting::solve::solve_in_place_with_conj(
+			LU.as_ref(),
+			LU.as_ref(),
+			p,
+			Conj::No,
+			x.as_mut(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(crate::linalg::lu::partial_pivoting::solve::solve_in_place_scratch::<
+				usize,
+				f64,
+			>(n, 3, Par::Seq))),
+		);
+
+		let report = take();
+		assert!(report.matmul == Counts::default());
+		assert!(report.lu.calls == 1);
+		assert!(report.lu.flops == 2 * (n * n * n / 3) as u64);
+		assert!(report.solve.calls == 1);
+		assert!(report.solve.flops == 2 * (n * n * 3) as u64);
+	}
+}
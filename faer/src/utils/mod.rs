@@ -82,3 +82,22 @@ pub mod simd;
 
 /// approximate comparators for testing purposes
 pub mod approx;
+
+/// scalar type with stochastic rounding, for low precision experiments
+pub mod stochastic;
+
+/// feature-gated instrumentation with `tracing` spans
+pub(crate) mod trace;
+
+/// flop and memory traffic accounting
+#[cfg(feature = "counters")]
+pub mod counters;
+
+#[cfg(not(feature = "counters"))]
+pub(crate) mod counters {
+	macro_rules! count {
+		($kind: ident, $T: ty, fma = $fma: expr, elems = $elems: expr $(,)?) => {};
+	}
+
+	pub(crate) use count;
+}
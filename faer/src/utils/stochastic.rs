@@ -0,0 +1,629 @@
+//! the scalar type [`StochasticFloat`] emulates a floating point format with a reduced number of
+//! significand bits, where every operation is computed in `f64` then stochastically rounded to
+//! the target precision. since it implements [`RealField`], it can be used with the generic
+//! decompositions, e.g., to study the behavior of $LU$, $QR$ or cholesky factorizations under
+//! stochastic rounding
+//!
+//! a result that lies between two consecutive representable values is rounded to either of them,
+//! with a probability proportional to its proximity, so that the rounding is unbiased in
+//! expectation. the random bits are drawn from a generator owned by the current thread, which is
+//! reseeded by [`seed`]. the other threads derive their generator from the same seed and an index
+//! assigned to them when they first draw from it
+//!
+//! runs are reproducible when the computation is sequential, i.e. with [`Par::Seq`](crate::Par::Seq),
+//! on the thread that called [`seed`]. with [`Par::Rayon`](crate::Par::Rayon), the work assigned to
+//! each thread varies between runs, and so do the results. without the `std` feature, all the
+//! threads share a single generator
+//!
+//! only the precision of the format is emulated, the exponent range is the one of `f64`
+//!
+//! # example
+//! ```
+//! use faer::Mat;
+//! use faer::utils::stochastic::{self, Sr16};
+//!
+//! stochastic::seed(0);
+//!
+//! let A = Mat::from_fn(50, 50, |i, j| {
+//! 	Sr16::new(if i == j {
+//! 		10.0
+//! 	} else {
+//! 		1.0 / (i + j + 1) as f64
+//! 	})
+//! });
+//! let lu = A.partial_piv_lu();
+//! ```
+
+use crate::traits::{ComplexField, RealField, SimdCapabilities};
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use core::sync::atomic::{AtomicU64, Ordering};
+use pulp::Simd;
+
+const GAMMA: u64 = 0x9E3779B97F4A7C15;
+
+static SEED: AtomicU64 = AtomicU64::new(0);
+/// incremented by each call to [`seed`], so that the other threads reseed their generator
+#[cfg(feature = "std")]
+static EPOCH: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "std")]
+static THREAD_COUNT: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(feature = "std")]
+std::thread_local! {
+	/// epoch of the seed, index of the thread and state of the generator
+	static STATE: core::cell::Cell<Option<(u64, u64, u64)>> = const { core::cell::Cell::new(None) };
+}
+
+/// reseeds the generator of the current thread used for stochastic rounding, and the ones the
+/// other threads derive from it
+pub fn seed(seed: u64) {
+	SEED.store(seed, Ordering::Relaxed);
+	#[cfg(feature = "std")]
+	{
+		let epoch = EPOCH.fetch_add(1, Ordering::Relaxed) + 1;
+		STATE.with(|state| {
+			let index = state
+				.get()
+				.map(|(_, index, _)| index)
+				.unwrap_or_else(|| THREAD_COUNT.fetch_add(1, Ordering::Relaxed));
+			state.set(Some((epoch, index, seed)));
+		});
+	}
+}
+
+#[inline]
+fn mix(mut z: u64) -> u64 {
+	z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+	z ^ (z >> 31)
+}
+
+/// returns the next output of the splitmix64 generator
+#[inline]
+fn next_u64() -> u64 {
+	#[cfg(feature = "std")]
+	let z = STATE.with(|state| {
+		let epoch = EPOCH.load(Ordering::Relaxed);
+		let (z, index) = match state.get() {
+			Some((state_epoch, index, z)) if state_epoch == epoch => (z, index),
+			// the thread hasn't drawn since the last reseed
+			Some((_, index, _)) => (SEED.load(Ordering::Relaxed) ^ mix(index), index),
+			None => {
+				let index = THREAD_COUNT.fetch_add(1, Ordering::Relaxed);
+				(SEED.load(Ordering::Relaxed) ^ mix(index), index)
+			},
+		};
+		let z = z.wrapping_add(GAMMA);
+		state.set(Some((epoch, index, z)));
+		z
+	});
+	#[cfg(not(feature = "std"))]
+	let z = SEED.fetch_add(GAMMA, Ordering::Relaxed).wrapping_add(GAMMA);
+
+	mix(z)
+}
+
+/// floating point value with `BITS` significand bits (including the implicit leading bit), whose
+/// operations are stochastically rounded
+///
+/// `BITS` must be between `1` and `53`
+#[derive(Copy, Clone, PartialEq, PartialOrd, Default)]
+#[repr(transparent)]
+pub struct StochasticFloat<const BITS: u32>(f64);
+
+/// stochastically rounded value with the precision of `bf16`
+pub type SrBf16 = StochasticFloat<8>;
+/// stochastically rounded value with the precision of `f16`
+pub type Sr16 = StochasticFloat<11>;
+/// stochastically rounded value with the precision of `f32`
+pub type Sr32 = StochasticFloat<24>;
+
+impl<const BITS: u32> StochasticFloat<BITS> {
+	const CHECK: () = core::assert!(BITS >= 1 && BITS <= 53, "the number of significand bits must be between 1 and 53");
+	/// mask of the significand bits that are dropped when rounding
+	const MASK: u64 = (1u64 << (53 - BITS)) - 1;
+
+	/// returns `value` stochastically rounded to the target precision
+	#[inline]
+	pub fn new(value: f64) -> Self {
+		#[allow(clippy::let_unit_value)]
+		let () = Self::CHECK;
+
+		if BITS == 53 || value == 0.0 || !value.is_finite() {
+			return Self(value);
+		}
+
+		// adding a random value to the dropped bits of the magnitude then truncating them rounds up
+		// with a probability equal to the fraction that's dropped. a carry into the exponent moves
+		// the value to the next binade, which is the correct rounding
+		let bits = value.to_bits();
+		Self(f64::from_bits((bits + (next_u64() & Self::MASK)) & !Self::MASK))
+	}
+
+	/// returns `value` rounded to the nearest value representable in the target precision, with
+	/// ties rounded to even
+	#[inline]
+	pub fn new_nearest(value: f64) -> Self {
+		#[allow(clippy::let_unit_value)]
+		let () = Self::CHECK;
+
+		if BITS == 53 || value == 0.0 || !value.is_finite() {
+			return Self(value);
+		}
+
+		let bits = value.to_bits();
+		let half = (Self::MASK >> 1) + 1;
+		let odd = (bits >> (53 - BITS)) & 1;
+		Self(f64::from_bits((bits + half - 1 + odd) & !Self::MASK))
+	}
+
+	/// returns the stored value
+	#[inline]
+	pub fn value(self) -> f64 {
+		self.0
+	}
+}
+
+impl<const BITS: u32> fmt::Debug for StochasticFloat<BITS> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(&self.0, f)
+	}
+}
+
+impl<const BITS: u32> fmt::Display for StochasticFloat<BITS> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.0, f)
+	}
+}
+
+macro_rules! impl_binop {
+	($($trait: ident, $method: ident, $op: tt);*) => {$(
+		impl<const BITS: u32> $trait for StochasticFloat<BITS> {
+			type Output = Self;
+
+			#[inline]
+			fn $method(self, rhs: Self) -> Self {
+				Self::new(self.0 $op rhs.0)
+			}
+		}
+
+		impl<const BITS: u32> $trait for &StochasticFloat<BITS> {
+			type Output = StochasticFloat<BITS>;
+
+			#[inline]
+			fn $method(self, rhs: Self) -> StochasticFloat<BITS> {
+				StochasticFloat::new(self.0 $op rhs.0)
+			}
+		}
+	)*};
+}
+
+impl_binop!(Add, add, +; Sub, sub, -; Mul, mul, *; Div, div, /; Rem, rem, %);
+
+impl<const BITS: u32> Neg for StochasticFloat<BITS> {
+	type Output = Self;
+
+	#[inline]
+	fn neg(self) -> Self {
+		Self(-self.0)
+	}
+}
+
+impl<const BITS: u32> Neg for &StochasticFloat<BITS> {
+	type Output = StochasticFloat<BITS>;
+
+	#[inline]
+	fn neg(self) -> StochasticFloat<BITS> {
+		StochasticFloat(-self.0)
+	}
+}
+
+impl<const BITS: u32> num_traits::Zero for StochasticFloat<BITS> {
+	#[inline]
+	fn zero() -> Self {
+		Self(0.0)
+	}
+
+	#[inline]
+	fn is_zero(&self) -> bool {
+		self.0 == 0.0
+	}
+}
+
+impl<const BITS: u32> num_traits::One for StochasticFloat<BITS> {
+	#[inline]
+	fn one() -> Self {
+		Self(1.0)
+	}
+}
+
+impl<const BITS: u32> num_traits::Num for StochasticFloat<BITS> {
+	type FromStrRadixErr = num_traits::ParseFloatError;
+
+	fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+		f64::from_str_radix(str, radix).map(Self::new)
+	}
+}
+
+impl<const BITS: u32> RealField for StochasticFloat<BITS> {
+	#[inline]
+	fn epsilon_impl() -> Self {
+		Self(libm::exp2(1.0 - BITS as f64))
+	}
+
+	#[inline]
+	fn nbits_impl() -> usize {
+		BITS as usize
+	}
+
+	#[inline]
+	fn min_positive_impl() -> Self {
+		Self(f64::MIN_POSITIVE)
+	}
+
+	#[inline]
+	fn max_positive_impl() -> Self {
+		Self(f64::MIN_POSITIVE.recip())
+	}
+
+	#[inline]
+	fn sqrt_min_positive_impl() -> Self {
+		Self(libm::sqrt(f64::MIN_POSITIVE))
+	}
+
+	#[inline]
+	fn sqrt_max_positive_impl() -> Self {
+		Self(libm::sqrt(f64::MIN_POSITIVE.recip()))
+	}
+}
+
+impl<const BITS: u32> ComplexField for StochasticFloat<BITS> {
+	type Arch = pulp::Scalar;
+	type Index = usize;
+	type Real = Self;
+	type SimdCtx<S: Simd> = S;
+	type SimdIndex<S: Simd> = ();
+	type SimdMask<S: Simd> = ();
+	type SimdMemMask<S: Simd> = ();
+	type SimdVec<S: Simd> = ();
+	type Unit = Self;
+
+	const IS_REAL: bool = true;
+	const SIMD_CAPABILITIES: SimdCapabilities = SimdCapabilities::Copy;
+
+	#[inline]
+	fn zero_impl() -> Self {
+		Self(0.0)
+	}
+
+	#[inline]
+	fn one_impl() -> Self {
+		Self(1.0)
+	}
+
+	#[inline]
+	fn nan_impl() -> Self {
+		Self(f64::NAN)
+	}
+
+	#[inline]
+	fn infinity_impl() -> Self {
+		Self(f64::INFINITY)
+	}
+
+	#[inline]
+	fn from_real_impl(real: &Self::Real) -> Self {
+		*real
+	}
+
+	#[inline]
+	fn from_f64_impl(real: f64) -> Self {
+		Self::new(real)
+	}
+
+	#[inline]
+	fn real_part_impl(value: &Self) -> Self::Real {
+		*value
+	}
+
+	#[inline]
+	fn imag_part_impl(_: &Self) -> Self::Real {
+		Self(0.0)
+	}
+
+	#[inline]
+	fn copy_impl(value: &Self) -> Self {
+		*value
+	}
+
+	#[inline]
+	fn conj_impl(value: &Self) -> Self {
+		*value
+	}
+
+	#[inline]
+	fn recip_impl(value: &Self) -> Self {
+		Self::new(1.0 / value.0)
+	}
+
+	#[inline]
+	fn sqrt_impl(value: &Self) -> Self {
+		Self::new(libm::sqrt(value.0))
+	}
+
+	#[inline]
+	fn abs_impl(value: &Self) -> Self::Real {
+		Self(value.0.abs())
+	}
+
+	#[inline]
+	fn abs1_impl(value: &Self) -> Self::Real {
+		Self(value.0.abs())
+	}
+
+	#[inline]
+	fn abs2_impl(value: &Self) -> Self::Real {
+		Self::new(value.0 * value.0)
+	}
+
+	#[inline]
+	fn mul_real_impl(lhs: &Self, rhs: &Self::Real) -> Self {
+		Self::new(lhs.0 * rhs.0)
+	}
+
+	#[inline]
+	fn mul_pow2_impl(lhs: &Self, rhs: &Self::Real) -> Self {
+		Self(lhs.0 * rhs.0)
+	}
+
+	#[inline]
+	fn is_finite_impl(value: &Self) -> bool {
+		value.0.is_finite()
+	}
+
+	fn simd_ctx<S: Simd>(simd: S) -> Self::SimdCtx<S> {
+		simd
+	}
+
+	fn ctx_from_simd<S: Simd>(simd: &Self::SimdCtx<S>) -> S {
+		*simd
+	}
+
+	fn simd_mem_mask_between<S: Simd>(_: &Self::SimdCtx<S>, _: Self::Index, _: Self::Index) -> Self::SimdMemMask<S> {}
+
+	unsafe fn simd_mask_load_raw<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdMemMask<S>, _: *const Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	unsafe fn simd_mask_store_raw<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdMemMask<S>, _: *mut Self::SimdVec<S>, _: Self::SimdVec<S>) {}
+
+	fn simd_splat<S: Simd>(_: &Self::SimdCtx<S>, _: &Self) -> Self::SimdVec<S> {}
+
+	fn simd_splat_real<S: Simd>(_: &Self::SimdCtx<S>, _: &Self::Real) -> Self::SimdVec<S> {}
+
+	fn simd_add<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_sub<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_neg<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_conj<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_abs1<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_abs_max<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_mul_real<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_mul_pow2<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_mul<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_conj_mul<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_mul_add<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_conj_mul_add<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_abs2<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_abs2_add<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_reduce_sum<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>) -> Self {
+		Self(0.0)
+	}
+
+	fn simd_reduce_max<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>) -> Self {
+		Self(0.0)
+	}
+
+	fn simd_equal<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdMask<S> {}
+
+	fn simd_less_than<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdMask<S> {}
+
+	fn simd_less_than_or_equal<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdMask<S> {}
+
+	fn simd_greater_than<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdMask<S> {}
+
+	fn simd_greater_than_or_equal<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdMask<S> {}
+
+	fn simd_select<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdMask<S>, _: Self::SimdVec<S>, _: Self::SimdVec<S>) -> Self::SimdVec<S> {}
+
+	fn simd_index_select<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdMask<S>, _: Self::SimdIndex<S>, _: Self::SimdIndex<S>) -> Self::SimdIndex<S> {}
+
+	fn simd_index_splat<S: Simd>(_: &Self::SimdCtx<S>, _: Self::Index) -> Self::SimdIndex<S> {}
+
+	fn simd_index_add<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdIndex<S>, _: Self::SimdIndex<S>) -> Self::SimdIndex<S> {}
+
+	fn simd_and_mask<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdMask<S>, _: Self::SimdMask<S>) -> Self::SimdMask<S> {}
+
+	fn simd_or_mask<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdMask<S>, _: Self::SimdMask<S>) -> Self::SimdMask<S> {}
+
+	fn simd_not_mask<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdMask<S>) -> Self::SimdMask<S> {}
+
+	fn simd_first_true_mask<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdMask<S>) -> usize {
+		0
+	}
+
+	fn simd_mask_between<S: Simd>(_: &Self::SimdCtx<S>, _: Self::Index, _: Self::Index) -> Self::SimdMask<S> {}
+
+	fn simd_index_less_than<S: Simd>(_: &Self::SimdCtx<S>, _: Self::SimdIndex<S>, _: Self::SimdIndex<S>) -> Self::SimdMask<S> {}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::solvers::Solve;
+	use crate::stats::prelude::*;
+	use crate::{Mat, assert};
+
+	#[test]
+	fn test_rounding() {
+		seed(0);
+
+		// `1 + 2^-12` lies a quarter of the way between two consecutive `f16` values
+		let x = 1.0 + libm::exp2(-12.0);
+		let ulp = libm::exp2(-10.0);
+
+		let n = 100_000;
+		let mut up = 0;
+		for _ in 0..n {
+			let y = Sr16::new(x).value();
+			assert!(any(y == 1.0, y == 1.0 + ulp));
+			up += (y != 1.0) as usize;
+		}
+		let p = up as f64 / n as f64;
+		assert!((p - 0.25).abs() < 0.01);
+
+		// representable values are left untouched
+		for _ in 0..100 {
+			assert!(Sr16::new(1.5).value() == 1.5);
+			assert!(Sr16::new(-2048.0).value() == -2048.0);
+		}
+
+		// rounding up to the next binade
+		let x = 2.0 - libm::exp2(-20.0);
+		for _ in 0..100 {
+			let y = Sr16::new(x).value();
+			assert!(any(y == 2.0, y == 2.0 - ulp));
+		}
+
+		assert!(Sr16::new_nearest(1.0 + libm::exp2(-11.0)).value() == 1.0);
+		assert!(Sr16::new_nearest(1.0 + 3.0 * libm::exp2(-11.0)).value() == 1.0 + 2.0 * ulp);
+		assert!(Sr16::new_nearest(1.0 + 1.5 * libm::exp2(-11.0)).value() == 1.0 + ulp);
+	}
+
+	#[test]
+	fn test_unbiased_sum() {
+		seed(0);
+
+		// with round to nearest, the small increments are lost entirely once the sum is large
+		// enough. stochastic rounding keeps them on average
+		let n = 10_000;
+		let inc = 0.1;
+		let mut sum = Sr16::new(0.0);
+		let mut nearest = 0.0f64;
+		for _ in 0..n {
+			sum = sum + Sr16::new(inc);
+			nearest = Sr16::new_nearest(nearest + Sr16::new_nearest(inc).value()).value();
+		}
+		let exact = n as f64 * inc;
+		assert!((sum.value() - exact).abs() < 0.05 * exact);
+		assert!((nearest - exact).abs() > 0.5 * exact);
+	}
+
+	#[test]
+	fn test_reproducible() {
+		use crate::linalg::lu::partial_pivoting::factor::{lu_in_place, lu_in_place_scratch};
+		use dyn_stack::{MemBuffer, MemStack};
+
+		let n = 30;
+		let A = Mat::from_fn(n, n, |i, j| 1.0 / (i + j + 1) as f64 + if i == j { 1.0 } else { 0.0 });
+
+		let run = || {
+			let mut LU = Mat::from_fn(n, n, |i, j| Sr16::new(A[(i, j)]));
+			let perm = &mut *alloc::vec![0usize; n];
+			let perm_inv = &mut *alloc::vec![0usize; n];
+			lu_in_place(
+				LU.as_mut(),
+				perm,
+				perm_inv,
+				crate::Par::Seq,
+				MemStack::new(&mut MemBuffer::new(lu_in_place_scratch::<usize, Sr16>(
+					n,
+					n,
+					crate::Par::Seq,
+					Default::default(),
+				))),
+				Default::default(),
+			);
+			Mat::from_fn(n, n, |i, j| LU[(i, j)].value())
+		};
+
+		// sequential runs from the same seed are identical, even while the other threads draw from
+		// their own generator
+		seed(42);
+		let first = run();
+		std::thread::spawn(|| {
+			for _ in 0..1000 {
+				Sr16::new(0.1);
+			}
+		})
+		.join()
+		.unwrap();
+		seed(42);
+		assert!(run() == first);
+		seed(43);
+		assert!(run() != first);
+	}
+
+	#[test]
+	fn test_decompositions() {
+		seed(0);
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 40;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let B = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let mut spd = &A * A.transpose();
+		for i in 0..n {
+			spd[(i, i)] += n as f64;
+		}
+
+		let to_sr = |mat: &Mat<f64>| Mat::from_fn(mat.nrows(), mat.ncols(), |i, j| Sr32::new(mat[(i, j)]));
+		let to_f64 = |mat: &Mat<Sr32>| Mat::from_fn(mat.nrows(), mat.ncols(), |i, j| mat[(i, j)].value());
+
+		let rel_residual = |A: &Mat<f64>, X: &Mat<Sr32>| {
+			let X = to_f64(X);
+			(A * &X - &B).norm_l2() / (A.norm_l2() * X.norm_l2())
+		};
+
+		let X = to_sr(&A).partial_piv_lu().solve(to_sr(&B));
+		assert!(rel_residual(&A, &X) < 1e-5);
+
+		let X = to_sr(&A).qr().solve(to_sr(&B));
+		assert!(rel_residual(&A, &X) < 1e-5);
+
+		let X = to_sr(&spd).llt(crate::Side::Lower).unwrap().solve(to_sr(&B));
+		assert!(rel_residual(&spd, &X) < 1e-5);
+
+		// the same solve at a much lower precision is less accurate, but still meaningful
+		let X = Mat::from_fn(n, n, |i, j| SrBf16::new(spd[(i, j)]))
+			.llt(crate::Side::Lower)
+			.unwrap()
+			.solve(Mat::from_fn(n, 2, |i, j| SrBf16::new(B[(i, j)])));
+		let X = Mat::from_fn(n, 2, |i, j| X[(i, j)].value());
+		let residual = (&spd * &X - &B).norm_l2() / (spd.norm_l2() * X.norm_l2());
+		assert!(all(residual > 1e-5, residual < 1e-1));
+	}
+}
@@ -0,0 +1,152 @@
+//! instrumentation of the dense decompositions, enabled by the `tracing` feature
+//!
+//! the drivers open a [`tracing`] span with the target `"faer"` around each of their stages. every
+//! span carries the dimensions of the matrix it operates on, and records the time it took in
+//! nanoseconds in its `elapsed_ns` field when it closes (only when the `std` feature is enabled)
+//!
+//! - `debug` level: `lu_partial_piv`, `lu_full_piv`, `lu_panel`, `lu_block`, `qr`, `qr_panel`,
+//! `cholesky`, `svd`, `svd_bidiag`, `svd_bidiag_svd`, `self_adjoint_evd`, `evd`
+//! - `trace` level: `matmul`, `pivot_search`
+//!
+//! when the feature is disabled, the spans compile to nothing
+
+#[cfg(feature = "tracing")]
+pub(crate) struct Timed {
+	#[cfg_attr(not(feature = "std"), allow(dead_code))]
+	span: tracing::span::EnteredSpan,
+	#[cfg(feature = "std")]
+	start: Option<std::time::Instant>,
+}
+
+#[cfg(feature = "tracing")]
+impl Timed {
+	#[inline]
+	pub(crate) fn new(span: tracing::Span) -> Self {
+		Self {
+			#[cfg(feature = "std")]
+			start: if span.is_disabled() { None } else { Some(std::time::Instant::now()) },
+			span: span.entered(),
+		}
+	}
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for Timed {
+	#[inline]
+	fn drop(&mut self) {
+		#[cfg(feature = "std")]
+		if let Some(start) = self.start {
+			self.span.record("elapsed_ns", start.elapsed().as_nanos() as u64);
+		}
+	}
+}
+
+/// opens a span that stays entered until the end of the enclosing scope
+#[cfg(feature = "tracing")]
+macro_rules! span {
+	($level: ident, $name: literal $(, $field: ident = $value: expr)* $(,)?) => {
+		let __span__ = $crate::utils::trace::Timed::new(::tracing::span!(
+			target: "faer",
+			::tracing::Level::$level,
+			$name,
+			$($field = $value,)*
+			elapsed_ns = ::tracing::field::Empty,
+		));
+	};
+}
+
+/// opens a span that stays entered until the end of the enclosing scope
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+	($level: ident, $name: literal $(, $field: ident = $value: expr)* $(,)?) => {};
+}
+
+pub(crate) use span;
+
+#[cfg(all(test, feature = "tracing", feature = "std"))]
+mod tests {
+	use crate::internal_prelude::*;
+	use crate::stats::prelude::*;
+	use crate::{assert, linalg};
+	use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+	use dyn_stack::MemBuffer;
+	use std::sync::Mutex;
+	use tracing::field::{Field, Visit};
+	use tracing::span::{Attributes, Id, Record};
+	use tracing::{Event, Metadata, Subscriber};
+
+	/// records the names of the opened spans, and the number of closed spans that reported their
+	/// duration
+	#[derive(Default)]
+	struct Recorder {
+		next_id: AtomicU64,
+		names: Mutex<alloc::vec::Vec<&'static str>>,
+		timed: AtomicUsize,
+	}
+
+	struct ElapsedVisitor<'a>(&'a AtomicUsize);
+
+	impl Visit for ElapsedVisitor<'_> {
+		fn record_u64(&mut self, field: &Field, _: u64) {
+			if field.name() == "elapsed_ns" {
+				self.0.fetch_add(1, Ordering::Relaxed);
+			}
+		}
+
+		fn record_debug(&mut self, _: &Field, _: &dyn core::fmt::Debug) {}
+	}
+
+	impl Subscriber for Recorder {
+		fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+			metadata.target() == "faer"
+		}
+
+		fn new_span(&self, span: &Attributes<'_>) -> Id {
+			self.names.lock().unwrap().push(span.metadata().name());
+			Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+		}
+
+		fn record(&self, _: &Id, values: &Record<'_>) {
+			values.record(&mut ElapsedVisitor(&self.timed));
+		}
+
+		fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+		fn event(&self, _: &Event<'_>) {}
+
+		fn enter(&self, _: &Id) {}
+
+		fn exit(&self, _: &Id) {}
+	}
+
+	#[test]
+	fn test_spans() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 100;
+		let A = Mat::<f64>::random_normal(rng, n, n);
+
+		let recorder = std::sync::Arc::new(Recorder::default());
+		tracing::subscriber::with_default(recorder.clone(), || {
+			let mut LU = A.clone();
+			let perm = &mut *alloc::vec![0usize; n];
+			let perm_inv = &mut *alloc::vec![0usize; n];
+			let params = default();
+			linalg::lu::partial_pivoting::factor::lu_in_place(
+				LU.as_mut(),
+				perm,
+				perm_inv,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(
+					linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<usize, f64>(n, n, Par::Seq, params),
+				)),
+				params,
+			);
+		});
+
+		let names = recorder.names.lock().unwrap();
+		assert!(names[0] == "lu_partial_piv");
+		assert!(names.contains(&"matmul"));
+		assert!(names.iter().filter(|&&name| name == "pivot_search").count() == n);
+		assert!(recorder.timed.load(Ordering::Relaxed) == names.len());
+	}
+}